@@ -6,7 +6,9 @@ use std::time::Duration;
 use crate::{
     abort_manager::AbortRegistry,
     chat_manager::{
+        provider_adapter::{aws_bedrock, koboldcpp},
         request as chat_request, sse,
+        stop_guard::{truncate_at_stop, StopGuard},
         tooling::parse_tool_calls,
         types::{ErrorEnvelope, NormalizedEvent},
     },
@@ -14,7 +16,7 @@ use crate::{
     serde_utils::{
         json_value_to_string, parse_body_to_value, sanitize_header_value, summarize_json,
     },
-    transport::{emit_normalized, DEFAULT_REQUEST_TIMEOUT_MS},
+    transport::{emit_normalized, recorder, DEFAULT_REQUEST_TIMEOUT_MS},
     utils::{log_error, log_info, log_warn},
 };
 
@@ -189,9 +191,11 @@ pub(crate) async fn handle_streaming_response(
     status: reqwest::StatusCode,
     ok: bool,
     url_for_log: &str,
+    client_stops: Vec<String>,
 ) -> Result<Value, String> {
     let mut collected: Vec<u8> = Vec::new();
     let event_name = format!("api://{}", request_id);
+    let mut bedrock_stream = aws_bedrock::stream_transcoder(req.provider_id.as_deref(), &response);
     let mut body_stream = response.bytes_stream();
     log_info(
         app,
@@ -227,6 +231,7 @@ pub(crate) async fn handle_streaming_response(
             .unwrap_or(false)
     };
     let mut stream_filter_ctx = StreamFilterContext::new();
+    let mut stop_guard = StopGuard::new(client_stops);
 
     loop {
         let next_chunk = async { tokio::time::timeout(idle_timeout, body_stream.next()).await };
@@ -239,14 +244,56 @@ pub(crate) async fn handle_streaming_response(
                     format!("[api_request] request aborted by user: {}", url_for_log),
                 );
                 aborted = true;
+                if koboldcpp::is_koboldcpp_provider(req.provider_id.as_deref()) {
+                    koboldcpp::abort_generation(app, req);
+                }
                 break;
             }
             chunk_result = next_chunk => {
                 match chunk_result {
                     Ok(Some(Ok(chunk))) => {
+                        // Bedrock streams binary frames; everything below expects SSE text.
+                        let chunk = match bedrock_stream.as_mut() {
+                            Some(transcoder) => match transcoder.feed(&chunk) {
+                                Ok(transcoded) => bytes::Bytes::from(transcoded),
+                                Err(e) => {
+                                    log_error(
+                                        app,
+                                        "api_request",
+                                        format!("[api_request] event-stream decode error: {}", e),
+                                    );
+                                    use tauri::Manager;
+                                    let registry = app.state::<AbortRegistry>();
+                                    registry.unregister(&request_id);
+                                    return Err(e);
+                                }
+                            },
+                            None => chunk,
+                        };
                         let text = String::from_utf8_lossy(&chunk).to_string();
                         let mut content_blocked = false;
                         for event in decoder.feed(&text, req.provider_id.as_deref()) {
+                            let event = match event {
+                                NormalizedEvent::Delta { text } if stop_guard.is_active() => {
+                                    let text = stop_guard.feed(&text);
+                                    if text.is_empty() {
+                                        if stop_guard.stopped() {
+                                            break;
+                                        }
+                                        continue;
+                                    }
+                                    NormalizedEvent::Delta { text }
+                                }
+                                NormalizedEvent::Done if stop_guard.is_active() => {
+                                    let tail = stop_guard.finish();
+                                    if !tail.is_empty() {
+                                        text_emitted = true;
+                                        emit_normalized(app, &request_id, NormalizedEvent::Delta { text: tail });
+                                    }
+                                    NormalizedEvent::Done
+                                }
+                                other => other,
+                            };
                             if content_filter_active {
                                 if let NormalizedEvent::Delta { text: ref delta_text } = event {
                                     use tauri::Manager;
@@ -274,6 +321,9 @@ pub(crate) async fn handle_streaming_response(
                                 _ => {}
                             }
                             emit_normalized(app, &request_id, event);
+                            if stop_guard.stopped() {
+                                break;
+                            }
                         }
                         if content_blocked {
                             use tauri::Manager;
@@ -281,7 +331,17 @@ pub(crate) async fn handle_streaming_response(
                             registry.unregister(&request_id);
                             return Err("Response blocked by Pure Mode. Try rephrasing your message.".to_string());
                         }
+                        recorder::record_chunk(&request_id, &chunk);
                         collected.extend_from_slice(&chunk);
+                        if stop_guard.stopped() {
+                            log_info(
+                                app,
+                                "api_request",
+                                format!("[api_request] client stop reached, closing stream: {}", url_for_log),
+                            );
+                            emit_normalized(app, &request_id, NormalizedEvent::Done);
+                            break;
+                        }
                     }
                     Ok(Some(Err(e))) => {
                         log_error(
@@ -354,10 +414,28 @@ pub(crate) async fn handle_streaming_response(
         ),
     );
 
-    let value = parse_body_to_value(&text);
+    let tail = stop_guard.finish();
+    if !tail.is_empty() {
+        text_emitted = true;
+        emit_normalized(app, &request_id, NormalizedEvent::Delta { text: tail });
+    }
+
+    let mut value = parse_body_to_value(&text);
+    if stop_guard.stopped() {
+        value = chat_request::replace_response_text(
+            &value,
+            req.provider_id.as_deref(),
+            stop_guard.emitted(),
+        );
+    }
 
     if !text_emitted && ok {
-        if let Some(content) = chat_request::extract_text(&value, req.provider_id.as_deref()) {
+        if let Some(mut content) = chat_request::extract_text(&value, req.provider_id.as_deref()) {
+            if let Some(cut) = truncate_at_stop(&content, stop_guard.stops()) {
+                value =
+                    chat_request::replace_response_text(&value, req.provider_id.as_deref(), &cut);
+                content = cut;
+            }
             if !content.is_empty() {
                 enforce_pure_mode_on_text(app, req, Some(&request_id), &content)?;
                 log_info(
@@ -407,6 +485,7 @@ pub(crate) async fn handle_non_streaming_response(
     request_id: Option<String>,
     status: reqwest::StatusCode,
     ok: bool,
+    client_stops: &[String],
 ) -> Result<Value, String> {
     match response.bytes().await {
         Ok(bytes) => {
@@ -424,9 +503,28 @@ pub(crate) async fn handle_non_streaming_response(
                     crate::serde_utils::truncate_for_log(&text, 512)
                 ),
             );
-            let value = parse_body_to_value(&text);
-            let filter_candidate = chat_request::extract_text(&value, req.provider_id.as_deref())
-                .or_else(|| value.as_str().map(|s| s.to_string()));
+            let mut value = parse_body_to_value(&text);
+            let mut filter_candidate =
+                chat_request::extract_text(&value, req.provider_id.as_deref())
+                    .or_else(|| value.as_str().map(|s| s.to_string()));
+            if ok {
+                if let Some(cut) = filter_candidate
+                    .as_deref()
+                    .and_then(|text| truncate_at_stop(text, client_stops))
+                {
+                    log_info(
+                        app,
+                        "api_request",
+                        "[api_request] client stop reached, truncating response",
+                    );
+                    value = chat_request::replace_response_text(
+                        &value,
+                        req.provider_id.as_deref(),
+                        &cut,
+                    );
+                    filter_candidate = Some(cut);
+                }
+            }
             if ok {
                 if let Some(candidate_text) = filter_candidate.as_deref() {
                     enforce_pure_mode_on_text(app, req, request_id.as_deref(), candidate_text)?;
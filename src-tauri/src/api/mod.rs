@@ -4,11 +4,14 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 use crate::abort_manager::AbortRegistry;
+use crate::chat_manager::provider_adapter::{aws_bedrock, koboldcpp, mock};
+use crate::chat_manager::stop_guard;
 use crate::chat_manager::types::{ErrorEnvelope, NormalizedEvent};
 use crate::llama_cpp;
 use crate::serde_utils::truncate_for_log;
 use crate::transport;
 use crate::transport::emit_normalized;
+use crate::transport::recorder;
 use crate::utils::{log_error, log_info, log_warn};
 
 mod helpers;
@@ -29,6 +32,8 @@ pub struct ApiRequest {
     pub stream: Option<bool>,
     pub request_id: Option<String>,
     pub provider_id: Option<String>,
+    /// Credential the request is sent for; selects its proxy and TLS settings.
+    pub credential_id: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -50,6 +55,7 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
     log_info(&app, "api_request", "started");
 
     let mut req = req;
+    let client_stops = stop_guard::take_client_stops(req.body.as_mut());
 
     if crate::gemini_cache::is_gemini_provider(req.provider_id.as_deref()) {
         if let Err(err) =
@@ -66,6 +72,24 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
         }
     }
 
+    if koboldcpp::is_koboldcpp_provider(req.provider_id.as_deref()) {
+        koboldcpp::prepare_request(&app, &mut req).await;
+    }
+
+    if aws_bedrock::is_bedrock_provider(req.provider_id.as_deref()) {
+        if let Err(err) = aws_bedrock::prepare_request(&mut req) {
+            log_error(
+                &app,
+                "api_request",
+                format!("[api_request] Bedrock request signing failed: {}", err),
+            );
+            return Err(err);
+        }
+    }
+
+    if mock::is_mock_provider(req.provider_id.as_deref()) {
+        return mock::handle_request(app, req, client_stops).await;
+    }
     if llama_cpp::is_llama_cpp(req.provider_id.as_deref()) {
         return llama_cpp::handle_local_request(app, req).await;
     }
@@ -73,6 +97,17 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
         return crate::ollama::execute_chat_request(&app, &req).await;
     }
 
+    let recording = recorder::start(&req);
+    let result = send_http_request(app.clone(), req, client_stops).await;
+    recorder::finish(&app, recording, &result);
+    result
+}
+
+async fn send_http_request(
+    app: tauri::AppHandle,
+    req: ApiRequest,
+    client_stops: Vec<String>,
+) -> Result<ApiResponse, String> {
     if req.provider_id.as_deref() == Some("openrouter") {
         if let Some(api_key) = req
             .headers
@@ -118,7 +153,8 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
     let stream = req.stream.unwrap_or(false);
     let request_id = req.request_id.clone();
 
-    let client = match transport::build_client(req.timeout_ms, stream) {
+    let client = match transport::build_client(req.credential_id.as_deref(), req.timeout_ms, stream)
+    {
         Ok(c) => c,
         Err(e) => {
             log_error(&app, "api_request", format!("client build error: {}", e));
@@ -188,6 +224,9 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
     };
 
     let emit_abort = || {
+        if koboldcpp::is_koboldcpp_provider(req.provider_id.as_deref()) {
+            koboldcpp::abort_generation(&app, &req);
+        }
         if let Some(req_id) = request_id.as_ref() {
             let envelope = ErrorEnvelope {
                 code: Some("ABORTED".to_string()),
@@ -219,6 +258,7 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
                 request_builder,
                 2,
                 request_id.as_deref(),
+                req.credential_id.as_deref(),
             ) => {
                 match response {
                     Ok(resp) => {
@@ -252,6 +292,7 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
             request_builder,
             2,
             request_id.as_deref(),
+            req.credential_id.as_deref(),
         )
         .await
         {
@@ -308,6 +349,7 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
             status,
             ok,
             &url_for_log,
+            client_stops,
         )
         .await?
     } else {
@@ -322,11 +364,19 @@ pub async fn api_request(app: tauri::AppHandle, req: ApiRequest) -> Result<ApiRe
                     emit_abort();
                     return Err("Request was cancelled by user".to_string());
                 }
-                result = handle_non_streaming_response(&app, &req, response, request_id.clone(), status, ok) => result,
+                result = handle_non_streaming_response(&app, &req, response, request_id.clone(), status, ok, &client_stops) => result,
             }
         } else {
-            handle_non_streaming_response(&app, &req, response, request_id.clone(), status, ok)
-                .await
+            handle_non_streaming_response(
+                &app,
+                &req,
+                response,
+                request_id.clone(),
+                status,
+                ok,
+                &client_stops,
+            )
+            .await
         };
 
         if let Some(req_id) = request_id.as_ref() {
@@ -201,6 +201,7 @@ fn configure_runtime_state(app: &mut tauri::App, aptabase_plugin_enabled: bool)
 
     let pure_mode_level = read_pure_mode_level(app.handle());
     app.manage(content_filter::ContentFilter::new(pure_mode_level));
+    crate::transport::refresh_settings(app.handle());
 }
 
 fn run_bootstrap_tasks(app: &tauri::AppHandle) {
@@ -394,6 +395,8 @@ pub(crate) fn setup_app(
     start_usage_flush_task(app.handle(), app_usage_service);
     configure_runtime_state(app, aptabase_plugin_enabled);
     run_bootstrap_tasks(app.handle());
+    storage_manager::backup_schedule::start_backup_scheduler(app.handle());
+    chat_manager::companion::proactive::start_proactive_scheduler(app.handle());
     let app_handle = app.handle().clone();
     tauri::async_runtime::spawn(async move {
         host_api::maybe_start_from_settings(&app_handle).await;
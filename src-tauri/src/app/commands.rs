@@ -3,6 +3,11 @@ macro_rules! invoke_handler {
         tauri::generate_handler![
             crate::api::api_request,
             crate::api::abort_request,
+            crate::transport::recorder::request_recording_list,
+            crate::transport::recorder::request_recording_get,
+            crate::transport::recorder::request_recording_replay,
+            crate::transport::recorder::request_recording_clear,
+            crate::transport::network::network_set_proxy_password,
             crate::sync::commands::start_driver,
             crate::sync::commands::connect_as_passenger,
             crate::sync::commands::stop_sync,
@@ -12,6 +17,7 @@ macro_rules! invoke_handler {
             crate::sync::commands::start_sync_session,
             crate::models::verify_model_exists,
             crate::providers::verify_provider_api_key,
+            crate::providers::probe::probe_model_capabilities,
             crate::providers::get_provider_configs,
             crate::providers::commands::get_remote_models,
             crate::providers::openrouter::get_openrouter_models,
@@ -115,6 +121,31 @@ macro_rules! invoke_handler {
             crate::storage_manager::sessions::session_update_memory,
             crate::storage_manager::sessions::session_toggle_memory_pin,
             crate::storage_manager::sessions::session_set_memory_cold_state,
+            crate::storage_manager::character_memories::character_memory_get_config,
+            crate::storage_manager::character_memories::character_memory_set_config,
+            crate::storage_manager::character_memories::character_memory_list,
+            crate::storage_manager::character_memories::character_memory_promote,
+            crate::storage_manager::character_memories::character_memory_add,
+            crate::storage_manager::character_memories::character_memory_update,
+            crate::storage_manager::character_memories::character_memory_set_pinned,
+            crate::storage_manager::character_memories::character_memory_delete,
+            crate::storage_manager::companion_relationships::companion_relationship_get,
+            crate::storage_manager::companion_relationships::companion_relationship_history,
+            crate::storage_manager::companion_relationships::companion_relationship_adjust,
+            crate::storage_manager::companion_relationships::companion_relationship_reset,
+            crate::storage_manager::companion_proactive::companion_proactive_get_config,
+            crate::storage_manager::companion_proactive::companion_proactive_set_config,
+            crate::storage_manager::companion_proactive::companion_proactive_list_pending,
+            crate::storage_manager::companion_proactive::companion_proactive_resolve,
+            crate::storage_manager::model_fallbacks::character_fallback_chain_get,
+            crate::storage_manager::model_fallbacks::character_fallback_chain_set,
+            crate::storage_manager::context_summaries::session_context_summary_get,
+            crate::storage_manager::context_summaries::session_context_summary_clear,
+            crate::storage_manager::entity_graph::entity_graph_list,
+            crate::storage_manager::entity_graph::entity_graph_mentions,
+            crate::storage_manager::entity_graph::entity_graph_rename,
+            crate::storage_manager::entity_graph::entity_graph_merge,
+            crate::storage_manager::entity_graph::entity_graph_delete,
             crate::storage_manager::usage::storage_clear_all,
             crate::storage_manager::usage::storage_reset_database,
             crate::storage_manager::usage::storage_usage_summary,
@@ -151,6 +182,11 @@ macro_rules! invoke_handler {
             crate::storage_manager::backup::backup_check_dynamic_memory,
             crate::storage_manager::backup::backup_check_dynamic_memory_from_bytes,
             crate::storage_manager::backup::backup_disable_dynamic_memory,
+            crate::storage_manager::backup_schedule::backup_schedule_get,
+            crate::storage_manager::backup_schedule::backup_schedule_set,
+            crate::storage_manager::backup_schedule::backup_schedule_run_now,
+            crate::storage_manager::backup_selective::backup_list_contents,
+            crate::storage_manager::backup_selective::backup_restore_selected,
             crate::storage_manager::chatpkg::chatpkg_export_single_chat,
             crate::storage_manager::chatpkg::chatpkg_export_single_chat_sillytavern,
             crate::storage_manager::chatpkg::chatpkg_export_group_chat,
@@ -197,6 +233,7 @@ macro_rules! invoke_handler {
             crate::chat_manager::trigger_dynamic_memory,
             crate::chat_manager::abort_dynamic_memory,
             crate::storage_manager::companion_turn_effects::get_message_companion_effect,
+            crate::storage_manager::message_citations::get_message_citations,
             crate::chat_manager::list_prompt_templates,
             crate::chat_manager::export_prompt_template_as_usc,
             crate::chat_manager::chat_template_export_as_usc,
@@ -234,6 +271,7 @@ macro_rules! invoke_handler {
             crate::usage::usage_clear_before,
             crate::usage::usage_export_csv,
             crate::usage::usage_save_csv,
+            crate::usage::usage_provider_key_stats,
             crate::usage::usage_get_app_active_usage,
             crate::usage::usage_recalculate_costs,
             crate::utils::accessibility_sound_base64,
@@ -249,6 +287,7 @@ macro_rules! invoke_handler {
             crate::embedding::clear_embedding_runtime_cache,
             crate::embedding::run_embedding_test,
             crate::embedding::run_embedding_dev_benchmark,
+            crate::embedding::run_memory_ann_benchmark,
             crate::embedding::compare_custom_texts,
             crate::embedding::delete_embedding_model,
             crate::embedding::delete_embedding_model_version,
@@ -327,6 +366,7 @@ macro_rules! invoke_handler {
             crate::discovery::discovery_fetch_author_info,
             crate::discovery::discovery_import_character,
             crate::llama_cpp::llamacpp_context_info,
+            crate::llama_cpp::llamacpp_model_capabilities,
             crate::llama_cpp::llamacpp_unload,
             crate::hf_browser::hf_search_models,
             crate::host_api::host_api_get_status,
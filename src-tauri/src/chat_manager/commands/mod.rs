@@ -3,9 +3,10 @@ use serde_json::{json, Value};
 use tauri::{AppHandle, Manager};
 
 use crate::chat_manager::attachments::load_attachment_data;
+use crate::chat_manager::context_budget::fit_history_to_context;
 use crate::chat_manager::execution::{build_provider_extra_fields, RequestSettings};
 use crate::chat_manager::messages::{
-    push_prompt_entry_message, push_user_or_assistant_message_with_context,
+    push_prompt_entry_message, push_system_message, push_user_or_assistant_message_with_context,
     sanitize_placeholders_in_api_messages,
 };
 use crate::chat_manager::storage::{
@@ -13,8 +14,8 @@ use crate::chat_manager::storage::{
 };
 use crate::chat_manager::turn_builder::{
     append_image_directive_instructions, conversation_window_with_pinned,
-    insert_in_chat_prompt_entries, manual_window_size, maybe_swap_message_for_api,
-    message_visible_to_model, partition_prompt_entries,
+    insert_in_chat_prompt_entries, is_dynamic_memory_active, manual_window_size,
+    maybe_swap_message_for_api, message_visible_to_model, partition_prompt_entries,
 };
 use crate::utils::now_millis;
 
@@ -29,9 +30,9 @@ use super::types::{
     ChatGenerateCompanionSoulArgs, ChatGenerateDesignReferenceDescriptionArgs,
     ChatGenerateLorebookEntryDraftArgs, ChatGenerateLorebookKeywordDraftArgs,
     ChatGenerateSceneImageArgs, ChatGenerateScenePromptArgs, ChatRegenerateArgs, ChatTurnResult,
-    ContinueResult, ImageAttachment, LorebookEntryDraftResult, LorebookKeywordDraftResult,
-    PromptTemplateType, RegenerateResult, Session, Settings, StoredMessage, SystemPromptEntry,
-    SystemPromptTemplate,
+    ContextTrimReport, ContinueResult, ImageAttachment, LorebookEntryDraftResult,
+    LorebookKeywordDraftResult, ModelAttemptRecord, PromptTemplateType, RegenerateResult, Session,
+    Settings, StoredMessage, SystemPromptEntry, SystemPromptTemplate,
 };
 use crate::storage_manager::sessions::{messages_upsert_batch_typed, session_upsert_meta_typed};
 
@@ -67,6 +68,22 @@ pub struct ChatMessageDebugSnapshot {
     pub request_messages: Vec<Value>,
     pub request_body: Value,
     pub notes: Vec<String>,
+    /// Every model tried for this message, when more than one was needed.
+    pub attempts: Vec<ModelAttemptRecord>,
+    /// History trimmed from the live request to fit the context length.
+    pub context_trim: Option<ContextTrimReport>,
+}
+
+/// A rendered prompt and, for a stored session, how its history would be
+/// trimmed around it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreview {
+    pub rendered: String,
+    /// History dropped to fit the model's context length.
+    pub context_trim: Option<ContextTrimReport>,
+    /// Rolling summary sent in place of the dropped messages.
+    pub context_summary: Option<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -446,6 +463,32 @@ pub fn chat_message_debug_snapshot(
         return Err("Debug snapshot currently supports assistant messages only".to_string());
     }
 
+    let conn = crate::storage_manager::db::open_db(&app)?;
+    let attempts =
+        crate::storage_manager::model_fallbacks::load_message_attempts(&conn, &args.message_id)?;
+    let context_trim =
+        crate::storage_manager::context_summaries::load_message_trim(&conn, &args.message_id)?;
+    let (session, target_index) = match context_trim.as_ref() {
+        Some(trim) => {
+            let dropped: std::collections::HashSet<&str> = trim
+                .dropped_message_ids
+                .iter()
+                .map(String::as_str)
+                .collect();
+            let mut trimmed = session.clone();
+            trimmed
+                .messages
+                .retain(|message| !dropped.contains(message.id.as_str()));
+            let index = trimmed
+                .messages
+                .iter()
+                .position(|message| message.id == args.message_id)
+                .unwrap_or(target_index);
+            (trimmed, index)
+        }
+        None => (session, target_index),
+    };
+
     let character = context.find_character(&session.character_id)?;
     let persona = context
         .choose_persona(resolve_persona_id(&session, None))
@@ -577,6 +620,24 @@ pub fn chat_message_debug_snapshot(
         DebugMessageOperation::Completion => {}
     }
 
+    if let Some(trim) = context_trim.as_ref() {
+        notes.push(format!(
+            "The live request dropped {} older messages ({} tokens) to fit a {} token context; they are left out here too.",
+            trim.dropped_message_ids.len(),
+            trim.dropped_tokens,
+            trim.context_length
+        ));
+        if trim.summary_tokens > 0 {
+            notes.push("A rolling summary of earlier messages was sent in their place; it is not reconstructed.".to_string());
+        }
+    }
+    if attempts.len() > 1 {
+        notes.push(format!(
+            "This reply took {} model attempts; the request above is reconstructed for the model that answered.",
+            attempts.len()
+        ));
+    }
+
     Ok(ChatMessageDebugSnapshot {
         source: "reconstructed".to_string(),
         session_id: args.session_id,
@@ -600,6 +661,8 @@ pub fn chat_message_debug_snapshot(
         request_messages,
         request_body: built.body,
         notes,
+        attempts,
+        context_trim,
     })
 }
 
@@ -935,7 +998,7 @@ pub fn render_prompt_preview(
     character_id: String,
     session_id: Option<String>,
     persona_id: Option<String>,
-) -> Result<String, String> {
+) -> Result<PromptPreview, String> {
     let context = super::service::ChatContext::initialize(app.clone())?;
     let settings = &context.settings;
 
@@ -988,7 +1051,43 @@ pub fn render_prompt_preview(
 
     let rendered =
         prompt_engine::render_with_context(&app, &content, &character, persona, &session, settings);
-    Ok(rendered)
+
+    let (context_trim, context_summary) = if session_id.is_some() {
+        let (model, credential) = context.select_model_with_credential(&character)?;
+        let system_role = crate::chat_manager::request_builder::system_role_for(credential);
+        let mut fixed_messages = Vec::new();
+        push_system_message(&mut fixed_messages, &system_role, Some(rendered.clone()));
+        let (pinned_msgs, recent_msgs) = if is_dynamic_memory_active(settings, &character) {
+            conversation_window_with_pinned(
+                &session.messages,
+                crate::chat_manager::memory::dynamic::dynamic_window_size(settings),
+            )
+        } else {
+            (
+                Vec::new(),
+                recent_messages(&session, manual_window_size(settings)),
+            )
+        };
+        let fit = fit_history_to_context(
+            &app,
+            &session,
+            model,
+            settings,
+            &fixed_messages,
+            &[],
+            &pinned_msgs,
+            recent_msgs,
+        );
+        (fit.report, fit.summary)
+    } else {
+        (None, None)
+    };
+
+    Ok(PromptPreview {
+        rendered,
+        context_trim,
+        context_summary,
+    })
 }
 
 #[tauri::command]
@@ -1,11 +1,26 @@
+pub mod proactive;
+
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use tauri::AppHandle;
 
+use crate::chat_manager::memory::flow::summarize_turn_effect;
 use crate::chat_manager::types::{Character, Persona, Session};
 use crate::embedding::emotion::{EmotionClassification, EmotionLabelScore};
+use crate::storage_manager::companion_relationships::{
+    self, RelationshipLedger, RelationshipValues, SessionDelta,
+};
+use crate::storage_manager::companion_turn_effects::{
+    get_effect_for_message, record_ready_effect, CompanionTurnEffectSeed,
+};
+use crate::storage_manager::db::{now_ms, open_db};
 use crate::utils::log_warn;
 
 const DECAY_MINUTES: f64 = 45.0;
+/// A reply's classified emotion counts for less than the same words from the user.
+const REPLY_DELTA_SCALE: f64 = 0.6;
+const REPLY_RELATIONSHIP_WEIGHT: f64 = 0.5;
+const MIN_EFFECT_DELTA: f64 = 0.0005;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -246,6 +261,51 @@ pub struct CompanionSessionState {
     pub relationship_state: RelationshipState,
     pub active_signals: Vec<String>,
     pub updated_at: u64,
+    /// State before the latest user message; turn effects are measured from here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_start: Option<CompanionSnapshot>,
+    /// State before the latest reply was applied, so a regenerated reply can
+    /// replace its predecessor's effect instead of stacking on top of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reply: Option<ReplyCheckpoint>,
+    /// Relationship values at the last merge into the character ledger; the
+    /// difference to `relationship_state` is what the next merge adds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger_baseline: Option<RelationshipState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionSnapshot {
+    pub emotional_state: EmotionalState,
+    pub relationship_state: RelationshipState,
+    pub active_signals: Vec<String>,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyCheckpoint {
+    pub message_id: String,
+    pub before: CompanionSnapshot,
+}
+
+impl CompanionSessionState {
+    fn snapshot(&self) -> CompanionSnapshot {
+        CompanionSnapshot {
+            emotional_state: self.emotional_state.clone(),
+            relationship_state: self.relationship_state.clone(),
+            active_signals: self.active_signals.clone(),
+            updated_at: self.updated_at,
+        }
+    }
+
+    fn restore(&mut self, snapshot: CompanionSnapshot) {
+        self.emotional_state = snapshot.emotional_state;
+        self.relationship_state = snapshot.relationship_state;
+        self.active_signals = snapshot.active_signals;
+        self.updated_at = snapshot.updated_at;
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -376,13 +436,18 @@ pub async fn update_state_for_user_message(
     character: &Character,
     user_message: &str,
     now: u64,
-) -> bool {
+) -> Option<PendingLedgerMerge> {
     if !is_companion_mode(session, character) {
-        return false;
+        return None;
     }
 
     let config = companion_config(character);
     let mut state = current_state(session, &config);
+    if session.companion_state.is_none() {
+        inherit_ledger(app, &character.id, &mut state);
+    }
+    state.turn_start = Some(state.snapshot());
+    state.last_reply = None;
     let baseline = config.soul.baseline_affect.clone();
     let regulation = config.soul.regulation_style.clone();
     let elapsed_minutes = elapsed_minutes(state.updated_at, now);
@@ -441,8 +506,371 @@ pub async fn update_state_for_user_message(
     state.active_signals = bundle.signals;
     state.updated_at = now;
 
+    let ledger = take_ledger_delta(app, &session.id, &character.id, &config, &mut state);
+    session.companion_state = serde_json::to_value(state).ok();
+    Some(ledger)
+}
+
+/// Applies the character's own reply to the companion state. The reply is
+/// classified like a user message, but what the character shows also nudges
+/// what it feels: transparent characters are moved more by their own words,
+/// suppressive ones less. Calling this again for the same message (regenerate,
+/// or a continuation that replaced it) first rolls back the previous reply.
+/// Returns the turn effect measured from before the latest user message.
+pub async fn update_state_for_reply(
+    app: &AppHandle,
+    session: &mut Session,
+    character: &Character,
+    message_id: &str,
+    reply: &str,
+    now: u64,
+) -> Option<ReplyStateUpdate> {
+    if !is_companion_mode(session, character) || reply.trim().is_empty() {
+        return None;
+    }
+
+    let config = companion_config(character);
+    let regulation = config.soul.regulation_style.clone();
+    let mut state = current_state(session, &config);
+
+    let effect_start = match state.last_reply.take() {
+        Some(checkpoint) if checkpoint.message_id == message_id => {
+            state.restore(checkpoint.before);
+            state.turn_start.clone()
+        }
+        // A second reply without a new user message (continue) is its own turn.
+        Some(_) => Some(state.snapshot()),
+        None => state.turn_start.clone(),
+    };
+    let before = state.snapshot();
+
+    let bundle = detect_signals(app, reply).await;
+    apply_reply_bundle(&mut state, &bundle, &regulation, now);
+    state.last_reply = Some(ReplyCheckpoint {
+        message_id: message_id.to_string(),
+        before: before.clone(),
+    });
+
+    let turn_effect = turn_effect_seed(&effect_start.unwrap_or(before), &state);
+    let ledger = take_ledger_delta(app, &session.id, &character.id, &config, &mut state);
     session.companion_state = serde_json::to_value(state).ok();
-    true
+    Some(ReplyStateUpdate {
+        turn_effect,
+        ledger,
+    })
+}
+
+fn apply_reply_bundle(
+    state: &mut CompanionSessionState,
+    bundle: &SignalBundle,
+    regulation: &RegulationStyle,
+    now: u64,
+) {
+    let observed = bundle.delta.scaled(REPLY_DELTA_SCALE);
+    let self_influence =
+        0.25 + regulation.emotional_transparency * 0.5 - regulation.suppression * 0.2;
+    let felt = state
+        .emotional_state
+        .felt
+        .add(&observed.scaled(self_influence.max(0.05)));
+    // The reply is what was actually shown, so expression follows it, pulled
+    // back toward what the regulation style would normally let through.
+    let expressed = state
+        .emotional_state
+        .expressed
+        .add(&observed)
+        .lerp(&regulate_expressed(&felt, regulation), 0.35)
+        .clamp();
+
+    state.emotional_state.blocked = felt.subtract_positive(&expressed);
+    state.emotional_state.momentum = state.emotional_state.momentum.lerp(&observed, 0.25);
+    state.emotional_state.felt = felt;
+    state.emotional_state.expressed = expressed;
+    state.emotional_state.confidence =
+        clamp01((state.emotional_state.confidence + bundle.confidence) / 2.0);
+    state.emotional_state.updated_at = now;
+
+    let rel = &bundle.relationship_delta;
+    let weight = REPLY_RELATIONSHIP_WEIGHT;
+    state.relationship_state.closeness =
+        clamp01(state.relationship_state.closeness + rel.closeness * weight);
+    state.relationship_state.trust = clamp01(state.relationship_state.trust + rel.trust * weight);
+    state.relationship_state.affection =
+        clamp01(state.relationship_state.affection + rel.affection * weight);
+    state.relationship_state.tension =
+        clamp01(state.relationship_state.tension + rel.tension * weight);
+    state.relationship_state.stability =
+        clamp01(state.relationship_state.stability + rel.stability * weight);
+    state.relationship_state.last_interaction_at = now;
+
+    for signal in &bundle.signals {
+        let reply_signal = format!("reply:{}", signal);
+        push_signal(&mut state.active_signals, &reply_signal);
+    }
+    state.updated_at = now;
+}
+
+fn turn_effect_seed(
+    start: &CompanionSnapshot,
+    state: &CompanionSessionState,
+) -> CompanionTurnEffectSeed {
+    let before_rel = &start.relationship_state;
+    let after_rel = &state.relationship_state;
+    let relationship_delta = non_zero_deltas(&[
+        ("closeness", after_rel.closeness - before_rel.closeness),
+        ("trust", after_rel.trust - before_rel.trust),
+        ("affection", after_rel.affection - before_rel.affection),
+        ("tension", after_rel.tension - before_rel.tension),
+        ("stability", after_rel.stability - before_rel.stability),
+    ]);
+    let felt = emotion_deltas(&start.emotional_state.felt, &state.emotional_state.felt);
+    let expressed = emotion_deltas(
+        &start.emotional_state.expressed,
+        &state.emotional_state.expressed,
+    );
+    let mut emotion_delta = serde_json::Map::new();
+    if !felt.is_empty() {
+        emotion_delta.insert("companion".to_string(), Value::Object(felt));
+    }
+    if !expressed.is_empty() {
+        emotion_delta.insert("expressed".to_string(), Value::Object(expressed));
+    }
+
+    let added: Vec<&String> = state
+        .active_signals
+        .iter()
+        .filter(|signal| !start.active_signals.contains(signal))
+        .collect();
+    let removed: Vec<&String> = start
+        .active_signals
+        .iter()
+        .filter(|signal| !state.active_signals.contains(signal))
+        .collect();
+
+    CompanionTurnEffectSeed {
+        relationship_delta: Value::Object(relationship_delta),
+        emotion_delta: Value::Object(emotion_delta),
+        signal_changes: json!({ "added": added, "removed": removed }),
+    }
+}
+
+fn emotion_deltas(before: &EmotionVector, after: &EmotionVector) -> serde_json::Map<String, Value> {
+    non_zero_deltas(&[
+        ("warmth", after.warmth - before.warmth),
+        ("trust", after.trust - before.trust),
+        ("calm", after.calm - before.calm),
+        ("vulnerability", after.vulnerability - before.vulnerability),
+        ("longing", after.longing - before.longing),
+        ("hurt", after.hurt - before.hurt),
+        ("tension", after.tension - before.tension),
+        ("irritation", after.irritation - before.irritation),
+        (
+            "affection_intensity",
+            after.affection_intensity - before.affection_intensity,
+        ),
+        (
+            "reassurance_need",
+            after.reassurance_need - before.reassurance_need,
+        ),
+    ])
+}
+
+fn non_zero_deltas(items: &[(&str, f64)]) -> serde_json::Map<String, Value> {
+    items
+        .iter()
+        .filter(|(_, value)| value.abs() >= MIN_EFFECT_DELTA)
+        .map(|(key, value)| ((*key).to_string(), json!((value * 1000.0).round() / 1000.0)))
+        .collect()
+}
+
+/// Records the effect of a reply that will not pass through the post-turn
+/// memory job, which otherwise finalizes the effect itself.
+pub fn record_turn_effect(
+    app: &AppHandle,
+    session_id: &str,
+    user_message_id: Option<&str>,
+    assistant_message_id: &str,
+    seed: CompanionTurnEffectSeed,
+) {
+    let memory_changes = get_effect_for_message(app, session_id, assistant_message_id)
+        .ok()
+        .flatten()
+        .map(|effect| effect.memory_changes)
+        .unwrap_or_else(|| json!({}));
+    let summary = summarize_turn_effect(
+        &seed.relationship_delta,
+        &seed.emotion_delta,
+        &seed.signal_changes,
+        &memory_changes,
+    );
+    if let Err(err) = record_ready_effect(
+        app,
+        session_id,
+        user_message_id,
+        assistant_message_id,
+        seed,
+        summary,
+    ) {
+        log_warn(
+            app,
+            "companion",
+            format!(
+                "failed to record companion turn effect session={} assistantMessage={}: {}",
+                session_id, assistant_message_id, err
+            ),
+        );
+    }
+}
+
+/// The character's authored starting relationship, used for new ledgers.
+pub fn relationship_defaults(character: &Character) -> RelationshipValues {
+    default_relationship_values(&companion_config(character))
+}
+
+fn default_relationship_values(config: &CompanionConfig) -> RelationshipValues {
+    let defaults = &config.relationship_defaults;
+    RelationshipValues {
+        closeness: defaults.closeness,
+        trust: defaults.trust,
+        affection: defaults.affection,
+        tension: defaults.tension,
+        stability: RelationshipState::default().stability,
+    }
+}
+
+fn relationship_values(state: &RelationshipState) -> RelationshipValues {
+    RelationshipValues {
+        closeness: state.closeness,
+        trust: state.trust,
+        affection: state.affection,
+        tension: state.tension,
+        stability: state.stability,
+    }
+}
+
+fn apply_ledger(state: &mut CompanionSessionState, ledger: &RelationshipLedger) {
+    let rel = &mut state.relationship_state;
+    rel.closeness = ledger.values.closeness;
+    rel.trust = ledger.values.trust;
+    rel.affection = ledger.values.affection;
+    rel.tension = ledger.values.tension;
+    rel.stability = ledger.values.stability;
+    rel.interaction_count = ledger.interaction_count;
+    rel.last_interaction_at = rel.last_interaction_at.max(ledger.last_interaction_at);
+    state.ledger_baseline = Some(rel.clone());
+}
+
+/// Starts a new session from the character's ledger, if it has one.
+fn inherit_ledger(app: &AppHandle, character_id: &str, state: &mut CompanionSessionState) {
+    let ledger =
+        open_db(app).and_then(|conn| companion_relationships::load_ledger(&conn, character_id));
+    match ledger {
+        Ok(Some(ledger)) => apply_ledger(state, &ledger),
+        Ok(None) => {}
+        Err(err) => log_warn(
+            app,
+            "companion",
+            format!("failed to load relationship ledger: {}", err),
+        ),
+    }
+}
+
+/// Relationship change of one turn, merged into the character ledger by the
+/// flow once the session carrying it is saved. Aborted or discarded turns are
+/// never saved, so their changes never reach the ledger.
+pub struct PendingLedgerMerge {
+    character_id: String,
+    session_id: String,
+    delta: SessionDelta,
+}
+
+/// What a reply changed in the companion state.
+pub struct ReplyStateUpdate {
+    pub turn_effect: CompanionTurnEffectSeed,
+    pub ledger: PendingLedgerMerge,
+}
+
+/// Takes the session's relationship changes since the last merge and moves
+/// the session onto the ledger as if they were already merged, so changes
+/// from other sessions and manual adjustments show up here too. Sessions from
+/// before the ledger existed have no baseline and merge everything since
+/// their defaults.
+fn take_ledger_delta(
+    app: &AppHandle,
+    session_id: &str,
+    character_id: &str,
+    config: &CompanionConfig,
+    state: &mut CompanionSessionState,
+) -> PendingLedgerMerge {
+    let delta = session_delta(default_relationship_values(config), state);
+    let ledger =
+        open_db(app).and_then(|conn| companion_relationships::load_ledger(&conn, character_id));
+    match ledger {
+        Ok(ledger) => {
+            let merged =
+                companion_relationships::fold_session_delta(ledger, character_id, &delta, now_ms());
+            apply_ledger(state, &merged);
+        }
+        Err(err) => {
+            log_warn(
+                app,
+                "companion",
+                format!("failed to load relationship ledger: {}", err),
+            );
+            state.ledger_baseline = Some(state.relationship_state.clone());
+        }
+    }
+
+    PendingLedgerMerge {
+        character_id: character_id.to_string(),
+        session_id: session_id.to_string(),
+        delta,
+    }
+}
+
+/// Relationship change since `ledger_baseline`. Rolling back a reply for a
+/// regenerate leaves the baseline alone, so the next delta also takes back
+/// what the replaced reply already merged.
+fn session_delta(initial: RelationshipValues, state: &CompanionSessionState) -> SessionDelta {
+    let (baseline, baseline_count) = match &state.ledger_baseline {
+        Some(baseline) => (relationship_values(baseline), baseline.interaction_count),
+        None => (initial, 0),
+    };
+    let current = relationship_values(&state.relationship_state);
+    SessionDelta {
+        initial,
+        values: RelationshipValues {
+            closeness: current.closeness - baseline.closeness,
+            trust: current.trust - baseline.trust,
+            affection: current.affection - baseline.affection,
+            tension: current.tension - baseline.tension,
+            stability: current.stability - baseline.stability,
+        },
+        interactions: state
+            .relationship_state
+            .interaction_count
+            .saturating_sub(baseline_count),
+        last_interaction_at: state.relationship_state.last_interaction_at,
+    }
+}
+
+/// Adds a saved turn's relationship change to the character ledger.
+pub fn merge_into_ledger(app: &AppHandle, pending: PendingLedgerMerge) {
+    let merged = open_db(app).and_then(|mut conn| {
+        companion_relationships::merge_session_delta(
+            &mut conn,
+            &pending.character_id,
+            &pending.session_id,
+            &pending.delta,
+        )
+    });
+    if let Err(err) = merged {
+        log_warn(
+            app,
+            "companion",
+            format!("failed to merge relationship into ledger: {}", err),
+        );
+    }
 }
 
 pub fn render_prompt_state(
@@ -573,6 +1001,9 @@ fn current_state(session: &Session, config: &CompanionConfig) -> CompanionSessio
         },
         active_signals: Vec::new(),
         updated_at: 0,
+        turn_start: None,
+        last_reply: None,
+        ledger_baseline: None,
     }
 }
 
@@ -915,4 +1346,84 @@ mod tests {
         assert!(state.hurt < 0.5);
         assert!(state.tension < 0.4);
     }
+
+    #[test]
+    fn reply_effect_is_measured_from_turn_start() {
+        let mut state = CompanionSessionState::default();
+        let start = state.snapshot();
+        let bundle = SignalBundle {
+            signals: vec!["emotion:caring".into()],
+            delta: EmotionVector {
+                warmth: 0.3,
+                hurt: -0.2,
+                ..EmotionVector::default()
+            },
+            relationship_delta: RelationshipDelta {
+                affection: 0.04,
+                ..RelationshipDelta::default()
+            },
+            confidence: 0.7,
+        };
+        apply_reply_bundle(&mut state, &bundle, &RegulationStyle::default(), 10);
+
+        assert!(state.emotional_state.expressed.warmth > state.emotional_state.felt.warmth);
+        let seed = turn_effect_seed(&start, &state);
+        assert!(seed.relationship_delta["affection"].as_f64().unwrap() > 0.0);
+        assert!(seed.emotion_delta["companion"]["warmth"].as_f64().unwrap() > 0.0);
+        assert!(seed.emotion_delta["expressed"].get("hurt").is_none());
+        assert_eq!(seed.signal_changes["added"][0], "reply:emotion:caring");
+
+        state.restore(start.clone());
+        let seed = turn_effect_seed(&start, &state);
+        assert!(seed.relationship_delta.as_object().unwrap().is_empty());
+    }
+
+    fn affection_bundle(affection: f64) -> SignalBundle {
+        SignalBundle {
+            signals: Vec::new(),
+            delta: EmotionVector::default(),
+            relationship_delta: RelationshipDelta {
+                affection,
+                ..RelationshipDelta::default()
+            },
+            confidence: 0.5,
+        }
+    }
+
+    #[test]
+    fn regenerated_reply_replaces_its_merged_delta() {
+        let regulation = RegulationStyle::default();
+        let mut state = CompanionSessionState::default();
+        let initial = relationship_values(&state.relationship_state);
+        let start = companion_relationships::fold_session_delta(
+            None,
+            "character",
+            &session_delta(initial, &state),
+            0,
+        );
+        apply_ledger(&mut state, &start);
+        let before = state.snapshot();
+
+        apply_reply_bundle(&mut state, &affection_bundle(0.2), &regulation, 10);
+        let first = companion_relationships::fold_session_delta(
+            Some(start.clone()),
+            "character",
+            &session_delta(initial, &state),
+            10,
+        );
+        apply_ledger(&mut state, &first);
+
+        // Regenerate rolls the reply back but keeps the baseline of the merge.
+        state.restore(before);
+        apply_reply_bundle(&mut state, &affection_bundle(0.06), &regulation, 20);
+        let delta = session_delta(initial, &state);
+        let second =
+            companion_relationships::fold_session_delta(Some(first), "character", &delta, 20);
+
+        let expected = start.values.affection + 0.06 * REPLY_RELATIONSHIP_WEIGHT;
+        assert!((second.values.affection - expected).abs() < 1e-9);
+        assert!((second.values.affection - state.relationship_state.affection).abs() < 1e-9);
+        assert_eq!(delta.interactions, 0);
+        assert_eq!(second.interaction_count, start.interaction_count);
+    }
 }
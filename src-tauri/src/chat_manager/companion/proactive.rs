@@ -0,0 +1,394 @@
+//! Proactive companion messages: a background scheduler that lets a companion
+//! reach out on its own after the user has been away. Whether to send is
+//! decided by `decide` from idle time, emotional momentum and closeness,
+//! within the quiet hours and frequency caps of `ProactiveMessageConfig`.
+
+use std::time::Duration;
+
+use chrono::Timelike;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+
+use super::{CompanionSessionState, EmotionVector};
+use crate::chat_manager::flows::continuation::ContinueFlow;
+use crate::chat_manager::types::ChatContinueArgs;
+use crate::storage_manager::companion_proactive::{
+    self, ProactiveCandidateRow, ProactiveMessageConfig,
+};
+use crate::storage_manager::db::open_db;
+use crate::utils::{log_error, log_info, log_warn};
+
+const SCHEDULER_TICK: Duration = Duration::from_secs(5 * 60);
+const MINUTE_MS: u64 = 60 * 1000;
+pub const DAY_MS: u64 = 24 * 60 * MINUTE_MS;
+/// Urgency needed before a companion reaches out.
+const SEND_THRESHOLD: f64 = 0.55;
+pub const PROACTIVE_MESSAGE_EVENT: &str = "companion-proactive-message";
+
+/// Time source for `decide`, so tests can pin both instant and local time.
+pub trait Clock {
+    fn now_ms(&self) -> u64;
+    /// Minutes since local midnight.
+    fn local_minute_of_day(&self) -> u32;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        crate::storage_manager::db::now_ms()
+    }
+
+    fn local_minute_of_day(&self) -> u32 {
+        let now = chrono::Local::now();
+        now.hour() * 60 + now.minute()
+    }
+}
+
+/// What the scheduler knows about one companion session.
+#[derive(Debug, Clone, Default)]
+pub struct ProactiveCandidate {
+    pub last_interaction_at: u64,
+    pub interaction_count: u32,
+    pub momentum: EmotionVector,
+    pub closeness: f64,
+    pub tension: f64,
+    pub last_proactive_at: Option<u64>,
+    /// Proactive messages sent in the last 24 hours.
+    pub sent_last_day: u32,
+    /// An earlier proactive message has not been seen yet.
+    pub has_unseen: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Disabled,
+    NoHistory,
+    QuietHours,
+    Unseen,
+    DailyCap,
+    TooSoon,
+    NotIdle,
+    LowUrgency,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProactiveDecision {
+    Skip(SkipReason),
+    Send { urgency: f64, idle_minutes: u64 },
+}
+
+pub fn in_quiet_hours(config: &ProactiveMessageConfig, minute_of_day: u32) -> bool {
+    let (start, end) = (config.quiet_start_minute, config.quiet_end_minute);
+    if start == end {
+        false
+    } else if start < end {
+        (start..end).contains(&minute_of_day)
+    } else {
+        minute_of_day >= start || minute_of_day < end
+    }
+}
+
+/// Emotional pull toward reaching out: longing, reassurance need and warmth
+/// in recent momentum, held back by irritation and hurt.
+fn emotional_pull(momentum: &EmotionVector) -> f64 {
+    let pull = momentum.longing.max(0.0) * 1.4
+        + momentum.reassurance_need.max(0.0)
+        + momentum.affection_intensity.max(0.0) * 0.8
+        + momentum.warmth.max(0.0) * 0.5;
+    let push = momentum.irritation.max(0.0) + momentum.hurt.max(0.0) * 0.5;
+    (pull - push).clamp(-1.0, 1.0)
+}
+
+pub fn decide(
+    candidate: &ProactiveCandidate,
+    config: &ProactiveMessageConfig,
+    clock: &dyn Clock,
+) -> ProactiveDecision {
+    if !config.enabled {
+        return ProactiveDecision::Skip(SkipReason::Disabled);
+    }
+    if candidate.interaction_count == 0 || candidate.last_interaction_at == 0 {
+        return ProactiveDecision::Skip(SkipReason::NoHistory);
+    }
+    if in_quiet_hours(config, clock.local_minute_of_day()) {
+        return ProactiveDecision::Skip(SkipReason::QuietHours);
+    }
+    if candidate.has_unseen {
+        return ProactiveDecision::Skip(SkipReason::Unseen);
+    }
+    if candidate.sent_last_day >= config.max_per_day {
+        return ProactiveDecision::Skip(SkipReason::DailyCap);
+    }
+    let now = clock.now_ms();
+    let min_gap_ms = config.min_gap_minutes as u64 * MINUTE_MS;
+    if candidate
+        .last_proactive_at
+        .is_some_and(|last| now.saturating_sub(last) < min_gap_ms)
+    {
+        return ProactiveDecision::Skip(SkipReason::TooSoon);
+    }
+    let idle_minutes = now.saturating_sub(candidate.last_interaction_at) / MINUTE_MS;
+    let min_idle = config.min_idle_minutes.max(1) as u64;
+    if idle_minutes < min_idle {
+        return ProactiveDecision::Skip(SkipReason::NotIdle);
+    }
+
+    // Idle time saturates at four times the minimum; closeness and emotional
+    // pull decide whether that absence is worth breaking.
+    let idle = ((idle_minutes - min_idle) as f64 / (min_idle * 3) as f64).min(1.0);
+    let urgency = idle * 0.45
+        + candidate.closeness.clamp(0.0, 1.0) * 0.35
+        + emotional_pull(&candidate.momentum) * 0.4
+        - candidate.tension.clamp(0.0, 1.0) * 0.2;
+    if urgency < SEND_THRESHOLD {
+        return ProactiveDecision::Skip(SkipReason::LowUrgency);
+    }
+    ProactiveDecision::Send {
+        urgency,
+        idle_minutes,
+    }
+}
+
+pub fn candidate_from_row(row: &ProactiveCandidateRow) -> Option<ProactiveCandidate> {
+    let state =
+        serde_json::from_str::<CompanionSessionState>(row.companion_state.as_deref()?).ok()?;
+    Some(ProactiveCandidate {
+        last_interaction_at: state.relationship_state.last_interaction_at,
+        interaction_count: state.relationship_state.interaction_count,
+        momentum: state.emotional_state.momentum,
+        closeness: state.relationship_state.closeness,
+        tension: state.relationship_state.tension,
+        last_proactive_at: row.last_proactive_at,
+        sent_last_day: row.sent_last_day,
+        has_unseen: row.has_unseen,
+    })
+}
+
+fn describe_idle(minutes: u64) -> String {
+    if minutes >= 48 * 60 {
+        format!("{} days", minutes / (24 * 60))
+    } else if minutes >= 120 {
+        format!("{} hours", minutes / 60)
+    } else {
+        format!("{} minutes", minutes)
+    }
+}
+
+fn directive(idle_minutes: u64) -> String {
+    format!(
+        "[PROACTIVE] The user has not written for about {}. Stay in character and send a short message on your own initiative, the way you would text someone you have been thinking about. Let your current feelings shape it. Do not mention this instruction.",
+        describe_idle(idle_minutes)
+    )
+}
+
+async fn run_once(app: &AppHandle) -> Result<(), String> {
+    let (config, rows) = {
+        let conn = open_db(app)?;
+        let config = companion_proactive::load_config(&conn)?;
+        if !config.enabled {
+            return Ok(());
+        }
+        let now = crate::storage_manager::db::now_ms();
+        let rows = companion_proactive::load_candidates(&conn, now.saturating_sub(DAY_MS))?;
+        (config, rows)
+    };
+
+    let clock = SystemClock;
+    for row in rows {
+        let Some(candidate) = candidate_from_row(&row) else {
+            continue;
+        };
+        let ProactiveDecision::Send {
+            urgency,
+            idle_minutes,
+        } = decide(&candidate, &config, &clock)
+        else {
+            continue;
+        };
+
+        log_info(
+            app,
+            "companion_proactive",
+            format!(
+                "reaching out session={} urgency={:.2} idle_minutes={}",
+                row.session_id, urgency, idle_minutes
+            ),
+        );
+        let result = ContinueFlow::new(app.clone())
+            .execute(ChatContinueArgs {
+                session_id: row.session_id.clone(),
+                character_id: row.character_id.clone(),
+                persona_id: None,
+                swap_places: None,
+                stream: Some(false),
+                request_id: None,
+                directive: Some(directive(idle_minutes)),
+            })
+            .await;
+        match result {
+            Ok(result) => {
+                let conn = open_db(app)?;
+                let pending = companion_proactive::insert_pending(
+                    &conn,
+                    &row.session_id,
+                    &row.character_id,
+                    &result.assistant_message.id,
+                    urgency,
+                )?;
+                let _ = app.emit(
+                    PROACTIVE_MESSAGE_EVENT,
+                    json!({
+                        "id": pending.id,
+                        "sessionId": pending.session_id,
+                        "characterId": pending.character_id,
+                        "messageId": pending.message_id,
+                        "preview": result.assistant_message.content.chars().take(140).collect::<String>(),
+                    }),
+                );
+            }
+            Err(err) => log_warn(
+                app,
+                "companion_proactive",
+                format!(
+                    "proactive message failed session={}: {}",
+                    row.session_id, err
+                ),
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub fn start_proactive_scheduler(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if let Err(err) = run_once(&app_handle).await {
+                log_error(
+                    &app_handle,
+                    "companion_proactive",
+                    format!("proactive scheduler run failed: {}", err),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeClock {
+        now: u64,
+        minute: u32,
+    }
+
+    impl Clock for FakeClock {
+        fn now_ms(&self) -> u64 {
+            self.now
+        }
+
+        fn local_minute_of_day(&self) -> u32 {
+            self.minute
+        }
+    }
+
+    fn config() -> ProactiveMessageConfig {
+        ProactiveMessageConfig {
+            enabled: true,
+            quiet_start_minute: 22 * 60,
+            quiet_end_minute: 8 * 60,
+            max_per_day: 2,
+            min_gap_minutes: 240,
+            min_idle_minutes: 120,
+        }
+    }
+
+    fn candidate(idle_minutes: u64, now: u64) -> ProactiveCandidate {
+        ProactiveCandidate {
+            last_interaction_at: now - idle_minutes * MINUTE_MS,
+            interaction_count: 12,
+            momentum: EmotionVector {
+                longing: 0.3,
+                ..EmotionVector::default()
+            },
+            closeness: 0.6,
+            ..ProactiveCandidate::default()
+        }
+    }
+
+    #[test]
+    fn quiet_hours_wrap_midnight() {
+        let config = config();
+        assert!(in_quiet_hours(&config, 23 * 60));
+        assert!(in_quiet_hours(&config, 7 * 60 + 59));
+        assert!(!in_quiet_hours(&config, 8 * 60));
+        assert!(!in_quiet_hours(&config, 12 * 60));
+    }
+
+    #[test]
+    fn sends_after_long_idle_outside_quiet_hours() {
+        let now = 100 * DAY_MS;
+        let clock = FakeClock {
+            now,
+            minute: 14 * 60,
+        };
+        let config = config();
+
+        assert_eq!(
+            decide(&candidate(30, now), &config, &clock),
+            ProactiveDecision::Skip(SkipReason::NotIdle)
+        );
+        assert!(matches!(
+            decide(&candidate(8 * 60, now), &config, &clock),
+            ProactiveDecision::Send { .. }
+        ));
+
+        let night = FakeClock {
+            now,
+            minute: 23 * 60,
+        };
+        assert_eq!(
+            decide(&candidate(8 * 60, now), &config, &night),
+            ProactiveDecision::Skip(SkipReason::QuietHours)
+        );
+    }
+
+    #[test]
+    fn caps_and_mood_hold_back_messages() {
+        let now = 100 * DAY_MS;
+        let clock = FakeClock {
+            now,
+            minute: 14 * 60,
+        };
+        let config = config();
+
+        let mut capped = candidate(8 * 60, now);
+        capped.sent_last_day = 2;
+        assert_eq!(
+            decide(&capped, &config, &clock),
+            ProactiveDecision::Skip(SkipReason::DailyCap)
+        );
+
+        let mut recent = candidate(8 * 60, now);
+        recent.last_proactive_at = Some(now - 60 * MINUTE_MS);
+        assert_eq!(
+            decide(&recent, &config, &clock),
+            ProactiveDecision::Skip(SkipReason::TooSoon)
+        );
+
+        let mut cold = candidate(3 * 60, now);
+        cold.closeness = 0.1;
+        cold.momentum = EmotionVector {
+            irritation: 0.4,
+            ..EmotionVector::default()
+        };
+        assert_eq!(
+            decide(&cold, &config, &clock),
+            ProactiveDecision::Skip(SkipReason::LowUrgency)
+        );
+    }
+}
@@ -584,6 +584,7 @@ async fn send_request(
             stream: Some(false),
             request_id: built.request_id,
             provider_id: Some(credential.provider_id.clone()),
+            credential_id: Some(credential.id.clone()),
         },
     )
     .await?;
@@ -632,6 +633,7 @@ async fn send_request(
                         stream: Some(false),
                         request_id: built.request_id,
                         provider_id: Some(credential.provider_id.clone()),
+                        credential_id: Some(credential.id.clone()),
                     },
                 )
                 .await;
@@ -676,6 +678,7 @@ async fn send_request(
                         stream: Some(false),
                         request_id: built.request_id,
                         provider_id: Some(credential.provider_id.clone()),
+                        credential_id: Some(credential.id.clone()),
                     },
                 )
                 .await;
@@ -1,9 +1,42 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
 use tauri::AppHandle;
 
 use crate::chat_manager::storage::resolve_credential_for_model;
-use crate::chat_manager::types::{Character, Model, ProviderCredential, Settings};
+use crate::chat_manager::types::{
+    Character, CircuitBreakerSettings, FallbackChainStep, FallbackCondition, Model,
+    ModelAttemptRecord, ProviderCredential, Settings,
+};
+use crate::storage_manager::db::{now_ms, open_db};
+use crate::storage_manager::model_fallbacks::{load_character_chain, save_message_attempts};
 use crate::utils::{emit_toast, log_info, log_warn};
 
+lazy_static! {
+    static ref CIRCUIT_BREAKER: Mutex<CircuitBreaker> = Mutex::new(CircuitBreaker::default());
+}
+
+const CONTEXT_OVERFLOW_MARKERS: &[&str] = &[
+    "context length",
+    "context window",
+    "maximum context",
+    "context_length_exceeded",
+    "too many tokens",
+    "prompt is too long",
+    "input is too long",
+];
+
+const REFUSAL_FINISH_REASONS: &[&str] = &[
+    "content_filter",
+    "safety",
+    "refusal",
+    "prohibited_content",
+    "recitation",
+    "spii",
+    "blocklist",
+];
+
 pub(crate) fn find_model_with_credential<'a>(
     settings: &'a Settings,
     model_id: &str,
@@ -13,57 +46,404 @@ pub(crate) fn find_model_with_credential<'a>(
     Some((model, credential))
 }
 
-pub(crate) fn build_model_attempts<'a>(
+/// Why a model attempt failed, as far as fallback conditions and the circuit
+/// breaker are concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttemptFailure {
+    Timeout,
+    ServerError,
+    RateLimited,
+    Network,
+    ContextOverflow,
+    ContentRefusal,
+    /// The attempt was never sent because its provider's circuit is open.
+    CircuitOpen,
+    Other,
+}
+
+impl AttemptFailure {
+    pub(crate) fn from_transport_error(err: &str) -> Self {
+        let lower = err.to_ascii_lowercase();
+        if lower.contains("timed out") || lower.contains("timeout") {
+            Self::Timeout
+        } else {
+            Self::Network
+        }
+    }
+
+    pub(crate) fn from_response(status: u16, message: &str) -> Self {
+        match status {
+            408 | 504 => Self::Timeout,
+            429 => Self::RateLimited,
+            500..=599 => Self::ServerError,
+            400..=499 if is_context_overflow_message(message) => Self::ContextOverflow,
+            _ => Self::Other,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::ServerError => "serverError",
+            Self::RateLimited => "rateLimited",
+            Self::Network => "network",
+            Self::ContextOverflow => "contextOverflow",
+            Self::ContentRefusal => "contentRefusal",
+            Self::CircuitOpen => "circuitOpen",
+            Self::Other => "other",
+        }
+    }
+
+    /// Failures that say something about the provider's health rather than
+    /// about this particular request.
+    fn counts_against_provider(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::ServerError | Self::RateLimited | Self::Network
+        )
+    }
+}
+
+fn is_context_overflow_message(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    CONTEXT_OVERFLOW_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+pub(crate) fn is_refusal_finish_reason(finish_reason: Option<&str>) -> bool {
+    finish_reason.is_some_and(|reason| {
+        let lower = reason.to_ascii_lowercase();
+        REFUSAL_FINISH_REASONS.contains(&lower.as_str())
+    })
+}
+
+fn step_accepts(conditions: &[FallbackCondition], failure: AttemptFailure) -> bool {
+    if conditions.is_empty() {
+        return failure != AttemptFailure::ContentRefusal;
+    }
+    conditions.iter().any(|condition| match condition {
+        FallbackCondition::Timeout => failure == AttemptFailure::Timeout,
+        FallbackCondition::ServerError => matches!(
+            failure,
+            AttemptFailure::ServerError | AttemptFailure::CircuitOpen
+        ),
+        FallbackCondition::ContentRefusal => failure == AttemptFailure::ContentRefusal,
+        FallbackCondition::ContextOverflow => failure == AttemptFailure::ContextOverflow,
+    })
+}
+
+/// Recent health failures per provider credential.
+#[derive(Default)]
+struct CircuitBreaker {
+    failures: HashMap<String, Vec<u64>>,
+}
+
+impl CircuitBreaker {
+    fn record_failure(&mut self, credential_id: &str, now: u64) {
+        self.failures
+            .entry(credential_id.to_string())
+            .or_default()
+            .push(now);
+    }
+
+    fn reset(&mut self, credential_id: &str) {
+        self.failures.remove(credential_id);
+    }
+
+    fn is_open(&mut self, credential_id: &str, config: &CircuitBreakerSettings, now: u64) -> bool {
+        if !config.enabled || config.failure_threshold == 0 {
+            return false;
+        }
+        let window_ms = config.window_minutes as u64 * 60_000;
+        let Some(times) = self.failures.get_mut(credential_id) else {
+            return false;
+        };
+        times.retain(|at| now.saturating_sub(*at) < window_ms);
+        times.len() >= config.failure_threshold as usize
+    }
+}
+
+fn circuit_is_open(credential_id: &str, config: &CircuitBreakerSettings, now: u64) -> bool {
+    CIRCUIT_BREAKER
+        .lock()
+        .map(|mut breaker| breaker.is_open(credential_id, config, now))
+        .unwrap_or(false)
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct ModelAttempt<'a> {
+    pub model: &'a Model,
+    pub credential: &'a ProviderCredential,
+    pub is_fallback: bool,
+}
+
+struct PlannedStep<'a> {
+    attempt: ModelAttempt<'a>,
+    conditions: Vec<FallbackCondition>,
+}
+
+/// The ordered models to try for one chat turn. The primary model always
+/// runs; each fallback only runs when its conditions accept the last failure.
+pub(crate) struct AttemptPlan<'a> {
+    steps: Vec<PlannedStep<'a>>,
+    next: usize,
+    current: Option<usize>,
+    last_failure: Option<AttemptFailure>,
+    breaker: CircuitBreakerSettings,
+    trace: Vec<ModelAttemptRecord>,
+    log_scope: String,
+}
+
+impl<'a> AttemptPlan<'a> {
+    pub(crate) fn next_attempt(&mut self, app: &AppHandle) -> Option<ModelAttempt<'a>> {
+        let now = now_ms();
+        while let Some(idx) = self.next_eligible(self.next) {
+            self.next = idx + 1;
+            let attempt = self.steps[idx].attempt;
+            if circuit_is_open(&attempt.credential.id, &self.breaker, now)
+                && self.has_closed_step_after(idx, now)
+            {
+                log_warn(
+                    app,
+                    &self.log_scope,
+                    format!(
+                        "circuit open for provider={} credential={}, skipping model={}",
+                        attempt.credential.provider_id, attempt.credential.id, attempt.model.name
+                    ),
+                );
+                let mut record = attempt_record(attempt, now);
+                record.outcome = "skipped".to_string();
+                record.failure = Some(AttemptFailure::CircuitOpen.as_str().to_string());
+                self.trace.push(record);
+                self.last_failure = Some(AttemptFailure::CircuitOpen);
+                continue;
+            }
+            self.trace.push(attempt_record(attempt, now));
+            self.current = Some(self.trace.len() - 1);
+            return Some(attempt);
+        }
+        None
+    }
+
+    /// Records the failure of the current attempt and reports whether a later
+    /// step is willing to run for it.
+    pub(crate) fn fail(&mut self, failure: AttemptFailure, message: &str) -> bool {
+        let now = now_ms();
+        if let Some(record) = self.current.take().map(|idx| &mut self.trace[idx]) {
+            record.outcome = "failed".to_string();
+            record.failure = Some(failure.as_str().to_string());
+            record.error = Some(message.to_string());
+            record.elapsed_ms = Some(now.saturating_sub(record.started_at));
+            if failure.counts_against_provider() {
+                if let Ok(mut breaker) = CIRCUIT_BREAKER.lock() {
+                    breaker.record_failure(&record.credential_id, now);
+                }
+            }
+        }
+        self.last_failure = Some(failure);
+        self.next_eligible(self.next).is_some()
+    }
+
+    pub(crate) fn succeed(&mut self) {
+        let now = now_ms();
+        if let Some(record) = self.current.take().map(|idx| &mut self.trace[idx]) {
+            record.outcome = "success".to_string();
+            record.elapsed_ms = Some(now.saturating_sub(record.started_at));
+            if let Ok(mut breaker) = CIRCUIT_BREAKER.lock() {
+                breaker.reset(&record.credential_id);
+            }
+        }
+    }
+
+    /// Whether a later step would run for `failure`, without recording it.
+    pub(crate) fn has_fallback_for(&self, failure: AttemptFailure) -> bool {
+        self.steps
+            .iter()
+            .skip(self.next)
+            .any(|step| step_accepts(&step.conditions, failure))
+    }
+
+    pub(crate) fn trace(&self) -> &[ModelAttemptRecord] {
+        &self.trace
+    }
+
+    fn next_eligible(&self, from: usize) -> Option<usize> {
+        if from == 0 {
+            return (!self.steps.is_empty()).then_some(0);
+        }
+        let failure = self.last_failure?;
+        (from..self.steps.len()).find(|idx| step_accepts(&self.steps[*idx].conditions, failure))
+    }
+
+    fn has_closed_step_after(&self, idx: usize, now: u64) -> bool {
+        self.steps[idx + 1..].iter().any(|step| {
+            step_accepts(&step.conditions, AttemptFailure::CircuitOpen)
+                && !circuit_is_open(&step.attempt.credential.id, &self.breaker, now)
+        })
+    }
+}
+
+fn attempt_record(attempt: ModelAttempt<'_>, now: u64) -> ModelAttemptRecord {
+    ModelAttemptRecord {
+        model_id: attempt.model.id.clone(),
+        model_name: attempt.model.name.clone(),
+        provider_id: attempt.credential.provider_id.clone(),
+        credential_id: attempt.credential.id.clone(),
+        is_fallback: attempt.is_fallback,
+        outcome: "pending".to_string(),
+        failure: None,
+        error: None,
+        started_at: now,
+        elapsed_ms: None,
+    }
+}
+
+fn push_chain_steps<'a>(
+    app: &AppHandle,
+    settings: &'a Settings,
+    chain: &[FallbackChainStep],
+    steps: &mut Vec<PlannedStep<'a>>,
+    log_scope: &str,
+) {
+    for step in chain {
+        match find_model_with_credential(settings, &step.model_id) {
+            Some((model, credential)) => steps.push(PlannedStep {
+                attempt: ModelAttempt {
+                    model,
+                    credential,
+                    is_fallback: true,
+                },
+                conditions: step.conditions.clone(),
+            }),
+            None => log_warn(
+                app,
+                log_scope,
+                format!(
+                    "fallback chain model id {} could not be resolved",
+                    step.model_id
+                ),
+            ),
+        }
+    }
+}
+
+/// Primary model first, then the character's chain (or its legacy single
+/// fallback model), then the app-wide chain.
+pub(crate) fn build_attempt_plan<'a>(
     app: &AppHandle,
     settings: &'a Settings,
     character: &Character,
     primary_model: &'a Model,
     primary_credential: &'a ProviderCredential,
     log_scope: &str,
-) -> Vec<(&'a Model, &'a ProviderCredential, bool)> {
-    let explicit_fallback_candidate = character
-        .fallback_model_id
-        .as_ref()
-        .filter(|fallback_id| *fallback_id != &primary_model.id)
-        .and_then(|fallback_id| find_model_with_credential(settings, fallback_id));
+) -> AttemptPlan<'a> {
+    let mut steps = vec![PlannedStep {
+        attempt: ModelAttempt {
+            model: primary_model,
+            credential: primary_credential,
+            is_fallback: false,
+        },
+        conditions: Vec::new(),
+    }];
 
-    let app_default_fallback_candidate = settings
-        .default_model_id
-        .as_ref()
-        .filter(|default_id| *default_id != &primary_model.id)
-        .and_then(|default_id| find_model_with_credential(settings, default_id));
-
-    let mut attempts: Vec<(&Model, &ProviderCredential, bool)> =
-        vec![(primary_model, primary_credential, false)];
-    if let Some((fallback_model, fallback_cred)) = explicit_fallback_candidate {
-        attempts.push((fallback_model, fallback_cred, true));
-    } else if character
+    let character_chain =
+        match open_db(app).and_then(|conn| load_character_chain(&conn, &character.id)) {
+            Ok(chain) => chain,
+            Err(err) => {
+                log_warn(
+                    app,
+                    log_scope,
+                    format!("failed to load character fallback chain: {}", err),
+                );
+                Vec::new()
+            }
+        };
+
+    if !character_chain.is_empty() {
+        push_chain_steps(app, settings, &character_chain, &mut steps, log_scope);
+    } else if let Some(fallback_id) = character
         .fallback_model_id
         .as_ref()
-        .is_some_and(|id| id != &primary_model.id)
+        .filter(|fallback_id| *fallback_id != &primary_model.id)
     {
-        log_warn(
-            app,
-            log_scope,
-            format!(
-                "configured character fallback model id {} could not be resolved",
-                character.fallback_model_id.as_deref().unwrap_or("")
-            ),
-        );
-        if let Some((fallback_model, fallback_cred)) = app_default_fallback_candidate {
+        let candidate = find_model_with_credential(settings, fallback_id).or_else(|| {
+            log_warn(
+                app,
+                log_scope,
+                format!(
+                    "configured character fallback model id {} could not be resolved",
+                    fallback_id
+                ),
+            );
+            let (model, credential) = settings
+                .default_model_id
+                .as_ref()
+                .filter(|default_id| *default_id != &primary_model.id)
+                .and_then(|default_id| find_model_with_credential(settings, default_id))?;
             log_info(
                 app,
                 log_scope,
                 format!(
                     "using app default model {} as fallback candidate",
-                    fallback_model.name
+                    model.name
                 ),
             );
-            attempts.push((fallback_model, fallback_cred, true));
+            Some((model, credential))
+        });
+        if let Some((model, credential)) = candidate {
+            steps.push(PlannedStep {
+                attempt: ModelAttempt {
+                    model,
+                    credential,
+                    is_fallback: true,
+                },
+                conditions: Vec::new(),
+            });
         }
     }
 
-    attempts
+    let advanced = settings.advanced_settings.as_ref();
+    if let Some(global_chain) = advanced.and_then(|a| a.fallback_chain.as_ref()) {
+        push_chain_steps(app, settings, global_chain, &mut steps, log_scope);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    steps.retain(|step| seen.insert(step.attempt.model.id.clone()));
+
+    AttemptPlan {
+        steps,
+        next: 0,
+        current: None,
+        last_failure: None,
+        breaker: advanced.and_then(|a| a.circuit_breaker).unwrap_or_default(),
+        trace: Vec::new(),
+        log_scope: log_scope.to_string(),
+    }
+}
+
+/// Keeps the attempt trace of a message that needed more than one model, and
+/// drops a stale one when a regeneration succeeded on the first try.
+pub(crate) fn persist_attempt_trace(
+    app: &AppHandle,
+    plan: &AttemptPlan<'_>,
+    session_id: &str,
+    message_id: &str,
+) {
+    let attempts = if plan.trace().len() > 1 {
+        plan.trace()
+    } else {
+        &[]
+    };
+    if let Err(err) =
+        open_db(app).and_then(|conn| save_message_attempts(&conn, session_id, message_id, attempts))
+    {
+        log_warn(
+            app,
+            &plan.log_scope,
+            format!("failed to save model attempt trace: {}", err),
+        );
+    }
 }
 
 pub(crate) fn emit_fallback_retry_toast(app: &AppHandle, shown: &mut bool) {
@@ -78,3 +458,88 @@ pub(crate) fn emit_fallback_retry_toast(app: &AppHandle, shown: &mut bool) {
     );
     *shown = true;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_failures_from_status_and_message() {
+        assert_eq!(
+            AttemptFailure::from_response(503, "overloaded"),
+            AttemptFailure::ServerError
+        );
+        assert_eq!(
+            AttemptFailure::from_response(429, "slow down"),
+            AttemptFailure::RateLimited
+        );
+        assert_eq!(
+            AttemptFailure::from_response(
+                400,
+                "This model's maximum context length is 8192 tokens"
+            ),
+            AttemptFailure::ContextOverflow
+        );
+        assert_eq!(
+            AttemptFailure::from_response(400, "invalid temperature"),
+            AttemptFailure::Other
+        );
+        assert_eq!(
+            AttemptFailure::from_transport_error("operation timed out"),
+            AttemptFailure::Timeout
+        );
+        assert!(is_refusal_finish_reason(Some("content_filter")));
+        assert!(is_refusal_finish_reason(Some("SAFETY")));
+        assert!(!is_refusal_finish_reason(Some("stop")));
+        assert!(!is_refusal_finish_reason(None));
+    }
+
+    #[test]
+    fn steps_without_conditions_skip_only_refusals() {
+        assert!(step_accepts(&[], AttemptFailure::Other));
+        assert!(step_accepts(&[], AttemptFailure::ContextOverflow));
+        assert!(!step_accepts(&[], AttemptFailure::ContentRefusal));
+
+        let on_server_error = [FallbackCondition::ServerError];
+        assert!(step_accepts(&on_server_error, AttemptFailure::ServerError));
+        assert!(step_accepts(&on_server_error, AttemptFailure::CircuitOpen));
+        assert!(!step_accepts(&on_server_error, AttemptFailure::Timeout));
+
+        let on_refusal = [FallbackCondition::ContentRefusal];
+        assert!(step_accepts(&on_refusal, AttemptFailure::ContentRefusal));
+        assert!(!step_accepts(&on_refusal, AttemptFailure::ServerError));
+    }
+
+    #[test]
+    fn circuit_opens_at_threshold_and_closes_after_window() {
+        let config = CircuitBreakerSettings::default();
+        let mut breaker = CircuitBreaker::default();
+        let start = 1_000_000;
+
+        breaker.record_failure("cred", start);
+        breaker.record_failure("cred", start + 1_000);
+        assert!(!breaker.is_open("cred", &config, start + 2_000));
+
+        breaker.record_failure("cred", start + 2_000);
+        assert!(breaker.is_open("cred", &config, start + 3_000));
+        assert!(!breaker.is_open("other", &config, start + 3_000));
+
+        let later = start + config.window_minutes as u64 * 60_000 + 1_000;
+        assert!(!breaker.is_open("cred", &config, later));
+
+        breaker.record_failure("cred", later);
+        breaker.record_failure("cred", later);
+        breaker.record_failure("cred", later);
+        breaker.reset("cred");
+        assert!(!breaker.is_open("cred", &config, later));
+
+        let disabled = CircuitBreakerSettings {
+            enabled: false,
+            ..config
+        };
+        breaker.record_failure("cred", later);
+        breaker.record_failure("cred", later);
+        breaker.record_failure("cred", later);
+        assert!(!breaker.is_open("cred", &disabled, later));
+    }
+}
@@ -0,0 +1,95 @@
+//! Grounding documents for providers that take them (Cohere): injected
+//! lorebook entries and retrieved memories ride along on a system message,
+//! and the citations of the reply are stored with the assistant message.
+
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::chat_manager::messages::push_system_message;
+use crate::chat_manager::provider_adapter::cohere::{
+    citations_from_response, is_cohere_provider, DOCUMENTS_KEY, LORE_DOCUMENT_PREFIX,
+    MEMORY_DOCUMENT_PREFIX,
+};
+use crate::chat_manager::types::{MemoryEmbedding, ProviderCredential};
+use crate::storage_manager::db::open_db;
+use crate::storage_manager::lorebook::LorebookEntry;
+use crate::storage_manager::message_citations::save_message_citations;
+use crate::utils::log_warn;
+
+pub(crate) fn grounding_documents(
+    injected_lore: &[(String, LorebookEntry)],
+    memories: &[MemoryEmbedding],
+) -> Vec<Value> {
+    let lore = injected_lore.iter().map(|(label, entry)| {
+        json!({
+            "id": format!("{}{}", LORE_DOCUMENT_PREFIX, entry.id),
+            "title": label,
+            "snippet": entry.content.trim(),
+        })
+    });
+    let memories = memories.iter().map(|memory| {
+        json!({
+            "id": format!("{}{}", MEMORY_DOCUMENT_PREFIX, memory.id),
+            "title": "Memory",
+            "snippet": memory.text.trim(),
+        })
+    });
+    lore.chain(memories).collect()
+}
+
+/// Pushes `memory_block` as a system message carrying `documents`. The block
+/// stays the plain-text fallback for other providers; when the documents do
+/// not cover it (manual memories), it is pushed on its own instead.
+pub(crate) fn push_grounding_message(
+    target: &mut Vec<Value>,
+    system_role: &str,
+    memory_block: Option<String>,
+    documents: Vec<Value>,
+) {
+    if documents.is_empty() {
+        push_system_message(target, system_role, memory_block);
+        return;
+    }
+    let covers_memories = documents.iter().any(|doc| {
+        doc.get("id")
+            .and_then(Value::as_str)
+            .is_some_and(|id| id.starts_with(MEMORY_DOCUMENT_PREFIX))
+    });
+    let fallback = if covers_memories {
+        memory_block.unwrap_or_default()
+    } else {
+        push_system_message(target, system_role, memory_block);
+        String::new()
+    };
+    target.push(json!({
+        "role": system_role,
+        "content": fallback,
+        DOCUMENTS_KEY: documents,
+    }));
+}
+
+/// Saves the citations returned for a message, clearing those of an earlier
+/// generation when there are none.
+pub(crate) fn persist_citations(
+    app: &AppHandle,
+    credential: &ProviderCredential,
+    session_id: &str,
+    message_id: &str,
+    variant_id: Option<&str>,
+    response: &Value,
+) {
+    let citations = if is_cohere_provider(&credential.provider_id) {
+        citations_from_response(response)
+    } else {
+        Vec::new()
+    };
+    if let Err(err) = open_db(app).and_then(|conn| {
+        save_message_citations(&conn, session_id, message_id, variant_id, &citations)
+    }) {
+        log_warn(
+            app,
+            "citations",
+            format!("failed to save citations: {}", err),
+        );
+    }
+}
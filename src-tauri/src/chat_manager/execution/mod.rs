@@ -828,8 +828,17 @@ pub(crate) fn prepare_default_sampling_request(
 mod fallback;
 
 pub(crate) use fallback::{
-    build_model_attempts, emit_fallback_retry_toast, find_model_with_credential,
+    build_attempt_plan, emit_fallback_retry_toast, find_model_with_credential,
+    is_refusal_finish_reason, persist_attempt_trace, AttemptFailure,
 };
 
+mod grounding;
+pub(crate) use grounding::{grounding_documents, persist_citations, push_grounding_message};
+
 mod provider_fields;
 pub(crate) use provider_fields::{build_provider_extra_fields, RequestSettings};
+
+mod reasoning_items;
+pub(crate) use reasoning_items::{
+    attach_reasoning_items, load_reasoning_items, persist_reasoning_items,
+};
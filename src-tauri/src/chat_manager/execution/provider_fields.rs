@@ -1,7 +1,9 @@
 use serde_json::{json, Map, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::chat_manager::types::{Model, Session, Settings};
+use crate::chat_manager::provider_adapter::output_controls::{OutputControls, OUTPUT_CONTROLS_KEY};
+use crate::chat_manager::types::{AdvancedModelSettings, Model, Session, Settings};
+use crate::utils::log_warn_global;
 
 use super::{
     is_llama_cpp_model, llama_sampler_profile_defaults, resolve_context_length,
@@ -360,6 +362,79 @@ fn build_ollama_extra_fields(
     Some(extra)
 }
 
+/// KoboldCpp's extended samplers, keyed by their `/api/v1/generate` field
+/// names. Session settings win over the model's, which win over the global
+/// defaults.
+fn build_koboldcpp_extra_fields(
+    session: &Session,
+    model: &Model,
+    settings: &Settings,
+) -> Option<HashMap<String, Value>> {
+    let layers = [
+        session.advanced_model_settings.as_ref(),
+        model.advanced_model_settings.as_ref(),
+        Some(&settings.advanced_model_settings),
+    ];
+    let pick = |field: fn(&AdvancedModelSettings) -> Option<Value>| {
+        layers.iter().flatten().find_map(|cfg| field(cfg))
+    };
+
+    let fields: [(&str, fn(&AdvancedModelSettings) -> Option<Value>); 18] = [
+        ("rep_pen", |cfg| cfg.kobold_rep_pen.map(|v| json!(v))),
+        ("rep_pen_range", |cfg| {
+            cfg.kobold_rep_pen_range.map(|v| json!(v))
+        }),
+        ("rep_pen_slope", |cfg| {
+            cfg.kobold_rep_pen_slope.map(|v| json!(v))
+        }),
+        ("min_p", |cfg| cfg.kobold_min_p.map(|v| json!(v))),
+        ("top_a", |cfg| cfg.kobold_top_a.map(|v| json!(v))),
+        ("typical", |cfg| cfg.kobold_typical.map(|v| json!(v))),
+        ("tfs", |cfg| cfg.kobold_tfs.map(|v| json!(v))),
+        ("dry_multiplier", |cfg| {
+            cfg.kobold_dry_multiplier.map(|v| json!(v))
+        }),
+        ("dry_base", |cfg| cfg.kobold_dry_base.map(|v| json!(v))),
+        ("dry_allowed_length", |cfg| {
+            cfg.kobold_dry_allowed_length.map(|v| json!(v))
+        }),
+        ("dry_penalty_last_n", |cfg| {
+            cfg.kobold_dry_penalty_last_n.map(|v| json!(v))
+        }),
+        ("dry_sequence_breakers", |cfg| {
+            cfg.kobold_dry_sequence_breakers.as_ref().map(|v| json!(v))
+        }),
+        ("xtc_threshold", |cfg| {
+            cfg.kobold_xtc_threshold.map(|v| json!(v))
+        }),
+        ("xtc_probability", |cfg| {
+            cfg.kobold_xtc_probability.map(|v| json!(v))
+        }),
+        ("smoothing_factor", |cfg| {
+            cfg.kobold_smoothing_factor.map(|v| json!(v))
+        }),
+        ("smoothing_curve", |cfg| {
+            cfg.kobold_smoothing_curve.map(|v| json!(v))
+        }),
+        ("sampler_order", |cfg| {
+            cfg.kobold_sampler_order.as_ref().map(|v| json!(v))
+        }),
+        ("banned_tokens", |cfg| {
+            cfg.kobold_banned_tokens.as_ref().map(|v| json!(v))
+        }),
+    ];
+
+    let extra: HashMap<String, Value> = fields
+        .iter()
+        .filter_map(|(key, field)| pick(*field).map(|value| (key.to_string(), value)))
+        .collect();
+    if extra.is_empty() {
+        None
+    } else {
+        Some(extra)
+    }
+}
+
 fn resolve_reasoning_enabled(session: &Session, model: &Model, _settings: &Settings) -> bool {
     session
         .advanced_model_settings
@@ -487,6 +562,85 @@ impl RequestSettings {
     }
 }
 
+fn resolve_output_setting<T>(
+    session: &Session,
+    model: &Model,
+    settings: &Settings,
+    pick: fn(&AdvancedModelSettings) -> Option<T>,
+) -> Option<T> {
+    session
+        .advanced_model_settings
+        .as_ref()
+        .and_then(pick)
+        .or_else(|| model.advanced_model_settings.as_ref().and_then(pick))
+        .or_else(|| pick(&settings.advanced_model_settings))
+}
+
+fn non_empty_strings(values: Option<Vec<String>>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for value in values.unwrap_or_default() {
+        if !value.is_empty() && !out.contains(&value) {
+            out.push(value);
+        }
+    }
+    out
+}
+
+/// Keys are token ids, or text that is split with the model's tokenizer and
+/// biases every resulting token. Text is dropped when no tokenizer is loaded.
+fn resolve_logit_bias(model: &Model, bias: &HashMap<String, f64>) -> BTreeMap<u32, f64> {
+    let encoder = crate::tokens::registry::loaded_encoder(model);
+    let mut out = BTreeMap::new();
+    for (key, value) in bias {
+        let value = value.clamp(-100.0, 100.0);
+        if let Ok(token_id) = key.trim().parse::<u32>() {
+            out.insert(token_id, value);
+            continue;
+        }
+        match encoder.as_ref().and_then(|encoder| encoder.encode(key)) {
+            Some(ids) => {
+                for token_id in ids {
+                    out.insert(token_id, value);
+                }
+            }
+            None => log_warn_global(
+                "provider_fields",
+                format!(
+                    "skipping logit bias for {:?}: no tokenizer loaded for {}",
+                    key, model.name
+                ),
+            ),
+        }
+    }
+    out
+}
+
+/// Stop sequences, logit bias and banned strings for every provider. The
+/// request builder writes them in the adapter's shape.
+fn build_output_controls(session: &Session, model: &Model, settings: &Settings) -> Option<Value> {
+    let controls = OutputControls {
+        stop_sequences: non_empty_strings(resolve_output_setting(
+            session,
+            model,
+            settings,
+            |cfg| cfg.stop_sequences.clone(),
+        )),
+        logit_bias: resolve_output_setting(session, model, settings, |cfg| cfg.logit_bias.clone())
+            .map(|bias| resolve_logit_bias(model, &bias))
+            .unwrap_or_default(),
+        banned_strings: non_empty_strings(resolve_output_setting(
+            session,
+            model,
+            settings,
+            |cfg| cfg.banned_strings.clone(),
+        )),
+    };
+    if controls.is_empty() {
+        return None;
+    }
+    serde_json::to_value(controls).ok()
+}
+
 pub(crate) fn build_provider_extra_fields(
     provider_id: &str,
     session: &Session,
@@ -501,6 +655,10 @@ pub(crate) fn build_provider_extra_fields(
         if let Some(llama_extra) = build_llama_extra_fields(session, model, settings) {
             extra.extend(llama_extra);
         }
+    } else if provider_id == "koboldcpp" {
+        if let Some(kobold_extra) = build_koboldcpp_extra_fields(session, model, settings) {
+            extra.extend(kobold_extra);
+        }
     } else if provider_id == "ollama" {
         if let Some(ollama_extra) =
             build_ollama_extra_fields(session, model, settings, request_settings)
@@ -532,6 +690,10 @@ pub(crate) fn build_provider_extra_fields(
         extra.insert("promptCachingTtl".to_string(), json!(ttl));
     }
 
+    if let Some(controls) = build_output_controls(session, model, settings) {
+        extra.insert(OUTPUT_CONTROLS_KEY.to_string(), controls);
+    }
+
     if extra.is_empty() {
         None
     } else {
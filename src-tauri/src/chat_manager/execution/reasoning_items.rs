@@ -0,0 +1,84 @@
+//! Carries OpenAI Responses reasoning items from one turn to the next: they
+//! are saved with the assistant message that produced them and attached to
+//! that message again when it is replayed as history.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::chat_manager::provider_adapter::openai_responses::{
+    is_responses_credential, reasoning_items_from_response, REASONING_ITEMS_KEY,
+};
+use crate::chat_manager::types::{ProviderCredential, StoredMessage};
+use crate::storage_manager::db::open_db;
+use crate::storage_manager::reasoning_items::{
+    load_session_reasoning_items, save_message_reasoning_items, StoredReasoningItems,
+};
+use crate::utils::log_warn;
+
+pub(crate) fn load_reasoning_items(
+    app: &AppHandle,
+    session_id: &str,
+) -> HashMap<String, StoredReasoningItems> {
+    open_db(app)
+        .and_then(|conn| load_session_reasoning_items(&conn, session_id))
+        .unwrap_or_else(|err| {
+            log_warn(
+                app,
+                "reasoning_items",
+                format!("failed to load reasoning items: {}", err),
+            );
+            HashMap::new()
+        })
+}
+
+/// Tags the API messages just pushed for `message` with its stored reasoning
+/// items, as long as they belong to the variant being sent.
+pub(crate) fn attach_reasoning_items(
+    pushed: &mut [Value],
+    message: &StoredMessage,
+    stored: &HashMap<String, StoredReasoningItems>,
+) {
+    if message.role != "assistant" {
+        return;
+    }
+    let Some(entry) = stored.get(&message.id) else {
+        return;
+    };
+    if entry.variant_id.is_some() && entry.variant_id != message.selected_variant_id {
+        return;
+    }
+    if let Some(obj) = pushed.last_mut().and_then(Value::as_object_mut) {
+        obj.insert(
+            REASONING_ITEMS_KEY.to_string(),
+            Value::Array(entry.items.clone()),
+        );
+    }
+}
+
+/// Saves the reasoning items returned for a message, replacing (or clearing)
+/// whatever an earlier generation of it left behind.
+pub(crate) fn persist_reasoning_items(
+    app: &AppHandle,
+    credential: &ProviderCredential,
+    session_id: &str,
+    message_id: &str,
+    variant_id: Option<&str>,
+    response: &Value,
+) {
+    let items = if is_responses_credential(credential) {
+        reasoning_items_from_response(response)
+    } else {
+        Vec::new()
+    };
+    if let Err(err) = open_db(app).and_then(|conn| {
+        save_message_reasoning_items(&conn, session_id, message_id, variant_id, &items)
+    }) {
+        log_warn(
+            app,
+            "reasoning_items",
+            format!("failed to save reasoning items: {}", err),
+        );
+    }
+}
@@ -8,13 +8,21 @@ use crate::chat_manager::attachments::{
 };
 use crate::chat_manager::commands::take_aborted_request;
 use crate::chat_manager::companion;
+use crate::chat_manager::context_budget::{fit_history_to_context, record_context_fit};
 use crate::chat_manager::execution::{
-    build_model_attempts, build_provider_extra_fields, emit_fallback_retry_toast, RequestSettings,
+    attach_reasoning_items, build_attempt_plan, build_provider_extra_fields,
+    emit_fallback_retry_toast, grounding_documents, is_refusal_finish_reason, load_reasoning_items,
+    persist_attempt_trace, persist_citations, persist_reasoning_items, push_grounding_message,
+    AttemptFailure, RequestSettings,
 };
+use crate::chat_manager::memory::character::character_memory_prompt_entry;
 use crate::chat_manager::memory::dynamic::{
     context_enrichment_enabled, dynamic_min_similarity, dynamic_retrieval_limit,
-    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, mark_memories_accessed,
-    promote_cold_memories,
+    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, hybrid_retrieval_boosts,
+    mark_memories_accessed, promote_cold_memories,
+};
+use crate::chat_manager::memory::entity_graph::{
+    entity_graph_prompt_entry, spawn_entity_ingest, EntitySource,
 };
 use crate::chat_manager::memory::flow::{
     enqueue_post_turn_dynamic_memory, select_relevant_memories,
@@ -170,23 +178,37 @@ impl CompletionFlow {
         session.messages.push(user_msg.clone());
         session.updated_at = now;
 
-        if companion::update_state_for_user_message(
+        let user_ledger = companion::update_state_for_user_message(
             &app,
             &mut session,
             &character,
             &user_message,
             now,
         )
-        .await
-        {
+        .await;
+        if user_ledger.is_some() {
             log_info(
                 &app,
                 "companion",
                 format!("updated companion state for session={}", session.id),
             );
         }
+        if companion::is_companion_mode(&session, &character) {
+            if let Err(err) = crate::storage_manager::db::open_db(&app).and_then(|conn| {
+                crate::storage_manager::companion_proactive::mark_session_seen(&conn, &session.id)
+            }) {
+                log_warn(
+                    &app,
+                    "companion",
+                    format!("failed to resolve proactive messages: {}", err),
+                );
+            }
+        }
 
         context.save_session(&session)?;
+        if let Some(pending) = user_ledger {
+            companion::merge_into_ledger(&app, pending);
+        }
 
         emit_debug(
             &app,
@@ -199,7 +221,7 @@ impl CompletionFlow {
             }),
         );
 
-        let prompt_entries = if swap_places {
+        let mut prompt_entries = if swap_places {
             let (prompt_character, prompt_persona) =
                 swapped_prompt_entities(&character, persona.as_ref());
             append_image_directive_instructions(
@@ -218,14 +240,38 @@ impl CompletionFlow {
             )
         };
 
-        let used_lorebook_entries =
-            crate::chat_manager::prompt_engine::resolve_used_lorebook_entries(
+        if let Some(entry) = character_memory_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages,
+        )
+        .await
+        {
+            prompt_entries.push(entry);
+        }
+        if let Some(entry) = entity_graph_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages,
+        ) {
+            prompt_entries.push(entry);
+        }
+
+        let injected_lorebook_entries =
+            crate::chat_manager::prompt_engine::resolve_injected_lorebook_entries(
                 &app,
                 &character.id,
                 persona.as_ref(),
                 &session,
                 &prompt_entries,
             );
+        let used_lorebook_entries = crate::chat_manager::prompt_engine::used_lorebook_entry_labels(
+            &injected_lorebook_entries,
+        );
         let (relative_entries, in_chat_entries) = partition_prompt_entries(prompt_entries);
 
         let (pinned_msgs, recent_msgs) = if dynamic_memory_enabled {
@@ -276,6 +322,7 @@ impl CompletionFlow {
                 dynamic_retrieval_limit(settings),
                 dynamic_min_similarity(settings),
                 dynamic_retrieval_strategy(settings),
+                hybrid_retrieval_boosts(settings),
             )
             .await
         } else {
@@ -357,12 +404,25 @@ impl CompletionFlow {
         } else {
             None
         };
-        if let Some(block) = memory_block {
-            push_system_message(
-                &mut messages_for_api,
-                &system_role,
-                Some(format!("Relevant memories:\n{}", block)),
-            );
+        push_grounding_message(
+            &mut messages_for_api,
+            &system_role,
+            memory_block.map(|block| format!("Relevant memories:\n{}", block)),
+            grounding_documents(&injected_lorebook_entries, &relevant_memories),
+        );
+
+        let context_fit = fit_history_to_context(
+            &app,
+            &session,
+            &model,
+            settings,
+            &messages_for_api,
+            &in_chat_entries,
+            &pinned_msgs,
+            recent_msgs,
+        );
+        if let Some(summary) = context_fit.summary.clone() {
+            push_system_message(&mut messages_for_api, &system_role, Some(summary));
         }
 
         let char_name = if swap_places {
@@ -380,10 +440,12 @@ impl CompletionFlow {
             .iter()
             .any(|scope| scope.eq_ignore_ascii_case("image"));
 
+        let reasoning_items = load_reasoning_items(&app, &session.id);
         let mut chat_messages = Vec::new();
         for msg in &pinned_msgs {
             let msg_with_data = load_attachment_data(&app, msg);
             let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
+            let pushed_from = chat_messages.len();
             push_user_or_assistant_message_with_context(
                 &mut chat_messages,
                 &msg_with_data,
@@ -391,11 +453,17 @@ impl CompletionFlow {
                 persona_name,
                 allow_image_input,
             );
+            attach_reasoning_items(
+                &mut chat_messages[pushed_from..],
+                &msg_with_data,
+                &reasoning_items,
+            );
         }
 
-        for msg in &recent_msgs {
+        for msg in &context_fit.kept {
             let msg_with_data = load_attachment_data(&app, msg);
             let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
+            let pushed_from = chat_messages.len();
             push_user_or_assistant_message_with_context(
                 &mut chat_messages,
                 &msg_with_data,
@@ -403,6 +471,11 @@ impl CompletionFlow {
                 persona_name,
                 allow_image_input,
             );
+            attach_reasoning_items(
+                &mut chat_messages[pushed_from..],
+                &msg_with_data,
+                &reasoning_items,
+            );
         }
 
         insert_in_chat_prompt_entries(&mut chat_messages, &system_role, &in_chat_entries);
@@ -416,7 +489,7 @@ impl CompletionFlow {
             None
         };
 
-        let attempts = build_model_attempts(
+        let mut plan = build_attempt_plan(
             &app,
             settings,
             &character,
@@ -433,10 +506,10 @@ impl CompletionFlow {
         let mut last_error = "request failed".to_string();
         let mut fallback_toast_shown = false;
 
-        for (idx, (attempt_model, attempt_credential, is_fallback_attempt)) in
-            attempts.iter().enumerate()
-        {
-            let has_next_attempt = idx + 1 < attempts.len();
+        while let Some(attempt) = plan.next_attempt(&app) {
+            let attempt_model = attempt.model;
+            let attempt_credential = attempt.credential;
+            let is_fallback_attempt = attempt.is_fallback;
 
             let attempt_api_key = match require_api_key(&app, attempt_credential, "chat_completion")
             {
@@ -450,6 +523,7 @@ impl CompletionFlow {
                             attempt_model.name, attempt_credential.provider_id, err
                         ),
                     );
+                    let has_next_attempt = plan.fail(AttemptFailure::Other, &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -563,6 +637,7 @@ impl CompletionFlow {
                 stream: Some(built.stream),
                 request_id: built.request_id.clone(),
                 provider_id: Some(attempt_credential.provider_id.clone()),
+                credential_id: Some(attempt_credential.id.clone()),
             };
 
             let api_response = match api_request(app.clone(), api_request_payload).await {
@@ -576,6 +651,8 @@ impl CompletionFlow {
                             attempt_model.name, attempt_credential.provider_id, err
                         ),
                     );
+                    let has_next_attempt =
+                        plan.fail(AttemptFailure::from_transport_error(&err), &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -604,6 +681,10 @@ impl CompletionFlow {
                 let err_message =
                     extract_error_message(api_response.data()).unwrap_or(fallback.clone());
                 let failed_usage = extract_usage(api_response.data());
+                let has_next_attempt = plan.fail(
+                    AttemptFailure::from_response(api_response.status, &err_message),
+                    &err_message,
+                );
 
                 if !has_next_attempt {
                     record_failed_usage(
@@ -646,10 +727,31 @@ impl CompletionFlow {
                 return Err(last_error);
             }
 
+            let finish_reason =
+                extract_usage(api_response.data()).and_then(|usage| usage.finish_reason);
+            if is_refusal_finish_reason(finish_reason.as_deref())
+                && plan.has_fallback_for(AttemptFailure::ContentRefusal)
+            {
+                let message = format!(
+                    "Model refused the request (finish reason {})",
+                    finish_reason.as_deref().unwrap_or_default()
+                );
+                log_warn(
+                    &app,
+                    "chat_completion",
+                    format!("{} model={}", message, attempt_model.name),
+                );
+                plan.fail(AttemptFailure::ContentRefusal, &message);
+                last_error = message;
+                emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
+                continue;
+            }
+            plan.succeed();
+
             selected_model = attempt_model;
             selected_credential = attempt_credential;
             selected_api_key = attempt_api_key;
-            fallback_from_model_id = if *is_fallback_attempt {
+            fallback_from_model_id = if is_fallback_attempt {
                 Some(model.id.clone())
             } else {
                 None
@@ -801,11 +903,49 @@ impl CompletionFlow {
 
         session.messages.push(assistant_message.clone());
         session.updated_at = now_millis()?;
+        let reply_update = companion::update_state_for_reply(
+            &app,
+            &mut session,
+            &character,
+            &assistant_message.id,
+            &assistant_message.content,
+            session.updated_at,
+        )
+        .await;
         if take_aborted_request(&app, request_id.as_deref()) {
             cleanup_attachments(&app, &assistant_message.attachments, "chat_completion");
             return Err("Request aborted by user".to_string());
         }
         context.save_session(&session)?;
+        let reply_effect = reply_update.map(|update| {
+            companion::merge_into_ledger(&app, update.ledger);
+            update.turn_effect
+        });
+        persist_attempt_trace(&app, &plan, &session.id, &assistant_message.id);
+        persist_reasoning_items(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_message.id,
+            assistant_message.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        persist_citations(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_message.id,
+            assistant_message.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        record_context_fit(
+            &app,
+            &session,
+            &character,
+            settings,
+            &assistant_message.id,
+            &context_fit,
+        );
 
         log_info(
             &app,
@@ -851,10 +991,29 @@ impl CompletionFlow {
                 session.id.clone(),
                 Some(user_msg.id.clone()),
                 assistant_message.id.clone(),
-                companion_mode_enabled.then(Default::default),
+                reply_effect.or_else(|| companion_mode_enabled.then(Default::default)),
+            );
+        } else if let Some(seed) = reply_effect {
+            companion::record_turn_effect(
+                &app,
+                &session.id,
+                Some(user_msg.id.as_str()),
+                &assistant_message.id,
+                seed,
             );
         }
 
+        spawn_entity_ingest(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            vec![
+                EntitySource::message(&user_msg),
+                EntitySource::message(&assistant_message),
+            ],
+        );
+
         Ok(ChatTurnResult {
             session_id: session.id,
             session_updated_at: session.updated_at,
@@ -8,13 +8,21 @@ use crate::chat_manager::attachments::{
 };
 use crate::chat_manager::commands::take_aborted_request;
 use crate::chat_manager::companion;
+use crate::chat_manager::context_budget::{fit_history_to_context, record_context_fit};
 use crate::chat_manager::execution::{
-    build_model_attempts, build_provider_extra_fields, emit_fallback_retry_toast, RequestSettings,
+    attach_reasoning_items, build_attempt_plan, build_provider_extra_fields,
+    emit_fallback_retry_toast, grounding_documents, is_refusal_finish_reason, load_reasoning_items,
+    persist_attempt_trace, persist_citations, persist_reasoning_items, push_grounding_message,
+    AttemptFailure, RequestSettings,
 };
+use crate::chat_manager::memory::character::character_memory_prompt_entry;
 use crate::chat_manager::memory::dynamic::{
     context_enrichment_enabled, dynamic_min_similarity, dynamic_retrieval_limit,
-    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, mark_memories_accessed,
-    promote_cold_memories,
+    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, hybrid_retrieval_boosts,
+    mark_memories_accessed, promote_cold_memories,
+};
+use crate::chat_manager::memory::entity_graph::{
+    entity_graph_prompt_entry, spawn_entity_ingest, EntitySource,
 };
 use crate::chat_manager::memory::flow::{
     enqueue_post_turn_dynamic_memory, select_relevant_memories,
@@ -62,6 +70,7 @@ impl ContinueFlow {
             swap_places,
             stream,
             request_id,
+            directive,
         } = args;
         let swap_places = role_swap_enabled(swap_places);
 
@@ -176,6 +185,7 @@ impl ContinueFlow {
                 dynamic_retrieval_limit(&context.settings),
                 dynamic_min_similarity(&context.settings),
                 dynamic_retrieval_strategy(&context.settings),
+                hybrid_retrieval_boosts(&context.settings),
             )
             .await
         } else {
@@ -217,7 +227,7 @@ impl ContinueFlow {
             }
         }
 
-        let prompt_entries = if swap_places {
+        let mut prompt_entries = if swap_places {
             let (prompt_character, prompt_persona) =
                 swapped_prompt_entities(&character, persona.as_ref());
             append_image_directive_instructions(
@@ -235,14 +245,37 @@ impl ContinueFlow {
                 settings,
             )
         };
-        let used_lorebook_entries =
-            crate::chat_manager::prompt_engine::resolve_used_lorebook_entries(
+        if let Some(entry) = character_memory_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages,
+        )
+        .await
+        {
+            prompt_entries.push(entry);
+        }
+        if let Some(entry) = entity_graph_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages,
+        ) {
+            prompt_entries.push(entry);
+        }
+        let injected_lorebook_entries =
+            crate::chat_manager::prompt_engine::resolve_injected_lorebook_entries(
                 &app,
                 &character.id,
                 persona.as_ref(),
                 &session,
                 &prompt_entries,
             );
+        let used_lorebook_entries = crate::chat_manager::prompt_engine::used_lorebook_entry_labels(
+            &injected_lorebook_entries,
+        );
         let (relative_entries, in_chat_entries) = partition_prompt_entries(prompt_entries);
 
         let (pinned_msgs, recent_msgs) = if dynamic_memory_enabled {
@@ -275,6 +308,26 @@ impl ContinueFlow {
                 )),
             );
         }
+        push_grounding_message(
+            &mut messages_for_api,
+            &system_role,
+            None,
+            grounding_documents(&injected_lorebook_entries, &relevant_memories),
+        );
+
+        let context_fit = fit_history_to_context(
+            &app,
+            &session,
+            &model,
+            settings,
+            &messages_for_api,
+            &in_chat_entries,
+            &pinned_msgs,
+            recent_msgs,
+        );
+        if let Some(summary) = context_fit.summary.clone() {
+            push_system_message(&mut messages_for_api, &system_role, Some(summary));
+        }
 
         let char_name = if swap_places {
             persona.as_ref().map(|p| p.title.as_str()).unwrap_or("user")
@@ -291,10 +344,12 @@ impl ContinueFlow {
             .iter()
             .any(|scope| scope.eq_ignore_ascii_case("image"));
 
+        let reasoning_items = load_reasoning_items(&app, &session.id);
         let mut chat_messages = Vec::new();
         for msg in &pinned_msgs {
             let msg_with_data = load_attachment_data(&app, msg);
             let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
+            let pushed_from = chat_messages.len();
             push_user_or_assistant_message_with_context(
                 &mut chat_messages,
                 &msg_with_data,
@@ -302,11 +357,17 @@ impl ContinueFlow {
                 persona_name,
                 allow_image_input,
             );
+            attach_reasoning_items(
+                &mut chat_messages[pushed_from..],
+                &msg_with_data,
+                &reasoning_items,
+            );
         }
 
-        for msg in &recent_msgs {
+        for msg in &context_fit.kept {
             let msg_with_data = load_attachment_data(&app, msg);
             let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
+            let pushed_from = chat_messages.len();
             push_user_or_assistant_message_with_context(
                 &mut chat_messages,
                 &msg_with_data,
@@ -314,6 +375,11 @@ impl ContinueFlow {
                 persona_name,
                 allow_image_input,
             );
+            attach_reasoning_items(
+                &mut chat_messages[pushed_from..],
+                &msg_with_data,
+                &reasoning_items,
+            );
         }
         insert_in_chat_prompt_entries(&mut chat_messages, &system_role, &in_chat_entries);
         messages_for_api.extend(chat_messages);
@@ -327,7 +393,12 @@ impl ContinueFlow {
             .map(|message| message.role != "user")
             .unwrap_or(true);
 
-        if should_inject_continue_prompt {
+        if let Some(directive) = directive {
+            messages_for_api.push(json!({
+                "role": "user",
+                "content": directive
+            }));
+        } else if should_inject_continue_prompt {
             messages_for_api.push(json!({
                 "role": "user",
                 "content": "[CONTINUE] You were in the middle of a response. Continue writing from exactly where you left off. Do NOT restart, regenerate, or rewrite what you already said. Simply pick up the narrative thread and continue the scene forward with new content."
@@ -340,7 +411,7 @@ impl ContinueFlow {
         } else {
             None
         };
-        let attempts = build_model_attempts(
+        let mut plan = build_attempt_plan(
             &app,
             settings,
             &character,
@@ -357,14 +428,15 @@ impl ContinueFlow {
         let mut last_error = "request failed".to_string();
         let mut fallback_toast_shown = false;
 
-        for (idx, (attempt_model, attempt_credential, is_fallback_attempt)) in
-            attempts.iter().enumerate()
-        {
-            let has_next_attempt = idx + 1 < attempts.len();
+        while let Some(attempt) = plan.next_attempt(&app) {
+            let attempt_model = attempt.model;
+            let attempt_credential = attempt.credential;
+            let is_fallback_attempt = attempt.is_fallback;
 
             let attempt_api_key = match require_api_key(&app, attempt_credential, "chat_continue") {
                 Ok(key) => key,
                 Err(err) => {
+                    let has_next_attempt = plan.fail(AttemptFailure::Other, &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -448,11 +520,14 @@ impl ContinueFlow {
                 stream: Some(built.stream),
                 request_id: built.request_id.clone(),
                 provider_id: Some(attempt_credential.provider_id.clone()),
+                credential_id: Some(attempt_credential.id.clone()),
             };
 
             let api_response = match api_request(app.clone(), api_request_payload).await {
                 Ok(resp) => resp,
                 Err(err) => {
+                    let has_next_attempt =
+                        plan.fail(AttemptFailure::from_transport_error(&err), &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -481,6 +556,10 @@ impl ContinueFlow {
                 let err_message =
                     extract_error_message(api_response.data()).unwrap_or(fallback.clone());
                 let failed_usage = extract_usage(api_response.data());
+                let has_next_attempt = plan.fail(
+                    AttemptFailure::from_response(api_response.status, &err_message),
+                    &err_message,
+                );
                 emit_error_event(
                     &app,
                     "continue_provider_error",
@@ -519,10 +598,31 @@ impl ContinueFlow {
                 return Err(last_error);
             }
 
+            let finish_reason =
+                extract_usage(api_response.data()).and_then(|usage| usage.finish_reason);
+            if is_refusal_finish_reason(finish_reason.as_deref())
+                && plan.has_fallback_for(AttemptFailure::ContentRefusal)
+            {
+                let message = format!(
+                    "Model refused the request (finish reason {})",
+                    finish_reason.as_deref().unwrap_or_default()
+                );
+                log_warn(
+                    &app,
+                    "chat_continue",
+                    format!("{} model={}", message, attempt_model.name),
+                );
+                plan.fail(AttemptFailure::ContentRefusal, &message);
+                last_error = message;
+                emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
+                continue;
+            }
+            plan.succeed();
+
             selected_model = attempt_model;
             selected_credential = attempt_credential;
             selected_api_key = attempt_api_key;
-            fallback_from_model_id = if *is_fallback_attempt {
+            fallback_from_model_id = if is_fallback_attempt {
                 Some(model.id.clone())
             } else {
                 None
@@ -679,11 +779,49 @@ impl ContinueFlow {
 
         session.messages.push(assistant_message.clone());
         session.updated_at = now_millis()?;
+        let reply_update = companion::update_state_for_reply(
+            &app,
+            &mut session,
+            &character,
+            &assistant_message.id,
+            &assistant_message.content,
+            session.updated_at,
+        )
+        .await;
         if take_aborted_request(&app, request_id.as_deref()) {
             cleanup_attachments(&app, &assistant_message.attachments, "chat_continue");
             return Err("Request aborted by user".to_string());
         }
         context.save_session(&session)?;
+        let reply_effect = reply_update.map(|update| {
+            companion::merge_into_ledger(&app, update.ledger);
+            update.turn_effect
+        });
+        persist_attempt_trace(&app, &plan, &session.id, &assistant_message.id);
+        persist_reasoning_items(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_message.id,
+            assistant_message.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        persist_citations(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_message.id,
+            assistant_message.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        record_context_fit(
+            &app,
+            &session,
+            &character,
+            settings,
+            &assistant_message.id,
+            &context_fit,
+        );
 
         emit_debug(
             &app,
@@ -729,10 +867,20 @@ impl ContinueFlow {
                 session.id.clone(),
                 None,
                 assistant_message.id.clone(),
-                companion_mode_enabled.then(Default::default),
+                reply_effect.or_else(|| companion_mode_enabled.then(Default::default)),
             );
+        } else if let Some(seed) = reply_effect {
+            companion::record_turn_effect(&app, &session.id, None, &assistant_message.id, seed);
         }
 
+        spawn_entity_ingest(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            vec![EntitySource::message(&assistant_message)],
+        );
+
         Ok(ContinueResult {
             session_id: session.id,
             session_updated_at: session.updated_at,
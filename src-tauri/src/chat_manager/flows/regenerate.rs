@@ -8,13 +8,21 @@ use crate::chat_manager::attachments::{
 };
 use crate::chat_manager::commands::take_aborted_request;
 use crate::chat_manager::companion;
+use crate::chat_manager::context_budget::{fit_history_to_context, record_context_fit};
 use crate::chat_manager::execution::{
-    build_model_attempts, build_provider_extra_fields, emit_fallback_retry_toast, RequestSettings,
+    attach_reasoning_items, build_attempt_plan, build_provider_extra_fields,
+    emit_fallback_retry_toast, grounding_documents, is_refusal_finish_reason, load_reasoning_items,
+    persist_attempt_trace, persist_citations, persist_reasoning_items, push_grounding_message,
+    AttemptFailure, RequestSettings,
 };
+use crate::chat_manager::memory::character::character_memory_prompt_entry;
 use crate::chat_manager::memory::dynamic::{
     context_enrichment_enabled, dynamic_min_similarity, dynamic_retrieval_limit,
-    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, mark_memories_accessed,
-    promote_cold_memories,
+    dynamic_retrieval_strategy, dynamic_window_size, ensure_pinned_hot, hybrid_retrieval_boosts,
+    mark_memories_accessed, promote_cold_memories,
+};
+use crate::chat_manager::memory::entity_graph::{
+    entity_graph_prompt_entry, spawn_entity_ingest, EntitySource,
 };
 use crate::chat_manager::memory::flow::select_relevant_memories;
 use crate::chat_manager::messages::{
@@ -217,6 +225,7 @@ impl RegenerateFlow {
                 dynamic_retrieval_limit(&context.settings),
                 dynamic_min_similarity(&context.settings),
                 dynamic_retrieval_strategy(&context.settings),
+                hybrid_retrieval_boosts(&context.settings),
             )
             .await
         } else {
@@ -258,7 +267,7 @@ impl RegenerateFlow {
             }
         }
 
-        let prompt_entries = if swap_places {
+        let mut prompt_entries = if swap_places {
             let (prompt_character, prompt_persona) =
                 swapped_prompt_entities(&character, persona.as_ref());
             append_image_directive_instructions(
@@ -276,18 +285,41 @@ impl RegenerateFlow {
                 settings,
             )
         };
-        let used_lorebook_entries =
-            crate::chat_manager::prompt_engine::resolve_used_lorebook_entries(
+        if let Some(entry) = character_memory_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages[..(target_index + 1).min(session.messages.len())],
+        )
+        .await
+        {
+            prompt_entries.push(entry);
+        }
+        if let Some(entry) = entity_graph_prompt_entry(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            &session.messages[..(target_index + 1).min(session.messages.len())],
+        ) {
+            prompt_entries.push(entry);
+        }
+        let injected_lorebook_entries =
+            crate::chat_manager::prompt_engine::resolve_injected_lorebook_entries(
                 &app,
                 &character.id,
                 persona.as_ref(),
                 &session,
                 &prompt_entries,
             );
+        let used_lorebook_entries = crate::chat_manager::prompt_engine::used_lorebook_entry_labels(
+            &injected_lorebook_entries,
+        );
         let (relative_entries, in_chat_entries) = partition_prompt_entries(prompt_entries);
 
         let system_role = crate::chat_manager::request_builder::system_role_for(&credential);
-        let messages_for_api = {
+        let (messages_for_api, context_fit) = {
             let mut out = Vec::new();
             for entry in &relative_entries {
                 push_prompt_entry_message(&mut out, &system_role, entry);
@@ -306,6 +338,12 @@ impl RegenerateFlow {
                     )),
                 );
             }
+            push_grounding_message(
+                &mut out,
+                &system_role,
+                None,
+                grounding_documents(&injected_lorebook_entries, &relevant_memories),
+            );
 
             let char_name = if swap_places {
                 persona.as_ref().map(|p| p.title.as_str()).unwrap_or("user")
@@ -330,56 +368,47 @@ impl RegenerateFlow {
                 .map(|(_, msg)| msg.clone())
                 .collect();
 
-            let mut chat_messages = Vec::new();
-            if dynamic_memory_enabled {
-                let (pinned_msgs, recent_msgs) =
-                    conversation_window_with_pinned(&messages_before_target, dynamic_window);
-
-                for msg in &pinned_msgs {
-                    let msg_with_data = load_attachment_data(&app, msg);
-                    let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
-                    push_user_or_assistant_message_with_context(
-                        &mut chat_messages,
-                        &msg_with_data,
-                        char_name,
-                        persona_name,
-                        allow_image_input,
-                    );
-                }
-
-                for msg in &recent_msgs {
-                    let msg_with_data = load_attachment_data(&app, msg);
-                    let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
-                    push_user_or_assistant_message_with_context(
-                        &mut chat_messages,
-                        &msg_with_data,
-                        char_name,
-                        persona_name,
-                        allow_image_input,
-                    );
-                }
+            let (pinned_msgs, recent_msgs) = if dynamic_memory_enabled {
+                conversation_window_with_pinned(&messages_before_target, dynamic_window)
             } else {
                 let start_index = target_index.saturating_sub(manual_window_size(settings));
-                for (idx, msg) in session.messages.iter().enumerate() {
-                    if idx < start_index {
-                        continue;
-                    }
-                    if idx > target_index {
-                        break;
-                    }
-                    if idx == target_index {
-                        continue;
-                    }
-                    let msg_with_data = load_attachment_data(&app, msg);
-                    let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
-                    push_user_or_assistant_message_with_context(
-                        &mut chat_messages,
-                        &msg_with_data,
-                        char_name,
-                        persona_name,
-                        allow_image_input,
-                    );
-                }
+                (
+                    Vec::new(),
+                    session.messages[start_index..target_index].to_vec(),
+                )
+            };
+            let context_fit = fit_history_to_context(
+                &app,
+                &session,
+                &model,
+                settings,
+                &out,
+                &in_chat_entries,
+                &pinned_msgs,
+                recent_msgs,
+            );
+            if let Some(summary) = context_fit.summary.clone() {
+                push_system_message(&mut out, &system_role, Some(summary));
+            }
+
+            let reasoning_items = load_reasoning_items(&app, &session.id);
+            let mut chat_messages = Vec::new();
+            for msg in pinned_msgs.iter().chain(context_fit.kept.iter()) {
+                let msg_with_data = load_attachment_data(&app, msg);
+                let msg_with_data = maybe_swap_message_for_api(&msg_with_data, swap_places);
+                let pushed_from = chat_messages.len();
+                push_user_or_assistant_message_with_context(
+                    &mut chat_messages,
+                    &msg_with_data,
+                    char_name,
+                    persona_name,
+                    allow_image_input,
+                );
+                attach_reasoning_items(
+                    &mut chat_messages[pushed_from..],
+                    &msg_with_data,
+                    &reasoning_items,
+                );
             }
 
             insert_in_chat_prompt_entries(&mut chat_messages, &system_role, &in_chat_entries);
@@ -394,7 +423,7 @@ impl RegenerateFlow {
                 }));
             }
             sanitize_placeholders_in_api_messages(&mut out, char_name, persona_name);
-            out
+            (out, context_fit)
         };
 
         let should_stream = stream.unwrap_or(true);
@@ -404,7 +433,7 @@ impl RegenerateFlow {
             None
         };
 
-        let attempts = build_model_attempts(
+        let mut plan = build_attempt_plan(
             &app,
             settings,
             &character,
@@ -429,15 +458,16 @@ impl RegenerateFlow {
             ensure_assistant_variant(message);
         }
 
-        for (idx, (attempt_model, attempt_credential, is_fallback_attempt)) in
-            attempts.iter().enumerate()
-        {
-            let has_next_attempt = idx + 1 < attempts.len();
+        while let Some(attempt) = plan.next_attempt(&app) {
+            let attempt_model = attempt.model;
+            let attempt_credential = attempt.credential;
+            let is_fallback_attempt = attempt.is_fallback;
 
             let attempt_api_key = match require_api_key(&app, attempt_credential, "chat_regenerate")
             {
                 Ok(key) => key,
                 Err(err) => {
+                    let has_next_attempt = plan.fail(AttemptFailure::Other, &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -522,11 +552,14 @@ impl RegenerateFlow {
                 stream: Some(built.stream),
                 request_id: built.request_id.clone(),
                 provider_id: Some(attempt_credential.provider_id.clone()),
+                credential_id: Some(attempt_credential.id.clone()),
             };
 
             let api_response = match api_request(app.clone(), api_request_payload).await {
                 Ok(resp) => resp,
                 Err(err) => {
+                    let has_next_attempt =
+                        plan.fail(AttemptFailure::from_transport_error(&err), &err);
                     last_error = err;
                     if has_next_attempt {
                         emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
@@ -556,6 +589,10 @@ impl RegenerateFlow {
                 let err_message =
                     extract_error_message(api_response.data()).unwrap_or(fallback.clone());
                 let failed_usage = extract_usage(api_response.data());
+                let has_next_attempt = plan.fail(
+                    AttemptFailure::from_response(api_response.status, &err_message),
+                    &err_message,
+                );
                 emit_error_event(
                     &app,
                     "regenerate_provider_error",
@@ -595,10 +632,31 @@ impl RegenerateFlow {
                 return Err(last_error);
             }
 
+            let finish_reason =
+                extract_usage(api_response.data()).and_then(|usage| usage.finish_reason);
+            if is_refusal_finish_reason(finish_reason.as_deref())
+                && plan.has_fallback_for(AttemptFailure::ContentRefusal)
+            {
+                let message = format!(
+                    "Model refused the request (finish reason {})",
+                    finish_reason.as_deref().unwrap_or_default()
+                );
+                log_warn(
+                    &app,
+                    "chat_regenerate",
+                    format!("{} model={}", message, attempt_model.name),
+                );
+                plan.fail(AttemptFailure::ContentRefusal, &message);
+                last_error = message;
+                emit_fallback_retry_toast(&app, &mut fallback_toast_shown);
+                continue;
+            }
+            plan.succeed();
+
             selected_model = attempt_model;
             selected_credential = attempt_credential;
             selected_api_key = attempt_api_key;
-            fallback_from_model_id = if *is_fallback_attempt {
+            fallback_from_model_id = if is_fallback_attempt {
                 Some(model.id.clone())
             } else {
                 None
@@ -734,12 +792,64 @@ impl RegenerateFlow {
         let (assistant_clone, previous_attachments) = assistant_clone;
 
         session.updated_at = now_millis()?;
+        let reply_update = companion::update_state_for_reply(
+            &app,
+            &mut session,
+            &character,
+            &assistant_clone.id,
+            &assistant_clone.content,
+            session.updated_at,
+        )
+        .await;
         if take_aborted_request(&app, request_id.as_deref()) {
             cleanup_attachments(&app, &cleanup_assistant_attachments, "chat_regenerate");
             return Err("Request aborted by user".to_string());
         }
         context.save_session(&session)?;
+        let reply_effect = reply_update.map(|update| {
+            companion::merge_into_ledger(&app, update.ledger);
+            update.turn_effect
+        });
+        persist_attempt_trace(&app, &plan, &session.id, &assistant_clone.id);
+        persist_reasoning_items(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_clone.id,
+            assistant_clone.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        persist_citations(
+            &app,
+            selected_credential,
+            &session.id,
+            &assistant_clone.id,
+            assistant_clone.selected_variant_id.as_deref(),
+            api_response.data(),
+        );
+        record_context_fit(
+            &app,
+            &session,
+            &character,
+            settings,
+            &assistant_clone.id,
+            &context_fit,
+        );
         cleanup_attachments(&app, &previous_attachments, "chat_regenerate");
+        if let Some(seed) = reply_effect {
+            let user_message_id = session.messages[..target_index]
+                .iter()
+                .rev()
+                .find(|message| message.role == "user")
+                .map(|message| message.id.clone());
+            companion::record_turn_effect(
+                &app,
+                &session.id,
+                user_message_id.as_deref(),
+                &assistant_clone.id,
+                seed,
+            );
+        }
 
         emit_debug(
             &app,
@@ -781,6 +891,14 @@ impl RegenerateFlow {
         )
         .await;
 
+        spawn_entity_ingest(
+            &app,
+            settings,
+            &character.id,
+            &session.id,
+            vec![EntitySource::message(&assistant_clone)],
+        );
+
         Ok(RegenerateResult {
             session_id: session.id,
             session_updated_at: session.updated_at,
@@ -1362,6 +1362,7 @@ async fn send_lorebook_entry_request(
             stream: Some(false),
             request_id: built.request_id,
             provider_id: Some(provider_cred.provider_id.clone()),
+            credential_id: Some(provider_cred.id.clone()),
         },
     )
     .await?;
@@ -1409,6 +1410,7 @@ async fn send_lorebook_entry_request(
                         stream: Some(false),
                         request_id: built.request_id,
                         provider_id: Some(provider_cred.provider_id.clone()),
+                        credential_id: Some(provider_cred.id.clone()),
                     },
                 )
                 .await;
@@ -1452,6 +1454,7 @@ async fn send_lorebook_entry_request(
                         stream: Some(false),
                         request_id: built.request_id,
                         provider_id: Some(provider_cred.provider_id.clone()),
+                        credential_id: Some(provider_cred.id.clone()),
                     },
                 )
                 .await;
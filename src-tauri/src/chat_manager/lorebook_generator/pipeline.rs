@@ -314,6 +314,7 @@ async fn invoke_tool(
             stream: Some(false),
             request_id: built.request_id,
             provider_id: Some(credential.provider_id.clone()),
+            credential_id: Some(credential.id.clone()),
         },
     )
     .await?;
@@ -1006,7 +1007,10 @@ pub async fn run_coherence(
 }
 
 fn require_api_key(credential: &ProviderCredential) -> Result<String, String> {
-    if credential.provider_id == "llamacpp" || credential.provider_id == "ollama" {
+    if matches!(
+        credential.provider_id.as_str(),
+        "llamacpp" | "ollama" | "mock"
+    ) {
         return Ok(credential.api_key.clone().unwrap_or_default());
     }
     credential
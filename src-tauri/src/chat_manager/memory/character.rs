@@ -0,0 +1,333 @@
+//! Retrieval and auto-promotion for the character-level memory store
+//! (`storage_manager::character_memories`).
+
+use tauri::AppHandle;
+
+use super::dynamic::{
+    context_enrichment_enabled, dynamic_min_similarity, dynamic_retrieval_strategy,
+    hybrid_retrieval_boosts, mark_memories_accessed, normalize_query_text,
+    search_memory_indices_by_keyword, select_relevant_memory_indices,
+    select_top_cosine_memory_indices,
+};
+use super::hybrid::select_hybrid_memory_indices;
+use crate::chat_manager::turn_builder::build_enriched_query;
+use crate::chat_manager::types::{
+    MemoryEmbedding, MemoryRetrievalStrategy, PromptEntryPosition, PromptEntryRole, Session,
+    Settings, StoredMessage, SystemPromptEntry,
+};
+use crate::embedding;
+use crate::storage_manager::character_memories::{self, CharacterMemory, CharacterMemoryConfig};
+use crate::storage_manager::db::open_db;
+use crate::utils::{log_info, log_warn, now_millis};
+
+pub const CHARACTER_MEMORY_PROMPT_ENTRY_ID: &str = "character_long_term_memory";
+
+/// Session memories worth keeping across chats: pinned ones, or ones that have
+/// both kept their importance and been retrieved often enough.
+pub fn qualifies_for_promotion(memory: &MemoryEmbedding, config: &CharacterMemoryConfig) -> bool {
+    if memory.superseded_by.is_some() {
+        return false;
+    }
+    memory.is_pinned
+        || (memory.importance_score >= config.promote_min_importance
+            && memory.access_count >= config.promote_min_access_count)
+}
+
+/// Keeps memories in ranked order until the token budget runs out. A memory
+/// that does not fit is skipped so smaller, lower-ranked ones can still fill
+/// the remaining budget.
+pub fn fit_to_token_budget(ranked: Vec<MemoryEmbedding>, budget: u32) -> Vec<MemoryEmbedding> {
+    let mut used = 0u32;
+    let mut kept = Vec::new();
+    for memory in ranked {
+        let cost = memory.token_count.max(1);
+        if used + cost > budget {
+            continue;
+        }
+        used += cost;
+        kept.push(memory);
+    }
+    kept
+}
+
+/// Promotes qualifying memories of `session` when auto-promotion is enabled
+/// for its character. Returns the number of newly promoted memories.
+pub fn auto_promote_session_memories(app: &AppHandle, session: &Session) -> usize {
+    let conn = match open_db(app) {
+        Ok(conn) => conn,
+        Err(err) => {
+            log_warn(app, "character_memory", format!("open db failed: {}", err));
+            return 0;
+        }
+    };
+    let config = match character_memories::load_config(&conn, &session.character_id) {
+        Ok(config) => config,
+        Err(err) => {
+            log_warn(
+                app,
+                "character_memory",
+                format!("load config failed: {}", err),
+            );
+            return 0;
+        }
+    };
+    if !config.enabled || !config.auto_promote {
+        return 0;
+    }
+
+    let mut promoted = 0;
+    for memory in session
+        .memory_embeddings
+        .iter()
+        .filter(|memory| qualifies_for_promotion(memory, &config))
+    {
+        match character_memories::promote_session_memory(
+            &conn,
+            &session.character_id,
+            &session.id,
+            memory,
+            "auto",
+        ) {
+            Ok(Some(_)) => promoted += 1,
+            Ok(None) => {}
+            Err(err) => log_warn(
+                app,
+                "character_memory",
+                format!("auto-promotion of {} failed: {}", memory.id, err),
+            ),
+        }
+    }
+    if promoted > 0 {
+        log_info(
+            app,
+            "character_memory",
+            format!(
+                "auto-promoted {} memories for character {}",
+                promoted, session.character_id
+            ),
+        );
+    }
+    promoted
+}
+
+/// Builds the prompt entry carrying character-level memories relevant to the
+/// latest turn, or `None` when the store is disabled or nothing matched.
+/// Memories promoted from the current session are skipped since the session's
+/// own dynamic memory already covers them.
+pub async fn character_memory_prompt_entry(
+    app: &AppHandle,
+    settings: &Settings,
+    character_id: &str,
+    session_id: &str,
+    messages: &[StoredMessage],
+) -> Option<SystemPromptEntry> {
+    let (config, stored) = {
+        let conn = open_db(app).ok()?;
+        let config = character_memories::load_config(&conn, character_id).ok()?;
+        if !config.enabled {
+            return None;
+        }
+        let stored = character_memories::load_for_character(&conn, character_id).ok()?;
+        (config, stored)
+    };
+    let candidates: Vec<CharacterMemory> = stored
+        .into_iter()
+        .filter(|entry| entry.source_session_id.as_deref() != Some(session_id))
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let query = if context_enrichment_enabled(settings) {
+        build_enriched_query(messages)
+    } else {
+        messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .map(|m| m.content.clone())
+            .unwrap_or_default()
+    };
+    if query.is_empty() {
+        return None;
+    }
+
+    let selected = select_character_memories(app, settings, &config, &candidates, &query).await;
+    if selected.is_empty() {
+        return None;
+    }
+
+    let memory_ids: Vec<String> = selected.iter().map(|m| m.id.clone()).collect();
+    let mut memories: Vec<MemoryEmbedding> =
+        candidates.into_iter().map(|entry| entry.memory).collect();
+    let access_updates =
+        mark_memories_accessed(&mut memories, &memory_ids, now_millis().unwrap_or_default());
+    if let Ok(mut conn) = open_db(app) {
+        if let Err(err) =
+            character_memories::apply_access_updates(&mut conn, character_id, &access_updates)
+        {
+            log_warn(
+                app,
+                "character_memory",
+                format!("access update failed: {}", err),
+            );
+        }
+    }
+
+    let content = selected
+        .iter()
+        .map(|m| format!("- {}", m.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(SystemPromptEntry {
+        id: CHARACTER_MEMORY_PROMPT_ENTRY_ID.to_string(),
+        name: "Memories from earlier chats".to_string(),
+        role: PromptEntryRole::System,
+        content: format!("Memories from earlier chats:\n{}", content),
+        enabled: true,
+        injection_position: PromptEntryPosition::Relative,
+        injection_depth: 0,
+        conditional_min_messages: None,
+        interval_turns: None,
+        system_prompt: true,
+        conditions: None,
+        prompt_entry_payload: None,
+    })
+}
+
+async fn select_character_memories(
+    app: &AppHandle,
+    settings: &Settings,
+    config: &CharacterMemoryConfig,
+    candidates: &[CharacterMemory],
+    query: &str,
+) -> Vec<MemoryEmbedding> {
+    let memories: Vec<MemoryEmbedding> = candidates.iter().map(|e| e.memory.clone()).collect();
+    let limit = config.retrieval_limit.max(1) as usize;
+    let min_similarity = dynamic_min_similarity(settings);
+
+    let query_embedding = match embedding::compute_embedding(app.clone(), query.to_string()).await {
+        Ok(vec) => Some(vec),
+        Err(err) => {
+            log_warn(
+                app,
+                "character_memory",
+                format!("embedding failed: {}", err),
+            );
+            None
+        }
+    };
+
+    let ranked: Vec<(usize, Option<f32>)> =
+        match (dynamic_retrieval_strategy(settings), query_embedding) {
+            (MemoryRetrievalStrategy::Hybrid, query_embedding) => select_hybrid_memory_indices(
+                query_embedding.as_deref(),
+                query,
+                &memories,
+                limit,
+                min_similarity,
+                hybrid_retrieval_boosts(settings),
+            )
+            .into_iter()
+            .map(|m| (m.index, m.cosine))
+            .collect(),
+            (MemoryRetrievalStrategy::Cosine, Some(query_embedding)) => {
+                select_top_cosine_memory_indices(&query_embedding, &memories, limit, min_similarity)
+                    .into_iter()
+                    .map(|(idx, score)| (idx, Some(score)))
+                    .collect()
+            }
+            (MemoryRetrievalStrategy::Smart, Some(query_embedding)) => {
+                select_relevant_memory_indices(&query_embedding, &memories, limit, min_similarity)
+                    .into_iter()
+                    .map(|(idx, score)| (idx, Some(score)))
+                    .collect()
+            }
+            (_, None) => Vec::new(),
+        };
+
+    let ranked = if ranked.is_empty() {
+        search_memory_indices_by_keyword(&memories, &normalize_query_text(query), limit, false)
+            .into_iter()
+            .map(|idx| (idx, None))
+            .collect()
+    } else {
+        ranked
+    };
+
+    let ranked = ranked
+        .into_iter()
+        .filter_map(|(idx, score)| {
+            memories.get(idx).map(|memory| {
+                let mut cloned = memory.clone();
+                cloned.match_score = score;
+                cloned
+            })
+        })
+        .collect();
+    fit_to_token_budget(ranked, config.token_budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(id: &str, tokens: u32) -> MemoryEmbedding {
+        MemoryEmbedding {
+            id: id.to_string(),
+            text: format!("memory {}", id),
+            embedding: Vec::new(),
+            created_at: 0,
+            token_count: tokens,
+            is_cold: false,
+            last_accessed_at: 0,
+            importance_score: 1.0,
+            persistence_importance: 1.0,
+            prompt_importance: 1.0,
+            volatility: 0.0,
+            is_pinned: false,
+            access_count: 0,
+            embedding_source_version: None,
+            embedding_dimensions: None,
+            match_score: None,
+            category: None,
+            canonical_entities: Vec::new(),
+            fact_signature: None,
+            fact_polarity: None,
+            source_role: None,
+            source_message_id: None,
+            superseded_by: None,
+            superseded_at: None,
+            supersedes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn promotion_requires_importance_and_access_unless_pinned() {
+        let config = CharacterMemoryConfig::default();
+        let mut m = memory("a", 10);
+        m.importance_score = 0.9;
+        m.access_count = 1;
+        assert!(!qualifies_for_promotion(&m, &config));
+
+        m.access_count = config.promote_min_access_count;
+        assert!(qualifies_for_promotion(&m, &config));
+
+        m.importance_score = 0.5;
+        assert!(!qualifies_for_promotion(&m, &config));
+
+        m.is_pinned = true;
+        assert!(qualifies_for_promotion(&m, &config));
+
+        m.superseded_by = Some("b".to_string());
+        assert!(!qualifies_for_promotion(&m, &config));
+    }
+
+    #[test]
+    fn token_budget_skips_oversized_memories() {
+        let ranked = vec![memory("a", 50), memory("b", 200), memory("c", 40)];
+        let kept = fit_to_token_budget(ranked, 100);
+        let ids: Vec<&str> = kept.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+}
@@ -28,6 +28,7 @@ pub trait MemoryEntry {
     fn access_count(&self) -> u32;
     fn set_access_count(&mut self, value: u32);
     fn category(&self) -> Option<&str>;
+    fn created_at(&self) -> u64;
 }
 
 impl MemoryEntry for crate::chat_manager::types::MemoryEmbedding {
@@ -73,6 +74,9 @@ impl MemoryEntry for crate::chat_manager::types::MemoryEmbedding {
     fn category(&self) -> Option<&str> {
         self.category.as_deref()
     }
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
 }
 
 // `storage_manager::group_sessions::MemoryEmbedding` is now a re-export of the
@@ -189,6 +193,26 @@ pub fn dynamic_cold_threshold(settings: &Settings) -> f32 {
         .unwrap_or(FALLBACK_COLD_THRESHOLD)
 }
 
+/// Recency and pin boosts applied on top of the fused hybrid ranking.
+pub fn hybrid_retrieval_boosts(settings: &Settings) -> super::hybrid::HybridBoosts {
+    settings
+        .advanced_settings
+        .as_ref()
+        .and_then(|a| a.dynamic_memory.as_ref())
+        .map(super::hybrid::HybridBoosts::from_settings)
+        .unwrap_or_default()
+}
+
+/// Returns `(enabled, character_scope)` for the NER entity graph.
+pub fn entity_graph_settings(settings: &Settings) -> (bool, bool) {
+    settings
+        .advanced_settings
+        .as_ref()
+        .and_then(|a| a.dynamic_memory.as_ref())
+        .map(|dm| (dm.entity_graph_enabled, dm.entity_graph_character_scope))
+        .unwrap_or((false, false))
+}
+
 /// Check if context enrichment (semantic search) is enabled
 pub fn context_enrichment_enabled(settings: &Settings) -> bool {
     settings
@@ -241,6 +265,10 @@ pub fn effective_dynamic_memory_settings(
         context_enrichment_enabled: true,
         recursive_memory_loops: false,
         recursive_memory_loop_hard_cap: 20,
+        entity_graph_enabled: false,
+        entity_graph_character_scope: false,
+        hybrid_recency_boost: 0.0,
+        hybrid_pin_boost: 0.0,
     }
 }
 
@@ -526,7 +554,7 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 /// strong semantic matches. Discourages cold memories from displacing fresh
 /// hot ones for borderline queries while keeping them reachable when the
 /// match is genuinely good.
-const COLD_MEMORY_SCORE_MULTIPLIER: f32 = 0.7;
+pub(crate) const COLD_MEMORY_SCORE_MULTIPLIER: f32 = 0.7;
 
 /// Select memories by similarity. Cold memories are included with a score
 /// multiplier (`COLD_MEMORY_SCORE_MULTIPLIER`) so they can resurface on
@@ -624,6 +652,16 @@ pub fn search_cold_memory_indices_by_keyword<E: MemoryEntry>(
     memories: &[E],
     query: &str,
     limit: usize,
+) -> Vec<usize> {
+    search_memory_indices_by_keyword(memories, query, limit, true)
+}
+
+/// Keyword search ranked by the number of matched query keywords. Returns indices.
+pub fn search_memory_indices_by_keyword<E: MemoryEntry>(
+    memories: &[E],
+    query: &str,
+    limit: usize,
+    cold_only: bool,
 ) -> Vec<usize> {
     let keywords = extract_keywords(query);
     if keywords.is_empty() {
@@ -634,7 +672,7 @@ pub fn search_cold_memory_indices_by_keyword<E: MemoryEntry>(
         .iter()
         .enumerate()
         .filter_map(|(idx, mem)| {
-            if !mem.is_cold() {
+            if cold_only && !mem.is_cold() {
                 return None;
             }
             let text = normalize_query_text(mem.text());
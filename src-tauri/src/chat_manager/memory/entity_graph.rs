@@ -0,0 +1,332 @@
+//! Feeds turns and memories through the companion NER model into the entity
+//! graph (`storage_manager::entity_graph`) and injects the facts of entities
+//! the user mentions again.
+
+use std::collections::HashSet;
+
+use tauri::AppHandle;
+
+use super::dynamic::entity_graph_settings;
+use crate::chat_manager::types::{
+    PromptEntryPosition, PromptEntryRole, Settings, StoredMessage, SystemPromptEntry,
+};
+use crate::embedding::ner;
+use crate::storage_manager::db::open_db;
+use crate::storage_manager::entity_graph::{self, EntityNode};
+use crate::utils::{log_info, log_warn};
+
+pub const ENTITY_GRAPH_PROMPT_ENTRY_ID: &str = "entity_graph_facts";
+const MAX_PROMPT_ENTITIES: usize = 5;
+const FACTS_PER_ENTITY: usize = 3;
+
+/// Text extracted from one message or memory.
+#[derive(Debug, Clone)]
+pub struct EntitySource {
+    /// "message" or "memory".
+    pub kind: &'static str,
+    pub id: String,
+    pub text: String,
+}
+
+impl EntitySource {
+    pub fn message(message: &StoredMessage) -> Self {
+        Self {
+            kind: "message",
+            id: message.id.clone(),
+            text: message.content.clone(),
+        }
+    }
+}
+
+/// Maps a NER label to a graph node kind. Dates and unknown labels are skipped.
+pub fn entity_kind_for_label(label: &str) -> Option<&'static str> {
+    match label.to_ascii_uppercase().as_str() {
+        "PER" | "PERSON" => Some("person"),
+        "LOC" | "GPE" | "LOCATION" => Some("place"),
+        "ORG" | "ORGANIZATION" | "ORGANISATION" => Some("organisation"),
+        "MISC" | "PRODUCT" | "ITEM" | "OBJECT" => Some("item"),
+        _ => None,
+    }
+}
+
+fn entity_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace(['\u{2019}', '`'], "'")
+        .split(|ch: char| !(ch.is_alphanumeric() || ch == '\''))
+        .map(|word| {
+            word.trim_matches('\'')
+                .trim_end_matches("'s")
+                .trim_matches('\'')
+                .to_string()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Dedup key for entity spans: lowercase words without punctuation,
+/// possessives or a leading article.
+pub fn normalize_entity_name(name: &str) -> String {
+    let mut words = entity_words(name);
+    if words.len() > 1 && matches!(words[0].as_str(), "the" | "a" | "an") {
+        words.remove(0);
+    }
+    words.join(" ")
+}
+
+/// Node whose name or one of its aliases normalizes to `normalized`.
+pub fn find_matching_node<'a>(nodes: &'a [EntityNode], normalized: &str) -> Option<&'a EntityNode> {
+    nodes.iter().find(|node| {
+        node.normalized == normalized
+            || node
+                .aliases
+                .iter()
+                .any(|alias| normalize_entity_name(alias) == normalized)
+    })
+}
+
+/// Indices of nodes mentioned in `text` by name or alias, matched on whole words.
+pub fn find_mentioned_entities(text: &str, nodes: &[EntityNode]) -> Vec<usize> {
+    let words = entity_words(text);
+    let contains = |needle: &str| {
+        let needle: Vec<&str> = needle.split(' ').filter(|w| !w.is_empty()).collect();
+        !needle.is_empty()
+            && words
+                .windows(needle.len())
+                .any(|window| window.iter().zip(&needle).all(|(a, b)| a == b))
+    };
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| {
+            contains(&node.normalized)
+                || node
+                    .aliases
+                    .iter()
+                    .any(|alias| contains(&normalize_entity_name(alias)))
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Extracts entities from `sources` and links them into the session graph and,
+/// when `character_scope` is set, the character-wide graph. Sources are
+/// re-extracted from scratch, so a rewritten message replaces its old links.
+pub async fn ingest_sources(
+    app: &AppHandle,
+    character_id: &str,
+    session_id: &str,
+    sources: Vec<EntitySource>,
+    character_scope: bool,
+) -> Result<usize, String> {
+    let scopes: Vec<Option<&str>> = if character_scope {
+        vec![Some(session_id), None]
+    } else {
+        vec![Some(session_id)]
+    };
+    let mut linked = 0;
+    for source in sources {
+        let spans = ner::extract_entities(app, &source.text)
+            .await?
+            .unwrap_or_default();
+        let conn = open_db(app)?;
+        entity_graph::clear_source_mentions(&conn, session_id, source.kind, &source.id)?;
+        for span in &spans {
+            let Some(kind) = entity_kind_for_label(&span.label) else {
+                continue;
+            };
+            for scope in &scopes {
+                if entity_graph::record_mention(
+                    &conn,
+                    character_id,
+                    *scope,
+                    kind,
+                    &span.text,
+                    source.kind,
+                    &source.id,
+                    session_id,
+                )?
+                .is_some()
+                {
+                    linked += 1;
+                }
+            }
+        }
+        entity_graph::mark_source_processed(&conn, session_id, source.kind, &source.id)?;
+    }
+    Ok(linked)
+}
+
+/// Runs `ingest_sources` in the background when the entity graph is enabled.
+pub fn spawn_entity_ingest(
+    app: &AppHandle,
+    settings: &Settings,
+    character_id: &str,
+    session_id: &str,
+    sources: Vec<EntitySource>,
+) {
+    let (enabled, character_scope) = entity_graph_settings(settings);
+    if !enabled || sources.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    let character_id = character_id.to_string();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match ingest_sources(&app, &character_id, &session_id, sources, character_scope).await {
+            Ok(linked) if linked > 0 => log_info(
+                &app,
+                "entity_graph",
+                format!(
+                    "linked {} entity mentions for session {}",
+                    linked, session_id
+                ),
+            ),
+            Ok(_) => {}
+            Err(err) => log_warn(
+                &app,
+                "entity_graph",
+                format!("entity extraction failed: {}", err),
+            ),
+        }
+    });
+}
+
+/// Memory sources of a session the graph has not seen yet.
+pub fn unprocessed_memory_sources(
+    app: &AppHandle,
+    session_id: &str,
+    memories: &[crate::chat_manager::types::MemoryEmbedding],
+) -> Vec<EntitySource> {
+    let processed: HashSet<String> = open_db(app)
+        .and_then(|conn| entity_graph::processed_source_ids(&conn, session_id, "memory"))
+        .unwrap_or_default();
+    memories
+        .iter()
+        .filter(|memory| !processed.contains(&memory.id))
+        .map(|memory| EntitySource {
+            kind: "memory",
+            id: memory.id.clone(),
+            text: memory.text.clone(),
+        })
+        .collect()
+}
+
+/// Builds the prompt entry with facts about entities named in the latest user
+/// message, or `None` when the graph is disabled or nothing known was mentioned.
+pub fn entity_graph_prompt_entry(
+    app: &AppHandle,
+    settings: &Settings,
+    character_id: &str,
+    session_id: &str,
+    messages: &[StoredMessage],
+) -> Option<SystemPromptEntry> {
+    let (enabled, character_scope) = entity_graph_settings(settings);
+    if !enabled {
+        return None;
+    }
+    let latest = messages.iter().rev().find(|m| m.role == "user")?;
+    let conn = open_db(app).ok()?;
+
+    let mut nodes = entity_graph::load_nodes(&conn, character_id, Some(session_id)).ok()?;
+    if character_scope {
+        let shared = entity_graph::load_nodes(&conn, character_id, None).unwrap_or_default();
+        for node in shared {
+            if find_matching_node(&nodes, &node.normalized).is_none() {
+                nodes.push(node);
+            }
+        }
+    }
+
+    let mentioned: Vec<&EntityNode> = find_mentioned_entities(&latest.content, &nodes)
+        .into_iter()
+        .map(|idx| &nodes[idx])
+        .collect();
+    if mentioned.is_empty() {
+        return None;
+    }
+    let ids: Vec<String> = mentioned.iter().map(|node| node.id.clone()).collect();
+    let facts = entity_graph::facts_for_entities(&conn, &ids, FACTS_PER_ENTITY).ok()?;
+
+    let lines: Vec<String> = mentioned
+        .iter()
+        .filter_map(|node| {
+            let node_facts = facts.get(&node.id)?;
+            Some(format!(
+                "- {} ({}): {}",
+                node.name,
+                node.kind,
+                node_facts.join(" | ")
+            ))
+        })
+        .take(MAX_PROMPT_ENTITIES)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(SystemPromptEntry {
+        id: ENTITY_GRAPH_PROMPT_ENTRY_ID.to_string(),
+        name: "Known entities".to_string(),
+        role: PromptEntryRole::System,
+        content: format!(
+            "Known facts about people, places and things mentioned:\n{}",
+            lines.join("\n")
+        ),
+        enabled: true,
+        injection_position: PromptEntryPosition::Relative,
+        injection_depth: 0,
+        conditional_min_messages: None,
+        interval_turns: None,
+        system_prompt: true,
+        conditions: None,
+        prompt_entry_payload: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, aliases: &[&str]) -> EntityNode {
+        EntityNode {
+            id: name.to_string(),
+            character_id: "c".to_string(),
+            session_id: Some("s".to_string()),
+            kind: "person".to_string(),
+            name: name.to_string(),
+            normalized: normalize_entity_name(name),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            mention_count: 1,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn normalization_folds_case_articles_and_possessives() {
+        assert_eq!(normalize_entity_name("The Iron Keep"), "iron keep");
+        assert_eq!(normalize_entity_name("  Mira’s "), "mira");
+        assert_eq!(normalize_entity_name("Saint-Malo"), "saint malo");
+        assert_eq!(normalize_entity_name("The"), "the");
+    }
+
+    #[test]
+    fn mentions_match_whole_words_and_aliases() {
+        let nodes = vec![
+            node("Mira", &[]),
+            node("Iron Keep", &["the Keep"]),
+            node("Al", &[]),
+        ];
+        let found = find_mentioned_entities("Did you see Mira's sword at the keep?", &nodes);
+        assert_eq!(found, vec![0, 1]);
+        assert!(find_mentioned_entities("Albert left.", &nodes).is_empty());
+    }
+
+    #[test]
+    fn labels_map_to_node_kinds() {
+        assert_eq!(entity_kind_for_label("PER"), Some("person"));
+        assert_eq!(entity_kind_for_label("loc"), Some("place"));
+        assert_eq!(entity_kind_for_label("ORG"), Some("organisation"));
+        assert_eq!(entity_kind_for_label("DATE"), None);
+    }
+}
@@ -15,18 +15,21 @@ use crate::storage_manager::companion_turn_effects::{
     create_processing_effect, mark_effect_failed, mark_effect_ready, CompanionTurnEffectSeed,
 };
 use crate::storage_manager::db::open_db;
+use crate::storage_manager::memory_ann;
+use crate::storage_manager::memory_embeddings::SessionKind;
 use crate::storage_manager::sessions::session_conversation_count;
 use crate::usage::tracking::UsageOperationType;
-use crate::utils::{log_error, log_info, log_warn, now_millis};
+use crate::utils::{emit_debug, log_error, log_info, log_warn, now_millis};
 
 use super::dynamic::{
     apply_memory_decay, calculate_hot_memory_tokens, dynamic_cold_threshold, dynamic_decay_rate,
     dynamic_hot_memory_token_budget, dynamic_max_entries,
     dynamic_memory_structured_fallback_format, enforce_hot_memory_budget, ensure_pinned_hot,
-    find_duplicate_memory_reason, generate_memory_id, normalize_query_text,
+    entity_graph_settings, find_duplicate_memory_reason, generate_memory_id, normalize_query_text,
     search_cold_memory_indices_by_keyword, select_relevant_memory_indices,
     select_top_cosine_memory_indices, trim_memories_to_max,
 };
+use super::hybrid::{select_hybrid_memory_indices, HybridBoosts};
 use super::structured_fallback::{
     memory_operations_fallback_prompt, memory_repairs_fallback_prompt,
     parse_memory_operations_from_text, parse_memory_tag_repairs_from_text,
@@ -933,6 +936,7 @@ pub(crate) async fn select_relevant_memories(
     limit: usize,
     min_similarity: f32,
     strategy: MemoryRetrievalStrategy,
+    boosts: HybridBoosts,
 ) -> Vec<MemoryEmbedding> {
     if query.is_empty() || session.memory_embeddings.is_empty() {
         return Vec::new();
@@ -947,23 +951,81 @@ pub(crate) async fn select_relevant_memories(
     }
 
     let query_embedding = match embedding::compute_embedding(app.clone(), query.to_string()).await {
-        Ok(vec) => vec,
+        Ok(vec) => Some(vec),
         Err(err) => {
             log_warn(
                 app,
                 "memory_retrieval",
                 format!("embedding failed: {}", err),
             );
-            return Vec::new();
+            None
         }
     };
 
-    if matches!(strategy, MemoryRetrievalStrategy::Cosine) {
-        let cosine_indices = select_top_cosine_memory_indices(
-            &query_embedding,
+    if matches!(strategy, MemoryRetrievalStrategy::Hybrid) {
+        // BM25 still works without an embedding, so hybrid degrades to keyword-only.
+        let matches = select_hybrid_memory_indices(
+            query_embedding.as_deref(),
+            query,
             &session.memory_embeddings,
             limit,
             min_similarity,
+            boosts,
+        );
+        emit_debug(
+            app,
+            "memory_retrieval_scores",
+            json!({
+                "sessionId": session.id,
+                "strategy": "hybrid",
+                "results": matches
+                    .iter()
+                    .map(|m| {
+                        let mut value = json!(m);
+                        value["explanation"] = json!(m.explanation());
+                        value
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        );
+        return matches
+            .into_iter()
+            .filter_map(|m| {
+                session.memory_embeddings.get(m.index).map(|mem| {
+                    let mut cloned = mem.clone();
+                    cloned.match_score = m.cosine;
+                    cloned
+                })
+            })
+            .collect();
+    }
+
+    let Some(query_embedding) = query_embedding else {
+        return Vec::new();
+    };
+
+    // Large sessions only score the ANN candidates instead of every memory.
+    let ann_candidates = memory_ann::candidate_indices_app(
+        app,
+        &session.id,
+        SessionKind::Session,
+        &query_embedding,
+        &session.memory_embeddings,
+        memory_ann::candidate_count(limit),
+    );
+
+    if matches!(strategy, MemoryRetrievalStrategy::Cosine) {
+        let cosine_indices = memory_ann::select_from_candidates(
+            ann_candidates.as_deref(),
+            &session.memory_embeddings,
+            |candidates| {
+                select_top_cosine_memory_indices(
+                    &query_embedding,
+                    candidates,
+                    limit,
+                    min_similarity,
+                )
+            },
         );
         if cosine_indices.is_empty() {
             return Vec::new();
@@ -984,11 +1046,12 @@ pub(crate) async fn select_relevant_memories(
     // "most-accessed" picks below act as fallbacks for slots cosine could not
     // fill, not as guaranteed reservations. With v4 retrieval quality this
     // keeps unrelated padding out of the LLM context when cosine succeeds.
-    let cosine_indices = select_relevant_memory_indices(
-        &query_embedding,
+    let cosine_indices = memory_ann::select_from_candidates(
+        ann_candidates.as_deref(),
         &session.memory_embeddings,
-        limit,
-        min_similarity,
+        |candidates| {
+            select_relevant_memory_indices(&query_embedding, candidates, limit, min_similarity)
+        },
     );
 
     let mut selected: HashSet<usize> = HashSet::new();
@@ -1036,11 +1099,12 @@ pub(crate) async fn select_relevant_memories(
     }
 
     if results.len() < limit {
-        let extra_indices = select_relevant_memory_indices(
-            &query_embedding,
+        let extra_indices = memory_ann::select_from_candidates(
+            ann_candidates.as_deref(),
             &session.memory_embeddings,
-            limit,
-            min_similarity,
+            |candidates| {
+                select_relevant_memory_indices(&query_embedding, candidates, limit, min_similarity)
+            },
         );
         for (idx, score) in extra_indices {
             if results.len() >= limit {
@@ -1444,7 +1508,7 @@ fn memory_change_item(memory: &MemoryEmbedding) -> Value {
     })
 }
 
-fn summarize_turn_effect(
+pub(crate) fn summarize_turn_effect(
     relationship_delta: &Value,
     emotion_delta: &Value,
     signal_changes: &Value,
@@ -1993,8 +2057,12 @@ async fn process_dynamic_memory_cycle_with_model(
                 "createdAt": now_millis().unwrap_or_default(),
             });
             session.memory_summary = Some(summary.clone());
-            session.memory_summary_token_count =
-                crate::embedding::tokenizer::count_tokens(app, &summary).unwrap_or(0);
+            session.memory_summary_token_count = crate::tokens::counter_for_model_id(
+                app,
+                settings,
+                character.default_model_id.as_deref(),
+            )
+            .count(&summary);
             session.memory_tool_events.push(event);
             if session.memory_tool_events.len() > 50 {
                 let excess = session.memory_tool_events.len() - 50;
@@ -2027,7 +2095,8 @@ async fn process_dynamic_memory_cycle_with_model(
 
     session.memory_summary = Some(summary.clone());
     session.memory_summary_token_count =
-        crate::embedding::tokenizer::count_tokens(app, &summary).unwrap_or(0);
+        crate::tokens::counter_for_model_id(app, settings, character.default_model_id.as_deref())
+            .count(&summary);
     let event = json!({
         "id": Uuid::new_v4().to_string(),
         "windowStart": window_start,
@@ -2061,6 +2130,22 @@ async fn process_dynamic_memory_cycle_with_model(
         return Err(err);
     }
 
+    super::character::auto_promote_session_memories(app, session);
+    if entity_graph_settings(settings).0 {
+        let sources = super::entity_graph::unprocessed_memory_sources(
+            app,
+            &session.id,
+            &session.memory_embeddings,
+        );
+        super::entity_graph::spawn_entity_ingest(
+            app,
+            settings,
+            &character.id,
+            &session.id,
+            sources,
+        );
+    }
+
     if update_default_on_success && model_id_override.is_some() {
         log_info(
             app,
@@ -2510,6 +2595,7 @@ async fn send_dynamic_memory_request(
         stream: Some(false),
         request_id: built.request_id.clone(),
         provider_id: Some(provider_cred.provider_id.clone()),
+        credential_id: Some(provider_cred.id.clone()),
     };
 
     let first_response = match api_request(app.clone(), api_request_payload).await {
@@ -2573,6 +2659,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 api_request(app.clone(), api_request_payload).await?
@@ -2648,6 +2735,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 return api_request(app.clone(), api_request_payload).await;
@@ -2700,6 +2788,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 return api_request(app.clone(), api_request_payload).await;
@@ -2788,6 +2877,9 @@ async fn run_memory_tool_update(
     let overwrite_llama_sampler_config = dynamic_memory_llama_sampler_overwrite_enabled(settings);
     let tool_config = build_memory_tool_config();
     let max_entries = dynamic_max_entries(settings);
+    // Hot memories are budgeted in the chat model's tokens, not the memory model's
+    let token_counter =
+        crate::tokens::counter_for_model_id(app, settings, character.default_model_id.as_deref());
 
     let mut messages_for_api = Vec::new();
     let system_role = request_builder::system_role_for(provider_cred);
@@ -3038,8 +3130,7 @@ async fn run_memory_tool_update(
                             }));
                             continue;
                         }
-                        let token_count =
-                            crate::embedding::tokenizer::count_tokens(app, &text).unwrap_or(0);
+                        let token_count = token_counter.count(&text);
                         // Check if memory should be pinned
                         let is_pinned = call
                             .arguments
@@ -3536,8 +3627,7 @@ async fn run_memory_tool_update(
                         }));
                         continue;
                     }
-                    let token_count =
-                        crate::embedding::tokenizer::count_tokens(app, &text).unwrap_or(0);
+                    let token_count = token_counter.count(&text);
                     let (embedding_source_version, embedding_dimensions) =
                         embedding::resolve_active_embedding_signature(app)
                             .unwrap_or_else(|_| ("v3".to_string(), 512));
@@ -3978,7 +4068,7 @@ fn summarization_tool_config() -> ToolConfig {
     }
 }
 
-async fn summarize_messages(
+pub(crate) async fn summarize_messages(
     app: &AppHandle,
     provider_cred: &ProviderCredential,
     model: &Model,
@@ -0,0 +1,464 @@
+//! Hierarchical navigable small-world graph for approximate nearest-neighbour
+//! search over memory embeddings. The graph only stores structure; vectors stay
+//! in `memory_embeddings` and callers pass them in by node position.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::dynamic::cosine_similarity;
+
+/// Neighbours kept per node on the upper layers; layer 0 keeps twice as many.
+const M: usize = 16;
+const EF_CONSTRUCTION: usize = 100;
+const MAX_LEVEL: usize = 12;
+
+fn layer_capacity(layer: usize) -> usize {
+    if layer == 0 {
+        M * 2
+    } else {
+        M
+    }
+}
+
+/// Random insertion level with the usual `1 / ln(M)` decay.
+pub fn random_level<R: Rng>(rng: &mut R) -> usize {
+    let ml = 1.0 / (M as f64).ln();
+    let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+    ((-u.ln() * ml).floor() as usize).min(MAX_LEVEL)
+}
+
+/// FNV-1a over the length and up to 16 evenly spaced components. Cheap enough
+/// for the per-turn save path and changes whenever a memory is re-embedded.
+pub fn vector_fingerprint(vector: &[f32]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut mix = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    mix(&(vector.len() as u64).to_le_bytes());
+    let step = (vector.len() / 16).max(1);
+    for value in vector.iter().step_by(step) {
+        mix(&value.to_bits().to_le_bytes());
+    }
+    hash
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswNode {
+    pub memory_id: String,
+    pub fingerprint: u64,
+    pub cold: bool,
+    pub pinned: bool,
+    /// Neighbour lists, one per layer the node lives on (layer 0 first).
+    layers: Vec<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswGraph {
+    pub dim: usize,
+    entry: Option<u32>,
+    max_level: usize,
+    nodes: Vec<HnswNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    similarity: f32,
+    node: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .total_cmp(&other.similarity)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl HnswGraph {
+    pub fn new(dim: usize) -> Self {
+        Self {
+            dim,
+            entry: None,
+            max_level: 0,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[HnswNode] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, memory_id: &str) -> bool {
+        self.nodes.iter().any(|node| node.memory_id == memory_id)
+    }
+
+    /// Sets `cold` on the listed nodes. Returns true when anything changed.
+    pub fn set_cold(&mut self, memory_ids: &HashSet<&str>, cold: bool) -> bool {
+        let mut changed = false;
+        for node in &mut self.nodes {
+            if node.cold != cold && memory_ids.contains(node.memory_id.as_str()) {
+                node.cold = cold;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Replaces the flags of every node `flags` knows about.
+    pub fn set_flags(&mut self, flags: impl Fn(&str) -> Option<(bool, bool)>) {
+        for node in &mut self.nodes {
+            if let Some((cold, pinned)) = flags(&node.memory_id) {
+                node.cold = cold;
+                node.pinned = pinned;
+            }
+        }
+    }
+
+    fn neighbours(&self, node: u32, layer: usize) -> &[u32] {
+        self.nodes[node as usize]
+            .layers
+            .get(layer)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Best-first search of one layer; returns up to `ef` nodes, best first.
+    fn search_layer(
+        &self,
+        vectors: &[&[f32]],
+        query: &[f32],
+        entry_points: &[Candidate],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entry_points.iter().map(|c| c.node).collect();
+        let mut candidates: BinaryHeap<Candidate> = entry_points.iter().copied().collect();
+        let mut found: BinaryHeap<Reverse<Candidate>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            if let Some(Reverse(worst)) = found.peek() {
+                if found.len() >= ef && current.similarity < worst.similarity {
+                    break;
+                }
+            }
+            for &next in self.neighbours(current.node, layer) {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let similarity = cosine_similarity(query, vectors[next as usize]);
+                let worst = found.peek().map(|Reverse(c)| c.similarity);
+                if found.len() < ef || worst.is_none_or(|worst| similarity > worst) {
+                    let candidate = Candidate {
+                        similarity,
+                        node: next,
+                    };
+                    candidates.push(candidate);
+                    found.push(Reverse(candidate));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<Candidate> = found.into_iter().map(|Reverse(c)| c).collect();
+        out.sort_by(|a, b| b.cmp(a));
+        out
+    }
+
+    fn descend(&self, vectors: &[&[f32]], query: &[f32], down_to: usize) -> Vec<Candidate> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        let mut entry_points = vec![Candidate {
+            similarity: cosine_similarity(query, vectors[entry as usize]),
+            node: entry,
+        }];
+        for layer in (down_to.max(1)..=self.max_level).rev() {
+            entry_points = self.search_layer(vectors, query, &entry_points, 1, layer);
+        }
+        entry_points
+    }
+
+    fn prune(&self, vectors: &[&[f32]], node: usize, pool: &mut Vec<u32>, capacity: usize) {
+        let base = vectors[node];
+        pool.sort_by(|a, b| {
+            cosine_similarity(base, vectors[*b as usize])
+                .total_cmp(&cosine_similarity(base, vectors[*a as usize]))
+        });
+        pool.truncate(capacity);
+    }
+
+    /// Inserts a node at `level`. `vectors` must hold one vector per existing
+    /// node and receives the new one.
+    pub fn insert<'v>(
+        &mut self,
+        vectors: &mut Vec<&'v [f32]>,
+        memory_id: String,
+        vector: &'v [f32],
+        cold: bool,
+        pinned: bool,
+        level: usize,
+    ) {
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(HnswNode {
+            memory_id,
+            fingerprint: vector_fingerprint(vector),
+            cold,
+            pinned,
+            layers: vec![Vec::new(); level + 1],
+        });
+        vectors.push(vector);
+
+        if self.entry.is_none() {
+            self.entry = Some(idx);
+            self.max_level = level;
+            return;
+        }
+
+        let mut entry_points = self.descend(vectors, vector, level + 1);
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let found = self.search_layer(vectors, vector, &entry_points, EF_CONSTRUCTION, layer);
+            let capacity = layer_capacity(layer);
+            let neighbours: Vec<u32> = found
+                .iter()
+                .filter(|c| c.node != idx)
+                .take(capacity)
+                .map(|c| c.node)
+                .collect();
+            for &neighbour in &neighbours {
+                let mut pool = self.nodes[neighbour as usize].layers[layer].clone();
+                pool.push(idx);
+                if pool.len() > capacity {
+                    self.prune(vectors, neighbour as usize, &mut pool, capacity);
+                }
+                self.nodes[neighbour as usize].layers[layer] = pool;
+            }
+            self.nodes[idx as usize].layers[layer] = neighbours;
+            entry_points = found;
+        }
+
+        if level > self.max_level {
+            self.entry = Some(idx);
+            self.max_level = level;
+        }
+    }
+
+    /// Removes the node for `memory_id` and reconnects the nodes that pointed
+    /// at it to its former neighbours. `vectors` is kept aligned with the
+    /// nodes; entries for memories that no longer exist may be empty.
+    pub fn remove(&mut self, vectors: &mut Vec<&[f32]>, memory_id: &str) -> bool {
+        let Some(pos) = self.nodes.iter().position(|n| n.memory_id == memory_id) else {
+            return false;
+        };
+        let removed = pos as u32;
+        let removed_layers = self.nodes[pos].layers.clone();
+
+        for (layer, former) in removed_layers.iter().enumerate() {
+            for idx in 0..self.nodes.len() {
+                if idx == pos
+                    || !self.nodes[idx]
+                        .layers
+                        .get(layer)
+                        .is_some_and(|list| list.contains(&removed))
+                {
+                    continue;
+                }
+                let mut pool: Vec<u32> = self.nodes[idx].layers[layer]
+                    .iter()
+                    .copied()
+                    .filter(|&n| n != removed)
+                    .collect();
+                for &candidate in former {
+                    if candidate as usize != idx
+                        && candidate != removed
+                        && !pool.contains(&candidate)
+                    {
+                        pool.push(candidate);
+                    }
+                }
+                self.prune(vectors, idx, &mut pool, layer_capacity(layer));
+                self.nodes[idx].layers[layer] = pool;
+            }
+        }
+
+        let last = (self.nodes.len() - 1) as u32;
+        self.nodes.swap_remove(pos);
+        vectors.swap_remove(pos);
+        if removed != last {
+            for node in &mut self.nodes {
+                for list in &mut node.layers {
+                    for neighbour in list.iter_mut() {
+                        if *neighbour == last {
+                            *neighbour = removed;
+                        }
+                    }
+                }
+            }
+        }
+
+        match self.entry {
+            Some(entry) if entry == removed => {
+                self.entry = self
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, node)| node.layers.len())
+                    .map(|(idx, _)| idx as u32);
+                self.max_level = self
+                    .entry
+                    .map(|entry| self.nodes[entry as usize].layers.len() - 1)
+                    .unwrap_or(0);
+            }
+            Some(entry) if entry == last => self.entry = Some(removed),
+            _ => {}
+        }
+        true
+    }
+
+    /// Approximate top `k` nodes for `query` as `(node, score)`, best first.
+    /// Cold, unpinned nodes have their score scaled by `cold_multiplier` so
+    /// the ordering matches brute-force retrieval.
+    pub fn search(
+        &self,
+        vectors: &[&[f32]],
+        query: &[f32],
+        k: usize,
+        ef: usize,
+        cold_multiplier: f32,
+    ) -> Vec<(usize, f32)> {
+        if k == 0 || query.len() != self.dim || self.entry.is_none() {
+            return Vec::new();
+        }
+        let entry_points = self.descend(vectors, query, 1);
+        let mut scored: Vec<(usize, f32)> = self
+            .search_layer(vectors, query, &entry_points, ef.max(k), 0)
+            .into_iter()
+            .map(|c| {
+                let node = &self.nodes[c.node as usize];
+                let score = if node.cold && !node.pinned {
+                    c.similarity * cold_multiplier
+                } else {
+                    c.similarity
+                };
+                (c.node as usize, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(k);
+        scored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn random_vectors(rng: &mut StdRng, count: usize, dim: usize) -> Vec<Vec<f32>> {
+        (0..count)
+            .map(|_| (0..dim).map(|_| rng.gen_range(-1.0f32..1.0)).collect())
+            .collect()
+    }
+
+    fn build<'a>(rng: &mut StdRng, data: &'a [Vec<f32>]) -> (HnswGraph, Vec<&'a [f32]>) {
+        let mut graph = HnswGraph::new(data[0].len());
+        let mut vectors = Vec::new();
+        for (i, vector) in data.iter().enumerate() {
+            let level = random_level(rng);
+            graph.insert(&mut vectors, format!("m{}", i), vector, false, false, level);
+        }
+        (graph, vectors)
+    }
+
+    fn brute_force(data: &[Vec<f32>], query: &[f32], k: usize) -> Vec<usize> {
+        let mut scored: Vec<(usize, f32)> = data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, cosine_similarity(query, v)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn search_recalls_exact_neighbours() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let data = random_vectors(&mut rng, 600, 24);
+        let (graph, vectors) = build(&mut rng, &data);
+        let queries = random_vectors(&mut rng, 20, 24);
+
+        let mut hits = 0;
+        for query in &queries {
+            let exact: HashSet<usize> = brute_force(&data, query, 10).into_iter().collect();
+            hits += graph
+                .search(&vectors, query, 10, 64, 1.0)
+                .iter()
+                .filter(|(node, _)| exact.contains(node))
+                .count();
+        }
+        assert!(hits as f32 / 200.0 >= 0.9, "recall too low: {}", hits);
+    }
+
+    #[test]
+    fn removal_keeps_graph_consistent() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let data = random_vectors(&mut rng, 200, 16);
+        let (mut graph, mut vectors) = build(&mut rng, &data);
+
+        for i in (0..200).step_by(3) {
+            assert!(graph.remove(&mut vectors, &format!("m{}", i)));
+        }
+        assert!(!graph.remove(&mut vectors, "m0"));
+        assert_eq!(graph.len(), vectors.len());
+        for node in graph.nodes() {
+            for list in &node.layers {
+                assert!(list.iter().all(|&n| (n as usize) < graph.len()));
+            }
+        }
+
+        let query = &data[1];
+        let results = graph.search(&vectors, query, 1, 32, 1.0);
+        assert_eq!(graph.nodes()[results[0].0].memory_id, "m1");
+    }
+
+    #[test]
+    fn cold_nodes_are_penalised() {
+        let data = vec![vec![1.0, 0.0], vec![0.9, 0.1]];
+        let mut graph = HnswGraph::new(2);
+        let mut vectors = Vec::new();
+        graph.insert(&mut vectors, "a".into(), &data[0], false, false, 0);
+        graph.insert(&mut vectors, "b".into(), &data[1], false, false, 0);
+        graph.set_cold(&HashSet::from(["a"]), true);
+
+        let results = graph.search(&vectors, &[1.0, 0.0], 2, 8, 0.7);
+        assert_eq!(graph.nodes()[results[0].0].memory_id, "b");
+    }
+}
@@ -0,0 +1,343 @@
+//! Hybrid memory retrieval: BM25 over memory text fused with cosine ranking via
+//! reciprocal-rank fusion, plus optional recency and pin boosts.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::dynamic::{normalize_query_text, select_top_cosine_memory_indices, MemoryEntry};
+use crate::chat_manager::types::DynamicMemorySettings;
+
+/// Rank offset for reciprocal-rank fusion; 60 is the value from the original
+/// RRF paper and keeps a single top-ranked list from dominating.
+pub const RRF_K: f32 = 60.0;
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+/// Each ranker contributes up to `limit * CANDIDATE_MULTIPLIER` candidates.
+const CANDIDATE_MULTIPLIER: usize = 4;
+const COLD_BM25_MULTIPLIER: f32 = 0.7;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HybridBoosts {
+    pub recency: f32,
+    pub pinned: f32,
+}
+
+impl HybridBoosts {
+    pub fn from_settings(settings: &DynamicMemorySettings) -> Self {
+        Self {
+            recency: settings.hybrid_recency_boost.clamp(0.0, 1.0),
+            pinned: settings.hybrid_pin_boost.clamp(0.0, 1.0),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    normalize_query_text(text)
+        .split_whitespace()
+        .filter(|token| token.chars().count() >= 2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Inverted index over memory text.
+pub struct Bm25Index {
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    doc_lengths: Vec<u32>,
+    avg_doc_length: f32,
+}
+
+impl Bm25Index {
+    pub fn build<E: MemoryEntry>(memories: &[E]) -> Self {
+        let mut postings: HashMap<String, Vec<(usize, u32)>> = HashMap::new();
+        let mut doc_lengths = Vec::with_capacity(memories.len());
+        for (idx, memory) in memories.iter().enumerate() {
+            let tokens = tokenize(memory.text());
+            doc_lengths.push(tokens.len() as u32);
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            for (token, tf) in counts {
+                postings.entry(token).or_default().push((idx, tf));
+            }
+        }
+        let total: u32 = doc_lengths.iter().sum();
+        let avg_doc_length = if doc_lengths.is_empty() {
+            0.0
+        } else {
+            total as f32 / doc_lengths.len() as f32
+        };
+        Self {
+            postings,
+            doc_lengths,
+            avg_doc_length,
+        }
+    }
+
+    /// BM25 scores for documents matching at least one query term, best first.
+    pub fn score(&self, query: &str) -> Vec<(usize, f32)> {
+        let doc_count = self.doc_lengths.len() as f32;
+        if doc_count == 0.0 {
+            return Vec::new();
+        }
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+        for term in &terms {
+            let Some(posting) = self.postings.get(term) else {
+                continue;
+            };
+            let df = posting.len() as f32;
+            let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+            for &(idx, tf) in posting {
+                let tf = tf as f32;
+                let len_norm = if self.avg_doc_length > 0.0 {
+                    self.doc_lengths[idx] as f32 / self.avg_doc_length
+                } else {
+                    1.0
+                };
+                let score = idf * (tf * (BM25_K1 + 1.0))
+                    / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len_norm));
+                *scores.entry(idx).or_insert(0.0) += score;
+            }
+        }
+        let mut ranked: Vec<(usize, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+        ranked
+    }
+}
+
+/// One fused result with the pieces that produced its score.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HybridMatch {
+    pub index: usize,
+    pub memory_id: String,
+    pub score: f32,
+    pub cosine: Option<f32>,
+    pub cosine_rank: Option<usize>,
+    pub bm25: Option<f32>,
+    pub bm25_rank: Option<usize>,
+    /// Multiplier applied for recency (1.0 = none).
+    pub recency_factor: f32,
+    /// Multiplier applied for pinning (1.0 = none).
+    pub pin_factor: f32,
+}
+
+impl HybridMatch {
+    pub fn explanation(&self) -> String {
+        let part = |label: &str, value: Option<f32>, rank: Option<usize>| match (value, rank) {
+            (Some(value), Some(rank)) => format!("{} {:.3} #{}", label, value, rank + 1),
+            _ => format!("{} -", label),
+        };
+        format!(
+            "rrf {:.4} = ({}, {}) x recency {:.2} x pin {:.2}",
+            self.score,
+            part("cosine", self.cosine, self.cosine_rank),
+            part("bm25", self.bm25, self.bm25_rank),
+            self.recency_factor,
+            self.pin_factor
+        )
+    }
+}
+
+/// Fuses cosine and BM25 rankings with reciprocal-rank fusion. Cosine results
+/// honour `min_similarity`; BM25 needs at least one shared term. Without a
+/// query embedding the ranking is BM25 only.
+pub fn select_hybrid_memory_indices<E: MemoryEntry>(
+    query_embedding: Option<&[f32]>,
+    query: &str,
+    memories: &[E],
+    limit: usize,
+    min_similarity: f32,
+    boosts: HybridBoosts,
+) -> Vec<HybridMatch> {
+    if memories.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let depth = limit.saturating_mul(CANDIDATE_MULTIPLIER);
+
+    let cosine = query_embedding
+        .filter(|embedding| !embedding.is_empty())
+        .map(|embedding| {
+            select_top_cosine_memory_indices(embedding, memories, depth, min_similarity)
+        })
+        .unwrap_or_default();
+
+    let mut bm25: Vec<(usize, f32)> = Bm25Index::build(memories)
+        .score(query)
+        .into_iter()
+        .map(|(idx, score)| {
+            let memory = &memories[idx];
+            if memory.is_cold() && !memory.is_pinned() {
+                (idx, score * COLD_BM25_MULTIPLIER)
+            } else {
+                (idx, score)
+            }
+        })
+        .collect();
+    bm25.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    bm25.truncate(depth);
+
+    let mut fused: HashMap<usize, HybridMatch> = HashMap::new();
+    for (rank, (idx, score)) in cosine.iter().enumerate() {
+        let entry = fused
+            .entry(*idx)
+            .or_insert_with(|| empty_match(*idx, memories));
+        entry.cosine = Some(*score);
+        entry.cosine_rank = Some(rank);
+        entry.score += 1.0 / (RRF_K + rank as f32 + 1.0);
+    }
+    for (rank, (idx, score)) in bm25.iter().enumerate() {
+        let entry = fused
+            .entry(*idx)
+            .or_insert_with(|| empty_match(*idx, memories));
+        entry.bm25 = Some(*score);
+        entry.bm25_rank = Some(rank);
+        entry.score += 1.0 / (RRF_K + rank as f32 + 1.0);
+    }
+
+    let (oldest, newest) = memories
+        .iter()
+        .map(|m| m.created_at())
+        .fold((u64::MAX, 0u64), |(lo, hi), t| (lo.min(t), hi.max(t)));
+    let span = newest.saturating_sub(oldest).max(1) as f32;
+
+    let mut results: Vec<HybridMatch> = fused
+        .into_values()
+        .map(|mut entry| {
+            let memory = &memories[entry.index];
+            let recency = memory.created_at().saturating_sub(oldest) as f32 / span;
+            entry.recency_factor = 1.0 + boosts.recency * recency;
+            entry.pin_factor = if memory.is_pinned() {
+                1.0 + boosts.pinned
+            } else {
+                1.0
+            };
+            entry.score *= entry.recency_factor * entry.pin_factor;
+            entry
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.index.cmp(&b.index))
+    });
+    results.truncate(limit);
+    results
+}
+
+fn empty_match<E: MemoryEntry>(index: usize, memories: &[E]) -> HybridMatch {
+    HybridMatch {
+        index,
+        memory_id: memories[index].id().to_string(),
+        score: 0.0,
+        cosine: None,
+        cosine_rank: None,
+        bm25: None,
+        bm25_rank: None,
+        recency_factor: 1.0,
+        pin_factor: 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_manager::types::MemoryEmbedding;
+
+    fn memory(id: &str, text: &str, embedding: Vec<f32>, created_at: u64) -> MemoryEmbedding {
+        MemoryEmbedding {
+            id: id.to_string(),
+            text: text.to_string(),
+            embedding,
+            created_at,
+            token_count: 0,
+            is_cold: false,
+            last_accessed_at: 0,
+            importance_score: 1.0,
+            persistence_importance: 1.0,
+            prompt_importance: 1.0,
+            volatility: 0.0,
+            is_pinned: false,
+            access_count: 0,
+            embedding_source_version: None,
+            embedding_dimensions: None,
+            match_score: None,
+            category: None,
+            canonical_entities: Vec::new(),
+            fact_signature: None,
+            fact_polarity: None,
+            source_role: None,
+            source_message_id: None,
+            superseded_by: None,
+            superseded_at: None,
+            supersedes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn bm25_prefers_rare_terms() {
+        let memories = vec![
+            memory("a", "the cat sat on the mat", vec![], 0),
+            memory("b", "the dog sat on the rug", vec![], 0),
+            memory("c", "Zephyrine keeps the cat fed", vec![], 0),
+        ];
+        let ranked = Bm25Index::build(&memories).score("where is zephyrine");
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0, 2);
+
+        let ranked = Bm25Index::build(&memories).score("cat on the rug");
+        assert_eq!(ranked[0].0, 1);
+    }
+
+    #[test]
+    fn fusion_rewards_agreement_between_rankers() {
+        let memories = vec![
+            memory("a", "sunny beach holiday", vec![1.0, 0.0], 0),
+            memory("b", "Orlan forged the blade", vec![0.9, 0.1], 0),
+            memory("c", "unrelated note", vec![0.0, 1.0], 0),
+        ];
+        let results = select_hybrid_memory_indices(
+            Some(&[1.0, 0.0]),
+            "who is orlan",
+            &memories,
+            2,
+            0.5,
+            HybridBoosts::default(),
+        );
+        assert_eq!(results[0].memory_id, "b");
+        assert!(results[0].bm25.is_some() && results[0].cosine.is_some());
+        assert_eq!(results[1].memory_id, "a");
+        assert!(results[1].bm25.is_none());
+    }
+
+    #[test]
+    fn boosts_and_keyword_only_mode() {
+        let mut memories = vec![
+            memory("old", "the lighthouse keeper", vec![], 0),
+            memory("new", "the lighthouse keeper", vec![], 100),
+        ];
+        let boosts = HybridBoosts {
+            recency: 0.5,
+            pinned: 0.0,
+        };
+        let results = select_hybrid_memory_indices(None, "lighthouse", &memories, 2, 0.5, boosts);
+        assert_eq!(results[0].memory_id, "new");
+        assert!((results[0].recency_factor - 1.5).abs() < 1e-6);
+
+        memories[0].is_pinned = true;
+        let boosts = HybridBoosts {
+            recency: 0.0,
+            pinned: 1.0,
+        };
+        let results = select_hybrid_memory_indices(None, "lighthouse", &memories, 2, 0.5, boosts);
+        assert_eq!(results[0].memory_id, "old");
+        assert!(results[0].explanation().contains("pin 2.00"));
+    }
+}
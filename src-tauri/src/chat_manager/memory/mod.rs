@@ -1,4 +1,8 @@
+pub mod character;
 pub mod dynamic;
+pub mod entity_graph;
 pub mod flow;
+pub mod hnsw;
+pub mod hybrid;
 pub mod manual;
 pub mod structured_fallback;
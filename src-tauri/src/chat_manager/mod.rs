@@ -13,13 +13,15 @@ pub mod reply_helper;
 pub mod scene;
 pub mod service;
 pub mod sse;
+pub mod stop_guard;
 pub mod thinking;
 pub mod tooling;
 pub mod types;
 
 pub use persistence::{attachments, repository, storage};
 pub use prompting::{
-    lorebook_matcher, messages, prompt_engine, prompts, request, request_builder, turn_builder,
+    context_budget, lorebook_matcher, messages, prompt_engine, prompts, request, request_builder,
+    turn_builder,
 };
 
 pub use commands::{
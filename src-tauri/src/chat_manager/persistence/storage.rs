@@ -275,6 +275,11 @@ fn default_settings() -> Settings {
                     volume: 0.6,
                 },
             }),
+            fallback_chain: None,
+            circuit_breaker: None,
+            context_overflow: None,
+            network: None,
+            request_recording_enabled: None,
         }),
         prompt_template_id: None,
         system_prompt: None,
@@ -386,6 +391,7 @@ pub fn resolve_credential_for_model<'a>(
             default_model: None,
             headers: None,
             config: None,
+            api_keys: Vec::new(),
         }));
     }
 
@@ -485,6 +491,7 @@ mod tests {
             default_model: default_model.map(str::to_string),
             headers: None,
             config: None,
+            api_keys: Vec::new(),
         }
     }
 
@@ -0,0 +1,399 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::chat_manager::execution::{find_model_with_credential, RequestSettings};
+use crate::chat_manager::service::require_api_key;
+use crate::chat_manager::turn_builder::message_visible_to_model;
+use crate::chat_manager::types::{
+    Character, ContextOverflowSettings, ContextTrimReport, Model, Session, Settings, StoredMessage,
+    SystemPromptEntry,
+};
+use crate::storage_manager::context_summaries::{
+    load_rolling_summary, save_message_trim, save_rolling_summary,
+};
+use crate::storage_manager::db::open_db;
+use crate::tokens::{counter_for_model, counter_for_model_id, TokenCounter};
+use crate::utils::{emit_debug, log_info, log_warn};
+
+/// Role markers and separators providers add around every message.
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+/// Flat estimate for an attached image; providers bill anywhere from ~85 to
+/// well over a thousand tokens depending on size and detail.
+const IMAGE_PART_TOKENS: u32 = 768;
+/// Headroom for instructions the flows append after the history (continue,
+/// regenerate guidance) and for counting drift.
+const SAFETY_MARGIN_TOKENS: u32 = 128;
+
+lazy_static! {
+    static ref SUMMARIES_IN_FLIGHT: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// History that fits the model's context, and what had to go.
+pub(crate) struct ContextFit {
+    pub kept: Vec<StoredMessage>,
+    pub dropped: Vec<StoredMessage>,
+    /// Rolling summary to send in place of the dropped messages.
+    pub summary: Option<String>,
+    pub report: Option<ContextTrimReport>,
+}
+
+impl ContextFit {
+    fn untouched(history: Vec<StoredMessage>) -> Self {
+        Self {
+            kept: history,
+            dropped: Vec::new(),
+            summary: None,
+            report: None,
+        }
+    }
+}
+
+fn overflow_settings(settings: &Settings) -> ContextOverflowSettings {
+    settings
+        .advanced_settings
+        .as_ref()
+        .and_then(|advanced| advanced.context_overflow.clone())
+        .unwrap_or_default()
+}
+
+pub(crate) fn count_api_message_tokens(message: &Value, counter: &TokenCounter) -> u32 {
+    let content = match message.get("content") {
+        Some(Value::String(text)) => counter.count(text),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(|part| match part.get("type").and_then(|t| t.as_str()) {
+                Some("text") => part
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .map(|text| counter.count(text))
+                    .unwrap_or(0),
+                Some(_) => IMAGE_PART_TOKENS,
+                None => 0,
+            })
+            .sum(),
+        _ => 0,
+    };
+    content + MESSAGE_OVERHEAD_TOKENS
+}
+
+fn count_stored_message_tokens(message: &StoredMessage, counter: &TokenCounter) -> u32 {
+    if !message_visible_to_model(message) {
+        return 0;
+    }
+    counter.count(&message.content)
+        + MESSAGE_OVERHEAD_TOKENS
+        + message.attachments.len() as u32 * IMAGE_PART_TOKENS
+}
+
+/// Indices to drop, oldest first, until the remaining cost fits `budget`.
+/// Protected entries are never dropped, even if that leaves it over budget.
+fn select_dropped(costs: &[(u32, bool)], budget: u32) -> Vec<usize> {
+    let mut total: u32 = costs.iter().map(|(tokens, _)| *tokens).sum();
+    let mut dropped = Vec::new();
+    for (idx, (tokens, protected)) in costs.iter().enumerate() {
+        if total <= budget {
+            break;
+        }
+        if *protected {
+            continue;
+        }
+        total -= tokens;
+        dropped.push(idx);
+    }
+    dropped
+}
+
+/// Drops the oldest unpinned history until the request fits the model's
+/// context length. `fixed_messages` is everything already queued ahead of the
+/// history; pinned messages and scenes are never evicted, and neither is the
+/// newest message.
+pub(crate) fn fit_history_to_context(
+    app: &AppHandle,
+    session: &Session,
+    model: &Model,
+    settings: &Settings,
+    fixed_messages: &[Value],
+    in_chat_entries: &[SystemPromptEntry],
+    pinned: &[StoredMessage],
+    history: Vec<StoredMessage>,
+) -> ContextFit {
+    let config = overflow_settings(settings);
+    let request_settings = RequestSettings::resolve(session, model, settings);
+    let Some(context_length) = request_settings.context_length.filter(|_| config.enabled) else {
+        return ContextFit::untouched(history);
+    };
+    let counter = counter_for_model(app, settings, model);
+
+    let fixed_tokens = fixed_messages
+        .iter()
+        .map(|message| count_api_message_tokens(message, &counter))
+        .sum::<u32>()
+        + in_chat_entries
+            .iter()
+            .map(|entry| counter.count(&entry.content) + MESSAGE_OVERHEAD_TOKENS)
+            .sum::<u32>()
+        + pinned
+            .iter()
+            .map(|message| count_stored_message_tokens(message, &counter))
+            .sum::<u32>()
+        + SAFETY_MARGIN_TOKENS;
+    let reserved_output_tokens = request_settings.max_tokens;
+    let budget = context_length.saturating_sub(fixed_tokens + reserved_output_tokens);
+
+    let last_idx = history.len().saturating_sub(1);
+    let costs: Vec<(u32, bool)> = history
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| {
+            let protected = message.is_pinned || message.role == "scene" || idx == last_idx;
+            (count_stored_message_tokens(message, &counter), protected)
+        })
+        .collect();
+    let history_tokens_before: u32 = costs.iter().map(|(tokens, _)| *tokens).sum();
+    if history_tokens_before <= budget {
+        return ContextFit::untouched(history);
+    }
+
+    let mut dropped_idx = select_dropped(&costs, budget);
+    let mut summary = None;
+    let mut summary_tokens = 0;
+    if config.rolling_summary && !dropped_idx.is_empty() {
+        match open_db(app).and_then(|conn| load_rolling_summary(&conn, &session.id)) {
+            Ok(Some(existing)) if !existing.summary.trim().is_empty() => {
+                let text = format!("Summary of earlier conversation:\n{}", existing.summary);
+                summary_tokens = counter.count(&text) + MESSAGE_OVERHEAD_TOKENS;
+                dropped_idx = select_dropped(&costs, budget.saturating_sub(summary_tokens));
+                summary = Some(text);
+            }
+            Ok(_) => {}
+            Err(err) => log_warn(
+                app,
+                "context_budget",
+                format!("failed to load rolling summary: {}", err),
+            ),
+        }
+    }
+
+    let dropped_set: HashSet<usize> = dropped_idx.iter().copied().collect();
+    let mut kept = Vec::with_capacity(history.len() - dropped_set.len());
+    let mut dropped = Vec::with_capacity(dropped_set.len());
+    for (idx, message) in history.into_iter().enumerate() {
+        if dropped_set.contains(&idx) {
+            dropped.push(message);
+        } else {
+            kept.push(message);
+        }
+    }
+
+    let dropped_tokens: u32 = dropped_idx.iter().map(|idx| costs[*idx].0).sum();
+    let history_tokens_after = history_tokens_before - dropped_tokens;
+    let report = ContextTrimReport {
+        context_length,
+        reserved_output_tokens,
+        fixed_tokens,
+        history_tokens_before,
+        history_tokens_after,
+        dropped_message_ids: dropped.iter().map(|m| m.id.clone()).collect(),
+        dropped_tokens,
+        summary_tokens,
+        over_budget: history_tokens_after + summary_tokens > budget,
+    };
+
+    log_info(
+        app,
+        "context_budget",
+        format!(
+            "trimmed history to fit context_length={}: dropped={} messages ({} tokens) kept={} summary={} over_budget={}",
+            context_length,
+            report.dropped_message_ids.len(),
+            dropped_tokens,
+            kept.len(),
+            summary.is_some(),
+            report.over_budget
+        ),
+    );
+    emit_debug(
+        app,
+        "context_trimmed",
+        json!({
+            "sessionId": session.id,
+            "report": report,
+        }),
+    );
+
+    ContextFit {
+        kept,
+        dropped,
+        summary,
+        report: Some(report),
+    }
+}
+
+/// Keeps the trim report with the assistant message and, when rolling
+/// summaries are on, folds newly dropped messages into the summary in the
+/// background.
+pub(crate) fn record_context_fit(
+    app: &AppHandle,
+    session: &Session,
+    character: &Character,
+    settings: &Settings,
+    message_id: &str,
+    fit: &ContextFit,
+) {
+    if let Err(err) = open_db(app)
+        .and_then(|conn| save_message_trim(&conn, &session.id, message_id, fit.report.as_ref()))
+    {
+        log_warn(
+            app,
+            "context_budget",
+            format!("failed to save context trim report: {}", err),
+        );
+    }
+
+    if fit.dropped.is_empty() || !overflow_settings(settings).rolling_summary {
+        return;
+    }
+    let app = app.clone();
+    let session = session.clone();
+    let character = character.clone();
+    let settings = settings.clone();
+    let dropped = fit.dropped.clone();
+    tauri::async_runtime::spawn(async move {
+        {
+            let Ok(mut in_flight) = SUMMARIES_IN_FLIGHT.lock() else {
+                return;
+            };
+            if !in_flight.insert(session.id.clone()) {
+                return;
+            }
+        }
+        if let Err(err) =
+            update_rolling_summary(&app, &session, &character, &settings, &dropped).await
+        {
+            log_warn(
+                &app,
+                "context_budget",
+                format!("rolling summary update failed: {}", err),
+            );
+        }
+        if let Ok(mut in_flight) = SUMMARIES_IN_FLIGHT.lock() {
+            in_flight.remove(&session.id);
+        }
+    });
+}
+
+async fn update_rolling_summary(
+    app: &AppHandle,
+    session: &Session,
+    character: &Character,
+    settings: &Settings,
+    dropped: &[StoredMessage],
+) -> Result<(), String> {
+    let existing = open_db(app).and_then(|conn| load_rolling_summary(&conn, &session.id))?;
+    let covered_until = existing.as_ref().map(|s| s.covered_until).unwrap_or(0);
+    let pending: Vec<StoredMessage> = dropped
+        .iter()
+        .filter(|m| m.created_at > covered_until && message_visible_to_model(m))
+        .cloned()
+        .collect();
+    let Some(newest) = pending.iter().map(|m| m.created_at).max() else {
+        return Ok(());
+    };
+
+    let model_id = overflow_settings(settings)
+        .summary_model_id
+        .or_else(|| {
+            settings
+                .advanced_settings
+                .as_ref()
+                .and_then(|advanced| advanced.summarisation_model_id.clone())
+        })
+        .ok_or_else(|| "No summary model configured for rolling summaries".to_string())?;
+    let (model, credential) = find_model_with_credential(settings, &model_id)
+        .ok_or_else(|| format!("Rolling summary model {} is unavailable", model_id))?;
+    let api_key = require_api_key(app, credential, "context_budget")?;
+
+    let summary = crate::chat_manager::memory::flow::summarize_messages(
+        app,
+        credential,
+        model,
+        &api_key,
+        &pending,
+        existing.as_ref().map(|s| s.summary.as_str()),
+        character,
+        session,
+        settings,
+        None,
+        false,
+        &mut Vec::new(),
+        None,
+        None,
+    )
+    .await?;
+
+    let token_count =
+        counter_for_model_id(app, settings, character.default_model_id.as_deref()).count(&summary);
+    open_db(app)
+        .and_then(|conn| save_rolling_summary(&conn, &session.id, &summary, newest, token_count))?;
+    log_info(
+        app,
+        "context_budget",
+        format!(
+            "rolling summary updated session={} folded={} messages",
+            session.id,
+            pending.len()
+        ),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_oldest_unprotected_until_within_budget() {
+        let costs = [
+            (50, false),
+            (40, true),
+            (30, false),
+            (20, false),
+            (10, true),
+        ];
+        assert!(select_dropped(&costs, 150).is_empty());
+        assert_eq!(select_dropped(&costs, 100), vec![0]);
+        assert_eq!(select_dropped(&costs, 70), vec![0, 2]);
+        assert_eq!(select_dropped(&costs, 60), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn never_drops_protected_even_when_over_budget() {
+        let costs = [(50, true), (30, false), (40, true)];
+        assert_eq!(select_dropped(&costs, 10), vec![1]);
+        assert!(select_dropped(&[(500, true)], 10).is_empty());
+    }
+
+    #[test]
+    fn counts_text_and_image_parts() {
+        let plain = json!({ "role": "user", "content": "" });
+        assert_eq!(
+            count_api_message_tokens(&plain, &TokenCounter::O200k),
+            MESSAGE_OVERHEAD_TOKENS
+        );
+        let multimodal = json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,AA" } }
+            ]
+        });
+        assert_eq!(
+            count_api_message_tokens(&multimodal, &TokenCounter::O200k),
+            IMAGE_PART_TOKENS + MESSAGE_OVERHEAD_TOKENS
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Instruct formats for rendering chat messages into a single raw prompt for
+//! `/v1/completions`-style backends (KoboldCpp, text-generation-webui,
+//! TabbyAPI, vLLM). Preset names follow llama.cpp's built-in template names
+//! where one exists.
+
+use serde_json::Value;
+
+use crate::chat_manager::provider_adapter::extract_text_content;
+
+pub const DEFAULT_PRESET: &str = "chatml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructFormat {
+    pub system_prefix: String,
+    pub system_suffix: String,
+    pub user_prefix: String,
+    pub user_suffix: String,
+    pub assistant_prefix: String,
+    pub assistant_suffix: String,
+    pub stop: Vec<String>,
+    /// Formats without a system turn fold system text into the next user turn.
+    pub system_in_user: bool,
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+pub fn preset(name: &str) -> Option<InstructFormat> {
+    let format = match name.trim().to_ascii_lowercase().as_str() {
+        "chatml" => InstructFormat {
+            system_prefix: "<|im_start|>system\n".into(),
+            system_suffix: "<|im_end|>\n".into(),
+            user_prefix: "<|im_start|>user\n".into(),
+            user_suffix: "<|im_end|>\n".into(),
+            assistant_prefix: "<|im_start|>assistant\n".into(),
+            assistant_suffix: "<|im_end|>\n".into(),
+            stop: strings(&["<|im_end|>", "<|im_start|>"]),
+            system_in_user: false,
+        },
+        "llama3" => InstructFormat {
+            system_prefix: "<|start_header_id|>system<|end_header_id|>\n\n".into(),
+            system_suffix: "<|eot_id|>".into(),
+            user_prefix: "<|start_header_id|>user<|end_header_id|>\n\n".into(),
+            user_suffix: "<|eot_id|>".into(),
+            assistant_prefix: "<|start_header_id|>assistant<|end_header_id|>\n\n".into(),
+            assistant_suffix: "<|eot_id|>".into(),
+            stop: strings(&["<|eot_id|>", "<|start_header_id|>"]),
+            system_in_user: false,
+        },
+        "mistral" | "mistral-v7" => InstructFormat {
+            system_prefix: String::new(),
+            system_suffix: "\n\n".into(),
+            user_prefix: "[INST] ".into(),
+            user_suffix: " [/INST]".into(),
+            assistant_prefix: String::new(),
+            assistant_suffix: "</s>".into(),
+            stop: strings(&["</s>", "[INST]"]),
+            system_in_user: true,
+        },
+        "gemma" => InstructFormat {
+            system_prefix: String::new(),
+            system_suffix: "\n\n".into(),
+            user_prefix: "<start_of_turn>user\n".into(),
+            user_suffix: "<end_of_turn>\n".into(),
+            assistant_prefix: "<start_of_turn>model\n".into(),
+            assistant_suffix: "<end_of_turn>\n".into(),
+            stop: strings(&["<end_of_turn>", "<start_of_turn>"]),
+            system_in_user: true,
+        },
+        "alpaca" => InstructFormat {
+            system_prefix: String::new(),
+            system_suffix: "\n\n".into(),
+            user_prefix: "### Instruction:\n".into(),
+            user_suffix: "\n\n".into(),
+            assistant_prefix: "### Response:\n".into(),
+            assistant_suffix: "\n\n".into(),
+            stop: strings(&["### Instruction:", "### Input:"]),
+            system_in_user: false,
+        },
+        _ => return None,
+    };
+    Some(format)
+}
+
+/// Resolves the credential's `instructTemplate`; `custom` reads the affixes
+/// and stop strings from `customTemplate`.
+pub fn from_config(config: Option<&Value>) -> InstructFormat {
+    let name = config
+        .and_then(|cfg| cfg.get("instructTemplate"))
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_PRESET);
+    if name != "custom" {
+        return preset(name).unwrap_or_else(|| preset(DEFAULT_PRESET).unwrap_or_default());
+    }
+
+    let custom = config.and_then(|cfg| cfg.get("customTemplate"));
+    let field = |key: &str| {
+        custom
+            .and_then(|cfg| cfg.get(key))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    InstructFormat {
+        system_prefix: field("systemPrefix"),
+        system_suffix: field("systemSuffix"),
+        user_prefix: field("userPrefix"),
+        user_suffix: field("userSuffix"),
+        assistant_prefix: field("assistantPrefix"),
+        assistant_suffix: field("assistantSuffix"),
+        stop: custom
+            .and_then(|cfg| cfg.get("stopStrings"))
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        system_in_user: custom
+            .and_then(|cfg| cfg.get("systemInUser"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+    }
+}
+
+impl InstructFormat {
+    /// Renders OpenAI-style messages and leaves an assistant turn open. When
+    /// the last message is already the assistant's (continue), its turn stays
+    /// open so the model picks up mid-reply.
+    pub fn render(&self, messages: &[Value]) -> String {
+        let mut prompt = String::new();
+        let mut pending_system = String::new();
+        let mut open_assistant = false;
+        let last_idx = messages.len().saturating_sub(1);
+
+        for (idx, message) in messages.iter().enumerate() {
+            let Some(content) = extract_text_content(message.get("content")) else {
+                continue;
+            };
+            let role = message
+                .get("role")
+                .and_then(Value::as_str)
+                .unwrap_or("user");
+            match role {
+                "system" | "developer" if self.system_in_user => {
+                    pending_system.push_str(&content);
+                    pending_system.push_str(&self.system_suffix);
+                }
+                "system" | "developer" => {
+                    prompt.push_str(&self.system_prefix);
+                    prompt.push_str(&content);
+                    prompt.push_str(&self.system_suffix);
+                }
+                "assistant" => {
+                    prompt.push_str(&self.assistant_prefix);
+                    prompt.push_str(&content);
+                    if idx == last_idx {
+                        open_assistant = true;
+                    } else {
+                        prompt.push_str(&self.assistant_suffix);
+                    }
+                }
+                _ => {
+                    prompt.push_str(&self.user_prefix);
+                    prompt.push_str(&std::mem::take(&mut pending_system));
+                    prompt.push_str(&content);
+                    prompt.push_str(&self.user_suffix);
+                }
+            }
+        }
+
+        if !pending_system.is_empty() {
+            // System text after the last user turn still needs a turn to live in.
+            prompt.push_str(&self.user_prefix);
+            prompt.push_str(pending_system.trim_end());
+            prompt.push_str(&self.user_suffix);
+        }
+        if !open_assistant {
+            prompt.push_str(&self.assistant_prefix);
+        }
+        prompt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_chatml_with_open_assistant_turn() {
+        let format = preset("chatml").unwrap();
+        let prompt = format.render(&[
+            json!({ "role": "system", "content": "Be brief." }),
+            json!({ "role": "user", "content": "Hi" }),
+        ]);
+        assert_eq!(
+            prompt,
+            "<|im_start|>system\nBe brief.<|im_end|>\n<|im_start|>user\nHi<|im_end|>\n<|im_start|>assistant\n"
+        );
+    }
+
+    #[test]
+    fn folds_system_into_user_turn_for_formats_without_one() {
+        let format = preset("mistral").unwrap();
+        let prompt = format.render(&[
+            json!({ "role": "system", "content": "Stay in character." }),
+            json!({ "role": "user", "content": "Hello" }),
+            json!({ "role": "assistant", "content": "Greetings." }),
+            json!({ "role": "user", "content": "Again" }),
+        ]);
+        assert_eq!(
+            prompt,
+            "[INST] Stay in character.\n\nHello [/INST]Greetings.</s>[INST] Again [/INST]"
+        );
+    }
+
+    #[test]
+    fn leaves_trailing_assistant_turn_open_for_continue() {
+        let format = preset("gemma").unwrap();
+        let prompt = format.render(&[
+            json!({ "role": "user", "content": "Tell a story" }),
+            json!({ "role": "assistant", "content": "Once upon" }),
+        ]);
+        assert!(prompt.ends_with("<start_of_turn>model\nOnce upon"));
+    }
+
+    #[test]
+    fn custom_template_reads_affixes_and_stops() {
+        let config = json!({
+            "instructTemplate": "custom",
+            "customTemplate": {
+                "userPrefix": "USER: ",
+                "userSuffix": "\n",
+                "assistantPrefix": "ASSISTANT: ",
+                "stopStrings": ["USER:", ""]
+            }
+        });
+        let format = from_config(Some(&config));
+        assert_eq!(format.stop, vec!["USER:".to_string()]);
+        assert_eq!(
+            format.render(&[json!({ "role": "user", "content": "Hi" })]),
+            "USER: Hi\nASSISTANT: "
+        );
+        assert_eq!(from_config(None), preset(DEFAULT_PRESET).unwrap());
+    }
+}
@@ -1,4 +1,6 @@
+pub mod context_budget;
 pub mod entry_conditions;
+pub mod instruct_templates;
 pub mod lorebook_matcher;
 pub mod messages;
 pub mod parameter_engine;
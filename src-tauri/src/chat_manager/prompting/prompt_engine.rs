@@ -14,7 +14,9 @@ use crate::chat_manager::types::{
     Settings, SystemPromptEntry,
 };
 use crate::storage_manager::db::open_db;
-use crate::storage_manager::lorebook::{get_character_active_lorebook_ids, get_lorebook};
+use crate::storage_manager::lorebook::{
+    get_character_active_lorebook_ids, get_lorebook, LorebookEntry,
+};
 use crate::utils;
 
 pub fn default_system_prompt_template() -> String {
@@ -1976,13 +1978,15 @@ fn get_lorebook_content(
     Ok(format_lorebook_for_prompt(&active_entries))
 }
 
-pub fn resolve_used_lorebook_entries(
+/// Active lorebook entries whose content made it into the rendered prompt,
+/// each with its "lorebook / entry" label.
+pub fn resolve_injected_lorebook_entries(
     app: &AppHandle,
     character_id: &str,
     persona: Option<&Persona>,
     session: &Session,
     rendered_entries: &[SystemPromptEntry],
-) -> Vec<String> {
+) -> Vec<(String, LorebookEntry)> {
     let conn = match open_db(app) {
         Ok(conn) => conn,
         Err(_) => return Vec::new(),
@@ -2040,7 +2044,7 @@ pub fn resolve_used_lorebook_entries(
         return Vec::new();
     }
 
-    let mut used: Vec<String> = Vec::new();
+    let mut used: Vec<(String, LorebookEntry)> = Vec::new();
     for entry in active_entries {
         let content = entry.content.trim();
         if content.is_empty() {
@@ -2067,14 +2071,23 @@ pub fn resolve_used_lorebook_entries(
             format!("[{}]", &entry.id[..6.min(entry.id.len())])
         };
         let label = format!("{} / {}", lorebook_name, entry_name);
-        if !used.iter().any(|existing| existing == &label) {
-            used.push(label);
-        }
+        used.push((label, entry));
     }
 
     used
 }
 
+/// Labels of the injected entries, as shown on the message.
+pub fn used_lorebook_entry_labels(injected: &[(String, LorebookEntry)]) -> Vec<String> {
+    let mut used: Vec<String> = Vec::new();
+    for (label, _) in injected {
+        if !used.contains(label) {
+            used.push(label.clone());
+        }
+    }
+    used
+}
+
 pub fn default_local_roleplay_entries() -> Vec<SystemPromptEntry> {
     vec![
         SystemPromptEntry {
@@ -1,4 +1,4 @@
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
 use uuid::Uuid;
 
 use crate::chat_manager::sse;
@@ -51,6 +51,22 @@ pub fn extract_reasoning(data: &Value, provider_id: Option<&str>) -> Option<Stri
     }
 }
 
+/// Rebuilds a finished response around `text` after a client-side stop cut
+/// it short. Reasoning and usage survive as stream frames.
+pub fn replace_response_text(data: &Value, provider_id: Option<&str>, text: &str) -> Value {
+    let mut raw = match data {
+        Value::String(raw) => sse::strip_text_frames(raw),
+        other => sse::usage_frame(other).unwrap_or_default(),
+    };
+    if let Some(reasoning) = extract_reasoning(data, provider_id) {
+        let frame = json!({ "choices": [{ "index": 0, "delta": { "reasoning": reasoning } }] });
+        raw.push_str(&format!("data: {}\n", frame));
+    }
+    let frame = json!({ "choices": [{ "index": 0, "delta": { "content": text } }] });
+    raw.push_str(&format!("data: {}\ndata: [DONE]\n", frame));
+    Value::String(raw)
+}
+
 pub fn extract_text(data: &Value, provider_id: Option<&str>) -> Option<String> {
     match data {
         Value::String(s) => {
@@ -117,6 +133,42 @@ fn extract_explicit_reasoning(data: &Value) -> Option<String> {
                     }
                 }
             }
+            // OpenAI Responses API: reasoning items -> summary[].text
+            if let Some(Value::Array(output)) = map.get("output") {
+                let summary = output
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("reasoning"))
+                    .filter_map(|item| item.get("summary").and_then(|s| s.as_array()))
+                    .flatten()
+                    .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                if !summary.trim().is_empty() {
+                    return Some(summary);
+                }
+            }
+            // Bedrock Converse: output.message.content[].reasoningContent
+            if let Some(Value::Array(content)) = data.pointer("/output/message/content") {
+                let reasoning = content
+                    .iter()
+                    .filter_map(|block| block.pointer("/reasoningContent/reasoningText/text"))
+                    .filter_map(|t| t.as_str())
+                    .collect::<String>();
+                if !reasoning.trim().is_empty() {
+                    return Some(reasoning);
+                }
+            }
+            // Cohere v2: message.content[] thinking parts
+            if let Some(Value::Array(content)) = data.pointer("/message/content") {
+                let reasoning = content
+                    .iter()
+                    .filter(|part| part.get("type").and_then(|t| t.as_str()) == Some("thinking"))
+                    .filter_map(|part| part.get("thinking").and_then(|t| t.as_str()))
+                    .collect::<String>();
+                if !reasoning.trim().is_empty() {
+                    return Some(reasoning);
+                }
+            }
             map.get("message")
                 .and_then(|message| message.get("thinking"))
                 .and_then(|thinking| thinking.as_str())
@@ -163,6 +215,57 @@ fn extract_raw_text(data: &Value, _provider_id: Option<&str>) -> Option<String>
                                 }
                             }
                         }
+                        if let Some(Value::String(text)) = choice_map.get("text") {
+                            if !text.trim().is_empty() {
+                                return Some(text.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            // OpenAI Responses API: output[] message items -> content[].text
+            if let Some(Value::Array(output)) = map.get("output") {
+                let text = output
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("message"))
+                    .filter_map(|item| item.get("content").and_then(|c| c.as_array()))
+                    .flatten()
+                    .filter(|part| part.get("type").and_then(|t| t.as_str()) == Some("output_text"))
+                    .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                    .collect::<String>();
+                if !text.trim().is_empty() {
+                    return Some(text);
+                }
+            }
+            // Bedrock Converse: output.message.content[].text (reasoning blocks
+            // sit alongside and must not be picked up)
+            if let Some(Value::Array(content)) = data.pointer("/output/message/content") {
+                let text = content
+                    .iter()
+                    .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+                    .collect::<String>();
+                if !text.trim().is_empty() {
+                    return Some(text);
+                }
+            }
+            // Cohere v2: message.content[] text parts (thinking parts sit alongside)
+            if let Some(Value::Array(content)) = data.pointer("/message/content") {
+                let text = content
+                    .iter()
+                    .filter(|part| part.get("type").and_then(|t| t.as_str()) == Some("text"))
+                    .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                    .collect::<String>();
+                if !text.trim().is_empty() {
+                    return Some(text);
+                }
+            }
+            // KoboldCpp /api/v1/generate: results[].text
+            if let Some(Value::Array(results)) = map.get("results") {
+                for result in results {
+                    if let Some(Value::String(text)) = result.get("text") {
+                        if !text.trim().is_empty() {
+                            return Some(text.clone());
+                        }
                     }
                 }
             }
@@ -317,7 +420,12 @@ pub fn extract_usage(data: &Value) -> Option<UsageSummary> {
         Value::Object(map) => {
             if let Some(usage_value) = map.get("usage") {
                 if let Some(summary) = match usage_value {
-                    Value::Object(obj) => usage_from_map(obj),
+                    // Cohere nests the counts under `tokens`.
+                    Value::Object(obj) => usage_from_map(obj).or_else(|| {
+                        obj.get("tokens")
+                            .and_then(|v| v.as_object())
+                            .and_then(usage_from_map)
+                    }),
                     _ => extract_usage(usage_value),
                 } {
                     return Some(enrich_usage_summary_from_map(summary, map));
@@ -414,6 +522,7 @@ fn usage_from_map(map: &Map<String, Value>) -> Option<UsageSummary> {
             "cachedContentTokenCount",
             "cache_read",
             "cacheRead",
+            "cacheReadInputTokens",
         ],
     )
     .or_else(|| {
@@ -421,10 +530,11 @@ fn usage_from_map(map: &Map<String, Value>) -> Option<UsageSummary> {
             .and_then(|v| v.as_object())
             .and_then(|details| take_first(details, &["cached_tokens", "cachedTokens"]))
     });
-    let cache_write_tokens = map
-        .get("prompt_tokens_details")
-        .and_then(|v| v.as_object())
-        .and_then(|details| take_first(details, &["cache_write_tokens", "cacheWriteTokens"]));
+    let cache_write_tokens = take_first(map, &["cacheWriteInputTokens"]).or_else(|| {
+        map.get("prompt_tokens_details")
+            .and_then(|v| v.as_object())
+            .and_then(|details| take_first(details, &["cache_write_tokens", "cacheWriteTokens"]))
+    });
     let web_search_requests = map
         .get("server_tool_use")
         .and_then(|v| v.as_object())
@@ -515,6 +625,15 @@ fn enrich_usage_summary_from_map(
             .or_else(|| map.get("cost").and_then(parse_float_value));
     }
 
+    if summary.finish_reason.is_none() {
+        // Bedrock Converse and Cohere report it next to usage rather than inside it.
+        summary.finish_reason = map
+            .get("stopReason")
+            .or_else(|| map.get("finish_reason"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+    }
+
     summary
 }
 
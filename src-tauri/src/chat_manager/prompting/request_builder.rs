@@ -3,6 +3,16 @@ use std::collections::HashMap;
 
 use super::request::provider_base_url;
 use crate::chat_manager::provider_adapter::adapter_for;
+use crate::chat_manager::provider_adapter::cohere::{
+    is_cohere_provider, strip_documents, DOCUMENTS_KEY,
+};
+use crate::chat_manager::provider_adapter::openai_responses::{
+    is_responses_credential, strip_reasoning_items, REASONING_ITEMS_KEY,
+};
+use crate::chat_manager::provider_adapter::output_controls::{
+    self, OutputControls, OUTPUT_CONTROLS_KEY,
+};
+use crate::chat_manager::stop_guard::CLIENT_STOPS_KEY;
 use crate::chat_manager::tooling::ToolConfig;
 use crate::chat_manager::types::ProviderCredential;
 use crate::providers::config::supported_extra_body_keys_for_provider;
@@ -164,6 +174,28 @@ pub fn build_chat_request(
     );
     let url = adapter.build_url(&base_url, model_name, api_key, effective_stream);
     let headers = adapter.headers(api_key, credential.headers.as_ref());
+    let stripped_messages;
+    let messages_for_api = if !is_responses_credential(credential)
+        && messages_for_api
+            .iter()
+            .any(|message| message.get(REASONING_ITEMS_KEY).is_some())
+    {
+        stripped_messages = strip_reasoning_items(messages_for_api);
+        &stripped_messages
+    } else {
+        messages_for_api
+    };
+    let without_documents;
+    let messages_for_api = if !is_cohere_provider(&credential.provider_id)
+        && messages_for_api
+            .iter()
+            .any(|message| message.get(DOCUMENTS_KEY).is_some())
+    {
+        without_documents = strip_documents(messages_for_api);
+        &without_documents
+    } else {
+        messages_for_api
+    };
     let mut body = adapter.body(
         model_name,
         messages_for_api,
@@ -183,6 +215,10 @@ pub fn build_chat_request(
     );
 
     let mut extra_body_fields = extra_body_fields.unwrap_or_default();
+    let output_controls = extra_body_fields
+        .remove(OUTPUT_CONTROLS_KEY)
+        .and_then(|value| serde_json::from_value::<OutputControls>(value).ok())
+        .filter(|controls| !controls.is_empty());
     strip_provider_incompatible_extra_fields(credential, &mut extra_body_fields);
     if should_force_local_parallel_tool_calls(credential, tool_config)
         && !extra_body_fields.contains_key("parallel_tool_calls")
@@ -292,6 +328,16 @@ pub fn build_chat_request(
         }
     }
 
+    // Applied last so stop lists merge with ones set by the adapter or extra fields.
+    if let Some(controls) = output_controls {
+        let client_stops = output_controls::apply(&mut body, adapter.output_shape(), &controls);
+        if !client_stops.is_empty() {
+            if let Some(map) = body.as_object_mut() {
+                map.insert(CLIENT_STOPS_KEY.to_string(), json!(client_stops));
+            }
+        }
+    }
+
     BuiltRequest {
         url,
         headers,
@@ -321,6 +367,7 @@ mod tests {
             default_model: None,
             headers: None,
             config: None,
+            api_keys: Vec::new(),
         }
     }
 
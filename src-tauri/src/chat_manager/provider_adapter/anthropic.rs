@@ -5,7 +5,7 @@ use serde_json::{json, Value};
 
 use super::{
     extract_image_data_urls, extract_text_content, parse_data_url,
-    visible_chat_system_instruction_text, ProviderAdapter,
+    visible_chat_system_instruction_text, OutputShape, ProviderAdapter,
 };
 use crate::chat_manager::tooling::{anthropic_tool_choice, anthropic_tools, ToolConfig};
 
@@ -55,6 +55,10 @@ impl ProviderAdapter for AnthropicAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop_sequences"], usize::MAX)
+    }
+
     fn supports_stream(&self) -> bool {
         true
     }
@@ -258,6 +262,7 @@ impl ProviderAdapter for AnthropicAdapter {
                         context_length: None, // Anthropic doesn't explicitly send context_length in this list usually
                         input_price: None,
                         output_price: None,
+                        capabilities: Default::default(),
                     });
                 }
             }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::{ModelInfo, ProviderAdapter};
+use super::{ModelInfo, OutputShape, ProviderAdapter};
 use crate::chat_manager::tooling::ToolConfig;
 
 pub struct Automatic1111Adapter;
@@ -20,6 +20,10 @@ impl ProviderAdapter for Automatic1111Adapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::NONE
+    }
+
     fn supports_stream(&self) -> bool {
         false
     }
@@ -101,6 +105,7 @@ impl ProviderAdapter for Automatic1111Adapter {
                     context_length: None,
                     input_price: None,
                     output_price: None,
+                    capabilities: Default::default(),
                 })
             })
             .collect()
@@ -0,0 +1,870 @@
+//! Amazon Bedrock through the Converse / ConverseStream API.
+//!
+//! Credentials come in two shapes: an access key pair (the secret is the
+//! credential's API key, the key id and optional session token live in its
+//! config), which is SigV4-signed in `prepare_request`, or a Bedrock API key
+//! sent as a bearer token. Streams arrive in the binary event-stream framing
+//! and are transcoded to `data:` lines before the SSE decoder sees them.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    capabilities, extract_image_data_urls, extract_text_content, parse_data_url,
+    visible_chat_system_instruction_text, ModelInfo, OutputShape, ProviderAdapter,
+};
+use crate::api::ApiRequest;
+use crate::chat_manager::tooling::{converse_tool_choice, converse_tools, ToolChoice, ToolConfig};
+use crate::chat_manager::types::ProviderCredential;
+use crate::transport::aws_event_stream::{EventStreamDecoder, EventStreamMessage};
+use crate::transport::sigv4::{self, AwsCredentials};
+
+pub const DEFAULT_BASE_URL: &str = "https://bedrock-runtime.us-east-1.amazonaws.com";
+const DEFAULT_REGION: &str = "us-east-1";
+const SERVICE: &str = "bedrock";
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+// Carry the signing material from `headers` to `prepare_request`, which
+// removes them before anything is logged or sent.
+const ACCESS_KEY_HEADER: &str = "X-Lettuce-Aws-Access-Key-Id";
+const SECRET_KEY_HEADER: &str = "X-Lettuce-Aws-Secret-Access-Key";
+const SESSION_TOKEN_HEADER: &str = "X-Lettuce-Aws-Session-Token";
+const REGION_HEADER: &str = "X-Lettuce-Aws-Region";
+
+pub fn is_bedrock_provider(provider_id: Option<&str>) -> bool {
+    provider_id == Some("aws-bedrock")
+}
+
+pub struct AwsBedrockAdapter {
+    region: String,
+    access_key_id: Option<String>,
+    session_token: Option<String>,
+}
+
+impl AwsBedrockAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        let config_str = |key: &str| {
+            credential
+                .config
+                .as_ref()
+                .and_then(|config| config.get(key))
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            region: config_str("region").unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            access_key_id: config_str("accessKeyId"),
+            session_token: config_str("sessionToken"),
+        }
+    }
+
+    /// The runtime endpoint follows the configured region unless the base URL
+    /// was changed (VPC endpoints, proxies).
+    fn runtime_root(&self, base_url: &str) -> String {
+        let trimmed = base_url.trim().trim_end_matches('/');
+        if trimmed.is_empty() || trimmed == DEFAULT_BASE_URL {
+            format!("https://bedrock-runtime.{}.amazonaws.com", self.region)
+        } else {
+            trimmed.to_string()
+        }
+    }
+}
+
+fn is_anthropic_model(model_name: &str) -> bool {
+    model_name.contains("anthropic.")
+}
+
+impl ProviderAdapter for AwsBedrockAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/model/{{modelId}}/converse", self.runtime_root(base_url))
+    }
+
+    fn build_url(
+        &self,
+        base_url: &str,
+        model_name: &str,
+        _api_key: &str,
+        should_stream: bool,
+    ) -> String {
+        format!(
+            "{}/model/{}/{}",
+            self.runtime_root(base_url),
+            urlencoding::encode(model_name),
+            if should_stream {
+                "converse-stream"
+            } else {
+                "converse"
+            }
+        )
+    }
+
+    fn system_role(&self) -> Cow<'static, str> {
+        "system".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["inferenceConfig", "stopSequences"], 4)
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["Authorization"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert(
+            "Authorization".into(),
+            "AWS4-HMAC-SHA256 <signature>".into(),
+        );
+        out.insert("Content-Type".into(), "application/json".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        match self.access_key_id.as_ref() {
+            Some(access_key_id) => {
+                out.insert(ACCESS_KEY_HEADER.into(), access_key_id.clone());
+                out.insert(SECRET_KEY_HEADER.into(), api_key.to_string());
+                if let Some(token) = self.session_token.as_ref() {
+                    out.insert(SESSION_TOKEN_HEADER.into(), token.clone());
+                }
+                out.insert(REGION_HEADER.into(), self.region.clone());
+            }
+            None => {
+                out.insert("Authorization".into(), format!("Bearer {}", api_key));
+            }
+        }
+        out.insert("Content-Type".into(), "application/json".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        _context_length: Option<u32>,
+        _should_stream: bool,
+        _frequency_penalty: Option<f64>,
+        _presence_penalty: Option<f64>,
+        top_k: Option<u32>,
+        tool_config: Option<&ToolConfig>,
+        reasoning_enabled: bool,
+        _reasoning_effort: Option<String>,
+        reasoning_budget: Option<u32>,
+    ) -> Value {
+        let mut system: Vec<Value> = Vec::new();
+        if let Some(s) = system_prompt.filter(|s| !s.trim().is_empty()) {
+            system.push(json!({ "text": s }));
+        }
+        let mut messages: Vec<Value> = Vec::new();
+
+        for msg in messages_for_api {
+            let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("");
+            let content_text = extract_text_content(msg.get("content"));
+
+            if role == "system" || role == "developer" {
+                if let Some(visible_instruction) = visible_chat_system_instruction_text(msg) {
+                    push_message(
+                        &mut messages,
+                        "user",
+                        vec![json!({ "text": visible_instruction })],
+                    );
+                } else if let Some(text) = content_text.filter(|text| !text.trim().is_empty()) {
+                    system.push(json!({ "text": text }));
+                }
+                continue;
+            }
+
+            let mut blocks: Vec<Value> = Vec::new();
+            if let Some(text) = content_text.filter(|text| !text.trim().is_empty()) {
+                blocks.push(json!({ "text": text }));
+            }
+            // Converse only takes inline bytes (or S3 locations) for images.
+            for image_url in extract_image_data_urls(msg.get("content")) {
+                let Some((mime_type, data)) = parse_data_url(&image_url) else {
+                    continue;
+                };
+                let Some(format) = image_format(&mime_type) else {
+                    continue;
+                };
+                blocks.push(json!({
+                    "image": { "format": format, "source": { "bytes": data } }
+                }));
+            }
+            if blocks.is_empty() {
+                continue;
+            }
+            let mapped_role = if role == "assistant" {
+                "assistant"
+            } else {
+                "user"
+            };
+            push_message(&mut messages, mapped_role, blocks);
+        }
+
+        let anthropic = is_anthropic_model(model_name);
+        let thinking_budget = reasoning_budget.filter(|_| reasoning_enabled && anthropic);
+
+        let mut inference = Map::new();
+        inference.insert(
+            "maxTokens".into(),
+            json!(max_tokens + thinking_budget.unwrap_or(0)),
+        );
+        if thinking_budget.is_some() {
+            // Extended thinking only runs at temperature 1.
+            inference.insert("temperature".into(), json!(1.0));
+        } else if let Some(v) = temperature {
+            inference.insert("temperature".into(), json!(v));
+        }
+        if let Some(v) = top_p {
+            inference.insert("topP".into(), json!(v));
+        }
+
+        let mut body = Map::new();
+        body.insert("messages".into(), Value::Array(messages));
+        if !system.is_empty() {
+            body.insert("system".into(), Value::Array(system));
+        }
+        body.insert("inferenceConfig".into(), Value::Object(inference));
+
+        // Anything outside inferenceConfig is model specific.
+        let mut additional = Map::new();
+        if anthropic {
+            if let Some(k) = top_k {
+                additional.insert("top_k".into(), json!(k));
+            }
+            if let Some(budget) = thinking_budget {
+                additional.insert(
+                    "thinking".into(),
+                    json!({ "type": "enabled", "budget_tokens": budget }),
+                );
+            }
+        }
+        if !additional.is_empty() {
+            body.insert(
+                "additionalModelRequestFields".into(),
+                Value::Object(additional),
+            );
+        }
+
+        let choice = tool_config.and_then(|cfg| cfg.choice.as_ref());
+        if !matches!(choice, Some(ToolChoice::None)) {
+            if let Some(tools) = tool_config.and_then(converse_tools) {
+                let mut tool_config = json!({ "tools": tools });
+                if let Some(choice) = converse_tool_choice(choice) {
+                    tool_config["toolChoice"] = choice;
+                }
+                body.insert("toolConfig".into(), tool_config);
+            }
+        }
+
+        Value::Object(body)
+    }
+
+    /// Model listing lives on the control-plane host, not the runtime one.
+    fn list_models_endpoint(&self, base_url: &str) -> String {
+        format!(
+            "{}/foundation-models?byOutputModality=TEXT",
+            self.runtime_root(base_url)
+                .replacen("bedrock-runtime", "bedrock", 1)
+        )
+    }
+
+    fn parse_models_list(&self, response: Value) -> Vec<ModelInfo> {
+        let Some(summaries) = response.get("modelSummaries").and_then(Value::as_array) else {
+            return Vec::new();
+        };
+        summaries
+            .iter()
+            .filter(|item| {
+                item.pointer("/modelLifecycle/status")
+                    .and_then(Value::as_str)
+                    .map_or(true, |status| status != "LEGACY")
+            })
+            .filter_map(|item| {
+                let id = item.get("modelId").and_then(Value::as_str)?;
+                let display_name = match (
+                    item.get("providerName").and_then(Value::as_str),
+                    item.get("modelName").and_then(Value::as_str),
+                ) {
+                    (Some(provider), Some(model)) => Some(format!("{} {}", provider, model)),
+                    (None, Some(model)) => Some(model.to_string()),
+                    _ => None,
+                };
+                let on_demand = item
+                    .get("inferenceTypesSupported")
+                    .and_then(Value::as_array)
+                    .map_or(true, |types| {
+                        types.iter().any(|t| t.as_str() == Some("ON_DEMAND"))
+                    });
+                Some(ModelInfo {
+                    id: id.to_string(),
+                    display_name,
+                    description: (!on_demand).then(|| {
+                        "Requires an inference profile (e.g. a `us.` prefixed id)".to_string()
+                    }),
+                    context_length: None,
+                    input_price: None,
+                    output_price: None,
+                    capabilities: capabilities::from_bedrock_summary(item),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Converse rejects two consecutive turns with the same role.
+fn push_message(messages: &mut Vec<Value>, role: &str, blocks: Vec<Value>) {
+    if let Some(last) = messages.last_mut() {
+        if last.get("role").and_then(Value::as_str) == Some(role) {
+            if let Some(content) = last.get_mut("content").and_then(Value::as_array_mut) {
+                content.extend(blocks);
+                return;
+            }
+        }
+    }
+    messages.push(json!({ "role": role, "content": blocks }));
+}
+
+fn image_format(mime_type: &str) -> Option<&'static str> {
+    match mime_type {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpeg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        _ => None,
+    }
+}
+
+fn take_header(headers: &mut HashMap<String, String>, name: &str) -> Option<String> {
+    let key = headers
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(name))?
+        .clone();
+    headers.remove(&key)
+}
+
+/// Signs access-key requests with SigV4. The body is fixed to the exact
+/// bytes that were signed; bearer-key requests pass through untouched.
+pub(crate) fn prepare_request(req: &mut ApiRequest) -> Result<(), String> {
+    let Some(headers) = req.headers.as_mut() else {
+        return Ok(());
+    };
+    let Some(access_key_id) = take_header(headers, ACCESS_KEY_HEADER) else {
+        return Ok(());
+    };
+    let secret_access_key = take_header(headers, SECRET_KEY_HEADER).unwrap_or_default();
+    let session_token = take_header(headers, SESSION_TOKEN_HEADER);
+    let region = take_header(headers, REGION_HEADER).unwrap_or_else(|| DEFAULT_REGION.into());
+
+    let body = match req.body.take() {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(raw)) => raw,
+        Some(other) => serde_json::to_string(&other)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?,
+    };
+    let method = req.method.clone().unwrap_or_else(|| "POST".to_string());
+    sigv4::sign(
+        &method,
+        &req.url,
+        headers,
+        body.as_bytes(),
+        &AwsCredentials {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        },
+        &region,
+        SERVICE,
+        chrono::Utc::now(),
+    )?;
+    if !body.is_empty() {
+        req.body = Some(Value::String(body));
+    }
+    Ok(())
+}
+
+/// Signs a model-listing GET built outside `api_request`.
+pub(crate) fn sign_listing_headers(
+    url: &str,
+    headers: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    let mut req = ApiRequest {
+        url: url.to_string(),
+        method: Some("GET".to_string()),
+        headers: Some(std::mem::take(headers)),
+        query: None,
+        body: None,
+        timeout_ms: None,
+        stream: None,
+        request_id: None,
+        provider_id: Some("aws-bedrock".to_string()),
+        credential_id: None,
+    };
+    let result = prepare_request(&mut req);
+    *headers = req.headers.unwrap_or_default();
+    result
+}
+
+/// Returns a transcoder when the response is an event stream. Error
+/// responses are plain JSON and go through untouched.
+pub(crate) fn stream_transcoder(
+    provider_id: Option<&str>,
+    response: &reqwest::Response,
+) -> Option<ConverseStreamTranscoder> {
+    if !is_bedrock_provider(provider_id) {
+        return None;
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())?;
+    content_type
+        .starts_with(EVENT_STREAM_CONTENT_TYPE)
+        .then(ConverseStreamTranscoder::new)
+}
+
+struct PendingToolUse {
+    id: String,
+    name: String,
+    input: String,
+}
+
+/// Turns ConverseStream frames into `data: {...}` lines tagged with
+/// `converseEvent`. Tool input arrives as string fragments, so each tool use
+/// is emitted once, whole, when its block stops; the stop reason is copied
+/// onto the final `metadata` event, which carries usage.
+#[derive(Default)]
+pub(crate) struct ConverseStreamTranscoder {
+    decoder: EventStreamDecoder,
+    tool_uses: HashMap<u64, PendingToolUse>,
+    stop_reason: Option<String>,
+}
+
+impl ConverseStreamTranscoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        let mut out = String::new();
+        for message in self.decoder.feed(chunk)? {
+            if let Some(event) = self.transcode(message) {
+                out.push_str("data: ");
+                out.push_str(&event.to_string());
+                out.push_str("\n\n");
+            }
+        }
+        Ok(out.into_bytes())
+    }
+
+    fn transcode(&mut self, message: EventStreamMessage) -> Option<Value> {
+        let payload = serde_json::from_slice::<Value>(&message.payload).unwrap_or(Value::Null);
+
+        if matches!(message.header(":message-type"), Some("exception" | "error")) {
+            let kind = message
+                .header(":exception-type")
+                .or_else(|| message.header(":error-code"))
+                .unwrap_or("exception");
+            let text = payload
+                .get("message")
+                .or_else(|| payload.get("Message"))
+                .and_then(Value::as_str)
+                .or_else(|| message.header(":error-message"))
+                .unwrap_or(kind);
+            return Some(json!({
+                "converseEvent": "exception",
+                "exceptionType": kind,
+                "message": text,
+            }));
+        }
+
+        let event_type = message.header(":event-type")?.to_string();
+        let mut event = match payload {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+        let index = event
+            .get("contentBlockIndex")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+
+        match event_type.as_str() {
+            "contentBlockStart" => {
+                if let Some(tool) = event.get("start").and_then(|s| s.get("toolUse")) {
+                    self.tool_uses.insert(
+                        index,
+                        PendingToolUse {
+                            id: string_field(tool, "toolUseId"),
+                            name: string_field(tool, "name"),
+                            input: String::new(),
+                        },
+                    );
+                }
+            }
+            "contentBlockDelta" => {
+                if let Some(fragment) = event
+                    .get("delta")
+                    .and_then(|d| d.get("toolUse"))
+                    .and_then(|t| t.get("input"))
+                    .and_then(Value::as_str)
+                {
+                    if let Some(pending) = self.tool_uses.get_mut(&index) {
+                        pending.input.push_str(fragment);
+                    }
+                }
+            }
+            "contentBlockStop" => {
+                if let Some(pending) = self.tool_uses.remove(&index) {
+                    let input = serde_json::from_str::<Value>(&pending.input)
+                        .unwrap_or_else(|_| Value::String(pending.input.clone()));
+                    return Some(json!({
+                        "converseEvent": "toolUse",
+                        "toolUse": {
+                            "toolUseId": pending.id,
+                            "name": pending.name,
+                            "input": input,
+                        }
+                    }));
+                }
+            }
+            "messageStop" => {
+                self.stop_reason = event
+                    .get("stopReason")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            "metadata" => {
+                if let Some(reason) = self.stop_reason.as_ref() {
+                    event.insert("stopReason".into(), json!(reason));
+                }
+            }
+            _ => {}
+        }
+
+        event.insert("converseEvent".into(), json!(event_type));
+        Some(Value::Object(event))
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_manager::sse::SseDecoder;
+    use crate::chat_manager::tooling::{parse_tool_calls, ToolDefinition};
+    use crate::chat_manager::types::NormalizedEvent;
+    use crate::transport::aws_event_stream::encode_message;
+
+    fn credential(config: Value) -> ProviderCredential {
+        ProviderCredential {
+            id: "bedrock-cred".to_string(),
+            provider_id: "aws-bedrock".to_string(),
+            label: "Bedrock".to_string(),
+            api_key: Some("secret".to_string()),
+            base_url: Some(DEFAULT_BASE_URL.to_string()),
+            default_model: None,
+            headers: None,
+            config: Some(config),
+            api_keys: Vec::new(),
+        }
+    }
+
+    fn event(event_type: &str, payload: &str) -> Vec<u8> {
+        encode_message(
+            &[
+                (":event-type", event_type),
+                (":content-type", "application/json"),
+                (":message-type", "event"),
+            ],
+            payload.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn signs_access_key_requests_and_strips_internal_headers() {
+        let adapter = AwsBedrockAdapter::new(&credential(json!({
+            "region": "eu-central-1",
+            "accessKeyId": "AKIDEXAMPLE",
+            "sessionToken": "token"
+        })));
+        let url = adapter.build_url(
+            DEFAULT_BASE_URL,
+            "anthropic.claude-3-haiku-20240307-v1:0",
+            "",
+            true,
+        );
+        assert_eq!(
+            url,
+            "https://bedrock-runtime.eu-central-1.amazonaws.com/model/anthropic.claude-3-haiku-20240307-v1%3A0/converse-stream"
+        );
+
+        let mut req = ApiRequest {
+            url,
+            method: Some("POST".to_string()),
+            headers: Some(adapter.headers("secret", None)),
+            query: None,
+            body: Some(json!({ "messages": [] })),
+            timeout_ms: None,
+            stream: Some(true),
+            request_id: None,
+            provider_id: Some("aws-bedrock".to_string()),
+            credential_id: None,
+        };
+        prepare_request(&mut req).unwrap();
+
+        let headers = req.headers.unwrap();
+        assert!(headers.keys().all(|key| !key.starts_with("X-Lettuce-Aws")));
+        assert_eq!(headers["x-amz-security-token"], "token");
+        let auth = &headers["Authorization"];
+        assert!(auth.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(auth.contains("/eu-central-1/bedrock/aws4_request"));
+        assert!(auth.contains("SignedHeaders=host;x-amz-date;x-amz-security-token"));
+        assert_eq!(req.body, Some(json!("{\"messages\":[]}")));
+    }
+
+    #[test]
+    fn recorded_converse_request_body() {
+        let adapter = AwsBedrockAdapter::new(&credential(json!({})));
+        let messages = vec![
+            json!({ "role": "system", "content": "You are Mira." }),
+            json!({ "role": "assistant", "content": "Hello there." }),
+            json!({ "role": "user", "content": "Hi" }),
+            json!({ "role": "user", "content": [
+                { "type": "text", "text": "Look" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,AAA" } }
+            ]}),
+        ];
+        let tools = ToolConfig {
+            tools: vec![ToolDefinition {
+                name: "roll_dice".to_string(),
+                description: Some("Roll dice".to_string()),
+                parameters: json!({ "type": "object", "properties": {} }),
+            }],
+            choice: Some(ToolChoice::Auto),
+        };
+        let body = adapter.body(
+            "anthropic.claude-3-7-sonnet-20250219-v1:0",
+            &messages,
+            None,
+            Some(0.7),
+            Some(0.9),
+            512,
+            None,
+            true,
+            None,
+            None,
+            Some(40),
+            Some(&tools),
+            true,
+            None,
+            Some(1024),
+        );
+        assert_eq!(
+            body,
+            json!({
+                "messages": [
+                    { "role": "assistant", "content": [{ "text": "Hello there." }] },
+                    { "role": "user", "content": [
+                        { "text": "Hi" },
+                        { "text": "Look" },
+                        { "image": { "format": "png", "source": { "bytes": "AAA" } } }
+                    ]}
+                ],
+                "system": [{ "text": "You are Mira." }],
+                "inferenceConfig": { "maxTokens": 1536, "temperature": 1.0, "topP": 0.9 },
+                "additionalModelRequestFields": {
+                    "top_k": 40,
+                    "thinking": { "type": "enabled", "budget_tokens": 1024 }
+                },
+                "toolConfig": {
+                    "tools": [{ "toolSpec": {
+                        "name": "roll_dice",
+                        "description": "Roll dice",
+                        "inputSchema": { "json": { "type": "object", "properties": {} } }
+                    }}],
+                    "toolChoice": { "auto": {} }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_recorded_converse_stream() {
+        let mut recorded = Vec::new();
+        for (event_type, payload) in [
+            ("messageStart", r#"{"p":"abc","role":"assistant"}"#),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":0,"delta":{"reasoningContent":{"text":"Thinking."}},"p":"ab"}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":1,"delta":{"text":"Rolling"},"p":"abcd"}"#,
+            ),
+            ("contentBlockStop", r#"{"contentBlockIndex":1,"p":"a"}"#),
+            (
+                "contentBlockStart",
+                r#"{"contentBlockIndex":2,"start":{"toolUse":{"name":"roll_dice","toolUseId":"tooluse_1"}},"p":"ab"}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":2,"delta":{"toolUse":{"input":"{\"sides\":"}},"p":"a"}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":2,"delta":{"toolUse":{"input":"20}"}},"p":"a"}"#,
+            ),
+            ("contentBlockStop", r#"{"contentBlockIndex":2,"p":"abc"}"#),
+            ("messageStop", r#"{"p":"ab","stopReason":"tool_use"}"#),
+            (
+                "metadata",
+                r#"{"metrics":{"latencyMs":812},"p":"abc","usage":{"cacheReadInputTokens":5,"inputTokens":31,"outputTokens":42,"totalTokens":73}}"#,
+            ),
+        ] {
+            recorded.extend(event(event_type, payload));
+        }
+
+        let mut transcoder = ConverseStreamTranscoder::new();
+        let mut sse_decoder = SseDecoder::new();
+        let mut text = String::new();
+        let mut events = Vec::new();
+        // Feed in uneven slices, the way the network delivers them.
+        for chunk in recorded.chunks(37) {
+            let transcoded = transcoder.feed(chunk).unwrap();
+            let piece = String::from_utf8(transcoded).unwrap();
+            events.extend(sse_decoder.feed(&piece, Some("aws-bedrock")));
+            text.push_str(&piece);
+        }
+
+        let deltas: String = events
+            .iter()
+            .filter_map(|e| match e {
+                NormalizedEvent::Delta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, "Rolling");
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, NormalizedEvent::Reasoning { text } if text == "Thinking.")));
+        let calls = events
+            .iter()
+            .find_map(|e| match e {
+                NormalizedEvent::ToolCall { calls } => Some(calls.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(calls[0].id, "tooluse_1");
+        assert_eq!(calls[0].arguments, json!({ "sides": 20 }));
+        let usage = events
+            .iter()
+            .find_map(|e| match e {
+                NormalizedEvent::Usage { usage } => Some(usage.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(usage.prompt_tokens, Some(31));
+        assert_eq!(usage.completion_tokens, Some(42));
+        assert_eq!(usage.cached_prompt_tokens, Some(5));
+        assert_eq!(usage.finish_reason.as_deref(), Some("tool_use"));
+        assert!(matches!(events.last(), Some(NormalizedEvent::Done)));
+        assert!(text.contains("\"converseEvent\":\"metadata\""));
+    }
+
+    #[test]
+    fn reads_recorded_converse_response() {
+        let recorded = json!({
+            "metrics": { "latencyMs": 640 },
+            "output": { "message": { "role": "assistant", "content": [
+                { "reasoningContent": { "reasoningText": { "text": "Hmm.", "signature": "sig" } } },
+                { "text": "The tavern is quiet." },
+                { "toolUse": { "toolUseId": "tooluse_2", "name": "roll_dice", "input": { "sides": 6 } } }
+            ]}},
+            "stopReason": "tool_use",
+            "usage": { "inputTokens": 12, "outputTokens": 9, "totalTokens": 21 }
+        });
+        assert_eq!(
+            crate::chat_manager::request::extract_text(&recorded, Some("aws-bedrock")).as_deref(),
+            Some("The tavern is quiet.")
+        );
+        assert_eq!(
+            crate::chat_manager::request::extract_reasoning(&recorded, Some("aws-bedrock"))
+                .as_deref(),
+            Some("Hmm.")
+        );
+        let calls = parse_tool_calls("aws-bedrock", &recorded);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "roll_dice");
+        let usage = crate::chat_manager::request::extract_usage(&recorded).unwrap();
+        assert_eq!(usage.total_tokens, Some(21));
+        assert_eq!(usage.finish_reason.as_deref(), Some("tool_use"));
+    }
+
+    #[test]
+    fn parses_recorded_foundation_models_listing() {
+        let adapter = AwsBedrockAdapter::new(&credential(json!({ "region": "us-west-2" })));
+        assert_eq!(
+            adapter.list_models_endpoint(DEFAULT_BASE_URL),
+            "https://bedrock.us-west-2.amazonaws.com/foundation-models?byOutputModality=TEXT"
+        );
+        let recorded = json!({ "modelSummaries": [
+            {
+                "modelId": "anthropic.claude-3-haiku-20240307-v1:0",
+                "modelName": "Claude 3 Haiku",
+                "providerName": "Anthropic",
+                "inferenceTypesSupported": ["ON_DEMAND"],
+                "modelLifecycle": { "status": "ACTIVE" }
+            },
+            {
+                "modelId": "anthropic.claude-3-7-sonnet-20250219-v1:0",
+                "modelName": "Claude 3.7 Sonnet",
+                "providerName": "Anthropic",
+                "inferenceTypesSupported": ["INFERENCE_PROFILE"],
+                "modelLifecycle": { "status": "ACTIVE" }
+            },
+            {
+                "modelId": "anthropic.claude-v2",
+                "modelName": "Claude",
+                "providerName": "Anthropic",
+                "inferenceTypesSupported": ["ON_DEMAND"],
+                "modelLifecycle": { "status": "LEGACY" }
+            }
+        ]});
+        let models = adapter.parse_models_list(recorded);
+        assert_eq!(models.len(), 2);
+        assert_eq!(
+            models[0].display_name.as_deref(),
+            Some("Anthropic Claude 3 Haiku")
+        );
+        assert!(models[0].description.is_none());
+        assert!(models[1].description.is_some());
+    }
+}
@@ -0,0 +1,276 @@
+//! Azure OpenAI. Requests address a deployment rather than a model, carry
+//! the `api-version` query parameter and authenticate with `api-key`. The
+//! model name configured in the app is the deployment name.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use super::{openai::OpenAIAdapter, ModelInfo, OutputShape, ProviderAdapter};
+use crate::chat_manager::tooling::ToolConfig;
+use crate::chat_manager::types::ProviderCredential;
+
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+/// The deployments listing was dropped from later data-plane versions.
+const DEPLOYMENTS_API_VERSION: &str = "2022-12-01";
+
+pub struct AzureOpenAIAdapter {
+    api_version: String,
+}
+
+impl AzureOpenAIAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        let api_version = credential
+            .config
+            .as_ref()
+            .and_then(|config| config.get("apiVersion"))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|version| !version.is_empty())
+            .unwrap_or(DEFAULT_API_VERSION)
+            .to_string();
+        Self { api_version }
+    }
+}
+
+/// Resource root, tolerating base URLs pasted with `/openai` or a full
+/// deployment path.
+fn resource_root(base_url: &str) -> &str {
+    let trimmed = base_url.trim_end_matches('/');
+    match trimmed.find("/openai") {
+        Some(idx) => &trimmed[..idx],
+        None => trimmed,
+    }
+}
+
+impl ProviderAdapter for AzureOpenAIAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        format!(
+            "{}/openai/deployments/{{deployment}}/chat/completions",
+            resource_root(base_url)
+        )
+    }
+
+    fn build_url(
+        &self,
+        base_url: &str,
+        model_name: &str,
+        _api_key: &str,
+        _should_stream: bool,
+    ) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            resource_root(base_url),
+            urlencoding::encode(model_name),
+            urlencoding::encode(&self.api_version)
+        )
+    }
+
+    fn system_role(&self) -> std::borrow::Cow<'static, str> {
+        "system".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::OPENAI.with_logit_bias()
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["api-key"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("api-key".into(), "<apiKey>".into());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        out.insert("api-key".into(), api_key.to_string());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        context_length: Option<u32>,
+        should_stream: bool,
+        frequency_penalty: Option<f64>,
+        presence_penalty: Option<f64>,
+        top_k: Option<u32>,
+        tool_config: Option<&ToolConfig>,
+        reasoning_enabled: bool,
+        reasoning_effort: Option<String>,
+        reasoning_budget: Option<u32>,
+    ) -> Value {
+        let mut body = OpenAIAdapter.body(
+            model_name,
+            messages_for_api,
+            system_prompt,
+            temperature,
+            top_p,
+            max_tokens,
+            context_length,
+            should_stream,
+            frequency_penalty,
+            presence_penalty,
+            top_k,
+            tool_config,
+            reasoning_enabled,
+            reasoning_effort,
+            reasoning_budget,
+        );
+        if let Some(obj) = body.as_object_mut() {
+            // Azure rejects arguments it does not recognise.
+            obj.remove("context_length");
+            if should_stream {
+                obj.insert("stream_options".into(), json!({ "include_usage": true }));
+            }
+        }
+        body
+    }
+
+    fn list_models_endpoint(&self, base_url: &str) -> String {
+        format!(
+            "{}/openai/deployments?api-version={}",
+            resource_root(base_url),
+            DEPLOYMENTS_API_VERSION
+        )
+    }
+
+    fn parse_models_list(&self, response: Value) -> Vec<ModelInfo> {
+        let Some(data) = response.get("data").and_then(Value::as_array) else {
+            return Vec::new();
+        };
+        data.iter()
+            .filter(|item| {
+                item.get("status")
+                    .and_then(Value::as_str)
+                    .map_or(true, |status| status == "succeeded")
+            })
+            .filter_map(|item| {
+                let id = item.get("id").and_then(Value::as_str)?;
+                Some(ModelInfo {
+                    id: id.to_string(),
+                    display_name: None,
+                    description: item
+                        .get("model")
+                        .and_then(Value::as_str)
+                        .map(|model| format!("Deployment of {}", model)),
+                    context_length: None,
+                    input_price: None,
+                    output_price: None,
+                    capabilities: Default::default(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(config: Option<Value>) -> ProviderCredential {
+        ProviderCredential {
+            id: "azure-cred".to_string(),
+            provider_id: "azure-openai".to_string(),
+            label: "Azure".to_string(),
+            api_key: Some("azure-key".to_string()),
+            base_url: Some("https://contoso.openai.azure.com".to_string()),
+            default_model: None,
+            headers: None,
+            config,
+            api_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn builds_deployment_url_with_api_version() {
+        let adapter = AzureOpenAIAdapter::new(&credential(Some(json!({
+            "apiVersion": "2025-01-01-preview"
+        }))));
+        assert_eq!(
+            adapter.build_url("https://contoso.openai.azure.com/openai/", "gpt-4o-prod", "", true),
+            "https://contoso.openai.azure.com/openai/deployments/gpt-4o-prod/chat/completions?api-version=2025-01-01-preview"
+        );
+        let headers = adapter.headers("azure-key", None);
+        assert_eq!(
+            headers.get("api-key").map(String::as_str),
+            Some("azure-key")
+        );
+        assert!(!headers.contains_key("Authorization"));
+    }
+
+    #[test]
+    fn recorded_request_body_requests_stream_usage() {
+        let adapter = AzureOpenAIAdapter::new(&credential(None));
+        let messages = vec![json!({ "role": "user", "content": "Hello" })];
+        let body = adapter.body(
+            "gpt-4o-prod",
+            &messages,
+            None,
+            Some(0.7),
+            None,
+            256,
+            Some(8192),
+            true,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+        );
+        assert_eq!(
+            body,
+            json!({
+                "model": "gpt-4o-prod",
+                "messages": [{ "role": "user", "content": "Hello" }],
+                "stream": true,
+                "temperature": 0.7,
+                "max_tokens": 256,
+                "stream_options": { "include_usage": true }
+            })
+        );
+    }
+
+    #[test]
+    fn parses_recorded_deployments_listing() {
+        let recorded = json!({
+            "data": [
+                { "id": "gpt-4o-prod", "model": "gpt-4o", "status": "succeeded", "object": "deployment" },
+                { "id": "gpt-4o-mini-new", "model": "gpt-4o-mini", "status": "creating", "object": "deployment" }
+            ],
+            "object": "list"
+        });
+        let models = AzureOpenAIAdapter::new(&credential(None)).parse_models_list(recorded);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "gpt-4o-prod");
+        assert_eq!(
+            models[0].description.as_deref(),
+            Some("Deployment of gpt-4o")
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use serde_json::Value;
+
+use crate::chat_manager::types::ModelCapabilities;
+
+pub(crate) const METADATA_SOURCE: &str = "metadata";
+pub(crate) const PROBE_SOURCE: &str = "probe";
+
+fn string_list(value: Option<&Value>) -> Option<Vec<&str>> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+}
+
+fn from_metadata(capabilities: ModelCapabilities) -> ModelCapabilities {
+    if capabilities == ModelCapabilities::default() {
+        return capabilities;
+    }
+    ModelCapabilities {
+        source: Some(METADATA_SOURCE.to_string()),
+        ..capabilities
+    }
+}
+
+/// OpenAI-compatible listings. OpenRouter exposes `supported_parameters` and
+/// `architecture.input_modalities`; Mistral exposes a `capabilities` object.
+pub(crate) fn from_openai_listing(item: &Value) -> ModelCapabilities {
+    let mut capabilities = ModelCapabilities::default();
+
+    if let Some(params) = string_list(item.get("supported_parameters")) {
+        if !params.is_empty() {
+            capabilities.tools = Some(params.contains(&"tools"));
+            capabilities.reasoning = Some(
+                params
+                    .iter()
+                    .any(|param| matches!(*param, "reasoning" | "include_reasoning")),
+            );
+            capabilities.json_mode = Some(
+                params
+                    .iter()
+                    .any(|param| matches!(*param, "response_format" | "structured_outputs")),
+            );
+        }
+    }
+
+    if let Some(modalities) = string_list(
+        item.get("architecture")
+            .and_then(|arch| arch.get("input_modalities")),
+    ) {
+        capabilities.vision = Some(modalities.contains(&"image"));
+    }
+
+    if let Some(flags) = item.get("capabilities").and_then(Value::as_object) {
+        let flag = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| flags.get(*key).and_then(Value::as_bool))
+        };
+        capabilities.vision = capabilities.vision.or(flag(&["vision"]));
+        capabilities.tools =
+            capabilities
+                .tools
+                .or(flag(&["function_calling", "tools", "tool_use"]));
+        capabilities.reasoning = capabilities.reasoning.or(flag(&["reasoning", "thinking"]));
+        capabilities.json_mode = capabilities
+            .json_mode
+            .or(flag(&["json_mode", "structured_outputs"]));
+    }
+
+    from_metadata(capabilities)
+}
+
+/// Gemini listings only say which methods a model serves and whether it thinks.
+pub(crate) fn from_gemini_listing(id: &str, item: &Value) -> ModelCapabilities {
+    let Some(methods) = string_list(item.get("supportedGenerationMethods")) else {
+        return ModelCapabilities::default();
+    };
+    let chat = methods.contains(&"generateContent");
+    let gemini_chat = chat && id.starts_with("gemini-");
+
+    from_metadata(ModelCapabilities {
+        vision: gemini_chat.then_some(true),
+        tools: Some(gemini_chat),
+        reasoning: item.get("thinking").and_then(Value::as_bool),
+        json_mode: Some(gemini_chat),
+        source: None,
+    })
+}
+
+/// Bedrock foundation model summaries list `inputModalities` such as `["TEXT", "IMAGE"]`.
+pub(crate) fn from_bedrock_summary(item: &Value) -> ModelCapabilities {
+    from_metadata(ModelCapabilities {
+        vision: string_list(item.get("inputModalities")).map(|list| list.contains(&"IMAGE")),
+        ..Default::default()
+    })
+}
+
+/// Ollama `/api/show` reports a `capabilities` list such as `["completion", "vision"]`.
+pub(crate) fn from_ollama_show(payload: &Value) -> ModelCapabilities {
+    let Some(list) = string_list(payload.get("capabilities")) else {
+        return ModelCapabilities::default();
+    };
+    from_metadata(ModelCapabilities {
+        vision: Some(list.contains(&"vision")),
+        tools: Some(list.contains(&"tools")),
+        reasoning: Some(list.contains(&"thinking")),
+        json_mode: Some(list.contains(&"completion")),
+        source: None,
+    })
+}
+
+/// GGUF files carry no capability flags, but the embedded chat template
+/// shows whether the model was trained to call tools or think.
+pub(crate) fn from_chat_template(template: &str) -> ModelCapabilities {
+    let tools = ["tools", "tool_call", "<|python_tag|>"]
+        .iter()
+        .any(|marker| template.contains(marker));
+    let reasoning = ["<think>", "enable_thinking", "reasoning_content"]
+        .iter()
+        .any(|marker| template.contains(marker));
+
+    from_metadata(ModelCapabilities {
+        vision: None,
+        tools: Some(tools),
+        reasoning: Some(reasoning),
+        json_mode: None,
+        source: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_openrouter_parameters_and_modalities() {
+        let caps = from_openai_listing(&json!({
+            "id": "openai/gpt-4o",
+            "architecture": { "input_modalities": ["text", "image"] },
+            "supported_parameters": ["tools", "tool_choice", "response_format", "max_tokens"]
+        }));
+        assert_eq!(caps.vision, Some(true));
+        assert_eq!(caps.tools, Some(true));
+        assert_eq!(caps.reasoning, Some(false));
+        assert_eq!(caps.json_mode, Some(true));
+        assert_eq!(caps.source.as_deref(), Some(METADATA_SOURCE));
+    }
+
+    #[test]
+    fn plain_openai_listing_stays_unknown() {
+        let caps = from_openai_listing(&json!({ "id": "gpt-4o", "object": "model" }));
+        assert_eq!(caps, ModelCapabilities::default());
+    }
+
+    #[test]
+    fn reads_ollama_show_capabilities() {
+        let caps = from_ollama_show(&json!({
+            "capabilities": ["completion", "vision", "thinking"]
+        }));
+        assert_eq!(caps.vision, Some(true));
+        assert_eq!(caps.tools, Some(false));
+        assert_eq!(caps.reasoning, Some(true));
+    }
+
+    #[test]
+    fn detects_tools_and_thinking_in_chat_templates() {
+        let template = "{% if tools %}<tools>{{ tools }}</tools>{% endif %}\
+                        {% if enable_thinking %}<think>{% endif %}";
+        let caps = from_chat_template(template);
+        assert_eq!(caps.tools, Some(true));
+        assert_eq!(caps.reasoning, Some(true));
+        assert_eq!(caps.vision, None);
+    }
+}
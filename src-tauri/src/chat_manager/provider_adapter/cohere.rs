@@ -0,0 +1,496 @@
+//! Cohere v2 chat (`/v2/chat`). Besides the usual chat fields it takes
+//! `documents`: active lorebook entries and retrieved memories are sent that
+//! way rather than as system text, and replies come back with citations that
+//! point at the documents they relied on.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    extract_image_data_urls, extract_text_content, visible_chat_system_instruction_text, ModelInfo,
+    OutputShape, ProviderAdapter,
+};
+use crate::chat_manager::tooling::{cohere_tool_choice, openai_tools, ToolConfig};
+use crate::storage_manager::message_citations::{CitationSource, MessageCitation};
+
+/// Internal key carrying grounding documents (`{id, title, snippet}`) on a
+/// system message. The message content is the plain-text fallback; only this
+/// adapter reads the documents, the request builder strips them for others.
+pub(crate) const DOCUMENTS_KEY: &str = "_lettuceDocuments";
+/// Document id prefix of lorebook entries, whose text also sits in the
+/// rendered system prompt.
+pub(crate) const LORE_DOCUMENT_PREFIX: &str = "lore:";
+pub(crate) const MEMORY_DOCUMENT_PREFIX: &str = "memory:";
+
+pub(crate) fn is_cohere_provider(provider_id: &str) -> bool {
+    provider_id == "cohere"
+}
+
+pub struct CohereAdapter;
+
+impl CohereAdapter {
+    fn api_root(base_url: &str) -> &str {
+        let trimmed = base_url.trim_end_matches('/');
+        trimmed
+            .strip_suffix("/v2")
+            .or_else(|| trimmed.strip_suffix("/v1"))
+            .unwrap_or(trimmed)
+    }
+}
+
+impl ProviderAdapter for CohereAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/v2/chat", Self::api_root(base_url))
+    }
+
+    fn system_role(&self) -> std::borrow::Cow<'static, str> {
+        "system".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop_sequences"], 5)
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["Authorization"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("Authorization".into(), "Bearer <apiKey>".into());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        out.insert("Authorization".into(), format!("Bearer {}", api_key));
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        _context_length: Option<u32>,
+        should_stream: bool,
+        frequency_penalty: Option<f64>,
+        presence_penalty: Option<f64>,
+        top_k: Option<u32>,
+        tool_config: Option<&ToolConfig>,
+        reasoning_enabled: bool,
+        _reasoning_effort: Option<String>,
+        reasoning_budget: Option<u32>,
+    ) -> Value {
+        let documents = collect_documents(messages_for_api);
+        let lore_snippets: Vec<&str> = documents
+            .iter()
+            .filter(|doc| {
+                doc.get("id")
+                    .and_then(Value::as_str)
+                    .is_some_and(|id| id.starts_with(LORE_DOCUMENT_PREFIX))
+            })
+            .filter_map(|doc| doc.pointer("/data/snippet").and_then(Value::as_str))
+            .collect();
+
+        let mut messages: Vec<Value> = Vec::new();
+        if let Some(s) = system_prompt.filter(|s| !s.trim().is_empty()) {
+            messages.push(json!({ "role": "system", "content": s }));
+        }
+
+        for msg in messages_for_api {
+            // The content of a documents message only stands in for them.
+            if msg.get(DOCUMENTS_KEY).is_some() {
+                continue;
+            }
+            let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("");
+            let content_text = extract_text_content(msg.get("content"));
+
+            match role {
+                "system" | "developer" => {
+                    let text = visible_chat_system_instruction_text(msg)
+                        .or_else(|| content_text.map(|text| strip_snippets(&text, &lore_snippets)));
+                    if let Some(text) = text.filter(|text| !text.trim().is_empty()) {
+                        messages.push(json!({ "role": "system", "content": text }));
+                    }
+                }
+                "assistant" => {
+                    let mut out = Map::new();
+                    out.insert("role".into(), json!("assistant"));
+                    if let Some(text) = content_text.filter(|text| !text.trim().is_empty()) {
+                        out.insert("content".into(), json!(text));
+                    }
+                    if let Some(calls) = msg.get("tool_calls").filter(|calls| calls.is_array()) {
+                        out.insert("tool_calls".into(), calls.clone());
+                    }
+                    if out.len() > 1 {
+                        messages.push(Value::Object(out));
+                    }
+                }
+                "tool" => {
+                    messages.push(json!({
+                        "role": "tool",
+                        "tool_call_id": msg.get("tool_call_id").cloned().unwrap_or(Value::Null),
+                        "content": content_text.unwrap_or_default(),
+                    }));
+                }
+                _ => {
+                    let images = extract_image_data_urls(msg.get("content"));
+                    let text = content_text.filter(|text| !text.trim().is_empty());
+                    if images.is_empty() {
+                        if let Some(text) = text {
+                            messages.push(json!({ "role": "user", "content": text }));
+                        }
+                        continue;
+                    }
+                    let mut parts: Vec<Value> = Vec::new();
+                    if let Some(text) = text {
+                        parts.push(json!({ "type": "text", "text": text }));
+                    }
+                    for url in images {
+                        parts.push(json!({ "type": "image_url", "image_url": { "url": url } }));
+                    }
+                    messages.push(json!({ "role": "user", "content": parts }));
+                }
+            }
+        }
+
+        let mut body = Map::new();
+        body.insert("model".into(), json!(model_name));
+        body.insert("messages".into(), Value::Array(messages));
+        body.insert("stream".into(), json!(should_stream));
+        body.insert("max_tokens".into(), json!(max_tokens));
+        if let Some(v) = temperature {
+            body.insert("temperature".into(), json!(v));
+        }
+        if let Some(v) = top_p {
+            body.insert("p".into(), json!(v));
+        }
+        if let Some(v) = top_k {
+            body.insert("k".into(), json!(v));
+        }
+        if let Some(v) = frequency_penalty {
+            body.insert("frequency_penalty".into(), json!(v));
+        }
+        if let Some(v) = presence_penalty {
+            body.insert("presence_penalty".into(), json!(v));
+        }
+        if !documents.is_empty() {
+            body.insert("documents".into(), Value::Array(documents));
+        }
+        if reasoning_enabled {
+            let mut thinking = json!({ "type": "enabled" });
+            if let Some(budget) = reasoning_budget {
+                thinking["token_budget"] = json!(budget);
+            }
+            body.insert("thinking".into(), thinking);
+        }
+        if let Some(cfg) = tool_config {
+            if let Some(tools) = openai_tools(cfg) {
+                body.insert("tools".into(), json!(tools));
+                if let Some(choice) = cohere_tool_choice(cfg.choice.as_ref()) {
+                    body.insert("tool_choice".into(), choice);
+                }
+            }
+        }
+
+        Value::Object(body)
+    }
+
+    fn list_models_endpoint(&self, base_url: &str) -> String {
+        format!(
+            "{}/v1/models?endpoint=chat&page_size=1000",
+            Self::api_root(base_url)
+        )
+    }
+
+    fn parse_models_list(&self, response: Value) -> Vec<ModelInfo> {
+        let Some(models) = response.get("models").and_then(Value::as_array) else {
+            return Vec::new();
+        };
+        models
+            .iter()
+            .filter(|item| {
+                !item
+                    .get("is_deprecated")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+            })
+            .filter_map(|item| {
+                let name = item.get("name").and_then(Value::as_str)?;
+                Some(ModelInfo {
+                    id: name.to_string(),
+                    display_name: None,
+                    description: None,
+                    context_length: item.get("context_length").and_then(Value::as_u64),
+                    input_price: None,
+                    output_price: None,
+                    capabilities: Default::default(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Gathers the documents carried on the request messages, in the shape the
+/// v2 API takes them.
+fn collect_documents(messages: &[Value]) -> Vec<Value> {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut out = Vec::new();
+    for doc in messages
+        .iter()
+        .filter_map(|msg| msg.get(DOCUMENTS_KEY).and_then(Value::as_array))
+        .flatten()
+    {
+        let Some(id) = doc.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(snippet) = doc
+            .get("snippet")
+            .and_then(Value::as_str)
+            .filter(|snippet| !snippet.trim().is_empty())
+        else {
+            continue;
+        };
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+        let mut data = Map::new();
+        if let Some(title) = doc.get("title").and_then(Value::as_str) {
+            data.insert("title".into(), json!(title));
+        }
+        data.insert("snippet".into(), json!(snippet));
+        out.push(json!({ "id": id, "data": data }));
+    }
+    out
+}
+
+/// Removes text that is sent as a document from a system message, so it is
+/// not given to the model twice.
+fn strip_snippets(text: &str, snippets: &[&str]) -> String {
+    let mut out = text.to_string();
+    for snippet in snippets {
+        let snippet = snippet.trim();
+        if !snippet.is_empty() {
+            out = out.replace(snippet, "");
+        }
+    }
+    while out.contains("\n\n\n") {
+        out = out.replace("\n\n\n", "\n\n");
+    }
+    out
+}
+
+/// Drops the internal documents key from messages bound for any other
+/// adapter; documents messages without fallback text are dropped entirely.
+pub(crate) fn strip_documents(messages: &[Value]) -> Vec<Value> {
+    messages
+        .iter()
+        .filter_map(|message| {
+            if message.get(DOCUMENTS_KEY).is_none() {
+                return Some(message.clone());
+            }
+            let mut message = message.clone();
+            if let Some(obj) = message.as_object_mut() {
+                obj.remove(DOCUMENTS_KEY);
+            }
+            extract_text_content(message.get("content"))
+                .filter(|text| !text.trim().is_empty())
+                .map(|_| message)
+        })
+        .collect()
+}
+
+/// Citations of a reply, from either a raw stream (`citation-start` events)
+/// or a complete response body.
+pub(crate) fn citations_from_response(data: &Value) -> Vec<MessageCitation> {
+    let mut out = Vec::new();
+    match data {
+        Value::String(raw) => {
+            for line in raw.lines() {
+                let Some(payload) = line.trim().strip_prefix("data:") else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<Value>(payload.trim()) else {
+                    continue;
+                };
+                if event.get("type").and_then(Value::as_str) != Some("citation-start") {
+                    continue;
+                }
+                match event.pointer("/delta/message/citations") {
+                    Some(Value::Array(citations)) => {
+                        out.extend(citations.iter().filter_map(citation_from_value))
+                    }
+                    Some(citation) => out.extend(citation_from_value(citation)),
+                    None => {}
+                }
+            }
+        }
+        Value::Object(_) => {
+            if let Some(citations) = data.pointer("/message/citations").and_then(Value::as_array) {
+                out.extend(citations.iter().filter_map(citation_from_value));
+            }
+        }
+        _ => {}
+    }
+    out
+}
+
+fn citation_from_value(value: &Value) -> Option<MessageCitation> {
+    let sources: Vec<CitationSource> = value
+        .get("sources")
+        .and_then(Value::as_array)?
+        .iter()
+        .filter_map(|source| {
+            let id = source
+                .get("id")
+                .or_else(|| source.pointer("/document/id"))
+                .and_then(Value::as_str)?;
+            Some(CitationSource {
+                id: id.to_string(),
+                title: source
+                    .pointer("/document/title")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    if sources.is_empty() {
+        return None;
+    }
+    Some(MessageCitation {
+        text: value.get("text").and_then(Value::as_str)?.to_string(),
+        start: value.get("start").and_then(Value::as_u64),
+        end: value.get("end").and_then(Value::as_u64),
+        sources,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body_for(messages: Vec<Value>) -> Value {
+        CohereAdapter.body(
+            "command-a-03-2025",
+            &messages,
+            None,
+            Some(0.7),
+            Some(0.9),
+            512,
+            None,
+            true,
+            None,
+            None,
+            Some(40),
+            None,
+            false,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn sends_lore_and_memories_as_documents() {
+        let body = body_for(vec![
+            json!({
+                "role": "system",
+                "content": "You are Aria.\n\n# World Information\nEldara sank beneath the waves.\n\nStay in character."
+            }),
+            json!({
+                "role": "system",
+                "content": "Relevant memories:\n- Aria fears the sea.",
+                DOCUMENTS_KEY: [
+                    { "id": "lore:e1", "title": "World / Eldara", "snippet": "Eldara sank beneath the waves." },
+                    { "id": "memory:m1", "title": "Memory", "snippet": "Aria fears the sea." }
+                ]
+            }),
+            json!({ "role": "user", "content": "Tell me about Eldara." }),
+        ]);
+
+        assert_eq!(body["p"], json!(0.9));
+        assert_eq!(body["k"], json!(40));
+        assert_eq!(
+            body["documents"],
+            json!([
+                { "id": "lore:e1", "data": { "title": "World / Eldara", "snippet": "Eldara sank beneath the waves." } },
+                { "id": "memory:m1", "data": { "title": "Memory", "snippet": "Aria fears the sea." } }
+            ])
+        );
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0]["content"],
+            json!("You are Aria.\n\n# World Information\n\nStay in character.")
+        );
+        assert_eq!(
+            messages[1],
+            json!({ "role": "user", "content": "Tell me about Eldara." })
+        );
+    }
+
+    #[test]
+    fn other_adapters_keep_only_the_fallback_text() {
+        let stripped = strip_documents(&[
+            json!({ "role": "system", "content": "", DOCUMENTS_KEY: [{ "id": "lore:e1", "snippet": "x" }] }),
+            json!({ "role": "system", "content": "Relevant memories:\n- y", DOCUMENTS_KEY: [] }),
+        ]);
+        assert_eq!(
+            stripped,
+            vec![json!({ "role": "system", "content": "Relevant memories:\n- y" })]
+        );
+    }
+
+    #[test]
+    fn reads_citations_from_streams_and_bodies() {
+        let stream = Value::String(
+            "event: citation-start\n\
+             data: {\"type\":\"citation-start\",\"index\":0,\"delta\":{\"message\":{\"citations\":{\"start\":6,\"end\":12,\"text\":\"Eldara\",\"sources\":[{\"type\":\"document\",\"id\":\"lore:e1\",\"document\":{\"id\":\"lore:e1\",\"title\":\"World / Eldara\"}}]}}}}\n\n\
+             event: citation-end\n\
+             data: {\"type\":\"citation-end\",\"index\":0}\n\n"
+                .to_string(),
+        );
+        let expected = vec![MessageCitation {
+            text: "Eldara".into(),
+            start: Some(6),
+            end: Some(12),
+            sources: vec![CitationSource {
+                id: "lore:e1".into(),
+                title: Some("World / Eldara".into()),
+            }],
+        }];
+        assert_eq!(citations_from_response(&stream), expected);
+
+        let body = json!({
+            "message": {
+                "role": "assistant",
+                "content": [{ "type": "text", "text": "About Eldara..." }],
+                "citations": [{
+                    "start": 6, "end": 12, "text": "Eldara",
+                    "sources": [{ "type": "document", "id": "lore:e1", "document": { "title": "World / Eldara" } }]
+                }]
+            }
+        });
+        assert_eq!(citations_from_response(&body), expected);
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::{OpenAIChatRequest, ProviderAdapter, ReasoningConfig};
+use super::{OpenAIChatRequest, OutputShape, ProviderAdapter, ReasoningConfig};
 use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
 use crate::chat_manager::types::ProviderCredential;
 
@@ -80,6 +80,10 @@ impl ProviderAdapter for CustomGenericAdapter {
             .unwrap_or(Cow::Borrowed("system"))
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::OPENAI.with_logit_bias()
+    }
+
     fn build_url(
         &self,
         base_url: &str,
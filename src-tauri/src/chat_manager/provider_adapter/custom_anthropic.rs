@@ -6,7 +6,7 @@ use serde_json::{json, Value};
 
 use super::{
     extract_image_data_urls, extract_text_content, parse_data_url,
-    visible_chat_system_instruction_text, ProviderAdapter,
+    visible_chat_system_instruction_text, OutputShape, ProviderAdapter,
 };
 use crate::chat_manager::tooling::{anthropic_tool_choice, anthropic_tools, ToolConfig};
 use crate::chat_manager::types::ProviderCredential;
@@ -108,6 +108,10 @@ impl ProviderAdapter for CustomAnthropicAdapter {
             .unwrap_or(Cow::Borrowed("system"))
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop_sequences"], usize::MAX)
+    }
+
     fn build_url(
         &self,
         base_url: &str,
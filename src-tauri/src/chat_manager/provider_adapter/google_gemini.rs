@@ -4,8 +4,8 @@ use serde::Serialize;
 use serde_json::{json, Map, Value};
 
 use super::{
-    extract_image_data_urls, extract_text_content, parse_data_url,
-    visible_chat_system_instruction_text, ProviderAdapter,
+    capabilities, extract_image_data_urls, extract_text_content, parse_data_url,
+    visible_chat_system_instruction_text, OutputShape, ProviderAdapter,
 };
 use crate::chat_manager::tooling::{gemini_tool_config, gemini_tools, ToolConfig};
 
@@ -84,6 +84,10 @@ impl ProviderAdapter for GoogleGeminiAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["generationConfig", "stopSequences"], 5)
+    }
+
     fn supports_stream(&self) -> bool {
         true
     }
@@ -391,6 +395,7 @@ impl ProviderAdapter for GoogleGeminiAdapter {
             for item in list {
                 if let Some(name) = item.get("name").and_then(|n| n.as_str()) {
                     let id = name.strip_prefix("models/").unwrap_or(name).to_string();
+                    let capabilities = capabilities::from_gemini_listing(&id, item);
                     models.push(crate::chat_manager::provider_adapter::ModelInfo {
                         id,
                         display_name: item
@@ -404,6 +409,7 @@ impl ProviderAdapter for GoogleGeminiAdapter {
                         context_length: item.get("inputTokenLimit").and_then(|c| c.as_u64()),
                         input_price: None,
                         output_price: None,
+                        capabilities,
                     });
                 }
             }
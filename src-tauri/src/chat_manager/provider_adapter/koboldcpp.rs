@@ -0,0 +1,352 @@
+//! Native KoboldCpp adapter. Generation goes through `/api/v1/generate` and
+//! `/api/extra/generate/stream` so the extended samplers (DRY, XTC,
+//! smoothing, sampler order, banned tokens) reach the backend; the OpenAI
+//! compatibility layer drops most of them.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde_json::{json, Map, Value};
+
+use super::{ModelInfo, OutputShape, ProviderAdapter};
+use crate::api::ApiRequest;
+use crate::chat_manager::prompting::instruct_templates::{self, InstructFormat};
+use crate::chat_manager::tooling::ToolConfig;
+use crate::chat_manager::types::ProviderCredential;
+use crate::utils::{log_info, log_warn};
+
+lazy_static! {
+    /// `true_max_context_length` per server root; it only changes when the
+    /// server is restarted with another `--contextsize`.
+    static ref MAX_CONTEXT: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
+}
+
+pub fn is_koboldcpp_provider(provider_id: Option<&str>) -> bool {
+    provider_id == Some("koboldcpp")
+}
+
+pub struct KoboldCppAdapter {
+    format: InstructFormat,
+}
+
+impl KoboldCppAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        Self {
+            format: instruct_templates::from_config(credential.config.as_ref()),
+        }
+    }
+}
+
+impl ProviderAdapter for KoboldCppAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/api/v1/generate", server_root(base_url))
+    }
+
+    fn build_url(
+        &self,
+        base_url: &str,
+        _model_name: &str,
+        _api_key: &str,
+        should_stream: bool,
+    ) -> String {
+        if should_stream {
+            format!("{}/api/extra/generate/stream", server_root(base_url))
+        } else {
+            self.endpoint(base_url)
+        }
+    }
+
+    fn system_role(&self) -> Cow<'static, str> {
+        "system".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop_sequence"], usize::MAX)
+            .with_logit_bias()
+            .with_banned(&["banned_tokens"])
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["Authorization"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("Authorization".into(), "Bearer <apiKey>".into());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        // Only servers started with --password expect a key.
+        if !api_key.trim().is_empty() {
+            out.insert("Authorization".into(), format!("Bearer {}", api_key));
+        }
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        _model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        context_length: Option<u32>,
+        _should_stream: bool,
+        _frequency_penalty: Option<f64>,
+        presence_penalty: Option<f64>,
+        top_k: Option<u32>,
+        _tool_config: Option<&ToolConfig>,
+        _reasoning_enabled: bool,
+        _reasoning_effort: Option<String>,
+        _reasoning_budget: Option<u32>,
+    ) -> Value {
+        let prompt = match system_prompt.filter(|s| !s.trim().is_empty()) {
+            Some(system) => {
+                let mut messages = Vec::with_capacity(messages_for_api.len() + 1);
+                messages.push(json!({ "role": "system", "content": system }));
+                messages.extend(messages_for_api.iter().cloned());
+                self.format.render(&messages)
+            }
+            None => self.format.render(messages_for_api),
+        };
+
+        let mut body = Map::new();
+        body.insert("prompt".into(), json!(prompt));
+        body.insert("max_length".into(), json!(max_tokens));
+        if let Some(v) = context_length {
+            body.insert("max_context_length".into(), json!(v));
+        }
+        if let Some(v) = temperature {
+            body.insert("temperature".into(), json!(v));
+        }
+        if let Some(v) = top_p {
+            body.insert("top_p".into(), json!(v));
+        }
+        if let Some(v) = top_k {
+            body.insert("top_k".into(), json!(v));
+        }
+        if let Some(v) = presence_penalty {
+            body.insert("presence_penalty".into(), json!(v));
+        }
+        if !self.format.stop.is_empty() {
+            body.insert("stop_sequence".into(), json!(self.format.stop));
+        }
+        // User stop sequences are appended after the body is built.
+        body.insert("trim_stop".into(), json!(true));
+        Value::Object(body)
+    }
+
+    fn list_models_endpoint(&self, base_url: &str) -> String {
+        format!("{}/api/v1/model", server_root(base_url))
+    }
+
+    fn parse_models_list(&self, response: Value) -> Vec<ModelInfo> {
+        let Some(name) = response.get("result").and_then(Value::as_str) else {
+            return Vec::new();
+        };
+        let name = name.strip_prefix("koboldcpp/").unwrap_or(name);
+        vec![ModelInfo {
+            id: name.to_string(),
+            display_name: None,
+            description: None,
+            context_length: None,
+            input_price: None,
+            output_price: None,
+            capabilities: Default::default(),
+        }]
+    }
+}
+
+/// Server root for a base URL or any KoboldCpp endpoint URL. Users often paste
+/// the OpenAI-compatible `/v1` address, so that suffix is dropped too.
+fn server_root(url: &str) -> &str {
+    let trimmed = url.trim_end_matches('/');
+    if let Some(idx) = trimmed.find("/api/") {
+        return &trimmed[..idx];
+    }
+    let trimmed = trimmed.strip_suffix("/api").unwrap_or(trimmed);
+    trimmed.strip_suffix("/v1").unwrap_or(trimmed)
+}
+
+/// Generation key tying a request to its abort call; KoboldCpp only aborts
+/// the generation whose key matches.
+fn genkey_for(request_id: &str) -> String {
+    format!("KCPP{}", request_id.replace('-', ""))
+}
+
+fn auth_header(req: &ApiRequest) -> Option<String> {
+    req.headers.as_ref().and_then(|headers| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("Authorization"))
+            .map(|(_, value)| value.clone())
+    })
+}
+
+async fn true_max_context_length(req: &ApiRequest, root: &str) -> Result<u32, String> {
+    if let Some(cached) = MAX_CONTEXT
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(root).copied())
+    {
+        return Ok(cached);
+    }
+
+    let client = crate::transport::build_client(req.credential_id.as_deref(), Some(5_000), false)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut builder = client.get(format!("{}/api/extra/true_max_context_length", root));
+    if let Some(auth) = auth_header(req) {
+        builder = builder.header("Authorization", auth);
+    }
+    let payload: Value = builder
+        .send()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .json()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let value = payload
+        .get("value")
+        .and_then(Value::as_u64)
+        .map(|value| value as u32)
+        .ok_or_else(|| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                "true_max_context_length response missing value",
+            )
+        })?;
+    if let Ok(mut cache) = MAX_CONTEXT.lock() {
+        cache.insert(root.to_string(), value);
+    }
+    Ok(value)
+}
+
+/// Fills `max_context_length` from the server's real context size (and caps
+/// a configured one to it), and tags the request with a generation key so it
+/// can be aborted.
+pub(crate) async fn prepare_request(app: &tauri::AppHandle, req: &mut ApiRequest) {
+    let root = server_root(&req.url).to_string();
+    let max_context = match true_max_context_length(req, &root).await {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log_warn(
+                app,
+                "koboldcpp",
+                format!("could not read true_max_context_length: {}", err),
+            );
+            None
+        }
+    };
+    let genkey = req.request_id.as_deref().map(genkey_for);
+
+    let Some(body) = req.body.as_mut().and_then(Value::as_object_mut) else {
+        return;
+    };
+    if let Some(max_context) = max_context {
+        let requested = body
+            .get("max_context_length")
+            .and_then(Value::as_u64)
+            .map(|value| value as u32);
+        let effective = requested.map_or(max_context, |value| value.min(max_context));
+        body.insert("max_context_length".into(), json!(effective));
+    }
+    if let Some(genkey) = genkey {
+        body.entry("genkey").or_insert_with(|| json!(genkey));
+    }
+}
+
+/// Stops the server-side generation. Dropping the HTTP connection is not
+/// enough: KoboldCpp keeps generating and blocks the next request until done.
+pub(crate) fn abort_generation(app: &tauri::AppHandle, req: &ApiRequest) {
+    let root = server_root(&req.url).to_string();
+    let genkey = req
+        .body
+        .as_ref()
+        .and_then(|body| body.get("genkey"))
+        .cloned();
+    let auth = auth_header(req);
+    let credential_id = req.credential_id.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client =
+            match crate::transport::build_client(credential_id.as_deref(), Some(5_000), false) {
+                Ok(client) => client,
+                Err(err) => {
+                    log_warn(&app, "koboldcpp", format!("abort skipped: {}", err));
+                    return;
+                }
+            };
+        let body = match genkey {
+            Some(genkey) => json!({ "genkey": genkey }),
+            None => json!({}),
+        };
+        let mut builder = client.post(format!("{}/api/extra/abort", root)).json(&body);
+        if let Some(auth) = auth {
+            builder = builder.header("Authorization", auth);
+        }
+        match builder.send().await {
+            Ok(response) => log_info(
+                &app,
+                "koboldcpp",
+                format!("abort sent to {} (status {})", root, response.status()),
+            ),
+            Err(err) => log_warn(
+                &app,
+                "koboldcpp",
+                format!("abort request to {} failed: {}", root, err),
+            ),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_root_strips_endpoint_paths() {
+        assert_eq!(
+            server_root("http://127.0.0.1:5001/api/extra/generate/stream"),
+            "http://127.0.0.1:5001"
+        );
+        assert_eq!(
+            server_root("http://127.0.0.1:5001/v1/"),
+            "http://127.0.0.1:5001"
+        );
+        assert_eq!(
+            server_root("http://127.0.0.1:5001/api"),
+            "http://127.0.0.1:5001"
+        );
+        assert_eq!(server_root("http://host:5001"), "http://host:5001");
+    }
+
+    #[test]
+    fn genkey_is_stable_per_request() {
+        assert_eq!(genkey_for("a1-b2"), "KCPPa1b2");
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::{OpenAIChatRequest, ProviderAdapter};
+use super::{OpenAIChatRequest, OutputShape, ProviderAdapter};
 use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
 
 pub struct LlamaCppAdapter;
@@ -23,6 +23,10 @@ impl ProviderAdapter for LlamaCppAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop"], usize::MAX).with_banned(&["stop"])
+    }
+
     fn supports_stream(&self) -> bool {
         true
     }
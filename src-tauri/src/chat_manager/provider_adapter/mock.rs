@@ -0,0 +1,677 @@
+//! Offline mock provider for demos, tutorials and deterministic tests. Nothing
+//! leaves the machine: `api_request` hands mock requests to
+//! [`handle_request`], which answers with scripted or echoed replies shaped
+//! like OpenAI chat completions and can simulate reasoning, tool calls and
+//! provider failures.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use super::{extract_text_content, ModelInfo, ProviderAdapter};
+use crate::abort_manager::AbortRegistry;
+use crate::api::{ApiRequest, ApiResponse};
+use crate::chat_manager::sse::SseDecoder;
+use crate::chat_manager::stop_guard::truncate_at_stop;
+use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
+use crate::chat_manager::types::{ErrorEnvelope, NormalizedEvent, ProviderCredential};
+use crate::transport::emit_normalized;
+use crate::utils::log_info;
+
+const DEFAULT_TOKENS_PER_SECOND: u32 = 40;
+const MOCK_MODEL_ID: &str = "mock-chat";
+
+pub fn is_mock_provider(provider_id: Option<&str>) -> bool {
+    provider_id == Some("mock")
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MockMode {
+    /// Repeat the last user message.
+    #[default]
+    Echo,
+    /// Cycle through `script`, one entry per user turn.
+    Script,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum MockError {
+    #[serde(rename = "429")]
+    RateLimited,
+    #[serde(rename = "500")]
+    ServerError,
+    #[serde(rename = "timeout")]
+    Timeout,
+    #[serde(rename = "contextOverflow")]
+    ContextOverflow,
+}
+
+/// `config.mock` of a mock credential; the adapter copies it into the request
+/// body so the handler sees it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MockConfig {
+    #[serde(default)]
+    pub mode: MockMode,
+    #[serde(default)]
+    pub script: Vec<String>,
+    /// Streaming speed in words per second; 0 streams instantly.
+    #[serde(default)]
+    pub tokens_per_second: Option<u32>,
+    #[serde(default)]
+    pub reasoning: bool,
+    #[serde(default)]
+    pub tool_calls: bool,
+    #[serde(default)]
+    pub error: Option<MockError>,
+}
+
+impl MockConfig {
+    fn from_value(value: Option<&Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+pub struct MockAdapter {
+    config: MockConfig,
+}
+
+impl MockAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        Self {
+            config: MockConfig::from_value(credential.config.as_ref().and_then(|c| c.get("mock"))),
+        }
+    }
+}
+
+impl ProviderAdapter for MockAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/chat/completions", base_url.trim_end_matches('/'))
+    }
+
+    fn system_role(&self) -> Cow<'static, str> {
+        "system".into()
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("Content-Type".into(), "application/json".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        _api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out = self.default_headers_template();
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        _context_length: Option<u32>,
+        should_stream: bool,
+        _frequency_penalty: Option<f64>,
+        _presence_penalty: Option<f64>,
+        _top_k: Option<u32>,
+        tool_config: Option<&ToolConfig>,
+        reasoning_enabled: bool,
+        _reasoning_effort: Option<String>,
+        _reasoning_budget: Option<u32>,
+    ) -> Value {
+        let mut messages = Vec::with_capacity(messages_for_api.len() + 1);
+        if let Some(system) = system_prompt.filter(|s| !s.trim().is_empty()) {
+            messages.push(json!({ "role": "system", "content": system }));
+        }
+        messages.extend(messages_for_api.iter().cloned());
+
+        let mut body = Map::new();
+        body.insert("model".into(), json!(model_name));
+        body.insert("messages".into(), Value::Array(messages));
+        body.insert("stream".into(), json!(should_stream));
+        body.insert("max_tokens".into(), json!(max_tokens));
+        if let Some(v) = temperature {
+            body.insert("temperature".into(), json!(v));
+        }
+        if let Some(v) = top_p {
+            body.insert("top_p".into(), json!(v));
+        }
+        if let Some(cfg) = tool_config {
+            if let Some(tools) = openai_tools(cfg) {
+                body.insert("tools".into(), json!(tools));
+            }
+            if let Some(choice) = openai_tool_choice(cfg.choice.as_ref()) {
+                body.insert("tool_choice".into(), choice);
+            }
+        }
+        body.insert(
+            "reasoning".into(),
+            json!(reasoning_enabled || self.config.reasoning),
+        );
+        body.insert("mock".into(), json!(self.config));
+        Value::Object(body)
+    }
+
+    fn list_models_endpoint(&self, base_url: &str) -> String {
+        format!("{}/models", base_url.trim_end_matches('/'))
+    }
+
+    fn parse_models_list(&self, _response: Value) -> Vec<ModelInfo> {
+        mock_models()
+    }
+}
+
+pub(crate) fn mock_models() -> Vec<ModelInfo> {
+    vec![ModelInfo {
+        id: MOCK_MODEL_ID.to_string(),
+        display_name: Some("Mock Chat".to_string()),
+        description: Some("Offline scripted or echoed replies".to_string()),
+        context_length: Some(32_768),
+        input_price: None,
+        output_price: None,
+        capabilities: Default::default(),
+    }]
+}
+
+#[derive(Debug, Default)]
+struct MockReply {
+    content: String,
+    reasoning: Option<String>,
+    tool_calls: Vec<Value>,
+    prompt_tokens: usize,
+}
+
+impl MockReply {
+    fn finish_reason(&self) -> &'static str {
+        if self.tool_calls.is_empty() {
+            "stop"
+        } else {
+            "tool_calls"
+        }
+    }
+
+    fn usage(&self) -> Value {
+        let completion_tokens = word_count(&self.content)
+            + self.reasoning.as_deref().map(word_count).unwrap_or(0)
+            + self.tool_calls.len();
+        json!({
+            "prompt_tokens": self.prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": self.prompt_tokens + completion_tokens,
+        })
+    }
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+fn messages_of(body: &Value) -> &[Value] {
+    body.get("messages")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn role_of(message: &Value) -> Option<&str> {
+    message.get("role").and_then(Value::as_str)
+}
+
+fn reply_text(config: &MockConfig, messages: &[Value]) -> String {
+    let user_turns = messages
+        .iter()
+        .filter(|m| role_of(m) == Some("user"))
+        .count();
+    if config.mode == MockMode::Script && !config.script.is_empty() {
+        // Regenerating the same turn replays the same entry.
+        let index = user_turns.saturating_sub(1) % config.script.len();
+        return config.script[index].clone();
+    }
+    messages
+        .iter()
+        .rev()
+        .find(|m| role_of(m) == Some("user"))
+        .and_then(|m| extract_text_content(m.get("content")))
+        .filter(|text| !text.trim().is_empty())
+        .unwrap_or_else(|| "(mock) There is no user message to echo yet.".to_string())
+}
+
+/// The tool to call, when the request asks for one or the credential is set
+/// to simulate tool calls.
+fn tool_to_call(config: &MockConfig, body: &Value) -> Option<Value> {
+    let tools = body.get("tools").and_then(Value::as_array)?;
+    let choice = body.get("tool_choice");
+    if choice.and_then(Value::as_str) == Some("none") {
+        return None;
+    }
+    if let Some(name) = choice
+        .and_then(|c| c.pointer("/function/name"))
+        .and_then(Value::as_str)
+    {
+        return tools
+            .iter()
+            .find(|tool| tool.pointer("/function/name").and_then(Value::as_str) == Some(name))
+            .cloned();
+    }
+    let required = choice.and_then(Value::as_str) == Some("required");
+    let answering_tool_result = messages_of(body)
+        .last()
+        .is_some_and(|m| role_of(m) == Some("tool"));
+    if required || (config.tool_calls && !answering_tool_result) {
+        tools.first().cloned()
+    } else {
+        None
+    }
+}
+
+fn placeholder_value(name: &str, schema: &Value) -> Value {
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|values| values.first())
+    {
+        return first.clone();
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("integer") | Some("number") => json!(1),
+        Some("boolean") => json!(true),
+        Some("array") => json!([]),
+        Some("object") => placeholder_arguments(schema),
+        _ => json!(format!("mock {}", name)),
+    }
+}
+
+/// Arguments filling the required properties of a JSON schema.
+fn placeholder_arguments(parameters: &Value) -> Value {
+    let mut args = Map::new();
+    let Some(properties) = parameters.get("properties").and_then(Value::as_object) else {
+        return Value::Object(args);
+    };
+    let required: Vec<&str> = parameters
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    for (name, schema) in properties {
+        if !required.is_empty() && !required.contains(&name.as_str()) {
+            continue;
+        }
+        args.insert(name.clone(), placeholder_value(name, schema));
+    }
+    Value::Object(args)
+}
+
+/// Builds the whole reply up front, so client stops and banned strings can cut
+/// it before any frame is sent.
+fn compose_reply(config: &MockConfig, body: &Value, client_stops: &[String]) -> MockReply {
+    let messages = messages_of(body);
+    let prompt_tokens = messages
+        .iter()
+        .filter_map(|m| extract_text_content(m.get("content")))
+        .map(|text| word_count(&text))
+        .sum();
+
+    let mut reply = MockReply {
+        prompt_tokens,
+        ..MockReply::default()
+    };
+    if let Some(tool) = tool_to_call(config, body) {
+        let name = tool
+            .pointer("/function/name")
+            .and_then(Value::as_str)
+            .unwrap_or("tool");
+        let arguments = tool
+            .pointer("/function/parameters")
+            .map(placeholder_arguments)
+            .unwrap_or_else(|| json!({}));
+        reply.tool_calls.push(json!({
+            "id": format!("call_mock_{}", messages.len()),
+            "type": "function",
+            "function": { "name": name, "arguments": arguments.to_string() },
+        }));
+    } else {
+        let text = reply_text(config, messages);
+        reply.content = truncate_at_stop(&text, client_stops).unwrap_or(text);
+    }
+
+    if body.get("reasoning").and_then(Value::as_bool) == Some(true) {
+        reply.reasoning = Some(format!(
+            "Reading the conversation ({} messages). {}",
+            messages.len(),
+            if reply.tool_calls.is_empty() {
+                "Drafting a reply."
+            } else {
+                "A tool call is needed first."
+            }
+        ));
+    }
+    reply
+}
+
+fn completion_body(reply: &MockReply) -> Value {
+    let mut message = Map::new();
+    message.insert("role".into(), json!("assistant"));
+    message.insert("content".into(), json!(reply.content));
+    if let Some(reasoning) = &reply.reasoning {
+        message.insert("reasoning_content".into(), json!(reasoning));
+    }
+    if !reply.tool_calls.is_empty() {
+        message.insert("tool_calls".into(), json!(reply.tool_calls));
+    }
+    json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion",
+        "model": MOCK_MODEL_ID,
+        "choices": [{
+            "index": 0,
+            "message": message,
+            "finish_reason": reply.finish_reason(),
+        }],
+        "usage": reply.usage(),
+    })
+}
+
+fn chunk_frame(delta: Value, finish_reason: Option<&str>, usage: Option<Value>) -> String {
+    let mut chunk = json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion.chunk",
+        "model": MOCK_MODEL_ID,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+    });
+    if let Some(usage) = usage {
+        chunk["usage"] = usage;
+    }
+    format!("data: {}\n\n", chunk)
+}
+
+/// The reply as OpenAI-style SSE frames, one word per frame.
+fn sse_frames(reply: &MockReply) -> Vec<String> {
+    let mut frames = Vec::new();
+    if let Some(reasoning) = &reply.reasoning {
+        for piece in reasoning.split_inclusive(char::is_whitespace) {
+            frames.push(chunk_frame(
+                json!({ "reasoning_content": piece }),
+                None,
+                None,
+            ));
+        }
+    }
+    for piece in reply.content.split_inclusive(char::is_whitespace) {
+        frames.push(chunk_frame(json!({ "content": piece }), None, None));
+    }
+    for (index, call) in reply.tool_calls.iter().enumerate() {
+        let mut call = call.clone();
+        call["index"] = json!(index);
+        frames.push(chunk_frame(json!({ "tool_calls": [call] }), None, None));
+    }
+    frames.push(chunk_frame(
+        json!({}),
+        Some(reply.finish_reason()),
+        Some(reply.usage()),
+    ));
+    frames.push("data: [DONE]\n\n".to_string());
+    frames
+}
+
+fn response(status: u16, data: Value) -> ApiResponse {
+    ApiResponse {
+        status,
+        ok: (200..300).contains(&status),
+        headers: HashMap::new(),
+        data,
+    }
+}
+
+fn simulated_error(
+    app: &AppHandle,
+    req: &ApiRequest,
+    error: MockError,
+) -> Result<ApiResponse, String> {
+    let (status, message) = match error {
+        MockError::Timeout => {
+            return Err("Request timed out (simulated by the mock provider)".to_string())
+        }
+        MockError::RateLimited => (429, "Rate limit reached (simulated by the mock provider)"),
+        MockError::ServerError => (
+            500,
+            "Internal server error (simulated by the mock provider)",
+        ),
+        MockError::ContextOverflow => (
+            400,
+            "This model's maximum context length was exceeded (context_length_exceeded, simulated by the mock provider)",
+        ),
+    };
+    if req.stream.unwrap_or(false) {
+        if let Some(request_id) = req.request_id.as_deref() {
+            let envelope = ErrorEnvelope {
+                code: None,
+                message: message.to_string(),
+                provider_id: req.provider_id.clone(),
+                request_id: Some(request_id.to_string()),
+                retryable: None,
+                status: Some(status),
+            };
+            emit_normalized(app, request_id, NormalizedEvent::Error { envelope });
+        }
+    }
+    Ok(response(
+        status,
+        json!({ "error": { "message": message, "code": status } }),
+    ))
+}
+
+async fn stream_reply(
+    app: &AppHandle,
+    req: &ApiRequest,
+    request_id: &str,
+    reply: &MockReply,
+    tokens_per_second: u32,
+) -> Result<ApiResponse, String> {
+    let delay = if tokens_per_second == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_millis(1_000 / u64::from(tokens_per_second).max(1))
+    };
+    let registry = app.state::<AbortRegistry>();
+    let mut abort_rx = registry.register(request_id.to_string());
+    let mut decoder = SseDecoder::new();
+    let mut raw = String::new();
+
+    for frame in sse_frames(reply) {
+        tokio::select! {
+            _ = &mut abort_rx => {
+                registry.unregister(request_id);
+                let envelope = ErrorEnvelope {
+                    code: Some("ABORTED".to_string()),
+                    message: "Request was cancelled by user".to_string(),
+                    provider_id: req.provider_id.clone(),
+                    request_id: Some(request_id.to_string()),
+                    retryable: Some(false),
+                    status: None,
+                };
+                emit_normalized(app, request_id, NormalizedEvent::Error { envelope });
+                return Err("Request was cancelled by user".to_string());
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+        for event in decoder.feed(&frame, req.provider_id.as_deref()) {
+            emit_normalized(app, request_id, event);
+        }
+        raw.push_str(&frame);
+    }
+
+    registry.unregister(request_id);
+    Ok(response(200, Value::String(raw)))
+}
+
+/// Answers a mock request without touching the network. `client_stops` are the
+/// stop and banned strings `api_request` took out of the body.
+pub(crate) async fn handle_request(
+    app: AppHandle,
+    req: ApiRequest,
+    client_stops: Vec<String>,
+) -> Result<ApiResponse, String> {
+    let body = req.body.clone().unwrap_or(Value::Null);
+    let config = MockConfig::from_value(body.get("mock"));
+    log_info(
+        &app,
+        "mock_provider",
+        format!(
+            "mock request (mode={:?}, stream={:?}, error={:?})",
+            config.mode, req.stream, config.error
+        ),
+    );
+
+    if let Some(error) = config.error {
+        return simulated_error(&app, &req, error);
+    }
+
+    let reply = compose_reply(&config, &body, &client_stops);
+    match req.request_id.as_deref() {
+        Some(request_id) if req.stream.unwrap_or(false) => {
+            let tokens_per_second = config
+                .tokens_per_second
+                .unwrap_or(DEFAULT_TOKENS_PER_SECOND);
+            stream_reply(&app, &req, request_id, &reply, tokens_per_second).await
+        }
+        _ => Ok(response(200, completion_body(&reply))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_config() -> MockConfig {
+        MockConfig {
+            mode: MockMode::Script,
+            script: vec!["First.".into(), "Second.".into()],
+            ..MockConfig::default()
+        }
+    }
+
+    #[test]
+    fn script_advances_per_user_turn() {
+        let config = script_config();
+        let one = vec![json!({ "role": "user", "content": "hi" })];
+        let three = vec![
+            json!({ "role": "user", "content": "hi" }),
+            json!({ "role": "assistant", "content": "First." }),
+            json!({ "role": "user", "content": "again" }),
+            json!({ "role": "assistant", "content": "Second." }),
+            json!({ "role": "user", "content": "and again" }),
+        ];
+        assert_eq!(reply_text(&config, &one), "First.");
+        assert_eq!(reply_text(&config, &three), "First.");
+
+        let echo = MockConfig::default();
+        assert_eq!(reply_text(&echo, &three), "and again");
+    }
+
+    #[test]
+    fn required_tool_choice_yields_placeholder_call() {
+        let body = json!({
+            "messages": [{ "role": "user", "content": "remember this" }],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "create_memory",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "text": { "type": "string" },
+                            "category": { "type": "string", "enum": ["fact", "event"] },
+                            "important": { "type": "boolean" }
+                        },
+                        "required": ["text", "category"]
+                    }
+                }
+            }],
+            "tool_choice": "required"
+        });
+        let reply = compose_reply(&MockConfig::default(), &body, &[]);
+        assert!(reply.content.is_empty());
+        let arguments: Value = serde_json::from_str(
+            reply.tool_calls[0]["function"]["arguments"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            arguments,
+            json!({ "text": "mock text", "category": "fact" })
+        );
+    }
+
+    #[test]
+    fn frames_decode_into_normalized_events() {
+        let config = MockConfig {
+            reasoning: true,
+            ..MockConfig::default()
+        };
+        let body = json!({
+            "messages": [{ "role": "user", "content": "hello there" }],
+            "reasoning": true,
+            "mock": config,
+        });
+        let reply = compose_reply(&config, &body, &[]);
+        let mut decoder = SseDecoder::new();
+        let events: Vec<NormalizedEvent> = sse_frames(&reply)
+            .iter()
+            .flat_map(|frame| decoder.feed(frame, Some("mock")))
+            .collect();
+
+        let text: String = events
+            .iter()
+            .filter_map(|event| match event {
+                NormalizedEvent::Delta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "hello there");
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, NormalizedEvent::Reasoning { .. })));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, NormalizedEvent::Usage { .. })));
+        assert!(matches!(events.last(), Some(NormalizedEvent::Done)));
+    }
+
+    #[test]
+    fn client_stops_cut_the_reply() {
+        let body = json!({
+            "messages": [{ "role": "user", "content": "hello there, shivers down my spine" }],
+        });
+        let stops = vec!["shivers down".to_string(), "\nUser:".to_string()];
+        let reply = compose_reply(&MockConfig::default(), &body, &stops);
+        assert_eq!(reply.content, "hello there, ");
+        assert_eq!(
+            completion_body(&reply)["choices"][0]["message"]["content"],
+            "hello there, "
+        );
+    }
+}
@@ -4,8 +4,9 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::types::ProviderCredential;
+use super::types::{ModelCapabilities, ProviderCredential};
 use crate::chat_manager::tooling::ToolConfig;
+use output_controls::OutputShape;
 
 pub trait ProviderAdapter {
     fn endpoint(&self, base_url: &str) -> String;
@@ -64,6 +65,11 @@ pub trait ProviderAdapter {
         reasoning_budget: Option<u32>,
     ) -> Value;
 
+    /// Where stop sequences, logit bias and banned strings go in `body()`.
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::OPENAI
+    }
+
     /// Endpoint to list models. Default implements OpenAI standard conventions.
     fn list_models_endpoint(&self, base_url: &str) -> String {
         let base = base_url.trim_end_matches('/');
@@ -101,6 +107,7 @@ pub trait ProviderAdapter {
                         context_length: item.get("context_length").and_then(|c| c.as_u64()),
                         input_price,
                         output_price,
+                        capabilities: capabilities::from_openai_listing(item),
                     });
                 }
             }
@@ -119,6 +126,8 @@ pub struct ModelInfo {
     // Pricing per 1M tokens or similar, strictly for display/estimation if available
     pub input_price: Option<f64>,
     pub output_price: Option<f64>,
+    #[serde(default)]
+    pub capabilities: ModelCapabilities,
 }
 
 fn value_to_f64(value: &Value) -> Option<f64> {
@@ -256,22 +265,31 @@ pub(crate) fn visible_chat_system_instruction_text(message: &Value) -> Option<St
 mod anannas;
 mod anthropic;
 mod automatic1111;
+pub(crate) mod aws_bedrock;
+mod azure_openai;
+pub(crate) mod capabilities;
 mod chutes;
+pub(crate) mod cohere;
 mod deepseek;
 mod featherless;
 mod google_gemini;
 mod groq;
 mod intenserp;
+pub(crate) mod koboldcpp;
 mod llamacpp;
 mod lmstudio;
 mod mistral;
+pub(crate) mod mock;
 mod moonshot;
 mod nanogpt;
 mod nvidia;
 mod ollama;
 mod openai;
+pub(crate) mod openai_responses;
+pub(crate) mod output_controls;
 mod qwen;
 mod stability;
+mod text_completion;
 mod xai;
 mod zai;
 
@@ -287,10 +305,14 @@ pub fn adapter_for(credential: &ProviderCredential) -> Box<dyn ProviderAdapter +
         "intenserp" => Box::new(intenserp::IntenseRpAdapter),
         "llamacpp" => Box::new(llamacpp::LlamaCppAdapter),
         "lmstudio" => Box::new(lmstudio::LMStudioAdapter),
+        "koboldcpp" => Box::new(koboldcpp::KoboldCppAdapter::new(credential)),
+        "mock" => Box::new(mock::MockAdapter::new(credential)),
+        "text-completion" => Box::new(text_completion::TextCompletionAdapter::new(credential)),
         "automatic1111" => Box::new(automatic1111::Automatic1111Adapter),
         "chutes" | "chutes.ai" => Box::new(chutes::ChutesAdapter),
         "anthropic" => Box::new(anthropic::AnthropicAdapter),
         "mistral" => Box::new(mistral::MistralAdapter),
+        "cohere" => Box::new(cohere::CohereAdapter),
         "groq" => Box::new(groq::GroqAdapter),
         "deepseek" => Box::new(deepseek::DeepSeekAdapter),
         "nanogpt" => Box::new(nanogpt::NanoGPTAdapter),
@@ -303,6 +325,11 @@ pub fn adapter_for(credential: &ProviderCredential) -> Box<dyn ProviderAdapter +
         "nvidia" | "nvidia-nim" => Box::new(nvidia::NvidiaAdapter),
         "qwen" => Box::new(qwen::QwenAdapter),
         "stability" => Box::new(stability::StabilityAdapter),
+        "openai" if openai_responses::is_responses_credential(credential) => {
+            Box::new(openai_responses::OpenAIResponsesAdapter::new(credential))
+        }
+        "azure-openai" => Box::new(azure_openai::AzureOpenAIAdapter::new(credential)),
+        "aws-bedrock" => Box::new(aws_bedrock::AwsBedrockAdapter::new(credential)),
         "openrouter" => Box::new(openai::OpenRouterAdapter),
         "lettuce-host" => Box::new(openai::OpenAIAdapter),
         "lettuce-engine" => Box::new(lettuce_engine::LettuceEngineAdapter),
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::ProviderAdapter;
+use super::{OutputShape, ProviderAdapter};
 use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
 
 pub struct OllamaAdapter;
@@ -21,6 +21,10 @@ impl ProviderAdapter for OllamaAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["options", "stop"], usize::MAX).with_banned(&["options", "stop"])
+    }
+
     fn required_auth_headers(&self) -> &'static [&'static str] {
         &["Authorization"]
     }
@@ -144,6 +148,7 @@ impl ProviderAdapter for OllamaAdapter {
                         context_length: None,
                         input_price: None,
                         output_price: None,
+                        capabilities: Default::default(),
                     });
                 }
             }
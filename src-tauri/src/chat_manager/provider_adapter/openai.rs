@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::{OpenAIChatRequest, ProviderAdapter};
+use super::{OpenAIChatRequest, OutputShape, ProviderAdapter};
 use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
 
 pub struct OpenAIAdapter;
@@ -21,6 +21,10 @@ impl ProviderAdapter for OpenAIAdapter {
         "developer".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::OPENAI.with_logit_bias()
+    }
+
     fn required_auth_headers(&self) -> &'static [&'static str] {
         &["Authorization"]
     }
@@ -134,6 +138,10 @@ impl ProviderAdapter for OpenRouterAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::OPENAI.with_logit_bias()
+    }
+
     fn required_auth_headers(&self) -> &'static [&'static str] {
         &["Authorization"]
     }
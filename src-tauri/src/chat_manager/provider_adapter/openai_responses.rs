@@ -0,0 +1,361 @@
+//! OpenAI Responses API (`/v1/responses`). Selected for `openai` credentials
+//! whose config sets `apiMode: "responses"`; it is the only OpenAI surface
+//! that returns reasoning summaries, encrypted reasoning items and built-in
+//! tools. Requests are sent with `store: false`, so the encrypted reasoning
+//! of earlier turns is kept on our side and replayed as input items.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::{extract_image_data_urls, extract_text_content, OutputShape, ProviderAdapter};
+use crate::chat_manager::tooling::{openai_tool_choice, openai_tools, ToolConfig};
+use crate::chat_manager::types::ProviderCredential;
+
+/// Internal key carrying stored reasoning items on an assistant message.
+/// Only this adapter reads it; the request builder strips it for the others.
+pub(crate) const REASONING_ITEMS_KEY: &str = "_lettuceReasoningItems";
+
+pub(crate) fn is_responses_credential(credential: &ProviderCredential) -> bool {
+    credential.provider_id == "openai"
+        && credential
+            .config
+            .as_ref()
+            .and_then(|config| config.get("apiMode"))
+            .and_then(Value::as_str)
+            == Some("responses")
+}
+
+pub struct OpenAIResponsesAdapter {
+    builtin_tools: Vec<String>,
+}
+
+impl OpenAIResponsesAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        let builtin_tools = credential
+            .config
+            .as_ref()
+            .and_then(|config| config.get("builtinTools"))
+            .and_then(Value::as_array)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::trim)
+                    .filter(|tool| !tool.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { builtin_tools }
+    }
+}
+
+impl ProviderAdapter for OpenAIResponsesAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        let trimmed = base_url.trim_end_matches('/');
+        if trimmed.ends_with("/v1") {
+            format!("{}/responses", trimmed)
+        } else {
+            format!("{}/v1/responses", trimmed)
+        }
+    }
+
+    fn system_role(&self) -> std::borrow::Cow<'static, str> {
+        "developer".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::NONE
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["Authorization"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("Authorization".into(), "Bearer <apiKey>".into());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        out.insert("Authorization".into(), format!("Bearer {}", api_key));
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        _context_length: Option<u32>,
+        should_stream: bool,
+        _frequency_penalty: Option<f64>,
+        _presence_penalty: Option<f64>,
+        _top_k: Option<u32>,
+        tool_config: Option<&ToolConfig>,
+        reasoning_enabled: bool,
+        reasoning_effort: Option<String>,
+        reasoning_budget: Option<u32>,
+    ) -> Value {
+        let mut body = Map::new();
+        body.insert("model".into(), json!(model_name));
+        body.insert("input".into(), json!(input_items(messages_for_api)));
+        if let Some(system) = system_prompt.filter(|s| !s.trim().is_empty()) {
+            body.insert("instructions".into(), json!(system));
+        }
+        body.insert("stream".into(), json!(should_stream));
+        body.insert(
+            "max_output_tokens".into(),
+            json!(max_tokens + reasoning_budget.unwrap_or(0)),
+        );
+        body.insert("store".into(), json!(false));
+
+        if reasoning_enabled {
+            // Reasoning models reject sampling parameters on this endpoint.
+            let mut reasoning = Map::new();
+            if let Some(effort) = reasoning_effort {
+                reasoning.insert("effort".into(), json!(effort));
+            }
+            reasoning.insert("summary".into(), json!("auto"));
+            body.insert("reasoning".into(), Value::Object(reasoning));
+            body.insert("include".into(), json!(["reasoning.encrypted_content"]));
+        } else {
+            if let Some(v) = temperature {
+                body.insert("temperature".into(), json!(v));
+            }
+            if let Some(v) = top_p {
+                body.insert("top_p".into(), json!(v));
+            }
+        }
+
+        let mut tools: Vec<Value> = tool_config
+            .and_then(openai_tools)
+            .unwrap_or_default()
+            .into_iter()
+            .map(flatten_function_tool)
+            .collect();
+        let has_function_tools = !tools.is_empty();
+        tools.extend(
+            self.builtin_tools
+                .iter()
+                .map(|tool| json!({ "type": tool })),
+        );
+        if !tools.is_empty() {
+            body.insert("tools".into(), json!(tools));
+        }
+        if has_function_tools {
+            if let Some(choice) = tool_config
+                .and_then(|cfg| openai_tool_choice(cfg.choice.as_ref()))
+                .map(flatten_function_tool)
+            {
+                body.insert("tool_choice".into(), choice);
+            }
+        }
+
+        Value::Object(body)
+    }
+}
+
+/// Chat Completions nests function tools under `function`; Responses expects
+/// `name`, `description` and `parameters` next to `type`.
+fn flatten_function_tool(value: Value) -> Value {
+    let Value::Object(mut obj) = value else {
+        return value;
+    };
+    if let Some(Value::Object(function)) = obj.remove("function") {
+        obj.extend(function);
+    }
+    Value::Object(obj)
+}
+
+/// Maps OpenAI-style chat messages onto Responses input items. Stored
+/// reasoning items of an assistant turn are replayed right before it.
+fn input_items(messages: &[Value]) -> Vec<Value> {
+    let mut items = Vec::with_capacity(messages.len());
+    for message in messages {
+        let role = match message.get("role").and_then(Value::as_str) {
+            Some("assistant") => "assistant",
+            Some("system") | Some("developer") => "developer",
+            _ => "user",
+        };
+
+        if role == "assistant" {
+            if let Some(reasoning) = message.get(REASONING_ITEMS_KEY).and_then(Value::as_array) {
+                items.extend(reasoning.iter().cloned());
+            }
+        }
+
+        let text = extract_text_content(message.get("content")).unwrap_or_default();
+        let images = if role == "user" {
+            extract_image_data_urls(message.get("content"))
+        } else {
+            Vec::new()
+        };
+
+        if images.is_empty() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            items.push(json!({ "role": role, "content": text }));
+            continue;
+        }
+
+        let mut content = Vec::with_capacity(images.len() + 1);
+        if !text.trim().is_empty() {
+            content.push(json!({ "type": "input_text", "text": text }));
+        }
+        for url in images {
+            content.push(json!({ "type": "input_image", "image_url": url }));
+        }
+        items.push(json!({ "role": role, "content": content }));
+    }
+    items
+}
+
+/// Reasoning output items worth replaying: only those carrying
+/// `encrypted_content`, since nothing is stored server-side.
+pub(crate) fn reasoning_items_from_response(data: &Value) -> Vec<Value> {
+    let mut items = Vec::new();
+    match data {
+        Value::String(raw) => {
+            for line in raw.lines() {
+                let Some(payload) = line.trim().strip_prefix("data:") else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<Value>(payload.trim()) else {
+                    continue;
+                };
+                if event.get("type").and_then(Value::as_str) == Some("response.output_item.done") {
+                    if let Some(item) = event.get("item") {
+                        push_reasoning_item(item, &mut items);
+                    }
+                }
+            }
+        }
+        Value::Object(map) => {
+            if let Some(output) = map.get("output").and_then(Value::as_array) {
+                for item in output {
+                    push_reasoning_item(item, &mut items);
+                }
+            }
+        }
+        _ => {}
+    }
+    items
+}
+
+fn push_reasoning_item(item: &Value, out: &mut Vec<Value>) {
+    if item.get("type").and_then(Value::as_str) != Some("reasoning") {
+        return;
+    }
+    let has_encrypted = item
+        .get("encrypted_content")
+        .and_then(Value::as_str)
+        .is_some_and(|content| !content.is_empty());
+    if !has_encrypted {
+        return;
+    }
+    let mut item = item.clone();
+    if let Some(obj) = item.as_object_mut() {
+        // Output-only field; the API rejects it on input items.
+        obj.remove("status");
+    }
+    out.push(item);
+}
+
+/// Drops the internal reasoning-items key from messages bound for any other
+/// adapter.
+pub(crate) fn strip_reasoning_items(messages: &[Value]) -> Vec<Value> {
+    messages
+        .iter()
+        .map(|message| {
+            let mut message = message.clone();
+            if let Some(obj) = message.as_object_mut() {
+                obj.remove(REASONING_ITEMS_KEY);
+            }
+            message
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_items_replay_reasoning_before_assistant_turns() {
+        let messages = vec![
+            json!({ "role": "developer", "content": "Stay in character." }),
+            json!({ "role": "user", "content": "Hi" }),
+            json!({
+                "role": "assistant",
+                "content": "Hello!",
+                REASONING_ITEMS_KEY: [{ "type": "reasoning", "id": "rs_1", "encrypted_content": "abc" }]
+            }),
+            json!({ "role": "user", "content": [
+                { "type": "text", "text": "Look" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,AAA" } }
+            ]}),
+        ];
+
+        let items = input_items(&messages);
+        assert_eq!(items.len(), 5);
+        assert_eq!(
+            items[0],
+            json!({ "role": "developer", "content": "Stay in character." })
+        );
+        assert_eq!(items[2]["type"], "reasoning");
+        assert_eq!(
+            items[3],
+            json!({ "role": "assistant", "content": "Hello!" })
+        );
+        assert_eq!(items[4]["content"][1]["type"], "input_image");
+    }
+
+    #[test]
+    fn reasoning_items_are_read_from_stream_and_body() {
+        let raw = concat!(
+            "event: response.output_item.done\n",
+            "data: {\"type\":\"response.output_item.done\",\"item\":{\"type\":\"reasoning\",",
+            "\"id\":\"rs_1\",\"status\":\"completed\",\"summary\":[],\"encrypted_content\":\"xyz\"}}\n\n",
+            "event: response.output_item.done\n",
+            "data: {\"type\":\"response.output_item.done\",\"item\":{\"type\":\"message\",\"id\":\"msg_1\"}}\n\n",
+        );
+        let items = reasoning_items_from_response(&Value::String(raw.to_string()));
+        assert_eq!(
+            items,
+            vec![
+                json!({ "type": "reasoning", "id": "rs_1", "summary": [], "encrypted_content": "xyz" })
+            ]
+        );
+
+        let body = json!({ "output": [
+            { "type": "reasoning", "id": "rs_2", "summary": [] },
+            { "type": "reasoning", "id": "rs_3", "summary": [], "encrypted_content": "e" }
+        ]});
+        let items = reasoning_items_from_response(&body);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], "rs_3");
+    }
+}
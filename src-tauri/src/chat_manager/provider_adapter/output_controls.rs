@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Extra body field carrying [`OutputControls`] from `provider_fields` to the
+/// request builder. It never reaches the provider.
+pub(crate) const OUTPUT_CONTROLS_KEY: &str = "outputControls";
+
+/// Stop sequences, token biases and banned strings resolved for one request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputControls {
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    /// Bias per token id.
+    #[serde(default)]
+    pub logit_bias: BTreeMap<u32, f64>,
+    #[serde(default)]
+    pub banned_strings: Vec<String>,
+}
+
+impl OutputControls {
+    pub fn is_empty(&self) -> bool {
+        self.stop_sequences.is_empty()
+            && self.logit_bias.is_empty()
+            && self.banned_strings.is_empty()
+    }
+}
+
+/// Where a provider takes output controls in its request body.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputShape {
+    /// Path of the stop list; empty when the provider has none.
+    pub stop_path: &'static [&'static str],
+    /// Stop strings the provider accepts, counting ones the body already has.
+    pub max_stops: usize,
+    /// Whether the body takes an OpenAI-style `logit_bias` map.
+    pub logit_bias: bool,
+    /// Path of a native banned phrase list. Local engines point this at
+    /// their stop list, which cuts the reply the same way the client would.
+    pub banned_path: &'static [&'static str],
+}
+
+impl OutputShape {
+    pub const OPENAI: Self = Self::stops(&["stop"], 4);
+    pub const NONE: Self = Self::stops(&[], 0);
+
+    pub const fn stops(stop_path: &'static [&'static str], max_stops: usize) -> Self {
+        Self {
+            stop_path,
+            max_stops,
+            logit_bias: false,
+            banned_path: &[],
+        }
+    }
+
+    pub const fn with_logit_bias(self) -> Self {
+        Self {
+            logit_bias: true,
+            ..self
+        }
+    }
+
+    pub const fn with_banned(self, banned_path: &'static [&'static str]) -> Self {
+        Self {
+            banned_path,
+            ..self
+        }
+    }
+}
+
+/// Writes `controls` into `body`. Returns the stop and banned strings the
+/// provider could not take, which the stream then enforces on the client.
+pub(crate) fn apply(
+    body: &mut Value,
+    shape: OutputShape,
+    controls: &OutputControls,
+) -> Vec<String> {
+    let mut client_stops: Vec<String> = Vec::new();
+
+    if shape.stop_path.is_empty() {
+        client_stops.extend(controls.stop_sequences.iter().cloned());
+    } else if !controls.stop_sequences.is_empty() {
+        if let Some(list) = array_at(body, shape.stop_path) {
+            for stop in &controls.stop_sequences {
+                if list
+                    .iter()
+                    .any(|existing| existing.as_str() == Some(stop.as_str()))
+                {
+                    continue;
+                }
+                if list.len() < shape.max_stops {
+                    list.push(Value::String(stop.clone()));
+                } else {
+                    client_stops.push(stop.clone());
+                }
+            }
+        }
+    }
+
+    if shape.logit_bias && !controls.logit_bias.is_empty() {
+        if let Some(map) = body.as_object_mut() {
+            let bias = map
+                .entry("logit_bias")
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Some(bias) = bias.as_object_mut() {
+                for (token, value) in &controls.logit_bias {
+                    bias.insert(token.to_string(), Value::from(*value));
+                }
+            }
+        }
+    }
+
+    if shape.banned_path.is_empty() {
+        client_stops.extend(controls.banned_strings.iter().cloned());
+    } else if !controls.banned_strings.is_empty() {
+        if let Some(list) = array_at(body, shape.banned_path) {
+            for banned in &controls.banned_strings {
+                if !list
+                    .iter()
+                    .any(|existing| existing.as_str() == Some(banned.as_str()))
+                {
+                    list.push(Value::String(banned.clone()));
+                }
+            }
+        }
+    }
+
+    client_stops
+}
+
+/// The array at `path`, created when missing. A lone string becomes a
+/// one-element list since OpenAI-style `stop` accepts either.
+fn array_at<'a>(body: &'a mut Value, path: &[&str]) -> Option<&'a mut Vec<Value>> {
+    let (leaf, parents) = path.split_last()?;
+    let mut current = body;
+    for key in parents {
+        current = current
+            .as_object_mut()?
+            .entry(*key)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    let slot = current
+        .as_object_mut()?
+        .entry(*leaf)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::String(single) = slot {
+        let single = std::mem::take(single);
+        *slot = Value::Array(vec![Value::String(single)]);
+    }
+    if !slot.is_array() {
+        *slot = Value::Array(Vec::new());
+    }
+    slot.as_array_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn controls() -> OutputControls {
+        OutputControls {
+            stop_sequences: vec!["\nUser:".into(), "###".into()],
+            logit_bias: BTreeMap::from([(1234, -100.0)]),
+            banned_strings: vec!["shivers down".into()],
+        }
+    }
+
+    #[test]
+    fn overflowing_stops_and_banned_strings_fall_back_to_the_client() {
+        let mut body = json!({ "stop": "</s>" });
+        let client = apply(&mut body, OutputShape::stops(&["stop"], 2), &controls());
+        assert_eq!(body["stop"], json!(["</s>", "\nUser:"]));
+        assert!(body.get("logit_bias").is_none());
+        assert_eq!(client, vec!["###".to_string(), "shivers down".to_string()]);
+    }
+
+    #[test]
+    fn nested_paths_and_native_bans_stay_in_the_body() {
+        let mut body = json!({ "options": { "num_ctx": 4096 } });
+        let shape = OutputShape::stops(&["options", "stop"], usize::MAX)
+            .with_logit_bias()
+            .with_banned(&["options", "stop"]);
+        let client = apply(&mut body, shape, &controls());
+        assert!(client.is_empty());
+        assert_eq!(
+            body["options"]["stop"],
+            json!(["\nUser:", "###", "shivers down"])
+        );
+        assert_eq!(body["logit_bias"], json!({ "1234": -100.0 }));
+    }
+
+    #[test]
+    fn bias_only_controls_leave_stop_lists_alone() {
+        let controls = OutputControls {
+            logit_bias: BTreeMap::from([(1234, -100.0)]),
+            ..OutputControls::default()
+        };
+        let mut body = json!({ "options": { "num_ctx": 4096 } });
+        let shape = OutputShape::stops(&["options", "stop"], usize::MAX)
+            .with_logit_bias()
+            .with_banned(&["options", "stop"]);
+        assert!(apply(&mut body, shape, &controls).is_empty());
+        assert_eq!(
+            body,
+            json!({ "options": { "num_ctx": 4096 }, "logit_bias": { "1234": -100.0 } })
+        );
+
+        let mut body = json!({ "model": "o3-mini" });
+        assert!(apply(&mut body, OutputShape::OPENAI, &controls).is_empty());
+        assert!(body.get("stop").is_none());
+    }
+}
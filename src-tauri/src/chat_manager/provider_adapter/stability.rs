@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::{json, Value};
 
-use super::ProviderAdapter;
+use super::{OutputShape, ProviderAdapter};
 use crate::chat_manager::tooling::ToolConfig;
 
 pub struct StabilityAdapter;
@@ -19,6 +19,10 @@ impl ProviderAdapter for StabilityAdapter {
         "system".into()
     }
 
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::NONE
+    }
+
     fn supports_stream(&self) -> bool {
         false
     }
@@ -0,0 +1,147 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::{OutputShape, ProviderAdapter};
+use crate::chat_manager::prompting::instruct_templates::{self, InstructFormat};
+use crate::chat_manager::tooling::ToolConfig;
+use crate::chat_manager::types::ProviderCredential;
+
+/// Raw-prompt adapter for OpenAI-compatible `/v1/completions` backends. The
+/// chat messages are rendered through the credential's instruct template.
+pub struct TextCompletionAdapter {
+    credential_config: Option<Value>,
+    format: InstructFormat,
+}
+
+impl TextCompletionAdapter {
+    pub fn new(credential: &ProviderCredential) -> Self {
+        Self {
+            credential_config: credential.config.clone(),
+            format: instruct_templates::from_config(credential.config.as_ref()),
+        }
+    }
+
+    fn config_value(&self, key: &str) -> Option<String> {
+        self.credential_config
+            .as_ref()
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+impl ProviderAdapter for TextCompletionAdapter {
+    fn endpoint(&self, base_url: &str) -> String {
+        let trimmed = base_url.trim_end_matches('/');
+        if let Some(path) = self.config_value("completionEndpoint") {
+            return format!("{}{}", trimmed, path);
+        }
+        if trimmed.ends_with("/v1") {
+            format!("{}/completions", trimmed)
+        } else {
+            format!("{}/v1/completions", trimmed)
+        }
+    }
+
+    fn system_role(&self) -> Cow<'static, str> {
+        "system".into()
+    }
+
+    fn output_shape(&self) -> OutputShape {
+        OutputShape::stops(&["stop"], usize::MAX).with_logit_bias()
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn required_auth_headers(&self) -> &'static [&'static str] {
+        &["Authorization"]
+    }
+
+    fn default_headers_template(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        out.insert("Authorization".into(), "Bearer <apiKey>".into());
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out
+    }
+
+    fn headers(
+        &self,
+        api_key: &str,
+        extra: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut out: HashMap<String, String> = HashMap::new();
+        if !api_key.trim().is_empty() {
+            out.insert("Authorization".into(), format!("Bearer {}", api_key));
+        }
+        out.insert("Content-Type".into(), "application/json".into());
+        out.insert("Accept".into(), "text/event-stream".into());
+        out.entry("User-Agent".into())
+            .or_insert_with(|| "LettuceAI/0.1".into());
+        if let Some(extra) = extra {
+            for (k, v) in extra.iter() {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        out
+    }
+
+    fn body(
+        &self,
+        model_name: &str,
+        messages_for_api: &Vec<Value>,
+        system_prompt: Option<String>,
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: u32,
+        _context_length: Option<u32>,
+        should_stream: bool,
+        frequency_penalty: Option<f64>,
+        presence_penalty: Option<f64>,
+        top_k: Option<u32>,
+        _tool_config: Option<&ToolConfig>,
+        _reasoning_enabled: bool,
+        _reasoning_effort: Option<String>,
+        _reasoning_budget: Option<u32>,
+    ) -> Value {
+        let prompt = match system_prompt.filter(|s| !s.trim().is_empty()) {
+            Some(system) => {
+                let mut messages = Vec::with_capacity(messages_for_api.len() + 1);
+                messages.push(json!({ "role": "system", "content": system }));
+                messages.extend(messages_for_api.iter().cloned());
+                self.format.render(&messages)
+            }
+            None => self.format.render(messages_for_api),
+        };
+
+        let mut body = Map::new();
+        body.insert("model".into(), json!(model_name));
+        body.insert("prompt".into(), json!(prompt));
+        body.insert("stream".into(), json!(should_stream));
+        body.insert("max_tokens".into(), json!(max_tokens));
+        if let Some(v) = temperature {
+            body.insert("temperature".into(), json!(v));
+        }
+        if let Some(v) = top_p {
+            body.insert("top_p".into(), json!(v));
+        }
+        if let Some(v) = top_k {
+            body.insert("top_k".into(), json!(v));
+        }
+        if let Some(v) = frequency_penalty {
+            body.insert("frequency_penalty".into(), json!(v));
+        }
+        if let Some(v) = presence_penalty {
+            body.insert("presence_penalty".into(), json!(v));
+        }
+        if !self.format.stop.is_empty() {
+            body.insert("stop".into(), json!(self.format.stop));
+        }
+        Value::Object(body)
+    }
+}
@@ -266,6 +266,7 @@ pub async fn chat_generate_user_reply(
         stream: Some(built.stream),
         request_id: built.request_id.clone(),
         provider_id: Some(credential.provider_id.clone()),
+        credential_id: Some(credential.id.clone()),
     };
 
     let api_response = api_request(app.clone(), api_request_payload).await?;
@@ -194,12 +194,23 @@ pub fn require_api_key(
     if credential.provider_id == "llamacpp" || credential.provider_id == "ollama" {
         return Ok(credential.api_key.clone().unwrap_or_default());
     }
+    // Credentials with several keys rotate through their key pool
+    if let Some(key) = crate::transport::key_pool::select_key(app, credential) {
+        return Ok(key);
+    }
     // Prefer inline api_key on the credential
     if let Some(ref key) = credential.api_key {
         if !key.is_empty() {
             return Ok(key.clone());
         }
     }
+    // Self-hosted completion servers usually run without auth
+    if matches!(
+        credential.provider_id.as_str(),
+        "text-completion" | "koboldcpp" | "mock"
+    ) {
+        return Ok(String::new());
+    }
     log_error(
         app,
         log_scope,
@@ -3,7 +3,7 @@ use serde_json::Value;
 use crate::chat_manager::thinking::{
     normalize_thinking_content, ThinkingSplit, ThinkingTagStreamParser,
 };
-use crate::chat_manager::tooling::ToolCall;
+use crate::chat_manager::tooling::{arguments_value_from_str, ToolCall};
 
 use super::tooling::parse_tool_calls;
 use super::types::{NormalizedEvent, UsageSummary};
@@ -62,6 +62,40 @@ fn accumulate_thinking_split_from_sse(raw: &str, provider_id: Option<&str>) -> T
     split
 }
 
+/// Drops the frames of a raw stream that carry reply text or reasoning so the
+/// reply can be rebuilt. Usage reported on a dropped frame is kept.
+pub fn strip_text_frames(raw: &str) -> String {
+    let mut out = String::new();
+    for line in raw.lines() {
+        let frame = line
+            .trim()
+            .strip_prefix("data:")
+            .and_then(|payload| serde_json::from_str::<Value>(payload.trim()).ok());
+        let Some(v) = frame else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+        if extract_text_from_value(&v).is_none() && extract_reasoning_from_value(&v).is_none() {
+            out.push_str(line);
+            out.push('\n');
+        } else if let Some(usage) = usage_frame(&v) {
+            out.push_str(&usage);
+        }
+    }
+    out
+}
+
+/// A `data:` line holding only the usage block of `v`, if it has one.
+pub fn usage_frame(v: &Value) -> Option<String> {
+    ["usage", "usageMetadata"].iter().find_map(|key| {
+        let usage = v.get(*key)?;
+        let mut frame = serde_json::Map::new();
+        frame.insert(key.to_string(), usage.clone());
+        Some(format!("data: {}\n", Value::Object(frame)))
+    })
+}
+
 pub fn accumulate_image_data_urls_from_sse(raw: &str) -> Vec<String> {
     let mut out = Vec::new();
 
@@ -86,6 +120,7 @@ pub fn accumulate_image_data_urls_from_sse(raw: &str) -> Vec<String> {
 
 pub fn accumulate_tool_calls_from_sse(raw: &str, provider_id: &str) -> Vec<ToolCall> {
     let mut out: Vec<ToolCall> = Vec::new();
+    let mut cohere_calls = CohereToolCalls::default();
 
     for line in raw.lines() {
         let l = line.trim();
@@ -102,6 +137,11 @@ pub fn accumulate_tool_calls_from_sse(raw: &str, provider_id: &str) -> Vec<ToolC
             continue;
         };
 
+        if let Some(call) = cohere_calls.observe(&v) {
+            out.push(call);
+            continue;
+        }
+
         let calls = parse_tool_calls(provider_id, &v);
         for call in calls {
             if let Some(existing) = out.iter_mut().find(|c| c.id == call.id) {
@@ -152,6 +192,7 @@ pub fn usage_from_sse(raw: &str) -> Option<UsageSummary> {
 pub struct SseDecoder {
     buffer: String,
     thinking_parser: ThinkingTagStreamParser,
+    cohere_tool_calls: CohereToolCalls,
 }
 
 impl SseDecoder {
@@ -159,6 +200,7 @@ impl SseDecoder {
         Self {
             buffer: String::new(),
             thinking_parser: ThinkingTagStreamParser::default(),
+            cohere_tool_calls: CohereToolCalls::default(),
         }
     }
 
@@ -230,9 +272,29 @@ impl SseDecoder {
                 continue;
             }
 
-            let is_done = v.get("done").and_then(|d| d.as_bool()).unwrap_or(false);
+            if let Some(envelope) = extract_responses_error(&v) {
+                events.push(NormalizedEvent::Error { envelope });
+                continue;
+            }
+
+            if let Some(envelope) = extract_converse_error(&v) {
+                events.push(NormalizedEvent::Error { envelope });
+                continue;
+            }
+
+            let is_done = v.get("done").and_then(|d| d.as_bool()).unwrap_or(false)
+                || matches!(
+                    responses_event_type(&v),
+                    Some("response.completed" | "response.incomplete")
+                )
+                || converse_event_type(&v) == Some("metadata")
+                || cohere_event_type(&v) == Some("message-end");
 
             // 2. Tool calls – HARD FILTER POINT
+            if let Some(call) = self.cohere_tool_calls.observe(&v) {
+                events.push(NormalizedEvent::ToolCall { calls: vec![call] });
+                continue;
+            }
             if let Some(provider) = provider_id {
                 let calls = parse_tool_calls(provider, &v);
                 if !calls.is_empty() {
@@ -290,7 +352,166 @@ impl SseDecoder {
     }
 }
 
+/// Event type of an OpenAI Responses API stream event (`response.*`).
+fn responses_event_type(v: &Value) -> Option<&str> {
+    v.get("type")
+        .and_then(|t| t.as_str())
+        .filter(|t| t.starts_with("response."))
+}
+
+fn extract_responses_error(v: &Value) -> Option<super::types::ErrorEnvelope> {
+    let (error, status) = match v.get("type").and_then(|t| t.as_str()) {
+        Some("error") => (v, None),
+        Some("response.failed") => (v.get("response")?.get("error")?, Some(500)),
+        _ => return None,
+    };
+    let message = error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("Response failed")
+        .to_string();
+    Some(super::types::ErrorEnvelope {
+        code: error
+            .get("code")
+            .and_then(|c| c.as_str())
+            .map(|c| c.to_string()),
+        message,
+        provider_id: Some("openai".to_string()),
+        request_id: None,
+        retryable: None,
+        status,
+    })
+}
+
+/// Event type of a Bedrock ConverseStream event, as tagged by the
+/// event-stream transcoder.
+fn converse_event_type(v: &Value) -> Option<&str> {
+    v.get("converseEvent").and_then(|t| t.as_str())
+}
+
+/// Event type of a Cohere v2 chat stream event.
+fn cohere_event_type(v: &Value) -> Option<&str> {
+    v.get("type").and_then(|t| t.as_str()).filter(|t| {
+        matches!(
+            *t,
+            "message-start"
+                | "content-start"
+                | "content-delta"
+                | "content-end"
+                | "tool-plan-delta"
+                | "tool-call-start"
+                | "tool-call-delta"
+                | "tool-call-end"
+                | "citation-start"
+                | "citation-end"
+                | "message-end"
+        )
+    })
+}
+
+/// Cohere streams a tool call as a start event, argument deltas and an end
+/// event; the call is reported once, complete, when it ends.
+#[derive(Default)]
+struct CohereToolCalls {
+    pending: Option<(String, String, String)>,
+}
+
+impl CohereToolCalls {
+    fn observe(&mut self, v: &Value) -> Option<ToolCall> {
+        let call = v.pointer("/delta/message/tool_calls");
+        let arguments = call
+            .and_then(|c| c.pointer("/function/arguments"))
+            .and_then(|a| a.as_str())
+            .unwrap_or_default();
+        match cohere_event_type(v)? {
+            "tool-call-start" => {
+                let call = call?;
+                self.pending = Some((
+                    call.get("id")
+                        .and_then(|id| id.as_str())
+                        .unwrap_or("tool_call")
+                        .to_string(),
+                    call.pointer("/function/name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    arguments.to_string(),
+                ));
+                None
+            }
+            "tool-call-delta" => {
+                if let Some(pending) = self.pending.as_mut() {
+                    pending.2.push_str(arguments);
+                }
+                None
+            }
+            "tool-call-end" => {
+                let (id, name, raw) = self.pending.take()?;
+                let (arguments, raw_arguments) = arguments_value_from_str(&raw);
+                Some(ToolCall {
+                    id,
+                    name,
+                    arguments,
+                    raw_arguments,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn extract_converse_error(v: &Value) -> Option<super::types::ErrorEnvelope> {
+    if converse_event_type(v) != Some("exception") {
+        return None;
+    }
+    let kind = v.get("exceptionType").and_then(|t| t.as_str());
+    Some(super::types::ErrorEnvelope {
+        code: kind.map(|k| k.to_string()),
+        message: v
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Bedrock stream failed")
+            .to_string(),
+        provider_id: Some("aws-bedrock".to_string()),
+        request_id: None,
+        retryable: Some(matches!(
+            kind,
+            Some("throttlingException" | "serviceUnavailableException")
+        )),
+        status: None,
+    })
+}
+
 fn extract_text_from_value(v: &Value) -> Option<String> {
+    if let Some(event) = cohere_event_type(v) {
+        if event != "content-delta" {
+            return None;
+        }
+        return v
+            .pointer("/delta/message/content/text")
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string());
+    }
+    if let Some(event) = converse_event_type(v) {
+        if event != "contentBlockDelta" {
+            return None;
+        }
+        return v
+            .get("delta")
+            .and_then(|d| d.get("text"))
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string());
+    }
+    // Responses API: only deltas; the `.done` events repeat the full text.
+    if let Some(event) = responses_event_type(v) {
+        return match event {
+            "response.output_text.delta" => v
+                .get("delta")
+                .and_then(|d| d.as_str())
+                .map(|s| s.to_string()),
+            _ => None,
+        };
+    }
     if let Some(s) = v
         .get("choices")
         .and_then(|c| c.get(0))
@@ -309,6 +530,19 @@ fn extract_text_from_value(v: &Value) -> Option<String> {
     {
         return Some(s.to_string());
     }
+    // Text completions (/v1/completions): choices[0].text
+    if let Some(s) = v
+        .get("choices")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+    {
+        return Some(s.to_string());
+    }
+    // KoboldCpp streaming (/api/extra/generate/stream): token
+    if let Some(s) = v.get("token").and_then(|t| t.as_str()) {
+        return Some(s.to_string());
+    }
     // Anthropic Messages API streaming: content_block_delta -> delta -> text
     if v.get("type").and_then(|t| t.as_str()) == Some("content_block_delta") {
         if let Some(s) = v
@@ -366,6 +600,45 @@ fn extract_text_from_value(v: &Value) -> Option<String> {
 /// Extract reasoning tokens from thinking models
 /// The reasoning content is found in choices[0].delta.reasoning or choices[0].delta.reasoning_content
 fn extract_reasoning_from_value(v: &Value) -> Option<String> {
+    // Cohere: thinking content, and the plan written before calling tools.
+    if let Some(event) = cohere_event_type(v) {
+        let pointer = match event {
+            "content-delta" => "/delta/message/content/thinking",
+            "tool-plan-delta" => "/delta/message/tool_plan",
+            _ => return None,
+        };
+        return v
+            .pointer(pointer)
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string());
+    }
+    if let Some(event) = converse_event_type(v) {
+        if event != "contentBlockDelta" {
+            return None;
+        }
+        return v
+            .get("delta")
+            .and_then(|d| d.get("reasoningContent"))
+            .and_then(|r| r.get("text"))
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string());
+    }
+    // Responses API: summary deltas (or raw reasoning text where exposed).
+    if let Some(event) = responses_event_type(v) {
+        return match event {
+            "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => v
+                .get("delta")
+                .and_then(|d| d.as_str())
+                .map(|s| s.to_string()),
+            // Each summary part is its own paragraph.
+            "response.reasoning_summary_part.added"
+                if v.get("summary_index").and_then(|i| i.as_u64()).unwrap_or(0) > 0 =>
+            {
+                Some("\n\n".to_string())
+            }
+            _ => None,
+        };
+    }
     // OpenAI/OpenRouter style: choices[0].delta.reasoning
     if let Some(s) = v
         .get("choices")
@@ -490,6 +763,43 @@ mod tests {
             other => panic!("unexpected second event: {other:?}"),
         }
     }
+
+    #[test]
+    fn decodes_cohere_v2_stream() {
+        let mut decoder = SseDecoder::new();
+        let stream = concat!(
+            "event: content-delta\n",
+            "data: {\"type\":\"content-delta\",\"index\":0,\"delta\":{\"message\":{\"content\":{\"text\":\"Hello\"}}}}\n\n",
+            "event: tool-call-start\n",
+            "data: {\"type\":\"tool-call-start\",\"index\":0,\"delta\":{\"message\":{\"tool_calls\":{\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"roll\",\"arguments\":\"\"}}}}}\n\n",
+            "data: {\"type\":\"tool-call-delta\",\"index\":0,\"delta\":{\"message\":{\"tool_calls\":{\"function\":{\"arguments\":\"{\\\"sides\\\":\"}}}}}\n\n",
+            "data: {\"type\":\"tool-call-delta\",\"index\":0,\"delta\":{\"message\":{\"tool_calls\":{\"function\":{\"arguments\":\"20}\"}}}}}\n\n",
+            "data: {\"type\":\"tool-call-end\",\"index\":0}\n\n",
+            "data: {\"type\":\"message-end\",\"delta\":{\"finish_reason\":\"TOOL_CALL\",\"usage\":{\"billed_units\":{\"input_tokens\":9,\"output_tokens\":4},\"tokens\":{\"input_tokens\":120,\"output_tokens\":6}}}}\n\n",
+        );
+
+        let events = decoder.feed(stream, Some("cohere"));
+        assert!(matches!(&events[0], NormalizedEvent::Delta { text } if text == "Hello"));
+        match &events[1] {
+            NormalizedEvent::ToolCall { calls } => {
+                assert_eq!(calls.len(), 1);
+                assert_eq!(calls[0].id, "call_1");
+                assert_eq!(calls[0].name, "roll");
+                assert_eq!(calls[0].arguments, serde_json::json!({ "sides": 20 }));
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match &events[2] {
+            NormalizedEvent::Usage { usage } => {
+                assert_eq!(usage.prompt_tokens, Some(120));
+                assert_eq!(usage.completion_tokens, Some(6));
+                assert_eq!(usage.finish_reason.as_deref(), Some("TOOL_CALL"));
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(matches!(events[3], NormalizedEvent::Done));
+        assert_eq!(events.len(), 4);
+    }
 }
 
 fn extract_image_data_urls_from_value(v: &Value, out: &mut Vec<String>) {
@@ -525,8 +835,22 @@ fn extract_image_data_urls_from_value(v: &Value, out: &mut Vec<String>) {
 }
 
 pub fn usage_from_value(v: &Value) -> Option<UsageSummary> {
+    // Responses API: usage arrives on the final event, inside `response`.
+    if matches!(
+        responses_event_type(v),
+        Some("response.completed" | "response.incomplete")
+    ) {
+        return v.get("response").and_then(usage_from_value);
+    }
+    // Cohere: usage and finish reason arrive in the `message-end` delta.
+    if cohere_event_type(v) == Some("message-end") {
+        return v.get("delta").and_then(usage_from_value);
+    }
+
     // Support both snake_case "usage" (OpenAI) and camelCase "usageMetadata" (Gemini)
     let u = v.get("usage").or_else(|| v.get("usageMetadata"));
+    // Cohere nests the counts under `tokens` (and `billed_units`).
+    let u = u.map(|u| u.get("tokens").filter(|t| t.is_object()).unwrap_or(u));
 
     let (
         prompt_tokens,
@@ -574,6 +898,7 @@ pub fn usage_from_value(v: &Value) -> Option<UsageSummary> {
         )
         .or_else(|| {
             u.get("completion_tokens_details")
+                .or_else(|| u.get("output_tokens_details"))
                 .and_then(|d| take_first(d, &["reasoning_tokens", "reasoningTokens"]))
         });
         let image_tokens = take_first(u, &["image_tokens", "imageTokens"]).or_else(|| {
@@ -587,15 +912,18 @@ pub fn usage_from_value(v: &Value) -> Option<UsageSummary> {
                 "cachedContentTokenCount",
                 "cache_read",
                 "cacheRead",
+                "cacheReadInputTokens",
             ],
         )
         .or_else(|| {
             u.get("prompt_tokens_details")
+                .or_else(|| u.get("input_tokens_details"))
                 .and_then(|d| take_first(d, &["cached_tokens", "cachedTokens"]))
         });
-        let cache_write_tokens = u
-            .get("prompt_tokens_details")
-            .and_then(|d| take_first(d, &["cache_write_tokens", "cacheWriteTokens"]));
+        let cache_write_tokens = take_first(u, &["cacheWriteInputTokens"]).or_else(|| {
+            u.get("prompt_tokens_details")
+                .and_then(|d| take_first(d, &["cache_write_tokens", "cacheWriteTokens"]))
+        });
         let web_search_requests = u.get("server_tool_use").and_then(|d| {
             take_first(
                 d,
@@ -660,6 +988,20 @@ pub fn usage_from_value(v: &Value) -> Option<UsageSummary> {
         .or_else(|| {
             // Anthropic/SSE specific if available in the same value
             v.get("stop_reason")
+                .or_else(|| v.get("stopReason"))
+                .and_then(|r| r.as_str())
+                .map(|s| s.to_string())
+        })
+        .or_else(|| {
+            // Cohere
+            v.get("finish_reason")
+                .and_then(|r| r.as_str())
+                .map(|s| s.to_string())
+        })
+        .or_else(|| {
+            // Responses API: only incomplete responses carry a reason
+            v.get("incomplete_details")
+                .and_then(|d| d.get("reason"))
                 .and_then(|r| r.as_str())
                 .map(|s| s.to_string())
         });
@@ -0,0 +1,143 @@
+//! Client-side stop strings for providers that cannot take them natively.
+//! Deltas are held back just long enough to catch a match split across
+//! chunks; once one lands the reply is cut where it starts.
+
+use serde_json::Value;
+
+/// Body field listing the strings a provider could not take. The request
+/// builder writes it and `api_request` removes it before sending.
+pub(crate) const CLIENT_STOPS_KEY: &str = "_lettuceClientStops";
+
+/// Removes the client stop list from a request body.
+pub(crate) fn take_client_stops(body: Option<&mut Value>) -> Vec<String> {
+    body.and_then(Value::as_object_mut)
+        .and_then(|map| map.remove(CLIENT_STOPS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Cuts `text` at the earliest stop string, or returns `None` if none appears.
+pub fn truncate_at_stop(text: &str, stops: &[String]) -> Option<String> {
+    earliest_match(text, stops).map(|index| text[..index].to_string())
+}
+
+fn earliest_match(text: &str, stops: &[String]) -> Option<usize> {
+    stops
+        .iter()
+        .filter_map(|stop| text.find(stop.as_str()))
+        .min()
+}
+
+#[derive(Debug, Default)]
+pub struct StopGuard {
+    stops: Vec<String>,
+    held_len: usize,
+    pending: String,
+    emitted: String,
+    stopped: bool,
+}
+
+impl StopGuard {
+    pub fn new(stops: Vec<String>) -> Self {
+        let stops: Vec<String> = stops.into_iter().filter(|stop| !stop.is_empty()).collect();
+        let held_len = stops
+            .iter()
+            .map(|stop| stop.len().saturating_sub(1))
+            .max()
+            .unwrap_or(0);
+        Self {
+            stops,
+            held_len,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.stops.is_empty()
+    }
+
+    pub fn stops(&self) -> &[String] {
+        &self.stops
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Everything released so far.
+    pub fn emitted(&self) -> &str {
+        &self.emitted
+    }
+
+    /// Returns the part of the text seen so far that is safe to show.
+    pub fn feed(&mut self, delta: &str) -> String {
+        if self.stopped {
+            return String::new();
+        }
+        self.pending.push_str(delta);
+        if let Some(index) = earliest_match(&self.pending, &self.stops) {
+            self.pending.truncate(index);
+            self.stopped = true;
+            return self.release(self.pending.len());
+        }
+        let mut safe = self.pending.len().saturating_sub(self.held_len);
+        while !self.pending.is_char_boundary(safe) {
+            safe -= 1;
+        }
+        self.release(safe)
+    }
+
+    /// Releases text held back at the end of the stream.
+    pub fn finish(&mut self) -> String {
+        self.release(self.pending.len())
+    }
+
+    fn release(&mut self, end: usize) -> String {
+        let out: String = self.pending.drain(..end).collect();
+        self.emitted.push_str(&out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(guard: &mut StopGuard, deltas: &[&str]) -> String {
+        let mut out: String = deltas.iter().map(|delta| guard.feed(delta)).collect();
+        out.push_str(&guard.finish());
+        out
+    }
+
+    #[test]
+    fn catches_a_stop_split_across_deltas() {
+        let mut guard = StopGuard::new(vec!["\nUser:".into()]);
+        let out = feed_all(&mut guard, &["Hello there.\nUs", "er: hi", " more"]);
+        assert_eq!(out, "Hello there.");
+        assert!(guard.stopped());
+        assert_eq!(guard.emitted(), "Hello there.");
+    }
+
+    #[test]
+    fn holds_back_only_a_possible_prefix() {
+        let mut guard = StopGuard::new(vec!["###".into()]);
+        assert_eq!(guard.feed("abc#"), "ab");
+        assert_eq!(guard.feed("d"), "c");
+        assert_eq!(guard.finish(), "#d");
+        assert!(!guard.stopped());
+    }
+
+    #[test]
+    fn keeps_multibyte_text_intact() {
+        let mut guard = StopGuard::new(vec!["END".into()]);
+        let out = feed_all(&mut guard, &["héllo wörld", " ✓"]);
+        assert_eq!(out, "héllo wörld ✓");
+    }
+
+    #[test]
+    fn earliest_stop_wins() {
+        let stops = vec!["b".to_string(), "a".to_string()];
+        assert_eq!(truncate_at_stop("xxaxb", &stops).as_deref(), Some("xx"));
+        assert_eq!(truncate_at_stop("xyz", &stops), None);
+    }
+}
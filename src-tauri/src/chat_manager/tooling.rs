@@ -46,7 +46,7 @@ fn has_tools(cfg: &ToolConfig) -> bool {
     !cfg.tools.is_empty()
 }
 
-fn arguments_value_from_str(raw: &str) -> (Value, Option<String>) {
+pub(crate) fn arguments_value_from_str(raw: &str) -> (Value, Option<String>) {
     if let Some(parsed) = parse_parameter_tag_arguments(raw) {
         return (parsed, Some(raw.to_string()));
     }
@@ -213,6 +213,55 @@ pub fn anthropic_tool_choice(choice: Option<&ToolChoice>) -> Option<Value> {
     }
 }
 
+/// Bedrock Converse wraps each tool in a `toolSpec` with a `json` input schema.
+pub fn converse_tools(cfg: &ToolConfig) -> Option<Vec<Value>> {
+    if !has_tools(cfg) {
+        return None;
+    }
+
+    let tools: Vec<Value> = cfg
+        .tools
+        .iter()
+        .map(|tool| {
+            let mut spec = json!({
+                "name": tool.name,
+                "inputSchema": { "json": tool.parameters }
+            });
+            if let Some(description) = tool.description.as_ref() {
+                spec["description"] = json!(description);
+            }
+            json!({ "toolSpec": spec })
+        })
+        .collect();
+
+    if tools.is_empty() {
+        None
+    } else {
+        Some(tools)
+    }
+}
+
+/// Converse has no "none" choice; callers drop the tools instead.
+pub fn converse_tool_choice(choice: Option<&ToolChoice>) -> Option<Value> {
+    match choice {
+        None | Some(ToolChoice::None) => None,
+        Some(ToolChoice::Auto) => Some(json!({ "auto": {} })),
+        Some(ToolChoice::Required) | Some(ToolChoice::Any) => Some(json!({ "any": {} })),
+        Some(ToolChoice::Tool { name }) => Some(json!({ "tool": { "name": name } })),
+    }
+}
+
+/// Cohere v2 only knows "REQUIRED" and "NONE"; it cannot force a specific tool.
+pub fn cohere_tool_choice(choice: Option<&ToolChoice>) -> Option<Value> {
+    match choice {
+        None | Some(ToolChoice::Auto) => None,
+        Some(ToolChoice::None) => Some(json!("NONE")),
+        Some(ToolChoice::Required) | Some(ToolChoice::Any) | Some(ToolChoice::Tool { .. }) => {
+            Some(json!("REQUIRED"))
+        }
+    }
+}
+
 /// Gemini needs a wrapped `tools` list plus a `toolConfig.functionCallingConfig`.
 pub fn gemini_tools(cfg: &ToolConfig) -> Option<Vec<Value>> {
     if !has_tools(cfg) {
@@ -355,6 +404,79 @@ pub fn parse_tool_calls(provider_id: &str, payload: &Value) -> Vec<ToolCall> {
         }
     }
 
+    // 4) OpenAI Responses API function_call items. Streams deliver each finished
+    // item once via `response.output_item.done`; `response.completed` repeats
+    // them, so only untyped (non-streamed) bodies are read through `output`.
+    if calls.is_empty() {
+        let items: Vec<&Value> = match payload.get("type").and_then(|v| v.as_str()) {
+            Some("response.output_item.done") => payload.get("item").into_iter().collect(),
+            Some(_) => Vec::new(),
+            None => payload
+                .get("output")
+                .and_then(|v| v.as_array())
+                .map(|output| output.iter().collect())
+                .unwrap_or_default(),
+        };
+        for item in items {
+            if item.get("type").and_then(|v| v.as_str()) != Some("function_call") {
+                continue;
+            }
+            let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let (arguments, raw_arguments) = match item.get("arguments") {
+                Some(Value::String(raw)) => arguments_value_from_str(raw),
+                Some(other) => (other.clone(), None),
+                None => (Value::Null, None),
+            };
+            let id = item
+                .get("call_id")
+                .or_else(|| item.get("id"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("function_call");
+            calls.push(ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                arguments,
+                raw_arguments,
+            });
+        }
+    }
+
+    // 5) Bedrock Converse toolUse blocks. The stream transcoder emits each one
+    // whole as a top-level `toolUse`; non-streamed responses carry them in
+    // `output.message.content`.
+    if calls.is_empty() {
+        let blocks: Vec<&Value> = match payload.get("toolUse") {
+            Some(block) => vec![block],
+            None => payload
+                .pointer("/output/message/content")
+                .and_then(|v| v.as_array())
+                .map(|content| content.iter().filter_map(|b| b.get("toolUse")).collect())
+                .unwrap_or_default(),
+        };
+        for block in blocks {
+            let Some(name) = block.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let (arguments, raw_arguments) = match block.get("input") {
+                Some(Value::String(raw)) => arguments_value_from_str(raw),
+                Some(other) => (other.clone(), None),
+                None => (Value::Null, None),
+            };
+            let id = block
+                .get("toolUseId")
+                .and_then(|v| v.as_str())
+                .unwrap_or("tool_use");
+            calls.push(ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                arguments,
+                raw_arguments,
+            });
+        }
+    }
+
     calls
 }
 
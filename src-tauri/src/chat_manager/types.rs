@@ -236,6 +236,20 @@ pub struct ProviderCredential {
     pub headers: Option<HashMap<String, String>>,
     #[serde(default)]
     pub config: Option<Value>,
+    /// Extra keys pooled with `api_key`; see `transport::key_pool`.
+    #[serde(default)]
+    pub api_keys: Vec<ProviderApiKey>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderApiKey {
+    pub id: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub api_key: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -266,6 +280,10 @@ pub struct Model {
     pub system_prompt: Option<String>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 fn default_input_scopes() -> Vec<String> {
     vec!["text".to_string()]
 }
@@ -384,6 +402,131 @@ pub struct AdvancedSettings {
     pub host_api: Option<HostApiSettings>,
     #[serde(default)]
     pub accessibility: Option<AccessibilitySettings>,
+    /// App-wide fallback chain, tried after the character's own fallbacks.
+    #[serde(default)]
+    pub fallback_chain: Option<Vec<FallbackChainStep>>,
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerSettings>,
+    #[serde(default)]
+    pub context_overflow: Option<ContextOverflowSettings>,
+    /// Default proxy and certificates; see `transport::network`.
+    #[serde(default)]
+    pub network: Option<crate::transport::network::NetworkSettings>,
+    /// Captures provider traffic for debugging; see `transport::recorder`.
+    #[serde(default)]
+    pub request_recording_enabled: Option<bool>,
+}
+
+/// Failure that lets a fallback step run. A step without conditions runs on
+/// any error; a content refusal only falls back when listed explicitly.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FallbackCondition {
+    Timeout,
+    ServerError,
+    ContentRefusal,
+    ContextOverflow,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackChainStep {
+    pub model_id: String,
+    #[serde(default)]
+    pub conditions: Vec<FallbackCondition>,
+}
+
+/// Skips a provider credential that failed `failure_threshold` times within
+/// the last `window_minutes`, as long as another attempt is left.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreakerSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_circuit_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_circuit_window_minutes")]
+    pub window_minutes: u32,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            failure_threshold: default_circuit_failure_threshold(),
+            window_minutes: default_circuit_window_minutes(),
+        }
+    }
+}
+
+fn default_circuit_failure_threshold() -> u32 {
+    3
+}
+
+fn default_circuit_window_minutes() -> u32 {
+    5
+}
+
+/// One model tried for a chat turn, shown in the message debug snapshot.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAttemptRecord {
+    pub model_id: String,
+    pub model_name: String,
+    pub provider_id: String,
+    pub credential_id: String,
+    pub is_fallback: bool,
+    /// "success", "failed" or "skipped".
+    pub outcome: String,
+    #[serde(default)]
+    pub failure: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub started_at: u64,
+    #[serde(default)]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// What to do when a conversation no longer fits the model's context length.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextOverflowSettings {
+    /// Drop the oldest unpinned history until the request fits.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Keep a rolling summary of dropped messages and send it in their place.
+    #[serde(default)]
+    pub rolling_summary: bool,
+    /// Falls back to the dynamic memory summarisation model.
+    #[serde(default)]
+    pub summary_model_id: Option<String>,
+}
+
+impl Default for ContextOverflowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rolling_summary: false,
+            summary_model_id: None,
+        }
+    }
+}
+
+/// How a request's history was cut down to fit the context length.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextTrimReport {
+    pub context_length: u32,
+    pub reserved_output_tokens: u32,
+    /// Prompt entries, memories, pinned messages and other fixed content.
+    pub fixed_tokens: u32,
+    pub history_tokens_before: u32,
+    pub history_tokens_after: u32,
+    pub dropped_message_ids: Vec<String>,
+    pub dropped_tokens: u32,
+    pub summary_tokens: u32,
+    /// Set when even the protected messages do not fit.
+    pub over_budget: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
@@ -451,6 +594,8 @@ pub struct AccessibilitySoundSettings {
 pub enum MemoryRetrievalStrategy {
     Smart,
     Cosine,
+    /// BM25 keyword ranking fused with cosine ranking.
+    Hybrid,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -488,6 +633,18 @@ pub struct DynamicMemorySettings {
     pub recursive_memory_loops: bool,
     #[serde(default = "default_recursive_memory_loop_hard_cap")]
     pub recursive_memory_loop_hard_cap: u32,
+    /// Build an entity graph from each turn with the companion NER model.
+    #[serde(default)]
+    pub entity_graph_enabled: bool,
+    /// Also link entities into a graph shared by every session with the character.
+    #[serde(default)]
+    pub entity_graph_character_scope: bool,
+    /// Hybrid retrieval: extra weight (0-1) for recently created memories.
+    #[serde(default)]
+    pub hybrid_recency_boost: f32,
+    /// Hybrid retrieval: extra weight (0-1) for pinned memories.
+    #[serde(default)]
+    pub hybrid_pin_boost: f32,
 }
 
 fn default_min_similarity() -> f32 {
@@ -612,6 +769,42 @@ pub struct AdvancedModelSettings {
     pub ollama_repeat_penalty: Option<f64>,
     pub ollama_seed: Option<u32>,
     pub ollama_stop: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_rep_pen: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_rep_pen_range: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_rep_pen_slope: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_min_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_top_a: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_typical: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_tfs: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_dry_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_dry_base: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_dry_allowed_length: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_dry_penalty_last_n: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_dry_sequence_breakers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_xtc_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_xtc_probability: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_smoothing_factor: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_smoothing_curve: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_sampler_order: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kobold_banned_tokens: Option<Vec<String>>,
     // Reasoning/thinking settings
     #[serde(default)]
     pub reasoning_enabled: Option<bool>,
@@ -623,6 +816,42 @@ pub struct AdvancedModelSettings {
     #[serde(default)]
     pub prompt_caching_enabled: Option<bool>,
     pub prompt_caching_ttl: Option<String>,
+    // Tokenizer used for budgeting: "auto", "gguf", "huggingFace", "provider", "o200k", "estimate"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+    /// HuggingFace repo whose tokenizer.json replaces the family default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer_repo: Option<String>,
+    /// Capabilities read from provider metadata or confirmed by a probe request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_capabilities: Option<ModelCapabilities>,
+    // Output controls applied to every provider
+    /// Strings that end the reply; enforced on the client where unsupported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    /// Bias per token, keyed by token id or by text resolved with the model tokenizer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<String, f64>>,
+    /// Phrases the reply may not contain; the reply is cut where one appears
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banned_strings: Option<Vec<String>>,
+}
+
+/// What a model supports beyond plain text chat. `None` means unknown.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vision: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_mode: Option<bool>,
+    /// "metadata" when read from a models endpoint or file, "probe" when verified live
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 impl Default for AdvancedModelSettings {
@@ -683,11 +912,35 @@ impl Default for AdvancedModelSettings {
             ollama_repeat_penalty: None,
             ollama_seed: None,
             ollama_stop: None,
+            kobold_rep_pen: None,
+            kobold_rep_pen_range: None,
+            kobold_rep_pen_slope: None,
+            kobold_min_p: None,
+            kobold_top_a: None,
+            kobold_typical: None,
+            kobold_tfs: None,
+            kobold_dry_multiplier: None,
+            kobold_dry_base: None,
+            kobold_dry_allowed_length: None,
+            kobold_dry_penalty_last_n: None,
+            kobold_dry_sequence_breakers: None,
+            kobold_xtc_threshold: None,
+            kobold_xtc_probability: None,
+            kobold_smoothing_factor: None,
+            kobold_smoothing_curve: None,
+            kobold_sampler_order: None,
+            kobold_banned_tokens: None,
             reasoning_enabled: None,
             reasoning_effort: None,
             reasoning_budget_tokens: None,
             prompt_caching_enabled: Some(false),
             prompt_caching_ttl: Some("5min".to_string()),
+            tokenizer: None,
+            tokenizer_repo: None,
+            model_capabilities: None,
+            stop_sequences: None,
+            logit_bias: None,
+            banned_strings: None,
         }
     }
 }
@@ -1100,6 +1353,10 @@ pub struct ChatContinueArgs {
     pub stream: Option<bool>,
     #[serde(alias = "requestId")]
     pub request_id: Option<String>,
+    /// Replaces the continue prompt; set by the proactive companion
+    /// scheduler, never by the frontend.
+    #[serde(skip)]
+    pub directive: Option<String>,
 }
 
 #[derive(Deserialize)]
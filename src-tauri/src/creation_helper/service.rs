@@ -1415,6 +1415,7 @@ async fn send_creation_api_request(
             stream: Some(built.stream),
             request_id: built.request_id.clone(),
             provider_id: Some(provider_id.to_string()),
+            credential_id: Some(cred.id.clone()),
         };
 
         let mut abort_rx = {
@@ -2615,6 +2616,11 @@ async fn process_assistant_turn(
             .cloned()
             .and_then(|value| serde_json::from_value(value).ok()),
         config: credential.get("config").cloned(),
+        api_keys: credential
+            .get("apiKeys")
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default(),
     };
 
     let streaming_enabled = effective_streaming_enabled(&cred, streaming_enabled);
@@ -3024,6 +3030,7 @@ async fn process_assistant_turn(
             stream: Some(finalize_built.stream),
             request_id: finalize_built.request_id.clone(),
             provider_id: Some(provider_id.to_string()),
+            credential_id: Some(cred.id.clone()),
         };
 
         let mut abort_rx = {
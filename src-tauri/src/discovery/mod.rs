@@ -35,6 +35,12 @@ fn now_epoch() -> i64 {
     chrono::Utc::now().timestamp()
 }
 
+/// Every discovery request goes to Character Tavern, so one client serves
+/// them all.
+fn http_client() -> Result<reqwest::Client, String> {
+    crate::transport::network::client_for(None)
+}
+
 fn read_pure_mode_level(app: &AppHandle) -> String {
     if let Ok(Some(raw)) = internal_read_settings(app) {
         if let Ok(json) = serde_json::from_str::<Value>(&raw) {
@@ -538,7 +544,7 @@ pub async fn discovery_fetch_card_detail(
         format!("fetching card detail from {}", url),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(&url)
         .send()
@@ -626,7 +632,7 @@ pub async fn discovery_fetch_cards(
     descending: Option<bool>,
 ) -> Result<Vec<DiscoveryCard>, String> {
     let card_type = normalize_type(&card_type)?;
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let mut cards = fetch_cards(&app, card_type, &client).await?;
     let pure_mode_level = read_pure_mode_level(&app);
     filter_nsfw_cards(&mut cards, &pure_mode_level);
@@ -645,7 +651,7 @@ pub async fn discovery_fetch_sections(
     sort_by: Option<String>,
     descending: Option<bool>,
 ) -> Result<DiscoverySections, String> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let (mut newest, mut popular, mut trending) = tokio::try_join!(
         fetch_cards(&app, "newest", &client),
         fetch_cards(&app, "popular", &client),
@@ -721,7 +727,7 @@ pub async fn discovery_search_cards(
         format!("fetching search cards with params {:?}", params),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(CARD_SEARCH_BASE_URL)
         .query(&params)
@@ -777,7 +783,7 @@ pub async fn discovery_fetch_alternate_greetings(
         format!("Fetching alternate greetings from: {}", url),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(&url)
         .send()
@@ -820,7 +826,7 @@ pub async fn discovery_fetch_tags(app: AppHandle, card_id: String) -> Result<Vec
         format!("Fetching tags from: {}", url),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(&url)
         .send()
@@ -881,7 +887,7 @@ pub async fn discovery_fetch_author_info(
         format!("Fetching author info from: {}", url),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(&url)
         .send()
@@ -930,7 +936,7 @@ async fn discovery_fetch_lorebook(
         format!("Fetching lorebook from: {}", url),
     );
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let resp = client
         .get(&url)
         .send()
@@ -986,7 +992,7 @@ pub async fn discovery_import_character(app: AppHandle, path: String) -> Result<
     };
 
     // Fetch avatar image from CDN
-    let client = reqwest::Client::new();
+    let client = http_client()?;
 
     // Save avatar image locally using CDN URL
     let avatar_cdn_url = get_card_image(
@@ -565,3 +565,169 @@ pub async fn run_embedding_dev_benchmark(app: AppHandle) -> Result<DevBenchmarkR
         )
     })?
 }
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryAnnBenchmarkResult {
+    dimensions: usize,
+    top_k: usize,
+    query_count: usize,
+    sizes: Vec<MemoryAnnBenchmarkSize>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryAnnBenchmarkSize {
+    memory_count: usize,
+    build_ms: f32,
+    brute_force_average_ms: f32,
+    brute_force_p95_ms: f32,
+    ann_average_ms: f32,
+    ann_p95_ms: f32,
+    average_speedup: f32,
+    recall_at_k: f32,
+}
+
+const ANN_BENCH_SIZES: [usize; 3] = [1_000, 5_000, 10_000];
+const ANN_BENCH_QUERIES: usize = 50;
+const ANN_BENCH_TOP_K: usize = 10;
+const ANN_BENCH_EF: usize = 96;
+const ANN_BENCH_CLUSTERS: usize = 64;
+
+/// Unit vectors scattered around random topic centroids, which gives
+/// neighbourhoods closer to real memory sets than uniform noise.
+fn synthetic_vectors(
+    rng: &mut impl rand::Rng,
+    centroids: &[Vec<f32>],
+    count: usize,
+) -> Vec<Vec<f32>> {
+    (0..count)
+        .map(|_| {
+            let centroid = &centroids[rng.gen_range(0..centroids.len())];
+            let mut vector: Vec<f32> = centroid
+                .iter()
+                .map(|value| value + rng.gen_range(-0.35f32..0.35))
+                .collect();
+            let norm = vector
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt()
+                .max(f32::EPSILON);
+            vector.iter_mut().for_each(|v| *v /= norm);
+            vector
+        })
+        .collect()
+}
+
+/// Compares brute-force cosine retrieval with the HNSW memory index on
+/// synthetic memory sets of increasing size.
+pub async fn run_memory_ann_benchmark(app: AppHandle) -> Result<MemoryAnnBenchmarkResult, String> {
+    use crate::chat_manager::memory::dynamic::cosine_similarity;
+    use crate::chat_manager::memory::hnsw::{random_level, HnswGraph};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::time::Instant;
+
+    let dimensions = resolve_target_embedding_dimensions(
+        EmbeddingSourceVersion::V4,
+        settings::read_embedding_preferences(&app).embedding_dimensions,
+    );
+
+    let benchmark_future =
+        tokio::task::spawn_blocking(move || -> Result<MemoryAnnBenchmarkResult, String> {
+            let mut rng = StdRng::seed_from_u64(0x4c65_7474);
+            let centroids: Vec<Vec<f32>> = (0..ANN_BENCH_CLUSTERS)
+                .map(|_| {
+                    (0..dimensions)
+                        .map(|_| rng.gen_range(-1.0f32..1.0))
+                        .collect()
+                })
+                .collect();
+            let queries = synthetic_vectors(&mut rng, &centroids, ANN_BENCH_QUERIES);
+
+            let mut sizes = Vec::with_capacity(ANN_BENCH_SIZES.len());
+            for &memory_count in &ANN_BENCH_SIZES {
+                let data = synthetic_vectors(&mut rng, &centroids, memory_count);
+
+                let build_started = Instant::now();
+                let mut graph = HnswGraph::new(dimensions);
+                let mut vectors: Vec<&[f32]> = Vec::with_capacity(memory_count);
+                for (idx, vector) in data.iter().enumerate() {
+                    let level = random_level(&mut rng);
+                    graph.insert(&mut vectors, idx.to_string(), vector, false, false, level);
+                }
+                let build_ms = build_started.elapsed().as_secs_f32() * 1000.0;
+
+                let mut brute_samples = Vec::with_capacity(queries.len());
+                let mut ann_samples = Vec::with_capacity(queries.len());
+                let mut hits = 0usize;
+                for query in &queries {
+                    let started = Instant::now();
+                    let mut scored: Vec<(usize, f32)> = data
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, vector)| (idx, cosine_similarity(query, vector)))
+                        .collect();
+                    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    scored.truncate(ANN_BENCH_TOP_K);
+                    brute_samples.push(started.elapsed().as_secs_f32() * 1000.0);
+
+                    let started = Instant::now();
+                    let approximate =
+                        graph.search(&vectors, query, ANN_BENCH_TOP_K, ANN_BENCH_EF, 1.0);
+                    ann_samples.push(started.elapsed().as_secs_f32() * 1000.0);
+
+                    hits += approximate
+                        .iter()
+                        .filter(|(node, _)| scored.iter().any(|(idx, _)| idx == node))
+                        .count();
+                }
+
+                let brute_force_average_ms = average(&brute_samples);
+                let ann_average_ms = average(&ann_samples);
+                let result = MemoryAnnBenchmarkSize {
+                    memory_count,
+                    build_ms,
+                    brute_force_average_ms,
+                    brute_force_p95_ms: percentile_ms(&brute_samples, 95.0),
+                    ann_average_ms,
+                    ann_p95_ms: percentile_ms(&ann_samples, 95.0),
+                    average_speedup: if ann_average_ms > 0.0 {
+                        brute_force_average_ms / ann_average_ms
+                    } else {
+                        0.0
+                    },
+                    recall_at_k: hits as f32 / (queries.len() * ANN_BENCH_TOP_K) as f32,
+                };
+                log_info(
+                    &app,
+                    "memory_ann_benchmark",
+                    format!(
+                        "n={} build={:.0}ms brute={:.2}ms ann={:.2}ms recall@{}={:.3}",
+                        memory_count,
+                        result.build_ms,
+                        result.brute_force_average_ms,
+                        result.ann_average_ms,
+                        ANN_BENCH_TOP_K,
+                        result.recall_at_k
+                    ),
+                );
+                sizes.push(result);
+            }
+
+            Ok(MemoryAnnBenchmarkResult {
+                dimensions,
+                top_k: ANN_BENCH_TOP_K,
+                query_count: ANN_BENCH_QUERIES,
+                sizes,
+            })
+        });
+
+    benchmark_future.await.map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Memory ANN benchmark task failed: {}", e),
+        )
+    })?
+}
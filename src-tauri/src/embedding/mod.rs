@@ -404,6 +404,13 @@ pub async fn run_embedding_dev_benchmark(
     benchmark::run_embedding_dev_benchmark(app).await
 }
 
+#[tauri::command]
+pub async fn run_memory_ann_benchmark(
+    app: AppHandle,
+) -> Result<benchmark::MemoryAnnBenchmarkResult, String> {
+    benchmark::run_memory_ann_benchmark(app).await
+}
+
 #[tauri::command]
 pub async fn run_embedding_test(app: AppHandle) -> Result<tests::TestResult, String> {
     tests::run_embedding_test(app).await
@@ -228,7 +228,8 @@ async fn create_gemini_cached_content(
     prefix_contents: &[Value],
     ttl: &str,
 ) -> Result<String, String> {
-    let client = transport::build_client(req.timeout_ms, false).map_err(|e| e.to_string())?;
+    let client = transport::build_client(req.credential_id.as_deref(), req.timeout_ms, false)
+        .map_err(|e| e.to_string())?;
     let mut builder = client.request(Method::POST, endpoint);
 
     if let Some(headers) = &req.headers {
@@ -264,7 +265,15 @@ async fn create_gemini_cached_content(
         ),
     );
 
-    let response = transport::send_with_retries(app, "gemini_cache_create", builder, 2, None).await;
+    let response = transport::send_with_retries(
+        app,
+        "gemini_cache_create",
+        builder,
+        2,
+        None,
+        req.credential_id.as_deref(),
+    )
+    .await;
     let response = match response {
         Ok(response) => response,
         Err(err) => {
@@ -36,6 +36,7 @@ use crate::chat_manager::memory::dynamic::{
     promote_cold_memories, search_cold_memory_indices_by_keyword, select_relevant_memory_indices,
     select_top_cosine_memory_indices, trim_memories_to_max,
 };
+use crate::chat_manager::memory::hybrid::{select_hybrid_memory_indices, HybridBoosts};
 use crate::chat_manager::memory::manual::{has_manual_memories, render_manual_memory_lines};
 use crate::chat_manager::memory::structured_fallback::{
     memory_operations_fallback_prompt, memory_repairs_fallback_prompt,
@@ -73,7 +74,9 @@ use crate::storage_manager::group_sessions::{
 use crate::storage_manager::lorebook::{
     get_enabled_lorebook_entry_contexts_for_ids, get_lorebook, LorebookEntry,
 };
-use crate::utils::{log_error, log_info, log_warn, now_millis};
+use crate::storage_manager::memory_ann;
+use crate::storage_manager::memory_embeddings::SessionKind;
+use crate::utils::{emit_debug, log_error, log_info, log_warn, now_millis};
 
 pub use selection::parse_mentions;
 
@@ -1655,6 +1658,7 @@ async fn send_dynamic_memory_request(
         stream: Some(false),
         request_id: built.request_id.clone(),
         provider_id: Some(provider_cred.provider_id.clone()),
+        credential_id: Some(provider_cred.id.clone()),
     };
 
     let first_response = match api_request(app.clone(), api_request_payload).await {
@@ -1718,6 +1722,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 api_request(app.clone(), api_request_payload).await?
@@ -1793,6 +1798,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 return api_request(app.clone(), api_request_payload).await;
@@ -1845,6 +1851,7 @@ async fn send_dynamic_memory_request(
                     stream: Some(false),
                     request_id: built.request_id.clone(),
                     provider_id: Some(provider_cred.provider_id.clone()),
+                    credential_id: Some(provider_cred.id.clone()),
                 };
 
                 return api_request(app.clone(), api_request_payload).await;
@@ -2034,6 +2041,7 @@ async fn select_relevant_memories(
     limit: usize,
     min_similarity: f32,
     strategy: &MemoryRetrievalStrategy,
+    boosts: HybridBoosts,
 ) -> Vec<MemoryEmbedding> {
     if query.is_empty() || session.memory_embeddings.is_empty() {
         return Vec::new();
@@ -2048,23 +2056,80 @@ async fn select_relevant_memories(
     }
 
     let query_embedding = match embedding::compute_embedding(app.clone(), query.to_string()).await {
-        Ok(vec) => vec,
+        Ok(vec) => Some(vec),
         Err(err) => {
             log_warn(
                 app,
                 "group_memory_retrieval",
                 format!("embedding failed: {}", err),
             );
-            return Vec::new();
+            None
         }
     };
 
-    if matches!(strategy, MemoryRetrievalStrategy::Cosine) {
-        let cosine_indices = select_top_cosine_memory_indices(
-            &query_embedding,
+    if matches!(strategy, MemoryRetrievalStrategy::Hybrid) {
+        let matches = select_hybrid_memory_indices(
+            query_embedding.as_deref(),
+            query,
             &session.memory_embeddings,
             limit,
             min_similarity,
+            boosts,
+        );
+        emit_debug(
+            app,
+            "memory_retrieval_scores",
+            json!({
+                "groupSessionId": session.id,
+                "strategy": "hybrid",
+                "results": matches
+                    .iter()
+                    .map(|m| {
+                        let mut value = json!(m);
+                        value["explanation"] = json!(m.explanation());
+                        value
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        );
+        return matches
+            .into_iter()
+            .filter_map(|m| {
+                session.memory_embeddings.get(m.index).map(|mem| {
+                    let mut cloned = mem.clone();
+                    cloned.match_score = m.cosine;
+                    cloned
+                })
+            })
+            .collect();
+    }
+
+    let Some(query_embedding) = query_embedding else {
+        return Vec::new();
+    };
+
+    // Large sessions only score the ANN candidates instead of every memory.
+    let ann_candidates = memory_ann::candidate_indices_app(
+        app,
+        &session.id,
+        SessionKind::GroupSession,
+        &query_embedding,
+        &session.memory_embeddings,
+        memory_ann::candidate_count(limit),
+    );
+
+    if matches!(strategy, MemoryRetrievalStrategy::Cosine) {
+        let cosine_indices = memory_ann::select_from_candidates(
+            ann_candidates.as_deref(),
+            &session.memory_embeddings,
+            |candidates| {
+                select_top_cosine_memory_indices(
+                    &query_embedding,
+                    candidates,
+                    limit,
+                    min_similarity,
+                )
+            },
         );
         if cosine_indices.is_empty() {
             return Vec::new();
@@ -2077,11 +2142,17 @@ async fn select_relevant_memories(
 
     // Smart mode: blend semantic match + recency/frequency + fallback fill.
     let cosine_limit = (limit.saturating_sub(2)).max(1);
-    let cosine_indices = select_relevant_memory_indices(
-        &query_embedding,
+    let cosine_indices = memory_ann::select_from_candidates(
+        ann_candidates.as_deref(),
         &session.memory_embeddings,
-        cosine_limit,
-        min_similarity,
+        |candidates| {
+            select_relevant_memory_indices(
+                &query_embedding,
+                candidates,
+                cosine_limit,
+                min_similarity,
+            )
+        },
     );
 
     let mut selected: HashSet<usize> = HashSet::new();
@@ -2130,11 +2201,12 @@ async fn select_relevant_memories(
 
     // 4. Fill remaining slots with next best cosine results
     if results.len() < limit {
-        let extra_indices = select_relevant_memory_indices(
-            &query_embedding,
+        let extra_indices = memory_ann::select_from_candidates(
+            ann_candidates.as_deref(),
             &session.memory_embeddings,
-            limit,
-            min_similarity,
+            |candidates| {
+                select_relevant_memory_indices(&query_embedding, candidates, limit, min_similarity)
+            },
         );
         for (idx, _score) in extra_indices {
             if results.len() >= limit {
@@ -2657,8 +2729,8 @@ async fn process_group_dynamic_memory_cycle(
     );
     ensure_group_dynamic_memory_not_cancelled(app, session, pool, &cancel_token)?;
     session.memory_summary = summary;
-    session.memory_summary_token_count =
-        crate::embedding::tokenizer::count_tokens(app, &session.memory_summary).unwrap_or(0) as i32;
+    session.memory_summary_token_count = crate::tokens::counter_for_model_id(app, settings, None)
+        .count(&session.memory_summary) as i32;
 
     // Enforce token budget
     let pinned_fixed = ensure_pinned_hot(&mut session.memory_embeddings);
@@ -3076,6 +3148,8 @@ async fn run_group_memory_tool_update(
     let overwrite_llama_sampler_config = dynamic_memory_llama_sampler_overwrite_enabled(settings);
     let tool_config = build_memory_tool_config();
     let max_entries = dynamic_settings.max_entries.max(1) as usize;
+    // Hot memories are budgeted in the default chat model's tokens
+    let token_counter = crate::tokens::counter_for_model_id(app, settings, None);
 
     let mut messages_for_api = Vec::new();
     let system_role = crate::chat_manager::request_builder::system_role_for(provider_cred);
@@ -3584,8 +3658,7 @@ async fn run_group_memory_tool_update(
                             }));
                             continue;
                         }
-                        let token_count =
-                            crate::embedding::tokenizer::count_tokens(app, &text).unwrap_or(0);
+                        let token_count = token_counter.count(&text);
                         let is_pinned = call
                             .arguments
                             .get("important")
@@ -4078,8 +4151,7 @@ async fn run_group_memory_tool_update(
                         continue;
                     }
 
-                    let token_count =
-                        crate::embedding::tokenizer::count_tokens(app, &text).unwrap_or(0);
+                    let token_count = token_counter.count(&text);
                     let (embedding_source_version, embedding_dimensions) =
                         embedding::resolve_active_embedding_signature(app)
                             .unwrap_or_else(|_| ("v3".to_string(), 512));
@@ -5794,6 +5866,7 @@ async fn select_speaker_via_llm_with_tracking(
         stream: Some(false),
         request_id: Some(request_id.to_string()),
         provider_id: Some(credential.provider_id.clone()),
+        credential_id: Some(credential.id.clone()),
     };
 
     let api_response = api_request(app.clone(), api_request_payload).await?;
@@ -5931,6 +6004,7 @@ async fn generate_character_response(
             dynamic_settings.retrieval_limit.max(1) as usize,
             min_similarity,
             &dynamic_settings.retrieval_strategy,
+            HybridBoosts::from_settings(&dynamic_settings),
         )
         .await
     } else {
@@ -6195,6 +6269,7 @@ async fn generate_character_response(
         stream: Some(true),
         request_id: Some(request_id.to_string()),
         provider_id: Some(credential.provider_id.clone()),
+        credential_id: Some(credential.id.clone()),
     };
 
     log_info(
@@ -7379,6 +7454,7 @@ pub async fn group_chat_generate_user_reply(
         stream: Some(built.stream),
         request_id: built.request_id.clone(),
         provider_id: Some(provider_cred.provider_id.clone()),
+        credential_id: Some(provider_cred.id.clone()),
     };
 
     let api_response = api_request(app.clone(), api_request_payload).await?;
@@ -347,6 +347,8 @@ fn hf_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
 }
 
 // GGUF header parser
+const GGUF_TEMPLATE_SCAN_BYTES: u64 = 32 * 1024 * 1024;
+
 #[derive(Debug, Default)]
 struct GgufModelMeta {
     architecture: Option<String>,
@@ -365,6 +367,8 @@ struct GgufModelMeta {
     key_length: Option<u64>,
     /// Per-head value dimension (used for MLA KV cache sizing)
     value_length: Option<u64>,
+    /// Jinja chat template embedded by the converter
+    chat_template: Option<String>,
     /// Number of metadata KV pairs declared in header (for truncation detection)
     metadata_kv_count: u64,
     /// Number of KV pairs actually parsed before buffer ran out
@@ -599,6 +603,9 @@ fn parse_gguf_meta(data: &[u8]) -> Option<GgufModelMeta> {
 
         let ok = if key == "general.architecture" {
             reader.skip_value(value_type).is_some()
+        } else if key == "tokenizer.chat_template" && value_type == 8 {
+            meta.chat_template = reader.read_string();
+            meta.chat_template.is_some()
         } else if key == "general.file_type" {
             meta.file_type = reader.read_value_as_u32(value_type);
             meta.file_type.is_some()
@@ -669,6 +676,17 @@ fn read_local_gguf_meta(path: &Path) -> Option<GgufModelMeta> {
     parse_gguf_meta(&fallback).or(primary_meta)
 }
 
+/// The chat template follows the tokenizer vocabulary, so this scans much
+/// further into the file than the architecture read above.
+pub(crate) fn read_local_gguf_chat_template(path: &Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut buffer = Vec::new();
+    file.take(GGUF_TEMPLATE_SCAN_BYTES)
+        .read_to_end(&mut buffer)
+        .ok()?;
+    parse_gguf_meta(&buffer)?.chat_template
+}
+
 // Runability scoring
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -1377,7 +1395,7 @@ fn extract_quantization(filename: &str) -> String {
 }
 
 fn build_client() -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
+    crate::transport::network::configure(reqwest::Client::builder(), None)?
         .user_agent("LettuceAI/1.0")
         .timeout(std::time::Duration::from_secs(30))
         .build()
@@ -1813,7 +1831,7 @@ async fn do_queue_download(app: &AppHandle, item: &QueuedDownload) -> Result<Str
         ),
     );
 
-    let client = reqwest::Client::builder()
+    let client = crate::transport::network::configure(reqwest::Client::builder(), None)?
         .user_agent("LettuceAI/1.0")
         .redirect(reqwest::redirect::Policy::limited(10))
         .build()
@@ -2217,7 +2235,8 @@ async fn fetch_gguf_meta(
         model_id, representative.filename
     );
 
-    let client = reqwest::Client::builder()
+    let client = crate::transport::network::configure(reqwest::Client::builder(), None)
+        .ok()?
         .user_agent("LettuceAI/1.0")
         .redirect(reqwest::redirect::Policy::limited(10))
         .timeout(std::time::Duration::from_secs(15))
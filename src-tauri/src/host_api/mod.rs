@@ -402,6 +402,7 @@ async fn chat_completions_handler(
             stream: Some(false),
             request_id: None,
             provider_id: Some(model.provider_id.clone()),
+            credential_id: Some(credential.id.clone()),
         },
     )
     .await
@@ -682,6 +683,7 @@ async fn execute_streaming_chat_completion(
 
     let app_for_task = state.app.clone();
     let model_provider_id = model.provider_id.clone();
+    let credential_id = credential.id.clone();
     let task_model_name = payload.model.clone();
     let task_stream_id = stream_id.clone();
     let task_final_emitted = final_emitted.clone();
@@ -700,6 +702,7 @@ async fn execute_streaming_chat_completion(
                 stream: Some(true),
                 request_id: Some(request_id.clone()),
                 provider_id: Some(model_provider_id),
+                credential_id: Some(credential_id),
             },
         )
         .await;
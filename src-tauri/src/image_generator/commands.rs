@@ -133,7 +133,7 @@ pub async fn generate_image(
             format!("Sending request to: {}", url),
         );
 
-        let client = reqwest::Client::new();
+        let client = crate::transport::network::client_for(Some(request.credential_id.as_str()))?;
         let mut req_builder = client.post(&url);
 
         let is_multipart = matches!(payload, ImageRequestPayload::Multipart(_));
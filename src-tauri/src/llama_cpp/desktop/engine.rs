@@ -894,3 +894,72 @@ pub(crate) fn consume_kqv_fallback_toast(
     guard.kqv_fallback_toast_shown = true;
     Ok(true)
 }
+
+static COUNTING_MODEL: Mutex<Option<(String, Arc<LlamaModel>)>> = Mutex::new(None);
+
+fn loaded_model(model_path: &str) -> Option<Arc<LlamaModel>> {
+    let guard = ENGINE.get()?.lock().ok()?;
+    if guard.model_path.as_deref() == Some(model_path) {
+        guard.model.clone()
+    } else {
+        None
+    }
+}
+
+fn counting_model(model_path: &str) -> Result<Arc<LlamaModel>, String> {
+    let mut guard = COUNTING_MODEL
+        .lock()
+        .map_err(|_| "llama.cpp counting model lock poisoned".to_string())?;
+    if let Some((path, model)) = guard.as_ref() {
+        if path == model_path {
+            return Ok(model.clone());
+        }
+    }
+    if !Path::new(model_path).exists() {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("llama.cpp model path not found: {}", model_path),
+        ));
+    }
+    let backend = shared_backend()?;
+    let model = LlamaModel::load_from_file(
+        backend.as_ref(),
+        model_path,
+        &llama_cpp_2::model::params::LlamaModelParams::default().with_n_gpu_layers(0),
+    )
+    .map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Failed to load llama model for token counting: {e}"),
+        )
+    })?;
+    let model = Arc::new(model);
+    *guard = Some((model_path.to_string(), model.clone()));
+    Ok(model)
+}
+
+/// Tokenizes with the GGUF vocabulary. Reuses the inference model when it is
+/// loaded, otherwise keeps a CPU-only copy of the last model counted.
+pub(crate) fn count_model_tokens(model_path: &str, texts: &[&str]) -> Result<Vec<u32>, String> {
+    let model = match loaded_model(model_path) {
+        Some(model) => model,
+        None => counting_model(model_path)?,
+    };
+    texts
+        .iter()
+        .map(|text| {
+            model
+                .str_to_token(text, AddBos::Never)
+                .map(|tokens| tokens.len() as u32)
+                .map_err(|e| {
+                    crate::utils::err_msg(
+                        module_path!(),
+                        line!(),
+                        format!("Failed to tokenize text: {e}"),
+                    )
+                })
+        })
+        .collect()
+}
@@ -12,6 +12,7 @@ use tauri::AppHandle;
 use tauri::Emitter;
 
 use crate::api::{ApiRequest, ApiResponse};
+use crate::chat_manager::provider_adapter::capabilities;
 #[cfg(not(mobile))]
 use crate::chat_manager::provider_adapter::{
     extract_image_data_urls, extract_text_content, parse_data_url,
@@ -22,6 +23,7 @@ use crate::chat_manager::thinking::{normalize_thinking_content, ThinkingTagStrea
 use crate::chat_manager::tooling::{
     parse_tool_calls, parse_tool_calls_from_text, strip_tool_call_blocks, ToolCall,
 };
+use crate::chat_manager::types::ModelCapabilities;
 #[cfg(not(mobile))]
 use crate::chat_manager::types::{ErrorEnvelope, NormalizedEvent, UsageSummary};
 #[cfg(not(mobile))]
@@ -2756,6 +2758,23 @@ pub async fn llamacpp_embedded_chat_template(
     }
 }
 
+/// Capabilities hinted by the GGUF's embedded chat template. Vision depends
+/// on the separate mmproj file, so it stays unknown here.
+#[tauri::command]
+pub async fn llamacpp_model_capabilities(model_path: String) -> Result<ModelCapabilities, String> {
+    let path = std::path::Path::new(&model_path);
+    if !path.exists() {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("llama.cpp model path not found: {}", model_path),
+        ));
+    }
+    Ok(crate::hf_browser::read_local_gguf_chat_template(path)
+        .map(|template| capabilities::from_chat_template(&template))
+        .unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn llamacpp_unload(app: AppHandle) -> Result<(), String> {
     #[cfg(not(mobile))]
@@ -2773,6 +2792,24 @@ pub async fn llamacpp_unload(app: AppHandle) -> Result<(), String> {
     }
 }
 
+/// Token counts from a GGUF model's own vocabulary.
+pub(crate) fn count_gguf_tokens(model_path: &str, texts: &[&str]) -> Result<Vec<u32>, String> {
+    #[cfg(not(mobile))]
+    {
+        desktop::engine::count_model_tokens(model_path, texts)
+    }
+    #[cfg(mobile)]
+    {
+        let _ = model_path;
+        let _ = texts;
+        Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            "llama.cpp is only supported on desktop builds",
+        ))
+    }
+}
+
 pub fn is_llama_cpp(provider_id: Option<&str>) -> bool {
     provider_id == Some(LOCAL_PROVIDER_ID)
 }
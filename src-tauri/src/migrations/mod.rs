@@ -7,7 +7,7 @@ use crate::storage_manager::settings::{read_settings_typed, write_settings_typed
 use crate::utils::log_info;
 
 /// Current migration version
-pub const CURRENT_MIGRATION_VERSION: u32 = 63;
+pub const CURRENT_MIGRATION_VERSION: u32 = 75;
 
 pub fn run_migrations(app: &AppHandle) -> Result<(), String> {
     log_info(app, "migrations", "Starting migration check");
@@ -669,6 +669,126 @@ pub fn run_migrations(app: &AppHandle) -> Result<(), String> {
         version = 63;
     }
 
+    if version < 64 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v63 -> v64: Add sync_asset_transfers table",
+        );
+        migrate_v63_to_v64(app)?;
+        version = 64;
+    }
+
+    if version < 65 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v64 -> v65: Add backup_schedule table",
+        );
+        migrate_v64_to_v65(app)?;
+        version = 65;
+    }
+
+    if version < 66 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v65 -> v66: Add character-level memory tables",
+        );
+        migrate_v65_to_v66(app)?;
+        version = 66;
+    }
+
+    if version < 67 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v66 -> v67: Add entity graph tables",
+        );
+        migrate_v66_to_v67(app)?;
+        version = 67;
+    }
+
+    if version < 68 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v67 -> v68: Add memory ANN index table",
+        );
+        migrate_v67_to_v68(app)?;
+        version = 68;
+    }
+
+    if version < 69 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v68 -> v69: Add companion relationship ledger tables",
+        );
+        migrate_v68_to_v69(app)?;
+        version = 69;
+    }
+
+    if version < 70 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v69 -> v70: Add proactive companion message tables",
+        );
+        migrate_v69_to_v70(app)?;
+        version = 70;
+    }
+
+    if version < 71 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v70 -> v71: Add provider key pools and per-key usage",
+        );
+        migrate_v70_to_v71(app)?;
+        version = 71;
+    }
+
+    if version < 72 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v71 -> v72: Add fallback chains and message attempt traces",
+        );
+        migrate_v71_to_v72(app)?;
+        version = 72;
+    }
+
+    if version < 73 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v72 -> v73: Add rolling context summaries and trim reports",
+        );
+        migrate_v72_to_v73(app)?;
+        version = 73;
+    }
+
+    if version < 74 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v73 -> v74: Add reasoning items carried between turns",
+        );
+        migrate_v73_to_v74(app)?;
+        version = 74;
+    }
+
+    if version < 75 {
+        log_info(
+            app,
+            "migrations",
+            "Running migration v74 -> v75: Add citations for grounded replies",
+        );
+        migrate_v74_to_v75(app)?;
+        version = 75;
+    }
+
     // Update the stored version
     set_migration_version(app, version)?;
 
@@ -3582,3 +3702,354 @@ fn migrate_v62_to_v63(app: &AppHandle) -> Result<(), String> {
     .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     Ok(())
 }
+
+fn migrate_v63_to_v64(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS sync_asset_transfers (
+          content_hash TEXT PRIMARY KEY,
+          path TEXT NOT NULL,
+          total_size INTEGER NOT NULL,
+          received_bytes INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v64_to_v65(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS backup_schedule (
+          id INTEGER PRIMARY KEY CHECK (id = 1),
+          enabled INTEGER NOT NULL DEFAULT 0,
+          frequency TEXT NOT NULL DEFAULT 'daily',
+          directory TEXT,
+          has_password INTEGER NOT NULL DEFAULT 0,
+          incrementals_per_full INTEGER NOT NULL DEFAULT 6,
+          keep_daily INTEGER NOT NULL DEFAULT 7,
+          keep_weekly INTEGER NOT NULL DEFAULT 4,
+          last_run_at INTEGER,
+          last_error TEXT,
+          updated_at INTEGER NOT NULL
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v65_to_v66(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS character_memories (
+          character_id TEXT NOT NULL,
+          memory_id TEXT NOT NULL,
+          embedding BLOB NOT NULL,
+          embedding_dim INTEGER NOT NULL DEFAULT 0,
+          embedding_model TEXT,
+          text TEXT NOT NULL,
+          token_count INTEGER NOT NULL DEFAULT 0,
+          category TEXT,
+          importance_score REAL NOT NULL DEFAULT 1.0,
+          is_cold INTEGER NOT NULL DEFAULT 0,
+          is_pinned INTEGER NOT NULL DEFAULT 0,
+          access_count INTEGER NOT NULL DEFAULT 0,
+          source_session_id TEXT,
+          source_memory_id TEXT,
+          promoted_by TEXT NOT NULL DEFAULT 'manual',
+          created_at INTEGER NOT NULL,
+          last_accessed_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          PRIMARY KEY(character_id, memory_id),
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS character_memory_settings (
+          character_id TEXT PRIMARY KEY,
+          enabled INTEGER NOT NULL DEFAULT 0,
+          token_budget INTEGER NOT NULL DEFAULT 600,
+          retrieval_limit INTEGER NOT NULL DEFAULT 4,
+          auto_promote INTEGER NOT NULL DEFAULT 0,
+          promote_min_importance REAL NOT NULL DEFAULT 0.8,
+          promote_min_access_count INTEGER NOT NULL DEFAULT 3,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_character_memories_source ON character_memories(character_id, source_session_id);
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v66_to_v67(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS entity_nodes (
+          id TEXT PRIMARY KEY,
+          character_id TEXT NOT NULL,
+          session_id TEXT,
+          kind TEXT NOT NULL,
+          name TEXT NOT NULL,
+          normalized TEXT NOT NULL,
+          aliases TEXT NOT NULL DEFAULT '[]',
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_mentions (
+          entity_id TEXT NOT NULL,
+          source_kind TEXT NOT NULL CHECK (source_kind IN ('message', 'memory')),
+          source_id TEXT NOT NULL,
+          session_id TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          PRIMARY KEY(entity_id, source_kind, source_id),
+          FOREIGN KEY(entity_id) REFERENCES entity_nodes(id) ON DELETE CASCADE,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_graph_sources (
+          session_id TEXT NOT NULL,
+          source_kind TEXT NOT NULL,
+          source_id TEXT NOT NULL,
+          processed_at INTEGER NOT NULL,
+          PRIMARY KEY(session_id, source_kind, source_id),
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_entity_nodes_scope ON entity_nodes(character_id, session_id, normalized);
+        CREATE INDEX IF NOT EXISTS idx_entity_mentions_source ON entity_mentions(session_id, source_kind, source_id);
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v67_to_v68(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS memory_ann_indexes (
+          session_id TEXT NOT NULL,
+          session_kind TEXT NOT NULL CHECK (session_kind IN ('session', 'group_session')),
+          dim INTEGER NOT NULL,
+          node_count INTEGER NOT NULL,
+          revision INTEGER NOT NULL,
+          graph BLOB NOT NULL,
+          updated_at INTEGER NOT NULL,
+          PRIMARY KEY(session_id, session_kind)
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v68_to_v69(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS companion_relationships (
+          character_id TEXT PRIMARY KEY,
+          closeness REAL NOT NULL,
+          trust REAL NOT NULL,
+          affection REAL NOT NULL,
+          tension REAL NOT NULL,
+          stability REAL NOT NULL,
+          interaction_count INTEGER NOT NULL DEFAULT 0,
+          last_interaction_at INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_relationship_history (
+          id TEXT PRIMARY KEY,
+          character_id TEXT NOT NULL,
+          session_id TEXT,
+          source TEXT NOT NULL,
+          closeness REAL NOT NULL,
+          trust REAL NOT NULL,
+          affection REAL NOT NULL,
+          tension REAL NOT NULL,
+          stability REAL NOT NULL,
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_companion_relationship_history_character ON companion_relationship_history(character_id, created_at);
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v69_to_v70(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS companion_proactive_settings (
+          id INTEGER PRIMARY KEY CHECK(id = 1),
+          enabled INTEGER NOT NULL DEFAULT 0,
+          quiet_start_minute INTEGER NOT NULL DEFAULT 1320,
+          quiet_end_minute INTEGER NOT NULL DEFAULT 480,
+          max_per_day INTEGER NOT NULL DEFAULT 2,
+          min_gap_minutes INTEGER NOT NULL DEFAULT 240,
+          min_idle_minutes INTEGER NOT NULL DEFAULT 180,
+          updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_proactive_messages (
+          id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          character_id TEXT NOT NULL,
+          message_id TEXT NOT NULL,
+          status TEXT NOT NULL DEFAULT 'pending',
+          urgency REAL NOT NULL DEFAULT 0,
+          created_at INTEGER NOT NULL,
+          resolved_at INTEGER,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_companion_proactive_messages_session ON companion_proactive_messages(session_id, status);
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Migration v70 -> v71: pooled API keys per credential and their usage counters
+fn migrate_v70_to_v71(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    // Add api_keys column if it doesn't exist
+    let _ = conn.execute(
+        "ALTER TABLE provider_credentials ADD COLUMN api_keys TEXT",
+        [],
+    );
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS provider_key_usage (
+          credential_id TEXT NOT NULL,
+          key_id TEXT NOT NULL,
+          requests INTEGER NOT NULL DEFAULT 0,
+          rate_limited INTEGER NOT NULL DEFAULT 0,
+          exhausted INTEGER NOT NULL DEFAULT 0,
+          last_used_at INTEGER,
+          last_rate_limited_at INTEGER,
+          PRIMARY KEY (credential_id, key_id)
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v71_to_v72(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS character_fallback_chains (
+          character_id TEXT PRIMARY KEY,
+          chain TEXT NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_attempts (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          attempts TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v72_to_v73(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS session_context_summaries (
+          session_id TEXT PRIMARY KEY,
+          summary TEXT NOT NULL,
+          covered_until INTEGER NOT NULL,
+          token_count INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_context_trims (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          report TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v73_to_v74(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS chat_message_reasoning_items (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          variant_id TEXT,
+          items TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn migrate_v74_to_v75(app: &AppHandle) -> Result<(), String> {
+    let conn = crate::storage_manager::db::open_db(app)?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS chat_message_citations (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          variant_id TEXT,
+          citations TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
@@ -10,9 +10,9 @@ use url::Url;
 use crate::{
     api::{ApiRequest, ApiResponse},
     chat_manager::{
-        provider_adapter::ModelInfo,
+        provider_adapter::{capabilities, ModelInfo},
         sse::SseDecoder,
-        types::{ErrorEnvelope, NormalizedEvent, ProviderCredential},
+        types::{ErrorEnvelope, ModelCapabilities, NormalizedEvent, ProviderCredential},
     },
     infra::abort_manager::AbortRegistry,
     transport::{self, DEFAULT_REQUEST_TIMEOUT_MS},
@@ -36,15 +36,23 @@ pub async fn list_models(
     )?;
     let url = format!("{}api/tags", base_url);
     let client = build_http_client(
+        Some(credential.id.as_str()),
         Some(&credential_runtime_headers(credential)),
         Some(DEFAULT_REQUEST_TIMEOUT_MS),
         false,
     )?;
 
     let request = client.get(&url);
-    let response = transport::send_with_retries(app, "ollama_list_models", request, 2, None)
-        .await
-        .map_err(|err| err.to_string())?;
+    let response = transport::send_with_retries(
+        app,
+        "ollama_list_models",
+        request,
+        2,
+        None,
+        Some(credential.id.as_str()),
+    )
+    .await
+    .map_err(|err| err.to_string())?;
     let status = response.status();
     let text = response
         .text()
@@ -58,7 +66,46 @@ pub async fn list_models(
     let payload = serde_json::from_str::<Value>(&text)
         .map_err(|err| crate::utils::err_to_string(module_path!(), line!(), err))?;
 
-    Ok(parse_models_list(&payload))
+    let mut models = parse_models_list(&payload);
+    let shown = futures_util::future::join_all(
+        models
+            .iter()
+            .map(|model| show_capabilities(app, &client, &base_url, &model.id)),
+    )
+    .await;
+    for (model, capabilities) in models.iter_mut().zip(shown) {
+        model.capabilities = capabilities;
+    }
+    Ok(models)
+}
+
+/// Reads a model's capability list from `/api/show`. Older servers don't
+/// report one, which leaves the capabilities unknown.
+async fn show_capabilities(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    base_url: &str,
+    model: &str,
+) -> ModelCapabilities {
+    let request = client
+        .post(format!("{}api/show", base_url))
+        .json(&json!({ "model": model }));
+    let payload = match transport::send_with_retries(
+        app,
+        "ollama_show_model",
+        request,
+        0,
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(response) if response.status().is_success() => response.json::<Value>().await.ok(),
+        _ => None,
+    };
+    payload
+        .map(|payload| capabilities::from_ollama_show(&payload))
+        .unwrap_or_default()
 }
 
 pub async fn execute_chat_request(
@@ -71,7 +118,12 @@ pub async fn execute_chat_request(
         .as_ref()
         .ok_or_else(|| "Ollama request body is required".to_string())?;
     let chat_body = normalize_request_body(body).await?;
-    let client = build_http_client(req.headers.as_ref(), req.timeout_ms, stream)?;
+    let client = build_http_client(
+        req.credential_id.as_deref(),
+        req.headers.as_ref(),
+        req.timeout_ms,
+        stream,
+    )?;
 
     if stream {
         let request_id = req.request_id.clone().unwrap_or_default();
@@ -95,11 +147,12 @@ fn credential_runtime_headers(credential: &ProviderCredential) -> HashMap<String
 }
 
 fn build_http_client(
+    credential_id: Option<&str>,
     headers: Option<&HashMap<String, String>>,
     timeout_ms: Option<u64>,
     stream: bool,
 ) -> Result<reqwest::Client, String> {
-    let mut builder = reqwest::Client::builder();
+    let mut builder = transport::network::configure(reqwest::Client::builder(), credential_id)?;
     if !stream {
         let timeout = timeout_ms
             .unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS)
@@ -378,10 +431,16 @@ async fn execute_streaming_chat_request(
     request_id: &str,
 ) -> Result<ApiResponse, String> {
     let request = client.post(&req.url).json(chat_body);
-    let response =
-        transport::send_with_retries(app, "ollama_chat_stream", request, 2, Some(request_id))
-            .await
-            .map_err(|err| err.to_string())?;
+    let response = transport::send_with_retries(
+        app,
+        "ollama_chat_stream",
+        request,
+        2,
+        Some(request_id),
+        req.credential_id.as_deref(),
+    )
+    .await
+    .map_err(|err| err.to_string())?;
     let status = response.status();
     if !status.is_success() {
         return error_response_from_http(status.as_u16(), response).await;
@@ -536,7 +595,14 @@ async fn execute_non_streaming_chat_request(
                 emit_abort();
                 return Err("Request was cancelled by user".to_string());
             }
-            response = transport::send_with_retries(app, "ollama_chat", request, 2, request_id) => {
+            response = transport::send_with_retries(
+                app,
+                "ollama_chat",
+                request,
+                2,
+                request_id,
+                req.credential_id.as_deref(),
+            ) => {
                 match response {
                     Ok(response) => response,
                     Err(err) => {
@@ -547,9 +613,16 @@ async fn execute_non_streaming_chat_request(
             }
         }
     } else {
-        transport::send_with_retries(app, "ollama_chat", request, 2, request_id)
-            .await
-            .map_err(|err| err.to_string())?
+        transport::send_with_retries(
+            app,
+            "ollama_chat",
+            request,
+            2,
+            request_id,
+            req.credential_id.as_deref(),
+        )
+        .await
+        .map_err(|err| err.to_string())?
     };
     let status = response.status();
     let status_code = status.as_u16();
@@ -682,6 +755,7 @@ fn parse_models_list(payload: &Value) -> Vec<ModelInfo> {
                 context_length: None,
                 input_price: None,
                 output_price: None,
+                capabilities: ModelCapabilities::default(),
             });
         }
     }
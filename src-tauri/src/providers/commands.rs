@@ -1,6 +1,6 @@
 use tauri::AppHandle;
 
-use crate::chat_manager::provider_adapter::{adapter_for, ModelInfo};
+use crate::chat_manager::provider_adapter::{adapter_for, aws_bedrock, mock, ModelInfo};
 use crate::storage_manager::providers::get_provider_credential;
 use crate::utils::{log_error, log_info};
 use serde_json::Value;
@@ -24,6 +24,9 @@ pub async fn get_remote_models(
     if credential.provider_id == "ollama" {
         return crate::ollama::list_models(&app, &credential).await;
     }
+    if credential.provider_id == "mock" {
+        return Ok(mock::mock_models());
+    }
 
     let is_custom_provider =
         credential.provider_id == "custom" || credential.provider_id == "custom-anthropic";
@@ -91,9 +94,12 @@ pub async fn get_remote_models(
 
     // 3. Prepare request
     let api_key = credential.api_key.as_deref().unwrap_or("");
-    let headers = adapter.headers(api_key, None);
+    let mut headers = adapter.headers(api_key, None);
+    if aws_bedrock::is_bedrock_provider(Some(&credential.provider_id)) {
+        aws_bedrock::sign_listing_headers(&url, &mut headers)?;
+    }
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(Some(credential.id.as_str()))?;
     let mut req_builder = client.get(&url);
 
     for (k, v) in headers {
@@ -204,6 +210,7 @@ fn parse_custom_models_list(config: Option<&Value>, payload: &Value) -> Option<V
             context_length,
             input_price: None,
             output_price: None,
+            capabilities: Default::default(),
         });
     }
 
@@ -56,6 +56,26 @@ fn supported_extra_body_keys(provider_id: &str) -> &'static [&'static str] {
             "frequency_penalty",
             "presence_penalty",
         ],
+        "koboldcpp" => &[
+            "rep_pen",
+            "rep_pen_range",
+            "rep_pen_slope",
+            "min_p",
+            "top_a",
+            "typical",
+            "tfs",
+            "dry_multiplier",
+            "dry_base",
+            "dry_allowed_length",
+            "dry_penalty_last_n",
+            "dry_sequence_breakers",
+            "xtc_threshold",
+            "xtc_probability",
+            "smoothing_factor",
+            "smoothing_curve",
+            "sampler_order",
+            "banned_tokens",
+        ],
         "ollama" => &["options"],
         "anthropic" | "custom-anthropic" | "openrouter" | "openai" | "gemini" | "google"
         | "google-gemini" => &["promptCachingTtl"],
@@ -118,6 +138,13 @@ fn get_all_provider_configs_internal() -> Vec<ProviderConfig> {
         ),
         ("anannas", "Anannas AI", "https://api.anannas.ai/v1"),
         ("groq", "Groq", "https://api.groq.com"),
+        ("cohere", "Cohere", "https://api.cohere.com"),
+        ("azure-openai", "Azure OpenAI", ""),
+        (
+            "aws-bedrock",
+            "AWS Bedrock",
+            "https://bedrock-runtime.us-east-1.amazonaws.com",
+        ),
         ("stability", "Stability AI", "https://api.stability.ai"),
         (
             "automatic1111",
@@ -126,11 +153,18 @@ fn get_all_provider_configs_internal() -> Vec<ProviderConfig> {
         ),
         ("ollama", "Ollama (Local)", ""),
         ("lmstudio", "LM Studio (Local)", ""),
+        ("koboldcpp", "KoboldCpp (Local)", "http://127.0.0.1:5001"),
+        (
+            "text-completion",
+            "Text Completion (vLLM, TabbyAPI)",
+            "http://127.0.0.1:5001",
+        ),
         (
             "intenserp",
             "IntenseRP Next (Local)",
             "http://127.0.0.1:7777/v1",
         ),
+        ("mock", "Mock (Offline)", "mock://local"),
         ("lettuce-host", "Lettuce Host", ""),
         ("lettuce-engine", "Lettuce Engine", ""),
         ("custom", "Custom (OpenAI-format)", ""),
@@ -149,6 +183,7 @@ fn get_all_provider_configs_internal() -> Vec<ProviderConfig> {
                 default_model: None,
                 headers: None,
                 config: None,
+                api_keys: Vec::new(),
             };
             let adapter = adapter_for(&cred);
             let endpoint_full = adapter.endpoint(base);
@@ -231,6 +266,7 @@ pub fn get_system_role(provider_id: &ProviderId) -> Cow<'static, str> {
         default_model: None,
         headers: None,
         config: None,
+        api_keys: Vec::new(),
     };
     adapter_for(&cred).system_role()
 }
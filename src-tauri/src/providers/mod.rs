@@ -1,6 +1,7 @@
 pub mod commands;
 pub mod config;
 pub mod openrouter;
+pub mod probe;
 pub mod util;
 pub mod verify;
 
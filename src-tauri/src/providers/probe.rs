@@ -0,0 +1,154 @@
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::api::{api_request, ApiRequest, ApiResponse};
+use crate::chat_manager::provider_adapter::capabilities::PROBE_SOURCE;
+use crate::chat_manager::request::extract_text;
+use crate::chat_manager::request_builder::build_chat_request;
+use crate::chat_manager::service::require_api_key;
+use crate::chat_manager::tooling::{parse_tool_calls, ToolChoice, ToolConfig, ToolDefinition};
+use crate::chat_manager::types::{ModelCapabilities, ProviderCredential};
+use crate::storage_manager::providers::get_provider_credential;
+use crate::utils::log_info;
+
+const PROBE_MAX_TOKENS: u32 = 256;
+const PROBE_TOOL_NAME: &str = "get_current_time";
+/// A 16x16 solid red PNG.
+const PROBE_IMAGE_DATA_URL: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAABAAAAAQCAIAAACQkWg2AAAAFklEQVR42mP4z8BAEmIY1TCqYfhqAACQ+f8B8u7oVwAAAABJRU5ErkJggg==";
+
+/// Sends two tiny requests to confirm tool calling and image input. A
+/// capability stays unknown when the provider fails for unrelated reasons.
+#[tauri::command]
+pub async fn probe_model_capabilities(
+    app: AppHandle,
+    credential_id: String,
+    model_name: String,
+) -> Result<ModelCapabilities, String> {
+    let credential = get_provider_credential(&app, &credential_id)?;
+    let api_key = require_api_key(&app, &credential, "probe_model_capabilities")?;
+    log_info(
+        &app,
+        "probe_model_capabilities",
+        format!(
+            "probing provider={} model={}",
+            credential.provider_id, model_name
+        ),
+    );
+
+    let tool_config = ToolConfig {
+        tools: vec![ToolDefinition {
+            name: PROBE_TOOL_NAME.to_string(),
+            description: Some("Returns the current time.".to_string()),
+            parameters: json!({ "type": "object", "properties": {} }),
+        }],
+        choice: Some(ToolChoice::Auto),
+    };
+    let tool_messages = vec![json!({
+        "role": "user",
+        "content": format!("What time is it? Call the {} tool to find out.", PROBE_TOOL_NAME),
+    })];
+    let tool_response = send_probe(
+        &app,
+        &credential,
+        &api_key,
+        &model_name,
+        &tool_messages,
+        Some(&tool_config),
+    )
+    .await?;
+    let tools = probe_verdict(&tool_response, |data| {
+        !parse_tool_calls(&credential.provider_id, data).is_empty()
+    });
+
+    let vision_messages = vec![json!({
+        "role": "user",
+        "content": [
+            { "type": "text", "text": "What color is this image? Reply with one word." },
+            { "type": "image_url", "image_url": { "url": PROBE_IMAGE_DATA_URL } }
+        ],
+    })];
+    let vision_response = send_probe(
+        &app,
+        &credential,
+        &api_key,
+        &model_name,
+        &vision_messages,
+        None,
+    )
+    .await?;
+    let vision = probe_verdict(&vision_response, |data| {
+        extract_text(data, Some(credential.provider_id.as_str()))
+            .is_some_and(|text| text.to_lowercase().contains("red"))
+    });
+
+    log_info(
+        &app,
+        "probe_model_capabilities",
+        format!("probe finished tools={:?} vision={:?}", tools, vision),
+    );
+
+    Ok(ModelCapabilities {
+        vision,
+        tools,
+        source: Some(PROBE_SOURCE.to_string()),
+        ..Default::default()
+    })
+}
+
+async fn send_probe(
+    app: &AppHandle,
+    credential: &ProviderCredential,
+    api_key: &str,
+    model_name: &str,
+    messages: &Vec<Value>,
+    tool_config: Option<&ToolConfig>,
+) -> Result<ApiResponse, String> {
+    let built = build_chat_request(
+        credential,
+        api_key,
+        model_name,
+        messages,
+        None,
+        Some(0.0),
+        None,
+        PROBE_MAX_TOKENS,
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        tool_config,
+        false,
+        None,
+        None,
+        false,
+        None,
+    );
+
+    api_request(
+        app.clone(),
+        ApiRequest {
+            url: built.url,
+            method: Some("POST".into()),
+            headers: Some(built.headers),
+            query: None,
+            body: Some(built.body),
+            timeout_ms: Some(crate::transport::DEFAULT_REQUEST_TIMEOUT_MS),
+            stream: Some(false),
+            request_id: built.request_id,
+            provider_id: Some(credential.provider_id.clone()),
+            credential_id: Some(credential.id.clone()),
+        },
+    )
+    .await
+}
+
+/// Request validation errors mean the model rejected the feature; auth,
+/// rate limit and server errors say nothing about it.
+fn probe_verdict(response: &ApiResponse, supported: impl Fn(&Value) -> bool) -> Option<bool> {
+    if response.ok {
+        return Some(supported(response.data()));
+    }
+    matches!(response.status, 400 | 404 | 415 | 422).then_some(false)
+}
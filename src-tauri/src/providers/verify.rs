@@ -23,7 +23,7 @@ pub async fn verify_provider_api_key(
     api_key: Option<String>,
     base_url: Option<String>,
 ) -> Result<VerifyProviderApiKeyResult, String> {
-    let unsupported_providers: &[&str] = &["chutes", "chutes.ai"];
+    let unsupported_providers: &[&str] = &["chutes", "chutes.ai", "mock"];
 
     if unsupported_providers.contains(&provider_id.as_str()) {
         return Ok(VerifyProviderApiKeyResult {
@@ -79,10 +79,12 @@ pub async fn verify_provider_api_key(
         }
     };
 
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let client = crate::transport::network::configure(
+        Client::builder().timeout(Duration::from_secs(10)),
+        credential_id.as_deref(),
+    )?
+    .build()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
     let headers = build_headers(&pid, &resolved_key)?;
     let url = build_verify_url(&pid, &base);
@@ -1,11 +1,13 @@
 use base64::{engine::general_purpose, Engine as _};
-use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
 use chacha20poly1305::{XChaCha20Poly1305, XNonce};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
@@ -59,9 +61,19 @@ fn open_backup_file(_app: &tauri::AppHandle, path: &str) -> Result<File, String>
     }
 }
 
-const BACKUP_VERSION: u32 = 2;
+const BACKUP_VERSION: u32 = 3;
+/// Oldest archive version that can still be verified and imported.
+const MIN_BACKUP_VERSION: u32 = 2;
+/// First version with Argon2id keys, per-entry nonces and an authenticated manifest.
+const AUTHENTICATED_BACKUP_VERSION: u32 = 3;
 
-#[derive(Serialize, Deserialize)]
+const ARGON2ID_ALGORITHM: &str = "argon2id";
+// Upper bounds accepted from a manifest, so a crafted archive cannot demand absurd work.
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 64;
+const MAX_KDF_PARALLELISM: u32 = 16;
+
+#[derive(Serialize, Deserialize, Clone)]
 struct BackupManifest {
     version: u32,
     created_at: u64,
@@ -71,9 +83,158 @@ struct BackupManifest {
     salt: Option<String>,
     /// Nonce used for encryption (base64)
     nonce: Option<String>,
+    /// Key derivation parameters (v3+). Absent on v2 archives, which use BLAKE3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf: Option<BackupKdfParams>,
+    /// BLAKE3 digest of every stored entry (v3+), authenticated through `manifest_mac`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<BackupEntryDigest>,
+    /// Position in a scheduled backup chain. Absent on standalone exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain: Option<BackupChainInfo>,
+    /// Keyed BLAKE3 MAC over this manifest with the field itself cleared (v3+).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    manifest_mac: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct BackupKdfParams {
+    algorithm: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Default for BackupKdfParams {
+    fn default() -> Self {
+        // OWASP-recommended Argon2id profile that still runs in about a second on phones.
+        Self {
+            algorithm: ARGON2ID_ALGORITHM.to_string(),
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct BackupEntryDigest {
+    name: String,
+    blake3: String,
+}
+
+/// Links an archive written by the backup scheduler to the rest of its chain.
+///
+/// A full archive is its own base. An incremental archive records its parent and
+/// carries only the sections touched since the parent's `change_id`; restoring it
+/// replays the whole chain from the base.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupChainInfo {
+    pub backup_id: String,
+    pub base_id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Highest `sync_changes` id covered by this archive.
+    pub change_id: i64,
+    #[serde(default)]
+    pub sections: Vec<BackupSectionChange>,
+    /// Media paths deleted since the parent archive.
+    #[serde(default)]
+    pub removed_media: Vec<String>,
+}
+
+impl BackupChainInfo {
+    pub fn is_incremental(&self) -> bool {
+        self.parent_id.is_some()
+    }
+}
+
+/// One data section of an incremental archive. With `full` set the stored section
+/// replaces the restored one outright; otherwise every root entity listed in `roots`
+/// is replaced by its stored copy, or removed when the archive has none.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupSectionChange {
+    pub name: String,
+    #[serde(default)]
+    pub full: bool,
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
+/// Identifies the root entity of an exported section item, matching the entity ids
+/// the sync change log uses for the same rows.
+fn section_root_id(section: &str, item: &JsonValue) -> Option<String> {
+    match section {
+        "meta" => item.get("key").and_then(|v| v.as_str()).map(str::to_string),
+        "secrets" => {
+            let service = item.get("service").and_then(|v| v.as_str())?;
+            let account = item.get("account").and_then(|v| v.as_str())?;
+            Some(format!("{}::{}", service, account))
+        }
+        _ => item.get("id").and_then(|v| v.as_str()).map(str::to_string),
+    }
+}
+
+/// Section data to store for `chain`. Full archives keep everything; incremental
+/// archives skip untouched sections and keep only the changed roots of the rest.
+fn incremental_section_json<'a>(
+    chain: Option<&BackupChainInfo>,
+    section: &str,
+    data: &'a JsonValue,
+) -> Option<Cow<'a, JsonValue>> {
+    let Some(chain) = chain.filter(|chain| chain.is_incremental()) else {
+        return Some(Cow::Borrowed(data));
+    };
+    let change = chain
+        .sections
+        .iter()
+        .find(|change| change.name == section)?;
+    if change.full {
+        return Some(Cow::Borrowed(data));
+    }
+    let roots: HashSet<&str> = change.roots.iter().map(String::as_str).collect();
+    let items = data
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| {
+                    section_root_id(section, item).is_some_and(|id| roots.contains(id.as_str()))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Some(Cow::Owned(JsonValue::Array(items)))
+}
+
+/// Applies one incremental section on top of the section restored so far.
+fn merge_incremental_section(
+    base: Option<JsonValue>,
+    change: &BackupSectionChange,
+    delta: Option<JsonValue>,
+) -> Option<JsonValue> {
+    if change.full {
+        return delta.or(base);
+    }
+    let roots: HashSet<&str> = change.roots.iter().map(String::as_str).collect();
+    let mut items = match base {
+        Some(JsonValue::Array(items)) => items,
+        _ => Vec::new(),
+    };
+    items.retain(|item| {
+        section_root_id(&change.name, item).is_none_or(|id| !roots.contains(id.as_str()))
+    });
+    if let Some(JsonValue::Array(delta_items)) = delta {
+        items.extend(delta_items);
+    }
+    Some(JsonValue::Array(items))
 }
 
-/// Derive encryption key from password using BLAKE3
+/// Derive encryption key from password using BLAKE3 (v2 archives only)
 fn derive_key_from_password(password: &str, salt: &[u8; 16]) -> [u8; 32] {
     let mut hasher = blake3::Hasher::new();
     hasher.update(password.as_bytes());
@@ -85,6 +246,37 @@ fn derive_key_from_password(password: &str, salt: &[u8; 16]) -> [u8; 32] {
     key
 }
 
+/// Derive the master key for v3 archives with Argon2id
+fn derive_key_argon2id(
+    password: &str,
+    salt: &[u8; 16],
+    kdf: &BackupKdfParams,
+) -> Result<[u8; 32], String> {
+    if kdf.algorithm != ARGON2ID_ALGORITHM {
+        return Err(format!(
+            "Unsupported backup key derivation: {}",
+            kdf.algorithm
+        ));
+    }
+    if kdf.memory_kib > MAX_KDF_MEMORY_KIB
+        || kdf.iterations == 0
+        || kdf.iterations > MAX_KDF_ITERATIONS
+        || kdf.parallelism == 0
+        || kdf.parallelism > MAX_KDF_PARALLELISM
+    {
+        return Err("Backup key derivation parameters are out of range".to_string());
+    }
+
+    let params = argon2::Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut key = [0u8; 32];
+    argon2
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(key)
+}
+
 /// Encrypt data using XChaCha20-Poly1305
 fn encrypt_data(data: &[u8], key: &[u8; 32], nonce: &[u8; 24]) -> Result<Vec<u8>, String> {
     let cipher = XChaCha20Poly1305::new(key.into());
@@ -103,6 +295,224 @@ fn decrypt_data(data: &[u8], key: &[u8; 32], nonce: &[u8; 24]) -> Result<Vec<u8>
     })
 }
 
+fn decode_fixed<const N: usize>(value: Option<&String>, label: &str) -> Result<[u8; N], String> {
+    let encoded = value.ok_or_else(|| format!("Missing {} in encrypted backup", label))?;
+    let bytes = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("Invalid {} length in encrypted backup", label))
+}
+
+/// Key material for one archive.
+///
+/// v2 archives share a BLAKE3-derived key and a single nonce across all entries.
+/// v3 archives derive a master key with Argon2id, split it into an encryption key and
+/// a manifest MAC key, give every entry its own nonce and bind its ciphertext to the
+/// entry name, and check each entry against the digests in the authenticated manifest.
+#[derive(Clone)]
+struct BackupCipher {
+    version: u32,
+    key: [u8; 32],
+    mac_key: [u8; 32],
+    nonce: [u8; 24],
+    entry_digests: Option<std::collections::HashMap<String, String>>,
+}
+
+impl BackupCipher {
+    /// Fresh v3 key material for a new export. Returns the cipher and the salt to store.
+    fn for_export(password: &str, kdf: &BackupKdfParams) -> Result<(Self, [u8; 16]), String> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let master = derive_key_argon2id(password, &salt, kdf)?;
+        Ok((Self::from_master(BACKUP_VERSION, &master, nonce), salt))
+    }
+
+    /// Key material for reading an existing archive described by `manifest`.
+    fn open(manifest: &BackupManifest, password: &str) -> Result<Self, String> {
+        let salt = decode_fixed::<16>(manifest.salt.as_ref(), "salt")?;
+        let nonce = decode_fixed::<24>(manifest.nonce.as_ref(), "nonce")?;
+
+        if manifest.version < AUTHENTICATED_BACKUP_VERSION {
+            let key = derive_key_from_password(password, &salt);
+            return Ok(Self {
+                version: manifest.version,
+                key,
+                mac_key: [0u8; 32],
+                nonce,
+                entry_digests: None,
+            });
+        }
+
+        let kdf = manifest
+            .kdf
+            .as_ref()
+            .ok_or_else(|| "Missing key derivation parameters in backup".to_string())?;
+        let master = derive_key_argon2id(password, &salt, kdf)?;
+        let mut cipher = Self::from_master(manifest.version, &master, nonce);
+        cipher.entry_digests = Some(
+            manifest
+                .entries
+                .iter()
+                .map(|entry| (entry.name.clone(), entry.blake3.clone()))
+                .collect(),
+        );
+        Ok(cipher)
+    }
+
+    fn from_master(version: u32, master: &[u8; 32], nonce: [u8; 24]) -> Self {
+        Self {
+            version,
+            key: blake3::derive_key("lettuce backup v3 entry encryption", master),
+            mac_key: blake3::derive_key("lettuce backup v3 manifest mac", master),
+            nonce,
+            entry_digests: None,
+        }
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.version >= AUTHENTICATED_BACKUP_VERSION
+    }
+
+    fn entry_nonce(&self, name: &str) -> [u8; 24] {
+        if !self.is_authenticated() {
+            return self.nonce;
+        }
+        let mut hasher = blake3::Hasher::new_derive_key("lettuce backup v3 entry nonce");
+        hasher.update(&self.nonce);
+        hasher.update(name.as_bytes());
+        let mut nonce = [0u8; 24];
+        hasher.finalize_xof().fill(&mut nonce);
+        nonce
+    }
+
+    fn encrypt_entry(&self, name: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+        if !self.is_authenticated() {
+            return encrypt_data(data, &self.key, &self.nonce);
+        }
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let xnonce: XNonce = self.entry_nonce(name).into();
+        cipher
+            .encrypt(
+                &xnonce,
+                Payload {
+                    msg: data,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|e| {
+                crate::utils::err_msg(module_path!(), line!(), format!("Encryption failed: {}", e))
+            })
+    }
+
+    /// Decrypts an entry stored under `name`. On v3 archives the stored bytes must also
+    /// match the digest recorded for that name in the authenticated manifest.
+    fn decrypt_entry(&self, name: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+        if !self.is_authenticated() {
+            return decrypt_data(data, &self.key, &self.nonce);
+        }
+        self.check_entry_digest(name, data)?;
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let xnonce: XNonce = self.entry_nonce(name).into();
+        cipher
+            .decrypt(
+                &xnonce,
+                Payload {
+                    msg: data,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|e| {
+                crate::utils::err_msg(module_path!(), line!(), format!("Decryption failed: {}", e))
+            })
+    }
+
+    fn check_entry_digest(&self, name: &str, data: &[u8]) -> Result<(), String> {
+        let Some(digests) = &self.entry_digests else {
+            return Ok(());
+        };
+        let expected = digests
+            .get(name)
+            .ok_or_else(|| format!("Backup entry {} is not listed in the manifest", name))?;
+        let matches = blake3::Hash::from_hex(expected)
+            .map(|expected| expected == blake3::hash(data))
+            .unwrap_or(false);
+        if matches {
+            Ok(())
+        } else {
+            Err(format!("Backup entry {} failed integrity check", name))
+        }
+    }
+
+    /// Fails when the authenticated manifest lists `path`, plain or encrypted, but the
+    /// archive has no such entry. Stripping an entry must not read as an empty section.
+    fn check_entry_missing(&self, path: &str) -> Result<(), String> {
+        let Some(digests) = &self.entry_digests else {
+            return Ok(());
+        };
+        let encrypted_path = format!("{}.enc", path);
+        for name in [encrypted_path.as_str(), path] {
+            if digests.contains_key(name) {
+                return Err(format!(
+                    "Backup entry {} is listed in the manifest but missing from the archive",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn manifest_mac(&self, manifest: &BackupManifest) -> Result<blake3::Hash, String> {
+        let mut unsigned = manifest.clone();
+        unsigned.manifest_mac = None;
+        let bytes = serde_json::to_vec(&unsigned)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        Ok(blake3::keyed_hash(&self.mac_key, &bytes))
+    }
+
+    fn sign_manifest(&self, manifest: &mut BackupManifest) -> Result<(), String> {
+        manifest.manifest_mac = Some(self.manifest_mac(manifest)?.to_hex().to_string());
+        Ok(())
+    }
+
+    /// True when the manifest MAC matches. A mismatch means a wrong password or a
+    /// modified manifest; v2 archives carry no MAC and always pass.
+    fn verify_manifest(&self, manifest: &BackupManifest) -> bool {
+        if !self.is_authenticated() {
+            return true;
+        }
+        let Some(stored) = manifest
+            .manifest_mac
+            .as_deref()
+            .and_then(|mac| blake3::Hash::from_hex(mac).ok())
+        else {
+            return false;
+        };
+        self.manifest_mac(manifest)
+            .map(|computed| computed == stored)
+            .unwrap_or(false)
+    }
+}
+
+fn check_backup_version(manifest: &BackupManifest) -> Result<(), String> {
+    if manifest.version < MIN_BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} is not supported. This app requires backup version {} or newer.",
+            manifest.version, MIN_BACKUP_VERSION
+        ));
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} was created by a newer app version. Please update to import it.",
+            manifest.version
+        ));
+    }
+    Ok(())
+}
+
 /// Get the downloads directory path
 fn get_downloads_dir() -> Result<PathBuf, String> {
     #[cfg(target_os = "android")]
@@ -277,7 +687,7 @@ fn export_settings(app: &tauri::AppHandle) -> Result<JsonValue, String> {
 fn export_provider_credentials(app: &tauri::AppHandle) -> Result<Vec<JsonValue>, String> {
     let conn = open_db(app)?;
     let mut stmt = conn
-        .prepare("SELECT id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config FROM provider_credentials")
+        .prepare("SELECT id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config, api_keys FROM provider_credentials")
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
     let rows = stmt
@@ -292,6 +702,7 @@ fn export_provider_credentials(app: &tauri::AppHandle) -> Result<Vec<JsonValue>,
                 "default_model": r.get::<_, Option<String>>(6)?,
                 "headers": r.get::<_, Option<String>>(7)?,
                 "config": r.get::<_, Option<String>>(8)?,
+                "api_keys": r.get::<_, Option<String>>(9)?,
             }))
         })
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
@@ -1133,7 +1544,34 @@ pub async fn backup_export(
     app: tauri::AppHandle,
     password: Option<String>,
 ) -> Result<String, String> {
-    let storage = storage_root(&app)?;
+    // Generate timestamp for filename
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let filename = format!("lettuce_backup_{}.lettuce", timestamp);
+    let downloads = get_downloads_dir()?;
+    let output_path = downloads.join(&filename);
+
+    let password = require_non_empty_password(
+        password.as_deref(),
+        "backup export. Unencrypted backups are no longer allowed",
+    )?;
+    write_backup_archive(&app, &output_path, password, None, None)?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Writes an encrypted archive to `output_path`.
+///
+/// Full archives contain every table and media directory. When `chain` describes an
+/// incremental archive, only the sections listed in it are written (filtered down to
+/// the changed root entities) and only media paths in `media` are included.
+pub(crate) fn write_backup_archive(
+    app: &tauri::AppHandle,
+    output_path: &Path,
+    password: &str,
+    chain: Option<BackupChainInfo>,
+    media: Option<&HashSet<String>>,
+) -> Result<(), String> {
+    let storage = storage_root(app)?;
     let images_dir = storage.join("images");
     let avatars_dir = storage.join("avatars");
     let attachments_dir = storage.join("attachments");
@@ -1144,31 +1582,19 @@ pub async fn backup_export(
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
         .join("generated_images");
 
-    // Generate timestamp for filename
-    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("lettuce_backup_{}.lettuce", timestamp);
-    let downloads = get_downloads_dir()?;
-    let output_path = downloads.join(&filename);
-
     log_info(
-        &app,
+        app,
         "backup",
         format!(
-            "Starting backup export (v2 JSON format) to {:?}",
+            "Starting backup export (v3 JSON format) to {:?}",
             output_path
         ),
     );
 
-    let password = require_non_empty_password(
-        password.as_deref(),
-        "backup export. Unencrypted backups are no longer allowed",
-    )?;
-    let mut salt = [0u8; 16];
-    let mut nonce = [0u8; 24];
-    OsRng.fill_bytes(&mut salt);
-    OsRng.fill_bytes(&mut nonce);
-    let key = derive_key_from_password(password, &salt);
-    let encryption = Some((salt, nonce, key));
+    let kdf = BackupKdfParams::default();
+    let (cipher, salt) = BackupCipher::for_export(password, &kdf)?;
+    let encryption = Some(cipher);
+    let mut entry_digests: Vec<BackupEntryDigest> = Vec::new();
 
     // Create the zip file
     let file = File::create(&output_path)
@@ -1180,154 +1606,218 @@ pub async fn backup_export(
     let add_json_to_zip = |zip: &mut ZipWriter<File>,
                            name: &str,
                            data: &JsonValue,
-                           enc: &Option<([u8; 16], [u8; 24], [u8; 32])>|
+                           enc: &Option<BackupCipher>,
+                           digests: &mut Vec<BackupEntryDigest>|
      -> Result<(), String> {
-        let json_bytes = serde_json::to_string_pretty(data)
+        let Some(data) = incremental_section_json(chain.as_ref(), name, data) else {
+            return Ok(());
+        };
+        let json_bytes = serde_json::to_string_pretty(data.as_ref())
             .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
             .into_bytes();
-        if let Some((_, nonce, key)) = enc {
-            let encrypted = encrypt_data(&json_bytes, key, nonce)?;
-            zip.start_file(format!("data/{}.json.enc", name), options)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-            zip.write_all(&encrypted)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let (zip_path, stored) = if let Some(cipher) = enc {
+            let zip_path = format!("data/{}.json.enc", name);
+            let encrypted = cipher.encrypt_entry(&zip_path, &json_bytes)?;
+            (zip_path, encrypted)
         } else {
-            zip.start_file(format!("data/{}.json", name), options)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-            zip.write_all(&json_bytes)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        }
+            (format!("data/{}.json", name), json_bytes)
+        };
+        zip.start_file(zip_path.as_str(), options)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        zip.write_all(&stored)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        digests.push(BackupEntryDigest {
+            name: zip_path,
+            blake3: blake3::hash(&stored).to_hex().to_string(),
+        });
         Ok(())
     };
 
     // Export all tables to JSON
-    log_info(&app, "backup", "Exporting metadata...");
-    let meta = export_meta(&app)?;
-    add_json_to_zip(&mut zip, "meta", &serde_json::json!(meta), &encryption)?;
+    log_info(app, "backup", "Exporting metadata...");
+    let meta = export_meta(app)?;
+    add_json_to_zip(
+        &mut zip,
+        "meta",
+        &serde_json::json!(meta),
+        &encryption,
+        &mut entry_digests,
+    )?;
 
-    log_info(&app, "backup", "Exporting settings...");
-    let settings = export_settings(&app)?;
-    add_json_to_zip(&mut zip, "settings", &settings, &encryption)?;
+    log_info(app, "backup", "Exporting settings...");
+    let settings = export_settings(app)?;
+    add_json_to_zip(
+        &mut zip,
+        "settings",
+        &settings,
+        &encryption,
+        &mut entry_digests,
+    )?;
 
-    log_info(&app, "backup", "Exporting provider credentials...");
-    let providers = export_provider_credentials(&app)?;
+    log_info(app, "backup", "Exporting provider credentials...");
+    let providers = export_provider_credentials(app)?;
     add_json_to_zip(
         &mut zip,
         "provider_credentials",
         &serde_json::json!(providers),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting models...");
-    let models = export_models(&app)?;
-    add_json_to_zip(&mut zip, "models", &serde_json::json!(models), &encryption)?;
+    log_info(app, "backup", "Exporting models...");
+    let models = export_models(app)?;
+    add_json_to_zip(
+        &mut zip,
+        "models",
+        &serde_json::json!(models),
+        &encryption,
+        &mut entry_digests,
+    )?;
 
-    log_info(&app, "backup", "Exporting model pricing cache...");
-    let pricing_cache = export_model_pricing_cache(&app)?;
+    log_info(app, "backup", "Exporting model pricing cache...");
+    let pricing_cache = export_model_pricing_cache(app)?;
     add_json_to_zip(
         &mut zip,
         "model_pricing_cache",
         &serde_json::json!(pricing_cache),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting secrets...");
-    let secrets = export_secrets(&app)?;
+    log_info(app, "backup", "Exporting secrets...");
+    let secrets = export_secrets(app)?;
     add_json_to_zip(
         &mut zip,
         "secrets",
         &serde_json::json!(secrets),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting prompt templates...");
-    let templates = export_prompt_templates(&app)?;
+    log_info(app, "backup", "Exporting prompt templates...");
+    let templates = export_prompt_templates(app)?;
     add_json_to_zip(
         &mut zip,
         "prompt_templates",
         &serde_json::json!(templates),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting chat templates...");
-    let chat_templates = export_chat_templates(&app)?;
+    log_info(app, "backup", "Exporting chat templates...");
+    let chat_templates = export_chat_templates(app)?;
     add_json_to_zip(
         &mut zip,
         "chat_templates",
         &serde_json::json!(chat_templates),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting personas...");
-    let personas = export_personas(&app)?;
+    log_info(app, "backup", "Exporting personas...");
+    let personas = export_personas(app)?;
     add_json_to_zip(
         &mut zip,
         "personas",
         &serde_json::json!(personas),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting characters...");
-    let characters = export_characters(&app)?;
+    log_info(app, "backup", "Exporting characters...");
+    let characters = export_characters(app)?;
     add_json_to_zip(
         &mut zip,
         "characters",
         &serde_json::json!(characters),
         &encryption,
+        &mut entry_digests,
+    )?;
+
+    log_info(app, "backup", "Exporting character memories...");
+    let character_memories = {
+        let conn = open_db(app)?;
+        super::character_memories::export_for_backup(&conn)?
+    };
+    add_json_to_zip(
+        &mut zip,
+        "character_memories",
+        &serde_json::json!(character_memories),
+        &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting sessions...");
-    let sessions = export_sessions(&app)?;
+    log_info(app, "backup", "Exporting companion relationships...");
+    let companion_relationships = {
+        let conn = open_db(app)?;
+        super::companion_relationships::export_for_backup(&conn)?
+    };
+    add_json_to_zip(
+        &mut zip,
+        "companion_relationships",
+        &serde_json::json!(companion_relationships),
+        &encryption,
+        &mut entry_digests,
+    )?;
+
+    log_info(app, "backup", "Exporting sessions...");
+    let sessions = export_sessions(app)?;
     add_json_to_zip(
         &mut zip,
         "sessions",
         &serde_json::json!(sessions),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting group sessions...");
-    let group_sessions = export_group_sessions(&app)?;
+    log_info(app, "backup", "Exporting group sessions...");
+    let group_sessions = export_group_sessions(app)?;
     add_json_to_zip(
         &mut zip,
         "group_sessions",
         &serde_json::json!(group_sessions),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting group character presets...");
-    let group_characters = export_group_characters(&app)?;
+    log_info(app, "backup", "Exporting group character presets...");
+    let group_characters = export_group_characters(app)?;
     add_json_to_zip(
         &mut zip,
         "group_characters",
         &serde_json::json!(group_characters),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting usage records...");
-    let usage_data = export_usage_records(&app)?;
+    log_info(app, "backup", "Exporting usage records...");
+    let usage_data = export_usage_records(app)?;
     add_json_to_zip(
         &mut zip,
         "usage_records",
         &serde_json::json!(usage_data),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting lorebooks...");
-    let lorebooks = export_lorebooks(&app)?;
+    log_info(app, "backup", "Exporting lorebooks...");
+    let lorebooks = export_lorebooks(app)?;
     add_json_to_zip(
         &mut zip,
         "lorebooks",
         &serde_json::json!(lorebooks),
         &encryption,
+        &mut entry_digests,
     )?;
 
-    log_info(&app, "backup", "Exporting creation helper sessions...");
-    let creation_helper_sessions = export_creation_helper_sessions(&app)?;
+    log_info(app, "backup", "Exporting creation helper sessions...");
+    let creation_helper_sessions = export_creation_helper_sessions(app)?;
     add_json_to_zip(
         &mut zip,
         "creation_helper_sessions",
         &serde_json::json!(creation_helper_sessions),
         &encryption,
+        &mut entry_digests,
     )?;
 
     // Add images directory
@@ -1338,8 +1828,10 @@ pub async fn backup_export(
             "images",
             options,
             encryption.as_ref(),
+            media,
+            &mut entry_digests,
         )?;
-        log_info(&app, "backup", "Added images to archive");
+        log_info(app, "backup", "Added images to archive");
     }
 
     // Add avatars directory
@@ -1350,8 +1842,10 @@ pub async fn backup_export(
             "avatars",
             options,
             encryption.as_ref(),
+            media,
+            &mut entry_digests,
         )?;
-        log_info(&app, "backup", "Added avatars to archive");
+        log_info(app, "backup", "Added avatars to archive");
     }
 
     // Add attachments directory
@@ -1362,8 +1856,10 @@ pub async fn backup_export(
             "attachments",
             options,
             encryption.as_ref(),
+            media,
+            &mut entry_digests,
         )?;
-        log_info(&app, "backup", "Added attachments to archive");
+        log_info(app, "backup", "Added attachments to archive");
     }
 
     if session_attachments_dir.exists() {
@@ -1373,8 +1869,10 @@ pub async fn backup_export(
             "sessions",
             options,
             encryption.as_ref(),
+            media,
+            &mut entry_digests,
         )?;
-        log_info(&app, "backup", "Added session attachments to archive");
+        log_info(app, "backup", "Added session attachments to archive");
     }
 
     if generated_images_dir.exists() {
@@ -1384,8 +1882,10 @@ pub async fn backup_export(
             "generated_images",
             options,
             encryption.as_ref(),
+            media,
+            &mut entry_digests,
         )?;
-        log_info(&app, "backup", "Added generated images to archive");
+        log_info(app, "backup", "Added generated images to archive");
     }
 
     // Create manifest
@@ -1394,27 +1894,36 @@ pub async fn backup_export(
         .unwrap()
         .as_millis() as u64;
 
-    let app_version = crate::utils::app_version(&app);
+    let app_version = crate::utils::app_version(app);
 
-    let (salt, nonce, key) = encryption
+    let cipher = encryption
         .as_ref()
         .expect("backup export encryption should always be configured");
     let marker = b"LETTUCE_BACKUP_VERIFIED";
-    let encrypted_marker = encrypt_data(marker, key, nonce)?;
+    let encrypted_marker = cipher.encrypt_entry("encrypted_marker.bin", marker)?;
 
     zip.start_file("encrypted_marker.bin", options)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     zip.write_all(&encrypted_marker)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    entry_digests.push(BackupEntryDigest {
+        name: "encrypted_marker.bin".to_string(),
+        blake3: blake3::hash(&encrypted_marker).to_hex().to_string(),
+    });
 
-    let manifest = BackupManifest {
+    let mut manifest = BackupManifest {
         version: BACKUP_VERSION,
         created_at: now,
         app_version,
         encrypted: true,
         salt: Some(general_purpose::STANDARD.encode(salt)),
-        nonce: Some(general_purpose::STANDARD.encode(nonce)),
+        nonce: Some(general_purpose::STANDARD.encode(cipher.nonce)),
+        kdf: Some(kdf),
+        entries: entry_digests,
+        chain,
+        manifest_mac: None,
     };
+    cipher.sign_manifest(&mut manifest)?;
 
     // Add manifest
     let manifest_json = serde_json::to_string_pretty(&manifest)
@@ -1428,12 +1937,12 @@ pub async fn backup_export(
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
     log_info(
-        &app,
+        app,
         "backup",
         format!("Backup export complete: {:?}", output_path),
     );
 
-    Ok(output_path.to_string_lossy().to_string())
+    Ok(())
 }
 
 /// Helper to add a directory recursively to zip (with optional encryption)
@@ -1442,7 +1951,9 @@ fn add_directory_to_zip<W: Write + std::io::Seek>(
     dir: &PathBuf,
     prefix: &str,
     options: SimpleFileOptions,
-    encryption: Option<&([u8; 16], [u8; 24], [u8; 32])>,
+    encryption: Option<&BackupCipher>,
+    media: Option<&HashSet<String>>,
+    digests: &mut Vec<BackupEntryDigest>,
 ) -> Result<(), String> {
     for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
@@ -1452,24 +1963,33 @@ fn add_directory_to_zip<W: Write + std::io::Seek>(
                 .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
                 .to_string_lossy();
 
+            if let Some(media) = media {
+                // Incremental archives only carry media the change log saw change
+                let media_path = format!("{}/{}", prefix, relative.replace('\\', "/"));
+                if !media.contains(&media_path) {
+                    continue;
+                }
+            }
+
             let bytes = fs::read(path)
                 .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
-            if let Some((_, nonce, key)) = encryption {
+            let (zip_path, stored) = if let Some(cipher) = encryption {
                 // Encrypt the file content
-                let encrypted = encrypt_data(&bytes, key, nonce)?;
                 let zip_path = format!("{}/{}.enc", prefix, relative);
-                zip.start_file(&zip_path, options)
-                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-                zip.write_all(&encrypted)
-                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+                let encrypted = cipher.encrypt_entry(&zip_path, &bytes)?;
+                (zip_path, encrypted)
             } else {
-                let zip_path = format!("{}/{}", prefix, relative);
-                zip.start_file(&zip_path, options)
-                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-                zip.write_all(&bytes)
-                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-            }
+                (format!("{}/{}", prefix, relative), bytes)
+            };
+            zip.start_file(&zip_path, options)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            zip.write_all(&stored)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            digests.push(BackupEntryDigest {
+                name: zip_path,
+                blake3: blake3::hash(&stored).to_hex().to_string(),
+            });
         }
     }
     Ok(())
@@ -1599,8 +2119,8 @@ fn import_provider_credentials(app: &tauri::AppHandle, data: &JsonValue) -> Resu
     if let Some(arr) = data.as_array() {
         for item in arr {
             conn.execute(
-                "INSERT INTO provider_credentials (id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO provider_credentials (id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config, api_keys)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
                 params![
                     item.get("id").and_then(|v| v.as_str()),
                     item.get("provider_id").and_then(|v| v.as_str()),
@@ -1611,6 +2131,7 @@ fn import_provider_credentials(app: &tauri::AppHandle, data: &JsonValue) -> Resu
                     item.get("default_model").and_then(|v| v.as_str()),
                     item.get("headers").and_then(|v| v.as_str()),
                     item.get("config").and_then(|v| v.as_str()),
+                    item.get("api_keys").and_then(|v| v.as_str()),
                 ],
             ).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
         }
@@ -1777,7 +2298,11 @@ fn import_personas(app: &tauri::AppHandle, data: &JsonValue) -> Result<(), Strin
     let conn = open_db(app)?;
     conn.execute("DELETE FROM personas", [])
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    insert_personas(&conn, data)
+}
 
+/// Inserts exported persona rows without touching existing ones.
+pub(crate) fn insert_personas(conn: &rusqlite::Connection, data: &JsonValue) -> Result<(), String> {
     if let Some(arr) = data.as_array() {
         for item in arr {
             conn.execute(
@@ -1818,6 +2343,15 @@ fn import_characters(app: &tauri::AppHandle, data: &JsonValue) -> Result<(), Str
     conn.execute("DELETE FROM characters", [])
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
+    insert_characters(app, &conn, data)
+}
+
+/// Inserts exported characters with their rules, scenes and scene variants.
+pub(crate) fn insert_characters(
+    app: &tauri::AppHandle,
+    conn: &rusqlite::Connection,
+    data: &JsonValue,
+) -> Result<(), String> {
     let mut char_count = 0;
     let mut scene_count = 0;
     let mut variant_count = 0;
@@ -1996,6 +2530,15 @@ fn import_sessions(app: &tauri::AppHandle, data: &JsonValue) -> Result<(), Strin
     conn.execute("DELETE FROM sessions", [])
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
+    insert_sessions(app, &mut conn, data)
+}
+
+/// Inserts exported sessions with their messages, variants and memory embeddings.
+pub(crate) fn insert_sessions(
+    app: &tauri::AppHandle,
+    conn: &mut rusqlite::Connection,
+    data: &JsonValue,
+) -> Result<(), String> {
     let mut session_count = 0;
     let mut message_count = 0;
     let mut variant_count = 0;
@@ -2067,7 +2610,7 @@ fn import_sessions(app: &tauri::AppHandle, data: &JsonValue) -> Result<(), Strin
                 ],
             ).map_err(|e| crate::utils::err_msg(module_path!(), line!(), format!("Failed to insert session (character_id={}): {}", character_id, e)))?;
             crate::storage_manager::memory_embeddings::replace_all_from_json(
-                &mut conn,
+                conn,
                 session_id,
                 crate::storage_manager::memory_embeddings::SessionKind::Session,
                 item.get("memory_embeddings").and_then(|v| v.as_str()),
@@ -2395,7 +2938,14 @@ fn import_lorebooks(app: &tauri::AppHandle, data: &JsonValue) -> Result<(), Stri
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     conn.execute("DELETE FROM lorebooks", [])
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    insert_lorebooks(&conn, data)
+}
 
+/// Inserts exported lorebooks and their entries.
+pub(crate) fn insert_lorebooks(
+    conn: &rusqlite::Connection,
+    data: &JsonValue,
+) -> Result<(), String> {
     if let Some(arr) = data.as_array() {
         for item in arr {
             let lorebook_id = item.get("id").and_then(|v| v.as_str()).unwrap_or("");
@@ -2707,28 +3257,12 @@ pub fn backup_verify_password(
 
     require_encrypted_backup(&manifest)?;
 
-    let salt_b64 = manifest
-        .salt
-        .ok_or_else(|| "Missing salt in encrypted backup".to_string())?;
-    let nonce_b64 = manifest
-        .nonce
-        .ok_or_else(|| "Missing nonce in encrypted backup".to_string())?;
-
-    let salt_vec = general_purpose::STANDARD
-        .decode(&salt_b64)
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    let nonce_vec = general_purpose::STANDARD
-        .decode(&nonce_b64)
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-    let mut salt = [0u8; 16];
-    let mut nonce = [0u8; 24];
-    salt.copy_from_slice(&salt_vec);
-    nonce.copy_from_slice(&nonce_vec);
-
     let password =
         require_non_empty_password(Some(password.as_str()), "backup password verification")?;
-    let key = derive_key_from_password(password, &salt);
+    let cipher = BackupCipher::open(&manifest, password)?;
+    if !cipher.verify_manifest(&manifest) {
+        return Ok(false);
+    }
 
     // Try to decrypt the marker
     drop(manifest_file);
@@ -2745,7 +3279,7 @@ pub fn backup_verify_password(
         .read_to_end(&mut encrypted_marker)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
-    match decrypt_data(&encrypted_marker, &key, &nonce) {
+    match cipher.decrypt_entry("encrypted_marker.bin", &encrypted_marker) {
         Ok(decrypted) => Ok(decrypted == b"LETTUCE_BACKUP_VERIFIED"),
         Err(_) => Ok(false),
     }
@@ -2821,145 +3355,203 @@ pub fn backup_get_info(
         "attachmentCount": attachment_count,
         "sessionAttachmentCount": session_attachment_count,
         "generatedImageCount": generated_image_count,
+        "chain": manifest.chain.as_ref().map(|chain| serde_json::json!({
+            "backupId": chain.backup_id,
+            "baseId": chain.base_id,
+            "parentId": chain.parent_id,
+            "kind": if chain.is_incremental() { "incremental" } else { "full" },
+        })),
     }))
 }
 
-/// Import a backup file, replacing all existing data (v2 format - JSON-based)
-#[tauri::command]
-pub async fn backup_import(
-    app: tauri::AppHandle,
-    backup_path: String,
-    password: Option<String>,
-) -> Result<(), String> {
-    let storage = storage_root(&app)?;
-
-    // First, read and validate manifest
-    let manifest: BackupManifest = {
-        let file = open_backup_file(&app, &backup_path)?;
-        let mut archive = ZipArchive::new(file).map_err(|e| {
-            crate::utils::err_msg(
-                module_path!(),
-                line!(),
-                format!("Failed to read backup archive: {}", e),
-            )
-        })?;
+fn read_backup_manifest(app: &tauri::AppHandle, path: &str) -> Result<BackupManifest, String> {
+    let file = open_backup_file(app, path)?;
+    let mut archive = ZipArchive::new(file).map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Failed to read backup archive: {}", e),
+        )
+    })?;
+    let mut manifest_file = archive.by_name("manifest.json").map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Invalid backup: missing manifest: {}", e),
+        )
+    })?;
+    let mut manifest_str = String::new();
+    manifest_file
+        .read_to_string(&mut manifest_str)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    serde_json::from_str(&manifest_str).map_err(|e| {
+        crate::utils::err_msg(module_path!(), line!(), format!("Invalid manifest: {}", e))
+    })
+}
 
-        let mut manifest_file = archive.by_name("manifest.json").map_err(|e| {
-            crate::utils::err_msg(
-                module_path!(),
-                line!(),
-                format!("Invalid backup: missing manifest: {}", e),
-            )
-        })?;
+/// An archive on disk that belongs to a backup chain.
+pub(crate) struct ChainArchive {
+    pub path: PathBuf,
+    pub chain: BackupChainInfo,
+    pub created_at: u64,
+}
 
-        let mut manifest_str = String::new();
-        manifest_file
-            .read_to_string(&mut manifest_str)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+/// Chain info of every `.lettuce` archive in `dir`, keyed by backup id.
+pub(crate) fn scan_backup_chains(
+    app: &tauri::AppHandle,
+    dir: &Path,
+) -> Result<std::collections::HashMap<String, ChainArchive>, String> {
+    let mut found = std::collections::HashMap::new();
+    if !dir.exists() {
+        return Ok(found);
+    }
+    for entry in
+        fs::read_dir(dir).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+    {
+        let path = entry
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+            .path();
+        if path.extension().is_none_or(|ext| ext != "lettuce") {
+            continue;
+        }
+        let Ok(manifest) = read_backup_manifest(app, &path.to_string_lossy()) else {
+            continue;
+        };
+        if let Some(chain) = manifest.chain {
+            found.insert(
+                chain.backup_id.clone(),
+                ChainArchive {
+                    path,
+                    chain,
+                    created_at: manifest.created_at,
+                },
+            );
+        }
+    }
+    Ok(found)
+}
 
-        serde_json::from_str(&manifest_str).map_err(|e| {
-            crate::utils::err_msg(module_path!(), line!(), format!("Invalid manifest: {}", e))
-        })?
+/// Archives needed to restore `backup_path`, base first. Standalone and full archives
+/// restore on their own; incremental archives pull their ancestors from the same folder.
+fn resolve_backup_chain(
+    app: &tauri::AppHandle,
+    backup_path: &str,
+    manifest: &BackupManifest,
+) -> Result<Vec<(String, BackupManifest)>, String> {
+    let target = (backup_path.to_string(), manifest.clone());
+    let Some(parent_id) = manifest
+        .chain
+        .as_ref()
+        .and_then(|chain| chain.parent_id.clone())
+    else {
+        return Ok(vec![target]);
     };
 
-    log_info(
-        &app,
-        "backup",
-        format!("Starting backup import v2 from {:?}", backup_path),
-    );
-
-    require_encrypted_backup(&manifest)?;
-
-    // Check backup version - only support v2
-    if manifest.version < BACKUP_VERSION {
-        return Err(format!(
-            "Backup version {} is not supported. This app requires backup version {}.",
-            manifest.version, BACKUP_VERSION
-        ));
-    }
-
-    // Prepare encryption params if encrypted
-    let encryption_params: Option<([u8; 32], [u8; 24])> = if manifest.encrypted {
-        let pwd = require_non_empty_password(password.as_deref(), "backup import")?;
+    let dir = Path::new(backup_path).parent().ok_or_else(|| {
+        "Incremental backups must be restored from their backup folder".to_string()
+    })?;
+    let archives = scan_backup_chains(app, dir)?;
 
-        let salt_b64 = manifest
-            .salt
-            .as_ref()
-            .ok_or_else(|| "Missing salt".to_string())?;
-        let nonce_b64 = manifest
-            .nonce
+    let mut links = vec![target];
+    let mut next = Some(parent_id);
+    while let Some(id) = next {
+        if links.len() > archives.len() + 1 {
+            return Err("Backup chain contains a cycle".to_string());
+        }
+        let path = archives
+            .get(&id)
+            .ok_or_else(|| format!("Backup chain is incomplete: archive {} is missing", id))?
+            .path
+            .to_string_lossy()
+            .to_string();
+        let link_manifest = read_backup_manifest(app, &path)?;
+        check_backup_version(&link_manifest)?;
+        next = link_manifest
+            .chain
             .as_ref()
-            .ok_or_else(|| "Missing nonce".to_string())?;
-
-        let salt_vec = general_purpose::STANDARD
-            .decode(salt_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        let nonce_vec = general_purpose::STANDARD
-            .decode(nonce_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-        let mut salt = [0u8; 16];
-        let mut nonce = [0u8; 24];
-        salt.copy_from_slice(&salt_vec);
-        nonce.copy_from_slice(&nonce_vec);
-
-        let key = derive_key_from_password(pwd, &salt);
-
-        // Verify marker BEFORE proceeding - this validates the password
-        let file = open_backup_file(&app, &backup_path)?;
-        let mut archive = ZipArchive::new(file)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-        let mut marker_file = archive.by_name("encrypted_marker.bin").map_err(|e| {
-            crate::utils::err_msg(
-                module_path!(),
-                line!(),
-                format!("Invalid backup: missing encryption marker: {}", e),
-            )
-        })?;
-
-        let mut encrypted_marker = Vec::new();
-        marker_file
-            .read_to_end(&mut encrypted_marker)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            .and_then(|chain| chain.parent_id.clone());
+        links.push((path, link_manifest));
+    }
+    links.reverse();
+    Ok(links)
+}
 
-        let decrypted = decrypt_data(&encrypted_marker, &key, &nonce)
-            .map_err(|_| "Invalid password - decryption failed".to_string())?;
+type ChainLink = (
+    ZipArchive<File>,
+    Option<BackupCipher>,
+    Option<BackupChainInfo>,
+);
+
+/// Read access to an archive and, for incremental archives, the rest of its chain.
+/// Sections and media come back as they were when the selected archive was written.
+pub(crate) struct BackupReader {
+    /// Base archive first, then each incremental archive in order.
+    links: Vec<ChainLink>,
+}
 
-        if decrypted != b"LETTUCE_BACKUP_VERIFIED" {
-            return Err(crate::utils::err_msg(
-                module_path!(),
-                line!(),
-                "Invalid password - verification marker mismatch",
-            ));
+impl BackupReader {
+    pub fn open(
+        app: &tauri::AppHandle,
+        backup_path: &str,
+        manifest: &BackupManifest,
+        password: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut links = Vec::new();
+        for (path, link_manifest) in resolve_backup_chain(app, backup_path, manifest)? {
+            let cipher = if link_manifest.encrypted {
+                let password = password
+                    .ok_or_else(|| "Password is required for encrypted backups".to_string())?;
+                Some(open_verified_cipher(app, &path, &link_manifest, password)?)
+            } else {
+                None
+            };
+            let archive = ZipArchive::new(open_backup_file(app, &path)?)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            links.push((archive, cipher, link_manifest.chain));
         }
+        Ok(Self { links })
+    }
 
-        log_info(&app, "backup", "Password verified successfully");
-        Some((key, nonce))
-    } else {
-        None
-    };
+    /// Validates the manifest at `backup_path` the way a full import does and opens it.
+    pub fn open_path(
+        app: &tauri::AppHandle,
+        backup_path: &str,
+        password: Option<&str>,
+    ) -> Result<Self, String> {
+        let manifest = read_backup_manifest(app, backup_path)?;
+        require_encrypted_backup(&manifest)?;
+        check_backup_version(&manifest)?;
+        let password = require_non_empty_password(password, "backup restore")?;
+        Self::open(app, backup_path, &manifest, Some(password))
+    }
 
-    // Helper to read and optionally decrypt a file from the archive
-    let read_backup_file = |archive: &mut ZipArchive<File>,
-                            path: &str,
-                            enc_params: &Option<([u8; 32], [u8; 24])>|
-     -> Result<Option<Vec<u8>>, String> {
+    pub fn chain_len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Read and optionally decrypt a file from one archive
+    fn read_entry(
+        archive: &mut ZipArchive<File>,
+        path: &str,
+        cipher: Option<&BackupCipher>,
+    ) -> Result<Option<Vec<u8>>, String> {
         // Try encrypted version first if we have encryption params
         let encrypted_path = format!("{}.enc", path);
 
-        if let Some((ref key, ref nonce)) = enc_params {
+        if let Some(cipher) = cipher {
             if let Ok(mut file) = archive.by_name(&encrypted_path) {
                 let mut contents = Vec::new();
                 file.read_to_end(&mut contents)
                     .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-                let decrypted = decrypt_data(&contents, key, nonce).map_err(|e| {
-                    crate::utils::err_msg(
-                        module_path!(),
-                        line!(),
-                        format!("Failed to decrypt {}: {}", path, e),
-                    )
-                })?;
+                let decrypted = cipher
+                    .decrypt_entry(&encrypted_path, &contents)
+                    .map_err(|e| {
+                        crate::utils::err_msg(
+                            module_path!(),
+                            line!(),
+                            format!("Failed to decrypt {}: {}", path, e),
+                        )
+                    })?;
                 return Ok(Some(decrypted));
             }
         }
@@ -2969,65 +3561,318 @@ pub async fn backup_import(
             let mut contents = Vec::new();
             file.read_to_end(&mut contents)
                 .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            if let Some(cipher) = cipher {
+                cipher.check_entry_digest(path, &contents)?;
+            }
             return Ok(Some(contents));
         }
 
+        if let Some(cipher) = cipher {
+            cipher.check_entry_missing(path)?;
+        }
         Ok(None)
-    };
+    }
 
-    // Re-open archive for reading data files
-    let file = open_backup_file(&app, &backup_path)?;
+    /// Reads `data/<name>.json`, replaying incremental archives on top of the base.
+    pub fn read_section(&mut self, name: &str) -> Result<Option<JsonValue>, String> {
+        let path = format!("data/{}.json", name);
+        let parse = |bytes: Option<Vec<u8>>| -> Result<Option<JsonValue>, String> {
+            bytes
+                .map(|bytes| serde_json::from_slice::<JsonValue>(&bytes))
+                .transpose()
+                .map_err(|e| {
+                    crate::utils::err_msg(
+                        module_path!(),
+                        line!(),
+                        format!("Failed to parse {} JSON: {}", name, e),
+                    )
+                })
+        };
+
+        let mut data = None;
+        for (index, (archive, cipher, chain)) in self.links.iter_mut().enumerate() {
+            let stored = Self::read_entry(archive, &path, cipher.as_ref())?;
+            if index == 0 {
+                data = parse(stored)?;
+                continue;
+            }
+            let Some(change) = chain
+                .as_ref()
+                .and_then(|chain| chain.sections.iter().find(|c| c.name == name))
+            else {
+                continue;
+            };
+            data = merge_incremental_section(data, change, parse(stored)?);
+        }
+        Ok(data)
+    }
+
+    /// Same as [`Self::read_section`], re-encoded for the byte-based import helpers.
+    pub fn read_section_bytes(&mut self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        if self.links.len() == 1 {
+            let path = format!("data/{}.json", name);
+            let (archive, cipher, _) = &mut self.links[0];
+            return Self::read_entry(archive, &path, cipher.as_ref());
+        }
+        self.read_section(name)?
+            .map(|value| serde_json::to_vec(&value))
+            .transpose()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+    }
+
+    /// Extracts the media of the whole chain into `staging_dir`, dropping media that
+    /// later archives recorded as deleted. When `select` is given, only the media it
+    /// maps to a destination path is extracted, under that path.
+    pub fn extract_media(
+        &mut self,
+        staging_dir: &Path,
+        select: Option<&dyn Fn(&str) -> Option<String>>,
+    ) -> Result<(), String> {
+        for (archive, cipher, chain) in self.links.iter_mut() {
+            extract_media_to_staging(archive, cipher, staging_dir, select)?;
+            let Some(chain) = chain.as_ref().filter(|chain| chain.is_incremental()) else {
+                continue;
+            };
+            for removed in &chain.removed_media {
+                let removed = match select {
+                    Some(select) => match select(removed) {
+                        Some(mapped) => mapped,
+                        None => continue,
+                    },
+                    None => removed.clone(),
+                };
+                let relative_path = sanitize_media_archive_name(&removed, false)?;
+                let staged = staging_dir.join(relative_path);
+                if staged.is_file() {
+                    fs::remove_file(&staged)
+                        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Derives the archive key and checks both the manifest MAC and the encrypted marker.
+fn open_verified_cipher(
+    app: &tauri::AppHandle,
+    path: &str,
+    manifest: &BackupManifest,
+    password: &str,
+) -> Result<BackupCipher, String> {
+    let cipher = BackupCipher::open(manifest, password)?;
+    if !cipher.verify_manifest(manifest) {
+        return Err("Invalid password or tampered backup manifest".to_string());
+    }
+
+    // Verify marker BEFORE proceeding - this validates the password
+    let file = open_backup_file(app, path)?;
     let mut archive = ZipArchive::new(file)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
+    let mut marker_file = archive.by_name("encrypted_marker.bin").map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Invalid backup: missing encryption marker: {}", e),
+        )
+    })?;
+
+    let mut encrypted_marker = Vec::new();
+    marker_file
+        .read_to_end(&mut encrypted_marker)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let decrypted = cipher
+        .decrypt_entry("encrypted_marker.bin", &encrypted_marker)
+        .map_err(|_| "Invalid password - decryption failed".to_string())?;
+
+    if decrypted != b"LETTUCE_BACKUP_VERIFIED" {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            "Invalid password - verification marker mismatch",
+        ));
+    }
+
+    Ok(cipher)
+}
+
+/// Extracts media files (images, avatars, attachments) into the staging directory.
+/// `select` receives the media path (without the `.enc` suffix) and returns where to
+/// stage it, or `None` to skip it.
+fn extract_media_to_staging(
+    archive: &mut ZipArchive<File>,
+    encryption_params: &Option<BackupCipher>,
+    staging_dir: &Path,
+    select: Option<&dyn Fn(&str) -> Option<String>>,
+) -> Result<(), String> {
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let file_name = file.name().to_string();
+        if !archive_name_targets_media(&file_name) {
+            continue;
+        }
+
+        let relative_path = sanitize_media_archive_name(&file_name, false)?;
+
+        if file.is_dir() {
+            if select.is_none() {
+                let outpath = staging_dir.join(&relative_path);
+                fs::create_dir_all(&outpath)
+                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            }
+            continue;
+        }
+
+        let encrypted_entry = encryption_params.is_some() && file_name.ends_with(".enc");
+        let out_name = if encrypted_entry {
+            sanitize_media_archive_name(&file_name, true)?
+        } else {
+            relative_path
+        };
+        let out_name = match select {
+            Some(select) => {
+                let media_name = out_name.to_string_lossy().replace('\\', "/");
+                match select(&media_name) {
+                    Some(mapped) => sanitize_media_archive_name(&mapped, false)?,
+                    None => continue,
+                }
+            }
+            None => out_name,
+        };
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+        // Decrypt if needed
+        let final_contents = match encryption_params {
+            Some(cipher) if encrypted_entry => {
+                cipher.decrypt_entry(&file_name, &contents).map_err(|e| {
+                    crate::utils::err_msg(
+                        module_path!(),
+                        line!(),
+                        format!("Failed to decrypt {}: {}", file_name, e),
+                    )
+                })?
+            }
+            Some(cipher) => {
+                cipher.check_entry_digest(&file_name, &contents)?;
+                contents
+            }
+            None => contents,
+        };
+
+        let outpath = staging_dir.join(out_name);
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+
+        let mut outfile = File::create(&outpath)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        outfile
+            .write_all(&final_contents)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    Ok(())
+}
+
+/// Import a backup file, replacing all existing data (v2 format - JSON-based)
+#[tauri::command]
+pub async fn backup_import(
+    app: tauri::AppHandle,
+    backup_path: String,
+    password: Option<String>,
+) -> Result<(), String> {
+    let storage = storage_root(&app)?;
+
+    // First, read and validate manifest
+    let manifest: BackupManifest = {
+        let file = open_backup_file(&app, &backup_path)?;
+        let mut archive = ZipArchive::new(file).map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Failed to read backup archive: {}", e),
+            )
+        })?;
+
+        let mut manifest_file = archive.by_name("manifest.json").map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Invalid backup: missing manifest: {}", e),
+            )
+        })?;
+
+        let mut manifest_str = String::new();
+        manifest_file
+            .read_to_string(&mut manifest_str)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+        serde_json::from_str(&manifest_str).map_err(|e| {
+            crate::utils::err_msg(module_path!(), line!(), format!("Invalid manifest: {}", e))
+        })?
+    };
+
+    log_info(
+        &app,
+        "backup",
+        format!("Starting backup import v2 from {:?}", backup_path),
+    );
+
+    require_encrypted_backup(&manifest)?;
+
+    // Check backup version - v2 (legacy key derivation) and v3 are supported
+    check_backup_version(&manifest)?;
+
+    // Incremental archives are restored by replaying their chain from the base
+    let pwd = if manifest.encrypted {
+        Some(require_non_empty_password(
+            password.as_deref(),
+            "backup import",
+        )?)
+    } else {
+        None
+    };
+    let mut reader = BackupReader::open(&app, &backup_path, &manifest, pwd)?;
+    if manifest.encrypted {
+        log_info(&app, "backup", "Password verified successfully");
+    }
+    if reader.chain_len() > 1 {
+        log_info(
+            &app,
+            "backup",
+            format!("Restoring backup chain of {} archives", reader.chain_len()),
+        );
+    }
+
     log_info(&app, "backup", "Reading JSON data files...");
 
     // Read all JSON data files
-    let meta_data = read_backup_file(&mut archive, "data/meta.json", &encryption_params)?;
-    let settings_data = read_backup_file(&mut archive, "data/settings.json", &encryption_params)?;
-    let provider_credentials_data = read_backup_file(
-        &mut archive,
-        "data/provider_credentials.json",
-        &encryption_params,
-    )?;
-    let models_data = read_backup_file(&mut archive, "data/models.json", &encryption_params)?;
-    let model_pricing_cache_data = read_backup_file(
-        &mut archive,
-        "data/model_pricing_cache.json",
-        &encryption_params,
-    )?;
-    let secrets_data = read_backup_file(&mut archive, "data/secrets.json", &encryption_params)?;
-    let prompt_templates_data = read_backup_file(
-        &mut archive,
-        "data/prompt_templates.json",
-        &encryption_params,
-    )?;
-    let chat_templates_data =
-        read_backup_file(&mut archive, "data/chat_templates.json", &encryption_params)?;
-    let personas_data = read_backup_file(&mut archive, "data/personas.json", &encryption_params)?;
-    let characters_data =
-        read_backup_file(&mut archive, "data/characters.json", &encryption_params)?;
-    let sessions_data = read_backup_file(&mut archive, "data/sessions.json", &encryption_params)?;
-    let creation_helper_sessions_data = read_backup_file(
-        &mut archive,
-        "data/creation_helper_sessions.json",
-        &encryption_params,
-    )?;
-    let group_characters_data = read_backup_file(
-        &mut archive,
-        "data/group_characters.json",
-        &encryption_params,
-    )?;
-    let group_sessions_data =
-        read_backup_file(&mut archive, "data/group_sessions.json", &encryption_params)?;
-    let usage_records_data =
-        read_backup_file(&mut archive, "data/usage_records.json", &encryption_params)?;
-    let lorebooks_data = read_backup_file(&mut archive, "data/lorebooks.json", &encryption_params)?;
-    let character_lorebooks_data = read_backup_file(
-        &mut archive,
-        "data/character_lorebooks.json",
-        &encryption_params,
-    )?;
+    let meta_data = reader.read_section_bytes("meta")?;
+    let settings_data = reader.read_section_bytes("settings")?;
+    let provider_credentials_data = reader.read_section_bytes("provider_credentials")?;
+    let models_data = reader.read_section_bytes("models")?;
+    let model_pricing_cache_data = reader.read_section_bytes("model_pricing_cache")?;
+    let secrets_data = reader.read_section_bytes("secrets")?;
+    let prompt_templates_data = reader.read_section_bytes("prompt_templates")?;
+    let chat_templates_data = reader.read_section_bytes("chat_templates")?;
+    let personas_data = reader.read_section_bytes("personas")?;
+    let characters_data = reader.read_section_bytes("characters")?;
+    let sessions_data = reader.read_section_bytes("sessions")?;
+    let creation_helper_sessions_data = reader.read_section_bytes("creation_helper_sessions")?;
+    let group_characters_data = reader.read_section_bytes("group_characters")?;
+    let group_sessions_data = reader.read_section_bytes("group_sessions")?;
+    let usage_records_data = reader.read_section_bytes("usage_records")?;
+    let lorebooks_data = reader.read_section_bytes("lorebooks")?;
+    let character_lorebooks_data = reader.read_section_bytes("character_lorebooks")?;
+    let character_memories_data = reader.read_section_bytes("character_memories")?;
+    let companion_relationships_data = reader.read_section_bytes("companion_relationships")?;
 
     log_info(&app, "backup", "Importing data to database...");
 
@@ -3333,6 +4178,44 @@ pub async fn backup_import(
         log_info(&app, "backup", "No character_lorebooks data found");
     }
 
+    // Character-level memories (depends on characters)
+    if let Some(data) = character_memories_data {
+        log_info(&app, "backup", "Found character_memories data");
+        let json_str = String::from_utf8(data)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let json_value: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Failed to parse character_memories JSON: {}", e),
+            )
+        })?;
+        let conn = open_db(&app)?;
+        super::character_memories::import_from_backup(&conn, &json_value)?;
+        log_info(&app, "backup", "Character memories imported");
+    } else {
+        log_info(&app, "backup", "No character_memories data found");
+    }
+
+    // Companion relationship ledgers (depends on characters)
+    if let Some(data) = companion_relationships_data {
+        log_info(&app, "backup", "Found companion_relationships data");
+        let json_str = String::from_utf8(data)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let json_value: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Failed to parse companion_relationships JSON: {}", e),
+            )
+        })?;
+        let conn = open_db(&app)?;
+        super::companion_relationships::import_from_backup(&conn, &json_value)?;
+        log_info(&app, "backup", "Companion relationships imported");
+    } else {
+        log_info(&app, "backup", "No companion_relationships data found");
+    }
+
     log_info(&app, "backup", "Extracting media files...");
 
     // Extract media files to staging directory, then copy
@@ -3344,63 +4227,7 @@ pub async fn backup_import(
     fs::create_dir_all(&staging_dir)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
-    // Re-open archive for media extraction
-    let file = open_backup_file(&app, &backup_path)?;
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-    // Extract media files (images, avatars, attachments)
-    for i in 0..archive.len() {
-        let mut file = archive
-            .by_index(i)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        let file_name = file.name().to_string();
-        if !archive_name_targets_media(&file_name) {
-            continue;
-        }
-
-        let relative_path = sanitize_media_archive_name(&file_name, false)?;
-
-        if file.is_dir() {
-            let outpath = staging_dir.join(&relative_path);
-            fs::create_dir_all(&outpath)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        } else {
-            let mut contents = Vec::new();
-            file.read_to_end(&mut contents)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-            // Decrypt if needed
-            let (outpath, final_contents) = if let Some((ref key, ref nonce)) = encryption_params {
-                if file_name.ends_with(".enc") {
-                    let decrypted = decrypt_data(&contents, key, nonce).map_err(|e| {
-                        crate::utils::err_msg(
-                            module_path!(),
-                            line!(),
-                            format!("Failed to decrypt {}: {}", file_name, e),
-                        )
-                    })?;
-                    let out_name = sanitize_media_archive_name(&file_name, true)?;
-                    (staging_dir.join(out_name), decrypted)
-                } else {
-                    (staging_dir.join(&relative_path), contents)
-                }
-            } else {
-                (staging_dir.join(&relative_path), contents)
-            };
-
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent)
-                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-            }
-
-            let mut outfile = File::create(&outpath)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-            outfile
-                .write_all(&final_contents)
-                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        }
-    }
+    reader.extract_media(&staging_dir, None)?;
 
     // Copy media from staging to actual locations
     let images_dir = storage.join("images");
@@ -3549,11 +4376,127 @@ mod tests {
             encrypted: false,
             salt: None,
             nonce: None,
+            kdf: None,
+            entries: Vec::new(),
+            chain: None,
+            manifest_mac: None,
         };
 
         assert!(super::require_encrypted_backup(&manifest).is_err());
     }
 
+    fn test_kdf() -> super::BackupKdfParams {
+        super::BackupKdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            ..Default::default()
+        }
+    }
+
+    fn signed_manifest(password: &str) -> (super::BackupManifest, super::BackupCipher, Vec<u8>) {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let kdf = test_kdf();
+        let (cipher, salt) = super::BackupCipher::for_export(password, &kdf).unwrap();
+        let stored = cipher.encrypt_entry("data/meta.json.enc", b"[]").unwrap();
+        let mut manifest = super::BackupManifest {
+            version: super::BACKUP_VERSION,
+            created_at: 0,
+            app_version: "test".to_string(),
+            encrypted: true,
+            salt: Some(general_purpose::STANDARD.encode(salt)),
+            nonce: Some(general_purpose::STANDARD.encode(cipher.nonce)),
+            kdf: Some(kdf),
+            entries: vec![super::BackupEntryDigest {
+                name: "data/meta.json.enc".to_string(),
+                blake3: blake3::hash(&stored).to_hex().to_string(),
+            }],
+            chain: None,
+            manifest_mac: None,
+        };
+        cipher.sign_manifest(&mut manifest).unwrap();
+        (manifest, cipher, stored)
+    }
+
+    #[test]
+    fn authenticated_backup_round_trips_with_correct_password() {
+        let (manifest, _, stored) = signed_manifest("hunter2");
+
+        let reopened = super::BackupCipher::open(&manifest, "hunter2").unwrap();
+        assert!(reopened.verify_manifest(&manifest));
+        assert_eq!(
+            reopened
+                .decrypt_entry("data/meta.json.enc", &stored)
+                .unwrap(),
+            b"[]"
+        );
+    }
+
+    #[test]
+    fn authenticated_backup_rejects_stripped_entries() {
+        let (manifest, _, _) = signed_manifest("hunter2");
+
+        let reopened = super::BackupCipher::open(&manifest, "hunter2").unwrap();
+        assert!(reopened.check_entry_missing("data/meta.json").is_err());
+        assert!(reopened.check_entry_missing("data/lorebooks.json").is_ok());
+    }
+
+    #[test]
+    fn authenticated_backup_rejects_wrong_password_and_tampering() {
+        let (manifest, _, stored) = signed_manifest("hunter2");
+
+        let wrong = super::BackupCipher::open(&manifest, "hunter3").unwrap();
+        assert!(!wrong.verify_manifest(&manifest));
+
+        let mut tampered = manifest.clone();
+        tampered.created_at = 1;
+        let cipher = super::BackupCipher::open(&tampered, "hunter2").unwrap();
+        assert!(!cipher.verify_manifest(&tampered));
+
+        let cipher = super::BackupCipher::open(&manifest, "hunter2").unwrap();
+        // Entries are bound to their archive name and to the manifest digest list.
+        assert!(cipher
+            .decrypt_entry("data/settings.json.enc", &stored)
+            .is_err());
+        let mut flipped = stored.clone();
+        flipped[0] ^= 1;
+        assert!(cipher
+            .decrypt_entry("data/meta.json.enc", &flipped)
+            .is_err());
+    }
+
+    #[test]
+    fn legacy_backup_key_derivation_still_decrypts() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let salt = [7u8; 16];
+        let nonce = [9u8; 24];
+        let key = super::derive_key_from_password("hunter2", &salt);
+        let stored = super::encrypt_data(b"LETTUCE_BACKUP_VERIFIED", &key, &nonce).unwrap();
+        let manifest = super::BackupManifest {
+            version: 2,
+            created_at: 0,
+            app_version: "legacy".to_string(),
+            encrypted: true,
+            salt: Some(general_purpose::STANDARD.encode(salt)),
+            nonce: Some(general_purpose::STANDARD.encode(nonce)),
+            kdf: None,
+            entries: Vec::new(),
+            chain: None,
+            manifest_mac: None,
+        };
+
+        let cipher = super::BackupCipher::open(&manifest, "hunter2").unwrap();
+        assert!(cipher.verify_manifest(&manifest));
+        assert_eq!(
+            cipher
+                .decrypt_entry("encrypted_marker.bin", &stored)
+                .unwrap(),
+            b"LETTUCE_BACKUP_VERIFIED"
+        );
+        assert!(super::check_backup_version(&manifest).is_ok());
+    }
+
     #[test]
     fn disable_dynamic_memory_preserves_imported_settings() {
         let mut settings = serde_json::json!({
@@ -3582,9 +4525,66 @@ mod tests {
         assert_eq!(settings["dynamicMemory"]["contextEnrichmentEnabled"], false);
         assert_eq!(settings["groupDynamicMemory"]["enabled"], true);
     }
+
+    #[test]
+    fn incremental_sections_round_trip_through_merge() {
+        let change = super::BackupSectionChange {
+            name: "personas".to_string(),
+            full: false,
+            roots: vec!["b".to_string(), "c".to_string(), "d".to_string()],
+        };
+        let chain = super::BackupChainInfo {
+            backup_id: "inc".to_string(),
+            base_id: "base".to_string(),
+            parent_id: Some("base".to_string()),
+            change_id: 10,
+            sections: vec![change.clone()],
+            removed_media: Vec::new(),
+        };
+
+        // "b" was edited, "c" deleted and "d" created since the base
+        let current = serde_json::json!([
+            {"id": "a", "title": "A"},
+            {"id": "b", "title": "B2"},
+            {"id": "d", "title": "D"}
+        ]);
+        let stored = super::incremental_section_json(Some(&chain), "personas", &current)
+            .unwrap()
+            .into_owned();
+        assert_eq!(stored.as_array().unwrap().len(), 2);
+        assert!(super::incremental_section_json(Some(&chain), "models", &current).is_none());
+
+        let base = serde_json::json!([
+            {"id": "a", "title": "A"},
+            {"id": "b", "title": "B"},
+            {"id": "c", "title": "C"}
+        ]);
+        let mut merged = super::merge_incremental_section(Some(base), &change, Some(stored))
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        merged.sort_by_key(|item| item["id"].as_str().unwrap().to_string());
+        assert_eq!(serde_json::Value::Array(merged), current);
+    }
+
+    #[test]
+    fn full_incremental_section_replaces_base() {
+        let change = super::BackupSectionChange {
+            name: "settings".to_string(),
+            full: true,
+            roots: Vec::new(),
+        };
+        let merged = super::merge_incremental_section(
+            Some(serde_json::json!({"system_prompt": "old"})),
+            &change,
+            Some(serde_json::json!({"system_prompt": "new"})),
+        );
+        assert_eq!(merged, Some(serde_json::json!({"system_prompt": "new"})));
+    }
 }
 
-/// List available backups in downloads directory
+/// List available backups in the downloads directory and the scheduled backup folder
 #[tauri::command]
 pub fn backup_list(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
     let downloads = get_downloads_dir()?;
@@ -3596,22 +4596,55 @@ pub fn backup_list(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, Stri
         format!("Looking for backups in: {:?}", downloads),
     );
 
-    if !downloads.exists() {
+    let mut dirs = vec![downloads];
+    if let Some(scheduled) = super::backup_schedule::scheduled_backup_dir(&app)? {
+        if !dirs.contains(&scheduled) {
+            dirs.push(scheduled);
+        }
+    }
+    for dir in &dirs {
+        collect_backups_in_dir(&app, dir, &mut backups)?;
+    }
+
+    log_info(
+        &app,
+        "backup",
+        format!("Found {} backups total", backups.len()),
+    );
+
+    annotate_backup_chains(&mut backups);
+
+    // Sort by creation date descending
+    backups.sort_by(|a, b| {
+        let a_time = a.get("createdAt").and_then(|v| v.as_u64()).unwrap_or(0);
+        let b_time = b.get("createdAt").and_then(|v| v.as_u64()).unwrap_or(0);
+        b_time.cmp(&a_time)
+    });
+
+    Ok(backups)
+}
+
+fn collect_backups_in_dir(
+    app: &tauri::AppHandle,
+    dir: &Path,
+    backups: &mut Vec<serde_json::Value>,
+) -> Result<(), String> {
+    if !dir.exists() {
         log_info(
-            &app,
+            app,
             "backup",
-            format!("Downloads directory does not exist: {:?}", downloads),
+            format!("Backup directory does not exist: {:?}", dir),
         );
-        return Ok(backups);
+        return Ok(());
     }
 
-    let read_result = fs::read_dir(&downloads);
+    let read_result = fs::read_dir(dir);
     match &read_result {
-        Ok(_) => log_info(&app, "backup", "Successfully opened downloads directory"),
+        Ok(_) => log_info(app, "backup", "Successfully opened backup directory"),
         Err(e) => log_info(
-            &app,
+            app,
             "backup",
-            format!("Failed to read downloads directory: {}", e),
+            format!("Failed to read backup directory: {}", e),
         ),
     }
 
@@ -3619,11 +4652,11 @@ pub fn backup_list(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, Stri
         let entry = entry.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
         let path = entry.path();
 
-        log_info(&app, "backup", format!("Found file: {:?}", path));
+        log_info(app, "backup", format!("Found file: {:?}", path));
 
         if let Some(ext) = path.extension() {
             if ext == "lettuce" {
-                log_info(&app, "backup", format!("Found .lettuce backup: {:?}", path));
+                log_info(app, "backup", format!("Found .lettuce backup: {:?}", path));
                 if let Ok(info) = backup_get_info(app.clone(), path.to_string_lossy().to_string()) {
                     let mut info_obj = info;
                     if let Some(obj) = info_obj.as_object_mut() {
@@ -3646,20 +4679,50 @@ pub fn backup_list(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, Stri
         }
     }
 
-    log_info(
-        &app,
-        "backup",
-        format!("Found {} backups total", backups.len()),
-    );
+    Ok(())
+}
 
-    // Sort by creation date descending
-    backups.sort_by(|a, b| {
-        let a_time = a.get("createdAt").and_then(|v| v.as_u64()).unwrap_or(0);
-        let b_time = b.get("createdAt").and_then(|v| v.as_u64()).unwrap_or(0);
-        b_time.cmp(&a_time)
-    });
+/// Marks every chained backup with its chain length and whether all of its
+/// ancestors are present, so the UI can group chains and hide broken ones.
+fn annotate_backup_chains(backups: &mut [serde_json::Value]) {
+    let parents: std::collections::HashMap<String, Option<String>> = backups
+        .iter()
+        .filter_map(|backup| {
+            let chain = backup.get("chain")?;
+            let id = chain.get("backupId")?.as_str()?.to_string();
+            let parent = chain
+                .get("parentId")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            Some((id, parent))
+        })
+        .collect();
 
-    Ok(backups)
+    for backup in backups.iter_mut() {
+        let Some(chain) = backup.get_mut("chain").and_then(|v| v.as_object_mut()) else {
+            continue;
+        };
+        let mut length = 1;
+        let mut restorable = true;
+        let mut next = chain
+            .get("parentId")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        while let Some(id) = next {
+            match parents.get(&id) {
+                Some(parent) if length <= parents.len() => {
+                    length += 1;
+                    next = parent.clone();
+                }
+                _ => {
+                    restorable = false;
+                    break;
+                }
+            }
+        }
+        chain.insert("length".to_string(), serde_json::json!(length));
+        chain.insert("restorable".to_string(), serde_json::json!(restorable));
+    }
 }
 
 /// Delete a backup file
@@ -3810,28 +4873,12 @@ pub fn backup_verify_password_from_bytes(data: Vec<u8>, password: String) -> Res
 
     require_encrypted_backup(&manifest)?;
 
-    let salt_b64 = manifest
-        .salt
-        .ok_or_else(|| "Missing salt in encrypted backup".to_string())?;
-    let nonce_b64 = manifest
-        .nonce
-        .ok_or_else(|| "Missing nonce in encrypted backup".to_string())?;
-
-    let salt_vec = general_purpose::STANDARD
-        .decode(&salt_b64)
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    let nonce_vec = general_purpose::STANDARD
-        .decode(&nonce_b64)
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-    let mut salt = [0u8; 16];
-    let mut nonce = [0u8; 24];
-    salt.copy_from_slice(&salt_vec);
-    nonce.copy_from_slice(&nonce_vec);
-
     let password =
         require_non_empty_password(Some(password.as_str()), "backup password verification")?;
-    let key = derive_key_from_password(password, &salt);
+    let cipher = BackupCipher::open(&manifest, password)?;
+    if !cipher.verify_manifest(&manifest) {
+        return Ok(false);
+    }
 
     drop(manifest_file);
     let mut marker_file = archive.by_name("encrypted_marker.bin").map_err(|e| {
@@ -3847,7 +4894,7 @@ pub fn backup_verify_password_from_bytes(data: Vec<u8>, password: String) -> Res
         .read_to_end(&mut encrypted_marker)
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
-    match decrypt_data(&encrypted_marker, &key, &nonce) {
+    match cipher.decrypt_entry("encrypted_marker.bin", &encrypted_marker) {
         Ok(decrypted) => Ok(decrypted == b"LETTUCE_BACKUP_VERIFIED"),
         Err(_) => Ok(false),
     }
@@ -3895,40 +4942,26 @@ pub async fn backup_import_from_bytes(
 
     require_encrypted_backup(&manifest)?;
 
-    // Check backup version - only support v2
-    if manifest.version < BACKUP_VERSION {
-        return Err(format!(
-            "Backup version {} is not supported. This app requires backup version {}.",
-            manifest.version, BACKUP_VERSION
-        ));
+    // Check backup version - v2 (legacy key derivation) and v3 are supported
+    check_backup_version(&manifest)?;
+
+    // An incremental archive on its own would wipe everything outside its delta
+    if manifest
+        .chain
+        .as_ref()
+        .is_some_and(|chain| chain.is_incremental())
+    {
+        return Err("Incremental backups must be restored from their backup folder".to_string());
     }
 
     // Prepare encryption params if encrypted
-    let encryption_params: Option<([u8; 32], [u8; 24])> = if manifest.encrypted {
+    let encryption_params: Option<BackupCipher> = if manifest.encrypted {
         let pwd = require_non_empty_password(password.as_deref(), "backup import from bytes")?;
 
-        let salt_b64 = manifest
-            .salt
-            .as_ref()
-            .ok_or_else(|| "Missing salt".to_string())?;
-        let nonce_b64 = manifest
-            .nonce
-            .as_ref()
-            .ok_or_else(|| "Missing nonce".to_string())?;
-
-        let salt_vec = general_purpose::STANDARD
-            .decode(salt_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        let nonce_vec = general_purpose::STANDARD
-            .decode(nonce_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-        let mut salt = [0u8; 16];
-        let mut nonce = [0u8; 24];
-        salt.copy_from_slice(&salt_vec);
-        nonce.copy_from_slice(&nonce_vec);
-
-        let key = derive_key_from_password(pwd, &salt);
+        let cipher = BackupCipher::open(&manifest, pwd)?;
+        if !cipher.verify_manifest(&manifest) {
+            return Err("Invalid password or tampered backup manifest".to_string());
+        }
 
         // Verify marker BEFORE proceeding - this validates the password
         let cursor = std::io::Cursor::new(&data);
@@ -3948,7 +4981,8 @@ pub async fn backup_import_from_bytes(
             .read_to_end(&mut encrypted_marker)
             .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
-        let decrypted = decrypt_data(&encrypted_marker, &key, &nonce)
+        let decrypted = cipher
+            .decrypt_entry("encrypted_marker.bin", &encrypted_marker)
             .map_err(|_| "Invalid password - decryption failed".to_string())?;
 
         if decrypted != b"LETTUCE_BACKUP_VERIFIED" {
@@ -3960,7 +4994,7 @@ pub async fn backup_import_from_bytes(
         }
 
         log_info(&app, "backup", "Password verified successfully");
-        Some((key, nonce))
+        Some(cipher)
     } else {
         None
     };
@@ -3968,7 +5002,7 @@ pub async fn backup_import_from_bytes(
     // Helper to read and optionally decrypt a file from the archive (bytes version)
     let read_backup_file_bytes = |data: &[u8],
                                   path: &str,
-                                  enc_params: &Option<([u8; 32], [u8; 24])>|
+                                  enc_params: &Option<BackupCipher>|
      -> Result<Option<Vec<u8>>, String> {
         let cursor = std::io::Cursor::new(data);
         let mut archive = ZipArchive::new(cursor)
@@ -3977,18 +5011,20 @@ pub async fn backup_import_from_bytes(
         // Try encrypted version first if we have encryption params
         let encrypted_path = format!("{}.enc", path);
 
-        if let Some((ref key, ref nonce)) = enc_params {
+        if let Some(cipher) = enc_params {
             if let Ok(mut file) = archive.by_name(&encrypted_path) {
                 let mut contents = Vec::new();
                 file.read_to_end(&mut contents)
                     .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-                let decrypted = decrypt_data(&contents, key, nonce).map_err(|e| {
-                    crate::utils::err_msg(
-                        module_path!(),
-                        line!(),
-                        format!("Failed to decrypt {}: {}", path, e),
-                    )
-                })?;
+                let decrypted = cipher
+                    .decrypt_entry(&encrypted_path, &contents)
+                    .map_err(|e| {
+                        crate::utils::err_msg(
+                            module_path!(),
+                            line!(),
+                            format!("Failed to decrypt {}: {}", path, e),
+                        )
+                    })?;
                 return Ok(Some(decrypted));
             }
         }
@@ -3998,9 +5034,15 @@ pub async fn backup_import_from_bytes(
             let mut contents = Vec::new();
             file.read_to_end(&mut contents)
                 .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            if let Some(cipher) = enc_params {
+                cipher.check_entry_digest(path, &contents)?;
+            }
             return Ok(Some(contents));
         }
 
+        if let Some(cipher) = enc_params {
+            cipher.check_entry_missing(path)?;
+        }
         Ok(None)
     };
 
@@ -4037,6 +5079,13 @@ pub async fn backup_import_from_bytes(
     let lorebooks_data = read_backup_file_bytes(&data, "data/lorebooks.json", &encryption_params)?;
     let character_lorebooks_data =
         read_backup_file_bytes(&data, "data/character_lorebooks.json", &encryption_params)?;
+    let character_memories_data =
+        read_backup_file_bytes(&data, "data/character_memories.json", &encryption_params)?;
+    let companion_relationships_data = read_backup_file_bytes(
+        &data,
+        "data/companion_relationships.json",
+        &encryption_params,
+    )?;
 
     log_info(&app, "backup", "Importing data to database...");
 
@@ -4281,6 +5330,38 @@ pub async fn backup_import_from_bytes(
         log_info(&app, "backup", "Character-lorebook links imported");
     }
 
+    // Character-level memories (depends on characters)
+    if let Some(file_data) = character_memories_data {
+        let json_str = String::from_utf8(file_data)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let json_value: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Failed to parse character_memories JSON: {}", e),
+            )
+        })?;
+        let conn = open_db(&app)?;
+        super::character_memories::import_from_backup(&conn, &json_value)?;
+        log_info(&app, "backup", "Character memories imported");
+    }
+
+    // Companion relationship ledgers (depends on characters)
+    if let Some(file_data) = companion_relationships_data {
+        let json_str = String::from_utf8(file_data)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let json_value: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Failed to parse companion_relationships JSON: {}", e),
+            )
+        })?;
+        let conn = open_db(&app)?;
+        super::companion_relationships::import_from_backup(&conn, &json_value)?;
+        log_info(&app, "backup", "Companion relationships imported");
+    }
+
     log_info(&app, "backup", "Extracting media files...");
 
     // Extract media files to staging directory
@@ -4318,9 +5399,9 @@ pub async fn backup_import_from_bytes(
                 .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
             // Decrypt if needed
-            let (outpath, final_contents) = if let Some((ref key, ref nonce)) = encryption_params {
+            let (outpath, final_contents) = if let Some(ref cipher) = encryption_params {
                 if file_name.ends_with(".enc") {
-                    let decrypted = decrypt_data(&contents, key, nonce).map_err(|e| {
+                    let decrypted = cipher.decrypt_entry(&file_name, &contents).map_err(|e| {
                         crate::utils::err_msg(
                             module_path!(),
                             line!(),
@@ -4330,6 +5411,7 @@ pub async fn backup_import_from_bytes(
                     let out_name = sanitize_media_archive_name(&file_name, true)?;
                     (staging_dir.join(out_name), decrypted)
                 } else {
+                    cipher.check_entry_digest(&file_name, &contents)?;
                     (staging_dir.join(&relative_path), contents)
                 }
             } else {
@@ -4479,32 +5561,14 @@ pub async fn backup_check_dynamic_memory(
     require_encrypted_backup(&manifest)?;
 
     // Prepare encryption params if needed
-    let encryption_params: Option<([u8; 32], [u8; 24])> = if manifest.encrypted {
+    let encryption_params: Option<BackupCipher> = if manifest.encrypted {
         let pwd = require_non_empty_password(password.as_deref(), "backup inspection")?;
 
-        let salt_b64 = manifest
-            .salt
-            .as_ref()
-            .ok_or_else(|| "Missing salt".to_string())?;
-        let nonce_b64 = manifest
-            .nonce
-            .as_ref()
-            .ok_or_else(|| "Missing nonce".to_string())?;
-
-        let salt_vec = general_purpose::STANDARD
-            .decode(salt_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        let nonce_vec = general_purpose::STANDARD
-            .decode(nonce_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-        let mut salt = [0u8; 16];
-        let mut nonce = [0u8; 24];
-        salt.copy_from_slice(&salt_vec);
-        nonce.copy_from_slice(&nonce_vec);
-
-        let key = derive_key_from_password(pwd, &salt);
-        Some((key, nonce))
+        let cipher = BackupCipher::open(&manifest, pwd)?;
+        if !cipher.verify_manifest(&manifest) {
+            return Err("Invalid password or tampered backup manifest".to_string());
+        }
+        Some(cipher)
     } else {
         None
     };
@@ -4542,13 +5606,13 @@ pub async fn backup_check_dynamic_memory(
     );
 
     // Decrypt if needed
-    let final_json_data = if let Some((key, nonce)) = encryption_params {
+    let final_json_data = if let Some(cipher) = encryption_params {
         log_info(
             &app,
             "backup_check_dynamic_memory",
             "Decrypting characters JSON...",
         );
-        decrypt_data(&json_data, &key, &nonce)?
+        cipher.decrypt_entry(json_path, &json_data)?
     } else {
         json_data
     };
@@ -4643,32 +5707,14 @@ pub async fn backup_check_dynamic_memory_from_bytes(
     require_encrypted_backup(&manifest)?;
 
     // Prepare encryption params if needed
-    let encryption_params: Option<([u8; 32], [u8; 24])> = if manifest.encrypted {
+    let encryption_params: Option<BackupCipher> = if manifest.encrypted {
         let pwd = require_non_empty_password(password.as_deref(), "backup inspection")?;
 
-        let salt_b64 = manifest
-            .salt
-            .as_ref()
-            .ok_or_else(|| "Missing salt".to_string())?;
-        let nonce_b64 = manifest
-            .nonce
-            .as_ref()
-            .ok_or_else(|| "Missing nonce".to_string())?;
-
-        let salt_vec = general_purpose::STANDARD
-            .decode(salt_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-        let nonce_vec = general_purpose::STANDARD
-            .decode(nonce_b64)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-
-        let mut salt = [0u8; 16];
-        let mut nonce = [0u8; 24];
-        salt.copy_from_slice(&salt_vec);
-        nonce.copy_from_slice(&nonce_vec);
-
-        let key = derive_key_from_password(pwd, &salt);
-        Some((key, nonce))
+        let cipher = BackupCipher::open(&manifest, pwd)?;
+        if !cipher.verify_manifest(&manifest) {
+            return Err("Invalid password or tampered backup manifest".to_string());
+        }
+        Some(cipher)
     } else {
         None
     };
@@ -4712,13 +5758,13 @@ pub async fn backup_check_dynamic_memory_from_bytes(
     );
 
     // Decrypt if needed
-    let final_json_data = if let Some((key, nonce)) = encryption_params {
+    let final_json_data = if let Some(cipher) = encryption_params {
         log_info(
             &app,
             "backup_check_dynamic_memory_from_bytes",
             "Decrypting characters JSON...",
         );
-        decrypt_data(&json_data, &key, &nonce)?
+        cipher.decrypt_entry(json_path, &json_data)?
     } else {
         json_data
     };
@@ -0,0 +1,737 @@
+use chrono::Datelike;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::backup::{
+    scan_backup_chains, write_backup_archive, BackupChainInfo, BackupSectionChange, ChainArchive,
+};
+use super::db::{now_ms, open_db, DbConnection};
+use super::secrets;
+use crate::utils::{log_error, log_info};
+
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// `secrets` row holding the scheduled backup password.
+const PASSWORD_SERVICE: &str = "lettuceai:backupSchedule";
+const PASSWORD_ACCOUNT: &str = "password";
+/// Sections the sync change log does not track; incremental archives carry them whole.
+const UNTRACKED_SECTIONS: [&str; 4] = [
+    "model_pricing_cache",
+    "creation_helper_sessions",
+    "character_memories",
+    "companion_relationships",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupFrequency {
+    Daily,
+    Weekly,
+}
+
+impl BackupFrequency {
+    fn interval_ms(self) -> i64 {
+        match self {
+            BackupFrequency::Daily => DAY_MS,
+            BackupFrequency::Weekly => 7 * DAY_MS,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupFrequency::Daily => "daily",
+            BackupFrequency::Weekly => "weekly",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "weekly" => BackupFrequency::Weekly,
+            _ => BackupFrequency::Daily,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupScheduleConfig {
+    pub enabled: bool,
+    pub frequency: BackupFrequency,
+    pub directory: Option<String>,
+    /// Incremental archives written after a full one before the next full archive.
+    pub incrementals_per_full: u32,
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupScheduleStatus {
+    #[serde(flatten)]
+    pub config: BackupScheduleConfig,
+    pub has_password: bool,
+    pub last_run_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_run_at: Option<i64>,
+}
+
+struct StoredSchedule {
+    config: BackupScheduleConfig,
+    /// The password itself lives in the `secrets` table.
+    has_password: bool,
+    last_run_at: Option<i64>,
+    last_error: Option<String>,
+}
+
+impl StoredSchedule {
+    fn next_run_at(&self) -> Option<i64> {
+        if !self.config.enabled {
+            return None;
+        }
+        Some(
+            self.last_run_at
+                .map(|last| last + self.config.frequency.interval_ms())
+                .unwrap_or_else(now_ms),
+        )
+    }
+
+    fn status(&self) -> BackupScheduleStatus {
+        BackupScheduleStatus {
+            config: self.config.clone(),
+            has_password: self.has_password,
+            last_run_at: self.last_run_at,
+            last_error: self.last_error.clone(),
+            next_run_at: self.next_run_at(),
+        }
+    }
+}
+
+fn load_schedule(conn: &DbConnection) -> Result<StoredSchedule, String> {
+    let row = conn
+        .query_row(
+            "SELECT enabled, frequency, directory, has_password, incrementals_per_full, keep_daily, keep_weekly, last_run_at, last_error
+             FROM backup_schedule WHERE id = 1",
+            [],
+            |row| {
+                Ok(StoredSchedule {
+                    config: BackupScheduleConfig {
+                        enabled: row.get::<_, i64>(0)? != 0,
+                        frequency: BackupFrequency::parse(&row.get::<_, String>(1)?),
+                        directory: row.get(2)?,
+                        incrementals_per_full: row.get::<_, i64>(4)?.max(0) as u32,
+                        keep_daily: row.get::<_, i64>(5)?.max(0) as u32,
+                        keep_weekly: row.get::<_, i64>(6)?.max(0) as u32,
+                    },
+                    has_password: row.get::<_, i64>(3)? != 0,
+                    last_run_at: row.get(7)?,
+                    last_error: row.get(8)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    Ok(row.unwrap_or(StoredSchedule {
+        config: BackupScheduleConfig {
+            enabled: false,
+            frequency: BackupFrequency::Daily,
+            directory: None,
+            incrementals_per_full: 6,
+            keep_daily: 7,
+            keep_weekly: 4,
+        },
+        has_password: false,
+        last_run_at: None,
+        last_error: None,
+    }))
+}
+
+fn save_schedule(conn: &DbConnection, schedule: &StoredSchedule) -> Result<(), String> {
+    conn.execute(
+        r#"INSERT INTO backup_schedule (id, enabled, frequency, directory, has_password, incrementals_per_full, keep_daily, keep_weekly, last_run_at, last_error, updated_at)
+           VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+           ON CONFLICT(id) DO UPDATE SET
+             enabled = excluded.enabled,
+             frequency = excluded.frequency,
+             directory = excluded.directory,
+             has_password = excluded.has_password,
+             incrementals_per_full = excluded.incrementals_per_full,
+             keep_daily = excluded.keep_daily,
+             keep_weekly = excluded.keep_weekly,
+             last_run_at = excluded.last_run_at,
+             last_error = excluded.last_error,
+             updated_at = excluded.updated_at"#,
+        params![
+            schedule.config.enabled as i64,
+            schedule.config.frequency.as_str(),
+            schedule.config.directory,
+            schedule.has_password as i64,
+            schedule.config.incrementals_per_full as i64,
+            schedule.config.keep_daily as i64,
+            schedule.config.keep_weekly as i64,
+            schedule.last_run_at,
+            schedule.last_error,
+            now_ms(),
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Folder scheduled backups are written to, if one has been chosen.
+pub fn scheduled_backup_dir(app: &tauri::AppHandle) -> Result<Option<PathBuf>, String> {
+    let conn = open_db(app)?;
+    Ok(load_schedule(&conn)?
+        .config
+        .directory
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from))
+}
+
+/// What an incremental archive has to carry since its parent.
+struct IncrementalPlan {
+    sections: Vec<BackupSectionChange>,
+    media: HashSet<String>,
+    removed_media: Vec<String>,
+}
+
+/// Maps a change-log entity to the backup section that stores it and the root entity id
+/// inside that section. `None` as root means the owner could not be resolved (usually
+/// because the row is gone) and the whole section has to be stored.
+fn section_for_change(
+    conn: &DbConnection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Option<(&'static str, Option<String>)> {
+    let lookup = |sql: &str| {
+        conn.query_row(sql, params![entity_id], |row| row.get::<_, String>(0))
+            .optional()
+            .ok()
+            .flatten()
+    };
+    let own = Some(entity_id.to_string());
+
+    let mapped = match entity_type {
+        "meta" => ("meta", own),
+        "settings" => ("settings", None),
+        "persona" => ("personas", own),
+        "model" => ("models", own),
+        "secret" => ("secrets", own),
+        "provider_credential" => ("provider_credentials", own),
+        "prompt_template" => ("prompt_templates", own),
+        "lorebook" => ("lorebooks", own),
+        "lorebook_entry" => (
+            "lorebooks",
+            lookup("SELECT lorebook_id FROM lorebook_entries WHERE id = ?1"),
+        ),
+        "character" => ("characters", own),
+        "character_rule" => (
+            "characters",
+            entity_id
+                .rsplit_once(':')
+                .map(|(character_id, _)| character_id.to_string()),
+        ),
+        "scene" => (
+            "characters",
+            lookup("SELECT character_id FROM scenes WHERE id = ?1"),
+        ),
+        "scene_variant" => (
+            "characters",
+            lookup(
+                "SELECT s.character_id FROM scene_variants v JOIN scenes s ON s.id = v.scene_id WHERE v.id = ?1",
+            ),
+        ),
+        "chat_template" => ("chat_templates", own),
+        "chat_template_message" => (
+            "chat_templates",
+            lookup("SELECT template_id FROM chat_template_messages WHERE id = ?1"),
+        ),
+        "group_character" => ("group_characters", own),
+        "group_session" => ("group_sessions", own),
+        "group_participation" => (
+            "group_sessions",
+            lookup("SELECT session_id FROM group_participation WHERE id = ?1"),
+        ),
+        "group_message" => (
+            "group_sessions",
+            lookup("SELECT session_id FROM group_messages WHERE id = ?1"),
+        ),
+        "group_message_variant" => (
+            "group_sessions",
+            lookup(
+                "SELECT m.session_id FROM group_message_variants v JOIN group_messages m ON m.id = v.message_id WHERE v.id = ?1",
+            ),
+        ),
+        "session" => ("sessions", own),
+        "message" => (
+            "sessions",
+            lookup("SELECT session_id FROM messages WHERE id = ?1"),
+        ),
+        "message_variant" => (
+            "sessions",
+            lookup(
+                "SELECT m.session_id FROM message_variants v JOIN messages m ON m.id = v.message_id WHERE v.id = ?1",
+            ),
+        ),
+        "usage_record" | "group_usage_record" => ("usage_records", own),
+        "usage_metadata" | "group_usage_metadata" => (
+            "usage_records",
+            entity_id
+                .split_once(':')
+                .map(|(usage_id, _)| usage_id.to_string()),
+        ),
+        // TTS providers and voices are not part of backups
+        _ => return None,
+    };
+    Some(mapped)
+}
+
+fn plan_incremental(conn: &DbConnection, since_change_id: i64) -> Result<IncrementalPlan, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT entity_type, entity_id, op FROM sync_changes WHERE id > ?1 ORDER BY id ASC",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![since_change_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut sections: BTreeMap<&'static str, Option<BTreeSet<String>>> = BTreeMap::new();
+    let mut media = HashSet::new();
+    let mut removed_media = BTreeSet::new();
+
+    for (entity_type, entity_id, op) in rows {
+        if entity_type == "asset" {
+            if op == "delete" {
+                media.remove(&entity_id);
+                removed_media.insert(entity_id);
+            } else {
+                removed_media.remove(&entity_id);
+                media.insert(entity_id);
+            }
+            continue;
+        }
+
+        let Some((section, root)) = section_for_change(conn, &entity_type, &entity_id) else {
+            continue;
+        };
+        let entry = sections
+            .entry(section)
+            .or_insert_with(|| Some(BTreeSet::new()));
+        match root {
+            Some(root) => {
+                if let Some(roots) = entry {
+                    roots.insert(root);
+                }
+            }
+            None => *entry = None,
+        }
+    }
+
+    for section in UNTRACKED_SECTIONS {
+        sections.insert(section, None);
+    }
+
+    Ok(IncrementalPlan {
+        sections: sections
+            .into_iter()
+            .map(|(name, roots)| BackupSectionChange {
+                name: name.to_string(),
+                full: roots.is_none(),
+                roots: roots
+                    .map(|roots| roots.into_iter().collect())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+        media,
+        removed_media: removed_media.into_iter().collect(),
+    })
+}
+
+/// Number of archives from `archive` back to its base, or `None` if an ancestor is missing.
+fn chain_length(archives: &HashMap<String, ChainArchive>, archive: &ChainArchive) -> Option<usize> {
+    let mut length = 1;
+    let mut next = archive.chain.parent_id.as_deref();
+    while let Some(id) = next {
+        if length > archives.len() {
+            return None;
+        }
+        next = archives.get(id)?.chain.parent_id.as_deref();
+        length += 1;
+    }
+    Some(length)
+}
+
+/// Backup ids to keep under the retention policy: the newest archive, the newest archive
+/// of each of the last `keep_daily` days and `keep_weekly` ISO weeks, and every ancestor
+/// those archives need to be restored.
+fn select_backups_to_keep(
+    archives: &HashMap<String, ChainArchive>,
+    keep_daily: u32,
+    keep_weekly: u32,
+) -> HashSet<String> {
+    let mut newest_first: Vec<&ChainArchive> = archives.values().collect();
+    newest_first.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let mut keep = HashSet::new();
+    if let Some(latest) = newest_first.first() {
+        keep.insert(latest.chain.backup_id.clone());
+    }
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    for archive in &newest_first {
+        let Some(date) = chrono::DateTime::from_timestamp_millis(archive.created_at as i64)
+            .map(|time| time.date_naive())
+        else {
+            continue;
+        };
+        if !days.contains(&date) && days.len() < keep_daily as usize {
+            days.insert(date);
+            keep.insert(archive.chain.backup_id.clone());
+        }
+        let week = (date.iso_week().year(), date.iso_week().week());
+        if !weeks.contains(&week) && weeks.len() < keep_weekly as usize {
+            weeks.insert(week);
+            keep.insert(archive.chain.backup_id.clone());
+        }
+    }
+
+    // Incremental archives are useless without their ancestors
+    let selected: Vec<String> = keep.iter().cloned().collect();
+    for id in selected {
+        let mut next = archives.get(&id).and_then(|a| a.chain.parent_id.clone());
+        while let Some(parent) = next {
+            if !keep.insert(parent.clone()) {
+                break;
+            }
+            next = archives
+                .get(&parent)
+                .and_then(|a| a.chain.parent_id.clone());
+        }
+    }
+
+    keep
+}
+
+fn apply_retention(app: &tauri::AppHandle, dir: &Path, config: &BackupScheduleConfig) {
+    let archives = match scan_backup_chains(app, dir) {
+        Ok(archives) => archives,
+        Err(err) => {
+            log_error(
+                app,
+                "backup_schedule",
+                format!("retention scan failed: {}", err),
+            );
+            return;
+        }
+    };
+    let keep = select_backups_to_keep(&archives, config.keep_daily, config.keep_weekly);
+    for (id, archive) in &archives {
+        if keep.contains(id) {
+            continue;
+        }
+        match std::fs::remove_file(&archive.path) {
+            Ok(()) => log_info(
+                app,
+                "backup_schedule",
+                format!("retention removed {:?}", archive.path),
+            ),
+            Err(err) => log_error(
+                app,
+                "backup_schedule",
+                format!("failed to remove {:?}: {}", archive.path, err),
+            ),
+        }
+    }
+}
+
+/// Writes the next archive of the scheduled chain: incremental on top of the newest
+/// archive while its chain is intact and short enough, full otherwise.
+fn write_scheduled_backup(
+    app: &tauri::AppHandle,
+    config: &BackupScheduleConfig,
+    password: &str,
+) -> Result<PathBuf, String> {
+    let dir = config
+        .directory
+        .as_deref()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| "No backup folder selected".to_string())?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut conn = open_db(app)?;
+    crate::sync::db::rebuild_change_log(app, &mut conn)?;
+    let change_id: i64 = conn
+        .query_row("SELECT COALESCE(MAX(id), 0) FROM sync_changes", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let archives = scan_backup_chains(app, &dir)?;
+    // A change log reset (sync schema upgrade) invalidates older change ids
+    let parent = archives
+        .values()
+        .max_by_key(|archive| archive.created_at)
+        .filter(|latest| latest.chain.change_id <= change_id)
+        .filter(|latest| {
+            chain_length(&archives, latest)
+                .is_some_and(|length| length <= config.incrementals_per_full as usize)
+        });
+
+    let backup_id = uuid::Uuid::new_v4().to_string();
+    let (chain, media) = match parent {
+        Some(parent) => {
+            let plan = plan_incremental(&conn, parent.chain.change_id)?;
+            (
+                BackupChainInfo {
+                    backup_id,
+                    base_id: parent.chain.base_id.clone(),
+                    parent_id: Some(parent.chain.backup_id.clone()),
+                    change_id,
+                    sections: plan.sections,
+                    removed_media: plan.removed_media,
+                },
+                Some(plan.media),
+            )
+        }
+        None => (
+            BackupChainInfo {
+                backup_id: backup_id.clone(),
+                base_id: backup_id,
+                parent_id: None,
+                change_id,
+                sections: Vec::new(),
+                removed_media: Vec::new(),
+            },
+            None,
+        ),
+    };
+    drop(conn);
+
+    let kind = if chain.is_incremental() {
+        "incremental"
+    } else {
+        "full"
+    };
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let output_path = dir.join(format!("lettuce_{}_{}.lettuce", kind, timestamp));
+    write_backup_archive(app, &output_path, password, Some(chain), media.as_ref())?;
+
+    apply_retention(app, &dir, config);
+    Ok(output_path)
+}
+
+fn run_scheduled_backup(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let conn = open_db(app)?;
+    let mut schedule = load_schedule(&conn)?;
+    let stored = secrets::get_secret(&conn, PASSWORD_SERVICE, PASSWORD_ACCOUNT)
+        .map_err(|e| format!("Could not read the scheduled backup password: {}", e))?;
+    drop(conn);
+
+    let password = match stored {
+        Some(password) => password,
+        None if schedule.has_password => {
+            return Err(
+                "The scheduled backup password is missing; enter it again in backup settings"
+                    .to_string(),
+            )
+        }
+        None => return Err("No password set for scheduled backups".to_string()),
+    };
+    let result = write_scheduled_backup(app, &schedule.config, &password);
+
+    schedule.last_run_at = Some(now_ms());
+    schedule.last_error = result.as_ref().err().cloned();
+    let conn = open_db(app)?;
+    save_schedule(&conn, &schedule)?;
+    result
+}
+
+fn run_if_due(app: &tauri::AppHandle) -> Result<(), String> {
+    let conn = open_db(app)?;
+    let schedule = load_schedule(&conn)?;
+    drop(conn);
+
+    let due = schedule
+        .next_run_at()
+        .is_some_and(|next_run_at| next_run_at <= now_ms());
+    if !due || !schedule.has_password {
+        return Ok(());
+    }
+
+    let path = run_scheduled_backup(app)?;
+    log_info(
+        app,
+        "backup_schedule",
+        format!("scheduled backup written to {:?}", path),
+    );
+    Ok(())
+}
+
+pub fn start_backup_scheduler(app: &tauri::AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            let app = app_handle.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || run_if_due(&app)).await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => log_error(
+                    &app_handle,
+                    "backup_schedule",
+                    format!("scheduled backup failed: {}", err),
+                ),
+                Err(err) => log_error(
+                    &app_handle,
+                    "backup_schedule",
+                    format!("scheduled backup task join error: {}", err),
+                ),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn backup_schedule_get(app: tauri::AppHandle) -> Result<BackupScheduleStatus, String> {
+    let conn = open_db(&app)?;
+    Ok(load_schedule(&conn)?.status())
+}
+
+/// Saves the schedule. `password` replaces the stored one when given and is kept otherwise.
+#[tauri::command]
+pub fn backup_schedule_set(
+    app: tauri::AppHandle,
+    config: BackupScheduleConfig,
+    password: Option<String>,
+) -> Result<BackupScheduleStatus, String> {
+    let conn = open_db(&app)?;
+    let mut schedule = load_schedule(&conn)?;
+
+    let new_password = password.filter(|value| !value.trim().is_empty());
+    if new_password.is_some() {
+        schedule.has_password = true;
+    }
+    if config.enabled {
+        if config
+            .directory
+            .as_deref()
+            .is_none_or(|dir| dir.trim().is_empty())
+        {
+            return Err("Choose a folder for scheduled backups".to_string());
+        }
+        if !schedule.has_password {
+            return Err("Scheduled backups need a password".to_string());
+        }
+    }
+    if config.keep_daily == 0 && config.keep_weekly == 0 {
+        return Err("Retention must keep at least one daily or weekly backup".to_string());
+    }
+
+    if let Some(password) = new_password {
+        secrets::set_secret(&conn, PASSWORD_SERVICE, PASSWORD_ACCOUNT, &password)?;
+    }
+    schedule.config = config;
+    save_schedule(&conn, &schedule)?;
+    Ok(schedule.status())
+}
+
+#[tauri::command]
+pub async fn backup_schedule_run_now(app: tauri::AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || run_scheduled_backup(&app))
+        .await
+        .map_err(|e| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Scheduled backup task join error: {}", e),
+            )
+        })?
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_backups_to_keep, BackupChainInfo, ChainArchive, DAY_MS};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    // 2026-03-02 00:00 UTC, a Monday
+    const MONDAY: u64 = 1_772_409_600_000;
+
+    fn archive(id: &str, parent: Option<&str>, base: &str, created_at: u64) -> ChainArchive {
+        ChainArchive {
+            path: PathBuf::from(format!("{}.lettuce", id)),
+            chain: BackupChainInfo {
+                backup_id: id.to_string(),
+                base_id: base.to_string(),
+                parent_id: parent.map(str::to_string),
+                change_id: 0,
+                sections: Vec::new(),
+                removed_media: Vec::new(),
+            },
+            created_at,
+        }
+    }
+
+    fn index(archives: Vec<ChainArchive>) -> HashMap<String, ChainArchive> {
+        archives
+            .into_iter()
+            .map(|archive| (archive.chain.backup_id.clone(), archive))
+            .collect()
+    }
+
+    #[test]
+    fn retention_keeps_newest_per_day_and_week() {
+        let day = DAY_MS as u64;
+        let archives = index(vec![
+            archive("w-old", None, "w-old", MONDAY - 7 * day),
+            archive("w-prev", None, "w-prev", MONDAY + day),
+            archive("d1", None, "d1", MONDAY + 8 * day),
+            archive("d2-early", None, "d2-early", MONDAY + 9 * day),
+            archive("d2-late", None, "d2-late", MONDAY + 9 * day + 3_600_000),
+        ]);
+
+        let keep = select_backups_to_keep(&archives, 2, 2);
+
+        assert!(keep.contains("d2-late"));
+        assert!(keep.contains("d1"));
+        assert!(!keep.contains("d2-early"));
+        // d2-late covers the newest week; w-prev is the newest of the week before
+        assert!(keep.contains("w-prev"));
+        assert!(!keep.contains("w-old"));
+    }
+
+    #[test]
+    fn retention_keeps_ancestors_of_kept_incrementals() {
+        let day = DAY_MS as u64;
+        let archives = index(vec![
+            archive("old", None, "old", MONDAY - 30 * day),
+            archive("base", None, "base", MONDAY),
+            archive("inc1", Some("base"), "base", MONDAY + day),
+            archive("inc2", Some("inc1"), "base", MONDAY + 2 * day),
+        ]);
+
+        let keep = select_backups_to_keep(&archives, 1, 0);
+
+        assert!(keep.contains("inc2"));
+        assert!(keep.contains("inc1"));
+        assert!(keep.contains("base"));
+        assert!(!keep.contains("old"));
+    }
+}
@@ -0,0 +1,704 @@
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::{Emitter, Manager};
+use walkdir::WalkDir;
+
+use super::backup::{
+    insert_characters, insert_lorebooks, insert_personas, insert_sessions, BackupReader,
+};
+use super::db::open_db;
+use super::legacy::storage_root;
+use crate::utils::log_info;
+
+/// One restorable entity as listed from a backup.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupEntitySummary {
+    pub id: String,
+    pub name: String,
+    /// Owning character, for sessions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character_id: Option<String>,
+    /// Sessions of a character, messages of a session or entries of a lorebook.
+    pub item_count: usize,
+    pub updated_at: Option<i64>,
+    /// Whether the live database already has a row with this id.
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupContents {
+    pub characters: Vec<BackupEntitySummary>,
+    pub sessions: Vec<BackupEntitySummary>,
+    pub lorebooks: Vec<BackupEntitySummary>,
+    pub personas: Vec<BackupEntitySummary>,
+}
+
+/// Entities to merge back. Selecting a character brings all of its sessions along.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSelection {
+    #[serde(default)]
+    pub character_ids: Vec<String>,
+    #[serde(default)]
+    pub session_ids: Vec<String>,
+    #[serde(default)]
+    pub lorebook_ids: Vec<String>,
+    #[serde(default)]
+    pub persona_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRestoreReport {
+    pub characters: usize,
+    pub sessions: usize,
+    pub lorebooks: usize,
+    pub personas: usize,
+    pub media_files: usize,
+    /// Backup id -> live id for every restored row whose id was already taken.
+    pub remapped: HashMap<String, String>,
+}
+
+fn section_items(reader: &mut BackupReader, name: &str) -> Result<Vec<JsonValue>, String> {
+    Ok(match reader.read_section(name)? {
+        Some(JsonValue::Array(items)) => items,
+        _ => Vec::new(),
+    })
+}
+
+fn str_field<'a>(item: &'a JsonValue, key: &str) -> Option<&'a str> {
+    item.get(key).and_then(|v| v.as_str())
+}
+
+fn is_selected(ids: &HashSet<&str>, item: &JsonValue) -> bool {
+    str_field(item, "id").is_some_and(|id| ids.contains(id))
+}
+
+fn array_len(item: &JsonValue, key: &str) -> usize {
+    item.get(key)
+        .and_then(|v| v.as_array())
+        .map(Vec::len)
+        .unwrap_or(0)
+}
+
+fn id_exists(conn: &rusqlite::Connection, table: &str, id: &str) -> Result<bool, String> {
+    conn.query_row(
+        &format!("SELECT 1 FROM {} WHERE id = ?1", table),
+        params![id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|row| row.is_some())
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+/// Assigns live ids to restored rows, keeping the backup id unless it is already taken.
+#[derive(Default)]
+struct IdRemap {
+    map: HashMap<String, String>,
+    claimed: HashSet<String>,
+}
+
+impl IdRemap {
+    fn assign(&mut self, id: &str, exists: bool) -> String {
+        if let Some(live) = self.map.get(id) {
+            return live.clone();
+        }
+        let live = if exists || self.claimed.contains(id) {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            id.to_string()
+        };
+        self.claimed.insert(live.clone());
+        self.map.insert(id.to_string(), live.clone());
+        live
+    }
+
+    fn claim(
+        &mut self,
+        conn: &rusqlite::Connection,
+        table: &str,
+        id: &str,
+    ) -> Result<String, String> {
+        if let Some(live) = self.map.get(id) {
+            return Ok(live.clone());
+        }
+        let exists = id_exists(conn, table, id)?;
+        Ok(self.assign(id, exists))
+    }
+
+    /// Live id for a backup id; ids that were not restored are left as they are.
+    fn resolve<'a>(&'a self, id: &'a str) -> &'a str {
+        self.map.get(id).map(String::as_str).unwrap_or(id)
+    }
+
+    fn resolve_field(&self, item: &mut JsonValue, key: &str) {
+        let Some(id) = str_field(item, key) else {
+            return;
+        };
+        let live = self.resolve(id).to_string();
+        item[key] = JsonValue::String(live);
+    }
+
+    /// Rewrites a JSON-encoded id array such as `active_lorebook_ids`.
+    fn resolve_list_field(&self, item: &mut JsonValue, key: &str) {
+        let Some(raw) = str_field(item, key) else {
+            return;
+        };
+        let Ok(list) = serde_json::from_str::<Vec<String>>(raw) else {
+            return;
+        };
+        let list = list
+            .iter()
+            .map(|id| self.resolve(id).to_string())
+            .collect::<Vec<_>>();
+        if let Ok(raw) = serde_json::to_string(&list) {
+            item[key] = JsonValue::String(raw);
+        }
+    }
+
+    fn into_renamed(self) -> HashMap<String, String> {
+        self.map
+            .into_iter()
+            .filter(|(backup_id, live_id)| backup_id != live_id)
+            .collect()
+    }
+}
+
+/// Media paths the restored entities reference, and where each one lands.
+#[derive(Default)]
+struct MediaPlan {
+    /// Archive directory prefix -> live directory prefix, both ending in '/'.
+    dirs: Vec<(String, String)>,
+    files: HashSet<String>,
+    image_ids: HashSet<String>,
+}
+
+impl MediaPlan {
+    fn add_dir(&mut self, from: String, to: String) {
+        self.dirs.push((format!("{}/", from), format!("{}/", to)));
+    }
+
+    fn add_avatar_dir(&mut self, kind: &str, backup_id: &str, live_id: &str) {
+        self.add_dir(
+            format!("avatars/{}", backup_id),
+            format!("avatars/{}", live_id),
+        );
+        self.add_dir(
+            format!("avatars/{}-{}", kind, backup_id),
+            format!("avatars/{}-{}", kind, live_id),
+        );
+    }
+
+    fn add_session_dir(&mut self, backup: (&str, &str), live: (&str, &str)) {
+        let ((backup_character, backup_session), (live_character, live_session)) = (backup, live);
+        self.add_dir(
+            format!("sessions/{}/{}", backup_character, backup_session),
+            format!("sessions/{}/{}", live_character, live_session),
+        );
+        self.add_dir(
+            format!("sessions/character-{}/{}", backup_character, backup_session),
+            format!("sessions/character-{}/{}", live_character, live_session),
+        );
+    }
+
+    /// Image references are either a bare image id under `images/` or a storage path.
+    fn add_image_ref(&mut self, reference: Option<&str>) {
+        let Some(reference) = reference.filter(|value| !value.is_empty()) else {
+            return;
+        };
+        if reference.contains('/') {
+            self.files.insert(reference.to_string());
+        } else {
+            self.image_ids.insert(reference.to_string());
+        }
+    }
+
+    fn add_image_list(&mut self, raw: Option<&str>) {
+        let Some(ids) = raw.and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok()) else {
+            return;
+        };
+        for id in ids {
+            self.add_image_ref(Some(&id));
+        }
+    }
+
+    /// Records a stored attachment path and returns the path it will have once restored.
+    fn add_attachment(&mut self, path: &str) -> String {
+        match self.map(path) {
+            Some(live) => live,
+            None => {
+                self.files.insert(path.to_string());
+                path.to_string()
+            }
+        }
+    }
+
+    fn map(&self, path: &str) -> Option<String> {
+        if self.files.contains(path) {
+            return Some(path.to_string());
+        }
+        if let Some(name) = path.strip_prefix("images/") {
+            let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+            if !name.contains('/') && self.image_ids.contains(stem) {
+                return Some(path.to_string());
+            }
+        }
+        self.dirs.iter().find_map(|(from, to)| {
+            path.strip_prefix(from.as_str())
+                .map(|rest| format!("{}{}", to, rest))
+        })
+    }
+}
+
+fn rewrite_attachments(message: &mut JsonValue, media: &mut MediaPlan) {
+    let Some(raw) = str_field(message, "attachments") else {
+        return;
+    };
+    let Ok(mut attachments) = serde_json::from_str::<Vec<JsonValue>>(raw) else {
+        return;
+    };
+    for attachment in attachments.iter_mut() {
+        if let Some(path) = str_field(attachment, "path").map(str::to_string) {
+            attachment["path"] = JsonValue::String(media.add_attachment(&path));
+        }
+    }
+    if let Ok(raw) = serde_json::to_string(&attachments) {
+        message["attachments"] = JsonValue::String(raw);
+    }
+}
+
+fn summarize(
+    conn: &rusqlite::Connection,
+    items: &[JsonValue],
+    table: &str,
+    name_key: &str,
+    count: impl Fn(&JsonValue) -> usize,
+) -> Result<Vec<BackupEntitySummary>, String> {
+    let mut summaries = Vec::new();
+    for item in items {
+        let Some(id) = str_field(item, "id") else {
+            continue;
+        };
+        summaries.push(BackupEntitySummary {
+            id: id.to_string(),
+            name: str_field(item, name_key).unwrap_or_default().to_string(),
+            character_id: str_field(item, "character_id").map(str::to_string),
+            item_count: count(item),
+            updated_at: item.get("updated_at").and_then(|v| v.as_i64()),
+            exists: id_exists(conn, table, id)?,
+        });
+    }
+    Ok(summaries)
+}
+
+/// Lists the characters, sessions, lorebooks and personas stored in a backup.
+#[tauri::command]
+pub async fn backup_list_contents(
+    app: tauri::AppHandle,
+    backup_path: String,
+    password: Option<String>,
+) -> Result<BackupContents, String> {
+    let mut reader = BackupReader::open_path(&app, &backup_path, password.as_deref())?;
+    let characters = section_items(&mut reader, "characters")?;
+    let sessions = section_items(&mut reader, "sessions")?;
+    let lorebooks = section_items(&mut reader, "lorebooks")?;
+    let personas = section_items(&mut reader, "personas")?;
+
+    let mut session_counts: HashMap<&str, usize> = HashMap::new();
+    for session in &sessions {
+        if let Some(character_id) = str_field(session, "character_id") {
+            *session_counts.entry(character_id).or_default() += 1;
+        }
+    }
+
+    let conn = open_db(&app)?;
+    Ok(BackupContents {
+        characters: summarize(&conn, &characters, "characters", "name", |item| {
+            str_field(item, "id")
+                .and_then(|id| session_counts.get(id).copied())
+                .unwrap_or(0)
+        })?,
+        sessions: summarize(&conn, &sessions, "sessions", "title", |item| {
+            array_len(item, "messages")
+        })?,
+        lorebooks: summarize(&conn, &lorebooks, "lorebooks", "name", |item| {
+            array_len(item, "entries")
+        })?,
+        personas: summarize(&conn, &personas, "personas", "title", |_| 0)?,
+    })
+}
+
+/// Merges the selected entities from a backup into the live database without touching
+/// anything else. Rows whose id is already taken get a fresh id, and only the media the
+/// restored rows reference is copied.
+#[tauri::command]
+pub async fn backup_restore_selected(
+    app: tauri::AppHandle,
+    backup_path: String,
+    password: Option<String>,
+    selection: BackupSelection,
+) -> Result<BackupRestoreReport, String> {
+    let mut reader = BackupReader::open_path(&app, &backup_path, password.as_deref())?;
+    let characters = section_items(&mut reader, "characters")?;
+    let sessions = section_items(&mut reader, "sessions")?;
+    let lorebooks = section_items(&mut reader, "lorebooks")?;
+    let personas = section_items(&mut reader, "personas")?;
+
+    // All rows land in one transaction so a failure leaves no half-merged restore.
+    // Media is copied only once the rows are committed.
+    let mut conn = open_db(&app)?;
+    conn.execute_batch("BEGIN IMMEDIATE")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let merged = merge_selected(
+        &app,
+        &mut conn,
+        &characters,
+        &sessions,
+        &lorebooks,
+        &personas,
+        &selection,
+    );
+    let (ids, media, mut report) = match merged {
+        Ok(merged) => {
+            conn.execute_batch("COMMIT")
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            merged
+        }
+        Err(err) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(err);
+        }
+    };
+    drop(conn);
+
+    let storage = storage_root(&app)?;
+    let staging_dir = storage.join(".restore_staging");
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    fs::create_dir_all(&staging_dir)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let generated_images_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .join("generated_images");
+    let select: &dyn Fn(&str) -> Option<String> = &|path| media.map(path);
+    let copied = reader
+        .extract_media(&staging_dir, Some(select))
+        .and_then(|_| {
+            let mut copied = 0;
+            for root in ["images", "avatars", "attachments", "sessions"] {
+                copied += copy_missing_files(&staging_dir.join(root), &storage.join(root))?;
+            }
+            copied +=
+                copy_missing_files(&staging_dir.join("generated_images"), &generated_images_dir)?;
+            Ok(copied)
+        });
+    fs::remove_dir_all(&staging_dir).ok();
+    report.media_files = copied?;
+
+    report.remapped = ids.into_renamed();
+    log_info(
+        &app,
+        "backup",
+        format!(
+            "Selective restore complete: {} characters, {} sessions, {} lorebooks, {} personas, {} media files, {} remapped ids",
+            report.characters,
+            report.sessions,
+            report.lorebooks,
+            report.personas,
+            report.media_files,
+            report.remapped.len()
+        ),
+    );
+    app.emit("database-reloaded", ()).ok();
+    Ok(report)
+}
+
+/// Inserts the selected rows under their live ids. Runs inside the caller's
+/// transaction and returns the id map and the media the rows reference.
+fn merge_selected(
+    app: &tauri::AppHandle,
+    conn: &mut rusqlite::Connection,
+    characters: &[JsonValue],
+    sessions: &[JsonValue],
+    lorebooks: &[JsonValue],
+    personas: &[JsonValue],
+    selection: &BackupSelection,
+) -> Result<(IdRemap, MediaPlan, BackupRestoreReport), String> {
+    let mut character_ids: HashSet<&str> =
+        selection.character_ids.iter().map(String::as_str).collect();
+    let mut session_ids: HashSet<&str> = selection.session_ids.iter().map(String::as_str).collect();
+    for session in sessions {
+        if let (Some(id), Some(character_id)) =
+            (str_field(session, "id"), str_field(session, "character_id"))
+        {
+            if character_ids.contains(character_id) {
+                session_ids.insert(id);
+            }
+        }
+    }
+    // A session on its own still needs its character; bring it back if it is gone.
+    for session in sessions {
+        let (Some(id), Some(character_id)) =
+            (str_field(session, "id"), str_field(session, "character_id"))
+        else {
+            continue;
+        };
+        if !session_ids.contains(id)
+            || character_ids.contains(character_id)
+            || id_exists(conn, "characters", character_id)?
+        {
+            continue;
+        }
+        if !characters
+            .iter()
+            .any(|item| str_field(item, "id") == Some(character_id))
+        {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!(
+                    "Session {} belongs to a character that is not in this backup",
+                    id
+                ),
+            ));
+        }
+        character_ids.insert(character_id);
+    }
+    let lorebook_ids: HashSet<&str> = selection.lorebook_ids.iter().map(String::as_str).collect();
+    let persona_ids: HashSet<&str> = selection.persona_ids.iter().map(String::as_str).collect();
+
+    let mut ids = IdRemap::default();
+    let mut media = MediaPlan::default();
+    let mut report = BackupRestoreReport::default();
+
+    // Lorebooks and personas first so characters and sessions can point at their live ids
+    let mut restored = Vec::new();
+    for item in lorebooks
+        .iter()
+        .filter(|item| is_selected(&lorebook_ids, item))
+    {
+        let mut item = item.clone();
+        let backup_id = str_field(&item, "id").unwrap_or_default().to_string();
+        item["id"] = JsonValue::String(ids.claim(conn, "lorebooks", &backup_id)?);
+        media.add_image_ref(str_field(&item, "avatar_path"));
+        if let Some(entries) = item.get_mut("entries").and_then(|v| v.as_array_mut()) {
+            for entry in entries {
+                if let Some(entry_id) = str_field(entry, "id").map(str::to_string) {
+                    entry["id"] =
+                        JsonValue::String(ids.claim(conn, "lorebook_entries", &entry_id)?);
+                }
+            }
+        }
+        restored.push(item);
+    }
+    report.lorebooks = restored.len();
+    insert_lorebooks(conn, &JsonValue::Array(restored))?;
+
+    let mut restored = Vec::new();
+    for item in personas
+        .iter()
+        .filter(|item| is_selected(&persona_ids, item))
+    {
+        let mut item = item.clone();
+        let backup_id = str_field(&item, "id").unwrap_or_default().to_string();
+        let live_id = ids.claim(conn, "personas", &backup_id)?;
+        media.add_avatar_dir("persona", &backup_id, &live_id);
+        media.add_image_list(str_field(&item, "design_reference_image_ids"));
+        item["id"] = JsonValue::String(live_id);
+        // Restoring never takes the default flag away from the live default persona
+        item["is_default"] = JsonValue::Bool(false);
+        ids.resolve_list_field(&mut item, "active_lorebook_ids");
+        restored.push(item);
+    }
+    report.personas = restored.len();
+    insert_personas(conn, &JsonValue::Array(restored))?;
+
+    let mut restored = Vec::new();
+    for item in characters
+        .iter()
+        .filter(|item| is_selected(&character_ids, item))
+    {
+        let mut item = item.clone();
+        let backup_id = str_field(&item, "id").unwrap_or_default().to_string();
+        let live_id = ids.claim(conn, "characters", &backup_id)?;
+        media.add_avatar_dir("character", &backup_id, &live_id);
+        media.add_image_ref(str_field(&item, "background_image_path"));
+        media.add_image_list(str_field(&item, "design_reference_image_ids"));
+        item["id"] = JsonValue::String(live_id);
+        if let Some(scenes) = item.get_mut("scenes").and_then(|v| v.as_array_mut()) {
+            for scene in scenes {
+                if let Some(scene_id) = str_field(scene, "id").map(str::to_string) {
+                    scene["id"] = JsonValue::String(ids.claim(conn, "scenes", &scene_id)?);
+                }
+                media.add_image_ref(str_field(scene, "background_image_path"));
+                if let Some(variants) = scene.get_mut("variants").and_then(|v| v.as_array_mut()) {
+                    for variant in variants {
+                        if let Some(variant_id) = str_field(variant, "id").map(str::to_string) {
+                            variant["id"] = JsonValue::String(ids.claim(
+                                conn,
+                                "scene_variants",
+                                &variant_id,
+                            )?);
+                        }
+                    }
+                }
+                ids.resolve_field(scene, "selected_variant_id");
+            }
+        }
+        ids.resolve_field(&mut item, "default_scene_id");
+        ids.resolve_list_field(&mut item, "active_lorebook_ids");
+        restored.push(item);
+    }
+    report.characters = restored.len();
+    insert_characters(app, conn, &JsonValue::Array(restored))?;
+
+    let mut restored = Vec::new();
+    for item in sessions
+        .iter()
+        .filter(|item| is_selected(&session_ids, item))
+    {
+        let mut item = item.clone();
+        let backup_id = str_field(&item, "id").unwrap_or_default().to_string();
+        let backup_character = str_field(&item, "character_id")
+            .unwrap_or_default()
+            .to_string();
+        let live_id = ids.claim(conn, "sessions", &backup_id)?;
+        let live_character = ids.resolve(&backup_character).to_string();
+        media.add_session_dir(
+            (backup_character.as_str(), backup_id.as_str()),
+            (live_character.as_str(), live_id.as_str()),
+        );
+        media.add_image_ref(str_field(&item, "background_image_path"));
+        item["id"] = JsonValue::String(live_id);
+        item["character_id"] = JsonValue::String(live_character);
+        ids.resolve_field(&mut item, "selected_scene_id");
+        ids.resolve_field(&mut item, "persona_id");
+        ids.resolve_list_field(&mut item, "lorebook_ids_override");
+        if let Some(messages) = item.get_mut("messages").and_then(|v| v.as_array_mut()) {
+            for message in messages {
+                if let Some(message_id) = str_field(message, "id").map(str::to_string) {
+                    message["id"] = JsonValue::String(ids.claim(conn, "messages", &message_id)?);
+                }
+                if let Some(variants) = message.get_mut("variants").and_then(|v| v.as_array_mut()) {
+                    for variant in variants {
+                        if let Some(variant_id) = str_field(variant, "id").map(str::to_string) {
+                            variant["id"] = JsonValue::String(ids.claim(
+                                conn,
+                                "message_variants",
+                                &variant_id,
+                            )?);
+                        }
+                    }
+                }
+                ids.resolve_field(message, "selected_variant_id");
+                rewrite_attachments(message, &mut media);
+            }
+        }
+        restored.push(item);
+    }
+    report.sessions = restored.len();
+    insert_sessions(app, conn, &JsonValue::Array(restored))?;
+    Ok((ids, media, report))
+}
+
+/// Copies staged media into place, leaving files that already exist untouched.
+fn copy_missing_files(src: &Path, dst: &Path) -> Result<usize, String> {
+    let mut copied = 0;
+    if !src.exists() {
+        return Ok(copied);
+    }
+    for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(src)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let target = dst.join(relative);
+        if target.exists() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+        fs::copy(entry.path(), &target)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdRemap, MediaPlan};
+
+    #[test]
+    fn id_remap_keeps_free_ids_and_renames_taken_ones() {
+        let mut ids = IdRemap::default();
+        assert_eq!(ids.assign("free", false), "free");
+        let renamed = ids.assign("taken", true);
+        assert_ne!(renamed, "taken");
+        assert_eq!(ids.assign("taken", true), renamed);
+        assert_eq!(ids.resolve("taken"), renamed);
+        assert_eq!(ids.resolve("other"), "other");
+
+        let mut item = serde_json::json!({ "active_lorebook_ids": "[\"taken\",\"other\"]" });
+        ids.resolve_list_field(&mut item, "active_lorebook_ids");
+        assert_eq!(
+            item["active_lorebook_ids"],
+            serde_json::to_string(&[renamed.as_str(), "other"]).unwrap()
+        );
+
+        let renamed_only = ids.into_renamed();
+        assert_eq!(renamed_only.len(), 1);
+        assert!(renamed_only.contains_key("taken"));
+    }
+
+    #[test]
+    fn media_plan_copies_only_referenced_media_under_live_ids() {
+        let mut media = MediaPlan::default();
+        media.add_avatar_dir("character", "old", "new");
+        media.add_session_dir(("old", "s1"), ("new", "s2"));
+        media.add_image_ref(Some("bg"));
+        media.add_image_list(Some("[\"generated_images/ref.png\"]"));
+
+        assert_eq!(
+            media
+                .map("avatars/character-old/avatar_base.webp")
+                .as_deref(),
+            Some("avatars/character-new/avatar_base.webp")
+        );
+        assert_eq!(
+            media.map("sessions/old/s1/user_m_a.webp").as_deref(),
+            Some("sessions/new/s2/user_m_a.webp")
+        );
+        assert_eq!(
+            media.map("images/bg.webp").as_deref(),
+            Some("images/bg.webp")
+        );
+        assert_eq!(
+            media.map("generated_images/ref.png").as_deref(),
+            Some("generated_images/ref.png")
+        );
+        assert_eq!(media.map("images/other.webp"), None);
+        assert_eq!(media.map("avatars/character-older/avatar.webp"), None);
+        assert_eq!(media.map("sessions/old/s3/user_m_a.webp"), None);
+        assert_eq!(
+            media.add_attachment("sessions/character-old/s1/ai_m_b.webp"),
+            "sessions/character-new/s2/ai_m_b.webp"
+        );
+    }
+}
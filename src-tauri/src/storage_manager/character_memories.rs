@@ -0,0 +1,573 @@
+//! Character-level long-term memory. Session memories that matter beyond a
+//! single chat are promoted into `character_memories` (manually or by the
+//! auto-promotion rule in `chat_manager::memory::character`) and retrieved into
+//! every session with that character. The store is opt-in per character via
+//! `character_memory_settings`.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::db::{now_ms, open_db};
+use super::memory_embeddings::{blob_to_embedding, embedding_to_blob, AccessUpdate, SessionKind};
+use crate::chat_manager::memory::dynamic::find_duplicate_memory_reason;
+use crate::chat_manager::types::MemoryEmbedding;
+use crate::embedding;
+use crate::utils::{log_info, log_warn};
+
+pub const DEFAULT_CHARACTER_MEMORY_TOKEN_BUDGET: u32 = 600;
+pub const DEFAULT_CHARACTER_MEMORY_RETRIEVAL_LIMIT: u32 = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterMemoryConfig {
+    pub enabled: bool,
+    /// Upper bound on tokens injected per turn from the character store.
+    pub token_budget: u32,
+    pub retrieval_limit: u32,
+    /// Promote qualifying session memories after each dynamic memory cycle.
+    pub auto_promote: bool,
+    pub promote_min_importance: f32,
+    pub promote_min_access_count: u32,
+}
+
+impl Default for CharacterMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_budget: DEFAULT_CHARACTER_MEMORY_TOKEN_BUDGET,
+            retrieval_limit: DEFAULT_CHARACTER_MEMORY_RETRIEVAL_LIMIT,
+            auto_promote: false,
+            promote_min_importance: 0.8,
+            promote_min_access_count: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterMemory {
+    pub character_id: String,
+    #[serde(flatten)]
+    pub memory: MemoryEmbedding,
+    /// Session the memory was promoted from, if any.
+    pub source_session_id: Option<String>,
+    /// Id of the session memory it was promoted from.
+    pub source_memory_id: Option<String>,
+    /// "manual" or "auto".
+    pub promoted_by: String,
+}
+
+pub fn load_config(conn: &Connection, character_id: &str) -> Result<CharacterMemoryConfig, String> {
+    let config = conn
+        .query_row(
+            "SELECT enabled, token_budget, retrieval_limit, auto_promote, promote_min_importance, promote_min_access_count
+             FROM character_memory_settings WHERE character_id = ?1",
+            params![character_id],
+            |r| {
+                Ok(CharacterMemoryConfig {
+                    enabled: r.get::<_, i64>(0)? != 0,
+                    token_budget: r.get::<_, i64>(1)?.max(0) as u32,
+                    retrieval_limit: r.get::<_, i64>(2)?.max(1) as u32,
+                    auto_promote: r.get::<_, i64>(3)? != 0,
+                    promote_min_importance: r.get::<_, f64>(4)? as f32,
+                    promote_min_access_count: r.get::<_, i64>(5)?.max(0) as u32,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(config.unwrap_or_default())
+}
+
+fn save_config(
+    conn: &Connection,
+    character_id: &str,
+    config: &CharacterMemoryConfig,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO character_memory_settings (character_id, enabled, token_budget, retrieval_limit, auto_promote, promote_min_importance, promote_min_access_count, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(character_id) DO UPDATE SET
+           enabled = excluded.enabled,
+           token_budget = excluded.token_budget,
+           retrieval_limit = excluded.retrieval_limit,
+           auto_promote = excluded.auto_promote,
+           promote_min_importance = excluded.promote_min_importance,
+           promote_min_access_count = excluded.promote_min_access_count,
+           updated_at = excluded.updated_at",
+        params![
+            character_id,
+            config.enabled as i64,
+            config.token_budget as i64,
+            config.retrieval_limit.max(1) as i64,
+            config.auto_promote as i64,
+            config.promote_min_importance.clamp(0.0, 1.0) as f64,
+            config.promote_min_access_count as i64,
+            now_ms() as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn load_for_character(
+    conn: &Connection,
+    character_id: &str,
+) -> Result<Vec<CharacterMemory>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT memory_id, embedding, embedding_model, text, token_count, category, \
+             importance_score, is_cold, is_pinned, access_count, source_session_id, \
+             source_memory_id, promoted_by, created_at, last_accessed_at \
+             FROM character_memories WHERE character_id = ?1 ORDER BY created_at ASC",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![character_id], |r| {
+            let embedding = blob_to_embedding(&r.get::<_, Vec<u8>>(1)?);
+            let embedding_dimensions = if embedding.is_empty() {
+                None
+            } else {
+                Some(embedding.len())
+            };
+            let importance_score = r.get::<_, f64>(6)? as f32;
+            Ok(CharacterMemory {
+                character_id: character_id.to_string(),
+                memory: MemoryEmbedding {
+                    id: r.get(0)?,
+                    text: r.get(3)?,
+                    embedding,
+                    created_at: r.get::<_, i64>(13)? as u64,
+                    token_count: r.get::<_, i64>(4)? as u32,
+                    is_cold: r.get::<_, i64>(7)? != 0,
+                    last_accessed_at: r.get::<_, i64>(14)? as u64,
+                    importance_score,
+                    persistence_importance: importance_score,
+                    prompt_importance: importance_score,
+                    volatility: 0.0,
+                    is_pinned: r.get::<_, i64>(8)? != 0,
+                    access_count: r.get::<_, i64>(9)? as u32,
+                    embedding_source_version: r.get(2)?,
+                    embedding_dimensions,
+                    match_score: None,
+                    category: r.get(5)?,
+                    canonical_entities: Vec::new(),
+                    fact_signature: None,
+                    fact_polarity: None,
+                    source_role: None,
+                    source_message_id: None,
+                    superseded_by: None,
+                    superseded_at: None,
+                    supersedes: Vec::new(),
+                },
+                source_session_id: r.get(10)?,
+                source_memory_id: r.get(11)?,
+                promoted_by: r.get(12)?,
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?);
+    }
+    Ok(out)
+}
+
+pub fn insert(conn: &Connection, entry: &CharacterMemory) -> Result<(), String> {
+    let m = &entry.memory;
+    conn.execute(
+        "INSERT INTO character_memories (\
+            character_id, memory_id, embedding, embedding_dim, embedding_model, text, \
+            token_count, category, importance_score, is_cold, is_pinned, access_count, \
+            source_session_id, source_memory_id, promoted_by, created_at, last_accessed_at, \
+            updated_at\
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        params![
+            &entry.character_id,
+            &m.id,
+            embedding_to_blob(&m.embedding),
+            m.embedding.len() as i64,
+            &m.embedding_source_version,
+            &m.text,
+            m.token_count as i64,
+            &m.category,
+            m.importance_score as f64,
+            m.is_cold as i64,
+            m.is_pinned as i64,
+            m.access_count as i64,
+            &entry.source_session_id,
+            &entry.source_memory_id,
+            &entry.promoted_by,
+            m.created_at as i64,
+            m.last_accessed_at as i64,
+            now_ms() as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Narrow update used after retrieval, mirroring `memory_embeddings::apply_access_updates`.
+pub fn apply_access_updates(
+    conn: &mut Connection,
+    character_id: &str,
+    updates: &[AccessUpdate],
+) -> Result<(), String> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+    let tx = conn
+        .transaction()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "UPDATE character_memories \
+                 SET importance_score = ?1, last_accessed_at = ?2, access_count = ?3, updated_at = ?4 \
+                 WHERE character_id = ?5 AND memory_id = ?6",
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let now = now_ms() as i64;
+        for u in updates {
+            stmt.execute(params![
+                u.importance_score as f64,
+                u.last_accessed_at as i64,
+                u.access_count as i64,
+                now,
+                character_id,
+                &u.memory_id,
+            ])
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Copies a session memory into the character store. Returns `None` when the
+/// store already holds the same memory or a near-duplicate of it.
+pub fn promote_session_memory(
+    conn: &Connection,
+    character_id: &str,
+    session_id: &str,
+    memory: &MemoryEmbedding,
+    promoted_by: &str,
+) -> Result<Option<CharacterMemory>, String> {
+    let existing = load_for_character(conn, character_id)?;
+    if existing.iter().any(|entry| {
+        entry.source_session_id.as_deref() == Some(session_id)
+            && entry.source_memory_id.as_deref() == Some(memory.id.as_str())
+    }) {
+        return Ok(None);
+    }
+    let existing = existing
+        .into_iter()
+        .map(|entry| entry.memory)
+        .collect::<Vec<_>>();
+    let embedding = (!memory.embedding.is_empty()).then_some(memory.embedding.as_slice());
+    if find_duplicate_memory_reason(&memory.text, embedding, &existing).is_some() {
+        return Ok(None);
+    }
+
+    let now = now_ms();
+    let entry = CharacterMemory {
+        character_id: character_id.to_string(),
+        memory: MemoryEmbedding {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: now,
+            last_accessed_at: now,
+            is_cold: false,
+            importance_score: 1.0,
+            access_count: 0,
+            match_score: None,
+            ..memory.clone()
+        },
+        source_session_id: Some(session_id.to_string()),
+        source_memory_id: Some(memory.id.clone()),
+        promoted_by: promoted_by.to_string(),
+    };
+    insert(conn, &entry)?;
+    Ok(Some(entry))
+}
+
+/// One backup item per character: its settings row and stored memories.
+pub(crate) fn export_for_backup(conn: &Connection) -> Result<Vec<serde_json::Value>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT character_id FROM character_memory_settings \
+             UNION SELECT character_id FROM character_memories",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let character_ids = stmt
+        .query_map([], |r| r.get::<_, String>(0))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut out = Vec::with_capacity(character_ids.len());
+    for character_id in character_ids {
+        out.push(serde_json::json!({
+            "id": character_id,
+            "config": load_config(conn, &character_id)?,
+            "memories": load_for_character(conn, &character_id)?,
+        }));
+    }
+    Ok(out)
+}
+
+/// Replaces the store with the backup items. Items whose character is not
+/// present are skipped.
+pub(crate) fn import_from_backup(
+    conn: &Connection,
+    data: &serde_json::Value,
+) -> Result<(), String> {
+    conn.execute_batch("DELETE FROM character_memories; DELETE FROM character_memory_settings;")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let Some(items) = data.as_array() else {
+        return Ok(());
+    };
+    for item in items {
+        let Some(character_id) = item.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM characters WHERE id = ?1)",
+                params![character_id],
+                |r| r.get(0),
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        if !exists {
+            continue;
+        }
+        if let Some(config) = item
+            .get("config")
+            .and_then(|v| serde_json::from_value::<CharacterMemoryConfig>(v.clone()).ok())
+        {
+            save_config(conn, character_id, &config)?;
+        }
+        let memories = item
+            .get("memories")
+            .and_then(|v| serde_json::from_value::<Vec<CharacterMemory>>(v.clone()).ok())
+            .unwrap_or_default();
+        for entry in memories {
+            insert(
+                conn,
+                &CharacterMemory {
+                    character_id: character_id.to_string(),
+                    ..entry
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn session_character_id(conn: &Connection, session_id: &str) -> Result<String, String> {
+    conn.query_row(
+        "SELECT character_id FROM sessions WHERE id = ?1",
+        params![session_id],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+    .ok_or_else(|| "Session not found".to_string())
+}
+
+fn find_memory(
+    conn: &Connection,
+    character_id: &str,
+    memory_id: &str,
+) -> Result<CharacterMemory, String> {
+    load_for_character(conn, character_id)?
+        .into_iter()
+        .find(|entry| entry.memory.id == memory_id)
+        .ok_or_else(|| "Character memory not found".to_string())
+}
+
+#[tauri::command]
+pub fn character_memory_get_config(
+    app: AppHandle,
+    character_id: String,
+) -> Result<CharacterMemoryConfig, String> {
+    let conn = open_db(&app)?;
+    load_config(&conn, &character_id)
+}
+
+#[tauri::command]
+pub fn character_memory_set_config(
+    app: AppHandle,
+    character_id: String,
+    config: CharacterMemoryConfig,
+) -> Result<CharacterMemoryConfig, String> {
+    let conn = open_db(&app)?;
+    save_config(&conn, &character_id, &config)?;
+    load_config(&conn, &character_id)
+}
+
+#[tauri::command]
+pub fn character_memory_list(
+    app: AppHandle,
+    character_id: String,
+) -> Result<Vec<CharacterMemory>, String> {
+    let conn = open_db(&app)?;
+    load_for_character(&conn, &character_id)
+}
+
+/// Manually promote one session memory into its character's store.
+#[tauri::command]
+pub fn character_memory_promote(
+    app: AppHandle,
+    session_id: String,
+    memory_id: String,
+) -> Result<Option<CharacterMemory>, String> {
+    let conn = open_db(&app)?;
+    let character_id = session_character_id(&conn, &session_id)?;
+    let memory =
+        super::memory_embeddings::load_for_session(&conn, &session_id, SessionKind::Session)?
+            .into_iter()
+            .find(|memory| memory.id == memory_id)
+            .ok_or_else(|| "Memory not found".to_string())?;
+
+    let promoted = promote_session_memory(&conn, &character_id, &session_id, &memory, "manual")?;
+    if promoted.is_none() {
+        log_info(
+            &app,
+            "character_memory",
+            format!(
+                "memory {} already present for character {}",
+                memory_id, character_id
+            ),
+        );
+    }
+    Ok(promoted)
+}
+
+#[tauri::command]
+pub async fn character_memory_add(
+    app: AppHandle,
+    character_id: String,
+    text: String,
+    category: Option<String>,
+) -> Result<CharacterMemory, String> {
+    let category = super::sessions::normalize_memory_category(category)?;
+    let (embedding, embedding_source_version) = embed_memory_text(&app, &text).await;
+    let now = now_ms();
+    let entry = CharacterMemory {
+        character_id,
+        memory: MemoryEmbedding {
+            id: uuid::Uuid::new_v4().to_string(),
+            token_count: crate::embedding::tokenizer::count_tokens(&app, &text).unwrap_or(0),
+            text,
+            embedding_dimensions: (!embedding.is_empty()).then_some(embedding.len()),
+            embedding,
+            created_at: now,
+            is_cold: false,
+            last_accessed_at: now,
+            importance_score: 1.0,
+            persistence_importance: 1.0,
+            prompt_importance: 1.0,
+            volatility: 0.0,
+            is_pinned: false,
+            access_count: 0,
+            embedding_source_version,
+            match_score: None,
+            category,
+            canonical_entities: Vec::new(),
+            fact_signature: None,
+            fact_polarity: None,
+            source_role: None,
+            source_message_id: None,
+            superseded_by: None,
+            superseded_at: None,
+            supersedes: Vec::new(),
+        },
+        source_session_id: None,
+        source_memory_id: None,
+        promoted_by: "manual".to_string(),
+    };
+    let conn = open_db(&app)?;
+    insert(&conn, &entry)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn character_memory_update(
+    app: AppHandle,
+    character_id: String,
+    memory_id: String,
+    text: String,
+) -> Result<CharacterMemory, String> {
+    let (embedding, embedding_source_version) = embed_memory_text(&app, &text).await;
+    let token_count = crate::embedding::tokenizer::count_tokens(&app, &text).unwrap_or(0);
+    let conn = open_db(&app)?;
+    conn.execute(
+        "UPDATE character_memories \
+         SET text = ?1, embedding = ?2, embedding_dim = ?3, embedding_model = ?4, token_count = ?5, updated_at = ?6 \
+         WHERE character_id = ?7 AND memory_id = ?8",
+        params![
+            &text,
+            embedding_to_blob(&embedding),
+            embedding.len() as i64,
+            embedding_source_version,
+            token_count as i64,
+            now_ms() as i64,
+            &character_id,
+            &memory_id,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    find_memory(&conn, &character_id, &memory_id)
+}
+
+#[tauri::command]
+pub fn character_memory_set_pinned(
+    app: AppHandle,
+    character_id: String,
+    memory_id: String,
+    pinned: bool,
+) -> Result<CharacterMemory, String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "UPDATE character_memories SET is_pinned = ?1, updated_at = ?2 WHERE character_id = ?3 AND memory_id = ?4",
+        params![pinned as i64, now_ms() as i64, &character_id, &memory_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    find_memory(&conn, &character_id, &memory_id)
+}
+
+#[tauri::command]
+pub fn character_memory_delete(
+    app: AppHandle,
+    character_id: String,
+    memory_id: String,
+) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "DELETE FROM character_memories WHERE character_id = ?1 AND memory_id = ?2",
+        params![&character_id, &memory_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+async fn embed_memory_text(app: &AppHandle, text: &str) -> (Vec<f32>, Option<String>) {
+    let embedding = match embedding::compute_embedding(app.clone(), text.to_string()).await {
+        Ok(vec) => vec,
+        Err(err) => {
+            log_warn(
+                app,
+                "character_memory",
+                format!("embedding failed: {}", err),
+            );
+            Vec::new()
+        }
+    };
+    let version = embedding::resolve_active_embedding_signature(app)
+        .ok()
+        .map(|(version, _)| version);
+    (embedding, version)
+}
@@ -0,0 +1,271 @@
+//! Storage for proactive companion messages: the global schedule limits and
+//! the messages the scheduler (`chat_manager::companion::proactive`) has sent
+//! that the app has not surfaced to the user yet.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::db::{now_ms, open_db};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProactiveMessageConfig {
+    pub enabled: bool,
+    /// Local minute of day quiet hours start at; equal start and end means none.
+    pub quiet_start_minute: u32,
+    pub quiet_end_minute: u32,
+    /// Per companion, over any 24 hours.
+    pub max_per_day: u32,
+    /// Minimum time between two proactive messages in one session.
+    pub min_gap_minutes: u32,
+    /// How long the user must have been away before a companion may reach out.
+    pub min_idle_minutes: u32,
+}
+
+impl Default for ProactiveMessageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quiet_start_minute: 22 * 60,
+            quiet_end_minute: 8 * 60,
+            max_per_day: 2,
+            min_gap_minutes: 240,
+            min_idle_minutes: 180,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProactiveMessage {
+    pub id: String,
+    pub session_id: String,
+    pub character_id: String,
+    pub message_id: String,
+    /// "pending", "seen" or "dismissed".
+    pub status: String,
+    pub urgency: f64,
+    pub created_at: u64,
+    pub resolved_at: Option<u64>,
+}
+
+/// Latest companion session of one character, with its proactive history.
+#[derive(Debug, Clone)]
+pub struct ProactiveCandidateRow {
+    pub session_id: String,
+    pub character_id: String,
+    pub companion_state: Option<String>,
+    pub last_proactive_at: Option<u64>,
+    pub sent_last_day: u32,
+    pub has_unseen: bool,
+}
+
+pub fn load_config(conn: &Connection) -> Result<ProactiveMessageConfig, String> {
+    let config = conn
+        .query_row(
+            "SELECT enabled, quiet_start_minute, quiet_end_minute, max_per_day, min_gap_minutes, min_idle_minutes
+             FROM companion_proactive_settings WHERE id = 1",
+            [],
+            |r| {
+                Ok(ProactiveMessageConfig {
+                    enabled: r.get::<_, i64>(0)? != 0,
+                    quiet_start_minute: r.get::<_, i64>(1)?.clamp(0, 1439) as u32,
+                    quiet_end_minute: r.get::<_, i64>(2)?.clamp(0, 1439) as u32,
+                    max_per_day: r.get::<_, i64>(3)?.max(0) as u32,
+                    min_gap_minutes: r.get::<_, i64>(4)?.max(0) as u32,
+                    min_idle_minutes: r.get::<_, i64>(5)?.max(1) as u32,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(config.unwrap_or_default())
+}
+
+fn save_config(conn: &Connection, config: &ProactiveMessageConfig) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO companion_proactive_settings (id, enabled, quiet_start_minute, quiet_end_minute, max_per_day, min_gap_minutes, min_idle_minutes, updated_at)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET
+           enabled = excluded.enabled,
+           quiet_start_minute = excluded.quiet_start_minute,
+           quiet_end_minute = excluded.quiet_end_minute,
+           max_per_day = excluded.max_per_day,
+           min_gap_minutes = excluded.min_gap_minutes,
+           min_idle_minutes = excluded.min_idle_minutes,
+           updated_at = excluded.updated_at",
+        params![
+            config.enabled as i64,
+            config.quiet_start_minute.min(1439) as i64,
+            config.quiet_end_minute.min(1439) as i64,
+            config.max_per_day as i64,
+            config.min_gap_minutes as i64,
+            config.min_idle_minutes.max(1) as i64,
+            now_ms() as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// The most recently updated unarchived session of every companion
+/// character, so a companion only ever reaches out from one chat.
+pub fn load_candidates(
+    conn: &Connection,
+    day_start: u64,
+) -> Result<Vec<ProactiveCandidateRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.character_id, s.companion_state,
+                    (SELECT MAX(p.created_at) FROM companion_proactive_messages p WHERE p.session_id = s.id),
+                    (SELECT COUNT(*) FROM companion_proactive_messages p WHERE p.session_id = s.id AND p.created_at >= ?1),
+                    EXISTS(SELECT 1 FROM companion_proactive_messages p WHERE p.session_id = s.id AND p.status = 'pending')
+             FROM sessions s
+             JOIN characters c ON c.id = s.character_id
+             WHERE s.archived = 0
+               AND s.companion_state IS NOT NULL
+               AND (LOWER(s.mode) = 'companion' OR LOWER(c.mode) = 'companion')
+               AND s.updated_at = (
+                   SELECT MAX(s2.updated_at) FROM sessions s2
+                   WHERE s2.character_id = s.character_id AND s2.archived = 0
+               )",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![day_start as i64], |r| {
+            Ok(ProactiveCandidateRow {
+                session_id: r.get(0)?,
+                character_id: r.get(1)?,
+                companion_state: r.get(2)?,
+                last_proactive_at: r.get::<_, Option<i64>>(3)?.map(|v| v.max(0) as u64),
+                sent_last_day: r.get::<_, i64>(4)?.max(0) as u32,
+                has_unseen: r.get::<_, i64>(5)? != 0,
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(rows)
+}
+
+pub fn insert_pending(
+    conn: &Connection,
+    session_id: &str,
+    character_id: &str,
+    message_id: &str,
+    urgency: f64,
+) -> Result<ProactiveMessage, String> {
+    let message = ProactiveMessage {
+        id: Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        character_id: character_id.to_string(),
+        message_id: message_id.to_string(),
+        status: "pending".to_string(),
+        urgency,
+        created_at: now_ms(),
+        resolved_at: None,
+    };
+    conn.execute(
+        "INSERT INTO companion_proactive_messages (id, session_id, character_id, message_id, status, urgency, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            message.id,
+            message.session_id,
+            message.character_id,
+            message.message_id,
+            message.status,
+            message.urgency,
+            message.created_at as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(message)
+}
+
+/// Marks a session's pending messages as seen, e.g. once the user replies.
+pub fn mark_session_seen(conn: &Connection, session_id: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE companion_proactive_messages SET status = 'seen', resolved_at = ?1
+         WHERE session_id = ?2 AND status = 'pending'",
+        params![now_ms() as i64, session_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn list_pending(
+    conn: &Connection,
+    session_id: Option<&str>,
+) -> Result<Vec<ProactiveMessage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, session_id, character_id, message_id, status, urgency, created_at, resolved_at
+             FROM companion_proactive_messages
+             WHERE status = 'pending' AND (?1 IS NULL OR session_id = ?1)
+             ORDER BY created_at DESC",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let messages = stmt
+        .query_map(params![session_id], |r| {
+            Ok(ProactiveMessage {
+                id: r.get(0)?,
+                session_id: r.get(1)?,
+                character_id: r.get(2)?,
+                message_id: r.get(3)?,
+                status: r.get(4)?,
+                urgency: r.get(5)?,
+                created_at: r.get::<_, i64>(6)?.max(0) as u64,
+                resolved_at: r.get::<_, Option<i64>>(7)?.map(|v| v.max(0) as u64),
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(messages)
+}
+
+#[tauri::command]
+pub fn companion_proactive_get_config(app: AppHandle) -> Result<ProactiveMessageConfig, String> {
+    let conn = open_db(&app)?;
+    load_config(&conn)
+}
+
+#[tauri::command]
+pub fn companion_proactive_set_config(
+    app: AppHandle,
+    config: ProactiveMessageConfig,
+) -> Result<ProactiveMessageConfig, String> {
+    let conn = open_db(&app)?;
+    save_config(&conn, &config)?;
+    load_config(&conn)
+}
+
+#[tauri::command]
+pub fn companion_proactive_list_pending(
+    app: AppHandle,
+    session_id: Option<String>,
+) -> Result<Vec<ProactiveMessage>, String> {
+    let conn = open_db(&app)?;
+    list_pending(&conn, session_id.as_deref())
+}
+
+/// Marks one message "seen" (opened) or "dismissed" (notification cleared).
+#[tauri::command]
+pub fn companion_proactive_resolve(
+    app: AppHandle,
+    id: String,
+    status: String,
+) -> Result<(), String> {
+    if status != "seen" && status != "dismissed" {
+        return Err(format!("Invalid proactive message status: {}", status));
+    }
+    let conn = open_db(&app)?;
+    conn.execute(
+        "UPDATE companion_proactive_messages SET status = ?1, resolved_at = ?2 WHERE id = ?3",
+        params![status, now_ms() as i64, id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
@@ -0,0 +1,646 @@
+//! Character-level companion relationship ledger. Each companion session keeps
+//! its own `RelationshipState`, but new sessions start from this ledger and
+//! every session merges its deltas back (`chat_manager::companion`), so the
+//! bond carries across chats. Changes are also appended to a history timeline.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::db::{now_ms, open_db};
+use crate::chat_manager::companion::relationship_defaults;
+use crate::chat_manager::service::ChatContext;
+
+/// Consecutive session merges within this window update one history point
+/// instead of adding a new one, so the timeline stays chart-sized.
+const HISTORY_COALESCE_MS: u64 = 30 * 60 * 1000;
+pub const DEFAULT_HISTORY_LIMIT: u32 = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipValues {
+    pub closeness: f64,
+    pub trust: f64,
+    pub affection: f64,
+    pub tension: f64,
+    pub stability: f64,
+}
+
+impl RelationshipValues {
+    fn add(&self, delta: &Self) -> Self {
+        Self {
+            closeness: (self.closeness + delta.closeness).clamp(0.0, 1.0),
+            trust: (self.trust + delta.trust).clamp(0.0, 1.0),
+            affection: (self.affection + delta.affection).clamp(0.0, 1.0),
+            tension: (self.tension + delta.tension).clamp(0.0, 1.0),
+            stability: (self.stability + delta.stability).clamp(0.0, 1.0),
+        }
+    }
+
+    fn clamped(&self) -> Self {
+        Self::default().add(self)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        [
+            self.closeness,
+            self.trust,
+            self.affection,
+            self.tension,
+            self.stability,
+        ]
+        .iter()
+        .all(|value| value.abs() < 1e-9)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipLedger {
+    pub character_id: String,
+    #[serde(flatten)]
+    pub values: RelationshipValues,
+    pub interaction_count: u32,
+    pub last_interaction_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipHistoryPoint {
+    pub id: String,
+    pub character_id: String,
+    pub session_id: Option<String>,
+    /// "session", "adjust" or "reset".
+    pub source: String,
+    #[serde(flatten)]
+    pub values: RelationshipValues,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Manual edit from the relationship page; unset fields keep their value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipAdjustment {
+    pub closeness: Option<f64>,
+    pub trust: Option<f64>,
+    pub affection: Option<f64>,
+    pub tension: Option<f64>,
+    pub stability: Option<f64>,
+}
+
+pub fn load_ledger(
+    conn: &Connection,
+    character_id: &str,
+) -> Result<Option<RelationshipLedger>, String> {
+    conn.query_row(
+        "SELECT character_id, closeness, trust, affection, tension, stability, interaction_count, last_interaction_at, updated_at
+         FROM companion_relationships WHERE character_id = ?1",
+        params![character_id],
+        ledger_from_row,
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+/// Relationship changes one session made since its last merge into the ledger.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionDelta {
+    /// Values a ledger created by this merge starts from.
+    pub initial: RelationshipValues,
+    pub values: RelationshipValues,
+    pub interactions: u32,
+    pub last_interaction_at: u64,
+}
+
+/// The ledger after adding `delta` to `current`, or to a fresh ledger if there is none.
+pub fn fold_session_delta(
+    current: Option<RelationshipLedger>,
+    character_id: &str,
+    delta: &SessionDelta,
+    now: u64,
+) -> RelationshipLedger {
+    let mut ledger = current.unwrap_or_else(|| RelationshipLedger {
+        character_id: character_id.to_string(),
+        values: delta.initial.clamped(),
+        interaction_count: 0,
+        last_interaction_at: 0,
+        updated_at: now,
+    });
+    ledger.values = ledger.values.add(&delta.values);
+    ledger.interaction_count = ledger.interaction_count.saturating_add(delta.interactions);
+    ledger.last_interaction_at = ledger.last_interaction_at.max(delta.last_interaction_at);
+    ledger.updated_at = now;
+    ledger
+}
+
+/// Adds one session's changes since its last merge to the ledger, creating it
+/// from `delta.initial` when the character has none yet.
+pub fn merge_session_delta(
+    conn: &mut Connection,
+    character_id: &str,
+    session_id: &str,
+    delta: &SessionDelta,
+) -> Result<RelationshipLedger, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let now = now_ms();
+    let current = load_ledger(&tx, character_id)?;
+    let created = current.is_none();
+    let ledger = fold_session_delta(current, character_id, delta, now);
+
+    if created || !delta.values.is_zero() || delta.interactions > 0 {
+        save_ledger(&tx, &ledger)?;
+    }
+    if !delta.values.is_zero() {
+        record_history(&tx, &ledger, Some(session_id), "session", now)?;
+    }
+    tx.commit()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(ledger)
+}
+
+/// Overwrites the ledger values, keeping its interaction count.
+pub fn set_values(
+    conn: &mut Connection,
+    character_id: &str,
+    values: RelationshipValues,
+    source: &str,
+) -> Result<RelationshipLedger, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let now = now_ms();
+    let mut ledger = load_ledger(&tx, character_id)?.unwrap_or_else(|| RelationshipLedger {
+        character_id: character_id.to_string(),
+        values,
+        interaction_count: 0,
+        last_interaction_at: 0,
+        updated_at: now,
+    });
+    ledger.values = values.clamped();
+    ledger.updated_at = now;
+    save_ledger(&tx, &ledger)?;
+    record_history(&tx, &ledger, None, source, now)?;
+    tx.commit()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(ledger)
+}
+
+/// Oldest first, limited to the most recent `limit` points.
+pub fn load_history(
+    conn: &Connection,
+    character_id: &str,
+    limit: u32,
+) -> Result<Vec<RelationshipHistoryPoint>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at
+             FROM companion_relationship_history WHERE character_id = ?1
+             ORDER BY created_at DESC LIMIT ?2",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut points = stmt
+        .query_map(params![character_id, limit.max(1) as i64], history_from_row)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    points.reverse();
+    Ok(points)
+}
+
+fn save_ledger(conn: &Connection, ledger: &RelationshipLedger) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO companion_relationships (character_id, closeness, trust, affection, tension, stability, interaction_count, last_interaction_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(character_id) DO UPDATE SET
+           closeness = excluded.closeness,
+           trust = excluded.trust,
+           affection = excluded.affection,
+           tension = excluded.tension,
+           stability = excluded.stability,
+           interaction_count = excluded.interaction_count,
+           last_interaction_at = excluded.last_interaction_at,
+           updated_at = excluded.updated_at",
+        params![
+            ledger.character_id,
+            ledger.values.closeness,
+            ledger.values.trust,
+            ledger.values.affection,
+            ledger.values.tension,
+            ledger.values.stability,
+            ledger.interaction_count as i64,
+            ledger.last_interaction_at as i64,
+            ledger.updated_at as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn record_history(
+    conn: &Connection,
+    ledger: &RelationshipLedger,
+    session_id: Option<&str>,
+    source: &str,
+    now: u64,
+) -> Result<(), String> {
+    let latest = conn
+        .query_row(
+            "SELECT id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at
+             FROM companion_relationship_history WHERE character_id = ?1
+             ORDER BY created_at DESC LIMIT 1",
+            params![ledger.character_id],
+            history_from_row,
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let coalesce = latest.filter(|point| {
+        source == "session"
+            && point.source == source
+            && point.session_id.as_deref() == session_id
+            && now.saturating_sub(point.created_at) < HISTORY_COALESCE_MS
+    });
+
+    let values = &ledger.values;
+    match coalesce {
+        Some(point) => conn.execute(
+            "UPDATE companion_relationship_history
+             SET closeness = ?1, trust = ?2, affection = ?3, tension = ?4, stability = ?5, updated_at = ?6
+             WHERE id = ?7",
+            params![
+                values.closeness,
+                values.trust,
+                values.affection,
+                values.tension,
+                values.stability,
+                now as i64,
+                point.id,
+            ],
+        ),
+        None => conn.execute(
+            "INSERT INTO companion_relationship_history (id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+            params![
+                Uuid::new_v4().to_string(),
+                ledger.character_id,
+                session_id,
+                source,
+                values.closeness,
+                values.trust,
+                values.affection,
+                values.tension,
+                values.stability,
+                now as i64,
+            ],
+        ),
+    }
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+fn ledger_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RelationshipLedger> {
+    Ok(RelationshipLedger {
+        character_id: row.get(0)?,
+        values: RelationshipValues {
+            closeness: row.get(1)?,
+            trust: row.get(2)?,
+            affection: row.get(3)?,
+            tension: row.get(4)?,
+            stability: row.get(5)?,
+        },
+        interaction_count: row.get::<_, i64>(6)?.max(0) as u32,
+        last_interaction_at: row.get::<_, i64>(7)?.max(0) as u64,
+        updated_at: row.get::<_, i64>(8)?.max(0) as u64,
+    })
+}
+
+fn history_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RelationshipHistoryPoint> {
+    Ok(RelationshipHistoryPoint {
+        id: row.get(0)?,
+        character_id: row.get(1)?,
+        session_id: row.get(2)?,
+        source: row.get(3)?,
+        values: RelationshipValues {
+            closeness: row.get(4)?,
+            trust: row.get(5)?,
+            affection: row.get(6)?,
+            tension: row.get(7)?,
+            stability: row.get(8)?,
+        },
+        created_at: row.get::<_, i64>(9)?.max(0) as u64,
+        updated_at: row.get::<_, i64>(10)?.max(0) as u64,
+    })
+}
+
+/// One backup item per character: its ledger and full history.
+pub(crate) fn export_for_backup(conn: &Connection) -> Result<Vec<serde_json::Value>, String> {
+    let mut stmt = conn
+        .prepare("SELECT character_id FROM companion_relationships")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let character_ids = stmt
+        .query_map([], |r| r.get::<_, String>(0))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut out = Vec::with_capacity(character_ids.len());
+    for character_id in character_ids {
+        out.push(serde_json::json!({
+            "id": character_id,
+            "ledger": load_ledger(conn, &character_id)?,
+            "history": load_history(conn, &character_id, u32::MAX)?,
+        }));
+    }
+    Ok(out)
+}
+
+/// Replaces the ledgers with the backup items. Items whose character is not
+/// present are skipped.
+pub(crate) fn import_from_backup(
+    conn: &Connection,
+    data: &serde_json::Value,
+) -> Result<(), String> {
+    conn.execute_batch(
+        "DELETE FROM companion_relationship_history; DELETE FROM companion_relationships;",
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let Some(items) = data.as_array() else {
+        return Ok(());
+    };
+    for item in items {
+        let Some(character_id) = item.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM characters WHERE id = ?1)",
+                params![character_id],
+                |r| r.get(0),
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        if !exists {
+            continue;
+        }
+        let Some(ledger) = item
+            .get("ledger")
+            .and_then(|v| serde_json::from_value::<RelationshipLedger>(v.clone()).ok())
+        else {
+            continue;
+        };
+        save_ledger(
+            conn,
+            &RelationshipLedger {
+                character_id: character_id.to_string(),
+                ..ledger
+            },
+        )?;
+        let history = item
+            .get("history")
+            .and_then(|v| serde_json::from_value::<Vec<RelationshipHistoryPoint>>(v.clone()).ok())
+            .unwrap_or_default();
+        for point in history {
+            conn.execute(
+                "INSERT OR REPLACE INTO companion_relationship_history (id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    point.id,
+                    character_id,
+                    point.session_id,
+                    point.source,
+                    point.values.closeness,
+                    point.values.trust,
+                    point.values.affection,
+                    point.values.tension,
+                    point.values.stability,
+                    point.created_at as i64,
+                    point.updated_at as i64,
+                ],
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn companion_relationship_get(
+    app: AppHandle,
+    character_id: String,
+) -> Result<Option<RelationshipLedger>, String> {
+    let conn = open_db(&app)?;
+    load_ledger(&conn, &character_id)
+}
+
+#[tauri::command]
+pub fn companion_relationship_history(
+    app: AppHandle,
+    character_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<RelationshipHistoryPoint>, String> {
+    let conn = open_db(&app)?;
+    load_history(&conn, &character_id, limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+}
+
+#[tauri::command]
+pub fn companion_relationship_adjust(
+    app: AppHandle,
+    character_id: String,
+    adjustment: RelationshipAdjustment,
+) -> Result<RelationshipLedger, String> {
+    let mut conn = open_db(&app)?;
+    let current = match load_ledger(&conn, &character_id)? {
+        Some(ledger) => ledger.values,
+        None => character_defaults(&app, &character_id)?,
+    };
+    let values = RelationshipValues {
+        closeness: adjustment.closeness.unwrap_or(current.closeness),
+        trust: adjustment.trust.unwrap_or(current.trust),
+        affection: adjustment.affection.unwrap_or(current.affection),
+        tension: adjustment.tension.unwrap_or(current.tension),
+        stability: adjustment.stability.unwrap_or(current.stability),
+    };
+    set_values(&mut conn, &character_id, values, "adjust")
+}
+
+/// Puts the ledger back to the character's authored relationship defaults.
+/// Open sessions pick the new values up on their next turn.
+#[tauri::command]
+pub fn companion_relationship_reset(
+    app: AppHandle,
+    character_id: String,
+) -> Result<RelationshipLedger, String> {
+    let values = character_defaults(&app, &character_id)?;
+    let mut conn = open_db(&app)?;
+    set_values(&mut conn, &character_id, values, "reset")
+}
+
+fn character_defaults(app: &AppHandle, character_id: &str) -> Result<RelationshipValues, String> {
+    let context = ChatContext::initialize(app.clone())?;
+    let character = context.find_character(character_id)?;
+    Ok(relationship_defaults(&character))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE characters (id TEXT PRIMARY KEY);
+             CREATE TABLE companion_relationships (
+               character_id TEXT PRIMARY KEY,
+               closeness REAL NOT NULL,
+               trust REAL NOT NULL,
+               affection REAL NOT NULL,
+               tension REAL NOT NULL,
+               stability REAL NOT NULL,
+               interaction_count INTEGER NOT NULL DEFAULT 0,
+               last_interaction_at INTEGER NOT NULL DEFAULT 0,
+               updated_at INTEGER NOT NULL
+             );
+             CREATE TABLE companion_relationship_history (
+               id TEXT PRIMARY KEY,
+               character_id TEXT NOT NULL,
+               session_id TEXT,
+               source TEXT NOT NULL,
+               closeness REAL NOT NULL,
+               trust REAL NOT NULL,
+               affection REAL NOT NULL,
+               tension REAL NOT NULL,
+               stability REAL NOT NULL,
+               created_at INTEGER NOT NULL,
+               updated_at INTEGER NOT NULL
+             );
+             INSERT INTO characters (id) VALUES ('c1');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn values(value: f64) -> RelationshipValues {
+        RelationshipValues {
+            closeness: value,
+            trust: value,
+            affection: value,
+            tension: value,
+            stability: value,
+        }
+    }
+
+    fn delta(change: RelationshipValues, interactions: u32) -> SessionDelta {
+        SessionDelta {
+            initial: values(0.5),
+            values: change,
+            interactions,
+            last_interaction_at: 100,
+        }
+    }
+
+    #[test]
+    fn fold_creates_the_ledger_from_initial_values() {
+        let ledger = fold_session_delta(None, "c1", &delta(values(0.1), 2), 7);
+
+        assert_eq!(ledger.character_id, "c1");
+        assert!((ledger.values.trust - 0.6).abs() < 1e-9);
+        assert_eq!(ledger.interaction_count, 2);
+        assert_eq!(ledger.last_interaction_at, 100);
+        assert_eq!(ledger.updated_at, 7);
+    }
+
+    #[test]
+    fn fold_clamps_values() {
+        let current = fold_session_delta(None, "c1", &delta(values(0.0), 0), 1);
+        let ledger = fold_session_delta(
+            Some(current),
+            "c1",
+            &delta(
+                RelationshipValues {
+                    closeness: 0.8,
+                    tension: -0.9,
+                    ..values(0.0)
+                },
+                1,
+            ),
+            2,
+        );
+
+        assert_eq!(ledger.values.closeness, 1.0);
+        assert_eq!(ledger.values.tension, 0.0);
+        assert!((ledger.values.trust - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merge_records_history_only_for_value_changes() {
+        let mut conn = test_conn();
+
+        merge_session_delta(&mut conn, "c1", "s1", &delta(values(0.0), 1)).unwrap();
+        assert!(load_ledger(&conn, "c1").unwrap().is_some());
+        assert!(load_history(&conn, "c1", 10).unwrap().is_empty());
+
+        merge_session_delta(&mut conn, "c1", "s1", &delta(values(0.1), 1)).unwrap();
+        merge_session_delta(&mut conn, "c1", "s1", &delta(values(0.1), 1)).unwrap();
+        let history = load_history(&conn, "c1", 10).unwrap();
+        assert_eq!(history.len(), 1, "merges of one session coalesce");
+        assert!((history[0].values.trust - 0.7).abs() < 1e-9);
+
+        merge_session_delta(&mut conn, "c1", "s2", &delta(values(-0.1), 0)).unwrap();
+        let history = load_history(&conn, "c1", 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history
+            .iter()
+            .any(|point| point.session_id.as_deref() == Some("s2")));
+
+        let ledger = load_ledger(&conn, "c1").unwrap().unwrap();
+        assert_eq!(ledger.interaction_count, 3);
+        assert!((ledger.values.trust - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn set_values_keeps_interactions_and_records_history() {
+        let mut conn = test_conn();
+        merge_session_delta(&mut conn, "c1", "s1", &delta(values(0.0), 4)).unwrap();
+
+        let ledger = set_values(&mut conn, "c1", values(1.5), "adjust").unwrap();
+
+        assert_eq!(ledger.values, values(1.0));
+        assert_eq!(ledger.interaction_count, 4);
+        let history = load_history(&conn, "c1", 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].source, "adjust");
+        assert_eq!(history[0].session_id, None);
+    }
+
+    #[test]
+    fn backup_round_trip_skips_missing_characters() {
+        let mut conn = test_conn();
+        merge_session_delta(&mut conn, "c1", "s1", &delta(values(0.1), 2)).unwrap();
+        set_values(&mut conn, "c1", values(0.3), "adjust").unwrap();
+        let mut exported = export_for_backup(&conn).unwrap();
+        exported.push(serde_json::json!({
+            "id": "gone",
+            "ledger": fold_session_delta(None, "gone", &delta(values(0.0), 0), 1),
+            "history": [],
+        }));
+
+        let restored = test_conn();
+        import_from_backup(&restored, &serde_json::Value::Array(exported)).unwrap();
+
+        let ledger = load_ledger(&restored, "c1").unwrap().unwrap();
+        assert_eq!(ledger.values, values(0.3));
+        assert_eq!(ledger.interaction_count, 2);
+        let history = load_history(&restored, "c1", 10).unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|p| p.source.as_str())
+                .collect::<Vec<_>>(),
+            vec!["session", "adjust"]
+        );
+        assert!(load_ledger(&restored, "gone").unwrap().is_none());
+    }
+}
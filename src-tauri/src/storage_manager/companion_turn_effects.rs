@@ -129,6 +129,53 @@ pub fn mark_effect_ready(
     Ok(())
 }
 
+/// Writes deltas computed after the reply was saved. A row still waiting on
+/// the post-turn memory job keeps its status and summary; that job finalizes it.
+pub fn record_ready_effect(
+    app: &AppHandle,
+    session_id: &str,
+    user_message_id: Option<&str>,
+    assistant_message_id: &str,
+    seed: CompanionTurnEffectSeed,
+    summary: Option<String>,
+) -> Result<(), String> {
+    let conn = open_db(app)?;
+    let now = now_millis()?;
+    conn.execute(
+        r#"
+        INSERT INTO companion_turn_effects (
+            id, session_id, user_message_id, assistant_message_id, created_at, updated_at,
+            status, summary, relationship_delta, emotion_delta, signal_changes,
+            memory_changes, source_window
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?5, 'ready', ?6, ?7, ?8, ?9, ?10, ?11)
+        ON CONFLICT(session_id, assistant_message_id) DO UPDATE SET
+            user_message_id = COALESCE(excluded.user_message_id, user_message_id),
+            updated_at = excluded.updated_at,
+            summary = CASE WHEN status = 'processing' THEN summary ELSE excluded.summary END,
+            status = CASE WHEN status = 'processing' THEN status ELSE 'ready' END,
+            relationship_delta = excluded.relationship_delta,
+            emotion_delta = excluded.emotion_delta,
+            signal_changes = excluded.signal_changes
+        "#,
+        params![
+            Uuid::new_v4().to_string(),
+            session_id,
+            user_message_id,
+            assistant_message_id,
+            now as i64,
+            summary,
+            seed.relationship_delta.to_string(),
+            seed.emotion_delta.to_string(),
+            seed.signal_changes.to_string(),
+            json!({ "added": [], "updated": [], "superseded": [] }).to_string(),
+            json!({}).to_string(),
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
 pub fn mark_effect_failed(
     app: &AppHandle,
     session_id: &str,
@@ -0,0 +1,134 @@
+//! Rolling summaries of history that no longer fits a model's context, and
+//! the trim report kept for assistant messages whose request was trimmed.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::db::{now_ms, open_db};
+use crate::chat_manager::types::ContextTrimReport;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingSummary {
+    pub session_id: String,
+    pub summary: String,
+    /// `created_at` of the newest message folded into the summary.
+    pub covered_until: u64,
+    pub token_count: u32,
+    pub updated_at: u64,
+}
+
+pub fn load_rolling_summary(
+    conn: &Connection,
+    session_id: &str,
+) -> Result<Option<RollingSummary>, String> {
+    conn.query_row(
+        "SELECT session_id, summary, covered_until, token_count, updated_at
+         FROM session_context_summaries WHERE session_id = ?1",
+        params![session_id],
+        |r| {
+            Ok(RollingSummary {
+                session_id: r.get(0)?,
+                summary: r.get(1)?,
+                covered_until: r.get::<_, i64>(2)?.max(0) as u64,
+                token_count: r.get::<_, i64>(3)?.max(0) as u32,
+                updated_at: r.get::<_, i64>(4)?.max(0) as u64,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+pub fn save_rolling_summary(
+    conn: &Connection,
+    session_id: &str,
+    summary: &str,
+    covered_until: u64,
+    token_count: u32,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO session_context_summaries (session_id, summary, covered_until, token_count, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(session_id) DO UPDATE SET
+           summary = excluded.summary,
+           covered_until = excluded.covered_until,
+           token_count = excluded.token_count,
+           updated_at = excluded.updated_at",
+        params![
+            session_id,
+            summary,
+            covered_until as i64,
+            token_count as i64,
+            now_ms() as i64,
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Stores the trim report of a message, or clears a stale one when its
+/// latest request fit without trimming.
+pub fn save_message_trim(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+    report: Option<&ContextTrimReport>,
+) -> Result<(), String> {
+    let Some(report) = report else {
+        conn.execute(
+            "DELETE FROM chat_message_context_trims WHERE message_id = ?1",
+            params![message_id],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        return Ok(());
+    };
+    let raw = serde_json::to_string(report)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO chat_message_context_trims (message_id, session_id, report, created_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(message_id) DO UPDATE SET
+           report = excluded.report,
+           created_at = excluded.created_at",
+        params![message_id, session_id, raw, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn load_message_trim(
+    conn: &Connection,
+    message_id: &str,
+) -> Result<Option<ContextTrimReport>, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT report FROM chat_message_context_trims WHERE message_id = ?1",
+            params![message_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+#[tauri::command]
+pub fn session_context_summary_get(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<RollingSummary>, String> {
+    let conn = open_db(&app)?;
+    load_rolling_summary(&conn, &session_id)
+}
+
+#[tauri::command]
+pub fn session_context_summary_clear(app: AppHandle, session_id: String) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "DELETE FROM session_context_summaries WHERE session_id = ?1",
+        params![session_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
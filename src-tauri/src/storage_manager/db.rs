@@ -743,6 +743,17 @@ pub fn init_db(_app: &tauri::AppHandle, conn: &Connection) -> Result<(), String>
           FOREIGN KEY(usage_id) REFERENCES usage_records(id) ON DELETE CASCADE
         );
 
+        CREATE TABLE IF NOT EXISTS provider_key_usage (
+          credential_id TEXT NOT NULL,
+          key_id TEXT NOT NULL,
+          requests INTEGER NOT NULL DEFAULT 0,
+          rate_limited INTEGER NOT NULL DEFAULT 0,
+          exhausted INTEGER NOT NULL DEFAULT 0,
+          last_used_at INTEGER,
+          last_rate_limited_at INTEGER,
+          PRIMARY KEY (credential_id, key_id)
+        );
+
         -- Model pricing cache (migrated from models_cache.json)
         CREATE TABLE IF NOT EXISTS model_pricing_cache (
           model_id TEXT PRIMARY KEY,
@@ -976,6 +987,215 @@ pub fn init_db(_app: &tauri::AppHandle, conn: &Connection) -> Result<(), String>
           PRIMARY KEY (peer_device_id, domain)
         );
 
+        CREATE TABLE IF NOT EXISTS sync_asset_transfers (
+          content_hash TEXT PRIMARY KEY,
+          path TEXT NOT NULL,
+          total_size INTEGER NOT NULL,
+          received_bytes INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS backup_schedule (
+          id INTEGER PRIMARY KEY CHECK (id = 1),
+          enabled INTEGER NOT NULL DEFAULT 0,
+          frequency TEXT NOT NULL DEFAULT 'daily',
+          directory TEXT,
+          has_password INTEGER NOT NULL DEFAULT 0,
+          incrementals_per_full INTEGER NOT NULL DEFAULT 6,
+          keep_daily INTEGER NOT NULL DEFAULT 7,
+          keep_weekly INTEGER NOT NULL DEFAULT 4,
+          last_run_at INTEGER,
+          last_error TEXT,
+          updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS character_memories (
+          character_id TEXT NOT NULL,
+          memory_id TEXT NOT NULL,
+          embedding BLOB NOT NULL,
+          embedding_dim INTEGER NOT NULL DEFAULT 0,
+          embedding_model TEXT,
+          text TEXT NOT NULL,
+          token_count INTEGER NOT NULL DEFAULT 0,
+          category TEXT,
+          importance_score REAL NOT NULL DEFAULT 1.0,
+          is_cold INTEGER NOT NULL DEFAULT 0,
+          is_pinned INTEGER NOT NULL DEFAULT 0,
+          access_count INTEGER NOT NULL DEFAULT 0,
+          source_session_id TEXT,
+          source_memory_id TEXT,
+          promoted_by TEXT NOT NULL DEFAULT 'manual',
+          created_at INTEGER NOT NULL,
+          last_accessed_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          PRIMARY KEY(character_id, memory_id),
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS character_memory_settings (
+          character_id TEXT PRIMARY KEY,
+          enabled INTEGER NOT NULL DEFAULT 0,
+          token_budget INTEGER NOT NULL DEFAULT 600,
+          retrieval_limit INTEGER NOT NULL DEFAULT 4,
+          auto_promote INTEGER NOT NULL DEFAULT 0,
+          promote_min_importance REAL NOT NULL DEFAULT 0.8,
+          promote_min_access_count INTEGER NOT NULL DEFAULT 3,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_nodes (
+          id TEXT PRIMARY KEY,
+          character_id TEXT NOT NULL,
+          session_id TEXT,
+          kind TEXT NOT NULL,
+          name TEXT NOT NULL,
+          normalized TEXT NOT NULL,
+          aliases TEXT NOT NULL DEFAULT '[]',
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_mentions (
+          entity_id TEXT NOT NULL,
+          source_kind TEXT NOT NULL CHECK (source_kind IN ('message', 'memory')),
+          source_id TEXT NOT NULL,
+          session_id TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          PRIMARY KEY(entity_id, source_kind, source_id),
+          FOREIGN KEY(entity_id) REFERENCES entity_nodes(id) ON DELETE CASCADE,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_graph_sources (
+          session_id TEXT NOT NULL,
+          source_kind TEXT NOT NULL,
+          source_id TEXT NOT NULL,
+          processed_at INTEGER NOT NULL,
+          PRIMARY KEY(session_id, source_kind, source_id),
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS memory_ann_indexes (
+          session_id TEXT NOT NULL,
+          session_kind TEXT NOT NULL CHECK (session_kind IN ('session', 'group_session')),
+          dim INTEGER NOT NULL,
+          node_count INTEGER NOT NULL,
+          revision INTEGER NOT NULL,
+          graph BLOB NOT NULL,
+          updated_at INTEGER NOT NULL,
+          PRIMARY KEY(session_id, session_kind)
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_relationships (
+          character_id TEXT PRIMARY KEY,
+          closeness REAL NOT NULL,
+          trust REAL NOT NULL,
+          affection REAL NOT NULL,
+          tension REAL NOT NULL,
+          stability REAL NOT NULL,
+          interaction_count INTEGER NOT NULL DEFAULT 0,
+          last_interaction_at INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_relationship_history (
+          id TEXT PRIMARY KEY,
+          character_id TEXT NOT NULL,
+          session_id TEXT,
+          source TEXT NOT NULL,
+          closeness REAL NOT NULL,
+          trust REAL NOT NULL,
+          affection REAL NOT NULL,
+          tension REAL NOT NULL,
+          stability REAL NOT NULL,
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_proactive_settings (
+          id INTEGER PRIMARY KEY CHECK(id = 1),
+          enabled INTEGER NOT NULL DEFAULT 0,
+          quiet_start_minute INTEGER NOT NULL DEFAULT 1320,
+          quiet_end_minute INTEGER NOT NULL DEFAULT 480,
+          max_per_day INTEGER NOT NULL DEFAULT 2,
+          min_gap_minutes INTEGER NOT NULL DEFAULT 240,
+          min_idle_minutes INTEGER NOT NULL DEFAULT 180,
+          updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS companion_proactive_messages (
+          id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          character_id TEXT NOT NULL,
+          message_id TEXT NOT NULL,
+          status TEXT NOT NULL DEFAULT 'pending',
+          urgency REAL NOT NULL DEFAULT 0,
+          created_at INTEGER NOT NULL,
+          resolved_at INTEGER,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS character_fallback_chains (
+          character_id TEXT PRIMARY KEY,
+          chain TEXT NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(character_id) REFERENCES characters(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_attempts (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          attempts TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS session_context_summaries (
+          session_id TEXT PRIMARY KEY,
+          summary TEXT NOT NULL,
+          covered_until INTEGER NOT NULL,
+          token_count INTEGER NOT NULL DEFAULT 0,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_context_trims (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          report TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_reasoning_items (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          variant_id TEXT,
+          items TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS chat_message_citations (
+          message_id TEXT PRIMARY KEY,
+          session_id TEXT NOT NULL,
+          variant_id TEXT,
+          citations TEXT NOT NULL,
+          created_at INTEGER NOT NULL,
+          FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_character_memories_source ON character_memories(character_id, source_session_id);
+        CREATE INDEX IF NOT EXISTS idx_companion_relationship_history_character ON companion_relationship_history(character_id, created_at);
+        CREATE INDEX IF NOT EXISTS idx_companion_proactive_messages_session ON companion_proactive_messages(session_id, status);
+        CREATE INDEX IF NOT EXISTS idx_entity_nodes_scope ON entity_nodes(character_id, session_id, normalized);
+        CREATE INDEX IF NOT EXISTS idx_entity_mentions_source ON entity_mentions(session_id, source_kind, source_id);
         CREATE INDEX IF NOT EXISTS idx_sync_changes_domain_id ON sync_changes(domain, id);
         CREATE INDEX IF NOT EXISTS idx_sync_changes_entity ON sync_changes(domain, entity_type, entity_id, id);
       "#,
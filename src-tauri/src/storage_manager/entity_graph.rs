@@ -0,0 +1,396 @@
+//! Entity graph built from the companion NER model. Nodes are scoped to a
+//! session (`session_id` set) or shared by every session with a character
+//! (`session_id` NULL). Mentions link nodes to the messages and memories they
+//! were extracted from.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::db::{now_ms, open_db};
+use crate::chat_manager::memory::entity_graph::{find_matching_node, normalize_entity_name};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityNode {
+    pub id: String,
+    pub character_id: String,
+    /// `None` for nodes in the character-wide graph.
+    pub session_id: Option<String>,
+    /// "person", "place", "item" or "organisation".
+    pub kind: String,
+    pub name: String,
+    pub normalized: String,
+    pub aliases: Vec<String>,
+    pub mention_count: u32,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityMention {
+    pub entity_id: String,
+    /// "message" or "memory".
+    pub source_kind: String,
+    pub source_id: String,
+    pub session_id: String,
+    pub created_at: u64,
+}
+
+fn read_node(r: &rusqlite::Row<'_>) -> rusqlite::Result<EntityNode> {
+    let aliases: String = r.get(6)?;
+    Ok(EntityNode {
+        id: r.get(0)?,
+        character_id: r.get(1)?,
+        session_id: r.get(2)?,
+        kind: r.get(3)?,
+        name: r.get(4)?,
+        normalized: r.get(5)?,
+        aliases: serde_json::from_str(&aliases).unwrap_or_default(),
+        mention_count: r.get::<_, i64>(7)?.max(0) as u32,
+        created_at: r.get::<_, i64>(8)? as u64,
+        updated_at: r.get::<_, i64>(9)? as u64,
+    })
+}
+
+const NODE_COLUMNS: &str =
+    "n.id, n.character_id, n.session_id, n.kind, n.name, n.normalized, n.aliases, \
+     (SELECT COUNT(*) FROM entity_mentions m WHERE m.entity_id = n.id), n.created_at, n.updated_at";
+
+/// Nodes of one scope: the session graph when `session_id` is set, the
+/// character-wide graph otherwise.
+pub fn load_nodes(
+    conn: &Connection,
+    character_id: &str,
+    session_id: Option<&str>,
+) -> Result<Vec<EntityNode>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM entity_nodes n WHERE n.character_id = ?1 AND n.session_id IS ?2 \
+             ORDER BY n.updated_at DESC",
+            NODE_COLUMNS
+        ))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![character_id, session_id], read_node)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?);
+    }
+    Ok(out)
+}
+
+fn load_node(conn: &Connection, entity_id: &str) -> Result<EntityNode, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM entity_nodes n WHERE n.id = ?1",
+            NODE_COLUMNS
+        ),
+        params![entity_id],
+        read_node,
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+    .ok_or_else(|| "Entity not found".to_string())
+}
+
+fn save_names(conn: &Connection, node: &EntityNode) -> Result<(), String> {
+    let aliases = serde_json::to_string(&node.aliases)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "UPDATE entity_nodes SET name = ?1, normalized = ?2, aliases = ?3, updated_at = ?4 WHERE id = ?5",
+        params![&node.name, &node.normalized, aliases, now_ms() as i64, &node.id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Links `source` to the node matching `name` in the given scope, creating the
+/// node on first sight. Returns the node id, or `None` when the name is empty
+/// after normalization.
+#[allow(clippy::too_many_arguments)]
+pub fn record_mention(
+    conn: &Connection,
+    character_id: &str,
+    scope_session_id: Option<&str>,
+    kind: &str,
+    name: &str,
+    source_kind: &str,
+    source_id: &str,
+    source_session_id: &str,
+) -> Result<Option<String>, String> {
+    let normalized = normalize_entity_name(name);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+    let nodes = load_nodes(conn, character_id, scope_session_id)?;
+    let now = now_ms() as i64;
+    let entity_id = match find_matching_node(&nodes, &normalized) {
+        Some(node) => {
+            conn.execute(
+                "UPDATE entity_nodes SET updated_at = ?1 WHERE id = ?2",
+                params![now, &node.id],
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            node.id.clone()
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO entity_nodes (id, character_id, session_id, kind, name, normalized, aliases, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, '[]', ?7, ?7)",
+                params![&id, character_id, scope_session_id, kind, name.trim(), &normalized, now],
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            id
+        }
+    };
+    conn.execute(
+        "INSERT OR IGNORE INTO entity_mentions (entity_id, source_kind, source_id, session_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![&entity_id, source_kind, source_id, source_session_id, now],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(Some(entity_id))
+}
+
+/// Drops the mentions of one source so it can be re-extracted (e.g. after a
+/// regenerated reply replaced the message text).
+pub fn clear_source_mentions(
+    conn: &Connection,
+    session_id: &str,
+    source_kind: &str,
+    source_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM entity_mentions WHERE session_id = ?1 AND source_kind = ?2 AND source_id = ?3",
+        params![session_id, source_kind, source_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn mark_source_processed(
+    conn: &Connection,
+    session_id: &str,
+    source_kind: &str,
+    source_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO entity_graph_sources (session_id, source_kind, source_id, processed_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![session_id, source_kind, source_id, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn processed_source_ids(
+    conn: &Connection,
+    session_id: &str,
+    source_kind: &str,
+) -> Result<std::collections::HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT source_id FROM entity_graph_sources WHERE session_id = ?1 AND source_kind = ?2",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let ids = stmt
+        .query_map(params![session_id, source_kind], |r| r.get::<_, String>(0))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(ids)
+}
+
+/// Texts of the live memories linked to each entity, newest first. Covers both
+/// session memories and promoted character memories.
+pub fn facts_for_entities(
+    conn: &Connection,
+    entity_ids: &[String],
+    per_entity_limit: usize,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT text FROM (
+               SELECT me.text AS text, me.created_at AS created_at
+               FROM entity_mentions em
+               JOIN memory_embeddings me
+                 ON me.memory_id = em.source_id AND me.session_id = em.session_id
+               WHERE em.entity_id = ?1 AND em.source_kind = 'memory' AND me.superseded_by IS NULL
+               UNION
+               SELECT cm.text AS text, cm.created_at AS created_at
+               FROM entity_mentions em
+               JOIN character_memories cm
+                 ON cm.source_memory_id = em.source_id AND cm.source_session_id = em.session_id
+               WHERE em.entity_id = ?1 AND em.source_kind = 'memory'
+             ) ORDER BY created_at DESC LIMIT ?2",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut out = HashMap::new();
+    for entity_id in entity_ids {
+        let facts = stmt
+            .query_map(params![entity_id, per_entity_limit as i64], |r| {
+                r.get::<_, String>(0)
+            })
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let mut unique: Vec<String> = Vec::new();
+        for fact in facts {
+            if !unique.contains(&fact) {
+                unique.push(fact);
+            }
+        }
+        if !unique.is_empty() {
+            out.insert(entity_id.clone(), unique);
+        }
+    }
+    Ok(out)
+}
+
+#[tauri::command]
+pub fn entity_graph_list(
+    app: AppHandle,
+    character_id: String,
+    session_id: Option<String>,
+) -> Result<Vec<EntityNode>, String> {
+    let conn = open_db(&app)?;
+    load_nodes(&conn, &character_id, session_id.as_deref())
+}
+
+#[tauri::command]
+pub fn entity_graph_mentions(
+    app: AppHandle,
+    entity_id: String,
+) -> Result<Vec<EntityMention>, String> {
+    let conn = open_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT entity_id, source_kind, source_id, session_id, created_at \
+             FROM entity_mentions WHERE entity_id = ?1 ORDER BY created_at DESC",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![&entity_id], |r| {
+            Ok(EntityMention {
+                entity_id: r.get(0)?,
+                source_kind: r.get(1)?,
+                source_id: r.get(2)?,
+                session_id: r.get(3)?,
+                created_at: r.get::<_, i64>(4)? as u64,
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?);
+    }
+    Ok(out)
+}
+
+/// Renames an entity, keeping the old name as an alias so later mentions of
+/// it still resolve to the same node.
+#[tauri::command]
+pub fn entity_graph_rename(
+    app: AppHandle,
+    entity_id: String,
+    name: String,
+) -> Result<EntityNode, String> {
+    let conn = open_db(&app)?;
+    let mut node = load_node(&conn, &entity_id)?;
+    let normalized = normalize_entity_name(&name);
+    if normalized.is_empty() {
+        return Err("Entity name cannot be empty".to_string());
+    }
+    let others = load_nodes(&conn, &node.character_id, node.session_id.as_deref())?
+        .into_iter()
+        .filter(|other| other.id != node.id)
+        .collect::<Vec<_>>();
+    if find_matching_node(&others, &normalized).is_some() {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            "Another entity already uses this name; merge them instead",
+        ));
+    }
+
+    if node.normalized != normalized && !node.aliases.contains(&node.name) {
+        node.aliases.push(node.name.clone());
+    }
+    node.aliases
+        .retain(|alias| normalize_entity_name(alias) != normalized);
+    node.name = name.trim().to_string();
+    node.normalized = normalized;
+    save_names(&conn, &node)?;
+    load_node(&conn, &entity_id)
+}
+
+/// Folds `source_id` into `target_id`: mentions move over and the source's
+/// names become aliases of the target.
+#[tauri::command]
+pub fn entity_graph_merge(
+    app: AppHandle,
+    source_id: String,
+    target_id: String,
+) -> Result<EntityNode, String> {
+    if source_id == target_id {
+        return Err("Cannot merge an entity into itself".to_string());
+    }
+    let mut conn = open_db(&app)?;
+    let source = load_node(&conn, &source_id)?;
+    let mut target = load_node(&conn, &target_id)?;
+    if source.character_id != target.character_id || source.session_id != target.session_id {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            "Entities belong to different graphs",
+        ));
+    }
+
+    for alias in std::iter::once(source.name.clone()).chain(source.aliases.clone()) {
+        let normalized = normalize_entity_name(&alias);
+        if normalized != target.normalized
+            && !target
+                .aliases
+                .iter()
+                .any(|existing| normalize_entity_name(existing) == normalized)
+        {
+            target.aliases.push(alias);
+        }
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    tx.execute(
+        "INSERT OR IGNORE INTO entity_mentions (entity_id, source_kind, source_id, session_id, created_at)
+         SELECT ?1, source_kind, source_id, session_id, created_at FROM entity_mentions WHERE entity_id = ?2",
+        params![&target_id, &source_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    tx.execute(
+        "DELETE FROM entity_nodes WHERE id = ?1",
+        params![&source_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    save_names(&tx, &target)?;
+    tx.commit()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    load_node(&conn, &target_id)
+}
+
+#[tauri::command]
+pub fn entity_graph_delete(app: AppHandle, entity_id: String) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    conn.execute(
+        "DELETE FROM entity_nodes WHERE id = ?1",
+        params![&entity_id],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
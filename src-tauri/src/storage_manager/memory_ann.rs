@@ -0,0 +1,385 @@
+//! Persisted HNSW index (`chat_manager::memory::hnsw`) over a session's memory
+//! embeddings. `memory_embeddings::replace_all`, `delete_many` and
+//! `set_cold_many` keep it in step with the rows; sessions with fewer than
+//! `ANN_MIN_MEMORIES` embedded memories have no index and retrieval scores
+//! every memory directly.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use lazy_static::lazy_static;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use super::memory_embeddings::SessionKind;
+use crate::chat_manager::memory::dynamic::COLD_MEMORY_SCORE_MULTIPLIER;
+use crate::chat_manager::memory::hnsw::{random_level, vector_fingerprint, HnswGraph};
+use crate::chat_manager::types::MemoryEmbedding;
+use crate::storage_manager::db::open_db;
+
+/// Below this many embedded memories brute force is fast enough.
+pub const ANN_MIN_MEMORIES: usize = 512;
+/// Rebuild instead of patching when more than 1/N of the nodes go away at once.
+const REBUILD_REMOVED_FRACTION: usize = 4;
+const SEARCH_EF: usize = 96;
+/// Retrieval re-ranks at least this many ANN candidates.
+const MIN_CANDIDATES: usize = 64;
+const CANDIDATES_PER_RESULT: usize = 8;
+
+type CacheKey = (String, &'static str);
+
+lazy_static! {
+    /// Parsed graphs keyed by session, tagged with the row revision they match.
+    static ref GRAPH_CACHE: Mutex<GraphCache> = Mutex::new(HashMap::new());
+}
+
+type GraphCache = HashMap<CacheKey, (i64, Arc<HnswGraph>)>;
+
+fn graph_cache() -> Result<MutexGuard<'static, GraphCache>, String> {
+    GRAPH_CACHE
+        .lock()
+        .map_err(|e| format!("ANN graph cache lock poisoned: {}", e))
+}
+
+fn cache_key(session_id: &str, kind: SessionKind) -> CacheKey {
+    (session_id.to_string(), kind.as_str())
+}
+
+fn now_ms() -> i64 {
+    super::db::now_ms() as i64
+}
+
+/// Number of ANN candidates to fetch for a retrieval `limit`.
+pub fn candidate_count(limit: usize) -> usize {
+    limit
+        .saturating_mul(CANDIDATES_PER_RESULT)
+        .max(MIN_CANDIDATES)
+}
+
+/// Shared embedding width, or `None` when the session mixes dimensions (for
+/// example mid-way through a model migration) and cannot be indexed.
+fn index_dimension(memories: &[MemoryEmbedding]) -> Option<usize> {
+    let mut dims = memories
+        .iter()
+        .map(|m| m.embedding.len())
+        .filter(|dim| *dim > 0);
+    let first = dims.next()?;
+    dims.all(|dim| dim == first).then_some(first)
+}
+
+fn load_graph(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+) -> Result<Option<(i64, Arc<HnswGraph>)>, String> {
+    let key = cache_key(session_id, kind);
+    let revision: Option<i64> = conn
+        .query_row(
+            "SELECT revision FROM memory_ann_indexes WHERE session_id = ?1 AND session_kind = ?2",
+            params![session_id, kind.as_str()],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let Some(revision) = revision else {
+        graph_cache()?.remove(&key);
+        return Ok(None);
+    };
+
+    if let Some((cached_revision, graph)) = graph_cache()?.get(&key) {
+        if *cached_revision == revision {
+            return Ok(Some((revision, graph.clone())));
+        }
+    }
+
+    let blob: Vec<u8> = conn
+        .query_row(
+            "SELECT graph FROM memory_ann_indexes WHERE session_id = ?1 AND session_kind = ?2",
+            params![session_id, kind.as_str()],
+            |r| r.get(0),
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let graph: HnswGraph = serde_json::from_slice(&blob)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let graph = Arc::new(graph);
+    graph_cache()?.insert(key, (revision, graph.clone()));
+    Ok(Some((revision, graph)))
+}
+
+fn store_graph(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    graph: HnswGraph,
+    previous_revision: Option<i64>,
+) -> Result<(), String> {
+    let revision = previous_revision.unwrap_or(0) + 1;
+    let blob = serde_json::to_vec(&graph)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO memory_ann_indexes \
+         (session_id, session_kind, dim, node_count, revision, graph, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) \
+         ON CONFLICT(session_id, session_kind) DO UPDATE SET \
+           dim = excluded.dim, node_count = excluded.node_count, revision = excluded.revision, \
+           graph = excluded.graph, updated_at = excluded.updated_at",
+        params![
+            session_id,
+            kind.as_str(),
+            graph.dim as i64,
+            graph.len() as i64,
+            revision,
+            blob,
+            now_ms(),
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    graph_cache()?.insert(cache_key(session_id, kind), (revision, Arc::new(graph)));
+    Ok(())
+}
+
+pub fn drop_index(conn: &Connection, session_id: &str, kind: SessionKind) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM memory_ann_indexes WHERE session_id = ?1 AND session_kind = ?2",
+        params![session_id, kind.as_str()],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    graph_cache()?.remove(&cache_key(session_id, kind));
+    Ok(())
+}
+
+/// Builds a fresh graph over every memory embedded at `dim`.
+pub fn build_graph(memories: &[MemoryEmbedding], dim: usize) -> HnswGraph {
+    let mut rng = rand::thread_rng();
+    let mut graph = HnswGraph::new(dim);
+    let mut vectors = Vec::with_capacity(memories.len());
+    for memory in memories.iter().filter(|m| m.embedding.len() == dim) {
+        graph.insert(
+            &mut vectors,
+            memory.id.clone(),
+            &memory.embedding,
+            memory.is_cold,
+            memory.is_pinned,
+            random_level(&mut rng),
+        );
+    }
+    graph
+}
+
+/// Brings the session index in line with `memories`, the full set of rows.
+/// Only the difference is applied: re-embedded or removed memories are
+/// unlinked, new ones inserted and flags refreshed. Nothing is written when
+/// the index already matches.
+pub fn sync_with_memories(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    memories: &[MemoryEmbedding],
+) -> Result<(), String> {
+    let Some(dim) = index_dimension(memories) else {
+        return drop_index(conn, session_id, kind);
+    };
+    let wanted: HashMap<&str, &MemoryEmbedding> = memories
+        .iter()
+        .filter(|m| m.embedding.len() == dim)
+        .map(|m| (m.id.as_str(), m))
+        .collect();
+    if wanted.len() < ANN_MIN_MEMORIES {
+        return drop_index(conn, session_id, kind);
+    }
+
+    let (revision, current) = match load_graph(conn, session_id, kind)? {
+        Some((revision, graph)) if graph.dim == dim => (Some(revision), Some(graph)),
+        Some((revision, _)) => (Some(revision), None),
+        None => (None, None),
+    };
+    let Some(current) = current else {
+        return store_graph(conn, session_id, kind, build_graph(memories, dim), revision);
+    };
+
+    let stale: HashSet<&str> = current
+        .nodes()
+        .iter()
+        .filter(|node| {
+            wanted
+                .get(node.memory_id.as_str())
+                .is_none_or(|m| vector_fingerprint(&m.embedding) != node.fingerprint)
+        })
+        .map(|node| node.memory_id.as_str())
+        .collect();
+    let indexed: HashSet<&str> = current
+        .nodes()
+        .iter()
+        .map(|node| node.memory_id.as_str())
+        .collect();
+    let pending: Vec<&MemoryEmbedding> = memories
+        .iter()
+        .filter(|m| wanted.contains_key(m.id.as_str()))
+        .filter(|m| !indexed.contains(m.id.as_str()) || stale.contains(m.id.as_str()))
+        .collect();
+    let flags_changed = current.nodes().iter().any(|node| {
+        wanted
+            .get(node.memory_id.as_str())
+            .is_some_and(|m| m.is_cold != node.cold || m.is_pinned != node.pinned)
+    });
+    if stale.is_empty() && pending.is_empty() && !flags_changed {
+        return Ok(());
+    }
+    if stale.len() * REBUILD_REMOVED_FRACTION > current.len() {
+        return store_graph(conn, session_id, kind, build_graph(memories, dim), revision);
+    }
+
+    let mut graph = (*current).clone();
+    let mut vectors: Vec<&[f32]> = graph
+        .nodes()
+        .iter()
+        .map(|node| {
+            wanted
+                .get(node.memory_id.as_str())
+                .map(|m| m.embedding.as_slice())
+                .unwrap_or(&[])
+        })
+        .collect();
+    for memory_id in &stale {
+        graph.remove(&mut vectors, memory_id);
+    }
+    graph.set_flags(|memory_id| wanted.get(memory_id).map(|m| (m.is_cold, m.is_pinned)));
+    let mut rng = rand::thread_rng();
+    for memory in pending {
+        graph.insert(
+            &mut vectors,
+            memory.id.clone(),
+            &memory.embedding,
+            memory.is_cold,
+            memory.is_pinned,
+            random_level(&mut rng),
+        );
+    }
+    store_graph(conn, session_id, kind, graph, revision)
+}
+
+/// Unlinks deleted memories. Runs after the rows are gone; the remaining rows
+/// supply the vectors needed to reconnect the graph.
+pub fn remove_memories(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    memory_ids: &[String],
+) -> Result<(), String> {
+    let Some((_, current)) = load_graph(conn, session_id, kind)? else {
+        return Ok(());
+    };
+    if !memory_ids.iter().any(|id| current.contains(id)) {
+        return Ok(());
+    }
+    let remaining = super::memory_embeddings::load_for_session(conn, session_id, kind)?;
+    sync_with_memories(conn, session_id, kind, &remaining)
+}
+
+pub fn set_cold(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    memory_ids: &[String],
+    is_cold: bool,
+) -> Result<(), String> {
+    let Some((revision, current)) = load_graph(conn, session_id, kind)? else {
+        return Ok(());
+    };
+    let ids: HashSet<&str> = memory_ids.iter().map(String::as_str).collect();
+    let mut graph = (*current).clone();
+    if graph.set_cold(&ids, is_cold) {
+        store_graph(conn, session_id, kind, graph, Some(revision))?;
+    }
+    Ok(())
+}
+
+/// Indices into `memories` of the approximate `k` best matches for `query`,
+/// or `None` when the session has no usable index and callers should score
+/// every memory. An index that disagrees with `memories` is ignored.
+pub fn candidate_indices(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    query: &[f32],
+    memories: &[MemoryEmbedding],
+    k: usize,
+) -> Option<Vec<usize>> {
+    if memories.len() < ANN_MIN_MEMORIES {
+        return None;
+    }
+    let (_, graph) = load_graph(conn, session_id, kind).ok()??;
+    if graph.dim != query.len() {
+        return None;
+    }
+    let positions: HashMap<&str, usize> = memories
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| !m.embedding.is_empty())
+        .map(|(idx, m)| (m.id.as_str(), idx))
+        .collect();
+    if positions.len() != graph.len() {
+        return None;
+    }
+
+    let mut vectors = Vec::with_capacity(graph.len());
+    let mut node_positions = Vec::with_capacity(graph.len());
+    for node in graph.nodes() {
+        let &position = positions.get(node.memory_id.as_str())?;
+        let embedding = memories[position].embedding.as_slice();
+        if embedding.len() != graph.dim {
+            return None;
+        }
+        vectors.push(embedding);
+        node_positions.push(position);
+    }
+
+    Some(
+        graph
+            .search(
+                &vectors,
+                query,
+                k,
+                SEARCH_EF.max(k),
+                COLD_MEMORY_SCORE_MULTIPLIER,
+            )
+            .into_iter()
+            .map(|(node, _)| node_positions[node])
+            .collect(),
+    )
+}
+
+pub fn candidate_indices_app(
+    app: &AppHandle,
+    session_id: &str,
+    kind: SessionKind,
+    query: &[f32],
+    memories: &[MemoryEmbedding],
+    k: usize,
+) -> Option<Vec<usize>> {
+    if memories.len() < ANN_MIN_MEMORIES {
+        return None;
+    }
+    let conn = open_db(app).ok()?;
+    candidate_indices(&conn, session_id, kind, query, memories, k)
+}
+
+/// Runs `select` over the ANN candidates when there are any, otherwise over
+/// every memory. Returned indices always refer to `memories`.
+pub fn select_from_candidates<F>(
+    candidates: Option<&[usize]>,
+    memories: &[MemoryEmbedding],
+    select: F,
+) -> Vec<(usize, f32)>
+where
+    F: Fn(&[MemoryEmbedding]) -> Vec<(usize, f32)>,
+{
+    let Some(positions) = candidates else {
+        return select(memories);
+    };
+    let pool: Vec<MemoryEmbedding> = positions.iter().map(|&idx| memories[idx].clone()).collect();
+    select(&pool)
+        .into_iter()
+        .map(|(idx, score)| (positions[idx], score))
+        .collect()
+}
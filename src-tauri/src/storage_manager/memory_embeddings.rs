@@ -14,6 +14,8 @@ use tauri::AppHandle;
 
 use crate::chat_manager::types::{MemoryEmbedding, MemoryEntityAnchor};
 use crate::storage_manager::db::open_db;
+use crate::storage_manager::memory_ann;
+use crate::utils::log_warn_global;
 
 /// Distinguishes between rows owned by single-character `sessions` and
 /// multi-character `group_sessions`. Persisted as a TEXT column.
@@ -43,7 +45,7 @@ pub struct AccessUpdate {
 }
 
 /// Encode an f32 slice as raw little-endian bytes suitable for a SQLite BLOB.
-fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+pub(crate) fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(embedding.len() * 4);
     for v in embedding {
         bytes.extend_from_slice(&v.to_le_bytes());
@@ -53,7 +55,7 @@ fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
 
 /// Decode raw little-endian f32 bytes back into a `Vec<f32>`. Trailing bytes
 /// (which should never occur for well-formed rows) are ignored.
-fn blob_to_embedding(bytes: &[u8]) -> Vec<f32> {
+pub(crate) fn blob_to_embedding(bytes: &[u8]) -> Vec<f32> {
     let mut out = Vec::with_capacity(bytes.len() / 4);
     let mut i = 0;
     while i + 4 <= bytes.len() {
@@ -235,7 +237,7 @@ pub fn is_empty_for_session(
 }
 
 /// Replace every row for the session with the supplied vector. Runs in a single
-/// transaction.
+/// savepoint, so it also nests inside a caller's transaction.
 pub fn replace_all(
     conn: &mut Connection,
     session_id: &str,
@@ -243,7 +245,7 @@ pub fn replace_all(
     memories: &[MemoryEmbedding],
 ) -> Result<(), String> {
     let tx = conn
-        .transaction()
+        .savepoint()
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
     tx.execute(
@@ -316,11 +318,36 @@ pub fn replace_all(
         }
     }
 
+    let synced = memory_ann::sync_with_memories(&tx, session_id, kind, memories);
+    drop_index_on_failure(&tx, session_id, kind, synced)?;
+
     tx.commit()
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     Ok(())
 }
 
+/// A failed ANN index update must not fail the write, but the index cannot be
+/// left disagreeing with the rows either: it is dropped, searches fall back to
+/// scoring every memory, and the next save rebuilds it.
+fn drop_index_on_failure(
+    conn: &Connection,
+    session_id: &str,
+    kind: SessionKind,
+    result: Result<(), String>,
+) -> Result<(), String> {
+    let Err(err) = result else {
+        return Ok(());
+    };
+    log_warn_global(
+        "memory_embeddings",
+        format!(
+            "memory index update failed for session {}, dropping the index: {}",
+            session_id, err
+        ),
+    );
+    memory_ann::drop_index(conn, session_id, kind)
+}
+
 /// Hot-path narrow update: only `importance_score`, `last_accessed_at`, and
 /// `access_count`. Single transaction.
 pub fn apply_access_updates(
@@ -391,7 +418,8 @@ pub fn set_cold_many(
     let refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|b| b.as_ref()).collect();
     conn.execute(&sql, refs.as_slice())
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    Ok(())
+    let updated = memory_ann::set_cold(conn, session_id, kind, memory_ids, is_cold);
+    drop_index_on_failure(conn, session_id, kind, updated)
 }
 
 /// Delete the listed memory ids. Single statement.
@@ -419,7 +447,8 @@ pub fn delete_many(
     let refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|b| b.as_ref()).collect();
     conn.execute(&sql, refs.as_slice())
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    Ok(())
+    let updated = memory_ann::remove_memories(conn, session_id, kind, memory_ids);
+    drop_index_on_failure(conn, session_id, kind, updated)
 }
 
 /// Delete every row for the session. Used when a session itself is deleted.
@@ -433,6 +462,7 @@ pub fn delete_all_for_session(
         params![session_id, kind.as_str()],
     )
     .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    memory_ann::drop_index(conn, session_id, kind)?;
     Ok(())
 }
 
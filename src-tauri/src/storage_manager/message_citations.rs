@@ -0,0 +1,103 @@
+//! Citations returned with grounded replies (Cohere `documents`), kept per
+//! assistant message so the UI can show which lore entry or memory a reply
+//! relied on. Like reasoning items, they belong to the variant that produced
+//! them.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::db::{now_ms, open_db};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationSource {
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageCitation {
+    pub text: String,
+    #[serde(default)]
+    pub start: Option<u64>,
+    #[serde(default)]
+    pub end: Option<u64>,
+    pub sources: Vec<CitationSource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredMessageCitations {
+    pub variant_id: Option<String>,
+    pub citations: Vec<MessageCitation>,
+}
+
+/// Stores the citations of a message, or clears stale ones when the latest
+/// generation returned none.
+pub fn save_message_citations(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+    variant_id: Option<&str>,
+    citations: &[MessageCitation],
+) -> Result<(), String> {
+    if citations.is_empty() {
+        conn.execute(
+            "DELETE FROM chat_message_citations WHERE message_id = ?1",
+            params![message_id],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        return Ok(());
+    }
+    let raw = serde_json::to_string(citations)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO chat_message_citations (message_id, session_id, variant_id, citations, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(message_id) DO UPDATE SET
+           variant_id = excluded.variant_id,
+           citations = excluded.citations,
+           created_at = excluded.created_at",
+        params![message_id, session_id, variant_id, raw, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn load_message_citations(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+) -> Result<Option<StoredMessageCitations>, String> {
+    let row = conn
+        .query_row(
+            "SELECT variant_id, citations FROM chat_message_citations
+             WHERE session_id = ?1 AND message_id = ?2",
+            params![session_id, message_id],
+            |r| Ok((r.get::<_, Option<String>>(0)?, r.get::<_, String>(1)?)),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let Some((variant_id, raw)) = row else {
+        return Ok(None);
+    };
+    let citations = serde_json::from_str::<Vec<MessageCitation>>(&raw)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(Some(StoredMessageCitations {
+        variant_id,
+        citations,
+    }))
+}
+
+#[tauri::command]
+pub fn get_message_citations(
+    app: AppHandle,
+    session_id: String,
+    message_id: String,
+) -> Result<Option<StoredMessageCitations>, String> {
+    let conn = open_db(&app)?;
+    load_message_citations(&conn, &session_id, &message_id)
+}
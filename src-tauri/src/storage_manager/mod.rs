@@ -1,8 +1,15 @@
 pub mod backup;
+pub mod backup_schedule;
+pub mod backup_selective;
+pub mod character_memories;
 pub mod characters;
 pub mod chatpkg;
+pub mod companion_proactive;
+pub mod companion_relationships;
 pub mod companion_turn_effects;
+pub mod context_summaries;
 pub mod db;
+pub mod entity_graph;
 pub mod entity_transfer;
 pub mod group_characters;
 pub mod group_sessions;
@@ -10,10 +17,15 @@ pub mod importer;
 pub mod legacy;
 pub mod lorebook;
 pub mod media;
+pub mod memory_ann;
 pub mod memory_embeddings;
+pub mod message_citations;
+pub mod model_fallbacks;
 pub mod models;
 pub mod personas;
 pub mod providers;
+pub mod reasoning_items;
+pub mod secrets;
 pub mod sessions;
 pub mod settings;
 pub mod system_cards;
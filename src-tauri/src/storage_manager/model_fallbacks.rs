@@ -0,0 +1,119 @@
+//! Per-character fallback chains and the model attempt trace recorded for
+//! assistant messages that needed more than one attempt.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use super::db::{now_ms, open_db};
+use crate::chat_manager::types::{FallbackChainStep, ModelAttemptRecord};
+
+pub fn load_character_chain(
+    conn: &Connection,
+    character_id: &str,
+) -> Result<Vec<FallbackChainStep>, String> {
+    let chain: Option<String> = conn
+        .query_row(
+            "SELECT chain FROM character_fallback_chains WHERE character_id = ?1",
+            params![character_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(chain
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+fn save_character_chain(
+    conn: &Connection,
+    character_id: &str,
+    chain: &[FallbackChainStep],
+) -> Result<(), String> {
+    if chain.is_empty() {
+        conn.execute(
+            "DELETE FROM character_fallback_chains WHERE character_id = ?1",
+            params![character_id],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        return Ok(());
+    }
+    let raw = serde_json::to_string(chain)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO character_fallback_chains (character_id, chain, updated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(character_id) DO UPDATE SET
+           chain = excluded.chain,
+           updated_at = excluded.updated_at",
+        params![character_id, raw, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn save_message_attempts(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+    attempts: &[ModelAttemptRecord],
+) -> Result<(), String> {
+    if attempts.is_empty() {
+        conn.execute(
+            "DELETE FROM chat_message_attempts WHERE message_id = ?1",
+            params![message_id],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        return Ok(());
+    }
+    let raw = serde_json::to_string(attempts)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO chat_message_attempts (message_id, session_id, attempts, created_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(message_id) DO UPDATE SET
+           attempts = excluded.attempts,
+           created_at = excluded.created_at",
+        params![message_id, session_id, raw, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn load_message_attempts(
+    conn: &Connection,
+    message_id: &str,
+) -> Result<Vec<ModelAttemptRecord>, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT attempts FROM chat_message_attempts WHERE message_id = ?1",
+            params![message_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(raw
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn character_fallback_chain_get(
+    app: AppHandle,
+    character_id: String,
+) -> Result<Vec<FallbackChainStep>, String> {
+    let conn = open_db(&app)?;
+    load_character_chain(&conn, &character_id)
+}
+
+/// Replaces the character's chain; an empty chain falls back to the legacy
+/// single `fallbackModelId`.
+#[tauri::command]
+pub fn character_fallback_chain_set(
+    app: AppHandle,
+    character_id: String,
+    chain: Vec<FallbackChainStep>,
+) -> Result<Vec<FallbackChainStep>, String> {
+    let conn = open_db(&app)?;
+    save_character_chain(&conn, &character_id, &chain)?;
+    load_character_chain(&conn, &character_id)
+}
@@ -2,6 +2,7 @@ use rusqlite::{params, OptionalExtension};
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
 use super::db::open_db;
+use crate::utils::log_warn;
 
 #[tauri::command]
 pub fn provider_upsert(app: tauri::AppHandle, credential_json: String) -> Result<String, String> {
@@ -39,9 +40,13 @@ pub fn provider_upsert(app: tauri::AppHandle, credential_json: String) -> Result
     let config = cred
         .get("config")
         .map(|v| serde_json::to_string(v).unwrap_or("null".into()));
+    let api_keys = cred
+        .get("apiKeys")
+        .filter(|v| v.is_array())
+        .map(|v| serde_json::to_string(v).unwrap_or("[]".into()));
     conn.execute(
-        r#"INSERT INTO provider_credentials (id, provider_id, label, api_key, base_url, default_model, headers, config)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        r#"INSERT INTO provider_credentials (id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 provider_id = excluded.provider_id,
                 label = excluded.label,
@@ -49,9 +54,11 @@ pub fn provider_upsert(app: tauri::AppHandle, credential_json: String) -> Result
                 base_url = excluded.base_url,
                 default_model = excluded.default_model,
                 headers = excluded.headers,
-                config = excluded.config"#,
-        params![id, provider_id, label, api_key, base_url, default_model, headers, config],
+                config = excluded.config,
+                api_keys = excluded.api_keys"#,
+        params![id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys],
     ).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    crate::transport::refresh_settings(&app);
 
     let mut out = JsonMap::new();
     out.insert("id".into(), JsonValue::String(id));
@@ -87,6 +94,9 @@ pub fn provider_upsert(app: tauri::AppHandle, credential_json: String) -> Result
             out.insert("config".into(), v);
         }
     }
+    if let Some(v) = cred.get("apiKeys").filter(|v| v.is_array()).cloned() {
+        out.insert("apiKeys".into(), v);
+    }
     serde_json::to_string(&JsonValue::Object(out))
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
 }
@@ -94,8 +104,32 @@ pub fn provider_upsert(app: tauri::AppHandle, credential_json: String) -> Result
 #[tauri::command]
 pub fn provider_delete(app: tauri::AppHandle, id: String) -> Result<(), String> {
     let conn = open_db(&app)?;
+    let config: Option<String> = conn
+        .query_row(
+            "SELECT config FROM provider_credentials WHERE id = ?",
+            params![id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .flatten();
     conn.execute("DELETE FROM provider_credentials WHERE id = ?", params![id])
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let has_proxy_password = config
+        .and_then(|raw| serde_json::from_str::<JsonValue>(&raw).ok())
+        .and_then(|config| config.pointer("/network/proxy/hasPassword")?.as_bool())
+        .unwrap_or(false);
+    if has_proxy_password {
+        let deleted = crate::transport::network::delete_proxy_password(&conn, Some(id.as_str()));
+        if let Err(err) = deleted {
+            log_warn(
+                &app,
+                "providers",
+                format!("failed to delete proxy password of {}: {}", id, err),
+            );
+        }
+    }
+    crate::transport::refresh_settings(&app);
     Ok(())
 }
 
@@ -105,7 +139,7 @@ pub fn get_provider_credential(
 ) -> Result<crate::chat_manager::types::ProviderCredential, String> {
     let conn = open_db(app).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     let mut stmt = conn
-        .prepare("SELECT id, provider_id, label, api_key, base_url, default_model, headers, config FROM provider_credentials WHERE id = ?")
+        .prepare("SELECT id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys FROM provider_credentials WHERE id = ?")
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
     let row = stmt
@@ -131,6 +165,11 @@ pub fn get_provider_credential(
                 None
             };
 
+            let api_keys = r
+                .get::<_, Option<String>>(8)?
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default();
+
             Ok(crate::chat_manager::types::ProviderCredential {
                 id,
                 provider_id,
@@ -140,6 +179,7 @@ pub fn get_provider_credential(
                 default_model,
                 headers,
                 config,
+                api_keys,
             })
         })
         .optional()
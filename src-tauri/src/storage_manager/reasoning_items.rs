@@ -0,0 +1,80 @@
+//! Opaque reasoning items (OpenAI Responses API) kept per assistant message so
+//! later turns can send them back. They belong to the variant that produced
+//! them; a swipe or edit makes them stale.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+use super::db::now_ms;
+
+#[derive(Debug, Clone)]
+pub struct StoredReasoningItems {
+    pub variant_id: Option<String>,
+    pub items: Vec<Value>,
+}
+
+/// Stores the reasoning items of a message, or clears stale ones when the
+/// latest generation returned none.
+pub fn save_message_reasoning_items(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+    variant_id: Option<&str>,
+    items: &[Value],
+) -> Result<(), String> {
+    if items.is_empty() {
+        conn.execute(
+            "DELETE FROM chat_message_reasoning_items WHERE message_id = ?1",
+            params![message_id],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        return Ok(());
+    }
+    let raw = serde_json::to_string(items)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "INSERT INTO chat_message_reasoning_items (message_id, session_id, variant_id, items, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(message_id) DO UPDATE SET
+           variant_id = excluded.variant_id,
+           items = excluded.items,
+           created_at = excluded.created_at",
+        params![message_id, session_id, variant_id, raw, now_ms() as i64],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn load_session_reasoning_items(
+    conn: &Connection,
+    session_id: &str,
+) -> Result<HashMap<String, StoredReasoningItems>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_id, variant_id, items FROM chat_message_reasoning_items
+             WHERE session_id = ?1",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![session_id], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, Option<String>>(1)?,
+                r.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut out = HashMap::new();
+    for row in rows {
+        let (message_id, variant_id, raw) =
+            row.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let Ok(items) = serde_json::from_str::<Vec<Value>>(&raw) else {
+            continue;
+        };
+        out.insert(message_id, StoredReasoningItems { variant_id, items });
+    }
+    Ok(out)
+}
@@ -0,0 +1,48 @@
+//! Secrets kept in the `secrets` table alongside provider API keys, for
+//! values that must not sit in plain settings JSON.
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::db::now_ms;
+
+pub fn get_secret(
+    conn: &Connection,
+    service: &str,
+    account: &str,
+) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM secrets WHERE service = ?1 AND account = ?2",
+        params![service, account],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+pub fn set_secret(
+    conn: &Connection,
+    service: &str,
+    account: &str,
+    value: &str,
+) -> Result<(), String> {
+    let now = now_ms() as i64;
+    conn.execute(
+        r#"INSERT INTO secrets (service, account, value, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?4)
+            ON CONFLICT(service, account) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at"#,
+        params![service, account, value, now],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn delete_secret(conn: &Connection, service: &str, account: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM secrets WHERE service = ?1 AND account = ?2",
+        params![service, account],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
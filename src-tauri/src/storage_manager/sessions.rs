@@ -80,7 +80,9 @@ fn write_session_embeddings_through_table(
     Ok(())
 }
 
-fn normalize_memory_category(category: Option<String>) -> Result<Option<String>, String> {
+pub(crate) fn normalize_memory_category(
+    category: Option<String>,
+) -> Result<Option<String>, String> {
     let normalized = category
         .map(|c| c.trim().to_string())
         .filter(|c| !c.is_empty());
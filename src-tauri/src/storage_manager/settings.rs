@@ -137,7 +137,7 @@ fn db_read_settings_json(app: &tauri::AppHandle) -> Result<Option<String>, Strin
 
     // Provider credentials
     let mut stmt = conn
-        .prepare("SELECT id, provider_id, label, api_key, base_url, default_model, headers, config FROM provider_credentials")
+        .prepare("SELECT id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys FROM provider_credentials")
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     let creds_iter = stmt
         .query_map([], |r| {
@@ -149,6 +149,7 @@ fn db_read_settings_json(app: &tauri::AppHandle) -> Result<Option<String>, Strin
             let default_model: Option<String> = r.get(5)?;
             let headers: Option<String> = r.get(6)?;
             let config: Option<String> = r.get(7)?;
+            let api_keys: Option<String> = r.get(8)?;
             let mut obj = JsonMap::new();
             obj.insert("id".into(), JsonValue::String(id));
             obj.insert("providerId".into(), JsonValue::String(provider_id));
@@ -176,6 +177,13 @@ fn db_read_settings_json(app: &tauri::AppHandle) -> Result<Option<String>, Strin
                     }
                 }
             }
+            if let Some(s) = api_keys {
+                if let Ok(v) = serde_json::from_str::<JsonValue>(&s) {
+                    if v.is_array() {
+                        obj.insert("apiKeys".into(), v);
+                    }
+                }
+            }
             Ok(JsonValue::Object(obj))
         })
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
@@ -464,9 +472,13 @@ fn db_write_settings_json(app: &tauri::AppHandle, data: String) -> Result<(), St
             let config = c
                 .get("config")
                 .map(|v| serde_json::to_string(v).unwrap_or("null".into()));
+            let api_keys = c
+                .get("apiKeys")
+                .filter(|v| v.is_array())
+                .map(|v| serde_json::to_string(v).unwrap_or("[]".into()));
             tx.execute(
-                "INSERT INTO provider_credentials (id, provider_id, label, api_key, base_url, default_model, headers, config) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                params![id, provider_id, label, api_key, base_url, default_model, headers, config],
+                "INSERT INTO provider_credentials (id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![id, provider_id, label, api_key, base_url, default_model, headers, config, api_keys],
             ).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
             log_info(
                 app,
@@ -633,6 +645,7 @@ where
             sync_content_filter_from_app_state(app, app_state);
         }
     }
+    crate::transport::refresh_settings(app);
     Ok(())
 }
 
@@ -750,6 +763,7 @@ pub fn storage_write_settings(app: tauri::AppHandle, data: String) -> Result<(),
             sync_content_filter_from_app_state(&app, app_state);
         }
     }
+    crate::transport::refresh_settings(&app);
     Ok(())
 }
 
@@ -801,6 +815,7 @@ pub fn settings_set_advanced(app: tauri::AppHandle, advanced_json: String) -> Re
         params![db_val, now],
     )
     .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    crate::transport::refresh_settings(&app);
     Ok(())
 }
 
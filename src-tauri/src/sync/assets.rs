@@ -0,0 +1,706 @@
+use futures::{SinkExt, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::net::TcpStream;
+use tokio_util::codec::Framed;
+
+use crate::storage_manager::db::DbConnection;
+use crate::sync::codec::P2PCodec;
+use crate::sync::db as sync_db;
+use crate::sync::protocol::{
+    AssetTransferRequest, AssetTransferState, ChangeOp, ChangeRecord, P2PMessage,
+};
+use crate::utils::{log_info, log_warn};
+
+/// First protocol version that transfers assets as acknowledged chunks.
+pub const CHUNKED_ASSETS_PROTOCOL_VERSION: u32 = 10;
+
+/// Payload size of a single `AssetChunk`; keeps every frame far below the codec limit.
+pub const ASSET_CHUNK_SIZE: u64 = 1024 * 1024;
+
+const STAGING_DIR: &str = "sync_staging";
+
+// Partial transfers nobody resumed within a week are dropped.
+const STALE_TRANSFER_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Receiver-side plan for one asset batch.
+#[derive(Default)]
+pub struct AssetBatchPlan {
+    pub requests: Vec<AssetTransferRequest>,
+    /// Entities whose content is already available locally and only needs copying.
+    pub present_entity_ids: HashSet<String>,
+    /// content hash -> existing local path that already holds the content.
+    pub local_sources: HashMap<String, String>,
+}
+
+pub fn resolve_asset_path(app: &AppHandle, relative_path: &str) -> Result<PathBuf, String> {
+    if relative_path.contains("..")
+        || relative_path.starts_with('/')
+        || relative_path.contains('\\')
+    {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Invalid asset path: {}", relative_path),
+        ));
+    }
+
+    if !relative_path.starts_with("avatars/")
+        && !relative_path.starts_with("sessions/")
+        && !relative_path.starts_with("images/")
+        && !relative_path.starts_with("generated_images/")
+    {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Unauthorized asset path: {}", relative_path),
+        ));
+    }
+
+    if relative_path.starts_with("generated_images/") {
+        Ok(app
+            .path()
+            .app_data_dir()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+            .join(relative_path))
+    } else {
+        Ok(crate::storage_manager::legacy::storage_root(app)?.join(relative_path))
+    }
+}
+
+pub async fn write_asset_path(
+    app: &AppHandle,
+    relative_path: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    let full_path = resolve_asset_path(app, relative_path)?;
+
+    if let Some(parent) = full_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    tokio::fs::write(&full_path, content)
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+pub fn remove_asset_path(app: &AppHandle, relative_path: &str) -> Result<(), String> {
+    let full_path = resolve_asset_path(app, relative_path)?;
+    if full_path.exists() {
+        std::fs::remove_file(&full_path)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<String, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let mut hasher = blake3::Hasher::new();
+    hasher
+        .update_reader(file)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+fn staging_path(app: &AppHandle, content_hash: &str) -> Result<PathBuf, String> {
+    if content_hash.is_empty() || !content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Invalid asset content hash: {}", content_hash),
+        ));
+    }
+    Ok(crate::storage_manager::legacy::storage_root(app)?
+        .join(STAGING_DIR)
+        .join(format!("{}.part", content_hash)))
+}
+
+fn local_copy_matches(app: &AppHandle, relative_path: &str, asset: &sync_db::AssetRecord) -> bool {
+    let Ok(path) = resolve_asset_path(app, relative_path) else {
+        return false;
+    };
+    match std::fs::metadata(&path) {
+        Ok(meta) if meta.is_file() && meta.len() == asset.size_bytes => {}
+        _ => return false,
+    }
+    hash_file(&path).is_ok_and(|hash| hash == asset.content_hash)
+}
+
+/// Offset at which a staged transfer for `asset` can resume, or 0 when nothing usable
+/// was staged.
+fn resume_offset(
+    app: &AppHandle,
+    conn: &DbConnection,
+    asset: &sync_db::AssetRecord,
+) -> Result<u64, String> {
+    let progress = sync_db::load_asset_transfer(conn, &asset.content_hash)?;
+    let staged = staging_path(app, &asset.content_hash)?;
+    match reconcile_staging_file(&staged, asset.size_bytes, progress.as_ref())? {
+        Some(offset) => Ok(offset),
+        None => {
+            if progress.is_some() {
+                sync_db::delete_asset_transfer(conn, &asset.content_hash)?;
+            }
+            Ok(0)
+        }
+    }
+}
+
+/// Trims the staging file back to the recorded offset and returns it. Returns `None`
+/// and removes any leftover file when there is no usable progress, so a fresh transfer
+/// never appends to stale bytes.
+fn reconcile_staging_file(
+    staged: &Path,
+    expected_size: u64,
+    progress: Option<&sync_db::AssetTransferProgress>,
+) -> Result<Option<u64>, String> {
+    let staged_len = std::fs::metadata(staged).map(|meta| meta.len()).ok();
+    let usable = progress.filter(|progress| {
+        progress.total_size == expected_size
+            && progress.received_bytes <= expected_size
+            && staged_len.is_some_and(|len| len >= progress.received_bytes)
+    });
+    let Some(progress) = usable else {
+        if staged_len.is_some() {
+            std::fs::remove_file(staged)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+        return Ok(None);
+    };
+
+    if staged_len != Some(progress.received_bytes) {
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(staged)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        file.set_len(progress.received_bytes)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    Ok(Some(progress.received_bytes))
+}
+
+/// Writes `data` at `offset` in the staging file, dropping anything already staged
+/// past that point.
+fn write_staged_chunk(staged: &Path, offset: u64, data: &[u8]) -> Result<(), String> {
+    use std::io::{Seek, Write};
+
+    if let Some(parent) = staged.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(offset == 0)
+        .open(staged)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let staged_len = file
+        .metadata()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .len();
+    if staged_len < offset {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Staged asset holds {} bytes, cannot resume at offset {}",
+                staged_len, offset
+            ),
+        ));
+    }
+    if staged_len > offset {
+        file.set_len(offset)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    file.seek(std::io::SeekFrom::Start(offset))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    file.write_all(data)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    file.sync_data()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+/// Decides, for every asset upsert in a batch, whether its bytes are needed and from
+/// which offset. Content already on disk (under any path) and repeated hashes within
+/// the batch are requested once at most.
+pub fn build_transfer_plan(
+    app: &AppHandle,
+    conn: &DbConnection,
+    changes: &[ChangeRecord],
+) -> Result<AssetBatchPlan, String> {
+    let cutoff = crate::storage_manager::db::now_ms() as i64 - STALE_TRANSFER_MS;
+    for content_hash in sync_db::prune_asset_transfers(conn, cutoff)? {
+        if let Ok(path) = staging_path(app, &content_hash) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    let hash_index = sync_db::load_asset_hash_index(conn)?;
+    let mut plan = AssetBatchPlan::default();
+    let mut requested_hashes = HashSet::new();
+
+    for change in changes {
+        if change.op != ChangeOp::Upsert {
+            continue;
+        }
+        let asset: sync_db::AssetRecord = bincode::deserialize(&change.payload)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+        let local_source = if plan.local_sources.contains_key(&asset.content_hash) {
+            plan.local_sources.get(&asset.content_hash).cloned()
+        } else {
+            [
+                Some(asset.path.clone()),
+                hash_index.get(&asset.content_hash).cloned(),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|candidate| local_copy_matches(app, candidate, &asset))
+        };
+
+        let state = if let Some(source) = local_source {
+            plan.local_sources
+                .insert(asset.content_hash.clone(), source);
+            AssetTransferState::Present
+        } else if !requested_hashes.insert(asset.content_hash.clone()) {
+            AssetTransferState::Present
+        } else {
+            AssetTransferState::Resume {
+                offset: resume_offset(app, conn, &asset)?,
+            }
+        };
+
+        if state == AssetTransferState::Present {
+            plan.present_entity_ids.insert(change.entity_id.clone());
+        }
+        plan.requests.push(AssetTransferRequest {
+            entity_id: change.entity_id.clone(),
+            content_hash: asset.content_hash,
+            state,
+        });
+    }
+
+    Ok(plan)
+}
+
+/// Result of appending a verified chunk to a staged asset.
+pub struct ReceivedChunk {
+    pub next_offset: u64,
+    pub complete: bool,
+}
+
+/// Verifies one chunk against its hash and the expected offset, writes it to the
+/// staging file and records the new offset. Once the last chunk arrives the whole
+/// file is checked against `content_hash` and moved to `path`.
+pub fn receive_asset_chunk(
+    app: &AppHandle,
+    conn: &DbConnection,
+    path: &str,
+    content_hash: &str,
+    total_size: u64,
+    offset: u64,
+    chunk_hash: &str,
+    data: &[u8],
+) -> Result<ReceivedChunk, String> {
+    let actual_chunk_hash = blake3::hash(data).to_hex().to_string();
+    if actual_chunk_hash != chunk_hash {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Received corrupted chunk for {} at offset {}: expected {}, got {}",
+                path, offset, chunk_hash, actual_chunk_hash
+            ),
+        ));
+    }
+
+    let received_bytes = sync_db::load_asset_transfer(conn, content_hash)?
+        .map(|progress| progress.received_bytes)
+        .unwrap_or(0);
+    if offset != received_bytes {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Out-of-order chunk for {}: expected offset {}, got {}",
+                path, received_bytes, offset
+            ),
+        ));
+    }
+    let next_offset = offset + data.len() as u64;
+    if next_offset > total_size {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Chunk for {} overruns declared size {} (ends at {})",
+                path, total_size, next_offset
+            ),
+        ));
+    }
+
+    let staged = staging_path(app, content_hash)?;
+    write_staged_chunk(&staged, offset, data)?;
+
+    sync_db::record_asset_transfer(
+        conn,
+        content_hash,
+        &sync_db::AssetTransferProgress {
+            path: path.to_string(),
+            total_size,
+            received_bytes: next_offset,
+        },
+    )?;
+
+    if next_offset < total_size {
+        return Ok(ReceivedChunk {
+            next_offset,
+            complete: false,
+        });
+    }
+
+    let actual_hash = hash_file(&staged)?;
+    if actual_hash != content_hash {
+        sync_db::delete_asset_transfer(conn, content_hash)?;
+        let _ = std::fs::remove_file(&staged);
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Received corrupted asset content for {}: expected {}, got {}",
+                path, content_hash, actual_hash
+            ),
+        ));
+    }
+
+    let target = resolve_asset_path(app, path)?;
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    if std::fs::rename(&staged, &target).is_err() {
+        // Staging and target may live on different volumes (generated_images/).
+        std::fs::copy(&staged, &target)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let _ = std::fs::remove_file(&staged);
+    }
+    sync_db::delete_asset_transfer(conn, content_hash)?;
+
+    Ok(ReceivedChunk {
+        next_offset,
+        complete: true,
+    })
+}
+
+/// Copies deduplicated content into place for entities the plan marked as present.
+/// `received_sources` holds files written earlier in the same batch, keyed by hash.
+pub fn materialize_present_assets(
+    app: &AppHandle,
+    plan: &AssetBatchPlan,
+    expected_paths: &HashMap<String, (String, String)>,
+    received_sources: &HashMap<String, String>,
+) -> Result<(), String> {
+    for entity_id in &plan.present_entity_ids {
+        let Some((path, content_hash)) = expected_paths.get(entity_id) else {
+            continue;
+        };
+        let source = plan
+            .local_sources
+            .get(content_hash)
+            .or_else(|| received_sources.get(content_hash))
+            .ok_or_else(|| {
+                crate::utils::err_msg(
+                    module_path!(),
+                    line!(),
+                    format!("No local source for deduplicated asset {}", path),
+                )
+            })?;
+        if source == path {
+            continue;
+        }
+
+        let source_path = resolve_asset_path(app, source)?;
+        let target_path = resolve_asset_path(app, path)?;
+        if let Some(parent) = target_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        }
+        std::fs::copy(&source_path, &target_path)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    Ok(())
+}
+
+async fn expect_chunk_ack(
+    framed: &mut Framed<TcpStream, P2PCodec>,
+    content_hash: &str,
+    expected_offset: u64,
+) -> Result<(), String> {
+    match framed.next().await {
+        Some(Ok(P2PMessage::AssetChunkAck {
+            content_hash: acked_hash,
+            next_offset,
+        })) if acked_hash == content_hash && next_offset == expected_offset => Ok(()),
+        Some(Ok(P2PMessage::AssetChunkAck {
+            content_hash: acked_hash,
+            next_offset,
+        })) => Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!(
+                "Unexpected chunk ack {}@{}, expected {}@{}",
+                acked_hash, next_offset, content_hash, expected_offset
+            ),
+        )),
+        Some(Ok(P2PMessage::Error(message))) => Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Receiver rejected asset chunk: {}", message),
+        )),
+        Some(Ok(other)) => Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Expected AssetChunkAck, got {:?}", other),
+        )),
+        Some(Err(e)) => Err(crate::utils::err_to_string(module_path!(), line!(), e)),
+        None => Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            "Connection closed during asset transfer",
+        )),
+    }
+}
+
+/// Driver side of a chunked asset batch: waits for the receiver's transfer plan and
+/// streams only the requested byte ranges, one acknowledged chunk at a time.
+pub async fn send_asset_chunks(
+    app: &AppHandle,
+    framed: &mut Framed<TcpStream, P2PCodec>,
+    changes: &[ChangeRecord],
+) -> Result<(), String> {
+    let requests = match framed.next().await {
+        Some(Ok(P2PMessage::AssetTransferPlan { requests })) => requests,
+        Some(Ok(other)) => {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("Expected AssetTransferPlan, got {:?}", other),
+            ))
+        }
+        Some(Err(e)) => return Err(crate::utils::err_to_string(module_path!(), line!(), e)),
+        None => {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                "Connection closed before asset transfer plan",
+            ))
+        }
+    };
+
+    let mut assets = HashMap::new();
+    for change in changes {
+        if change.op != ChangeOp::Upsert {
+            continue;
+        }
+        let asset: sync_db::AssetRecord = bincode::deserialize(&change.payload)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        assets.insert(change.entity_id.clone(), asset);
+    }
+
+    let skipped = requests
+        .iter()
+        .filter(|request| request.state == AssetTransferState::Present)
+        .count();
+    if skipped > 0 {
+        log_info(
+            app,
+            "sync_driver",
+            format!(
+                "Receiver already has {} asset(s); skipping their content",
+                skipped
+            ),
+        );
+    }
+
+    for request in requests {
+        let AssetTransferState::Resume { offset } = request.state else {
+            continue;
+        };
+        let asset = assets.get(&request.entity_id).ok_or_else(|| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!(
+                    "Transfer plan requested unknown asset {}",
+                    request.entity_id
+                ),
+            )
+        })?;
+        if asset.content_hash != request.content_hash {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!(
+                    "Transfer plan hash mismatch for {}: expected {}, got {}",
+                    asset.path, asset.content_hash, request.content_hash
+                ),
+            ));
+        }
+
+        let absolute_path = resolve_asset_path(app, &asset.path)?;
+        if !absolute_path.exists() {
+            continue;
+        }
+        let actual_hash = hash_file(&absolute_path)?;
+        if actual_hash != asset.content_hash {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!(
+                    "Asset {} changed during sync preparation; expected hash {}, found {}",
+                    asset.path, asset.content_hash, actual_hash
+                ),
+            ));
+        }
+
+        let mut file = tokio::fs::File::open(&absolute_path)
+            .await
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let total_size = file
+            .metadata()
+            .await
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+            .len();
+        let mut next_offset = if offset > total_size {
+            log_warn(
+                app,
+                "sync_driver",
+                format!(
+                    "Resume offset {} beyond size {} for {}; restarting",
+                    offset, total_size, asset.path
+                ),
+            );
+            0
+        } else {
+            offset
+        };
+        if next_offset > 0 {
+            log_info(
+                app,
+                "sync_driver",
+                format!("Resuming asset {} at byte {}", asset.path, next_offset),
+            );
+        }
+        file.seek(std::io::SeekFrom::Start(next_offset))
+            .await
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+        // Always send at least one chunk so empty files complete on the receiver.
+        loop {
+            let mut data = Vec::new();
+            (&mut file)
+                .take(ASSET_CHUNK_SIZE.min(total_size - next_offset))
+                .read_to_end(&mut data)
+                .await
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            if data.is_empty() && next_offset < total_size {
+                return Err(crate::utils::err_msg(
+                    module_path!(),
+                    line!(),
+                    format!("Asset {} was truncated during transfer", asset.path),
+                ));
+            }
+            let chunk_offset = next_offset;
+            next_offset += data.len() as u64;
+
+            framed
+                .send(P2PMessage::AssetChunk {
+                    entity_id: request.entity_id.clone(),
+                    path: asset.path.clone(),
+                    content_hash: asset.content_hash.clone(),
+                    total_size,
+                    offset: chunk_offset,
+                    chunk_hash: blake3::hash(&data).to_hex().to_string(),
+                    data,
+                })
+                .await
+                .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+            expect_chunk_ack(framed, &asset.content_hash, next_offset).await?;
+
+            if next_offset >= total_size {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("lettuce-assets-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("asset.part")
+    }
+
+    fn progress(total_size: u64, received_bytes: u64) -> sync_db::AssetTransferProgress {
+        sync_db::AssetTransferProgress {
+            path: "avatars/a.png".into(),
+            total_size,
+            received_bytes,
+        }
+    }
+
+    #[test]
+    fn resumes_at_the_recorded_offset() {
+        let staged = scratch_file("resume");
+        std::fs::write(&staged, b"abcd").unwrap();
+        let offset = reconcile_staging_file(&staged, 8, Some(&progress(8, 4))).unwrap();
+        assert_eq!(offset, Some(4));
+
+        write_staged_chunk(&staged, 4, b"efgh").unwrap();
+        assert_eq!(std::fs::read(&staged).unwrap(), b"abcdefgh");
+    }
+
+    #[test]
+    fn trims_bytes_written_after_the_last_recorded_offset() {
+        let staged = scratch_file("trim");
+        std::fs::write(&staged, b"abcdXY").unwrap();
+        let offset = reconcile_staging_file(&staged, 8, Some(&progress(8, 4))).unwrap();
+        assert_eq!(offset, Some(4));
+        assert_eq!(std::fs::read(&staged).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn stale_staging_file_without_progress_is_discarded() {
+        let staged = scratch_file("stale");
+        std::fs::write(&staged, b"old bytes").unwrap();
+        assert_eq!(reconcile_staging_file(&staged, 8, None).unwrap(), None);
+        assert!(!staged.exists());
+
+        std::fs::write(&staged, b"old bytes").unwrap();
+        write_staged_chunk(&staged, 0, b"new").unwrap();
+        assert_eq!(std::fs::read(&staged).unwrap(), b"new");
+    }
+
+    #[test]
+    fn mismatched_progress_restarts_the_transfer() {
+        let staged = scratch_file("mismatch");
+        std::fs::write(&staged, b"ab").unwrap();
+        assert_eq!(
+            reconcile_staging_file(&staged, 8, Some(&progress(8, 4))).unwrap(),
+            None
+        );
+        assert!(!staged.exists());
+        assert!(write_staged_chunk(&staged, 4, b"efgh").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tauri::Manager;
@@ -6,10 +6,11 @@ use tauri::Manager;
 use crate::storage_manager::db::DbConnection;
 use crate::storage_manager::memory_embeddings::SessionKind;
 use crate::sync::models::{
-    AudioProvider, Character, CharacterRule, ChatTemplate, ChatTemplateMessage, GroupMessage,
-    GroupMessageVariant, GroupParticipation, GroupSession, Message, MessageVariant, MetaEntry,
-    Model, Persona, PromptTemplate, ProviderCredential, Scene, SceneVariant, Secret, Session,
-    Settings, SyncLorebook, SyncLorebookEntry, UsageMetadata, UsageRecord, UserVoice,
+    AudioProvider, Character, CharacterRule, ChatTemplate, ChatTemplateMessage,
+    CompanionRelationship, CompanionRelationshipPoint, GroupMessage, GroupMessageVariant,
+    GroupParticipation, GroupSession, Message, MessageVariant, MetaEntry, Model, Persona,
+    PromptTemplate, ProviderCredential, Scene, SceneVariant, Secret, Session, Settings,
+    SyncLorebook, SyncLorebookEntry, UsageMetadata, UsageRecord, UserVoice,
 };
 use crate::sync::protocol::{ChangeOp, ChangeRecord, CursorSet, DomainCursor, SyncDomain};
 use crate::utils::{log_error_global, log_info_global};
@@ -82,6 +83,8 @@ struct CharactersSnapshot {
     scene_variants: Vec<SceneVariant>,
     chat_templates: Vec<ChatTemplate>,
     chat_template_messages: Vec<ChatTemplateMessage>,
+    companion_relationships: Vec<CompanionRelationship>,
+    companion_relationship_points: Vec<CompanionRelationshipPoint>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -629,6 +632,26 @@ fn collect_current_entity_records(
             item,
         )?;
     }
+    let (companion_relationships, companion_relationship_points) =
+        fetch_companion_relationships(conn)?;
+    for item in &companion_relationships {
+        push_entity_record(
+            &mut records,
+            SyncDomain::Characters,
+            "companion_relationship",
+            item.character_id.clone(),
+            item,
+        )?;
+    }
+    for item in &companion_relationship_points {
+        push_entity_record(
+            &mut records,
+            SyncDomain::Characters,
+            "companion_relationship_point",
+            item.id.clone(),
+            item,
+        )?;
+    }
 
     let group_characters = fetch_group_configs(conn)?;
     let group_session_ids = collect_text_ids(conn, "SELECT id FROM group_sessions")?;
@@ -1429,6 +1452,8 @@ fn materialize_domain_heads(conn: &mut DbConnection, domain: SyncDomain) -> Resu
                 scene_variants: Vec::new(),
                 chat_templates: Vec::new(),
                 chat_template_messages: Vec::new(),
+                companion_relationships: Vec::new(),
+                companion_relationship_points: Vec::new(),
             };
             for (key, head) in domain_heads {
                 match key.entity_type.as_str() {
@@ -1440,6 +1465,12 @@ fn materialize_domain_heads(conn: &mut DbConnection, domain: SyncDomain) -> Resu
                     "chat_template_message" => snapshot
                         .chat_template_messages
                         .push(deserialize_head(&key, &head)?),
+                    "companion_relationship" => snapshot
+                        .companion_relationships
+                        .push(deserialize_head(&key, &head)?),
+                    "companion_relationship_point" => snapshot
+                        .companion_relationship_points
+                        .push(deserialize_head(&key, &head)?),
                     _ => {}
                 }
             }
@@ -1876,10 +1907,20 @@ fn apply_core_snapshot(conn: &mut DbConnection, payload: &[u8]) -> Result<(), St
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     }
 
+    // Upsert rather than replace so the device-local key pool (`api_keys`) survives.
     for credential in snapshot.provider_credentials {
         tx.execute(
-            r#"INSERT OR REPLACE INTO provider_credentials (id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config)
-               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+            r#"INSERT INTO provider_credentials (id, provider_id, label, api_key_ref, api_key, base_url, default_model, headers, config)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+               ON CONFLICT(id) DO UPDATE SET
+                 provider_id = excluded.provider_id,
+                 label = excluded.label,
+                 api_key_ref = excluded.api_key_ref,
+                 api_key = excluded.api_key,
+                 base_url = excluded.base_url,
+                 default_model = excluded.default_model,
+                 headers = excluded.headers,
+                 config = excluded.config"#,
             params![
                 credential.id,
                 credential.provider_id,
@@ -2100,6 +2141,8 @@ fn apply_characters_snapshot(conn: &mut DbConnection, payload: &[u8]) -> Result<
         "scene_variants",
         "scenes",
         "character_rules",
+        "companion_relationship_history",
+        "companion_relationships",
     ] {
         tx.execute(&format!("DELETE FROM {}", table), [])
             .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
@@ -2145,6 +2188,22 @@ fn apply_characters_snapshot(conn: &mut DbConnection, payload: &[u8]) -> Result<
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
     }
 
+    for ledger in snapshot.companion_relationships {
+        tx.execute(
+            "INSERT OR REPLACE INTO companion_relationships (character_id, closeness, trust, affection, tension, stability, interaction_count, last_interaction_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![ledger.character_id, ledger.closeness, ledger.trust, ledger.affection, ledger.tension, ledger.stability, ledger.interaction_count, ledger.last_interaction_at, ledger.updated_at],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+
+    for point in snapshot.companion_relationship_points {
+        tx.execute(
+            "INSERT OR REPLACE INTO companion_relationship_history (id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![point.id, point.character_id, point.session_id, point.source, point.closeness, point.trust, point.affection, point.tension, point.stability, point.created_at, point.updated_at],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+
     tx.commit()
         .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
 }
@@ -3104,6 +3163,56 @@ fn fetch_characters_data(
     Ok((chars, rules, scenes, variants, templates, template_messages))
 }
 
+fn fetch_companion_relationships(
+    conn: &DbConnection,
+) -> Result<(Vec<CompanionRelationship>, Vec<CompanionRelationshipPoint>), String> {
+    let mut stmt = conn
+        .prepare("SELECT character_id, closeness, trust, affection, tension, stability, interaction_count, last_interaction_at, updated_at FROM companion_relationships")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let ledgers = stmt
+        .query_map([], |r| {
+            Ok(CompanionRelationship {
+                character_id: r.get(0)?,
+                closeness: r.get(1)?,
+                trust: r.get(2)?,
+                affection: r.get(3)?,
+                tension: r.get(4)?,
+                stability: r.get(5)?,
+                interaction_count: r.get(6)?,
+                last_interaction_at: r.get(7)?,
+                updated_at: r.get(8)?,
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, character_id, session_id, source, closeness, trust, affection, tension, stability, created_at, updated_at FROM companion_relationship_history")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let points = stmt
+        .query_map([], |r| {
+            Ok(CompanionRelationshipPoint {
+                id: r.get(0)?,
+                character_id: r.get(1)?,
+                session_id: r.get(2)?,
+                source: r.get(3)?,
+                closeness: r.get(4)?,
+                trust: r.get(5)?,
+                affection: r.get(6)?,
+                tension: r.get(7)?,
+                stability: r.get(8)?,
+                created_at: r.get(9)?,
+                updated_at: r.get(10)?,
+            })
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    Ok((ledgers, points))
+}
+
 fn fetch_sessions_data(
     conn: &DbConnection,
     ids: &[String],
@@ -3548,3 +3657,110 @@ pub fn scan_for_missing_files(conn: &DbConnection, app_handle: &tauri::AppHandle
     missing.dedup();
     missing
 }
+
+/// Maps content hashes to the relative path of a live asset that already holds
+/// that content, so identical files are not transferred again under another path.
+pub fn load_asset_hash_index(conn: &DbConnection) -> Result<HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT payload FROM sync_entity_heads
+             WHERE domain = ?1 AND entity_type = 'asset' AND deleted = 0",
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let rows = stmt
+        .query_map(params![sync_domain_name(SyncDomain::Assets)], |row| {
+            row.get::<_, Vec<u8>>(0)
+        })
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let mut index = HashMap::new();
+    for row in rows {
+        let payload = row.map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        if let Ok(asset) = bincode::deserialize::<AssetRecord>(&payload) {
+            index.entry(asset.content_hash).or_insert(asset.path);
+        }
+    }
+    Ok(index)
+}
+
+/// Progress of a partially received asset, keyed by content hash.
+pub struct AssetTransferProgress {
+    pub path: String,
+    pub total_size: u64,
+    pub received_bytes: u64,
+}
+
+pub fn load_asset_transfer(
+    conn: &DbConnection,
+    content_hash: &str,
+) -> Result<Option<AssetTransferProgress>, String> {
+    conn.query_row(
+        "SELECT path, total_size, received_bytes FROM sync_asset_transfers WHERE content_hash = ?1",
+        params![content_hash],
+        |row| {
+            Ok(AssetTransferProgress {
+                path: row.get(0)?,
+                total_size: row.get::<_, i64>(1)? as u64,
+                received_bytes: row.get::<_, i64>(2)? as u64,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+pub fn record_asset_transfer(
+    conn: &DbConnection,
+    content_hash: &str,
+    progress: &AssetTransferProgress,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO sync_asset_transfers (content_hash, path, total_size, received_bytes, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(content_hash) DO UPDATE SET
+           path = excluded.path,
+           total_size = excluded.total_size,
+           received_bytes = excluded.received_bytes,
+           updated_at = excluded.updated_at",
+        params![
+            content_hash,
+            progress.path,
+            progress.total_size as i64,
+            progress.received_bytes as i64,
+            crate::storage_manager::db::now_ms() as i64
+        ],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+pub fn delete_asset_transfer(conn: &DbConnection, content_hash: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM sync_asset_transfers WHERE content_hash = ?1",
+        params![content_hash],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(())
+}
+
+/// Removes transfer records not touched since `older_than_ms` and returns their hashes
+/// so the caller can drop the matching staging files.
+pub fn prune_asset_transfers(
+    conn: &DbConnection,
+    older_than_ms: i64,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT content_hash FROM sync_asset_transfers WHERE updated_at < ?1")
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let hashes = stmt
+        .query_map(params![older_than_ms], |row| row.get::<_, String>(0))
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    conn.execute(
+        "DELETE FROM sync_asset_transfers WHERE updated_at < ?1",
+        params![older_than_ms],
+    )
+    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(hashes)
+}
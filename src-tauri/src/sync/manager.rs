@@ -12,12 +12,15 @@ use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, Mutex, RwLock};
 use tokio_util::codec::Framed;
 
+use crate::sync::assets::{
+    self, remove_asset_path, resolve_asset_path, write_asset_path, CHUNKED_ASSETS_PROTOCOL_VERSION,
+};
 use crate::sync::codec::P2PCodec;
 use crate::sync::db as sync_db;
 use crate::sync::protocol::{ChangeOp, P2PMessage, SyncDomain};
 use crate::utils::{log_error, log_info, log_warn};
 
-const PROTOCOL_VERSION: u32 = 9;
+const PROTOCOL_VERSION: u32 = 10;
 
 struct PendingAssetFile {
     path: String,
@@ -29,6 +32,9 @@ struct PendingAssetBatch {
     expected_files: HashMap<String, PendingAssetFile>,
     received_entity_ids: HashSet<String>,
     last_change_id: i64,
+    // Chunked transfers only: what the plan skipped, and where completed content landed.
+    plan: Option<assets::AssetBatchPlan>,
+    received_sources: HashMap<String, String>,
 }
 
 fn derive_key(pin: &str, salt: &[u8]) -> [u8; 32] {
@@ -548,7 +554,11 @@ async fn handle_advertise_cursors(
             .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
 
         if cursor.domain == SyncDomain::Assets {
-            send_asset_change_contents(app, framed, &changes).await?;
+            if peer_protocol_version >= CHUNKED_ASSETS_PROTOCOL_VERSION {
+                assets::send_asset_chunks(app, framed, &changes).await?;
+            } else {
+                send_asset_change_contents(app, framed, &changes).await?;
+            }
             let last_change_id = changes.last().map(|change| change.change_id).unwrap_or(0);
             framed
                 .send(P2PMessage::AssetBatchComplete { last_change_id })
@@ -864,11 +874,28 @@ async fn run_passenger_session(
                                 );
                             }
 
+                            let mut received_entity_ids = HashSet::new();
+                            let plan = if driver_protocol_version >= CHUNKED_ASSETS_PROTOCOL_VERSION {
+                                let plan = assets::build_transfer_plan(&app, &conn, &changes)?;
+                                framed
+                                    .send(P2PMessage::AssetTransferPlan {
+                                        requests: plan.requests.clone(),
+                                    })
+                                    .await
+                                    .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+                                received_entity_ids.extend(plan.present_entity_ids.iter().cloned());
+                                Some(plan)
+                            } else {
+                                None
+                            };
+
                             pending_asset_batch = Some(PendingAssetBatch {
                                 changes,
                                 expected_files,
-                                received_entity_ids: HashSet::new(),
+                                received_entity_ids,
                                 last_change_id,
+                                plan,
+                                received_sources: HashMap::new(),
                             });
                             continue;
                         }
@@ -935,6 +962,53 @@ async fn run_passenger_session(
                         write_asset_path(&app, &path, &content).await?;
                         pending_batch.received_entity_ids.insert(entity_id);
                     }
+                    Some(Ok(P2PMessage::AssetChunk { entity_id, path, content_hash, total_size, offset, chunk_hash, data })) => {
+                        let pending_batch = pending_asset_batch.as_mut().ok_or_else(|| {
+                            crate::utils::err_msg(
+                                module_path!(),
+                                line!(),
+                                format!("Received unexpected asset chunk for {}", path),
+                            )
+                        })?;
+                        let pending_file = pending_batch.expected_files.get(&entity_id).ok_or_else(|| {
+                            crate::utils::err_msg(
+                                module_path!(),
+                                line!(),
+                                format!("Received asset chunk for unknown entity {}", entity_id),
+                            )
+                        })?;
+                        if pending_file.path != path || pending_file.content_hash != content_hash {
+                            return Err(crate::utils::err_msg(
+                                module_path!(),
+                                line!(),
+                                format!(
+                                    "Asset chunk metadata mismatch for {}: expected {} ({}), got {} ({})",
+                                    entity_id, pending_file.path, pending_file.content_hash, path, content_hash
+                                ),
+                            ));
+                        }
+                        let received = match assets::receive_asset_chunk(
+                            &app, &conn, &path, &content_hash, total_size, offset, &chunk_hash, &data,
+                        ) {
+                            Ok(received) => received,
+                            Err(e) => {
+                                framed.send(P2PMessage::Error(e.clone())).await.ok();
+                                return Err(e);
+                            }
+                        };
+                        framed
+                            .send(P2PMessage::AssetChunkAck {
+                                content_hash: content_hash.clone(),
+                                next_offset: received.next_offset,
+                            })
+                            .await
+                            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+                        if received.complete {
+                            log_info(&app, "sync_passenger", format!("Received asset content: {}", path));
+                            pending_batch.received_sources.insert(content_hash, path);
+                            pending_batch.received_entity_ids.insert(entity_id);
+                        }
+                    }
                     Some(Ok(P2PMessage::AssetBatchComplete { last_change_id })) => {
                         let pending_batch = pending_asset_batch.take().ok_or_else(|| {
                             crate::utils::err_msg(
@@ -966,6 +1040,21 @@ async fn run_passenger_session(
                                 format!("Asset batch incomplete, missing entities: {}", missing_assets.join(", ")),
                             ));
                         }
+                        if let Some(plan) = &pending_batch.plan {
+                            let expected_paths = pending_batch
+                                .expected_files
+                                .iter()
+                                .map(|(entity_id, file)| {
+                                    (entity_id.clone(), (file.path.clone(), file.content_hash.clone()))
+                                })
+                                .collect::<HashMap<_, _>>();
+                            assets::materialize_present_assets(
+                                &app,
+                                plan,
+                                &expected_paths,
+                                &pending_batch.received_sources,
+                            )?;
+                        }
                         for change in &pending_batch.changes {
                             if change.op == ChangeOp::Delete {
                                 remove_asset_path(&app, &change.entity_id)?;
@@ -1074,67 +1163,6 @@ fn sync_status_text(domain: SyncDomain) -> &'static str {
     }
 }
 
-fn resolve_asset_path(app: &AppHandle, relative_path: &str) -> Result<std::path::PathBuf, String> {
-    if relative_path.contains("..")
-        || relative_path.starts_with('/')
-        || relative_path.contains('\\')
-    {
-        return Err(crate::utils::err_msg(
-            module_path!(),
-            line!(),
-            format!("Invalid asset path: {}", relative_path),
-        ));
-    }
-
-    if !relative_path.starts_with("avatars/")
-        && !relative_path.starts_with("sessions/")
-        && !relative_path.starts_with("images/")
-        && !relative_path.starts_with("generated_images/")
-    {
-        return Err(crate::utils::err_msg(
-            module_path!(),
-            line!(),
-            format!("Unauthorized asset path: {}", relative_path),
-        ));
-    }
-
-    if relative_path.starts_with("generated_images/") {
-        Ok(app
-            .path()
-            .app_data_dir()
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
-            .join(relative_path))
-    } else {
-        Ok(crate::storage_manager::legacy::storage_root(app)?.join(relative_path))
-    }
-}
-
-async fn write_asset_path(
-    app: &AppHandle,
-    relative_path: &str,
-    content: &[u8],
-) -> Result<(), String> {
-    let full_path = resolve_asset_path(app, relative_path)?;
-
-    if let Some(parent) = full_path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    }
-    tokio::fs::write(&full_path, content)
-        .await
-        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
-}
-
-fn remove_asset_path(app: &AppHandle, relative_path: &str) -> Result<(), String> {
-    let full_path = resolve_asset_path(app, relative_path)?;
-    if full_path.exists() {
-        std::fs::remove_file(&full_path)
-            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
-    }
-    Ok(())
-}
-
 async fn send_asset_change_contents(
     app: &AppHandle,
     framed: &mut Framed<TcpStream, P2PCodec>,
@@ -1,3 +1,4 @@
+pub mod assets;
 pub mod codec;
 pub mod commands;
 pub mod db;
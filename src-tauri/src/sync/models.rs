@@ -323,6 +323,34 @@ pub struct ChatTemplateMessage {
     pub content: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanionRelationship {
+    pub character_id: String,
+    pub closeness: f64,
+    pub trust: f64,
+    pub affection: f64,
+    pub tension: f64,
+    pub stability: f64,
+    pub interaction_count: i64,
+    pub last_interaction_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanionRelationshipPoint {
+    pub id: String,
+    pub character_id: String,
+    pub session_id: Option<String>,
+    pub source: String,
+    pub closeness: f64,
+    pub trust: f64,
+    pub affection: f64,
+    pub tension: f64,
+    pub stability: f64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
 // Layer 4: Sessions
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +43,24 @@ pub struct ChangeRecord {
     pub payload: Vec<u8>,
 }
 
+/// Receiver-side state for one asset announced in an asset batch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AssetTransferState {
+    /// The receiver already holds a file with this content hash (possibly under
+    /// another path, or earlier in the same batch) and will copy it locally.
+    Present,
+    /// The receiver needs the content starting at `offset`. A non-zero offset
+    /// resumes an interrupted transfer from the last acknowledged chunk.
+    Resume { offset: u64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AssetTransferRequest {
+    pub entity_id: String,
+    pub content_hash: String,
+    pub state: AssetTransferState,
+}
+
 // 3. The Actual Messages over TCP
 #[derive(Serialize, Deserialize, Debug)]
 pub enum P2PMessage {
@@ -94,6 +112,24 @@ pub enum P2PMessage {
     StatusUpdate(String),
     Disconnect,
     Error(String),
+
+    // Chunked asset transfer (protocol v10+). Appended so older variant indices stay stable.
+    AssetTransferPlan {
+        requests: Vec<AssetTransferRequest>,
+    },
+    AssetChunk {
+        entity_id: String,
+        path: String,
+        content_hash: String,
+        total_size: u64,
+        offset: u64,
+        chunk_hash: String,
+        data: Vec<u8>,
+    },
+    AssetChunkAck {
+        content_hash: String,
+        next_offset: u64,
+    },
 }
 
 fn default_protocol_version() -> u32 {
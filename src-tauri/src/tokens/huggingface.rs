@@ -0,0 +1,145 @@
+//! `tokenizer.json` files fetched from HuggingFace and cached under the app
+//! data directory, one per repository.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use tauri::AppHandle;
+use tokenizers::Tokenizer;
+
+use crate::utils::{log_info, log_warn};
+
+const HF_RESOLVE_BASE: &str = "https://huggingface.co";
+
+lazy_static! {
+    static ref LOADED: Mutex<HashMap<String, Arc<Tokenizer>>> = Mutex::new(HashMap::new());
+    static ref DOWNLOADS_IN_FLIGHT: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+fn tokenizer_path(app: &AppHandle, repo: &str) -> Result<PathBuf, String> {
+    let file_name = format!("{}.json", repo.replace(['/', '\\', ':'], "__"));
+    Ok(crate::utils::lettuce_dir(app)?
+        .join("models")
+        .join("tokenizers")
+        .join(file_name))
+}
+
+/// Tokenizer for `repo` if an earlier lookup already loaded it.
+pub fn loaded_tokenizer(repo: &str) -> Option<Arc<Tokenizer>> {
+    LOADED.lock().ok()?.get(repo).cloned()
+}
+
+/// Tokenizer for `repo` if it is already on disk; never touches the network.
+pub fn cached_tokenizer(app: &AppHandle, repo: &str) -> Option<Arc<Tokenizer>> {
+    if let Some(tokenizer) = LOADED.lock().ok()?.get(repo) {
+        return Some(tokenizer.clone());
+    }
+    let path = tokenizer_path(app, repo).ok()?;
+    if !path.exists() {
+        return None;
+    }
+    match Tokenizer::from_file(&path) {
+        Ok(tokenizer) => {
+            let tokenizer = Arc::new(tokenizer);
+            if let Ok(mut loaded) = LOADED.lock() {
+                loaded.insert(repo.to_string(), tokenizer.clone());
+            }
+            Some(tokenizer)
+        }
+        Err(err) => {
+            log_warn(
+                app,
+                "tokens",
+                format!("discarding unreadable tokenizer {:?}: {}", path, err),
+            );
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+/// Downloads the tokenizer for `repo` unless it is cached or already coming.
+pub fn prefetch(app: &AppHandle, repo: &str) {
+    let app = app.clone();
+    let repo = repo.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = ensure_tokenizer(&app, &repo).await {
+            log_warn(
+                &app,
+                "tokens",
+                format!("tokenizer download failed for {}: {}", repo, err),
+            );
+        }
+    });
+}
+
+pub async fn ensure_tokenizer(app: &AppHandle, repo: &str) -> Result<(), String> {
+    let path = tokenizer_path(app, repo)?;
+    if path.exists() {
+        return Ok(());
+    }
+    {
+        let mut in_flight = DOWNLOADS_IN_FLIGHT
+            .lock()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        if !in_flight.insert(repo.to_string()) {
+            return Ok(());
+        }
+    }
+    let result = download(app, repo, &path).await;
+    if let Ok(mut in_flight) = DOWNLOADS_IN_FLIGHT.lock() {
+        in_flight.remove(repo);
+    }
+    result
+}
+
+async fn download(app: &AppHandle, repo: &str, path: &Path) -> Result<(), String> {
+    let url = format!("{}/{}/resolve/main/tokenizer.json", HF_RESOLVE_BASE, repo);
+    let builder = reqwest::Client::builder()
+        .user_agent("LettuceAI/1.0")
+        .redirect(reqwest::redirect::Policy::limited(10));
+    let client = crate::transport::network::configure(builder, None)?
+        .build()
+        .map_err(|e| crate::utils::err_msg(module_path!(), line!(), e.to_string()))?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    if !response.status().is_success() {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Failed to fetch {}: HTTP {}", url, response.status()),
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Tokenizer::from_bytes(&bytes).map_err(|e| {
+        crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("Invalid tokenizer.json from {}: {}", repo, e),
+        )
+    })?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    let partial = path.with_extension("json.part");
+    std::fs::write(&partial, &bytes)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    std::fs::rename(&partial, path)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    log_info(
+        app,
+        "tokens",
+        format!("cached tokenizer for {} ({} bytes)", repo, bytes.len()),
+    );
+    Ok(())
+}
@@ -1,7 +1,14 @@
 use std::sync::OnceLock;
 
+use tauri::AppHandle;
 use tiktoken_rs::{o200k_base, CoreBPE};
 
+mod huggingface;
+mod provider;
+pub mod registry;
+
+pub use registry::{counter_for_model, counter_for_model_id, TokenCounter};
+
 static TOKENIZER: OnceLock<Option<CoreBPE>> = OnceLock::new();
 
 fn tokenizer() -> Result<&'static CoreBPE, String> {
@@ -11,11 +18,41 @@ fn tokenizer() -> Result<&'static CoreBPE, String> {
         .ok_or_else(|| "Failed to initialize o200k tokenizer".to_string())
 }
 
+/// Counts with the tokenizer of `model_id`, or of the default model when
+/// omitted. Falls back to o200k when no model is configured.
 #[tauri::command]
-pub fn tokens_count_batch(texts: Vec<String>) -> Result<Vec<u32>, String> {
-    let bpe = tokenizer()?;
-    Ok(texts
-        .iter()
-        .map(|t| bpe.encode_ordinary(t).len() as u32)
-        .collect())
+pub async fn tokens_count_batch(
+    app: AppHandle,
+    texts: Vec<String>,
+    model_id: Option<String>,
+) -> Result<Vec<u32>, String> {
+    let settings = crate::chat_manager::storage::load_settings(&app).ok();
+    let model = settings.as_ref().and_then(|settings| {
+        model_id
+            .as_deref()
+            .or(settings.default_model_id.as_deref())
+            .and_then(|id| settings.models.iter().find(|model| model.id == id))
+    });
+    match (settings.as_ref(), model) {
+        (Some(settings), Some(model)) => {
+            let counter = registry::counter_for_model_ready(&app, settings, model).await;
+            Ok(counter.count_batch(&texts))
+        }
+        _ => {
+            let bpe = tokenizer()?;
+            Ok(texts
+                .iter()
+                .map(|t| bpe.encode_ordinary(t).len() as u32)
+                .collect())
+        }
+    }
+}
+
+/// Token count used for request budgeting. Falls back to roughly four
+/// characters per token when the tokenizer cannot be loaded.
+pub fn count_text(text: &str) -> u32 {
+    match tokenizer() {
+        Ok(bpe) => bpe.encode_ordinary(text).len() as u32,
+        Err(_) => (text.chars().count() as u32).div_ceil(4),
+    }
 }
@@ -0,0 +1,213 @@
+//! Calibrates o200k counts against providers that expose a count-tokens
+//! endpoint. Budgeting runs on every message, so rather than one request per
+//! count each model gets a ratio measured once per run.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use super::count_text;
+use crate::chat_manager::service::require_api_key;
+use crate::chat_manager::types::{Model, ProviderCredential, ProviderId};
+use crate::providers::util::{build_headers, resolve_base_url};
+use crate::utils::log_info;
+
+/// Dialogue-heavy prose, close to what the app actually sends.
+const CALIBRATION_SAMPLE: &str = "\"You're late again,\" Mara said, not looking up from the ledger. \
+The lantern light caught the ink still drying on the page. \
+*He shrugged off his coat, shaking rain onto the floorboards.* \
+\"The bridge road flooded. I took the long way past the old mill, and you wouldn't believe who I saw there.\" \
+She finally raised her eyes, one brow arched. \"Try me.\" \
+The clock on the mantel ticked twice before he answered, lowering his voice as if the walls might listen. \
+\"Your brother. With three men I've never seen in this town, loading crates onto a wagon at half past midnight.\" \
+Mara closed the ledger slowly. Numbers, dates, debts: all of it could wait. \
+\"Then we don't have until morning,\" she said, already reaching for her boots.";
+
+lazy_static! {
+    static ref RATIOS: Mutex<HashMap<String, f32>> = Mutex::new(HashMap::new());
+    static ref CALIBRATING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    /// Models whose calibration failed; background retries would only repeat
+    /// the same error on every message.
+    static ref FAILED: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+pub fn supports_count_endpoint(provider_id: &str) -> bool {
+    matches!(provider_id, "anthropic" | "gemini")
+}
+
+pub fn calibrated_ratio(model_id: &str) -> Option<f32> {
+    RATIOS.lock().ok()?.get(model_id).copied()
+}
+
+pub fn calibrate_in_background(app: &AppHandle, credential: ProviderCredential, model: Model) {
+    if FAILED
+        .lock()
+        .map(|failed| failed.contains(&model.id))
+        .unwrap_or(true)
+    {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = calibrate(&app, &credential, &model).await {
+            crate::utils::log_warn(
+                &app,
+                "tokens",
+                format!("token count calibration failed for {}: {}", model.name, err),
+            );
+        }
+    });
+}
+
+pub async fn calibrate(
+    app: &AppHandle,
+    credential: &ProviderCredential,
+    model: &Model,
+) -> Result<(), String> {
+    if calibrated_ratio(&model.id).is_some() {
+        return Ok(());
+    }
+    {
+        let mut calibrating = CALIBRATING
+            .lock()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        if !calibrating.insert(model.id.clone()) {
+            return Ok(());
+        }
+    }
+    let result = measure_ratio(app, credential, model).await;
+    if let Ok(mut calibrating) = CALIBRATING.lock() {
+        calibrating.remove(&model.id);
+    }
+    let ratio = match result {
+        Ok(ratio) => ratio,
+        Err(err) => {
+            if let Ok(mut failed) = FAILED.lock() {
+                failed.insert(model.id.clone());
+            }
+            return Err(err);
+        }
+    };
+    if let Ok(mut failed) = FAILED.lock() {
+        failed.remove(&model.id);
+    }
+    if let Ok(mut ratios) = RATIOS.lock() {
+        ratios.insert(model.id.clone(), ratio);
+    }
+    log_info(
+        app,
+        "tokens",
+        format!(
+            "calibrated {} token counts at {:.2}x o200k",
+            model.name, ratio
+        ),
+    );
+    Ok(())
+}
+
+async fn measure_ratio(
+    app: &AppHandle,
+    credential: &ProviderCredential,
+    model: &Model,
+) -> Result<f32, String> {
+    let api_key = require_api_key(app, credential, "tokens")?;
+    let provider_id = ProviderId(credential.provider_id.clone());
+    let base_url = resolve_base_url(
+        app,
+        &provider_id,
+        credential.base_url.clone(),
+        Some(&credential.id),
+    )?;
+    let headers = build_headers(&provider_id, &api_key)?;
+    let client = crate::transport::network::client_for(Some(credential.id.as_str()))?;
+
+    // The endpoints count the message envelope too; a one-word request
+    // measures that overhead so it can be taken back out.
+    let sample = count_endpoint(
+        &client,
+        &provider_id,
+        &base_url,
+        &headers,
+        model,
+        CALIBRATION_SAMPLE,
+    )
+    .await?;
+    let baseline = count_endpoint(&client, &provider_id, &base_url, &headers, model, "a").await?;
+    let measured = sample.saturating_sub(baseline) + 1;
+    let reference = count_text(CALIBRATION_SAMPLE).max(1);
+    Ok((measured as f32 / reference as f32).clamp(0.5, 3.0))
+}
+
+async fn count_endpoint(
+    client: &reqwest::Client,
+    provider_id: &ProviderId,
+    base_url: &str,
+    headers: &reqwest::header::HeaderMap,
+    model: &Model,
+    text: &str,
+) -> Result<u32, String> {
+    let base = base_url.trim_end_matches('/');
+    let (url, body, field) = match provider_id.0.as_str() {
+        "anthropic" => {
+            let base = base.trim_end_matches("/v1");
+            (
+                format!("{}/v1/messages/count_tokens", base),
+                json!({
+                    "model": model.name,
+                    "messages": [{ "role": "user", "content": text }],
+                }),
+                "input_tokens",
+            )
+        }
+        "gemini" => (
+            format!(
+                "{}/models/{}:countTokens",
+                base,
+                model.name.trim_start_matches("models/")
+            ),
+            json!({ "contents": [{ "role": "user", "parts": [{ "text": text }] }] }),
+            "totalTokens",
+        ),
+        other => {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("{} has no count-tokens endpoint", other),
+            ))
+        }
+    };
+
+    let response = client
+        .post(&url)
+        .headers(headers.clone())
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let status = response.status();
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    if !status.is_success() {
+        return Err(crate::utils::err_msg(
+            module_path!(),
+            line!(),
+            format!("count-tokens request failed: HTTP {} {}", status, payload),
+        ));
+    }
+    payload
+        .get(field)
+        .and_then(Value::as_u64)
+        .map(|count| count as u32)
+        .ok_or_else(|| {
+            crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                format!("count-tokens response missing {}", field),
+            )
+        })
+}
@@ -0,0 +1,358 @@
+//! Picks the tokenizer each model is budgeted with: the GGUF vocabulary for
+//! local llama.cpp models, HuggingFace `tokenizer.json` files for known open
+//! models, a ratio calibrated against the provider's count-tokens endpoint,
+//! or a per-family estimate on top of o200k.
+
+use std::sync::Arc;
+
+use tauri::AppHandle;
+use tokenizers::Tokenizer;
+
+use super::{count_text, huggingface, provider, tokenizer};
+use crate::chat_manager::storage::resolve_credential_for_model;
+use crate::chat_manager::types::{Model, Settings};
+use crate::utils::log_warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerKind {
+    Gguf,
+    HuggingFace,
+    Provider,
+    O200k,
+    Estimate,
+}
+
+impl TokenizerKind {
+    /// Parses the `tokenizer` model setting; `auto` and unknown values resolve
+    /// from the provider and model name instead.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "gguf" => Some(Self::Gguf),
+            "huggingFace" => Some(Self::HuggingFace),
+            "provider" => Some(Self::Provider),
+            "o200k" => Some(Self::O200k),
+            "estimate" => Some(Self::Estimate),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFamily {
+    Gpt,
+    Claude,
+    Gemini,
+    Gemma,
+    Llama3,
+    Llama2,
+    Mistral,
+    Qwen,
+    DeepSeek,
+    Phi,
+    Unknown,
+}
+
+impl ModelFamily {
+    pub fn detect(model_name: &str) -> Self {
+        let name = model_name.to_ascii_lowercase();
+        // Local models are stored by path; only the file name says anything.
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(&name);
+        if name.contains("claude") {
+            Self::Claude
+        } else if name.contains("gemini") {
+            Self::Gemini
+        } else if name.contains("gemma") {
+            Self::Gemma
+        } else if name.contains("llama-2") || name.contains("llama2") {
+            Self::Llama2
+        } else if name.contains("llama") {
+            Self::Llama3
+        } else if ["mistral", "mixtral", "ministral", "codestral", "magistral"]
+            .iter()
+            .any(|marker| name.contains(marker))
+        {
+            Self::Mistral
+        } else if name.contains("qwen") || name.contains("qwq") {
+            Self::Qwen
+        } else if name.contains("deepseek") {
+            Self::DeepSeek
+        } else if name.starts_with("phi") || name.contains("-phi") {
+            Self::Phi
+        } else if name.starts_with("gpt")
+            || name.starts_with("chatgpt")
+            || ["o1", "o3", "o4"]
+                .iter()
+                .any(|prefix| name == *prefix || name.starts_with(&format!("{prefix}-")))
+        {
+            Self::Gpt
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Public, ungated repository carrying the family's `tokenizer.json`.
+    pub fn huggingface_repo(self) -> Option<&'static str> {
+        match self {
+            Self::Llama3 => Some("unsloth/Llama-3.1-8B-Instruct"),
+            Self::Mistral => Some("unsloth/mistral-7b-instruct-v0.3"),
+            Self::Gemma => Some("unsloth/gemma-2-9b-it"),
+            Self::Qwen => Some("Qwen/Qwen2.5-7B-Instruct"),
+            Self::DeepSeek => Some("deepseek-ai/DeepSeek-V3"),
+            Self::Phi => Some("microsoft/Phi-3-mini-4k-instruct"),
+            _ => None,
+        }
+    }
+
+    /// Rough tokens per o200k token on mixed English prose and dialogue.
+    pub fn estimate_ratio(self) -> f32 {
+        match self {
+            Self::Gpt => 1.0,
+            Self::Claude => 1.2,
+            Self::Gemini | Self::Gemma => 1.05,
+            Self::Llama3 | Self::Qwen | Self::DeepSeek => 1.05,
+            Self::Llama2 | Self::Phi => 1.3,
+            Self::Mistral => 1.25,
+            Self::Unknown => 1.1,
+        }
+    }
+}
+
+/// Tokenizer a model uses unless its settings override it.
+pub fn default_kind(provider_id: &str, family: ModelFamily) -> TokenizerKind {
+    if provider_id.eq_ignore_ascii_case("llamacpp") {
+        return TokenizerKind::Gguf;
+    }
+    if provider::supports_count_endpoint(provider_id) {
+        return TokenizerKind::Provider;
+    }
+    match family {
+        ModelFamily::Gpt => TokenizerKind::O200k,
+        family if family.huggingface_repo().is_some() => TokenizerKind::HuggingFace,
+        _ => TokenizerKind::Estimate,
+    }
+}
+
+#[derive(Clone)]
+pub enum TokenCounter {
+    O200k,
+    HuggingFace(Arc<Tokenizer>),
+    Gguf(String),
+    Scaled(f32),
+}
+
+impl TokenCounter {
+    pub fn count(&self, text: &str) -> u32 {
+        if text.is_empty() {
+            return 0;
+        }
+        match self {
+            Self::O200k => count_text(text),
+            Self::HuggingFace(tokenizer) => match tokenizer.encode(text, false) {
+                Ok(encoding) => encoding.get_ids().len() as u32,
+                Err(_) => count_text(text),
+            },
+            Self::Gguf(path) => match crate::llama_cpp::count_gguf_tokens(path, &[text]) {
+                Ok(counts) => counts.first().copied().unwrap_or(0),
+                Err(_) => scale(count_text(text), ModelFamily::detect(path).estimate_ratio()),
+            },
+            Self::Scaled(ratio) => scale(count_text(text), *ratio),
+        }
+    }
+
+    /// Token ids for `text`, where the tokenizer exposes its vocabulary.
+    pub fn encode(&self, text: &str) -> Option<Vec<u32>> {
+        match self {
+            Self::O200k => tokenizer().ok().map(|bpe| bpe.encode_ordinary(text)),
+            Self::HuggingFace(tokenizer) => tokenizer
+                .encode(text, false)
+                .ok()
+                .map(|encoding| encoding.get_ids().to_vec()),
+            Self::Gguf(_) | Self::Scaled(_) => None,
+        }
+    }
+
+    pub fn count_batch(&self, texts: &[String]) -> Vec<u32> {
+        if let Self::Gguf(path) = self {
+            let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+            if let Ok(counts) = crate::llama_cpp::count_gguf_tokens(path, &refs) {
+                return counts;
+            }
+        }
+        texts.iter().map(|text| self.count(text)).collect()
+    }
+}
+
+fn scale(tokens: u32, ratio: f32) -> u32 {
+    (tokens as f32 * ratio).ceil() as u32
+}
+
+struct Resolved<'a> {
+    kind: TokenizerKind,
+    family: ModelFamily,
+    repo: Option<&'a str>,
+}
+
+fn resolve(model: &Model) -> Resolved<'_> {
+    let advanced = model.advanced_model_settings.as_ref();
+    let family = ModelFamily::detect(&model.name);
+    let kind = advanced
+        .and_then(|settings| settings.tokenizer.as_deref())
+        .and_then(TokenizerKind::parse)
+        .unwrap_or_else(|| default_kind(&model.provider_id, family));
+    let repo = advanced
+        .and_then(|settings| settings.tokenizer_repo.as_deref())
+        .map(str::trim)
+        .filter(|repo| !repo.is_empty())
+        .or_else(|| family.huggingface_repo());
+    Resolved { kind, family, repo }
+}
+
+/// Counter for budgeting requests to `model`. Never blocks on the network:
+/// tokenizer downloads and provider calibration run in the background and
+/// the family estimate is used until they land.
+pub fn counter_for_model(app: &AppHandle, settings: &Settings, model: &Model) -> TokenCounter {
+    let resolved = resolve(model);
+    let fallback = TokenCounter::Scaled(resolved.family.estimate_ratio());
+    match resolved.kind {
+        TokenizerKind::O200k => TokenCounter::O200k,
+        TokenizerKind::Estimate => fallback,
+        TokenizerKind::Gguf => TokenCounter::Gguf(model.name.clone()),
+        TokenizerKind::HuggingFace => {
+            let Some(repo) = resolved.repo else {
+                return fallback;
+            };
+            match huggingface::cached_tokenizer(app, repo) {
+                Some(tokenizer) => TokenCounter::HuggingFace(tokenizer),
+                None => {
+                    huggingface::prefetch(app, repo);
+                    fallback
+                }
+            }
+        }
+        TokenizerKind::Provider => match provider::calibrated_ratio(&model.id) {
+            Some(ratio) => TokenCounter::Scaled(ratio),
+            None => {
+                if let Some(credential) = resolve_credential_for_model(settings, model) {
+                    provider::calibrate_in_background(app, credential.clone(), model.clone());
+                }
+                fallback
+            }
+        },
+    }
+}
+
+/// Counter able to turn text into token ids for `model`, if its tokenizer is
+/// already in memory. Never touches the disk or the network.
+pub fn loaded_encoder(model: &Model) -> Option<TokenCounter> {
+    let resolved = resolve(model);
+    match resolved.kind {
+        TokenizerKind::O200k => Some(TokenCounter::O200k),
+        TokenizerKind::HuggingFace => {
+            huggingface::loaded_tokenizer(resolved.repo?).map(TokenCounter::HuggingFace)
+        }
+        _ => None,
+    }
+}
+
+/// Like [`counter_for_model`], but waits for the tokenizer download or the
+/// provider calibration instead of estimating.
+pub async fn counter_for_model_ready(
+    app: &AppHandle,
+    settings: &Settings,
+    model: &Model,
+) -> TokenCounter {
+    let resolved = resolve(model);
+    match (resolved.kind, resolved.repo) {
+        (TokenizerKind::HuggingFace, Some(repo)) => {
+            if let Err(err) = huggingface::ensure_tokenizer(app, repo).await {
+                log_warn(
+                    app,
+                    "tokens",
+                    format!("tokenizer download failed for {}: {}", repo, err),
+                );
+            }
+        }
+        (TokenizerKind::Provider, _) => {
+            if let Some(credential) = resolve_credential_for_model(settings, model) {
+                if let Err(err) = provider::calibrate(app, credential, model).await {
+                    log_warn(
+                        app,
+                        "tokens",
+                        format!("token count calibration failed for {}: {}", model.name, err),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+    counter_for_model(app, settings, model)
+}
+
+/// Counter for the model a character chats with, or o200k when none is set.
+pub fn counter_for_model_id(
+    app: &AppHandle,
+    settings: &Settings,
+    model_id: Option<&str>,
+) -> TokenCounter {
+    model_id
+        .or(settings.default_model_id.as_deref())
+        .and_then(|id| settings.models.iter().find(|model| model.id == id))
+        .map(|model| counter_for_model(app, settings, model))
+        .unwrap_or(TokenCounter::O200k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_families_from_remote_and_local_names() {
+        assert_eq!(
+            ModelFamily::detect("claude-3-5-sonnet-latest"),
+            ModelFamily::Claude
+        );
+        assert_eq!(
+            ModelFamily::detect("meta-llama/llama-3.1-70b-instruct"),
+            ModelFamily::Llama3
+        );
+        assert_eq!(
+            ModelFamily::detect("/models/Mistral-Nemo-Instruct-Q4_K_M.gguf"),
+            ModelFamily::Mistral
+        );
+        assert_eq!(ModelFamily::detect("gemma-2-9b-it"), ModelFamily::Gemma);
+        assert_eq!(ModelFamily::detect("gpt-4o-mini"), ModelFamily::Gpt);
+        assert_eq!(ModelFamily::detect("o3-mini"), ModelFamily::Gpt);
+        assert_eq!(ModelFamily::detect("Phi-3-mini.gguf"), ModelFamily::Phi);
+        assert_eq!(ModelFamily::detect("dolphin"), ModelFamily::Unknown);
+    }
+
+    #[test]
+    fn default_kind_prefers_model_native_tokenizers() {
+        assert_eq!(
+            default_kind("llamacpp", ModelFamily::Unknown),
+            TokenizerKind::Gguf
+        );
+        assert_eq!(
+            default_kind("anthropic", ModelFamily::Claude),
+            TokenizerKind::Provider
+        );
+        assert_eq!(
+            default_kind("openrouter", ModelFamily::Claude),
+            TokenizerKind::Estimate
+        );
+        assert_eq!(
+            default_kind("openrouter", ModelFamily::Qwen),
+            TokenizerKind::HuggingFace
+        );
+        assert_eq!(
+            default_kind("openai", ModelFamily::Gpt),
+            TokenizerKind::O200k
+        );
+    }
+
+    #[test]
+    fn scaled_counts_round_up() {
+        assert_eq!(scale(10, 1.25), 13);
+        assert_eq!(scale(0, 1.3), 0);
+    }
+}
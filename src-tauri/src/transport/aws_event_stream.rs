@@ -0,0 +1,177 @@
+//! Decoder for the `application/vnd.amazon.eventstream` binary framing used
+//! by Bedrock's streaming APIs.
+//!
+//! Each message is: total length (u32 BE), headers length (u32 BE), prelude
+//! CRC32, headers, payload, message CRC32. Chunks from the network rarely
+//! line up with messages, so bytes are buffered until a full frame arrived.
+
+use std::collections::HashMap;
+
+const PRELUDE_LEN: usize = 12;
+const MIN_MESSAGE_LEN: usize = PRELUDE_LEN + 4;
+/// Bedrock caps a single event at well under this; anything larger means
+/// the stream is out of sync.
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStreamMessage {
+    pub headers: HashMap<String, String>,
+    pub payload: Vec<u8>,
+}
+
+impl EventStreamMessage {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Default)]
+pub struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers `chunk` and returns every message completed by it.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<EventStreamMessage>, String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut out = Vec::new();
+        loop {
+            if self.buffer.len() < PRELUDE_LEN {
+                break;
+            }
+            let total_len = read_u32(&self.buffer[0..4]) as usize;
+            if !(MIN_MESSAGE_LEN..=MAX_MESSAGE_LEN).contains(&total_len) {
+                return Err(format!("invalid event-stream message length {}", total_len));
+            }
+            if self.buffer.len() < total_len {
+                break;
+            }
+            let frame: Vec<u8> = self.buffer.drain(..total_len).collect();
+            out.push(decode_message(&frame)?);
+        }
+        Ok(out)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn decode_message(frame: &[u8]) -> Result<EventStreamMessage, String> {
+    let total_len = frame.len();
+    let headers_len = read_u32(&frame[4..8]) as usize;
+    if crc32fast::hash(&frame[..8]) != read_u32(&frame[8..12]) {
+        return Err("event-stream prelude checksum mismatch".to_string());
+    }
+    if crc32fast::hash(&frame[..total_len - 4]) != read_u32(&frame[total_len - 4..]) {
+        return Err("event-stream message checksum mismatch".to_string());
+    }
+    let headers_end = PRELUDE_LEN + headers_len;
+    if headers_end > total_len - 4 {
+        return Err("event-stream headers overrun the message".to_string());
+    }
+    Ok(EventStreamMessage {
+        headers: decode_headers(&frame[PRELUDE_LEN..headers_end])?,
+        payload: frame[headers_end..total_len - 4].to_vec(),
+    })
+}
+
+/// Only string headers carry anything Bedrock clients need (`:event-type`,
+/// `:message-type`, `:exception-type`); other value types are skipped.
+fn decode_headers(mut bytes: &[u8]) -> Result<HashMap<String, String>, String> {
+    let truncated = || "truncated event-stream header".to_string();
+    let mut headers = HashMap::new();
+    while !bytes.is_empty() {
+        let name_len = bytes[0] as usize;
+        let name = bytes.get(1..1 + name_len).ok_or_else(truncated)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        bytes = &bytes[1 + name_len..];
+        let value_type = *bytes.first().ok_or_else(truncated)?;
+        bytes = &bytes[1..];
+        let fixed_len = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let len_bytes = bytes.get(..2).ok_or_else(truncated)?;
+                let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+                let value = bytes.get(2..2 + len).ok_or_else(truncated)?;
+                if value_type == 7 {
+                    headers.insert(name, String::from_utf8_lossy(value).into_owned());
+                }
+                bytes = &bytes[2 + len..];
+                continue;
+            }
+            other => return Err(format!("unknown event-stream header type {}", other)),
+        };
+        bytes = bytes.get(fixed_len..).ok_or_else(truncated)?;
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+pub(crate) fn encode_message(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut header_bytes = Vec::new();
+    for (name, value) in headers {
+        header_bytes.push(name.len() as u8);
+        header_bytes.extend_from_slice(name.as_bytes());
+        header_bytes.push(7);
+        header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header_bytes.extend_from_slice(value.as_bytes());
+    }
+    let total_len = PRELUDE_LEN + header_bytes.len() + payload.len() + 4;
+    let mut frame = Vec::with_capacity(total_len);
+    frame.extend_from_slice(&(total_len as u32).to_be_bytes());
+    frame.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    let prelude_crc = crc32fast::hash(&frame);
+    frame.extend_from_slice(&prelude_crc.to_be_bytes());
+    frame.extend_from_slice(&header_bytes);
+    frame.extend_from_slice(payload);
+    let message_crc = crc32fast::hash(&frame);
+    frame.extend_from_slice(&message_crc.to_be_bytes());
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_messages_split_across_chunks() {
+        let mut bytes = encode_message(
+            &[
+                (":event-type", "contentBlockDelta"),
+                (":message-type", "event"),
+            ],
+            br#"{"contentBlockIndex":0,"delta":{"text":"Hi"}}"#,
+        );
+        bytes.extend(encode_message(
+            &[(":event-type", "messageStop")],
+            br#"{"stopReason":"end_turn"}"#,
+        ));
+
+        let mut decoder = EventStreamDecoder::new();
+        let first = decoder.feed(&bytes[..20]).unwrap();
+        assert!(first.is_empty());
+        let rest = decoder.feed(&bytes[20..]).unwrap();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].header(":event-type"), Some("contentBlockDelta"));
+        assert_eq!(rest[0].header(":message-type"), Some("event"));
+        assert_eq!(rest[1].payload, br#"{"stopReason":"end_turn"}"#.to_vec());
+    }
+
+    #[test]
+    fn rejects_corrupted_messages() {
+        let mut bytes = encode_message(&[(":event-type", "messageStart")], b"{}");
+        let last = bytes.len() - 5;
+        bytes[last] ^= 0xff;
+        assert!(EventStreamDecoder::new().feed(&bytes).is_err());
+    }
+}
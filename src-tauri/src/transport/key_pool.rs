@@ -0,0 +1,526 @@
+//! Key pools for provider credentials that carry more than one API key.
+//!
+//! `select_key` hands out keys round-robin or least-used and skips keys that
+//! are cooling down after a 429 or whose quota looks exhausted.
+//! `send_with_retries` finds the key of the request's credential pool in an
+//! outgoing request, reports rate limits against it and swaps in another key
+//! before retrying; SigV4-signed requests are left alone. Pools live in
+//! memory; request counts persist through `usage::repository`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use reqwest::header::HeaderValue;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::chat_manager::types::ProviderCredential;
+use crate::storage_manager::db::now_ms;
+use crate::usage::tracking::ProviderKeyEvent;
+use crate::utils::log_warn;
+
+/// Id of the credential's own `api_key` inside its pool.
+pub const PRIMARY_KEY_ID: &str = "primary";
+/// Cooldown after a 429 that carried no usable `Retry-After`.
+const DEFAULT_COOLDOWN_MS: u64 = 60 * 1000;
+/// Cooldown for a key whose quota or billing limit was hit.
+const EXHAUSTED_COOLDOWN_MS: u64 = 6 * 60 * 60 * 1000;
+
+lazy_static! {
+    static ref KEY_POOLS: Mutex<HashMap<String, KeyPool>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySelection {
+    RoundRobin,
+    LeastUsed,
+}
+
+impl KeySelection {
+    /// Reads `keyRotation` from the credential config; round-robin by default.
+    pub fn from_config(config: Option<&Value>) -> Self {
+        match config
+            .and_then(|v| v.get("keyRotation"))
+            .and_then(|v| v.as_str())
+        {
+            Some("leastUsed") | Some("least_used") => Self::LeastUsed,
+            _ => Self::RoundRobin,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PooledKey {
+    id: String,
+    secret: String,
+    uses: u64,
+    cooldown_until: u64,
+    exhausted: bool,
+}
+
+impl PooledKey {
+    fn is_available(&self, now: u64) -> bool {
+        self.cooldown_until <= now
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyPool {
+    selection: KeySelection,
+    keys: Vec<PooledKey>,
+    cursor: usize,
+}
+
+/// Live state of one pooled key, for the usage screen.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStatus {
+    pub key_id: String,
+    pub cooldown_until: Option<u64>,
+    pub exhausted: bool,
+}
+
+impl KeyPool {
+    /// `keys` are `(id, secret)` pairs; `uses` seeds least-used selection.
+    pub fn new(
+        selection: KeySelection,
+        keys: Vec<(String, String)>,
+        uses: &HashMap<String, u64>,
+    ) -> Self {
+        Self {
+            selection,
+            keys: keys
+                .into_iter()
+                .map(|(id, secret)| PooledKey {
+                    uses: uses.get(&id).copied().unwrap_or(0),
+                    id,
+                    secret,
+                    cooldown_until: 0,
+                    exhausted: false,
+                })
+                .collect(),
+            cursor: 0,
+        }
+    }
+
+    /// Applies an edited key list, keeping runtime state for keys that stayed.
+    fn refresh(&mut self, selection: KeySelection, keys: Vec<(String, String)>) {
+        self.selection = selection;
+        let previous = std::mem::take(&mut self.keys);
+        self.keys = keys
+            .into_iter()
+            .map(|(id, secret)| {
+                match previous
+                    .iter()
+                    .find(|key| key.id == id && key.secret == secret)
+                {
+                    Some(existing) => existing.clone(),
+                    None => PooledKey {
+                        id,
+                        secret,
+                        uses: 0,
+                        cooldown_until: 0,
+                        exhausted: false,
+                    },
+                }
+            })
+            .collect();
+        if self.cursor >= self.keys.len() {
+            self.cursor = 0;
+        }
+    }
+
+    fn index_of(&self, secret: &str) -> Option<usize> {
+        self.keys.iter().position(|key| key.secret == secret)
+    }
+
+    /// Picks the next key and counts a use. When every key is cooling down,
+    /// the one that recovers first is returned so the caller can still wait
+    /// on it instead of failing outright.
+    pub fn select(&mut self, now: u64) -> Option<(String, String)> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let index = match self.selection {
+            KeySelection::RoundRobin => (0..self.keys.len())
+                .map(|offset| (self.cursor + offset) % self.keys.len())
+                .find(|&i| self.keys[i].is_available(now)),
+            KeySelection::LeastUsed => self
+                .keys
+                .iter()
+                .enumerate()
+                .filter(|(_, key)| key.is_available(now))
+                .min_by_key(|(_, key)| key.uses)
+                .map(|(i, _)| i),
+        }
+        .or_else(|| {
+            self.keys
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, key)| key.cooldown_until)
+                .map(|(i, _)| i)
+        })?;
+        self.cursor = (index + 1) % self.keys.len();
+        let key = &mut self.keys[index];
+        key.uses += 1;
+        if key.is_available(now) {
+            key.exhausted = false;
+        }
+        Some((key.id.clone(), key.secret.clone()))
+    }
+
+    /// Puts a key on cooldown; returns its id if it belongs to this pool.
+    pub fn mark_rate_limited(
+        &mut self,
+        secret: &str,
+        now: u64,
+        retry_after_ms: Option<u64>,
+        exhausted: bool,
+    ) -> Option<String> {
+        let index = self.index_of(secret)?;
+        let key = &mut self.keys[index];
+        let cooldown = if exhausted {
+            EXHAUSTED_COOLDOWN_MS
+        } else {
+            retry_after_ms.unwrap_or(DEFAULT_COOLDOWN_MS)
+        };
+        key.cooldown_until = key.cooldown_until.max(now + cooldown);
+        key.exhausted = key.exhausted || exhausted;
+        Some(key.id.clone())
+    }
+
+    fn has_other_available(&self, secret: &str, now: u64) -> bool {
+        self.keys
+            .iter()
+            .any(|key| key.secret != secret && key.is_available(now))
+    }
+
+    /// Another usable key than `secret`, picked by the pool's selection rule.
+    pub fn alternative(&mut self, secret: &str, now: u64) -> Option<(String, String)> {
+        if !self.has_other_available(secret, now) {
+            return None;
+        }
+        self.select(now).filter(|(_, next)| next != secret)
+    }
+
+    fn key_in_request(
+        &self,
+        request: &reqwest::Request,
+        credential_id: &str,
+    ) -> Option<RequestKey> {
+        self.keys
+            .iter()
+            .find(|key| request_contains(request, &key.secret))
+            .map(|key| RequestKey {
+                credential_id: credential_id.to_string(),
+                key_id: key.id.clone(),
+                secret: key.secret.clone(),
+            })
+    }
+
+    fn status(&self, now: u64) -> Vec<KeyStatus> {
+        self.keys
+            .iter()
+            .map(|key| KeyStatus {
+                key_id: key.id.clone(),
+                cooldown_until: (!key.is_available(now)).then_some(key.cooldown_until),
+                exhausted: key.exhausted && !key.is_available(now),
+            })
+            .collect()
+    }
+}
+
+/// The `(id, secret)` pairs a credential pools: its own key first, then the
+/// enabled extra keys, without blanks or duplicates.
+pub fn pooled_keys(credential: &ProviderCredential) -> Vec<(String, String)> {
+    let mut keys: Vec<(String, String)> = Vec::new();
+    if let Some(primary) = credential.api_key.as_deref().map(str::trim) {
+        if !primary.is_empty() {
+            keys.push((PRIMARY_KEY_ID.to_string(), primary.to_string()));
+        }
+    }
+    for extra in credential.api_keys.iter().filter(|key| key.enabled) {
+        let secret = extra.api_key.trim();
+        if !secret.is_empty() && !keys.iter().any(|(_, existing)| existing == secret) {
+            keys.push((extra.id.clone(), secret.to_string()));
+        }
+    }
+    keys
+}
+
+/// Picks a key for `credential`. Without a pool (fewer than two keys) this
+/// is its only key, if it has one.
+pub fn select_key(app: &AppHandle, credential: &ProviderCredential) -> Option<String> {
+    let keys = pooled_keys(credential);
+    if keys.len() < 2 {
+        if let Ok(mut pools) = KEY_POOLS.lock() {
+            pools.remove(&credential.id);
+        }
+        return keys.into_iter().next().map(|(_, secret)| secret);
+    }
+    let selection = KeySelection::from_config(credential.config.as_ref());
+    let known = KEY_POOLS.lock().ok()?.contains_key(&credential.id);
+    let uses = if known {
+        HashMap::new()
+    } else {
+        crate::usage::repository::provider_key_request_counts(app, &credential.id)
+            .unwrap_or_default()
+    };
+    let mut pools = KEY_POOLS.lock().ok()?;
+    let pool = pools
+        .entry(credential.id.clone())
+        .or_insert_with(|| KeyPool::new(selection, keys.clone(), &uses));
+    pool.refresh(selection, keys);
+    pool.select(now_ms()).map(|(_, secret)| secret)
+}
+
+/// A pooled key found in an outgoing request.
+#[derive(Debug, Clone)]
+pub struct RequestKey {
+    pub credential_id: String,
+    pub key_id: String,
+    pub secret: String,
+}
+
+fn request_contains(request: &reqwest::Request, secret: &str) -> bool {
+    request.url().as_str().contains(secret)
+        || request
+            .headers()
+            .values()
+            .filter_map(|value| value.to_str().ok())
+            .any(|value| value.contains(secret))
+}
+
+/// Whether `request` carries an AWS SigV4 signature. Swapping a key inside
+/// it would invalidate the signature, so such requests are never pooled.
+fn is_sigv4_signed(request: &reqwest::Request) -> bool {
+    request
+        .headers()
+        .get(reqwest::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256"))
+}
+
+/// Finds which key of `credential_id`'s pool, if any, authenticates `request`.
+pub fn key_in_request(request: &reqwest::Request, credential_id: &str) -> Option<RequestKey> {
+    if is_sigv4_signed(request) {
+        return None;
+    }
+    let pools = KEY_POOLS.lock().ok()?;
+    pools
+        .get(credential_id)?
+        .key_in_request(request, credential_id)
+}
+
+/// Whether the pool of `key` has another key that is not cooling down.
+pub fn has_alternative(key: &RequestKey) -> bool {
+    let now = now_ms();
+    KEY_POOLS
+        .lock()
+        .ok()
+        .and_then(|pools| {
+            pools
+                .get(&key.credential_id)
+                .map(|pool| pool.has_other_available(&key.secret, now))
+        })
+        .unwrap_or(false)
+}
+
+/// Replaces `key` in the request's headers and URL with the next usable key
+/// of its pool. Returns the new key, or `None` if the pool has no other.
+pub fn swap_key(request: &mut reqwest::Request, key: &RequestKey) -> Option<RequestKey> {
+    let (next_id, next_secret) = {
+        let mut pools = KEY_POOLS.lock().ok()?;
+        pools
+            .get_mut(&key.credential_id)?
+            .alternative(&key.secret, now_ms())?
+    };
+    for value in request.headers_mut().values_mut() {
+        let Ok(text) = value.to_str() else {
+            continue;
+        };
+        if text.contains(&key.secret) {
+            if let Ok(replaced) = HeaderValue::from_str(&text.replace(&key.secret, &next_secret)) {
+                *value = replaced;
+            }
+        }
+    }
+    if request.url().as_str().contains(&key.secret) {
+        if let Ok(url) =
+            reqwest::Url::parse(&request.url().as_str().replace(&key.secret, &next_secret))
+        {
+            *request.url_mut() = url;
+        }
+    }
+    Some(RequestKey {
+        credential_id: key.credential_id.clone(),
+        key_id: next_id,
+        secret: next_secret,
+    })
+}
+
+/// Records that `key` went out on a request.
+pub fn report_sent(app: &AppHandle, key: &RequestKey) {
+    record_event(app, key, ProviderKeyEvent::Request);
+}
+
+/// Cools `key` down after a 429 or a quota error.
+pub fn report_rate_limited(
+    app: &AppHandle,
+    key: &RequestKey,
+    retry_after_ms: Option<u64>,
+    exhausted: bool,
+) {
+    if let Ok(mut pools) = KEY_POOLS.lock() {
+        if let Some(pool) = pools.get_mut(&key.credential_id) {
+            pool.mark_rate_limited(&key.secret, now_ms(), retry_after_ms, exhausted);
+        }
+    }
+    record_event(
+        app,
+        key,
+        if exhausted {
+            ProviderKeyEvent::Exhausted
+        } else {
+            ProviderKeyEvent::RateLimited
+        },
+    );
+}
+
+fn record_event(app: &AppHandle, key: &RequestKey, event: ProviderKeyEvent) {
+    if let Err(err) = crate::usage::repository::record_provider_key_event(
+        app,
+        &key.credential_id,
+        &key.key_id,
+        event,
+    ) {
+        log_warn(
+            app,
+            "key_pool",
+            format!("failed to record key usage: {}", err),
+        );
+    }
+}
+
+/// Live cooldown state of a credential's keys; empty when it has no pool.
+pub fn key_status(credential_id: &str) -> Vec<KeyStatus> {
+    KEY_POOLS
+        .lock()
+        .ok()
+        .and_then(|pools| pools.get(credential_id).map(|pool| pool.status(now_ms())))
+        .unwrap_or_default()
+}
+
+/// Whether an error body says the key ran out of quota or credit rather than
+/// hitting a short-term rate limit.
+pub fn is_quota_exhausted(status: u16, body: &str) -> bool {
+    if status == 402 {
+        return true;
+    }
+    let body = body.to_ascii_lowercase();
+    [
+        "insufficient_quota",
+        "quota exceeded",
+        "exceeded your current quota",
+        "billing",
+        "credit balance",
+    ]
+    .iter()
+    .any(|marker| body.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(selection: KeySelection) -> KeyPool {
+        KeyPool::new(
+            selection,
+            vec![
+                ("primary".to_string(), "sk-a".to_string()),
+                ("k2".to_string(), "sk-b".to_string()),
+                ("k3".to_string(), "sk-c".to_string()),
+            ],
+            &HashMap::from([("primary".to_string(), 5), ("k2".to_string(), 1)]),
+        )
+    }
+
+    fn secret(selected: Option<(String, String)>) -> String {
+        selected.map(|(_, secret)| secret).unwrap_or_default()
+    }
+
+    #[test]
+    fn round_robin_skips_cooling_keys() {
+        let mut pool = pool(KeySelection::RoundRobin);
+        assert_eq!(secret(pool.select(0)), "sk-a");
+        assert_eq!(secret(pool.select(0)), "sk-b");
+
+        pool.mark_rate_limited("sk-c", 0, Some(30_000), false);
+        assert_eq!(secret(pool.select(1_000)), "sk-a");
+        assert_eq!(secret(pool.select(1_000)), "sk-b");
+        assert_eq!(secret(pool.select(31_000)), "sk-c");
+    }
+
+    #[test]
+    fn least_used_prefers_quiet_keys_and_falls_back_to_earliest_recovery() {
+        let mut pool = pool(KeySelection::LeastUsed);
+        assert_eq!(secret(pool.select(0)), "sk-c");
+        assert_eq!(secret(pool.select(0)), "sk-b");
+
+        pool.mark_rate_limited("sk-a", 0, Some(5_000), false);
+        pool.mark_rate_limited("sk-b", 0, None, false);
+        pool.mark_rate_limited("sk-c", 0, None, true);
+        assert_eq!(secret(pool.select(1_000)), "sk-a");
+        assert_eq!(pool.alternative("sk-a", 1_000), None);
+    }
+
+    fn request(authorization: &str) -> reqwest::Request {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::POST,
+            reqwest::Url::parse("https://api.example.com/v1/chat").unwrap(),
+        );
+        request.headers_mut().insert(
+            reqwest::header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        request
+    }
+
+    #[test]
+    fn finds_the_key_in_the_credentials_own_pool() {
+        let pool = pool(KeySelection::RoundRobin);
+        let key = pool
+            .key_in_request(&request("Bearer sk-b"), "cred-1")
+            .unwrap();
+        assert_eq!(key.credential_id, "cred-1");
+        assert_eq!(key.key_id, "k2");
+        assert!(pool
+            .key_in_request(&request("Bearer sk-other"), "cred-1")
+            .is_none());
+    }
+
+    #[test]
+    fn sigv4_requests_are_not_pooled() {
+        let signed = request(
+            "AWS4-HMAC-SHA256 Credential=sk-a/20240101/us-east-1/bedrock/aws4_request, \
+             SignedHeaders=host, Signature=abc",
+        );
+        assert!(is_sigv4_signed(&signed));
+        assert!(!is_sigv4_signed(&request("Bearer sk-a")));
+    }
+
+    #[test]
+    fn detects_quota_exhaustion() {
+        assert!(is_quota_exhausted(402, ""));
+        assert!(is_quota_exhausted(
+            429,
+            r#"{"error":{"code":"insufficient_quota","message":"You exceeded your current quota"}}"#
+        ));
+        assert!(!is_quota_exhausted(
+            429,
+            r#"{"error":"Rate limit reached"}"#
+        ));
+    }
+}
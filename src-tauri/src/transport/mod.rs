@@ -1,3 +1,9 @@
+pub mod aws_event_stream;
+pub mod key_pool;
+pub mod network;
+pub mod recorder;
+pub mod sigv4;
+
 use serde_json::{json, Value};
 use std::time::Duration;
 use tauri::Emitter;
@@ -8,9 +14,15 @@ use crate::error::AppError;
 use crate::utils::log_warn;
 
 pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+/// Key switches allowed per request on top of the regular retries.
+const MAX_KEY_SWAPS: u32 = 8;
 
-pub fn build_client(timeout_ms: Option<u64>, stream: bool) -> Result<reqwest::Client, AppError> {
-    let mut builder = reqwest::Client::builder();
+pub fn build_client(
+    credential_id: Option<&str>,
+    timeout_ms: Option<u64>,
+    stream: bool,
+) -> Result<reqwest::Client, AppError> {
+    let mut builder = network::configure(reqwest::Client::builder(), credential_id)?;
     let normalized_timeout_ms = timeout_ms
         .unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS)
         .min(DEFAULT_REQUEST_TIMEOUT_MS);
@@ -21,6 +33,25 @@ pub fn build_client(timeout_ms: Option<u64>, stream: bool) -> Result<reqwest::Cl
     builder.build().map_err(AppError::from)
 }
 
+/// Reloads the proxy, certificate and request recording settings; called
+/// whenever settings or credentials are written.
+pub fn refresh_settings(app: &tauri::AppHandle) {
+    let settings = match crate::storage_manager::settings::internal_read_settings(app) {
+        Ok(Some(raw)) => serde_json::from_str::<Value>(&raw).unwrap_or(Value::Null),
+        Ok(None) => Value::Null,
+        Err(err) => {
+            log_warn(
+                app,
+                "transport",
+                format!("failed to read transport settings: {}", err),
+            );
+            return;
+        }
+    };
+    network::apply(app, &settings);
+    recorder::apply(&settings);
+}
+
 pub fn emit_normalized(app: &tauri::AppHandle, request_id: &str, event: NormalizedEvent) {
     let channel = format!("api-normalized://{}", request_id);
     let payload = match &event {
@@ -68,23 +99,32 @@ pub async fn send_request(builder: reqwest::RequestBuilder) -> Result<reqwest::R
     builder.send().await.map_err(AppError::from)
 }
 
+/// Sends with retries on 429s and server errors. When `credential_id` has a
+/// key pool, rate-limited keys of that pool are swapped out between attempts.
 pub async fn send_with_retries(
     app: &tauri::AppHandle,
     scope: &str,
     builder: reqwest::RequestBuilder,
     max_retries: u32,
     request_id: Option<&str>,
+    credential_id: Option<&str>,
 ) -> Result<reqwest::Response, AppError> {
-    let base = match builder.try_clone() {
-        Some(b) => b,
-        None => return builder.send().await.map_err(AppError::from),
-    };
+    let (client, request) = builder.build_split();
+    let mut base = request.map_err(AppError::from)?;
+    if base.try_clone().is_none() {
+        return client.execute(base).await.map_err(AppError::from);
+    }
+    let mut pooled_key = credential_id.and_then(|id| key_pool::key_in_request(&base, id));
+    let mut key_swaps: u32 = 0;
     let mut attempt: u32 = 0;
     loop {
-        let attempt_builder = base
+        let attempt_request = base
             .try_clone()
-            .expect("reqwest::RequestBuilder should be clonable for retries");
-        let result = attempt_builder.send().await;
+            .expect("reqwest::Request should be clonable for retries");
+        if let Some(key) = pooled_key.as_ref() {
+            key_pool::report_sent(app, key);
+        }
+        let result = client.execute(attempt_request).await;
         match result {
             Ok(resp) => {
                 let status = resp.status();
@@ -97,19 +137,75 @@ pub async fn send_with_retries(
                     max_retries
                 };
 
+                // Honor Retry-After header for 429s when present; otherwise use exponential backoff.
+                let retry_after_ms = if is_rate_limited {
+                    resp.headers()
+                        .get("retry-after")
+                        .and_then(|h| h.to_str().ok())
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .map(|secs| secs * 1000)
+                } else {
+                    None
+                };
+
+                // A pooled key that hit its limit is cooled down and, when the
+                // pool has another key ready, swapped out without waiting.
+                if let Some(key) = pooled_key
+                    .clone()
+                    .filter(|_| is_rate_limited || status.as_u16() == 402)
+                {
+                    if key_swaps < MAX_KEY_SWAPS && key_pool::has_alternative(&key) {
+                        let body = resp.text().await.unwrap_or_default();
+                        let exhausted = key_pool::is_quota_exhausted(status.as_u16(), &body);
+                        key_pool::report_rate_limited(app, &key, retry_after_ms, exhausted);
+                        key_swaps += 1;
+                        match key_pool::swap_key(&mut base, &key) {
+                            Some(next) => {
+                                log_warn(
+                                    app,
+                                    scope,
+                                    format!(
+                                        "{} on key {} of credential {} - switching to key {}",
+                                        status, key.key_id, key.credential_id, next.key_id
+                                    ),
+                                );
+                                if let Some(request_id) = request_id {
+                                    crate::utils::emit_warn_event(
+                                        app,
+                                        "transport_key_rotated",
+                                        json!({
+                                            "requestId": request_id,
+                                            "scope": scope,
+                                            "status": status.as_u16(),
+                                            "credentialId": key.credential_id,
+                                            "fromKeyId": key.key_id,
+                                            "toKeyId": next.key_id,
+                                            "reason": if exhausted { "quota_exhausted" } else { "rate_limited" },
+                                        }),
+                                    );
+                                }
+                                pooled_key = Some(next);
+                            }
+                            None => {
+                                sleep(Duration::from_millis(
+                                    retry_after_ms.unwrap_or_else(|| backoff_delay_ms(key_swaps)),
+                                ))
+                                .await;
+                            }
+                        }
+                        continue;
+                    }
+                    key_pool::report_rate_limited(
+                        app,
+                        &key,
+                        retry_after_ms,
+                        status.as_u16() == 402,
+                    );
+                }
+
                 if (is_rate_limited || should_retry_status) && attempt < allowed_retries {
                     attempt += 1;
 
-                    // Honor Retry-After header for 429s when present; otherwise use exponential backoff.
-                    let retry_after_ms = if is_rate_limited {
-                        resp.headers()
-                            .get("retry-after")
-                            .and_then(|h| h.to_str().ok())
-                            .and_then(|s| s.parse::<u64>().ok())
-                            .map(|secs| secs * 1000)
-                    } else {
-                        None
-                    };
                     let delay = retry_after_ms.unwrap_or_else(|| backoff_delay_ms(attempt));
 
                     log_warn(
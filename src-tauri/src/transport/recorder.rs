@@ -0,0 +1,438 @@
+//! Opt-in recorder for provider traffic. With
+//! `advancedSettings.requestRecordingEnabled` set, every `api_request` that
+//! carries a request id is captured (redacted request, response headers and
+//! the raw stream chunks as they arrived) and written to a ring buffer of
+//! JSON files. Replaying a recording feeds the chunks back through
+//! `SseDecoder` with their original boundaries.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::api::{ApiRequest, ApiResponse};
+use crate::chat_manager::sse::SseDecoder;
+use crate::chat_manager::types::NormalizedEvent;
+use crate::utils::{ensure_lettuce_dir, log_info, log_warn, now_millis};
+
+/// Recordings kept on disk; the oldest are dropped first.
+const MAX_RECORDINGS: usize = 50;
+/// Stream bytes kept per recording.
+const MAX_STREAM_BYTES: usize = 8 * 1024 * 1024;
+const RECORDINGS_DIR: &str = "request_recordings";
+const REDACTED: &str = "***";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref ACTIVE: Mutex<HashMap<String, RequestRecording>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestRecording {
+    pub request_id: String,
+    pub provider_id: Option<String>,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub method: String,
+    pub url: String,
+    pub request_headers: BTreeMap<String, String>,
+    pub query: BTreeMap<String, Value>,
+    pub request_body: Option<Value>,
+    pub stream: bool,
+    pub status: Option<u16>,
+    pub response_headers: BTreeMap<String, String>,
+    /// Raw stream chunks, split exactly as they were received.
+    pub chunks: Vec<String>,
+    /// Parsed body of a non-streaming response.
+    pub response_body: Option<Value>,
+    /// Set when the stream outgrew `MAX_STREAM_BYTES`.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSummary {
+    pub request_id: String,
+    pub provider_id: Option<String>,
+    pub started_at: u64,
+    pub url: String,
+    pub stream: bool,
+    pub status: Option<u16>,
+    pub chunk_count: usize,
+    pub error: Option<String>,
+}
+
+impl From<&RequestRecording> for RecordingSummary {
+    fn from(recording: &RequestRecording) -> Self {
+        Self {
+            request_id: recording.request_id.clone(),
+            provider_id: recording.provider_id.clone(),
+            started_at: recording.started_at,
+            url: recording.url.clone(),
+            stream: recording.stream,
+            status: recording.status,
+            chunk_count: recording.chunks.len(),
+            error: recording.error.clone(),
+        }
+    }
+}
+
+/// Turns recording on or off from the stored settings.
+pub(super) fn apply(settings: &Value) {
+    let enabled = settings
+        .pointer("/advancedSettings/requestRecordingEnabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn is_sensitive_name(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    matches!(
+        lower.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie" | "key" | "sig" | "token"
+    ) || [
+        "api-key",
+        "api_key",
+        "apikey",
+        "secret",
+        "password",
+        "access_token",
+        "access-token",
+        "security-token",
+        "session-token",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker))
+}
+
+fn redact_map<V: Clone>(entries: &HashMap<String, V>, redacted: V) -> BTreeMap<String, V> {
+    entries
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_name(name) {
+                redacted.clone()
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    if parsed.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if is_sensitive_name(&name) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// Starts recording `req` when recording is enabled. Returns the request id
+/// to pass to [`finish`].
+pub fn start(req: &ApiRequest) -> Option<String> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let request_id = req.request_id.clone()?;
+    let recording = RequestRecording {
+        request_id: request_id.clone(),
+        provider_id: req.provider_id.clone(),
+        started_at: now_millis().unwrap_or_default(),
+        finished_at: None,
+        method: req.method.clone().unwrap_or_else(|| "POST".to_string()),
+        url: redact_url(&req.url),
+        request_headers: req
+            .headers
+            .as_ref()
+            .map(|headers| redact_map(headers, REDACTED.to_string()))
+            .unwrap_or_default(),
+        query: req
+            .query
+            .as_ref()
+            .map(|query| redact_map(query, Value::String(REDACTED.to_string())))
+            .unwrap_or_default(),
+        request_body: req.body.clone(),
+        stream: req.stream.unwrap_or(false),
+        status: None,
+        response_headers: BTreeMap::new(),
+        chunks: Vec::new(),
+        response_body: None,
+        truncated: false,
+        error: None,
+    };
+    if let Ok(mut active) = ACTIVE.lock() {
+        active.insert(request_id.clone(), recording);
+    }
+    Some(request_id)
+}
+
+/// Appends a stream chunk to the recording of `request_id`, if any.
+pub fn record_chunk(request_id: &str, chunk: &[u8]) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(mut active) = ACTIVE.lock() else {
+        return;
+    };
+    let Some(recording) = active.get_mut(request_id) else {
+        return;
+    };
+    let recorded: usize = recording.chunks.iter().map(String::len).sum();
+    if recorded + chunk.len() > MAX_STREAM_BYTES {
+        recording.truncated = true;
+        return;
+    }
+    recording
+        .chunks
+        .push(String::from_utf8_lossy(chunk).into_owned());
+}
+
+/// Completes the recording started for `request_id` and writes it to disk.
+pub fn finish(app: &AppHandle, request_id: Option<String>, result: &Result<ApiResponse, String>) {
+    let Some(request_id) = request_id else {
+        return;
+    };
+    let Some(mut recording) = ACTIVE
+        .lock()
+        .ok()
+        .and_then(|mut active| active.remove(&request_id))
+    else {
+        return;
+    };
+    recording.finished_at = now_millis().ok();
+    match result {
+        Ok(response) => {
+            recording.status = Some(response.status);
+            recording.response_headers = redact_map(&response.headers, REDACTED.to_string());
+            // Streams are kept as chunks; the joined text adds nothing.
+            if !recording.stream {
+                recording.response_body = Some(response.data.clone());
+            }
+        }
+        Err(err) => recording.error = Some(err.clone()),
+    }
+
+    match save(app, &recording) {
+        Ok(()) => log_info(
+            app,
+            "request_recorder",
+            format!(
+                "recorded request {} ({} chunks)",
+                request_id,
+                recording.chunks.len()
+            ),
+        ),
+        Err(err) => log_warn(
+            app,
+            "request_recorder",
+            format!("failed to save recording {}: {}", request_id, err),
+        ),
+    }
+}
+
+fn recordings_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = ensure_lettuce_dir(app)?.join(RECORDINGS_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    Ok(dir)
+}
+
+/// Recording files, oldest first.
+fn recording_files(app: &AppHandle) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(recordings_dir(app)?)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn save(app: &AppHandle, recording: &RequestRecording) -> Result<(), String> {
+    let file_name = format!(
+        "{:013}-{}.json",
+        recording.started_at,
+        sanitize_id(&recording.request_id)
+    );
+    let data = serde_json::to_vec_pretty(recording)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    fs::write(recordings_dir(app)?.join(file_name), data)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+
+    let files = recording_files(app)?;
+    let excess = files.len().saturating_sub(MAX_RECORDINGS);
+    for path in files.into_iter().take(excess) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+fn sanitize_id(request_id: &str) -> String {
+    request_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn read_recording(path: &Path) -> Result<RequestRecording, String> {
+    let data =
+        fs::read(path).map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    serde_json::from_slice(&data)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))
+}
+
+fn load_recording(app: &AppHandle, request_id: &str) -> Result<RequestRecording, String> {
+    let suffix = format!("-{}.json", sanitize_id(request_id));
+    let path = recording_files(app)?
+        .into_iter()
+        .rev()
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(&suffix))
+        })
+        .ok_or_else(|| format!("No recording for request {}", request_id))?;
+    read_recording(&path)
+}
+
+/// Decodes recorded chunks the same way the live stream was decoded.
+pub fn replay_chunks(chunks: &[String], provider_id: Option<&str>) -> Vec<NormalizedEvent> {
+    let mut decoder = SseDecoder::new();
+    chunks
+        .iter()
+        .flat_map(|chunk| decoder.feed(chunk, provider_id))
+        .collect()
+}
+
+#[tauri::command]
+pub fn request_recording_list(app: AppHandle) -> Result<Vec<RecordingSummary>, String> {
+    let mut summaries = Vec::new();
+    for path in recording_files(&app)?.iter().rev() {
+        match read_recording(path) {
+            Ok(recording) => summaries.push(RecordingSummary::from(&recording)),
+            Err(err) => log_warn(
+                &app,
+                "request_recorder",
+                format!("skipping unreadable recording {:?}: {}", path, err),
+            ),
+        }
+    }
+    Ok(summaries)
+}
+
+#[tauri::command]
+pub fn request_recording_get(
+    app: AppHandle,
+    request_id: String,
+) -> Result<RequestRecording, String> {
+    load_recording(&app, &request_id)
+}
+
+#[tauri::command]
+pub fn request_recording_replay(
+    app: AppHandle,
+    request_id: String,
+) -> Result<Vec<NormalizedEvent>, String> {
+    let recording = load_recording(&app, &request_id)?;
+    if !recording.stream {
+        return Err("Only streamed responses can be replayed".to_string());
+    }
+    Ok(replay_chunks(
+        &recording.chunks,
+        recording.provider_id.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub fn request_recording_clear(app: AppHandle) -> Result<(), String> {
+    for path in recording_files(&app)? {
+        fs::remove_file(path)
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_credentials_in_headers_and_urls() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer sk-123".to_string());
+        headers.insert("x-goog-api-key".to_string(), "abc".to_string());
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        let redacted = redact_map(&headers, REDACTED.to_string());
+        assert_eq!(redacted["Authorization"], REDACTED);
+        assert_eq!(redacted["x-goog-api-key"], REDACTED);
+        assert_eq!(redacted["Content-Type"], "application/json");
+
+        assert_eq!(
+            redact_url("https://example.com/v1beta/models?alt=sse&key=secret"),
+            "https://example.com/v1beta/models?alt=sse&key=***"
+        );
+        assert_eq!(
+            redact_url("https://api.openai.com/v1/chat/completions"),
+            "https://api.openai.com/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn replay_keeps_chunk_boundaries() {
+        // A thinking tag and an SSE line split across chunks, as they arrive
+        // from a real stream.
+        let chunks = vec![
+            "data: {\"choices\":[{\"delta\":{\"content\":\"<thi\"}}]}\n\ndata: {\"choi".to_string(),
+            "ces\":[{\"delta\":{\"content\":\"nk>plan</think>Hi\"}}]}\n\n".to_string(),
+            "data: [DONE]\n\n".to_string(),
+        ];
+        let events = replay_chunks(&chunks, Some("openai"));
+        let reasoning: String = events
+            .iter()
+            .filter_map(|event| match event {
+                NormalizedEvent::Reasoning { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let text: String = events
+            .iter()
+            .filter_map(|event| match event {
+                NormalizedEvent::Delta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reasoning, "plan");
+        assert_eq!(text, "Hi");
+        assert!(matches!(events.last(), Some(NormalizedEvent::Done)));
+    }
+}
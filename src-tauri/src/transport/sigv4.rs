@@ -0,0 +1,236 @@
+//! AWS Signature Version 4 request signing, used for Amazon Bedrock.
+//!
+//! Signs `host`, `x-amz-date` and, for temporary credentials,
+//! `x-amz-security-token`. The body must be signed as the exact bytes that
+//! will be sent.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+/// Adds `x-amz-date`, `x-amz-security-token` (when present) and the
+/// `Authorization` header for the request to `headers`.
+pub fn sign(
+    method: &str,
+    url: &str,
+    headers: &mut HashMap<String, String>,
+    body: &[u8],
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+    let host = match (parsed.host_str(), parsed.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => {
+            return Err(crate::utils::err_msg(
+                module_path!(),
+                line!(),
+                "cannot sign a URL without a host",
+            ))
+        }
+    };
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+
+    headers.retain(|key, _| {
+        !key.eq_ignore_ascii_case("authorization")
+            && !key.eq_ignore_ascii_case("x-amz-date")
+            && !key.eq_ignore_ascii_case("x-amz-security-token")
+    });
+    headers.insert("x-amz-date".into(), amz_date.clone());
+    if let Some(token) = credentials
+        .session_token
+        .as_deref()
+        .filter(|token| !token.is_empty())
+    {
+        headers.insert("x-amz-security-token".into(), token.to_string());
+    }
+
+    let mut signed: BTreeMap<String, String> = BTreeMap::new();
+    signed.insert("host".into(), host);
+    for (key, value) in headers.iter() {
+        let lower = key.to_ascii_lowercase();
+        if lower == "x-amz-date" || lower == "x-amz-security-token" {
+            signed.insert(lower, value.trim().to_string());
+        }
+    }
+    let canonical_headers: String = signed
+        .iter()
+        .map(|(key, value)| format!("{}:{}\n", key, value))
+        .collect();
+    let signed_headers = signed.keys().cloned().collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.to_ascii_uppercase(),
+        canonical_uri(parsed.path()),
+        canonical_query(&parsed),
+        canonical_headers,
+        signed_headers,
+        hex(&Sha256::digest(body))
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date_stamp, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        &date_stamp,
+    );
+    let k_region = hmac(&k_date, region);
+    let k_service = hmac(&k_region, service);
+    let k_signing = hmac(&k_service, "aws4_request");
+    let signature = hex(&hmac(&k_signing, &string_to_sign));
+
+    headers.insert(
+        "Authorization".into(),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            credentials.access_key_id, scope, signed_headers, signature
+        ),
+    );
+    Ok(())
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// RFC 3986 encoding with only unreserved characters left as-is.
+fn uri_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Every service but S3 signs the path encoded once more on top of the
+/// encoding it was sent with.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(url: &url::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (uri_encode(&key), uri_encode(&value)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn example_credentials() -> AwsCredentials {
+        AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        }
+    }
+
+    fn signature_of(headers: &HashMap<String, String>) -> &str {
+        headers["Authorization"]
+            .rsplit("Signature=")
+            .next()
+            .unwrap()
+    }
+
+    // Vectors from the AWS Signature Version 4 test suite.
+    #[test]
+    fn signs_get_vanilla() {
+        let mut headers = HashMap::new();
+        sign(
+            "GET",
+            "https://example.amazonaws.com/",
+            &mut headers,
+            b"",
+            &example_credentials(),
+            "us-east-1",
+            "service",
+            Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(headers["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            headers["Authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn signs_sorted_query_parameters() {
+        let mut headers = HashMap::new();
+        sign(
+            "GET",
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+            &mut headers,
+            b"",
+            &example_credentials(),
+            "us-east-1",
+            "service",
+            Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            signature_of(&headers),
+            "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        );
+    }
+
+    #[test]
+    fn canonical_uri_double_encodes_model_ids() {
+        assert_eq!(
+            canonical_uri("/model/anthropic.claude-3-haiku-20240307-v1%3A0/converse"),
+            "/model/anthropic.claude-3-haiku-20240307-v1%253A0/converse"
+        );
+    }
+}
@@ -87,7 +87,7 @@ pub async fn fetch_voices(
     api_key: &str,
     search: Option<&str>,
 ) -> Result<Vec<ProviderVoice>, String> {
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
 
     let mut url = "https://api.elevenlabs.io/v1/voices".to_string();
     if let Some(query) = search {
@@ -167,7 +167,7 @@ pub async fn generate_speech(
         model_id: model.to_string(),
     };
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .post(&url)
         .header("xi-api-key", api_key)
@@ -248,7 +248,7 @@ pub async fn design_voice(
         num_previews,
     };
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .post(url)
         .header("xi-api-key", api_key)
@@ -315,7 +315,7 @@ pub async fn create_voice_from_preview(
         labels,
     };
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .post(url)
         .header("xi-api-key", api_key)
@@ -350,7 +350,7 @@ pub async fn create_voice_from_preview(
 }
 
 pub async fn verify_api_key(api_key: &str) -> Result<bool, String> {
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .get("https://api.elevenlabs.io/v1/voices")
         .header("xi-api-key", api_key)
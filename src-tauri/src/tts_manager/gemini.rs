@@ -155,7 +155,7 @@ pub async fn generate_speech(
         },
     };
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", api_key))
@@ -221,7 +221,7 @@ pub async fn verify_api_key(api_key: &str, project_id: &str) -> Result<bool, Str
         project_id
     );
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", api_key))
@@ -83,7 +83,7 @@ pub async fn generate_speech(
         response_format: "mp3",
     };
 
-    let client = reqwest::Client::new();
+    let client = crate::transport::network::client_for(None)?;
     let response = client
         .post(&url)
         .header(AUTHORIZATION, format!("Bearer {}", api_key))
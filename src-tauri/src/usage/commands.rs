@@ -1,6 +1,6 @@
 use super::app_activity::AppActiveUsageService;
 use super::repository;
-use super::tracking::{RequestUsage, UsageFilter, UsageStats};
+use super::tracking::{ProviderKeyUsage, RequestUsage, UsageFilter, UsageStats};
 use crate::chat_manager::service::apply_openrouter_cost_to_usage;
 use crate::chat_manager::types::UsageSummary;
 use crate::utils::{log_error, log_info};
@@ -48,6 +48,37 @@ pub async fn usage_export_csv(app: AppHandle, filter: UsageFilter) -> Result<Str
     repository::export_usage_csv(&app, filter)
 }
 
+/// Per-key counters of a credential's key pool, with live cooldown state.
+#[tauri::command]
+pub async fn usage_provider_key_stats(
+    app: AppHandle,
+    credential_id: String,
+) -> Result<Vec<ProviderKeyUsage>, String> {
+    let mut usage = repository::provider_key_usage(&app, &credential_id)?;
+    for status in crate::transport::key_pool::key_status(&credential_id) {
+        let entry = match usage.iter_mut().find(|u| u.key_id == status.key_id) {
+            Some(entry) => entry,
+            None => {
+                usage.push(ProviderKeyUsage {
+                    credential_id: credential_id.clone(),
+                    key_id: status.key_id.clone(),
+                    requests: 0,
+                    rate_limited: 0,
+                    exhausted: 0,
+                    last_used_at: None,
+                    last_rate_limited_at: None,
+                    cooldown_until: None,
+                    currently_exhausted: false,
+                });
+                usage.last_mut().expect("entry was just pushed")
+            }
+        };
+        entry.cooldown_until = status.cooldown_until;
+        entry.currently_exhausted = status.exhausted;
+    }
+    Ok(usage)
+}
+
 #[tauri::command]
 pub async fn usage_save_csv(
     app: AppHandle,
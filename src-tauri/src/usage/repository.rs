@@ -6,7 +6,8 @@ use tauri::AppHandle;
 use tauri::Manager;
 
 use super::tracking::{
-    CharacterStats, ModelStats, ProviderStats, RequestUsage, UsageFilter, UsageStats,
+    CharacterStats, ModelStats, ProviderKeyEvent, ProviderKeyUsage, ProviderStats, RequestUsage,
+    UsageFilter, UsageStats,
 };
 use crate::storage_manager::db::open_db;
 use crate::utils::log_info;
@@ -537,6 +538,62 @@ impl UsageRepository {
         Ok(csv)
     }
 
+    fn record_key_event(
+        &self,
+        credential_id: &str,
+        key_id: &str,
+        event: ProviderKeyEvent,
+    ) -> Result<(), String> {
+        let conn = open_db(&self.app)?;
+        let now = crate::storage_manager::db::now_ms() as i64;
+        let (requests, rate_limited, exhausted) = match event {
+            ProviderKeyEvent::Request => (1, 0, 0),
+            ProviderKeyEvent::RateLimited => (0, 1, 0),
+            ProviderKeyEvent::Exhausted => (0, 1, 1),
+        };
+        conn.execute(
+            "INSERT INTO provider_key_usage (credential_id, key_id, requests, rate_limited, exhausted, last_used_at, last_rate_limited_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, CASE WHEN ?3 > 0 THEN ?6 END, CASE WHEN ?4 > 0 THEN ?6 END)
+             ON CONFLICT(credential_id, key_id) DO UPDATE SET
+               requests = requests + excluded.requests,
+               rate_limited = rate_limited + excluded.rate_limited,
+               exhausted = exhausted + excluded.exhausted,
+               last_used_at = COALESCE(excluded.last_used_at, last_used_at),
+               last_rate_limited_at = COALESCE(excluded.last_rate_limited_at, last_rate_limited_at)",
+            rusqlite::params![credential_id, key_id, requests, rate_limited, exhausted, now],
+        )
+        .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        Ok(())
+    }
+
+    fn key_usage(&self, credential_id: &str) -> Result<Vec<ProviderKeyUsage>, String> {
+        let conn = open_db(&self.app)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT credential_id, key_id, requests, rate_limited, exhausted, last_used_at, last_rate_limited_at
+                 FROM provider_key_usage WHERE credential_id = ? ORDER BY key_id",
+            )
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![credential_id], |r| {
+                Ok(ProviderKeyUsage {
+                    credential_id: r.get(0)?,
+                    key_id: r.get(1)?,
+                    requests: r.get::<_, i64>(2)?.max(0) as u64,
+                    rate_limited: r.get::<_, i64>(3)?.max(0) as u64,
+                    exhausted: r.get::<_, i64>(4)?.max(0) as u64,
+                    last_used_at: r.get::<_, Option<i64>>(5)?.map(|v| v.max(0) as u64),
+                    last_rate_limited_at: r.get::<_, Option<i64>>(6)?.map(|v| v.max(0) as u64),
+                    cooldown_until: None,
+                    currently_exhausted: false,
+                })
+            })
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| crate::utils::err_to_string(module_path!(), line!(), e))?;
+        Ok(rows)
+    }
+
     fn save_csv(&self, csv_data: &str, filename: &str) -> Result<String, String> {
         log_info(
             &self.app,
@@ -793,3 +850,30 @@ pub fn export_usage_csv(app: &AppHandle, filter: UsageFilter) -> Result<String,
 pub fn save_usage_csv(app: &AppHandle, csv_data: &str, filename: &str) -> Result<String, String> {
     UsageRepository::new(app.clone()).save_csv(csv_data, filename)
 }
+
+pub fn record_provider_key_event(
+    app: &AppHandle,
+    credential_id: &str,
+    key_id: &str,
+    event: ProviderKeyEvent,
+) -> Result<(), String> {
+    UsageRepository::new(app.clone()).record_key_event(credential_id, key_id, event)
+}
+
+pub fn provider_key_usage(
+    app: &AppHandle,
+    credential_id: &str,
+) -> Result<Vec<ProviderKeyUsage>, String> {
+    UsageRepository::new(app.clone()).key_usage(credential_id)
+}
+
+/// Lifetime request count per key id, to seed least-used key selection.
+pub fn provider_key_request_counts(
+    app: &AppHandle,
+    credential_id: &str,
+) -> Result<HashMap<String, u64>, String> {
+    Ok(provider_key_usage(app, credential_id)?
+        .into_iter()
+        .map(|usage| (usage.key_id, usage.requests))
+        .collect())
+}
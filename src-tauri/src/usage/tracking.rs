@@ -243,6 +243,32 @@ impl CharacterStats {
     }
 }
 
+/// What happened to one pooled API key (see `transport::key_pool`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKeyEvent {
+    Request,
+    RateLimited,
+    Exhausted,
+}
+
+/// Lifetime counters for one API key of a provider credential.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderKeyUsage {
+    pub credential_id: String,
+    pub key_id: String,
+    pub requests: u64,
+    pub rate_limited: u64,
+    pub exhausted: u64,
+    pub last_used_at: Option<u64>,
+    pub last_rate_limited_at: Option<u64>,
+    /// Set while the key is cooling down in this app session.
+    #[serde(default)]
+    pub cooldown_until: Option<u64>,
+    #[serde(default)]
+    pub currently_exhausted: bool,
+}
+
 /// Filter for querying usage records
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +48,33 @@ export interface ChatMessageDebugSnapshot {
   requestMessages: unknown[];
   requestBody: unknown;
   notes: string[];
+  attempts: ModelAttemptRecord[];
+  contextTrim?: ContextTrimReport | null;
+}
+
+export interface ContextTrimReport {
+  contextLength: number;
+  reservedOutputTokens: number;
+  fixedTokens: number;
+  historyTokensBefore: number;
+  historyTokensAfter: number;
+  droppedMessageIds: string[];
+  droppedTokens: number;
+  summaryTokens: number;
+  overBudget: boolean;
+}
+
+export interface ModelAttemptRecord {
+  modelId: string;
+  modelName: string;
+  providerId: string;
+  credentialId: string;
+  isFallback: boolean;
+  outcome: "success" | "failed" | "skipped";
+  failure?: string | null;
+  error?: string | null;
+  startedAt: number;
+  elapsedMs?: number | null;
 }
 
 export interface LorebookEntryDraft {
@@ -904,6 +904,8 @@ export const enMessages = {
       memoriesUsed: "{{count}} memories used",
       lorebookUsage: "Lorebook usage",
       lorebookUsageDesc: "This response used the following lorebook entries.",
+      sources: "Sources",
+      sourcesDesc: "The provider cited these lorebook entries and memories in this response.",
       matchScore: "Match: {{score}}%",
       unknownModel: "Unknown model",
       loadingModel: "Loading model...",
@@ -2648,12 +2650,35 @@ export const enMessages = {
     search: {
       didYouMean: "Did you mean:",
     },
+    outputControls: {
+      title: "Stop, bias and banned strings",
+      description: "Applied with every provider this model uses",
+      stopSequences: "Stop sequences",
+      stopSequencesPlaceholder: "One per line, \\n for a newline\n\\nUser:",
+      bannedStrings: "Banned strings",
+      bannedStringsPlaceholder: "One per line\nshivers down her spine",
+      logitBias: "Logit bias",
+      logitBiasPlaceholder: "token id or text: bias\n1234: -100\n\" Sure\": 5",
+      enforcementNote:
+        "Sent natively where the provider supports them. Otherwise the reply is cut on the device at the first stop or banned string. Text in logit bias needs the model's tokenizer; token ids always work.",
+    },
     capabilities: {
       helpLabel: "Help with capabilities",
       input: "Input",
       output: "Output",
       automatic1111Fixed:
         "AUTOMATIC1111 models are fixed to text + image input and image output.",
+      detected: "Detected",
+      detectedFromMetadata: "From provider metadata",
+      detectedFromProbe: "Verified with a test request",
+      notDetected: "Not detected yet",
+      probe: "Probe model",
+      readGguf: "Read GGUF",
+      probing: "Probing…",
+      vision: "Vision",
+      tools: "Tools",
+      reasoning: "Reasoning",
+      jsonMode: "JSON mode",
     },
     runtimeSummary: {
       ram: "RAM",
@@ -3269,7 +3294,13 @@ export const enMessages = {
       retrievalMode: "Retrieval Mode",
       retrievalModeSmart: "Smart",
       retrievalModeCosine: "Cosine",
-      retrievalModeDescription: "Smart blends relevance with recency/frequency. Cosine uses pure top similarity.",
+      retrievalModeHybrid: "Hybrid",
+      hybridRecencyBoost: "Recency Boost",
+      hybridRecencyBoostDescription: "Extra weight for recently created memories in hybrid ranking",
+      hybridPinBoost: "Pin Boost",
+      hybridPinBoostDescription: "Extra weight for pinned memories in hybrid ranking",
+      retrievalModeDescription:
+        "Smart blends relevance with recency/frequency. Cosine uses pure top similarity. Hybrid fuses similarity with keyword (BM25) ranking, which helps with names and rare terms.",
       retrievalLimit: "Retrieval Limit",
       retrievalLimitDescription: "Max memories selected per turn",
       decayRate: "Decay Rate",
@@ -35,16 +35,24 @@ export {
 } from "./index";
 
 import { invoke } from "@tauri-apps/api/core";
+import type { ContextTrimReport } from "../chat/manager";
 
 export async function exportPromptTemplateAsUsc(id: string): Promise<string> {
   return await invoke<string>("export_prompt_template_as_usc", { id });
 }
 
+export interface PromptPreview {
+  rendered: string;
+  /** What the context budget would trim from the session's history, when a session is given. */
+  contextTrim?: ContextTrimReport | null;
+  contextSummary?: string | null;
+}
+
 export async function renderPromptPreview(
   content: string,
   opts: { characterId: string; sessionId?: string; personaId?: string },
-): Promise<string> {
-  return await invoke<string>("render_prompt_preview", {
+): Promise<PromptPreview> {
+  return await invoke<PromptPreview>("render_prompt_preview", {
     content,
     characterId: opts.characterId,
     sessionId: opts.sessionId,
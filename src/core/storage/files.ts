@@ -2,6 +2,158 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 
+export type BackupScheduleConfig = {
+  enabled: boolean;
+  frequency: "daily" | "weekly";
+  directory: string | null;
+  incrementalsPerFull: number;
+  keepDaily: number;
+  keepWeekly: number;
+};
+
+export type BackupScheduleStatus = BackupScheduleConfig & {
+  hasPassword: boolean;
+  lastRunAt: number | null;
+  lastError: string | null;
+  nextRunAt: number | null;
+};
+
+export type BackupEntitySummary = {
+  id: string;
+  name: string;
+  characterId?: string;
+  itemCount: number;
+  updatedAt: number | null;
+  exists: boolean;
+};
+
+export type BackupContents = {
+  characters: BackupEntitySummary[];
+  sessions: BackupEntitySummary[];
+  lorebooks: BackupEntitySummary[];
+  personas: BackupEntitySummary[];
+};
+
+export type BackupSelection = {
+  characterIds?: string[];
+  sessionIds?: string[];
+  lorebookIds?: string[];
+  personaIds?: string[];
+};
+
+export type BackupRestoreReport = {
+  characters: number;
+  sessions: number;
+  lorebooks: number;
+  personas: number;
+  mediaFiles: number;
+  remapped: Record<string, string>;
+};
+
+export type CharacterMemoryConfig = {
+  enabled: boolean;
+  tokenBudget: number;
+  retrievalLimit: number;
+  autoPromote: boolean;
+  promoteMinImportance: number;
+  promoteMinAccessCount: number;
+};
+
+export type CharacterMemory = {
+  characterId: string;
+  id: string;
+  text: string;
+  createdAt: number;
+  tokenCount: number;
+  isCold: boolean;
+  lastAccessedAt: number;
+  importanceScore: number;
+  isPinned: boolean;
+  accessCount: number;
+  category?: string | null;
+  sourceSessionId: string | null;
+  sourceMemoryId: string | null;
+  promotedBy: "manual" | "auto";
+};
+
+export type RelationshipValues = {
+  closeness: number;
+  trust: number;
+  affection: number;
+  tension: number;
+  stability: number;
+};
+
+export type CompanionRelationshipLedger = RelationshipValues & {
+  characterId: string;
+  interactionCount: number;
+  lastInteractionAt: number;
+  updatedAt: number;
+};
+
+export type CompanionRelationshipPoint = RelationshipValues & {
+  id: string;
+  characterId: string;
+  sessionId: string | null;
+  source: "session" | "adjust" | "reset";
+  createdAt: number;
+  updatedAt: number;
+};
+
+export type ProactiveMessageConfig = {
+  enabled: boolean;
+  quietStartMinute: number;
+  quietEndMinute: number;
+  maxPerDay: number;
+  minGapMinutes: number;
+  minIdleMinutes: number;
+};
+
+export type ProactiveMessage = {
+  id: string;
+  sessionId: string;
+  characterId: string;
+  messageId: string;
+  status: "pending" | "seen" | "dismissed";
+  urgency: number;
+  createdAt: number;
+  resolvedAt: number | null;
+};
+
+export type CharacterFallbackChainStep = {
+  modelId: string;
+  conditions: Array<"timeout" | "serverError" | "contentRefusal" | "contextOverflow">;
+};
+
+export type RollingContextSummary = {
+  sessionId: string;
+  summary: string;
+  coveredUntil: number;
+  tokenCount: number;
+  updatedAt: number;
+};
+
+export type EntityNode = {
+  id: string;
+  characterId: string;
+  sessionId: string | null;
+  kind: "person" | "place" | "item" | "organisation";
+  name: string;
+  normalized: string;
+  aliases: string[];
+  mentionCount: number;
+  createdAt: number;
+  updatedAt: number;
+};
+
+export type EntityMention = {
+  entityId: string;
+  sourceKind: "message" | "memory";
+  sourceId: string;
+  sessionId: string;
+  createdAt: number;
+};
+
 async function readJsonCommand<T>(
   command: string,
   args?: Record<string, unknown>,
@@ -231,6 +383,22 @@ export const storageBridge = {
       };
       averageSpeedupV4VsV3: number;
     }>("run_embedding_dev_benchmark"),
+  runMemoryAnnBenchmark: () =>
+    invoke<{
+      dimensions: number;
+      topK: number;
+      queryCount: number;
+      sizes: Array<{
+        memoryCount: number;
+        buildMs: number;
+        bruteForceAverageMs: number;
+        bruteForceP95Ms: number;
+        annAverageMs: number;
+        annP95Ms: number;
+        averageSpeedup: number;
+        recallAtK: number;
+      }>;
+    }>("run_memory_ann_benchmark"),
   compareCustomTexts: (textA: string, textB: string) =>
     invoke<number>("compare_custom_texts", { textA, textB }),
   deleteEmbeddingModel: () => invoke("delete_embedding_model") as Promise<void>,
@@ -248,6 +416,8 @@ export const storageBridge = {
       JSON.parse(s),
     ),
   providerDelete: (id: string) => invoke("provider_delete", { id }) as Promise<void>,
+  networkSetProxyPassword: (credentialId: string | null, password: string | null) =>
+    invoke("network_set_proxy_password", { credentialId, password }) as Promise<void>,
 
   modelUpsert: (model: unknown) =>
     invoke<string>("model_upsert", { modelJson: JSON.stringify(model) }).then((s) => JSON.parse(s)),
@@ -365,6 +535,8 @@ export const storageBridge = {
       sessionId,
       assistantMessageId,
     }).then((s) => (typeof s === "string" ? JSON.parse(s) : null)),
+  messageCitations: (sessionId: string, messageId: string) =>
+    invoke<unknown | null>("get_message_citations", { sessionId, messageId }),
   sessionUpsert: (session: unknown) =>
     invoke("session_upsert", { sessionJson: JSON.stringify(session) }) as Promise<void>,
   sessionUpsertMeta: (session: unknown) =>
@@ -510,6 +682,14 @@ export const storageBridge = {
         attachmentCount: number;
         path: string;
         filename: string;
+        chain?: {
+          backupId: string;
+          baseId: string;
+          parentId: string | null;
+          kind: "full" | "incremental";
+          length: number;
+          restorable: boolean;
+        } | null;
       }>
     >("backup_list"),
   backupDelete: (backupPath: string) => invoke("backup_delete", { backupPath }) as Promise<void>,
@@ -544,6 +724,96 @@ export const storageBridge = {
     }),
   backupDisableDynamicMemory: () => invoke("backup_disable_dynamic_memory") as Promise<void>,
 
+  // Scheduled backups
+  backupScheduleGet: () => invoke<BackupScheduleStatus>("backup_schedule_get"),
+  backupScheduleSet: (config: BackupScheduleConfig, password?: string) =>
+    invoke<BackupScheduleStatus>("backup_schedule_set", { config, password: password ?? null }),
+  backupScheduleRunNow: () => invoke<string>("backup_schedule_run_now"),
+
+  // Selective restore
+  backupListContents: (backupPath: string, password?: string) =>
+    invoke<BackupContents>("backup_list_contents", { backupPath, password: password ?? null }),
+  backupRestoreSelected: (backupPath: string, selection: BackupSelection, password?: string) =>
+    invoke<BackupRestoreReport>("backup_restore_selected", {
+      backupPath,
+      selection,
+      password: password ?? null,
+    }),
+
+  // Character-level memory
+  characterMemoryGetConfig: (characterId: string) =>
+    invoke<CharacterMemoryConfig>("character_memory_get_config", { characterId }),
+  characterMemorySetConfig: (characterId: string, config: CharacterMemoryConfig) =>
+    invoke<CharacterMemoryConfig>("character_memory_set_config", { characterId, config }),
+  characterMemoryList: (characterId: string) =>
+    invoke<CharacterMemory[]>("character_memory_list", { characterId }),
+  characterMemoryPromote: (sessionId: string, memoryId: string) =>
+    invoke<CharacterMemory | null>("character_memory_promote", { sessionId, memoryId }),
+  characterMemoryAdd: (characterId: string, text: string, category?: string) =>
+    invoke<CharacterMemory>("character_memory_add", {
+      characterId,
+      text,
+      category: category ?? null,
+    }),
+  characterMemoryUpdate: (characterId: string, memoryId: string, text: string) =>
+    invoke<CharacterMemory>("character_memory_update", { characterId, memoryId, text }),
+  characterMemorySetPinned: (characterId: string, memoryId: string, pinned: boolean) =>
+    invoke<CharacterMemory>("character_memory_set_pinned", { characterId, memoryId, pinned }),
+  characterMemoryDelete: (characterId: string, memoryId: string) =>
+    invoke("character_memory_delete", { characterId, memoryId }) as Promise<void>,
+
+  // Companion relationship ledger
+  companionRelationshipGet: (characterId: string) =>
+    invoke<CompanionRelationshipLedger | null>("companion_relationship_get", { characterId }),
+  companionRelationshipHistory: (characterId: string, limit?: number) =>
+    invoke<CompanionRelationshipPoint[]>("companion_relationship_history", {
+      characterId,
+      limit: limit ?? null,
+    }),
+  companionRelationshipAdjust: (characterId: string, adjustment: Partial<RelationshipValues>) =>
+    invoke<CompanionRelationshipLedger>("companion_relationship_adjust", {
+      characterId,
+      adjustment,
+    }),
+  companionRelationshipReset: (characterId: string) =>
+    invoke<CompanionRelationshipLedger>("companion_relationship_reset", { characterId }),
+
+  // Proactive companion messages
+  companionProactiveGetConfig: () =>
+    invoke<ProactiveMessageConfig>("companion_proactive_get_config"),
+  companionProactiveSetConfig: (config: ProactiveMessageConfig) =>
+    invoke<ProactiveMessageConfig>("companion_proactive_set_config", { config }),
+  companionProactiveListPending: (sessionId?: string) =>
+    invoke<ProactiveMessage[]>("companion_proactive_list_pending", {
+      sessionId: sessionId ?? null,
+    }),
+  companionProactiveResolve: (id: string, status: "seen" | "dismissed") =>
+    invoke<void>("companion_proactive_resolve", { id, status }),
+
+  // Model fallback chains
+  characterFallbackChainGet: (characterId: string) =>
+    invoke<CharacterFallbackChainStep[]>("character_fallback_chain_get", { characterId }),
+  characterFallbackChainSet: (characterId: string, chain: CharacterFallbackChainStep[]) =>
+    invoke<CharacterFallbackChainStep[]>("character_fallback_chain_set", { characterId, chain }),
+
+  // Context overflow
+  sessionContextSummaryGet: (sessionId: string) =>
+    invoke<RollingContextSummary | null>("session_context_summary_get", { sessionId }),
+  sessionContextSummaryClear: (sessionId: string) =>
+    invoke<void>("session_context_summary_clear", { sessionId }),
+
+  // Entity graph
+  entityGraphList: (characterId: string, sessionId?: string) =>
+    invoke<EntityNode[]>("entity_graph_list", { characterId, sessionId: sessionId ?? null }),
+  entityGraphMentions: (entityId: string) =>
+    invoke<EntityMention[]>("entity_graph_mentions", { entityId }),
+  entityGraphRename: (entityId: string, name: string) =>
+    invoke<EntityNode>("entity_graph_rename", { entityId, name }),
+  entityGraphMerge: (sourceId: string, targetId: string) =>
+    invoke<EntityNode>("entity_graph_merge", { sourceId, targetId }),
+  entityGraphDelete: (entityId: string) =>
+    invoke("entity_graph_delete", { entityId }) as Promise<void>,
+
   // Chat package (single/group chat export/import)
   chatpkgExportSingleChat: (sessionId: string, includeCharacterId?: boolean) =>
     invoke<string>("chatpkg_export_single_chat", {
@@ -5,6 +5,7 @@ import { convertToImageRef } from "./images";
 import {
   CharacterSchema,
   CompanionTurnEffectSchema,
+  MessageCitationsSchema,
   LorebookSchema,
   LorebookEntrySchema,
   SessionSchema,
@@ -19,6 +20,7 @@ import {
   GroupSessionSchema,
   type Character,
   type CompanionTurnEffect,
+  type MessageCitations,
   type Session,
   type Settings,
   type Persona,
@@ -1176,6 +1178,14 @@ export async function getMessageCompanionEffect(
   return data ? CompanionTurnEffectSchema.parse(data) : null;
 }
 
+export async function getMessageCitations(
+  sessionId: string,
+  messageId: string,
+): Promise<MessageCitations | null> {
+  const data = await storageBridge.messageCitations(sessionId, messageId);
+  return data ? MessageCitationsSchema.parse(data) : null;
+}
+
 export async function listMessages(
   sessionId: string,
   options: { limit: number; before?: { createdAt: number; id: string } } = { limit: 120 },
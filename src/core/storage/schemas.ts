@@ -278,6 +278,16 @@ export const LlamaRuntimeSuggestedSettingsSchema = z.object({
 });
 export type LlamaRuntimeSuggestedSettings = z.infer<typeof LlamaRuntimeSuggestedSettingsSchema>;
 
+export const ModelCapabilitiesSchema = z.object({
+  vision: z.boolean().nullish().optional(),
+  tools: z.boolean().nullish().optional(),
+  reasoning: z.boolean().nullish().optional(),
+  jsonMode: z.boolean().nullish().optional(),
+  // "metadata" when read from a models endpoint or GGUF file, "probe" when verified live
+  source: z.enum(["metadata", "probe"]).nullish().optional(),
+});
+export type ModelCapabilities = z.infer<typeof ModelCapabilitiesSchema>;
+
 export const LlamaLastRuntimeReportSchema = z.object({
   status: z.enum(["succeeded", "cpuFallbackSucceeded", "cpuFallbackFailed", "failed"]),
   updatedAt: z.number().int().nullable().optional(),
@@ -410,6 +420,25 @@ export const AdvancedModelSettingsSchema = z.object({
   ollamaRepeatPenalty: z.number().min(0).max(2).nullable().optional(),
   ollamaSeed: z.number().int().min(0).max(2_147_483_647).nullable().optional(),
   ollamaStop: z.array(z.string().min(1)).nullable().optional(),
+  // KoboldCpp specific samplers
+  koboldRepPen: z.number().min(1).max(3).nullable().optional(),
+  koboldRepPenRange: z.number().int().min(0).max(262_144).nullable().optional(),
+  koboldRepPenSlope: z.number().min(0).max(10).nullable().optional(),
+  koboldMinP: z.number().min(0).max(1).nullable().optional(),
+  koboldTopA: z.number().min(0).max(1).nullable().optional(),
+  koboldTypical: z.number().min(0).max(1).nullable().optional(),
+  koboldTfs: z.number().min(0).max(1).nullable().optional(),
+  koboldDryMultiplier: z.number().min(0).max(10).nullable().optional(),
+  koboldDryBase: z.number().min(0).max(10).nullable().optional(),
+  koboldDryAllowedLength: z.number().int().min(0).max(128).nullable().optional(),
+  koboldDryPenaltyLastN: z.number().int().min(-1).max(262_144).nullable().optional(),
+  koboldDrySequenceBreakers: z.array(z.string()).nullable().optional(),
+  koboldXtcThreshold: z.number().min(0).max(1).nullable().optional(),
+  koboldXtcProbability: z.number().min(0).max(1).nullable().optional(),
+  koboldSmoothingFactor: z.number().min(0).max(10).nullable().optional(),
+  koboldSmoothingCurve: z.number().min(1).max(10).nullable().optional(),
+  koboldSamplerOrder: z.array(z.number().int().min(0).max(6)).nullable().optional(),
+  koboldBannedTokens: z.array(z.string().min(1)).nullable().optional(),
   // Reasoning/thinking settings
   reasoningEnabled: z.boolean().nullable().optional(),
   reasoningEffort: z.enum(["low", "medium", "high"]).nullable().optional(),
@@ -417,6 +446,18 @@ export const AdvancedModelSettingsSchema = z.object({
   // Caching settings
   promptCachingEnabled: z.boolean().nullable().optional(),
   promptCachingTtl: z.string().nullish().optional(),
+  // Tokenizer used for context budgeting
+  tokenizer: z
+    .enum(["auto", "gguf", "huggingFace", "provider", "o200k", "estimate"])
+    .nullish()
+    .optional(),
+  tokenizerRepo: z.string().nullish().optional(),
+  // Detected from provider metadata or a probe request
+  modelCapabilities: ModelCapabilitiesSchema.nullish().optional(),
+  // Output controls for every provider; unsupported ones are enforced while streaming
+  stopSequences: z.array(z.string().min(1)).nullable().optional(),
+  logitBias: z.record(z.string(), z.number().min(-100).max(100)).nullable().optional(),
+  bannedStrings: z.array(z.string().min(1)).nullable().optional(),
 });
 
 export type AdvancedModelSettings = z.infer<typeof AdvancedModelSettingsSchema>;
@@ -471,6 +512,14 @@ export const PROVIDER_REASONING_CAPABILITIES: Record<string, ReasoningCapability
       { value: "high", label: "High", description: "Maximum reasoning depth" },
     ],
   },
+  "azure-openai": {
+    type: "effort",
+    options: [
+      { value: "low", label: "Low", description: "Quick responses with less reasoning" },
+      { value: "medium", label: "Medium", description: "Balanced reasoning depth" },
+      { value: "high", label: "High", description: "Maximum reasoning depth" },
+    ],
+  },
   "lettuce-host": {
     type: "effort",
     options: [
@@ -545,6 +594,8 @@ export const PROVIDER_REASONING_CAPABILITIES: Record<string, ReasoningCapability
   },
   moonshot: { type: "budget-only" },
   anthropic: { type: "budget-only" },
+  "aws-bedrock": { type: "budget-only" },
+  cohere: { type: "budget-only" },
   mistral: { type: "none" },
   gemini: {
     type: "effort",
@@ -587,6 +638,9 @@ export const PROVIDER_REASONING_CAPABILITIES: Record<string, ReasoningCapability
       { value: "high", label: "High", description: "Deep reasoning" },
     ],
   },
+  koboldcpp: { type: "none" },
+  "text-completion": { type: "none" },
+  mock: { type: "budget-only" },
   intenserp: {
     type: "effort",
     options: [
@@ -809,6 +863,63 @@ export const PROVIDER_PARAMETER_SUPPORT = {
       ollamaStop: false,
     },
   },
+  "azure-openai": {
+    providerId: "azure-openai",
+    displayName: "Azure OpenAI",
+    reasoningSupport: "effort" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: false,
+      frequencyPenalty: true,
+      presencePenalty: true,
+      topK: false,
+      reasoningEnabled: true,
+      reasoningEffort: true,
+      reasoningBudgetTokens: true,
+      promptCachingEnabled: false,
+      promptCachingTtl: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      llamaDryMultiplier: false,
+      llamaDryBase: false,
+      llamaDryAllowedLength: false,
+      llamaDryPenaltyLastN: false,
+      llamaDrySequenceBreakers: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
   openrouter: {
     providerId: "openrouter",
     displayName: "OpenRouter",
@@ -923,6 +1034,120 @@ export const PROVIDER_PARAMETER_SUPPORT = {
       ollamaStop: false,
     },
   },
+  "aws-bedrock": {
+    providerId: "aws-bedrock",
+    displayName: "AWS Bedrock",
+    reasoningSupport: "budget-only" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: false,
+      frequencyPenalty: false,
+      presencePenalty: false,
+      topK: true, // Anthropic models only
+      reasoningEnabled: true,
+      reasoningEffort: false,
+      reasoningBudgetTokens: true,
+      promptCachingEnabled: false,
+      promptCachingTtl: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      llamaDryMultiplier: false,
+      llamaDryBase: false,
+      llamaDryAllowedLength: false,
+      llamaDryPenaltyLastN: false,
+      llamaDrySequenceBreakers: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
+  cohere: {
+    providerId: "cohere",
+    displayName: "Cohere",
+    reasoningSupport: "budget-only" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: false,
+      frequencyPenalty: true,
+      presencePenalty: true,
+      topK: true,
+      reasoningEnabled: true,
+      reasoningEffort: false,
+      reasoningBudgetTokens: true,
+      promptCachingEnabled: false,
+      promptCachingTtl: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      llamaDryMultiplier: false,
+      llamaDryBase: false,
+      llamaDryAllowedLength: false,
+      llamaDryPenaltyLastN: false,
+      llamaDrySequenceBreakers: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
   groq: {
     providerId: "groq",
     displayName: "Groq",
@@ -1679,6 +1904,156 @@ export const PROVIDER_PARAMETER_SUPPORT = {
       ollamaStop: false,
     },
   },
+  koboldcpp: {
+    providerId: "koboldcpp",
+    displayName: "KoboldCpp",
+    reasoningSupport: "none" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: true,
+      frequencyPenalty: false,
+      presencePenalty: true,
+      topK: true,
+      reasoningEnabled: false,
+      reasoningEffort: false,
+      reasoningBudgetTokens: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
+  "text-completion": {
+    providerId: "text-completion",
+    displayName: "Text Completion",
+    reasoningSupport: "none" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: false,
+      frequencyPenalty: true,
+      presencePenalty: true,
+      topK: true,
+      reasoningEnabled: false,
+      reasoningEffort: false,
+      reasoningBudgetTokens: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
+  mock: {
+    providerId: "mock",
+    displayName: "Mock (Offline)",
+    reasoningSupport: "budget-only" as ReasoningSupport,
+    supportedParameters: {
+      temperature: true,
+      topP: true,
+      maxOutputTokens: true,
+      contextLength: false,
+      frequencyPenalty: false,
+      presencePenalty: false,
+      topK: false,
+      reasoningEnabled: true,
+      reasoningEffort: false,
+      reasoningBudgetTokens: false,
+      llamaGpuLayers: false,
+      llamaThreads: false,
+      llamaThreadsBatch: false,
+      llamaSeed: false,
+      llamaRopeFreqBase: false,
+      llamaRopeFreqScale: false,
+      llamaOffloadKqv: false,
+      llamaBatchSize: false,
+      llamaKvType: false,
+      llamaFlashAttention: false,
+      llamaChatTemplateOverride: false,
+      llamaMmprojPath: false,
+      llamaChatTemplatePreset: false,
+      llamaRawCompletionFallback: false,
+      llamaSamplerProfile: false,
+      llamaSamplerOrder: false,
+      llamaMinP: false,
+      llamaTypicalP: false,
+      ollamaNumCtx: false,
+      ollamaNumPredict: false,
+      ollamaNumKeep: false,
+      ollamaNumBatch: false,
+      ollamaNumGpu: false,
+      ollamaNumThread: false,
+      ollamaTfsZ: false,
+      ollamaTypicalP: false,
+      ollamaMinP: false,
+      ollamaMirostat: false,
+      ollamaMirostatTau: false,
+      ollamaMirostatEta: false,
+      ollamaRepeatPenalty: false,
+      ollamaSeed: false,
+      ollamaStop: false,
+    },
+  },
   intenserp: {
     providerId: "intenserp",
     displayName: "IntenseRP Next",
@@ -2212,7 +2587,7 @@ export const DynamicMemorySettingsSchema = z.object({
   maxEntries: z.number().min(10).max(500).default(50),
   minSimilarityThreshold: z.number().min(0).max(1).default(0.32),
   retrievalLimit: z.number().min(1).max(20).default(5),
-  retrievalStrategy: z.enum(["smart", "cosine"]).default("smart"),
+  retrievalStrategy: z.enum(["smart", "cosine", "hybrid"]).default("smart"),
   hotMemoryTokenBudget: z.number().min(500).max(16384).default(2000),
   decayRate: z.number().min(0.01).max(0.3).default(0.08),
   coldThreshold: z.number().min(0.1).max(0.5).default(0.3),
@@ -2221,6 +2596,10 @@ export const DynamicMemorySettingsSchema = z.object({
   contextEnrichmentEnabled: z.boolean().default(true),
   recursiveMemoryLoops: z.boolean().default(false),
   recursiveMemoryLoopHardCap: z.number().min(1).max(100).default(20),
+  entityGraphEnabled: z.boolean().default(false),
+  entityGraphCharacterScope: z.boolean().default(false),
+  hybridRecencyBoost: z.number().min(0).max(1).default(0),
+  hybridPinBoost: z.number().min(0).max(1).default(0),
 });
 export type DynamicMemorySettings = z.infer<typeof DynamicMemorySettingsSchema>;
 
@@ -2403,11 +2782,21 @@ export const GroupChatResponseSchema = z.object({
 });
 export type GroupChatResponse = z.infer<typeof GroupChatResponseSchema>;
 
+export const ProviderApiKeySchema = z.object({
+  id: z.string(),
+  label: z.string().nullish(),
+  apiKey: z.string(),
+  enabled: z.boolean().default(true),
+});
+export type ProviderApiKey = z.infer<typeof ProviderApiKeySchema>;
+
 export const ProviderCredentialSchema = z.object({
   id: z.string().uuid(),
   providerId: z.string(),
   label: z.string().min(1),
   apiKey: z.string().optional(),
+  /** Extra keys rotated together with `apiKey`; see `config.keyRotation`. */
+  apiKeys: z.array(ProviderApiKeySchema).optional(),
   baseUrl: z.string().optional(),
   defaultModel: z.string().optional(),
   headers: z.record(z.string(), z.string()).optional(),
@@ -2545,6 +2934,54 @@ export const AccessibilitySoundSchema = z.object({
 });
 export type AccessibilitySound = z.infer<typeof AccessibilitySoundSchema>;
 
+export const FallbackConditionSchema = z.enum([
+  "timeout",
+  "serverError",
+  "contentRefusal",
+  "contextOverflow",
+]);
+export type FallbackCondition = z.infer<typeof FallbackConditionSchema>;
+
+export const FallbackChainStepSchema = z.object({
+  modelId: z.string(),
+  conditions: z.array(FallbackConditionSchema).default([]),
+});
+export type FallbackChainStep = z.infer<typeof FallbackChainStepSchema>;
+
+export const CircuitBreakerSettingsSchema = z.object({
+  enabled: z.boolean().default(true),
+  failureThreshold: z.number().int().min(1).default(3),
+  windowMinutes: z.number().int().min(1).default(5),
+});
+export type CircuitBreakerSettings = z.infer<typeof CircuitBreakerSettingsSchema>;
+
+export const ContextOverflowSettingsSchema = z.object({
+  enabled: z.boolean().default(true),
+  rollingSummary: z.boolean().default(false),
+  summaryModelId: z.string().nullish(),
+});
+export type ContextOverflowSettings = z.infer<typeof ContextOverflowSettingsSchema>;
+
+export const ProxySettingsSchema = z.object({
+  kind: z.enum(["direct", "http", "https", "socks5"]),
+  host: z.string().default(""),
+  port: z.number().int().min(1).max(65535).nullish(),
+  username: z.string().nullish(),
+  /** The password itself is stored apart from the settings. */
+  hasPassword: z.boolean().default(false),
+});
+export type ProxySettings = z.infer<typeof ProxySettingsSchema>;
+
+/** Proxy and TLS options; the default in `advancedSettings.network`, per
+ * credential in `config.network`. `acceptInvalidCerts` only applies to a
+ * credential. */
+export const NetworkSettingsSchema = z.object({
+  proxy: ProxySettingsSchema.nullish(),
+  caCertificates: z.array(z.string()).default([]),
+  acceptInvalidCerts: z.boolean().default(false),
+});
+export type NetworkSettings = z.infer<typeof NetworkSettingsSchema>;
+
 export const AccessibilitySettingsSchema = z.object({
   send: AccessibilitySoundSchema.default({ enabled: false, volume: 0.5 }),
   success: AccessibilitySoundSchema.default({ enabled: false, volume: 0.6 }),
@@ -2722,6 +3159,11 @@ export const SettingsSchema = z.object({
       groupDynamicMemory: DynamicMemorySettingsSchema.optional(),
       accessibility: AccessibilitySettingsSchema.optional(),
       chatAppearance: ChatAppearanceSettingsSchema.optional(),
+      fallbackChain: z.array(FallbackChainStepSchema).optional(),
+      circuitBreaker: CircuitBreakerSettingsSchema.optional(),
+      contextOverflow: ContextOverflowSettingsSchema.optional(),
+      network: NetworkSettingsSchema.optional(),
+      requestRecordingEnabled: z.boolean().optional(),
     })
     .optional(),
   promptTemplateId: z.string().nullish().optional(),
@@ -3036,12 +3478,24 @@ const DEFAULT_COMPANION_RELATIONSHIP_STATE: CompanionRelationshipState = {
   lastInteractionAt: 0,
 };
 
-export const CompanionSessionStateSchema = z.object({
+export const CompanionSnapshotSchema = z.object({
   emotionalState: CompanionEmotionalStateSchema.default(DEFAULT_COMPANION_EMOTIONAL_STATE),
   relationshipState: CompanionRelationshipStateSchema.default(DEFAULT_COMPANION_RELATIONSHIP_STATE),
   activeSignals: z.array(z.string()).default([]),
   updatedAt: z.number().int().default(0),
 });
+export type CompanionSnapshot = z.infer<typeof CompanionSnapshotSchema>;
+
+export const CompanionSessionStateSchema = CompanionSnapshotSchema.extend({
+  turnStart: CompanionSnapshotSchema.nullish(),
+  lastReply: z
+    .object({
+      messageId: z.string(),
+      before: CompanionSnapshotSchema,
+    })
+    .nullish(),
+  ledgerBaseline: CompanionRelationshipStateSchema.nullish(),
+});
 export type CompanionSessionState = z.infer<typeof CompanionSessionStateSchema>;
 
 export const CompanionTurnEffectSchema = z.object({
@@ -3072,6 +3526,20 @@ export const CompanionTurnEffectSchema = z.object({
 });
 export type CompanionTurnEffect = z.infer<typeof CompanionTurnEffectSchema>;
 
+export const MessageCitationSchema = z.object({
+  text: z.string(),
+  start: z.number().int().nullable().optional(),
+  end: z.number().int().nullable().optional(),
+  sources: z.array(z.object({ id: z.string(), title: z.string().nullable().optional() })),
+});
+export type MessageCitation = z.infer<typeof MessageCitationSchema>;
+
+export const MessageCitationsSchema = z.object({
+  variantId: z.string().nullable().optional(),
+  citations: z.array(MessageCitationSchema).default([]),
+});
+export type MessageCitations = z.infer<typeof MessageCitationsSchema>;
+
 export const MemoryEntityAnchorSchema = z.object({
   label: z.string(),
   surface: z.string(),
@@ -1,6 +1,10 @@
 import { invoke } from "@tauri-apps/api/core";
 
-export async function countTokensBatch(texts: string[]): Promise<number[]> {
+/**
+ * Counts tokens with the tokenizer of `modelId`, or of the default model when
+ * omitted.
+ */
+export async function countTokensBatch(texts: string[], modelId?: string): Promise<number[]> {
   if (texts.length === 0) return [];
-  return invoke<number[]>("tokens_count_batch", { texts });
+  return invoke<number[]>("tokens_count_batch", { texts, modelId: modelId ?? null });
 }
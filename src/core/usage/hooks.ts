@@ -1,6 +1,12 @@
 import { useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { RequestUsage, UsageStats, UsageFilter, AppActiveUsageSummary } from "./types";
+import {
+  RequestUsage,
+  UsageStats,
+  UsageFilter,
+  AppActiveUsageSummary,
+  ProviderKeyUsage,
+} from "./types";
 
 export interface UseUsageTrackingOptions {
   onError?: (error: string) => void;
@@ -133,6 +139,24 @@ export function useUsageTracking(options?: UseUsageTrackingOptions) {
     }
   }, [options]);
 
+  const getProviderKeyStats = useCallback(
+    async (credentialId: string): Promise<ProviderKeyUsage[]> => {
+      setLoading(true);
+      setError(null);
+      try {
+        return await invoke<ProviderKeyUsage[]>("usage_provider_key_stats", { credentialId });
+      } catch (err) {
+        const message = err instanceof Error ? err.message : String(err);
+        setError(message);
+        options?.onError?.(message);
+        return [];
+      } finally {
+        setLoading(false);
+      }
+    },
+    [options],
+  );
+
   return {
     queryRecords,
     getStats,
@@ -140,6 +164,7 @@ export function useUsageTracking(options?: UseUsageTrackingOptions) {
     saveCSV,
     clearBefore,
     getAppActiveUsage,
+    getProviderKeyStats,
     loading,
     error,
   };
@@ -113,3 +113,15 @@ export interface AppActiveUsageSummary {
   lastUpdatedAtMs?: number;
   byDayMs: Record<string, number>;
 }
+
+export interface ProviderKeyUsage {
+  credentialId: string;
+  keyId: string;
+  requests: number;
+  rateLimited: number;
+  exhausted: number;
+  lastUsedAt?: number;
+  lastRateLimitedAt?: number;
+  cooldownUntil?: number;
+  currentlyExhausted: boolean;
+}
@@ -1,5 +1,18 @@
 import type { ReactElement } from "react";
-import { Cpu, EthernetPort, Leaf, Mic, Settings, Sparkles, Volume2, Wrench } from "lucide-react";
+import {
+  Cloud,
+  Cpu,
+  EthernetPort,
+  Feather,
+  FileText,
+  FlaskConical,
+  Leaf,
+  Mic,
+  Settings,
+  Sparkles,
+  Volume2,
+  Wrench,
+} from "lucide-react";
 
 import OpenAIIcon from "../../assets/openai_light.svg";
 import AnthropicIcon from "../../assets/anthropic_light.svg";
@@ -23,6 +36,9 @@ import IntenserpIcon from "../../assets/intenserp.png";
 const ICON_MAP: Record<string, ReactElement> = {
   openai: <img src={OpenAIIcon} alt="OpenAI" className="h-6 w-6" />,
   anthropic: <img src={AnthropicIcon} alt="Anthropic" className="h-6 w-6" />,
+  "azure-openai": <img src={OpenAIIcon} alt="Azure OpenAI" className="h-6 w-6" />,
+  "aws-bedrock": <Cloud className="h-6 w-6 text-orange-300" />,
+  cohere: <Sparkles className="h-6 w-6 text-rose-300" />,
   openrouter: <img src={OpenRouterIcon} alt="OpenRouter" className="h-6 w-6" />,
   mistral: <img src={MistralAIIcon} alt="MistralAI" className="h-6 w-6" />,
   deepseek: <img src={DeepseekIcon} alt="Deepseek" className="h-6 w-6" />,
@@ -37,6 +53,9 @@ const ICON_MAP: Record<string, ReactElement> = {
   chutes: <img src={ChutesAIIcon} alt="Chutes" className="h-6 w-6" />,
   ollama: <img src={OllamaIcon} alt="Ollama" className="h-6 w-6" />,
   lmstudio: <img src={LMStudioIcon} alt="LM Studio" className="h-6 w-6" />,
+  koboldcpp: <Feather className="h-6 w-6 text-lime-300" />,
+  "text-completion": <FileText className="h-6 w-6 text-amber-300" />,
+  mock: <FlaskConical className="h-6 w-6 text-teal-300" />,
   intenserp: <img src={IntenserpIcon} alt="IntenseRP Next" className="h-6 w-6" />,
   llamacpp: <img src={LlamaCppIcon} alt="llama.cpp" className="h-6 w-6 object-contain" />,
   "lettuce-host": <EthernetPort className="h-6 w-6 text-emerald-300" />,
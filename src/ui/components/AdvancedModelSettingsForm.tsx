@@ -46,6 +46,12 @@ export const ADVANCED_OLLAMA_MIROSTAT_TAU_RANGE = { min: 0, max: 10 };
 export const ADVANCED_OLLAMA_MIROSTAT_ETA_RANGE = { min: 0, max: 1 };
 export const ADVANCED_OLLAMA_REPEAT_PENALTY_RANGE = { min: 0, max: 2 };
 export const ADVANCED_OLLAMA_SEED_RANGE = { min: 0, max: 2_147_483_647 };
+export const ADVANCED_KOBOLD_REP_PEN_RANGE = { min: 1, max: 3 };
+export const ADVANCED_KOBOLD_REP_PEN_RANGE_RANGE = { min: 0, max: 262_144 };
+export const ADVANCED_KOBOLD_REP_PEN_SLOPE_RANGE = { min: 0, max: 10 };
+export const ADVANCED_KOBOLD_UNIT_RANGE = { min: 0, max: 1 };
+export const ADVANCED_KOBOLD_SMOOTHING_FACTOR_RANGE = { min: 0, max: 10 };
+export const ADVANCED_KOBOLD_SMOOTHING_CURVE_RANGE = { min: 1, max: 10 };
 
 function clampValue(value: number, min: number, max: number) {
   return Math.min(Math.max(value, min), max);
@@ -76,6 +82,23 @@ export function sanitizeAdvancedModelSettings(input: AdvancedModelSettings): Adv
     return cleaned.length > 0 ? cleaned : null;
   };
 
+  // Unlike normalizeStop, keeps surrounding whitespace such as a leading newline.
+  const normalizeExactList = (value: unknown): string[] | null => {
+    if (!Array.isArray(value)) return null;
+    const cleaned = value.filter(
+      (entry): entry is string => typeof entry === "string" && entry.length > 0,
+    );
+    return cleaned.length > 0 ? Array.from(new Set(cleaned)) : null;
+  };
+
+  const normalizeLogitBias = (value: unknown): Record<string, number> | null => {
+    if (!value || typeof value !== "object" || Array.isArray(value)) return null;
+    const entries = Object.entries(value as Record<string, unknown>)
+      .filter(([key, bias]) => key.length > 0 && typeof bias === "number" && Number.isFinite(bias))
+      .map(([key, bias]) => [key, clampValue(bias as number, -100, 100)] as const);
+    return entries.length > 0 ? Object.fromEntries(entries) : null;
+  };
+
   const normalizeStringList = (value: unknown): string[] | null => {
     if (!Array.isArray(value)) return null;
     const cleaned = value
@@ -176,6 +199,51 @@ export function sanitizeAdvancedModelSettings(input: AdvancedModelSettings): Adv
     ),
     ollamaSeed: sanitize(input.ollamaSeed, ADVANCED_OLLAMA_SEED_RANGE, true),
     ollamaStop: normalizeStop(input.ollamaStop),
+    koboldRepPen: sanitize(input.koboldRepPen, ADVANCED_KOBOLD_REP_PEN_RANGE, false),
+    koboldRepPenRange: sanitize(input.koboldRepPenRange, ADVANCED_KOBOLD_REP_PEN_RANGE_RANGE, true),
+    koboldRepPenSlope: sanitize(
+      input.koboldRepPenSlope,
+      ADVANCED_KOBOLD_REP_PEN_SLOPE_RANGE,
+      false,
+    ),
+    koboldMinP: sanitize(input.koboldMinP, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldTopA: sanitize(input.koboldTopA, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldTypical: sanitize(input.koboldTypical, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldTfs: sanitize(input.koboldTfs, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldDryMultiplier: sanitize(
+      input.koboldDryMultiplier,
+      ADVANCED_LLAMA_DRY_MULTIPLIER_RANGE,
+      false,
+    ),
+    koboldDryBase: sanitize(input.koboldDryBase, ADVANCED_LLAMA_DRY_BASE_RANGE, false),
+    koboldDryAllowedLength: sanitize(
+      input.koboldDryAllowedLength,
+      ADVANCED_LLAMA_DRY_ALLOWED_LENGTH_RANGE,
+      true,
+    ),
+    koboldDryPenaltyLastN: sanitize(
+      input.koboldDryPenaltyLastN,
+      ADVANCED_LLAMA_DRY_PENALTY_LAST_N_RANGE,
+      true,
+    ),
+    koboldDrySequenceBreakers: normalizeStringList(input.koboldDrySequenceBreakers),
+    koboldXtcThreshold: sanitize(input.koboldXtcThreshold, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldXtcProbability: sanitize(input.koboldXtcProbability, ADVANCED_KOBOLD_UNIT_RANGE, false),
+    koboldSmoothingFactor: sanitize(
+      input.koboldSmoothingFactor,
+      ADVANCED_KOBOLD_SMOOTHING_FACTOR_RANGE,
+      false,
+    ),
+    koboldSmoothingCurve: sanitize(
+      input.koboldSmoothingCurve,
+      ADVANCED_KOBOLD_SMOOTHING_CURVE_RANGE,
+      false,
+    ),
+    koboldSamplerOrder:
+      input.koboldSamplerOrder && input.koboldSamplerOrder.length > 0
+        ? input.koboldSamplerOrder
+        : null,
+    koboldBannedTokens: normalizeStop(input.koboldBannedTokens),
     reasoningEnabled: input.reasoningEnabled ?? null,
     reasoningEffort: input.reasoningEffort ?? null,
     reasoningBudgetTokens: sanitize(
@@ -185,6 +253,12 @@ export function sanitizeAdvancedModelSettings(input: AdvancedModelSettings): Adv
     ),
     promptCachingEnabled: input.promptCachingEnabled ?? null,
     promptCachingTtl: input.promptCachingTtl ?? "5min",
+    tokenizer: input.tokenizer ?? null,
+    tokenizerRepo: input.tokenizerRepo?.trim() || null,
+    modelCapabilities: input.modelCapabilities ?? null,
+    stopSequences: normalizeExactList(input.stopSequences),
+    logitBias: normalizeLogitBias(input.logitBias),
+    bannedStrings: normalizeExactList(input.bannedStrings),
   };
 }
 
@@ -81,7 +81,7 @@ export function PromptTemplateEditor({ template, onClose, onSave }: PromptTempla
     if (!previewCharacterId) return;
     setPreviewing(true);
     try {
-      const rendered = await renderPromptPreview(content, {
+      const { rendered } = await renderPromptPreview(content, {
         characterId: previewCharacterId,
         personaId: previewPersonaId ?? undefined,
       });
@@ -16,6 +16,7 @@ import {
   Image,
   TriangleAlert,
   HeartPulse,
+  Quote,
   type LucideIcon,
 } from "lucide-react";
 import { BottomMenu } from "../../../components/BottomMenu";
@@ -25,9 +26,14 @@ import type {
   Model,
   ImageAttachment,
   CompanionTurnEffect,
+  MessageCitations,
 } from "../../../../core/storage/schemas";
 import { cn, radius } from "../../../design-tokens";
-import { getMessageCompanionEffect, readSettings } from "../../../../core/storage/repo";
+import {
+  getMessageCitations,
+  getMessageCompanionEffect,
+  readSettings,
+} from "../../../../core/storage/repo";
 import { useI18n } from "../../../../core/i18n/context";
 import { isDevelopmentMode } from "../../../../core/utils/env";
 import { useSessionAttachments } from "../../../hooks/useSessionAttachment";
@@ -212,6 +218,7 @@ export function MessageActionsBottomSheet({
   const [companionEffect, setCompanionEffect] = useState<CompanionTurnEffect | null>(null);
   const [companionEffectLoading, setCompanionEffectLoading] = useState(false);
   const [companionEffectError, setCompanionEffectError] = useState<string | null>(null);
+  const [citations, setCitations] = useState<MessageCitations | null>(null);
   const isSceneMessage = messageAction?.message.role === "scene";
   const isVisibleSystemMessage =
     messageAction?.message.role === "system" && Boolean(messageAction.message.visibleInChat);
@@ -311,10 +318,37 @@ export function MessageActionsBottomSheet({
     };
   }, [sessionId, companionEffectMessageId]);
 
+  const citationsMessageId =
+    sessionId && messageAction?.message.role === "assistant" ? messageAction.message.id : null;
+
+  useEffect(() => {
+    if (!sessionId || !citationsMessageId) {
+      setCitations(null);
+      return;
+    }
+    let cancelled = false;
+    getMessageCitations(sessionId, citationsMessageId)
+      .then((stored) => {
+        if (!cancelled) setCitations(stored);
+      })
+      .catch((error) => {
+        console.error("Failed to load citations", error);
+        if (!cancelled) setCitations(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [sessionId, citationsMessageId]);
+
   const modelLabel =
     modelName ?? (settings ? t("chats.actions.unknownModel") : t("chats.actions.loadingModel"));
   const usedFallback = Boolean(messageAction?.message.fallbackFromModelId);
   const usedLorebookEntries = messageAction?.message.usedLorebookEntries ?? [];
+  const citedSources =
+    citations &&
+    (!citations.variantId || citations.variantId === messageAction?.message.selectedVariantId)
+      ? citations.citations
+      : [];
   const isLlamaMessage = modelProviderId === "llamacpp";
   const firstTokenMs = messageAction?.message.usage?.firstTokenMs;
   const tokensPerSecond = messageAction?.message.usage?.tokensPerSecond;
@@ -613,6 +647,31 @@ export function MessageActionsBottomSheet({
                 </div>
               )}
 
+              {citedSources.length > 0 && (
+                <div className="mb-3 p-3 rounded-lg border border-amber-500/20 bg-amber-500/10">
+                  <div className="flex items-center gap-2 mb-2">
+                    <Quote size={14} className="text-amber-300" />
+                    <span className="text-xs font-medium text-amber-200">
+                      {t("chats.actions.sources")}
+                    </span>
+                  </div>
+                  <p className="text-xs text-amber-100/90 mb-2">{t("chats.actions.sourcesDesc")}</p>
+                  <div className="space-y-1">
+                    {citedSources.map((citation, idx) => (
+                      <div
+                        key={`${citation.start ?? idx}-${idx}`}
+                        className="text-xs text-amber-100/85 rounded bg-black/20 border border-amber-500/10 px-2 py-1.5"
+                      >
+                        <p className="italic">"{citation.text}"</p>
+                        <p className="mt-1 text-amber-200/70">
+                          {citation.sources.map((source) => source.title || source.id).join(", ")}
+                        </p>
+                      </div>
+                    ))}
+                  </div>
+                </div>
+              )}
+
               {/* Basic actions */}
               {canEdit && (
                 <ActionRow
@@ -19,12 +19,13 @@ import {
   checkEmbeddingModel,
   getHostApiStatus,
 } from "../../../core/storage/repo";
-import type { Settings } from "../../../core/storage/schemas";
+import type { NetworkSettings, Settings } from "../../../core/storage/schemas";
 import { cn, typography, spacing, interactive } from "../../design-tokens";
 import { EmbeddingDownloadPrompt } from "../../components/EmbeddingDownloadPrompt";
 import { openDocs, DOCS } from "../../../core/utils/docs";
 import { useI18n } from "../../../core/i18n/context";
 import { Switch } from "../../components/Switch";
+import { NetworkSettingsFields } from "./components/NetworkSettingsFields";
 
 type DocsKey = keyof typeof DOCS;
 
@@ -299,6 +300,7 @@ export function AdvancedPage() {
   const [manualWindow, setManualWindow] = useState<number | null>(50);
   const [hostApiEnabled, setHostApiEnabled] = useState(false);
   const [hostApiRunning, setHostApiRunning] = useState(false);
+  const [network, setNetwork] = useState<NetworkSettings | null>(null);
 
   const getAdvancedSettings = (settings: Settings) => {
     const advanced = settings.advancedSettings ?? {
@@ -325,6 +327,7 @@ export function AdvancedPage() {
         setHelpMeReplyEnabled(settings.advancedSettings?.helpMeReplyEnabled ?? true);
         setManualWindow(settings.advancedSettings?.manualModeContextWindow ?? 50);
         setHostApiEnabled(settings.advancedSettings?.hostApi?.enabled ?? false);
+        setNetwork(settings.advancedSettings?.network ?? null);
 
         try {
           const status = await getHostApiStatus();
@@ -400,6 +403,10 @@ export function AdvancedPage() {
           contextEnrichmentEnabled: true,
           recursiveMemoryLoops: false,
           recursiveMemoryLoopHardCap: 20,
+          entityGraphEnabled: false,
+          entityGraphCharacterScope: false,
+          hybridRecencyBoost: 0,
+          hybridPinBoost: 0,
         };
       }
 
@@ -469,6 +476,18 @@ export function AdvancedPage() {
     }
   };
 
+  const handleNetworkChange = async (next: NetworkSettings) => {
+    setNetwork(next);
+    try {
+      const settings = await readSettings();
+      const advanced = getAdvancedSettings(settings);
+      advanced.network = next;
+      await saveAdvancedSettings(advanced);
+    } catch (err) {
+      console.error("Failed to save network settings:", err);
+    }
+  };
+
   if (isLoading) {
     return (
       <div className="flex h-full items-center justify-center">
@@ -742,6 +761,17 @@ export function AdvancedPage() {
             onNavigate={() => navigate("/settings/advanced/host-api")}
             colorScheme="blue"
           />
+
+          <div className={cn("rounded-xl border px-4 py-4", "border-fg/10 bg-fg/5")}>
+            <span className={cn(typography.body.size, "font-medium text-fg")}>
+              Proxy & certificates
+            </span>
+            <p className="mt-0.5 mb-3 text-[11px] text-fg/45">
+              Default for chat, image, voice, model browser and discovery requests. Providers
+              can override it in their own network settings.
+            </p>
+            <NetworkSettingsFields value={network} onChange={handleNetworkChange} />
+          </div>
         </SettingsSection>
 
       </section>
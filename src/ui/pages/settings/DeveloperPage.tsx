@@ -26,6 +26,7 @@ import {
 import type { Character, StoredMessage } from "../../../core/storage/schemas";
 import { storageBridge } from "../../../core/storage/files";
 import { clearTooltipState } from "../../../core/storage/appState";
+import { RequestRecordingsSection } from "./components/RequestRecordingsSection";
 
 export function DeveloperPage() {
   const { t } = useI18n();
@@ -1131,6 +1132,11 @@ export function DeveloperPage() {
             onClick={resetAllTours}
           />
 
+          <h2 className={cn(typography.h2.size, typography.h2.weight, "text-fg mb-3 mt-6")}>
+            Provider debugging
+          </h2>
+          <RequestRecordingsSection />
+
           <h2 className={cn(typography.h2.size, typography.h2.weight, "text-fg mb-3 mt-6")}>
             {t("developer.sectionTitles.crashTesting")}
           </h2>
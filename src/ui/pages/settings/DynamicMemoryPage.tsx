@@ -67,6 +67,10 @@ const DEFAULT_DYNAMIC_MEMORY_SETTINGS: DynamicMemorySettings = {
   contextEnrichmentEnabled: true,
   recursiveMemoryLoops: false,
   recursiveMemoryLoopHardCap: 20,
+  entityGraphEnabled: false,
+  entityGraphCharacterScope: false,
+  hybridRecencyBoost: 0,
+  hybridPinBoost: 0,
 };
 
 type MemoryPreset = "minimal" | "balanced" | "comprehensive" | "custom";
@@ -82,6 +86,10 @@ const PRESETS: Record<
     | "contextEnrichmentEnabled"
     | "recursiveMemoryLoops"
     | "recursiveMemoryLoopHardCap"
+    | "entityGraphEnabled"
+    | "entityGraphCharacterScope"
+    | "hybridRecencyBoost"
+    | "hybridPinBoost"
     | "deleteConfidenceDefault"
     | "maxHardDeleteRatioPerCycle"
   >
@@ -911,7 +919,7 @@ export function DynamicMemoryPage() {
                             <div className="text-[11px] font-medium text-fg/90">
                               {t("dynamicMemory.page.retrievalMode")}
                             </div>
-                            <div className="grid grid-cols-2 gap-2">
+                            <div className="grid grid-cols-3 gap-2">
                               <button
                                 onClick={() => {
                                   if (activeTab === "direct") {
@@ -946,12 +954,67 @@ export function DynamicMemoryPage() {
                               >
                                 {t("dynamicMemory.page.retrievalModeCosine")}
                               </button>
+                              <button
+                                onClick={() => {
+                                  if (activeTab === "direct") {
+                                    handleDirectSettingChange("retrievalStrategy", "hybrid");
+                                  } else {
+                                    handleGroupSettingChange("retrievalStrategy", "hybrid");
+                                  }
+                                }}
+                                className={cn(
+                                  "rounded-lg border px-3 py-2 text-xs font-medium transition-colors",
+                                  currentSettings.retrievalStrategy === "hybrid"
+                                    ? "border-info/50 bg-info/20 text-info"
+                                    : "border-fg/10 bg-fg/5 text-fg/60 hover:border-fg/20",
+                                )}
+                              >
+                                {t("dynamicMemory.page.retrievalModeHybrid")}
+                              </button>
                             </div>
                             <p className="text-[11px] text-fg/45">
                               {t("dynamicMemory.page.retrievalModeDescription")}
                             </p>
                           </div>
 
+                          {currentSettings.retrievalStrategy === "hybrid" && (
+                            <SettingRow
+                              label={t("dynamicMemory.page.hybridRecencyBoost")}
+                              description={t("dynamicMemory.page.hybridRecencyBoostDescription")}
+                              value={currentSettings.hybridRecencyBoost}
+                              min={0}
+                              max={1}
+                              step={0.05}
+                              decimals={2}
+                              onChange={(val) => {
+                                if (activeTab === "direct") {
+                                  handleDirectSettingChange("hybridRecencyBoost", val);
+                                } else {
+                                  handleGroupSettingChange("hybridRecencyBoost", val);
+                                }
+                              }}
+                            />
+                          )}
+
+                          {currentSettings.retrievalStrategy === "hybrid" && (
+                            <SettingRow
+                              label={t("dynamicMemory.page.hybridPinBoost")}
+                              description={t("dynamicMemory.page.hybridPinBoostDescription")}
+                              value={currentSettings.hybridPinBoost}
+                              min={0}
+                              max={1}
+                              step={0.05}
+                              decimals={2}
+                              onChange={(val) => {
+                                if (activeTab === "direct") {
+                                  handleDirectSettingChange("hybridPinBoost", val);
+                                } else {
+                                  handleGroupSettingChange("hybridPinBoost", val);
+                                }
+                              }}
+                            />
+                          )}
+
                           {/* Retrieval Limit */}
                           <SettingRow
                             label={t("dynamicMemory.page.retrievalLimit")}
@@ -62,12 +62,18 @@ import {
 } from "lucide-react";
 import { ProviderParameterSupportInfo } from "../../components/ProviderParameterSupportInfo";
 import { LlamaSamplerOrderEditor } from "../../components/LlamaSamplerOrderEditor";
+import { ModelCapabilitiesSummary } from "./components/ModelCapabilitiesSummary";
+import { OutputControlsFields } from "./components/OutputControlsFields";
 import { toast } from "../../components/toast";
 import { useModelEditorController } from "./hooks/useModelEditorController";
 import { useNavigationManager } from "../../navigation";
 import { useSearchParams, useNavigate } from "react-router-dom";
 import { addOrUpdateModel } from "../../../core/storage/repo";
-import type { LlamaLastRuntimeReport, ReasoningSupport } from "../../../core/storage/schemas";
+import type {
+  LlamaLastRuntimeReport,
+  ModelCapabilities,
+  ReasoningSupport,
+} from "../../../core/storage/schemas";
 import {
   getProviderReasoningSupport,
   getProviderCachingSupport,
@@ -422,6 +428,7 @@ export function EditModelPage() {
       saving,
       verifying,
       fetchingModels,
+      probingCapabilities,
       fetchedModels,
       error,
       providers,
@@ -436,6 +443,8 @@ export function EditModelPage() {
     handleModelNameChange,
     handleProviderSelection,
     setModelAdvancedDraft,
+    applyModelCapabilities,
+    detectModelCapabilities,
     handleTemperatureChange,
     handleTopPChange,
     handleMaxTokensChange,
@@ -500,6 +509,7 @@ export function EditModelPage() {
   const isOnboardingReturnFlow = !!returnTo?.startsWith("/onboarding");
   const isLocalModel = editorModel?.providerId === "llamacpp";
   const isOllamaModel = editorModel?.providerId === "ollama";
+  const isKoboldModel = editorModel?.providerId === "koboldcpp";
   const llamaRuntimeReport = modelAdvancedDraft.llamaLastRuntimeReport ?? null;
   const llamaRuntimeFacts = useMemo(() => {
     if (!llamaRuntimeReport) {
@@ -658,6 +668,7 @@ export function EditModelPage() {
       syncImageInputScope(model.path);
     } else {
       handleModelNameChange(model.path);
+      void detectModelCapabilities(model.path);
       if (!editorModel?.displayName?.trim()) {
         const cleanName = deriveDisplayNameFromPath(model.filename);
         handleDisplayNameChange(cleanName);
@@ -676,6 +687,7 @@ export function EditModelPage() {
       if (!selected || typeof selected !== "string") return;
 
       handleModelNameChange(selected);
+      void detectModelCapabilities(selected);
       if (!editorModel?.displayName?.trim()) {
         handleDisplayNameChange(deriveDisplayNameFromPath(selected));
       }
@@ -1300,12 +1312,80 @@ export function EditModelPage() {
     editorModel?.providerLabel ||
     editorModel?.providerId ||
     t("editModel.setup.selectPlatform");
-  const hasRuntimePanel = isLocalModel || isOllamaModel;
+  const hasRuntimePanel = isLocalModel || isOllamaModel || isKoboldModel;
   const runtimePanelTitle = isLocalModel
     ? "llama.cpp"
     : isOllamaModel
       ? "Ollama"
-      : t("editModel.sections.runtime");
+      : isKoboldModel
+        ? "KoboldCpp"
+        : t("editModel.sections.runtime");
+  const koboldNumberFields: {
+    key:
+      | "koboldRepPen"
+      | "koboldRepPenRange"
+      | "koboldRepPenSlope"
+      | "koboldMinP"
+      | "koboldTopA"
+      | "koboldTypical"
+      | "koboldTfs"
+      | "koboldDryMultiplier"
+      | "koboldDryBase"
+      | "koboldDryAllowedLength"
+      | "koboldDryPenaltyLastN"
+      | "koboldXtcThreshold"
+      | "koboldXtcProbability"
+      | "koboldSmoothingFactor"
+      | "koboldSmoothingCurve";
+    label: string;
+    hint: string;
+    step: number;
+    integer?: boolean;
+  }[] = [
+    { key: "koboldRepPen", label: "Rep Pen", hint: "Repetition penalty", step: 0.01 },
+    {
+      key: "koboldRepPenRange",
+      label: "Rep Pen Range",
+      hint: "Tokens penalized",
+      step: 1,
+      integer: true,
+    },
+    { key: "koboldRepPenSlope", label: "Rep Pen Slope", hint: "Penalty falloff", step: 0.1 },
+    { key: "koboldMinP", label: "Min P", hint: "Min-p sampling", step: 0.01 },
+    { key: "koboldTopA", label: "Top A", hint: "Top-a sampling", step: 0.01 },
+    { key: "koboldTypical", label: "Typical", hint: "Typical sampling", step: 0.01 },
+    { key: "koboldTfs", label: "TFS", hint: "Tail-free", step: 0.01 },
+    { key: "koboldDryMultiplier", label: "DRY Multiplier", hint: "0 disables DRY", step: 0.05 },
+    { key: "koboldDryBase", label: "DRY Base", hint: "Penalty growth", step: 0.05 },
+    {
+      key: "koboldDryAllowedLength",
+      label: "DRY Allowed Length",
+      hint: "Repeats tolerated",
+      step: 1,
+      integer: true,
+    },
+    {
+      key: "koboldDryPenaltyLastN",
+      label: "DRY Range",
+      hint: "Tokens scanned",
+      step: 1,
+      integer: true,
+    },
+    { key: "koboldXtcThreshold", label: "XTC Threshold", hint: "Exclude top choices", step: 0.01 },
+    {
+      key: "koboldXtcProbability",
+      label: "XTC Probability",
+      hint: "0 disables XTC",
+      step: 0.01,
+    },
+    {
+      key: "koboldSmoothingFactor",
+      label: "Smoothing Factor",
+      hint: "Quadratic sampling",
+      step: 0.01,
+    },
+    { key: "koboldSmoothingCurve", label: "Smoothing Curve", hint: "Curve shape", step: 0.1 },
+  ];
   const effectiveEditorViewMode: EditorViewMode = isMobile ? "simple" : editorViewMode;
   const activeDetailPanel =
     effectiveEditorViewMode === "advanced" ? activeAdvancedPanel : activeSimplePanel;
@@ -1371,7 +1451,17 @@ export function EditModelPage() {
         ]
           .filter(Boolean)
           .join(" • ") || t("editModel.summaries.runtimeOllama")
-      : "";
+      : isKoboldModel
+        ? [
+            modelAdvancedDraft.koboldRepPen != null
+              ? `Rep Pen ${modelAdvancedDraft.koboldRepPen.toFixed(2)}`
+              : null,
+            modelAdvancedDraft.koboldDryMultiplier ? "DRY" : null,
+            modelAdvancedDraft.koboldXtcProbability ? "XTC" : null,
+          ]
+            .filter(Boolean)
+            .join(" • ") || "KoboldCpp samplers"
+        : "";
   const reasoningSummary = isAutoReasoning
     ? t("editModel.summaries.reasoningAlwaysEnabled")
     : modelAdvancedDraft.reasoningEnabled === false
@@ -1624,13 +1714,18 @@ export function EditModelPage() {
     updateEditorModel({ [key]: next } as any);
   };
 
-  const handleSelectModel = (modelId: string, displayName?: string) => {
+  const handleSelectModel = (
+    modelId: string,
+    displayName?: string,
+    capabilities?: ModelCapabilities,
+  ) => {
     handleModelNameChange(modelId);
     if (displayName) {
       handleDisplayNameChange(displayName);
     } else {
       handleDisplayNameChange(modelId);
     }
+    applyModelCapabilities(capabilities);
     setShowModelSelector(false);
   };
 
@@ -2114,7 +2209,7 @@ export function EditModelPage() {
                               )}
                               onSelectModel={(modelId) => {
                                 const model = filteredModels.find((item) => item.id === modelId);
-                                handleSelectModel(modelId, model?.displayName);
+                                handleSelectModel(modelId, model?.displayName, model?.capabilities);
                               }}
                             />
                           </>
@@ -2641,289 +2736,295 @@ export function EditModelPage() {
                                 </div>
                               </div>
                             ) : (
-                              <div className="grid grid-cols-1 gap-x-6 gap-y-8 md:grid-cols-2 xl:grid-cols-3 xl:gap-x-8">
-                                {/* Temperature */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Temperature
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.temperature")}
-                                        </span>
+                              <>
+                                <div className="grid grid-cols-1 gap-x-6 gap-y-8 md:grid-cols-2 xl:grid-cols-3 xl:gap-x-8">
+                                  {/* Temperature */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Temperature
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.temperature")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "temperature")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.temperature")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "temperature")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.temperature")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.temperature?.toFixed(2) ?? "0.70"}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="decimal"
+                                      min={ADVANCED_TEMPERATURE_RANGE.min}
+                                      max={ADVANCED_TEMPERATURE_RANGE.max}
+                                      step={0.01}
+                                      value={modelAdvancedDraft.temperature ?? ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        handleTemperatureChange(raw === "" ? null : Number(raw));
+                                      }}
+                                      placeholder={t("editModel.placeholders.temperature")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{ADVANCED_TEMPERATURE_RANGE.min}</span>
+                                      <span>{ADVANCED_TEMPERATURE_RANGE.max}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.temperature?.toFixed(2) ?? "0.70"}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="decimal"
-                                    min={ADVANCED_TEMPERATURE_RANGE.min}
-                                    max={ADVANCED_TEMPERATURE_RANGE.max}
-                                    step={0.01}
-                                    value={modelAdvancedDraft.temperature ?? ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      handleTemperatureChange(raw === "" ? null : Number(raw));
-                                    }}
-                                    placeholder={t("editModel.placeholders.temperature")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{ADVANCED_TEMPERATURE_RANGE.min}</span>
-                                    <span>{ADVANCED_TEMPERATURE_RANGE.max}</span>
                                   </div>
-                                </div>
 
-                                {/* Top P */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Top P
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.topP")}
-                                        </span>
+                                  {/* Top P */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Top P
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.topP")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "top-p")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.topP")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "top-p")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.topP")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.topP?.toFixed(2) ?? "1.00"}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="decimal"
+                                      min={ADVANCED_TOP_P_RANGE.min}
+                                      max={ADVANCED_TOP_P_RANGE.max}
+                                      step={0.01}
+                                      value={modelAdvancedDraft.topP ?? ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        handleTopPChange(raw === "" ? null : Number(raw));
+                                      }}
+                                      placeholder={t("editModel.placeholders.topP")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{ADVANCED_TOP_P_RANGE.min}</span>
+                                      <span>{ADVANCED_TOP_P_RANGE.max}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.topP?.toFixed(2) ?? "1.00"}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="decimal"
-                                    min={ADVANCED_TOP_P_RANGE.min}
-                                    max={ADVANCED_TOP_P_RANGE.max}
-                                    step={0.01}
-                                    value={modelAdvancedDraft.topP ?? ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      handleTopPChange(raw === "" ? null : Number(raw));
-                                    }}
-                                    placeholder={t("editModel.placeholders.topP")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{ADVANCED_TOP_P_RANGE.min}</span>
-                                    <span>{ADVANCED_TOP_P_RANGE.max}</span>
                                   </div>
-                                </div>
 
-                                {/* Max Tokens */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Max Output Tokens
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.maxOutputTokens")}
-                                        </span>
+                                  {/* Max Tokens */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Max Output Tokens
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.maxOutputTokens")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "max-output-tokens")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.maxOutputTokens")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "max-output-tokens")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.maxOutputTokens")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.maxOutputTokens
+                                          ? modelAdvancedDraft.maxOutputTokens.toLocaleString()
+                                          : t("common.labels.auto")}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="numeric"
+                                      min={ADVANCED_MAX_TOKENS_RANGE.min}
+                                      max={ADVANCED_MAX_TOKENS_RANGE.max}
+                                      step={1}
+                                      value={modelAdvancedDraft.maxOutputTokens || ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        const next = raw === "" ? null : Number(raw);
+                                        handleMaxTokensChange(
+                                          next === null || !Number.isFinite(next) || next === 0
+                                            ? null
+                                            : Math.trunc(next),
+                                        );
+                                      }}
+                                      placeholder={t("common.labels.auto")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{t("common.labels.auto")}</span>
+                                      <span>{ADVANCED_MAX_TOKENS_RANGE.max.toLocaleString()}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.maxOutputTokens
-                                        ? modelAdvancedDraft.maxOutputTokens.toLocaleString()
-                                        : t("common.labels.auto")}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="numeric"
-                                    min={ADVANCED_MAX_TOKENS_RANGE.min}
-                                    max={ADVANCED_MAX_TOKENS_RANGE.max}
-                                    step={1}
-                                    value={modelAdvancedDraft.maxOutputTokens || ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      const next = raw === "" ? null : Number(raw);
-                                      handleMaxTokensChange(
-                                        next === null || !Number.isFinite(next) || next === 0
-                                          ? null
-                                          : Math.trunc(next),
-                                      );
-                                    }}
-                                    placeholder={t("common.labels.auto")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{t("common.labels.auto")}</span>
-                                    <span>{ADVANCED_MAX_TOKENS_RANGE.max.toLocaleString()}</span>
                                   </div>
-                                </div>
 
-                                {/* Top K */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Top K
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.topK")}
-                                        </span>
+                                  {/* Top K */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Top K
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.topK")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "top-k-if-supported")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.topK")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "top-k-if-supported")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.topK")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.topK ? modelAdvancedDraft.topK : "Auto"}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="numeric"
+                                      min={ADVANCED_TOP_K_RANGE.min}
+                                      max={ADVANCED_TOP_K_RANGE.max}
+                                      step={1}
+                                      value={modelAdvancedDraft.topK || ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        const next = raw === "" ? null : Number(raw);
+                                        handleTopKChange(
+                                          next === null || !Number.isFinite(next) || next === 0
+                                            ? null
+                                            : Math.trunc(next),
+                                        );
+                                      }}
+                                      placeholder={t("common.labels.auto")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{t("common.labels.auto")}</span>
+                                      <span>{ADVANCED_TOP_K_RANGE.max}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.topK ? modelAdvancedDraft.topK : "Auto"}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="numeric"
-                                    min={ADVANCED_TOP_K_RANGE.min}
-                                    max={ADVANCED_TOP_K_RANGE.max}
-                                    step={1}
-                                    value={modelAdvancedDraft.topK || ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      const next = raw === "" ? null : Number(raw);
-                                      handleTopKChange(
-                                        next === null || !Number.isFinite(next) || next === 0
-                                          ? null
-                                          : Math.trunc(next),
-                                      );
-                                    }}
-                                    placeholder={t("common.labels.auto")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{t("common.labels.auto")}</span>
-                                    <span>{ADVANCED_TOP_K_RANGE.max}</span>
                                   </div>
-                                </div>
 
-                                {/* Penalties - Frequency */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Frequency Penalty
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.frequencyPenalty")}
-                                        </span>
+                                  {/* Penalties - Frequency */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Frequency Penalty
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.frequencyPenalty")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "frequency-penalty")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.frequencyPenalty")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "frequency-penalty")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.frequencyPenalty")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.frequencyPenalty?.toFixed(2) ?? "0.00"}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="decimal"
+                                      min={ADVANCED_FREQUENCY_PENALTY_RANGE.min}
+                                      max={ADVANCED_FREQUENCY_PENALTY_RANGE.max}
+                                      step={0.01}
+                                      value={modelAdvancedDraft.frequencyPenalty ?? ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        handleFrequencyPenaltyChange(raw === "" ? null : Number(raw));
+                                      }}
+                                      placeholder={t("editModel.placeholders.zero")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{ADVANCED_FREQUENCY_PENALTY_RANGE.min}</span>
+                                      <span>{ADVANCED_FREQUENCY_PENALTY_RANGE.max}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.frequencyPenalty?.toFixed(2) ?? "0.00"}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="decimal"
-                                    min={ADVANCED_FREQUENCY_PENALTY_RANGE.min}
-                                    max={ADVANCED_FREQUENCY_PENALTY_RANGE.max}
-                                    step={0.01}
-                                    value={modelAdvancedDraft.frequencyPenalty ?? ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      handleFrequencyPenaltyChange(raw === "" ? null : Number(raw));
-                                    }}
-                                    placeholder={t("editModel.placeholders.zero")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{ADVANCED_FREQUENCY_PENALTY_RANGE.min}</span>
-                                    <span>{ADVANCED_FREQUENCY_PENALTY_RANGE.max}</span>
                                   </div>
-                                </div>
 
-                                {/* Penalties - Presence */}
-                                <div className="space-y-4">
-                                  <div className="flex items-center justify-between">
-                                    <div className="flex items-center gap-2">
-                                      <div className="space-y-0.5">
-                                        <span className="block text-[13px] font-medium text-fg/70">
-                                          Presence Penalty
-                                        </span>
-                                        <span className="block text-[13px] text-fg/40">
-                                          {t("editModel.generationDescriptions.presencePenalty")}
-                                        </span>
+                                  {/* Penalties - Presence */}
+                                  <div className="space-y-4">
+                                    <div className="flex items-center justify-between">
+                                      <div className="flex items-center gap-2">
+                                        <div className="space-y-0.5">
+                                          <span className="block text-[13px] font-medium text-fg/70">
+                                            Presence Penalty
+                                          </span>
+                                          <span className="block text-[13px] text-fg/40">
+                                            {t("editModel.generationDescriptions.presencePenalty")}
+                                          </span>
+                                        </div>
+                                        <button
+                                          type="button"
+                                          onClick={() => openDocs("models", "presence-penalty")}
+                                          className="text-fg/30 hover:text-fg/60 transition"
+                                          aria-label={t("editModel.help.presencePenalty")}
+                                        >
+                                          <HelpCircle size={12} />
+                                        </button>
                                       </div>
-                                      <button
-                                        type="button"
-                                        onClick={() => openDocs("models", "presence-penalty")}
-                                        className="text-fg/30 hover:text-fg/60 transition"
-                                        aria-label={t("editModel.help.presencePenalty")}
-                                      >
-                                        <HelpCircle size={12} />
-                                      </button>
+                                      <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
+                                        {modelAdvancedDraft.presencePenalty?.toFixed(2) ?? "0.00"}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode="decimal"
+                                      min={ADVANCED_PRESENCE_PENALTY_RANGE.min}
+                                      max={ADVANCED_PRESENCE_PENALTY_RANGE.max}
+                                      step={0.01}
+                                      value={modelAdvancedDraft.presencePenalty ?? ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        handlePresencePenaltyChange(raw === "" ? null : Number(raw));
+                                      }}
+                                      placeholder={t("editModel.placeholders.zero")}
+                                      className={numberInputClassName}
+                                    />
+                                    <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
+                                      <span>{ADVANCED_PRESENCE_PENALTY_RANGE.min}</span>
+                                      <span>{ADVANCED_PRESENCE_PENALTY_RANGE.max}</span>
                                     </div>
-                                    <span className="rounded-lg bg-surface-el/30 px-2 py-1 font-mono text-[13px] text-accent">
-                                      {modelAdvancedDraft.presencePenalty?.toFixed(2) ?? "0.00"}
-                                    </span>
-                                  </div>
-                                  <input
-                                    type="number"
-                                    inputMode="decimal"
-                                    min={ADVANCED_PRESENCE_PENALTY_RANGE.min}
-                                    max={ADVANCED_PRESENCE_PENALTY_RANGE.max}
-                                    step={0.01}
-                                    value={modelAdvancedDraft.presencePenalty ?? ""}
-                                    onChange={(e) => {
-                                      const raw = e.target.value;
-                                      handlePresencePenaltyChange(raw === "" ? null : Number(raw));
-                                    }}
-                                    placeholder={t("editModel.placeholders.zero")}
-                                    className={numberInputClassName}
-                                  />
-                                  <div className="flex justify-between text-[13px] text-fg/30 px-0.5 mt-1">
-                                    <span>{ADVANCED_PRESENCE_PENALTY_RANGE.min}</span>
-                                    <span>{ADVANCED_PRESENCE_PENALTY_RANGE.max}</span>
                                   </div>
                                 </div>
-                              </div>
+                                <OutputControlsFields
+                                  settings={modelAdvancedDraft}
+                                  onChange={(key, value) => updateSdSetting(key, value)}
+                                />
+                              </>
                             )}
                           </div>
                         )}
@@ -4614,6 +4715,130 @@ export function EditModelPage() {
                           </div>
                         )}
 
+                        {/* KoboldCpp Settings */}
+                        {activeDetailPanel === "runtime" && isKoboldModel && (
+                          <div className="space-y-4">
+                            <label className="text-[13px] font-bold tracking-wider text-fg/50 uppercase">
+                              Samplers (KoboldCpp)
+                            </label>
+
+                            <div className="space-y-6">
+                              <div className="grid grid-cols-2 gap-6 rounded-xl border border-fg/8 bg-surface-el/10 p-4">
+                                {koboldNumberFields.map((field) => (
+                                  <div key={field.key} className="space-y-4">
+                                    <div className="space-y-0.5">
+                                      <span className="block text-[13px] font-medium text-fg/70">
+                                        {field.label}
+                                      </span>
+                                      <span className="block text-[13px] text-fg/40">
+                                        {field.hint}
+                                      </span>
+                                    </div>
+                                    <input
+                                      type="number"
+                                      inputMode={field.integer ? "numeric" : "decimal"}
+                                      step={field.step}
+                                      value={modelAdvancedDraft[field.key] ?? ""}
+                                      onChange={(e) => {
+                                        const raw = e.target.value;
+                                        const next = raw === "" ? null : Number(raw);
+                                        updateSdSetting(
+                                          field.key,
+                                          next === null || !Number.isFinite(next)
+                                            ? null
+                                            : field.integer
+                                              ? Math.trunc(next)
+                                              : next,
+                                        );
+                                      }}
+                                      placeholder={t("common.labels.auto")}
+                                      className={numberInputClassName}
+                                    />
+                                  </div>
+                                ))}
+                              </div>
+
+                              <div className="space-y-4 rounded-xl border border-fg/8 bg-surface-el/10 p-4">
+                                <div className="space-y-0.5">
+                                  <span className="block text-[13px] font-medium text-fg/70">
+                                    Sampler Order
+                                  </span>
+                                  <span className="block text-[13px] text-fg/40">
+                                    Comma-separated sampler ids, e.g. 6,0,1,3,4,2,5
+                                  </span>
+                                </div>
+                                <input
+                                  type="text"
+                                  value={(modelAdvancedDraft.koboldSamplerOrder ?? []).join(",")}
+                                  onChange={(e) => {
+                                    const next = e.target.value
+                                      .split(",")
+                                      .map((s) => Number(s.trim()))
+                                      .filter((n) => Number.isInteger(n) && n >= 0 && n <= 6);
+                                    updateSdSetting(
+                                      "koboldSamplerOrder",
+                                      next.length > 0 ? next : null,
+                                    );
+                                  }}
+                                  placeholder={t("common.labels.auto")}
+                                  className={numberInputClassName}
+                                />
+                              </div>
+
+                              <div className="space-y-4 rounded-xl border border-fg/8 bg-surface-el/10 p-4">
+                                <div className="space-y-0.5">
+                                  <span className="block text-[13px] font-medium text-fg/70">
+                                    DRY Sequence Breakers
+                                  </span>
+                                  <span className="block text-[13px] text-fg/40">One per line</span>
+                                </div>
+                                <textarea
+                                  value={(modelAdvancedDraft.koboldDrySequenceBreakers ?? []).join(
+                                    "\n",
+                                  )}
+                                  onChange={(e) => {
+                                    const next = e.target.value
+                                      .split("\n")
+                                      .filter((s) => s.length > 0);
+                                    updateSdSetting(
+                                      "koboldDrySequenceBreakers",
+                                      next.length > 0 ? next : null,
+                                    );
+                                  }}
+                                  rows={3}
+                                  className={textAreaInputClassName}
+                                />
+                              </div>
+
+                              <div className="space-y-4 rounded-xl border border-fg/8 bg-surface-el/10 p-4">
+                                <div className="space-y-0.5">
+                                  <span className="block text-[13px] font-medium text-fg/70">
+                                    Banned Tokens
+                                  </span>
+                                  <span className="block text-[13px] text-fg/40">
+                                    Strings the model may never produce, one per line
+                                  </span>
+                                </div>
+                                <textarea
+                                  value={(modelAdvancedDraft.koboldBannedTokens ?? []).join("\n")}
+                                  onChange={(e) => {
+                                    const next = e.target.value
+                                      .split("\n")
+                                      .map((s) => s.trim())
+                                      .filter((s) => s.length > 0);
+                                    updateSdSetting(
+                                      "koboldBannedTokens",
+                                      next.length > 0 ? next : null,
+                                    );
+                                  }}
+                                  rows={3}
+                                  className={textAreaInputClassName}
+                                />
+                              </div>
+                            </div>
+                          </div>
+                        )}
+
                         {/* Reasoning Section (Thinking) */}
                         {activeDetailPanel === "reasoning" && showReasoningSection && (
                           <div className="space-y-4">
@@ -4939,6 +5164,16 @@ export function EditModelPage() {
                               </div>
                             )}
 
+                            {!isAutomatic1111Provider && (
+                              <ModelCapabilitiesSummary
+                                capabilities={modelAdvancedDraft.modelCapabilities}
+                                probing={probingCapabilities}
+                                isLocalModel={isLocalModel}
+                                disabled={!editorModel.name?.trim()}
+                                onDetect={() => void detectModelCapabilities()}
+                              />
+                            )}
+
                             <div className="grid grid-cols-1 gap-4 sm:grid-cols-2">
                               <div className="space-y-3">
                                 <p className="text-[13px] font-medium text-fg/72">
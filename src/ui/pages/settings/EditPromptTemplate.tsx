@@ -60,8 +60,10 @@ import {
   resetCompanionSoulWriterTemplate,
   renderPromptPreview,
   getPromptParameterEngine,
+  type PromptPreview,
 } from "../../../core/prompts/service";
-import { listCharacters, listPersonas } from "../../../core/storage";
+import { listCharacters, listPersonas, listSessionPreviews } from "../../../core/storage";
+import type { SessionPreview } from "../../../core/storage";
 import type {
   Character,
   Persona,
@@ -2285,7 +2287,10 @@ export function EditPromptTemplate() {
   const [personas, setPersonas] = useState<Persona[]>([]);
   const [previewCharacterId, setPreviewCharacterId] = useState<string | null>(null);
   const [previewPersonaId, setPreviewPersonaId] = useState<string | null>(null);
+  const [previewSessions, setPreviewSessions] = useState<SessionPreview[]>([]);
+  const [previewSessionId, setPreviewSessionId] = useState<string | null>(null);
   const [preview, setPreview] = useState<string>("");
+  const [previewTrim, setPreviewTrim] = useState<PromptPreview | null>(null);
   const [previewEntries, setPreviewEntries] = useState<SystemPromptEntry[]>([]);
   const [previewing, setPreviewing] = useState(false);
   const [previewMode, setPreviewMode] = useState<"rendered" | "raw">("rendered");
@@ -2478,6 +2483,24 @@ export function EditPromptTemplate() {
     loadData();
   }, []);
 
+  useEffect(() => {
+    setPreviewSessionId(null);
+    setPreviewTrim(null);
+    if (!previewCharacterId) {
+      setPreviewSessions([]);
+      return;
+    }
+    let cancelled = false;
+    listSessionPreviews(previewCharacterId, 20)
+      .then((sessions) => {
+        if (!cancelled) setPreviewSessions(sessions.filter((session) => !session.archived));
+      })
+      .catch((error) => console.error("Failed to load preview sessions:", error));
+    return () => {
+      cancelled = true;
+    };
+  }, [previewCharacterId]);
+
   useEffect(() => {
     const source = usesEntryEditor ? entriesToValidationSource(entries) : content;
     const missing = requiredVariables.filter((v) => !source.includes(v));
@@ -2844,6 +2867,7 @@ export function EditPromptTemplate() {
   async function handlePreview() {
     if (!previewCharacterId) return;
     setPreviewing(true);
+    setPreviewTrim(null);
     try {
       if (usesEntryEditor) {
         if (previewMode === "raw") {
@@ -2851,7 +2875,7 @@ export function EditPromptTemplate() {
         } else {
           const renderedEntries = await Promise.all(
             entries.map(async (entry) => {
-              const rendered = await renderPromptPreview(entry.content, {
+              const { rendered } = await renderPromptPreview(entry.content, {
                 characterId: previewCharacterId,
                 personaId: previewPersonaId ?? undefined,
               });
@@ -2859,13 +2883,25 @@ export function EditPromptTemplate() {
             }),
           );
           setPreviewEntries(renderedEntries);
+          if (previewSessionId) {
+            // The budget is measured against the whole system prompt, not per entry.
+            setPreviewTrim(
+              await renderPromptPreview(entries.map((entry) => entry.content).join("\n\n"), {
+                characterId: previewCharacterId,
+                sessionId: previewSessionId,
+                personaId: previewPersonaId ?? undefined,
+              }),
+            );
+          }
         }
       } else {
-        const rendered = await renderPromptPreview(content, {
+        const result = await renderPromptPreview(content, {
           characterId: previewCharacterId,
+          sessionId: previewSessionId ?? undefined,
           personaId: previewPersonaId ?? undefined,
         });
-        setPreview(rendered);
+        setPreview(result.rendered);
+        setPreviewTrim(previewSessionId ? result : null);
       }
     } catch (e) {
       console.error("Preview failed", e);
@@ -3017,6 +3053,26 @@ export function EditPromptTemplate() {
             </select>
           </div>
 
+          <select
+            value={previewSessionId ?? ""}
+            onChange={(e) => setPreviewSessionId(e.target.value || null)}
+            disabled={previewSessions.length === 0}
+            className={cn(
+              "w-full px-3 py-2",
+              radius.md,
+              "border border-fg/10 bg-fg/5",
+              "text-sm text-fg",
+              "focus:border-fg/20 focus:outline-none",
+            )}
+          >
+            <option value="">No chat history</option>
+            {previewSessions.map((session) => (
+              <option key={session.id} value={session.id}>
+                {session.title || "Untitled chat"} · {session.messageCount} messages
+              </option>
+            ))}
+          </select>
+
           <button
             onClick={handlePreview}
             disabled={!previewCharacterId || previewing}
@@ -3091,6 +3147,43 @@ export function EditPromptTemplate() {
           </pre>
         )}
       </div>
+
+      {/* Context Budget */}
+      {previewMode === "rendered" && previewTrim && (
+        <div className={cn(radius.lg, "border border-fg/10 bg-fg/5 p-3 space-y-2")}>
+          {previewTrim.contextTrim ? (
+            <>
+              <p className="text-xs text-fg/70">
+                Drops the {previewTrim.contextTrim.droppedMessageIds.length} oldest messages (
+                {previewTrim.contextTrim.droppedTokens} tokens) to fit{" "}
+                {previewTrim.contextTrim.contextLength} tokens.
+              </p>
+              <p className="text-[11px] text-fg/40">
+                Prompt {previewTrim.contextTrim.fixedTokens} · history{" "}
+                {previewTrim.contextTrim.historyTokensAfter}/
+                {previewTrim.contextTrim.historyTokensBefore} · reserved output{" "}
+                {previewTrim.contextTrim.reservedOutputTokens}
+                {previewTrim.contextTrim.summaryTokens > 0 &&
+                  ` · summary ${previewTrim.contextTrim.summaryTokens}`}
+              </p>
+              {previewTrim.contextTrim.overBudget && (
+                <p className="text-xs text-warning/80">
+                  Pinned messages and the prompt alone exceed the context length.
+                </p>
+              )}
+              {previewTrim.contextSummary && (
+                <pre className="whitespace-pre-wrap text-xs leading-relaxed text-fg/60 font-mono">
+                  {previewTrim.contextSummary}
+                </pre>
+              )}
+            </>
+          ) : (
+            <p className="text-xs text-fg/50">
+              No history is trimmed for this chat.
+            </p>
+          )}
+        </div>
+      )}
     </div>
   );
 
@@ -106,6 +106,8 @@ interface DevBenchmarkResults {
   averageSpeedupV4VsV3: number;
 }
 
+type MemoryAnnBenchmarkResults = Awaited<ReturnType<typeof storageBridge.runMemoryAnnBenchmark>>;
+
 type TabKey = "retrieval" | "separation" | "compare" | "benchmark";
 type RetrievalFilter = "all" | "failed" | "passed";
 
@@ -778,6 +780,94 @@ function BenchmarkPanel() {
   );
 }
 
+function MemoryAnnBenchmarkPanel() {
+  const [status, setStatus] = useState<"idle" | "running" | "done" | "failed">("idle");
+  const [results, setResults] = useState<MemoryAnnBenchmarkResults | null>(null);
+  const [err, setErr] = useState<string | null>(null);
+
+  const run = async () => {
+    setStatus("running");
+    setErr(null);
+    setResults(null);
+    try {
+      setResults(await storageBridge.runMemoryAnnBenchmark());
+      setStatus("done");
+    } catch (e) {
+      setErr(e instanceof Error ? e.message : String(e));
+      setStatus("failed");
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      <div className="flex flex-col gap-3 rounded-2xl border border-info/25 bg-info/5 p-4 sm:flex-row sm:items-center sm:justify-between">
+        <div>
+          <h3 className="text-sm font-semibold text-info">Memory index benchmark</h3>
+          <p className="mt-1 text-xs text-info/70">
+            Brute-force cosine vs the HNSW memory index on synthetic memory sets.
+          </p>
+        </div>
+        <button
+          onClick={run}
+          disabled={status === "running"}
+          className={cn(
+            "inline-flex items-center justify-center gap-2 rounded-xl border px-3 py-2 text-xs font-medium",
+            status === "running"
+              ? "cursor-not-allowed border-info/20 bg-info/10 text-info/60"
+              : "border-info/40 bg-info/15 text-info hover:bg-info/25",
+          )}
+        >
+          {status === "running" ? (
+            <>
+              <Loader2 className="h-3.5 w-3.5 animate-spin" />
+              Running...
+            </>
+          ) : (
+            <>
+              <Cpu className="h-3.5 w-3.5" />
+              Run benchmark
+            </>
+          )}
+        </button>
+      </div>
+
+      {err && (
+        <div className="rounded-xl border border-danger/25 bg-danger/10 p-3 text-xs text-danger/85">
+          {err}
+        </div>
+      )}
+
+      {results && (
+        <div className="grid gap-3 md:grid-cols-3">
+          {results.sizes.map((size) => (
+            <div
+              key={size.memoryCount}
+              className="rounded-xl border border-fg/10 bg-fg/5 p-4 space-y-3"
+            >
+              <div className="flex items-center justify-between">
+                <div className="text-sm font-semibold text-fg">
+                  {size.memoryCount.toLocaleString()} memories
+                </div>
+                <Pill>{results.dimensions}d</Pill>
+              </div>
+              <div className="grid grid-cols-2 gap-2">
+                <MiniStat label="Brute ms" value={size.bruteForceAverageMs.toFixed(2)} />
+                <MiniStat label="ANN ms" value={size.annAverageMs.toFixed(2)} />
+                <MiniStat label="Speedup" value={`${size.averageSpeedup.toFixed(1)}x`} />
+                <MiniStat label={`Recall@${results.topK}`} value={pct(size.recallAtK)} />
+              </div>
+              <div className="text-[11px] text-fg/45">
+                build {size.buildMs.toFixed(0)}ms · p95 {size.bruteForceP95Ms.toFixed(2)} /{" "}
+                {size.annP95Ms.toFixed(2)}ms
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
 function BenchmarkVariantCard({ v }: { v: BenchmarkVariant }) {
   return (
     <div className="rounded-xl border border-fg/10 bg-fg/5 p-4 space-y-3">
@@ -1160,7 +1250,12 @@ export function EmbeddingTestPage() {
                     <SeparationPanel separation={testResults.separation} technical={technical} />
                   )}
                   {tab === "compare" && <ComparePanel technical={technical} />}
-                  {tab === "benchmark" && isDevBuild && <BenchmarkPanel />}
+                  {tab === "benchmark" && isDevBuild && (
+                    <div className="space-y-6">
+                      <BenchmarkPanel />
+                      <MemoryAnnBenchmarkPanel />
+                    </div>
+                  )}
                 </motion.div>
               </AnimatePresence>
             </>
@@ -15,6 +15,8 @@ import { useSearchParams, useNavigate } from "react-router-dom";
 
 import { useProvidersPageController } from "./hooks/useProvidersPageController";
 import { AudioProviderEditor } from "./components/AudioProviderEditor";
+import { MockProviderFields } from "./components/MockProviderFields";
+import { NetworkSettingsFields } from "./components/NetworkSettingsFields";
 
 import type { ProviderCapabilitiesCamel } from "../../../core/providers/capabilities";
 import { getProviderIcon } from "../../../core/utils/providerIcons";
@@ -116,12 +118,23 @@ export function ProvidersPage() {
   const isHostProvider = !!editorProvider && editorProvider.providerId === "lettuce-host";
   const isLocalProvider =
     !!editorProvider &&
-    ["ollama", "lmstudio", "intenserp", "automatic1111"].includes(editorProvider.providerId);
+    ["ollama", "lmstudio", "koboldcpp", "text-completion", "intenserp", "automatic1111"].includes(
+      editorProvider.providerId,
+    );
+  const isTextCompletionProvider =
+    !!editorProvider && editorProvider.providerId === "text-completion";
+  const isKoboldCppProvider = !!editorProvider && editorProvider.providerId === "koboldcpp";
+  const usesInstructTemplate = isTextCompletionProvider || isKoboldCppProvider;
+  const isOpenAIProvider = !!editorProvider && editorProvider.providerId === "openai";
+  const isAzureProvider = !!editorProvider && editorProvider.providerId === "azure-openai";
+  const isBedrockProvider = !!editorProvider && editorProvider.providerId === "aws-bedrock";
+  const isMockProvider = !!editorProvider && editorProvider.providerId === "mock";
   const isCustomProvider =
     !!editorProvider &&
     (editorProvider.providerId === "custom" || editorProvider.providerId === "custom-anthropic");
   const showBaseUrl =
-    !!editorProvider && (isLocalProvider || isCustomProvider || isEngineProvider || isHostProvider);
+    !!editorProvider &&
+    (isLocalProvider || isCustomProvider || isEngineProvider || isHostProvider || isAzureProvider);
   const customConfig = (editorProvider?.config ?? {}) as Record<string, any>;
   const customFetchModelsEnabled = customConfig.fetchModelsEnabled === true;
   const providerStreamingEnabled = customConfig.streamingEnabled !== false;
@@ -138,7 +151,12 @@ export function ProvidersPage() {
     : selectedCapability
       ? selectedCapability.requiresApiKey
       : true;
-  const showApiKeyInput = providerRequiresApiKey && !isEngineProvider;
+  const showApiKeyInput = (providerRequiresApiKey || usesInstructTemplate) && !isEngineProvider;
+  const textCompletionTemplate = (customConfig.customTemplate ?? {}) as Record<string, any>;
+  const openAIResponsesMode = customConfig.apiMode === "responses";
+  const openAIBuiltinTools = (customConfig.builtinTools ?? []) as string[];
+  const bedrockUsesAccessKeys =
+    typeof customConfig.accessKeyId === "string" && customConfig.accessKeyId.trim() !== "";
   const showOfficialProviderStreamingToggle =
     !!editorProvider && !isCustomProvider && selectedCapability?.supportsStream === true;
   const visibleCapabilities = isMobile
@@ -477,7 +495,14 @@ export function ProvidersPage() {
                                   supportsStream: true,
                                   mergeSameRoleMessages: true,
                                 }
-                              : undefined,
+                              : providerId === "text-completion"
+                                ? {
+                                    completionEndpoint: "/v1/completions",
+                                    instructTemplate: "chatml",
+                                  }
+                                : providerId === "koboldcpp"
+                                  ? { instructTemplate: "chatml" }
+                                  : undefined,
                       });
                       setValidationError(null);
                     }}
@@ -502,7 +527,9 @@ export function ProvidersPage() {
                 </div>
                 {showApiKeyInput && (
                   <div>
-                    <label className="mb-1 block text-[11px] font-medium text-fg/70">API Key</label>
+                    <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                      {bedrockUsesAccessKeys ? "Secret Access Key" : "API Key"}
+                    </label>
                     <input
                       type="password"
                       value={apiKey}
@@ -510,7 +537,9 @@ export function ProvidersPage() {
                         setApiKey(e.target.value);
                         if (validationError) setValidationError(null);
                       }}
-                      placeholder="Enter your API key"
+                      placeholder={
+                        bedrockUsesAccessKeys ? "Enter your secret access key" : "Enter your API key"
+                      }
                       className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
                     />
                   </div>
@@ -532,7 +561,9 @@ export function ProvidersPage() {
                           ? "http://localhost:8000"
                           : isHostProvider
                             ? "http://192.168.1.10:3333"
-                            : editorProvider.providerId === "intenserp"
+                            : isAzureProvider
+                              ? "https://my-resource.openai.azure.com"
+                              : editorProvider.providerId === "intenserp"
                               ? "http://127.0.0.1:7777/v1"
                               : isLocalProvider
                                 ? "http://localhost:11434"
@@ -583,6 +614,291 @@ export function ProvidersPage() {
                     </div>
                   </div>
                 )}
+                {isOpenAIProvider && (
+                  <>
+                    <div>
+                      <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                        API Mode
+                      </label>
+                      <select
+                        value={openAIResponsesMode ? "responses" : "chat"}
+                        onChange={(e) =>
+                          updateEditorProvider({
+                            config: { ...editorProvider.config, apiMode: e.target.value },
+                          })
+                        }
+                        className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg focus:border-fg/30 focus:outline-none"
+                      >
+                        <option value="chat" className="bg-surface-el">
+                          Chat Completions
+                        </option>
+                        <option value="responses" className="bg-surface-el">
+                          Responses
+                        </option>
+                      </select>
+                      <p className="mt-1 text-[11px] text-fg/45">
+                        Responses returns reasoning summaries and carries reasoning between turns
+                      </p>
+                    </div>
+                    {openAIResponsesMode && (
+                      <div className="rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2">
+                        <div className="flex items-center justify-between gap-3">
+                          <div className="min-w-0">
+                            <p className="text-sm font-medium text-fg/80">Web Search</p>
+                            <p className="text-[11px] text-fg/45">
+                              Let the model use OpenAI's built-in web search tool
+                            </p>
+                          </div>
+                          <Switch
+                            id="openaiWebSearch"
+                            checked={openAIBuiltinTools.includes("web_search")}
+                            onChange={(next) =>
+                              updateEditorProvider({
+                                config: {
+                                  ...editorProvider.config,
+                                  builtinTools: next
+                                    ? [...openAIBuiltinTools, "web_search"]
+                                    : openAIBuiltinTools.filter((tool) => tool !== "web_search"),
+                                },
+                              })
+                            }
+                          />
+                        </div>
+                      </div>
+                    )}
+                  </>
+                )}
+                {isAzureProvider && (
+                  <div>
+                    <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                      API Version
+                    </label>
+                    <input
+                      type="text"
+                      value={(customConfig.apiVersion as string | undefined) ?? ""}
+                      onChange={(e) =>
+                        updateEditorProvider({
+                          config: { ...editorProvider.config, apiVersion: e.target.value },
+                        })
+                      }
+                      placeholder="2024-10-21"
+                      className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                    />
+                    <p className="mt-1 text-[11px] text-fg/45">
+                      Models are addressed by deployment name
+                    </p>
+                  </div>
+                )}
+                {isBedrockProvider && (
+                  <>
+                    <div>
+                      <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                        Region
+                      </label>
+                      <input
+                        type="text"
+                        value={(customConfig.region as string | undefined) ?? ""}
+                        onChange={(e) =>
+                          updateEditorProvider({
+                            config: { ...editorProvider.config, region: e.target.value },
+                          })
+                        }
+                        placeholder="us-east-1"
+                        className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                      />
+                    </div>
+                    <div>
+                      <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                        Access Key ID (optional)
+                      </label>
+                      <input
+                        type="text"
+                        value={(customConfig.accessKeyId as string | undefined) ?? ""}
+                        onChange={(e) =>
+                          updateEditorProvider({
+                            config: { ...editorProvider.config, accessKeyId: e.target.value },
+                          })
+                        }
+                        placeholder="AKIA..."
+                        className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                      />
+                      <p className="mt-1 text-[11px] text-fg/45">
+                        Leave empty to use a Bedrock API key instead of IAM credentials
+                      </p>
+                    </div>
+                    {bedrockUsesAccessKeys && (
+                      <div>
+                        <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                          Session Token (optional)
+                        </label>
+                        <input
+                          type="password"
+                          value={(customConfig.sessionToken as string | undefined) ?? ""}
+                          onChange={(e) =>
+                            updateEditorProvider({
+                              config: { ...editorProvider.config, sessionToken: e.target.value },
+                            })
+                          }
+                          placeholder="For temporary credentials"
+                          className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                        />
+                      </div>
+                    )}
+                  </>
+                )}
+                {isMockProvider && (
+                  <MockProviderFields
+                    value={customConfig.mock}
+                    onChange={(mock) =>
+                      updateEditorProvider({ config: { ...editorProvider.config, mock } })
+                    }
+                  />
+                )}
+                {usesInstructTemplate && (
+                  <>
+                    {isTextCompletionProvider && (
+                      <div>
+                        <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                          Completion Endpoint
+                        </label>
+                        <input
+                          type="text"
+                          value={
+                            (customConfig.completionEndpoint as string | undefined) ??
+                            "/v1/completions"
+                          }
+                          onChange={(e) =>
+                            updateEditorProvider({
+                              config: {
+                                ...editorProvider.config,
+                                completionEndpoint: e.target.value,
+                              },
+                            })
+                          }
+                          placeholder="/v1/completions"
+                          className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                        />
+                      </div>
+                    )}
+                    <div>
+                      <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                        Instruct Template
+                      </label>
+                      <select
+                        value={(customConfig.instructTemplate as string | undefined) ?? "chatml"}
+                        onChange={(e) =>
+                          updateEditorProvider({
+                            config: {
+                              ...editorProvider.config,
+                              instructTemplate: e.target.value,
+                            },
+                          })
+                        }
+                        className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg focus:border-fg/30 focus:outline-none"
+                      >
+                        <option value="chatml" className="bg-surface-el">
+                          ChatML
+                        </option>
+                        <option value="llama3" className="bg-surface-el">
+                          Llama 3
+                        </option>
+                        <option value="mistral" className="bg-surface-el">
+                          Mistral
+                        </option>
+                        <option value="alpaca" className="bg-surface-el">
+                          Alpaca
+                        </option>
+                        <option value="gemma" className="bg-surface-el">
+                          Gemma
+                        </option>
+                        <option value="custom" className="bg-surface-el">
+                          Custom
+                        </option>
+                      </select>
+                    </div>
+                    {customConfig.instructTemplate === "custom" && (
+                      <>
+                        {(
+                          [
+                            ["systemPrefix", "System Prefix"],
+                            ["systemSuffix", "System Suffix"],
+                            ["userPrefix", "User Prefix"],
+                            ["userSuffix", "User Suffix"],
+                            ["assistantPrefix", "Assistant Prefix"],
+                            ["assistantSuffix", "Assistant Suffix"],
+                          ] as const
+                        ).map(([key, label]) => (
+                          <div key={key}>
+                            <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                              {label}
+                            </label>
+                            <textarea
+                              rows={2}
+                              value={(textCompletionTemplate[key] as string | undefined) ?? ""}
+                              onChange={(e) =>
+                                updateEditorProvider({
+                                  config: {
+                                    ...editorProvider.config,
+                                    customTemplate: {
+                                      ...textCompletionTemplate,
+                                      [key]: e.target.value,
+                                    },
+                                  },
+                                })
+                              }
+                              className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 font-mono text-xs text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                            />
+                          </div>
+                        ))}
+                        <div>
+                          <label className="mb-1 block text-[11px] font-medium text-fg/70">
+                            Stop Strings (one per line)
+                          </label>
+                          <textarea
+                            rows={3}
+                            value={(
+                              (textCompletionTemplate.stopStrings as string[] | undefined) ?? []
+                            ).join("\n")}
+                            onChange={(e) =>
+                              updateEditorProvider({
+                                config: {
+                                  ...editorProvider.config,
+                                  customTemplate: {
+                                    ...textCompletionTemplate,
+                                    stopStrings: e.target.value.split("\n"),
+                                  },
+                                },
+                              })
+                            }
+                            className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 font-mono text-xs text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none"
+                          />
+                        </div>
+                        <div className="flex items-center justify-between pt-1">
+                          <div className="min-w-0">
+                            <p className="text-sm font-medium text-fg/70">
+                              System Text in User Turn
+                            </p>
+                            <p className="text-[10px] text-fg/40 leading-tight">
+                              For formats without a system role
+                            </p>
+                          </div>
+                          <Switch
+                            id="textCompletionSystemInUser"
+                            checked={textCompletionTemplate.systemInUser === true}
+                            onChange={(next) =>
+                              updateEditorProvider({
+                                config: {
+                                  ...editorProvider.config,
+                                  customTemplate: { ...textCompletionTemplate, systemInUser: next },
+                                },
+                              })
+                            }
+                          />
+                        </div>
+                      </>
+                    )}
+                  </>
+                )}
                 {isCustomProvider && (
                   <>
                     <div>
@@ -974,6 +1290,28 @@ export function ProvidersPage() {
                     />
                   </div>
                 )}
+                {!isMockProvider && editorProvider.providerId !== "llamacpp" && (
+                  <details className="rounded-xl border border-fg/10 bg-fg/5 px-3 py-2.5 group">
+                    <summary className="flex cursor-pointer items-center justify-between text-xs font-semibold text-fg/70">
+                      <span>Network</span>
+                      <span className="text-[11px] text-fg/40 group-open:hidden">Show</span>
+                      <span className="hidden text-[11px] text-fg/40 group-open:inline">Hide</span>
+                    </summary>
+                    <div className="mt-3">
+                      <NetworkSettingsFields
+                        key={editorProvider.id}
+                        value={customConfig.network}
+                        perCredential
+                        credentialId={editorProvider.id}
+                        onChange={(network) =>
+                          updateEditorProvider({
+                            config: { ...editorProvider.config, network },
+                          })
+                        }
+                      />
+                    </div>
+                  </details>
+                )}
                 {validationError && (
                   <p className="text-xs font-medium text-danger/80">{validationError}</p>
                 )}
@@ -0,0 +1,148 @@
+import { useState } from "react";
+
+import { Switch } from "../../../components/Switch";
+
+export type MockError = "429" | "500" | "timeout" | "contextOverflow";
+
+export interface MockProviderConfig {
+  mode?: "echo" | "script";
+  script?: string[];
+  tokensPerSecond?: number | null;
+  reasoning?: boolean;
+  toolCalls?: boolean;
+  error?: MockError | null;
+}
+
+const INPUT_CLASS =
+  "w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none";
+const LABEL_CLASS = "mb-1 block text-[11px] font-medium text-fg/70";
+
+interface MockProviderFieldsProps {
+  value: MockProviderConfig | null | undefined;
+  onChange: (next: MockProviderConfig) => void;
+}
+
+export function MockProviderFields({ value, onChange }: MockProviderFieldsProps) {
+  const config: MockProviderConfig = value ?? {};
+  const mode = config.mode ?? "echo";
+  const [scriptText, setScriptText] = useState((config.script ?? []).join("\n\n"));
+
+  const update = (patch: Partial<MockProviderConfig>) => onChange({ ...config, ...patch });
+
+  return (
+    <div className="space-y-3">
+      <div>
+        <label className={LABEL_CLASS}>Replies</label>
+        <select
+          value={mode}
+          onChange={(e) => update({ mode: e.target.value as MockProviderConfig["mode"] })}
+          className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg focus:border-fg/30 focus:outline-none"
+        >
+          <option value="echo" className="bg-surface-el">
+            Echo the last message
+          </option>
+          <option value="script" className="bg-surface-el">
+            Scripted
+          </option>
+        </select>
+      </div>
+
+      {mode === "script" && (
+        <div>
+          <label className={LABEL_CLASS}>Script</label>
+          <textarea
+            value={scriptText}
+            onChange={(e) => {
+              setScriptText(e.target.value);
+              update({ script: splitReplies(e.target.value) });
+            }}
+            rows={5}
+            placeholder={
+              "Hello! I'm a scripted reply.\n\nThis is the reply to your second message."
+            }
+            className={INPUT_CLASS}
+          />
+          <p className="mt-1 text-[11px] text-fg/45">
+            One reply per user turn, separated by blank lines. The script loops
+          </p>
+        </div>
+      )}
+
+      <div>
+        <label className={LABEL_CLASS}>Streaming speed (words per second)</label>
+        <input
+          type="number"
+          min={0}
+          value={config.tokensPerSecond ?? ""}
+          onChange={(e) => {
+            const speed = parseInt(e.target.value, 10);
+            update({ tokensPerSecond: Number.isNaN(speed) ? null : Math.max(0, speed) });
+          }}
+          placeholder="40"
+          className={INPUT_CLASS}
+        />
+        <p className="mt-1 text-[11px] text-fg/45">0 streams the whole reply at once</p>
+      </div>
+
+      <div>
+        <label className={LABEL_CLASS}>Simulated error</label>
+        <select
+          value={config.error ?? ""}
+          onChange={(e) => update({ error: (e.target.value || null) as MockError | null })}
+          className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg focus:border-fg/30 focus:outline-none"
+        >
+          <option value="" className="bg-surface-el">
+            None
+          </option>
+          <option value="429" className="bg-surface-el">
+            429 Rate limited
+          </option>
+          <option value="500" className="bg-surface-el">
+            500 Server error
+          </option>
+          <option value="timeout" className="bg-surface-el">
+            Timeout
+          </option>
+          <option value="contextOverflow" className="bg-surface-el">
+            Context overflow
+          </option>
+        </select>
+      </div>
+
+      <div className="flex items-center justify-between pt-1">
+        <div className="min-w-0">
+          <p className="text-sm font-medium text-fg/70">Reasoning</p>
+          <p className="text-[10px] text-fg/40 leading-tight">
+            Stream a short thinking block before every reply
+          </p>
+        </div>
+        <Switch
+          id="mockReasoning"
+          checked={config.reasoning === true}
+          onChange={(next) => update({ reasoning: next })}
+        />
+      </div>
+
+      <div className="flex items-center justify-between pt-1">
+        <div className="min-w-0">
+          <p className="text-sm font-medium text-fg/70">Tool Calls</p>
+          <p className="text-[10px] text-fg/40 leading-tight">
+            Call the first available tool before answering
+          </p>
+        </div>
+        <Switch
+          id="mockToolCalls"
+          checked={config.toolCalls === true}
+          onChange={(next) => update({ toolCalls: next })}
+        />
+      </div>
+    </div>
+  );
+}
+
+function splitReplies(text: string): string[] {
+  return text
+    .split(/\n\s*\n/)
+    .map((entry) => entry.trim())
+    .filter((entry) => entry.length > 0);
+}
@@ -0,0 +1,84 @@
+import { Loader, Radar } from "lucide-react";
+
+import type { ModelCapabilities } from "../../../../core/storage/schemas";
+import { useI18n } from "../../../../core/i18n/context";
+import { cn } from "../../../design-tokens";
+
+const CAPABILITY_LABELS = [
+  ["vision", "editModel.capabilities.vision"],
+  ["tools", "editModel.capabilities.tools"],
+  ["reasoning", "editModel.capabilities.reasoning"],
+  ["jsonMode", "editModel.capabilities.jsonMode"],
+] as const;
+
+interface ModelCapabilitiesSummaryProps {
+  capabilities: ModelCapabilities | null | undefined;
+  probing: boolean;
+  isLocalModel: boolean;
+  disabled?: boolean;
+  onDetect: () => void;
+}
+
+export function ModelCapabilitiesSummary({
+  capabilities,
+  probing,
+  isLocalModel,
+  disabled,
+  onDetect,
+}: ModelCapabilitiesSummaryProps) {
+  const { t } = useI18n();
+  const known = CAPABILITY_LABELS.filter(([key]) => capabilities?.[key] != null);
+  const sourceLabel =
+    capabilities?.source === "probe"
+      ? t("editModel.capabilities.detectedFromProbe")
+      : t("editModel.capabilities.detectedFromMetadata");
+
+  return (
+    <div className="space-y-2 rounded-lg border border-fg/10 bg-fg/5 px-3 py-2.5">
+      <div className="flex items-center justify-between gap-3">
+        <div className="min-w-0">
+          <p className="text-[13px] font-medium text-fg/72">
+            {t("editModel.capabilities.detected")}
+          </p>
+          <p className="text-[11px] text-fg/40">
+            {known.length > 0 ? sourceLabel : t("editModel.capabilities.notDetected")}
+          </p>
+        </div>
+        <button
+          type="button"
+          onClick={onDetect}
+          disabled={disabled || probing}
+          className="flex shrink-0 items-center gap-1.5 rounded-md border border-fg/10 px-2.5 py-1.5 text-[12px] text-fg/60 transition hover:border-fg/20 hover:bg-fg/5 hover:text-fg/85 disabled:opacity-40"
+        >
+          {probing ? (
+            <Loader className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <Radar className="h-3.5 w-3.5" />
+          )}
+          {probing
+            ? t("editModel.capabilities.probing")
+            : isLocalModel
+              ? t("editModel.capabilities.readGguf")
+              : t("editModel.capabilities.probe")}
+        </button>
+      </div>
+      {known.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {known.map(([key, label]) => (
+            <span
+              key={key}
+              className={cn(
+                "rounded-full border px-2 py-0.5 text-[11px]",
+                capabilities?.[key]
+                  ? "border-accent/25 bg-accent/10 text-accent"
+                  : "border-fg/10 text-fg/35 line-through",
+              )}
+            >
+              {t(label)}
+            </span>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
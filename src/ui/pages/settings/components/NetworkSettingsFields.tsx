@@ -0,0 +1,200 @@
+import { useState } from "react";
+
+import { storageBridge } from "../../../../core/storage/files";
+import type { NetworkSettings, ProxySettings } from "../../../../core/storage/schemas";
+import { Switch } from "../../../components/Switch";
+
+type ProxyChoice = "inherit" | ProxySettings["kind"];
+
+const INPUT_CLASS =
+  "w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg placeholder-fg/40 focus:border-fg/30 focus:outline-none";
+const LABEL_CLASS = "mb-1 block text-[11px] font-medium text-fg/70";
+
+interface NetworkSettingsFieldsProps {
+  value: Partial<NetworkSettings> | null | undefined;
+  onChange: (next: NetworkSettings) => void;
+  /** Credential scope: the proxy can inherit the default and self-signed
+   * certificates can be accepted for its host. */
+  perCredential?: boolean;
+  /** Credential the proxy password is stored for; omitted for the default. */
+  credentialId?: string;
+}
+
+export function NetworkSettingsFields({
+  value,
+  onChange,
+  perCredential = false,
+  credentialId,
+}: NetworkSettingsFieldsProps) {
+  const network: NetworkSettings = {
+    proxy: value?.proxy ?? null,
+    caCertificates: value?.caCertificates ?? [],
+    acceptInvalidCerts: value?.acceptInvalidCerts ?? false,
+  };
+  const proxy = network.proxy;
+  const [certificatesText, setCertificatesText] = useState(network.caCertificates.join("\n\n"));
+  const [passwordDraft, setPasswordDraft] = useState("");
+  const choice: ProxyChoice = proxy?.kind ?? (perCredential ? "inherit" : "direct");
+  const usesProxyServer = proxy != null && proxy.kind !== "direct";
+
+  const update = (patch: Partial<NetworkSettings>) => onChange({ ...network, ...patch });
+  const updateProxy = (patch: Partial<ProxySettings>) => {
+    if (!proxy) return;
+    update({ proxy: { ...proxy, ...patch } });
+  };
+  // The password is stored separately; settings only record that one is set.
+  const savePassword = async (password: string | null) => {
+    try {
+      await storageBridge.networkSetProxyPassword(credentialId ?? null, password);
+      updateProxy({ hasPassword: password != null });
+    } catch (err) {
+      console.error("Failed to save proxy password:", err);
+    }
+    setPasswordDraft("");
+  };
+
+  return (
+    <div className="space-y-3">
+      <div>
+        <label className={LABEL_CLASS}>Proxy</label>
+        <select
+          value={choice}
+          onChange={(e) => {
+            const kind = e.target.value as ProxyChoice;
+            if (kind === "inherit" || (kind === "direct" && !perCredential)) {
+              update({ proxy: null });
+            } else {
+              update({ proxy: { host: "", hasPassword: false, ...proxy, kind } });
+            }
+          }}
+          className="w-full rounded-lg border border-fg/10 bg-surface-el/20 px-3 py-2 text-sm text-fg focus:border-fg/30 focus:outline-none"
+        >
+          {perCredential && (
+            <option value="inherit" className="bg-surface-el">
+              Use default
+            </option>
+          )}
+          <option value="direct" className="bg-surface-el">
+            {perCredential ? "No proxy" : "None"}
+          </option>
+          <option value="http" className="bg-surface-el">
+            HTTP
+          </option>
+          <option value="https" className="bg-surface-el">
+            HTTPS
+          </option>
+          <option value="socks5" className="bg-surface-el">
+            SOCKS5
+          </option>
+        </select>
+      </div>
+
+      {usesProxyServer && (
+        <>
+          <div className="grid grid-cols-3 gap-3">
+            <div className="col-span-2">
+              <label className={LABEL_CLASS}>Host</label>
+              <input
+                type="text"
+                value={proxy.host}
+                onChange={(e) => updateProxy({ host: e.target.value })}
+                placeholder={proxy.kind === "socks5" ? "127.0.0.1" : "proxy.example.com"}
+                className={INPUT_CLASS}
+              />
+            </div>
+            <div>
+              <label className={LABEL_CLASS}>Port</label>
+              <input
+                type="number"
+                min={1}
+                max={65535}
+                value={proxy.port ?? ""}
+                onChange={(e) => {
+                  const port = parseInt(e.target.value, 10);
+                  updateProxy({ port: Number.isNaN(port) ? null : port });
+                }}
+                placeholder={proxy.kind === "socks5" ? "9050" : "8080"}
+                className={INPUT_CLASS}
+              />
+            </div>
+          </div>
+          <div className="grid grid-cols-2 gap-3">
+            <div>
+              <label className={LABEL_CLASS}>Username (optional)</label>
+              <input
+                type="text"
+                value={proxy.username ?? ""}
+                onChange={(e) => updateProxy({ username: e.target.value || null })}
+                className={INPUT_CLASS}
+              />
+            </div>
+            <div>
+              <div className="flex items-center justify-between">
+                <label className={LABEL_CLASS}>Password (optional)</label>
+                {proxy.hasPassword && (
+                  <button
+                    type="button"
+                    onClick={() => void savePassword(null)}
+                    className="mb-1 text-[11px] text-fg/45 hover:text-fg/70"
+                  >
+                    Remove
+                  </button>
+                )}
+              </div>
+              <input
+                type="password"
+                value={passwordDraft}
+                onChange={(e) => setPasswordDraft(e.target.value)}
+                onBlur={() => {
+                  if (passwordDraft) void savePassword(passwordDraft);
+                }}
+                placeholder={proxy.hasPassword ? "Saved" : ""}
+                className={INPUT_CLASS}
+              />
+            </div>
+          </div>
+        </>
+      )}
+
+      <div>
+        <label className={LABEL_CLASS}>Custom CA certificates</label>
+        <textarea
+          value={certificatesText}
+          onChange={(e) => {
+            setCertificatesText(e.target.value);
+            update({ caCertificates: splitCertificates(e.target.value) });
+          }}
+          rows={3}
+          placeholder="-----BEGIN CERTIFICATE----- or /path/to/ca.pem"
+          className={`${INPUT_CLASS} font-mono text-xs`}
+        />
+        <p className="mt-1 text-[11px] text-fg/45">
+          PEM certificates or file paths, separated by blank lines
+        </p>
+      </div>
+
+      {perCredential && (
+        <div className="flex items-center justify-between pt-1">
+          <div className="min-w-0">
+            <p className="text-sm font-medium text-fg/70">Accept Self-signed Certificates</p>
+            <p className="text-[10px] text-fg/40 leading-tight">
+              Skips certificate checks for this provider's host. Only use on trusted networks
+            </p>
+          </div>
+          <Switch
+            id="acceptInvalidCerts"
+            checked={network.acceptInvalidCerts}
+            onChange={(next) => update({ acceptInvalidCerts: next })}
+          />
+        </div>
+      )}
+    </div>
+  );
+}
+
+function splitCertificates(text: string): string[] {
+  return text
+    .split(/\n\s*\n/)
+    .map((entry) => entry.trim())
+    .filter((entry) => entry.length > 0);
+}
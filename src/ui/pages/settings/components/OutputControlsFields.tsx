@@ -0,0 +1,121 @@
+import { useState } from "react";
+
+import type { AdvancedModelSettings } from "../../../../core/storage/schemas";
+import { useI18n } from "../../../../core/i18n/context";
+
+type OutputControlKey = "stopSequences" | "bannedStrings" | "logitBias";
+
+const TEXTAREA_CLASS =
+  "w-full rounded-lg border border-fg/10 bg-surface-el/20 px-4 py-3.5 font-mono text-[13px] text-fg placeholder-fg/40 transition focus:border-fg/30 focus:outline-none";
+
+// One entry per line; a literal "\n" stands for a newline inside the entry.
+function parseLines(text: string): string[] | null {
+  const entries = text
+    .split("\n")
+    .filter((line) => line.trim().length > 0)
+    .map((line) => line.replace(/\\n/g, "\n"));
+  return entries.length > 0 ? entries : null;
+}
+
+function formatLines(values: string[] | null | undefined): string {
+  return (values ?? []).map((value) => value.replace(/\n/g, "\\n")).join("\n");
+}
+
+// "token or text: bias" per line. Quote text to keep its leading space.
+function parseBias(text: string): Record<string, number> | null {
+  const entries: [string, number][] = [];
+  for (const line of text.split("\n")) {
+    const match = line.trim().match(/^(.*?)\s*[:=]\s*(-?\d+(?:\.\d+)?)$/);
+    if (!match) continue;
+    const quoted = match[1].match(/^"(.*)"$/);
+    const key = quoted ? quoted[1] : match[1];
+    if (key.length === 0) continue;
+    entries.push([key, Math.min(100, Math.max(-100, Number(match[2])))]);
+  }
+  return entries.length > 0 ? Object.fromEntries(entries) : null;
+}
+
+function formatBias(bias: Record<string, number> | null | undefined): string {
+  return Object.entries(bias ?? {})
+    .map(([key, value]) => `${key === key.trim() ? key : `"${key}"`}: ${value}`)
+    .join("\n");
+}
+
+interface OutputControlsFieldsProps {
+  settings: AdvancedModelSettings;
+  onChange: <K extends OutputControlKey>(key: K, value: AdvancedModelSettings[K]) => void;
+}
+
+export function OutputControlsFields({ settings, onChange }: OutputControlsFieldsProps) {
+  const { t } = useI18n();
+  const [stopText, setStopText] = useState(() => formatLines(settings.stopSequences));
+  const [bannedText, setBannedText] = useState(() => formatLines(settings.bannedStrings));
+  const [biasText, setBiasText] = useState(() => formatBias(settings.logitBias));
+
+  return (
+    <div className="space-y-4 rounded-xl border border-fg/8 bg-surface-el/10 p-4">
+      <div className="space-y-0.5">
+        <span className="block text-[13px] font-medium text-fg/70">
+          {t("editModel.outputControls.title")}
+        </span>
+        <span className="block text-[13px] text-fg/40">
+          {t("editModel.outputControls.description")}
+        </span>
+      </div>
+
+      <div className="grid grid-cols-1 gap-4 md:grid-cols-3">
+        <div className="space-y-1.5">
+          <span className="block text-[13px] text-fg/60">
+            {t("editModel.outputControls.stopSequences")}
+          </span>
+          <textarea
+            value={stopText}
+            onChange={(e) => {
+              setStopText(e.target.value);
+              onChange("stopSequences", parseLines(e.target.value));
+            }}
+            placeholder={t("editModel.outputControls.stopSequencesPlaceholder")}
+            rows={4}
+            className={TEXTAREA_CLASS}
+          />
+        </div>
+
+        <div className="space-y-1.5">
+          <span className="block text-[13px] text-fg/60">
+            {t("editModel.outputControls.bannedStrings")}
+          </span>
+          <textarea
+            value={bannedText}
+            onChange={(e) => {
+              setBannedText(e.target.value);
+              onChange("bannedStrings", parseLines(e.target.value));
+            }}
+            placeholder={t("editModel.outputControls.bannedStringsPlaceholder")}
+            rows={4}
+            className={TEXTAREA_CLASS}
+          />
+        </div>
+
+        <div className="space-y-1.5">
+          <span className="block text-[13px] text-fg/60">
+            {t("editModel.outputControls.logitBias")}
+          </span>
+          <textarea
+            value={biasText}
+            onChange={(e) => {
+              setBiasText(e.target.value);
+              onChange("logitBias", parseBias(e.target.value));
+            }}
+            placeholder={t("editModel.outputControls.logitBiasPlaceholder")}
+            rows={4}
+            className={TEXTAREA_CLASS}
+          />
+        </div>
+      </div>
+
+      <p className="text-[12px] leading-relaxed text-fg/40">
+        {t("editModel.outputControls.enforcementNote")}
+      </p>
+    </div>
+  );
+}
@@ -0,0 +1,184 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Play, Trash2 } from "lucide-react";
+
+import { readSettings, saveAdvancedSettings } from "../../../../core/storage/repo";
+import { Switch } from "../../../components/Switch";
+import { cn, radius, typography } from "../../../design-tokens";
+
+interface RecordingSummary {
+  requestId: string;
+  providerId?: string | null;
+  startedAt: number;
+  url: string;
+  stream: boolean;
+  status?: number | null;
+  chunkCount: number;
+  error?: string | null;
+}
+
+type ReplayEvent =
+  | { type: "delta" | "reasoning"; data: { text: string } }
+  | { type: "toolCall" | "usage" | "error"; data: unknown }
+  | { type: "done"; data?: null };
+
+interface ReplayResult {
+  requestId: string;
+  events: ReplayEvent[];
+}
+
+function summarizeReplay(events: ReplayEvent[]) {
+  let text = "";
+  let reasoning = "";
+  const other: string[] = [];
+  for (const event of events) {
+    if (event.type === "delta") text += event.data.text;
+    else if (event.type === "reasoning") reasoning += event.data.text;
+    else other.push(event.type);
+  }
+  return { text, reasoning, other };
+}
+
+function errorText(err: unknown) {
+  return err instanceof Error ? err.message : String(err);
+}
+
+export function RequestRecordingsSection() {
+  const [enabled, setEnabled] = useState(false);
+  const [recordings, setRecordings] = useState<RecordingSummary[]>([]);
+  const [replay, setReplay] = useState<ReplayResult | null>(null);
+  const [error, setError] = useState("");
+
+  const loadRecordings = useCallback(async () => {
+    try {
+      setRecordings(await invoke<RecordingSummary[]>("request_recording_list"));
+    } catch (err) {
+      setError(`Failed to load recordings: ${errorText(err)}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    readSettings()
+      .then((settings) => setEnabled(settings.advancedSettings?.requestRecordingEnabled === true))
+      .catch(() => setEnabled(false));
+    void loadRecordings();
+  }, [loadRecordings]);
+
+  const toggleRecording = async (next: boolean) => {
+    setEnabled(next);
+    try {
+      const settings = await readSettings();
+      await saveAdvancedSettings({
+        creationHelperEnabled: false,
+        helpMeReplyEnabled: true,
+        ...settings.advancedSettings,
+        requestRecordingEnabled: next,
+      });
+    } catch (err) {
+      setEnabled(!next);
+      setError(`Failed to save setting: ${errorText(err)}`);
+    }
+  };
+
+  const replayRecording = async (requestId: string) => {
+    try {
+      const events = await invoke<ReplayEvent[]>("request_recording_replay", { requestId });
+      setReplay({ requestId, events });
+      setError("");
+    } catch (err) {
+      setError(`Replay failed: ${errorText(err)}`);
+    }
+  };
+
+  const clearRecordings = async () => {
+    try {
+      await invoke("request_recording_clear");
+      setReplay(null);
+      await loadRecordings();
+    } catch (err) {
+      setError(`Failed to clear recordings: ${errorText(err)}`);
+    }
+  };
+
+  const replaySummary = replay ? summarizeReplay(replay.events) : null;
+
+  return (
+    <div className="space-y-3">
+      <div
+        className={cn(
+          "flex items-center justify-between gap-3 px-4 py-3",
+          radius.md,
+          "border border-fg/10 bg-fg/5",
+        )}
+      >
+        <div className="min-w-0">
+          <p className={cn(typography.body.size, "font-medium text-fg")}>
+            Record provider requests
+          </p>
+          <p className={cn(typography.caption.size, "text-fg/45")}>
+            Saves the last 50 requests with redacted keys, response headers and raw stream chunks
+          </p>
+        </div>
+        <Switch id="requestRecordingEnabled" checked={enabled} onChange={toggleRecording} />
+      </div>
+
+      {error && <p className={cn(typography.caption.size, "text-danger/80")}>{error}</p>}
+
+      {recordings.length > 0 && (
+        <div className={cn(radius.md, "border border-fg/10 bg-fg/5 divide-y divide-fg/10")}>
+          {recordings.map((recording) => (
+            <div key={recording.requestId} className="flex items-center gap-3 px-4 py-2.5">
+              <div className="min-w-0 flex-1">
+                <p className={cn(typography.caption.size, "truncate font-mono text-fg/80")}>
+                  {recording.providerId ?? "unknown"} · {recording.url}
+                </p>
+                <p className={cn(typography.caption.size, "text-fg/45")}>
+                  {new Date(recording.startedAt).toLocaleString()} ·{" "}
+                  {recording.error
+                    ? recording.error
+                    : `HTTP ${recording.status ?? "?"} · ${recording.chunkCount} chunks`}
+                </p>
+              </div>
+              {recording.stream && (
+                <button
+                  onClick={() => replayRecording(recording.requestId)}
+                  className="rounded-lg border border-fg/10 p-2 text-fg/60 hover:bg-fg/10 hover:text-fg"
+                  aria-label="Replay recording"
+                >
+                  <Play className="h-4 w-4" />
+                </button>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+
+      {replay && replaySummary && (
+        <div className={cn("space-y-2 px-4 py-3", radius.md, "border border-info/30 bg-info/10")}>
+          <p className={cn(typography.caption.size, "font-mono text-fg/60")}>
+            Replay of {replay.requestId}: {replay.events.length} events
+            {replaySummary.other.length > 0 && ` (${replaySummary.other.join(", ")})`}
+          </p>
+          {replaySummary.reasoning && (
+            <pre className="max-h-40 overflow-auto whitespace-pre-wrap text-xs text-fg/50">
+              {replaySummary.reasoning}
+            </pre>
+          )}
+          <pre className="max-h-60 overflow-auto whitespace-pre-wrap text-xs text-fg/80">
+            {replaySummary.text || "(no text)"}
+          </pre>
+        </div>
+      )}
+
+      {recordings.length > 0 && (
+        <button
+          onClick={clearRecordings}
+          className="flex items-center gap-2 text-xs text-danger/80 hover:text-danger"
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+          Clear recordings
+        </button>
+      )}
+    </div>
+  );
+}
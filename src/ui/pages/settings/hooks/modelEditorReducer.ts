@@ -1,4 +1,9 @@
-import type { Model, ProviderCredential, AdvancedModelSettings } from "../../../../core/storage/schemas";
+import type {
+  Model,
+  ModelCapabilities,
+  ProviderCredential,
+  AdvancedModelSettings,
+} from "../../../../core/storage/schemas";
 
 
 export type ModelInfo = {
@@ -8,6 +13,7 @@ export type ModelInfo = {
   contextLength?: number;
   inputPrice?: number;
   outputPrice?: number;
+  capabilities?: ModelCapabilities;
 };
 
 export type ModelEditorState = {
@@ -16,6 +22,7 @@ export type ModelEditorState = {
   deleting: boolean;
   verifying: boolean;
   fetchingModels: boolean;
+  probingCapabilities: boolean;
   fetchedModels: ModelInfo[];
   error: string | null;
   providers: ProviderCredential[];
@@ -30,6 +37,7 @@ export type ModelEditorAction =
   | { type: "set_deleting"; payload: boolean }
   | { type: "set_verifying"; payload: boolean }
   | { type: "set_fetching_models"; payload: boolean }
+  | { type: "set_probing_capabilities"; payload: boolean }
   | { type: "set_fetched_models"; payload: ModelInfo[] }
   | { type: "set_error"; payload: string | null }
   | {
@@ -44,7 +52,33 @@ export type ModelEditorAction =
   | { type: "update_editor_model"; payload: Partial<Model> }
   | { type: "set_providers"; payload: ProviderCredential[] }
   | { type: "set_default_model_id"; payload: string | null }
-  | { type: "set_model_advanced_draft"; payload: AdvancedModelSettings };
+  | { type: "set_model_advanced_draft"; payload: AdvancedModelSettings }
+  | {
+    type: "set_model_capabilities";
+    payload: { capabilities: ModelCapabilities | null | undefined; merge: boolean };
+  };
+
+const CAPABILITY_KEYS = ["vision", "tools", "reasoning", "jsonMode"] as const;
+
+function hasKnownCapability(
+  capabilities: ModelCapabilities | null | undefined,
+): capabilities is ModelCapabilities {
+  return CAPABILITY_KEYS.some((key) => capabilities?.[key] != null);
+}
+
+// Probe results only override the capabilities they actually measured.
+function mergeModelCapabilities(
+  current: ModelCapabilities | null | undefined,
+  next: ModelCapabilities | null | undefined,
+): ModelCapabilities | null {
+  if (!hasKnownCapability(next)) return current ?? null;
+  const merged: ModelCapabilities = { ...current };
+  for (const key of CAPABILITY_KEYS) {
+    if (next[key] != null) merged[key] = next[key];
+  }
+  merged.source = next.source ?? current?.source ?? null;
+  return merged;
+}
 
 export const initialModelEditorState: ModelEditorState = {
   loading: true,
@@ -52,6 +86,7 @@ export const initialModelEditorState: ModelEditorState = {
   deleting: false,
   verifying: false,
   fetchingModels: false,
+  probingCapabilities: false,
   fetchedModels: [],
   error: null,
   providers: [],
@@ -75,6 +110,8 @@ export function modelEditorReducer(
       return { ...state, verifying: action.payload };
     case "set_fetching_models":
       return { ...state, fetchingModels: action.payload };
+    case "set_probing_capabilities":
+      return { ...state, probingCapabilities: action.payload };
     case "set_fetched_models":
       return { ...state, fetchedModels: action.payload };
     case "set_error":
@@ -113,6 +150,24 @@ export function modelEditorReducer(
         ...state,
         modelAdvancedDraft: action.payload,
       };
+    case "set_model_capabilities": {
+      if (!state.editorModel) return state;
+      const { capabilities, merge } = action.payload;
+      const next = merge
+        ? mergeModelCapabilities(state.modelAdvancedDraft.modelCapabilities, capabilities)
+        : hasKnownCapability(capabilities)
+          ? capabilities
+          : null;
+      const scopes = state.editorModel.inputScopes ?? ["text"];
+      return {
+        ...state,
+        editorModel:
+          next?.vision === true && !scopes.includes("image")
+            ? { ...state.editorModel, inputScopes: [...scopes, "image"] }
+            : state.editorModel,
+        modelAdvancedDraft: { ...state.modelAdvancedDraft, modelCapabilities: next },
+      };
+    }
     default:
       return state;
   }
@@ -13,6 +13,7 @@ import {
 import type {
   AdvancedModelSettings,
   Model,
+  ModelCapabilities,
   ProviderCredential,
 } from "../../../../core/storage/schemas";
 import {
@@ -103,6 +104,11 @@ type ControllerReturn = {
   resetToInitial: () => void;
   clearError: () => void;
   fetchModels: () => Promise<void>;
+  applyModelCapabilities: (
+    capabilities: ModelCapabilities | null | undefined,
+    merge?: boolean,
+  ) => void;
+  detectModelCapabilities: (modelName?: string) => Promise<void>;
 };
 
 function useModelEditorState() {
@@ -510,6 +516,56 @@ export function useModelEditorController(): ControllerReturn {
     [dispatch],
   );
 
+  const applyModelCapabilities = useCallback(
+    (capabilities: ModelCapabilities | null | undefined, merge = false) => {
+      dispatch({ type: "set_model_capabilities", payload: { capabilities, merge } });
+    },
+    [dispatch],
+  );
+
+  // Reads GGUF metadata for local models and probes remote ones. Passing a
+  // model name means a new model was just picked, so earlier results are dropped.
+  const detectModelCapabilities = useCallback(
+    async (modelName?: string) => {
+      const { editorModel, providers } = state;
+      const name = modelName ?? editorModel?.name;
+      if (!editorModel || !name?.trim()) return;
+      const providerCred =
+        providers.find(
+          (p) => p.providerId === editorModel.providerId && p.label === editorModel.providerLabel,
+        ) || providers.find((p) => p.providerId === editorModel.providerId);
+      if (editorModel.providerId !== "llamacpp" && !providerCred) {
+        dispatch({
+          type: "set_error",
+          payload: "Select a provider with valid credentials to probe the model",
+        });
+        return;
+      }
+
+      dispatch({ type: "set_probing_capabilities", payload: true });
+      dispatch({ type: "set_error", payload: null });
+      try {
+        const capabilities =
+          editorModel.providerId === "llamacpp"
+            ? await invoke<ModelCapabilities>("llamacpp_model_capabilities", { modelPath: name })
+            : await invoke<ModelCapabilities>("probe_model_capabilities", {
+                credentialId: providerCred!.id,
+                modelName: name,
+              });
+        applyModelCapabilities(capabilities, modelName === undefined);
+      } catch (error: any) {
+        console.error("Failed to detect model capabilities", error);
+        dispatch({
+          type: "set_error",
+          payload: error?.message || String(error) || "Failed to detect model capabilities",
+        });
+      } finally {
+        dispatch({ type: "set_probing_capabilities", payload: false });
+      }
+    },
+    [applyModelCapabilities, dispatch, state],
+  );
+
   const toggleOverride = useCallback(() => {
     // No-op for now, removing usage
   }, []);
@@ -1600,5 +1656,7 @@ export function useModelEditorController(): ControllerReturn {
     resetToInitial,
     clearError,
     fetchModels,
+    applyModelCapabilities,
+    detectModelCapabilities,
   };
 }
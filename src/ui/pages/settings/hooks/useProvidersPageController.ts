@@ -178,6 +178,8 @@ export function useProvidersPageController(): ControllerReturn {
         "custom-anthropic",
         "ollama",
         "lmstudio",
+        "koboldcpp",
+        "text-completion",
         "intenserp",
         "automatic1111",
       ].includes(editorProvider.providerId);
@@ -250,6 +252,8 @@ export function useProvidersPageController(): ControllerReturn {
       const requiresBaseUrl = [
         "ollama",
         "lmstudio",
+        "koboldcpp",
+        "text-completion",
         "intenserp",
         "automatic1111",
         "lettuce-host",
@@ -261,6 +265,13 @@ export function useProvidersPageController(): ControllerReturn {
         });
         return;
       }
+      if (editorProvider.providerId === "azure-openai" && !editorProvider.baseUrl?.trim()) {
+        dispatch({
+          type: "set_validation_error",
+          payload: "Resource endpoint is required (e.g., https://my-resource.openai.azure.com)",
+        });
+        return;
+      }
 
       const isCustomProvider =
         editorProvider.providerId === "custom" || editorProvider.providerId === "custom-anthropic";